@@ -0,0 +1,6 @@
+//! `libtab_app_framework_c.a`, the `taf_*` C API of `tab-app-framework-core`.
+//!
+//! The bindings live behind the core's `c-bindings` feature; this crate only links them
+//! into a static library. Declarations are in `core/include/tab_app_framework.h`.
+
+extern crate tab_app_framework_core;
@@ -0,0 +1,153 @@
+/* NOLINTBEGIN */
+#ifndef TAB_APP_FRAMEWORK_H
+#define TAB_APP_FRAMEWORK_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#include <stdint.h>
+#include <stdbool.h>
+#include <stddef.h>
+
+/*
+ * C API of tab-app-framework-core, built with the `c-bindings` feature.
+ *
+ * The framework runs the event loop, tracks the monitor layout and paces swapchain
+ * buffers; the app renders into the DMA-BUF handed to `on_render`. The frame is
+ * submitted when the callback returns unless `taf_context_cancel_frame` was called.
+ *
+ * Strings and structs passed to callbacks are only valid during the callback.
+ */
+
+/* ============================================================================
+ * OPAQUE HANDLES
+ * ============================================================================
+ */
+
+typedef struct TafHandle TafHandle;
+/* Only valid inside the callback it was passed to. */
+typedef struct TafContext TafContext;
+
+/* ============================================================================
+ * EVENTS
+ * ============================================================================
+ */
+
+typedef struct {
+    int fd;
+    int stride;
+    int offset;
+    int fourcc;
+} TafDmabuf;
+
+typedef struct {
+    const char *monitor_id;
+    int32_t width;
+    int32_t height;
+    uint32_t buffer_index;
+    /* Frames since this buffer was presented, 0 when its contents are undefined. */
+    uint32_t buffer_age;
+    /* True on the first frame after the swapchain was recreated. */
+    bool resized;
+    TafDmabuf dmabuf;
+} TafRenderEvent;
+
+typedef struct {
+    const char *id;
+    const char *name;
+    int32_t width;
+    int32_t height;
+    int32_t refresh_rate;
+    int32_t x;
+    int32_t y;
+    double scale;
+} TafMonitor;
+
+typedef struct {
+    uint32_t device;
+    uint64_t time_usec;
+    uint32_t key;
+    bool pressed;
+} TafKeyEvent;
+
+typedef enum {
+    TAF_POINTER_TYPE_MOUSE = 0,
+    TAF_POINTER_TYPE_PEN = 1,
+    TAF_POINTER_TYPE_TOUCH = 2,
+    TAF_POINTER_TYPE_UNKNOWN = 3,
+} TafPointerType;
+
+/* Pointer move, down or up; `button` is 0 and `dx`/`dy` are set only for moves. */
+typedef struct {
+    const char *seat;
+    uint32_t device;
+    uint64_t time_usec;
+    TafPointerType pointer_type;
+    uint32_t button;
+    double x;
+    double y;
+    double dx;
+    double dy;
+} TafPointerEvent;
+
+/* ============================================================================
+ * CALLBACKS
+ * ============================================================================
+ */
+
+typedef void (*TafRenderCallback)(TafContext *ctx, const TafRenderEvent *ev, void *user_data);
+typedef void (*TafMonitorCallback)(TafContext *ctx, const TafMonitor *monitor, void *user_data);
+typedef void (*TafMonitorRemovedCallback)(
+    TafContext *ctx,
+    const char *monitor_id,
+    void *user_data
+);
+typedef void (*TafKeyCallback)(TafContext *ctx, const TafKeyEvent *ev, void *user_data);
+typedef void (*TafPointerCallback)(TafContext *ctx, const TafPointerEvent *ev, void *user_data);
+typedef void (*TafErrorCallback)(TafContext *ctx, const char *message, void *user_data);
+
+/* Any callback may be NULL. */
+typedef struct {
+    void *user_data;
+    TafRenderCallback on_render;
+    TafMonitorCallback on_monitor_added;
+    TafMonitorRemovedCallback on_monitor_removed;
+    TafKeyCallback on_key;
+    TafPointerCallback on_pointer_move;
+    TafPointerCallback on_pointer_down;
+    TafPointerCallback on_pointer_up;
+    TafErrorCallback on_error;
+} TafCallbacks;
+
+/* ============================================================================
+ * API
+ * ============================================================================
+ */
+
+/* A NULL token reads the configuration from the environment (SHIFT_SESSION_TOKEN, ...). */
+TafHandle *taf_init(const char *socket_path, const char *token);
+void taf_destroy(TafHandle *handle);
+
+void taf_set_callbacks(TafHandle *handle, const TafCallbacks *callbacks);
+/* Returns false on error; fetch the message with taf_take_error. */
+bool taf_run(TafHandle *handle);
+
+char *taf_take_error(TafHandle *handle);
+void taf_string_free(char *s);
+
+void taf_context_schedule_frame(TafContext *ctx, const char *monitor_id);
+void taf_context_schedule_all_frames(TafContext *ctx);
+void taf_context_request_exit(TafContext *ctx);
+void taf_context_cancel_frame(TafContext *ctx);
+/* Takes ownership of fence_fd; the server waits on it before scanning out the frame. */
+void taf_context_set_acquire_fence(TafContext *ctx, int fence_fd);
+bool taf_context_send_ready(TafContext *ctx);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TAB_APP_FRAMEWORK_H */
+
+/* NOLINTEND */
@@ -0,0 +1,178 @@
+//! Tweens and easing driven by frame timestamps.
+
+use std::time::{Duration, Instant};
+
+/// Easing curve mapping linear progress in `[0, 1]` to eased progress.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Easing {
+	/// Constant speed.
+	#[default]
+	Linear,
+	/// Cubic ease-in: starts slow, ends fast.
+	EaseIn,
+	/// Cubic ease-out: starts fast, settles slowly.
+	EaseOut,
+	/// Cubic ease-in-out.
+	EaseInOut,
+	/// Application-defined curve; should map 0 to 0 and 1 to 1.
+	Custom(fn(f64) -> f64),
+}
+
+impl Easing {
+	/// Eases `t`, clamped to `[0, 1]` first.
+	pub fn apply(self, t: f64) -> f64 {
+		let t = t.clamp(0.0, 1.0);
+		match self {
+			Self::Linear => t,
+			Self::EaseIn => t * t * t,
+			Self::EaseOut => 1.0 - (1.0 - t).powi(3),
+			Self::EaseInOut => {
+				if t < 0.5 {
+					4.0 * t * t * t
+				} else {
+					1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+				}
+			}
+			Self::Custom(curve) => curve(t),
+		}
+	}
+}
+
+/// Values a [`Tween`] can interpolate.
+pub trait Lerp: Clone {
+	/// Interpolates from `self` (at 0) to `to` (at 1).
+	fn lerp(&self, to: &Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+	fn lerp(&self, to: &Self, t: f64) -> Self {
+		self + (to - self) * t
+	}
+}
+
+impl Lerp for f32 {
+	fn lerp(&self, to: &Self, t: f64) -> Self {
+		self + (to - self) * t as f32
+	}
+}
+
+impl<T: Lerp> Lerp for (T, T) {
+	fn lerp(&self, to: &Self, t: f64) -> Self {
+		(self.0.lerp(&to.0, t), self.1.lerp(&to.1, t))
+	}
+}
+
+impl<T: Lerp, const N: usize> Lerp for [T; N] {
+	fn lerp(&self, to: &Self, t: f64) -> Self {
+		std::array::from_fn(|i| self[i].lerp(&to[i], t))
+	}
+}
+
+/// Animation clock advanced with frame timestamps.
+///
+/// Call [`Timeline::tick`] once per frame, e.g. with `Instant::now()` at the top of
+/// `on_render` or with a predicted present time from a
+/// [`PresentationClock`](crate::PresentationClock), then read tweens at that time. In
+/// [`RenderMode::Scheduled`](crate::RenderMode::Scheduled), keep scheduling frames only
+/// while [`Timeline::is_active`] holds, so idle apps stop rendering.
+#[derive(Debug, Clone)]
+pub struct Timeline {
+	now: Instant,
+	busy_until: Option<Instant>,
+}
+
+impl Default for Timeline {
+	fn default() -> Self {
+		Self::new(Instant::now())
+	}
+}
+
+impl Timeline {
+	/// Creates a timeline whose current time is `now`.
+	pub fn new(now: Instant) -> Self {
+		Self {
+			now,
+			busy_until: None,
+		}
+	}
+
+	/// Advances the current time to `now`; earlier timestamps are ignored.
+	pub fn tick(&mut self, now: Instant) {
+		self.now = self.now.max(now);
+	}
+
+	/// Current time of the timeline.
+	pub fn now(&self) -> Instant {
+		self.now
+	}
+
+	/// Returns true while a tween started on this timeline has not finished.
+	pub fn is_active(&self) -> bool {
+		self.busy_until.is_some_and(|until| self.now < until)
+	}
+
+	/// Starts a tween from `from` to `to` at the current time.
+	pub fn tween<T: Lerp>(&mut self, from: T, to: T, duration: Duration, easing: Easing) -> Tween<T> {
+		self.extend(duration);
+		Tween {
+			from,
+			to,
+			start: self.now,
+			duration,
+			easing,
+		}
+	}
+
+	fn extend(&mut self, duration: Duration) {
+		let until = self.now + duration;
+		self.busy_until = Some(self.busy_until.map_or(until, |busy| busy.max(until)));
+	}
+}
+
+/// Interpolation between two values over a span of a [`Timeline`].
+#[derive(Debug, Clone)]
+pub struct Tween<T> {
+	from: T,
+	to: T,
+	start: Instant,
+	duration: Duration,
+	easing: Easing,
+}
+
+impl<T: Lerp> Tween<T> {
+	/// Linear progress in `[0, 1]` at the timeline's current time.
+	pub fn progress(&self, timeline: &Timeline) -> f64 {
+		let elapsed = timeline.now.saturating_duration_since(self.start);
+		if elapsed >= self.duration {
+			1.0
+		} else {
+			elapsed.as_secs_f64() / self.duration.as_secs_f64()
+		}
+	}
+
+	/// Eased value at the timeline's current time.
+	pub fn value(&self, timeline: &Timeline) -> T {
+		let t = self.easing.apply(self.progress(timeline));
+		self.from.lerp(&self.to, t)
+	}
+
+	/// Returns true once the tween reached its target value.
+	pub fn is_finished(&self, timeline: &Timeline) -> bool {
+		self.progress(timeline) >= 1.0
+	}
+
+	/// Value the tween ends at.
+	pub fn target(&self) -> &T {
+		&self.to
+	}
+
+	/// Restarts the tween from its current value towards `to`, e.g. for an effect following
+	/// the cursor.
+	pub fn retarget(&mut self, timeline: &mut Timeline, to: T, duration: Duration) {
+		self.from = self.value(timeline);
+		self.to = to;
+		self.start = timeline.now;
+		self.duration = duration;
+		timeline.extend(duration);
+	}
+}
@@ -0,0 +1,484 @@
+#![allow(non_camel_case_types)]
+
+use std::{
+	ffi::{CStr, CString},
+	os::{
+		fd::{FromRawFd, OwnedFd},
+		raw::{c_char, c_int, c_void},
+	},
+	ptr,
+};
+
+use crate::{
+	Application, Config, Context, FrameworkError, InitContext, KeyEvent, Monitor, MonitorAddedEvent,
+	MonitorRemovedEvent, PointerDownEvent, PointerMoveEvent, PointerType, PointerUpEvent,
+	RenderEvent, TabAppFramework,
+};
+
+/// Framework handle returned by `taf_init`.
+pub struct TafHandle {
+	framework: TabAppFramework<CApp>,
+	last_error: Option<CString>,
+}
+
+/// Callback-scoped view of the framework; only valid during the callback it is passed to.
+pub struct TafContext {
+	_private: [u8; 0],
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TafDmabuf {
+	pub fd: c_int,
+	pub stride: c_int,
+	pub offset: c_int,
+	pub fourcc: c_int,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TafRenderEvent {
+	pub monitor_id: *const c_char,
+	pub width: i32,
+	pub height: i32,
+	pub buffer_index: u32,
+	pub buffer_age: u32,
+	pub resized: bool,
+	pub dmabuf: TafDmabuf,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TafMonitor {
+	pub id: *const c_char,
+	pub name: *const c_char,
+	pub width: i32,
+	pub height: i32,
+	pub refresh_rate: i32,
+	pub x: i32,
+	pub y: i32,
+	pub scale: f64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TafKeyEvent {
+	pub device: u32,
+	pub time_usec: u64,
+	pub key: u32,
+	pub pressed: bool,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub enum TafPointerType {
+	TAF_POINTER_TYPE_MOUSE = 0,
+	TAF_POINTER_TYPE_PEN = 1,
+	TAF_POINTER_TYPE_TOUCH = 2,
+	TAF_POINTER_TYPE_UNKNOWN = 3,
+}
+
+/// Pointer move, down or up; `button` is 0 and `dx`/`dy` are set only for moves.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TafPointerEvent {
+	pub seat: *const c_char,
+	pub device: u32,
+	pub time_usec: u64,
+	pub pointer_type: TafPointerType,
+	pub button: u32,
+	pub x: f64,
+	pub y: f64,
+	pub dx: f64,
+	pub dy: f64,
+}
+
+pub type TafRenderCallback =
+	unsafe extern "C" fn(ctx: *mut TafContext, ev: *const TafRenderEvent, user_data: *mut c_void);
+pub type TafMonitorCallback =
+	unsafe extern "C" fn(ctx: *mut TafContext, monitor: *const TafMonitor, user_data: *mut c_void);
+pub type TafMonitorRemovedCallback =
+	unsafe extern "C" fn(ctx: *mut TafContext, monitor_id: *const c_char, user_data: *mut c_void);
+pub type TafKeyCallback =
+	unsafe extern "C" fn(ctx: *mut TafContext, ev: *const TafKeyEvent, user_data: *mut c_void);
+pub type TafPointerCallback =
+	unsafe extern "C" fn(ctx: *mut TafContext, ev: *const TafPointerEvent, user_data: *mut c_void);
+pub type TafErrorCallback =
+	unsafe extern "C" fn(ctx: *mut TafContext, message: *const c_char, user_data: *mut c_void);
+
+/// Application callbacks; any of them may be null.
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct TafCallbacks {
+	pub user_data: *mut c_void,
+	pub on_render: Option<TafRenderCallback>,
+	pub on_monitor_added: Option<TafMonitorCallback>,
+	pub on_monitor_removed: Option<TafMonitorRemovedCallback>,
+	pub on_key: Option<TafKeyCallback>,
+	pub on_pointer_move: Option<TafPointerCallback>,
+	pub on_pointer_down: Option<TafPointerCallback>,
+	pub on_pointer_up: Option<TafPointerCallback>,
+	pub on_error: Option<TafErrorCallback>,
+}
+
+impl Default for TafCallbacks {
+	fn default() -> Self {
+		Self {
+			user_data: ptr::null_mut(),
+			on_render: None,
+			on_monitor_added: None,
+			on_monitor_removed: None,
+			on_key: None,
+			on_pointer_move: None,
+			on_pointer_down: None,
+			on_pointer_up: None,
+			on_error: None,
+		}
+	}
+}
+
+/// Application forwarding framework callbacks to C function pointers.
+struct CApp {
+	callbacks: TafCallbacks,
+}
+
+impl Application for CApp {
+	fn init(_ctx: &mut InitContext<Self>) -> anyhow::Result<Self> {
+		Ok(Self {
+			callbacks: TafCallbacks::default(),
+		})
+	}
+
+	fn on_render(&mut self, ctx: &mut Context<Self>, ev: RenderEvent<'_>) {
+		let Some(callback) = self.callbacks.on_render else {
+			return;
+		};
+		let monitor_id = c_string(ev.monitor_id());
+		let target = ev.target();
+		let event = TafRenderEvent {
+			monitor_id: monitor_id.as_ptr(),
+			width: ev.width(),
+			height: ev.height(),
+			buffer_index: target.buffer_index() as u32,
+			buffer_age: ev.buffer_age(),
+			resized: ev.resized(),
+			dmabuf: TafDmabuf {
+				fd: std::os::fd::AsRawFd::as_raw_fd(&target.dmabuf_fd()),
+				stride: target.stride(),
+				offset: target.offset(),
+				fourcc: target.fourcc(),
+			},
+		};
+		unsafe { callback(context_ptr(ctx), &event, self.callbacks.user_data) };
+	}
+
+	fn on_monitor_added(&mut self, ctx: &mut Context<Self>, ev: MonitorAddedEvent) {
+		let Some(callback) = self.callbacks.on_monitor_added else {
+			return;
+		};
+		let (id, name) = (c_string(&ev.monitor.id), c_string(&ev.monitor.name));
+		let monitor = monitor_to_c(&ev.monitor, &id, &name);
+		unsafe { callback(context_ptr(ctx), &monitor, self.callbacks.user_data) };
+	}
+
+	fn on_monitor_removed(&mut self, ctx: &mut Context<Self>, ev: MonitorRemovedEvent) {
+		let Some(callback) = self.callbacks.on_monitor_removed else {
+			return;
+		};
+		let monitor_id = c_string(&ev.monitor_id);
+		unsafe {
+			callback(
+				context_ptr(ctx),
+				monitor_id.as_ptr(),
+				self.callbacks.user_data,
+			)
+		};
+	}
+
+	fn on_key(&mut self, ctx: &mut Context<Self>, ev: KeyEvent) {
+		let Some(callback) = self.callbacks.on_key else {
+			return;
+		};
+		let event = TafKeyEvent {
+			device: ev.device,
+			time_usec: ev.time_usec,
+			key: ev.key,
+			pressed: ev.is_pressed(),
+		};
+		unsafe { callback(context_ptr(ctx), &event, self.callbacks.user_data) };
+	}
+
+	fn on_pointer_move(&mut self, ctx: &mut Context<Self>, ev: PointerMoveEvent) {
+		let Some(callback) = self.callbacks.on_pointer_move else {
+			return;
+		};
+		let seat = c_string(&ev.seat.0);
+		let (dx, dy) = ev.delta();
+		let event = TafPointerEvent {
+			seat: seat.as_ptr(),
+			device: ev.device,
+			time_usec: ev.time_usec,
+			pointer_type: pointer_type_to_c(ev.pointer_type),
+			button: 0,
+			x: ev.new_position.0,
+			y: ev.new_position.1,
+			dx,
+			dy,
+		};
+		unsafe { callback(context_ptr(ctx), &event, self.callbacks.user_data) };
+	}
+
+	fn on_pointer_down(&mut self, ctx: &mut Context<Self>, ev: PointerDownEvent) {
+		let Some(callback) = self.callbacks.on_pointer_down else {
+			return;
+		};
+		let seat = c_string(&ev.seat.0);
+		let event = pointer_button_to_c(
+			&seat,
+			ev.device,
+			ev.time_usec,
+			ev.pointer_type,
+			ev.button,
+			ev.position,
+		);
+		unsafe { callback(context_ptr(ctx), &event, self.callbacks.user_data) };
+	}
+
+	fn on_pointer_up(&mut self, ctx: &mut Context<Self>, ev: PointerUpEvent) {
+		let Some(callback) = self.callbacks.on_pointer_up else {
+			return;
+		};
+		let seat = c_string(&ev.seat.0);
+		let event = pointer_button_to_c(
+			&seat,
+			ev.device,
+			ev.time_usec,
+			ev.pointer_type,
+			ev.button,
+			ev.position,
+		);
+		unsafe { callback(context_ptr(ctx), &event, self.callbacks.user_data) };
+	}
+
+	fn on_error(&mut self, ctx: &mut Context<Self>, error: &FrameworkError) {
+		let Some(callback) = self.callbacks.on_error else {
+			return;
+		};
+		let message = c_string(&error.to_string());
+		unsafe { callback(context_ptr(ctx), message.as_ptr(), self.callbacks.user_data) };
+	}
+}
+
+fn context_ptr(ctx: &mut Context<CApp>) -> *mut TafContext {
+	(ctx as *mut Context<CApp>).cast()
+}
+
+unsafe fn context_from_ptr<'a>(ctx: *mut TafContext) -> Option<&'a mut Context<'a, CApp>> {
+	unsafe { ctx.cast::<Context<'a, CApp>>().as_mut() }
+}
+
+/// Converts to a C string, dropping interior NULs instead of failing.
+fn c_string(s: &str) -> CString {
+	CString::new(s.replace('\0', "")).unwrap_or_default()
+}
+
+fn cstring_to_string(ptr: *const c_char) -> Option<String> {
+	if ptr.is_null() {
+		return None;
+	}
+	unsafe { CStr::from_ptr(ptr) }
+		.to_str()
+		.ok()
+		.map(|s| s.to_string())
+}
+
+fn monitor_to_c(monitor: &Monitor, id: &CStr, name: &CStr) -> TafMonitor {
+	TafMonitor {
+		id: id.as_ptr(),
+		name: name.as_ptr(),
+		width: monitor.width,
+		height: monitor.height,
+		refresh_rate: monitor.refresh_rate,
+		x: monitor.x,
+		y: monitor.y,
+		scale: monitor.scale,
+	}
+}
+
+fn pointer_type_to_c(pointer_type: PointerType) -> TafPointerType {
+	match pointer_type {
+		PointerType::Mouse => TafPointerType::TAF_POINTER_TYPE_MOUSE,
+		PointerType::Pen => TafPointerType::TAF_POINTER_TYPE_PEN,
+		PointerType::Touch => TafPointerType::TAF_POINTER_TYPE_TOUCH,
+		PointerType::Unknown => TafPointerType::TAF_POINTER_TYPE_UNKNOWN,
+	}
+}
+
+fn pointer_button_to_c(
+	seat: &CStr,
+	device: u32,
+	time_usec: u64,
+	pointer_type: PointerType,
+	button: u32,
+	position: (f64, f64),
+) -> TafPointerEvent {
+	TafPointerEvent {
+		seat: seat.as_ptr(),
+		device,
+		time_usec,
+		pointer_type: pointer_type_to_c(pointer_type),
+		button,
+		x: position.0,
+		y: position.1,
+		dx: 0.0,
+		dy: 0.0,
+	}
+}
+
+/// Connects to the server; a null `token` reads the whole configuration from the
+/// environment like `TabAppFramework::init`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_init(
+	socket_path: *const c_char,
+	token: *const c_char,
+) -> *mut TafHandle {
+	let result = match cstring_to_string(token) {
+		Some(token) => {
+			let mut config = Config::from_token(token);
+			if let Some(path) = cstring_to_string(socket_path) {
+				config.set_socket_path(path);
+			}
+			TabAppFramework::<CApp>::init_with_config(config)
+		}
+		None => TabAppFramework::<CApp>::init(|config| {
+			if let Some(path) = cstring_to_string(socket_path) {
+				config.set_socket_path(path);
+			}
+		}),
+	};
+	match result {
+		Ok(framework) => Box::into_raw(Box::new(TafHandle {
+			framework,
+			last_error: None,
+		})),
+		Err(err) => {
+			eprintln!("taf_init failed: {err}");
+			ptr::null_mut()
+		}
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_destroy(handle: *mut TafHandle) {
+	unsafe {
+		if !handle.is_null() {
+			drop(Box::from_raw(handle));
+		}
+	}
+}
+
+/// Replaces the callbacks; the struct is copied, `user_data` is passed back unchanged.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_set_callbacks(handle: *mut TafHandle, callbacks: *const TafCallbacks) {
+	unsafe {
+		let Some(handle) = handle.as_mut() else {
+			return;
+		};
+		handle.framework.app_mut().callbacks = callbacks.as_ref().copied().unwrap_or_default();
+	}
+}
+
+/// Runs the loop until exit; returns false on error, see `taf_take_error`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_run(handle: *mut TafHandle) -> bool {
+	unsafe {
+		let Some(handle) = handle.as_mut() else {
+			return false;
+		};
+		match handle.framework.run() {
+			Ok(()) => true,
+			Err(err) => {
+				handle.last_error = Some(c_string(&err.to_string()));
+				false
+			}
+		}
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_take_error(handle: *mut TafHandle) -> *mut c_char {
+	unsafe {
+		handle
+			.as_mut()
+			.and_then(|h| h.last_error.take())
+			.map_or(ptr::null_mut(), CString::into_raw)
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_string_free(s: *mut c_char) {
+	unsafe {
+		if !s.is_null() {
+			drop(CString::from_raw(s));
+		}
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_context_schedule_frame(
+	ctx: *mut TafContext,
+	monitor_id: *const c_char,
+) {
+	unsafe {
+		let (Some(ctx), Some(id)) = (context_from_ptr(ctx), cstring_to_string(monitor_id)) else {
+			return;
+		};
+		ctx.schedule_frame(id);
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_context_schedule_all_frames(ctx: *mut TafContext) {
+	unsafe {
+		if let Some(ctx) = context_from_ptr(ctx) {
+			ctx.schedule_all_frames();
+		}
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_context_request_exit(ctx: *mut TafContext) {
+	unsafe {
+		if let Some(ctx) = context_from_ptr(ctx) {
+			ctx.request_exit();
+		}
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_context_cancel_frame(ctx: *mut TafContext) {
+	unsafe {
+		if let Some(ctx) = context_from_ptr(ctx) {
+			ctx.cancel_frame();
+		}
+	}
+}
+
+/// Takes ownership of `fence_fd` and attaches it to the frame being rendered.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_context_set_acquire_fence(ctx: *mut TafContext, fence_fd: c_int) {
+	unsafe {
+		if fence_fd < 0 {
+			return;
+		}
+		let fence = OwnedFd::from_raw_fd(fence_fd);
+		if let Some(ctx) = context_from_ptr(ctx) {
+			ctx.set_next_acquire_fence(fence);
+		}
+	}
+}
+
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn taf_context_send_ready(ctx: *mut TafContext) -> bool {
+	unsafe { context_from_ptr(ctx).is_some_and(|ctx| ctx.send_ready().is_ok()) }
+}
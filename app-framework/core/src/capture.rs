@@ -0,0 +1,88 @@
+//! Capture streams copying a monitor's output, or one session's frames, into buffers
+//! owned by the application, e.g. for screen recording.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use tab_client::TabSwapchain;
+use tab_protocol::BufferIndex;
+
+use crate::FrameworkError;
+
+/// Handle to a capture started with [`Context::start_capture`](crate::Context::start_capture).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CaptureId(u32);
+
+impl fmt::Display for CaptureId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "capture-{}", self.0)
+	}
+}
+
+impl CaptureId {
+	/// Parses the id sent over the wire back into a handle.
+	pub(crate) fn from_wire(capture_id: &str) -> Option<Self> {
+		capture_id
+			.strip_prefix("capture-")
+			.and_then(|n| n.parse().ok())
+			.map(Self)
+	}
+}
+
+pub(crate) struct CaptureRuntime {
+	pub(crate) swapchain: TabSwapchain,
+	/// Buffers holding a frame the application did not release yet.
+	pub(crate) held: [bool; 2],
+}
+
+/// Captures of the connection, keyed by their framework-assigned id.
+#[derive(Default)]
+pub(crate) struct Captures {
+	next_id: u32,
+	entries: HashMap<CaptureId, CaptureRuntime>,
+}
+
+impl Captures {
+	pub(crate) fn next_id(&mut self) -> CaptureId {
+		self.next_id += 1;
+		CaptureId(self.next_id)
+	}
+
+	pub(crate) fn insert(&mut self, id: CaptureId, swapchain: TabSwapchain) {
+		self.entries.insert(
+			id,
+			CaptureRuntime {
+				swapchain,
+				held: [false; 2],
+			},
+		);
+	}
+
+	pub(crate) fn remove(&mut self, id: CaptureId) -> Option<CaptureRuntime> {
+		self.entries.remove(&id)
+	}
+
+	pub(crate) fn get(&self, id: CaptureId) -> Result<&CaptureRuntime, FrameworkError> {
+		self
+			.entries
+			.get(&id)
+			.ok_or(FrameworkError::CaptureNotFound(id))
+	}
+
+	pub(crate) fn get_mut(&mut self, id: CaptureId) -> Result<&mut CaptureRuntime, FrameworkError> {
+		self
+			.entries
+			.get_mut(&id)
+			.ok_or(FrameworkError::CaptureNotFound(id))
+	}
+
+	/// Records a frame sent by the server for the capture with wire id `capture_id`.
+	///
+	/// Returns the capture's handle, or `None` when the capture was stopped meanwhile.
+	pub(crate) fn frame(&mut self, capture_id: &str, buffer: BufferIndex) -> Option<CaptureId> {
+		let id = CaptureId::from_wire(capture_id)?;
+		let capture = self.entries.get_mut(&id)?;
+		capture.held[buffer as usize] = true;
+		Some(id)
+	}
+}
@@ -0,0 +1,121 @@
+//! `clap` integration for [`Config`].
+//!
+//! Flatten `Config` into an application's own parser to accept the standard flags:
+//!
+//! ```ignore
+//! #[derive(clap::Parser)]
+//! struct Cli {
+//!     #[command(flatten)]
+//!     shift: tab_app_framework_core::Config,
+//! }
+//! ```
+//!
+//! Flags take precedence over the environment variables read by
+//! [`Config::from_env_full`].
+
+use clap::error::ErrorKind;
+use clap::{Arg, ArgMatches, Args, Command, FromArgMatches};
+
+use crate::{Config, RenderMode, SyncMode, parse_gl_version};
+
+const TOKEN: &str = "shift_session_token";
+const SOCKET: &str = "shift_socket";
+const RENDER_NODE: &str = "shift_render_node";
+const RENDER_MODE: &str = "shift_render_mode";
+const GL_VERSION: &str = "shift_gl_version";
+const SYNC_MODE: &str = "shift_sync_mode";
+
+impl Args for Config {
+	fn augment_args(cmd: Command) -> Command {
+		cmd
+			.arg(
+				Arg::new(TOKEN)
+					.long("shift-session-token")
+					.value_name("TOKEN")
+					.help("Session token; defaults to the one passed by shift in the environment"),
+			)
+			.arg(
+				Arg::new(SOCKET)
+					.long("shift-socket")
+					.value_name("PATH")
+					.value_parser(clap::value_parser!(std::path::PathBuf))
+					.help("Server socket path; defaults to $SHIFT_SOCKET"),
+			)
+			.arg(
+				Arg::new(RENDER_NODE)
+					.long("shift-render-node")
+					.value_name("PATH")
+					.value_parser(clap::value_parser!(std::path::PathBuf))
+					.help("DRM render node for buffer allocation; defaults to $SHIFT_RENDER_NODE"),
+			)
+			.arg(
+				Arg::new(RENDER_MODE)
+					.long("shift-render-mode")
+					.value_name("MODE")
+					.value_parser(|value: &str| value.parse::<RenderMode>())
+					.help("eager or scheduled; defaults to $SHIFT_RENDER_MODE"),
+			)
+			.arg(
+				Arg::new(GL_VERSION)
+					.long("shift-gl-version")
+					.value_name("MAJOR.MINOR")
+					.value_parser(parse_gl_version)
+					.help("Requested OpenGL version; defaults to $SHIFT_GL_VERSION"),
+			)
+			.arg(
+				Arg::new(SYNC_MODE)
+					.long("shift-sync-mode")
+					.value_name("MODE")
+					.value_parser(|value: &str| value.parse::<SyncMode>())
+					.help("auto, explicit or implicit frame sync; defaults to $SHIFT_SYNC_MODE"),
+			)
+	}
+
+	fn augment_args_for_update(cmd: Command) -> Command {
+		Self::augment_args(cmd)
+	}
+}
+
+impl FromArgMatches for Config {
+	fn from_arg_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+		let token = matches
+			.get_one::<String>(TOKEN)
+			.cloned()
+			.map(Ok)
+			.unwrap_or_else(tab_client::session_token_from_env)
+			.map_err(|err| {
+				clap::Error::raw(
+					ErrorKind::MissingRequiredArgument,
+					format!("missing --shift-session-token: {err}\n"),
+				)
+			})?;
+		let mut cfg = Self::from_token(token);
+		cfg
+			.apply_env()
+			.map_err(|err| clap::Error::raw(ErrorKind::ValueValidation, format!("{err}\n")))?;
+		cfg.update_from_arg_matches(matches)?;
+		Ok(cfg)
+	}
+
+	fn update_from_arg_matches(&mut self, matches: &ArgMatches) -> Result<(), clap::Error> {
+		if let Some(token) = matches.get_one::<String>(TOKEN) {
+			self.set_token(token.clone());
+		}
+		if let Some(path) = matches.get_one::<std::path::PathBuf>(SOCKET) {
+			self.set_socket_path(path);
+		}
+		if let Some(path) = matches.get_one::<std::path::PathBuf>(RENDER_NODE) {
+			self.set_render_node_path(path);
+		}
+		if let Some(mode) = matches.get_one::<RenderMode>(RENDER_MODE) {
+			self.set_render_mode(*mode);
+		}
+		if let Some(&(major, minor)) = matches.get_one::<(u8, u8)>(GL_VERSION) {
+			self.opengl_version(major, minor);
+		}
+		if let Some(mode) = matches.get_one::<SyncMode>(SYNC_MODE) {
+			self.set_sync_mode(*mode);
+		}
+		Ok(())
+	}
+}
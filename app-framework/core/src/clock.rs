@@ -0,0 +1,156 @@
+//! Refresh-cycle prediction from present feedback.
+
+use std::time::{Duration, Instant};
+
+use crate::{Monitor, PresentEvent};
+
+/// Weight of a new sample in the refresh period and phase estimates.
+const SMOOTHING: f64 = 0.1;
+/// Present intervals further than this fraction from a whole number of periods are ignored.
+const MAX_JITTER: f64 = 0.25;
+
+/// Predicts when a monitor presents next, for apps that pace frames against it.
+///
+/// Starts from the monitor's nominal refresh rate and refines the period and the
+/// refresh phase with every [`PresentEvent`] passed to [`PresentationClock::record_present`].
+/// Presents that skip refresh cycles are accounted for, so the clock keeps tracking
+/// while the app renders at a fraction of the refresh rate.
+#[derive(Debug, Clone)]
+pub struct PresentationClock {
+	nominal_period: Duration,
+	period: Duration,
+	last_present: Option<Instant>,
+	samples: u64,
+}
+
+impl PresentationClock {
+	/// Creates a clock for a monitor refreshing at `refresh_rate` Hz.
+	///
+	/// Non-positive rates fall back to 60 Hz.
+	pub fn new(refresh_rate: i32) -> Self {
+		let refresh_rate = if refresh_rate > 0 { refresh_rate } else { 60 };
+		let period = Duration::from_secs_f64(1.0 / f64::from(refresh_rate));
+		Self {
+			nominal_period: period,
+			period,
+			last_present: None,
+			samples: 0,
+		}
+	}
+
+	/// Creates a clock using the monitor's nominal refresh rate.
+	pub fn for_monitor(monitor: &Monitor) -> Self {
+		Self::new(monitor.refresh_rate)
+	}
+
+	/// Records a present of the monitor this clock tracks.
+	pub fn record_present(&mut self, event: &PresentEvent) {
+		self.record_present_at(event.timestamp);
+	}
+
+	/// Records a present observed at `timestamp`.
+	///
+	/// Timestamps older than the last recorded present are ignored.
+	pub fn record_present_at(&mut self, timestamp: Instant) {
+		let Some(last) = self.last_present else {
+			self.last_present = Some(timestamp);
+			return;
+		};
+		let Some(interval) = timestamp.checked_duration_since(last) else {
+			return;
+		};
+		let cycles = (interval.as_secs_f64() / self.period.as_secs_f64()).round();
+		if cycles < 1.0 {
+			return;
+		}
+		let measured = interval.as_secs_f64() / cycles;
+		let error = (measured - self.period.as_secs_f64()).abs() / self.period.as_secs_f64();
+		if error <= MAX_JITTER {
+			let period = self.period.as_secs_f64();
+			self.period = Duration::from_secs_f64(period + (measured - period) * SMOOTHING);
+			self.samples += 1;
+		}
+		// Blend the observed present into the predicted phase to absorb scheduling noise.
+		let predicted = last + self.period.mul_f64(cycles);
+		self.last_present = Some(if timestamp >= predicted {
+			predicted + (timestamp - predicted).mul_f64(SMOOTHING)
+		} else {
+			predicted - (predicted - timestamp).mul_f64(SMOOTHING)
+		});
+	}
+
+	/// Forgets recorded presents, e.g. after the monitor mode changed.
+	pub fn reset(&mut self, refresh_rate: i32) {
+		*self = Self::new(refresh_rate);
+	}
+
+	/// Estimated refresh period.
+	pub fn refresh_period(&self) -> Duration {
+		self.period
+	}
+
+	/// Estimated time of the last present, if any was recorded.
+	pub fn last_present(&self) -> Option<Instant> {
+		self.last_present
+	}
+
+	/// Number of present intervals the period estimate is based on.
+	pub fn samples(&self) -> u64 {
+		self.samples
+	}
+
+	/// Relative deviation of the estimated period from the nominal one.
+	///
+	/// Positive values mean the monitor refreshes slower than advertised; an audio clock
+	/// can use this to resample instead of dropping frames.
+	pub fn drift(&self) -> f64 {
+		let nominal = self.nominal_period.as_secs_f64();
+		(self.period.as_secs_f64() - nominal) / nominal
+	}
+
+	/// Predicts the first present strictly after `instant`.
+	///
+	/// Without recorded presents the phase is unknown and `instant` plus one period is
+	/// returned.
+	pub fn next_present_after(&self, instant: Instant) -> Instant {
+		let Some(last) = self.last_present else {
+			return instant + self.period;
+		};
+		if instant < last {
+			let cycles = ((last - instant).as_secs_f64() / self.period.as_secs_f64()).floor();
+			let candidate = last - self.period.mul_f64(cycles);
+			return if candidate > instant {
+				candidate
+			} else {
+				candidate + self.period
+			};
+		}
+		let cycles = ((instant - last).as_secs_f64() / self.period.as_secs_f64()).floor() + 1.0;
+		last + self.period.mul_f64(cycles)
+	}
+
+	/// Returns when rendering should start to make the next present, given how long a frame
+	/// takes to render.
+	///
+	/// Targets the first present that leaves `render_budget` of time from now.
+	pub fn render_slot(&self, now: Instant, render_budget: Duration) -> Instant {
+		self.targeted_present(now, render_budget).0
+	}
+
+	/// Sleeps until [`PresentationClock::render_slot`] and returns the targeted present time.
+	///
+	/// This blocks the event loop; call it from `on_render` only when the app renders
+	/// nothing else in the meantime.
+	pub fn sleep_until_render_slot(&self, render_budget: Duration) -> Instant {
+		let now = Instant::now();
+		let (slot, present) = self.targeted_present(now, render_budget);
+		std::thread::sleep(slot.saturating_duration_since(now));
+		present
+	}
+
+	fn targeted_present(&self, now: Instant, render_budget: Duration) -> (Instant, Instant) {
+		let present = self.next_present_after(now + render_budget);
+		let slot = present.checked_sub(render_budget).unwrap_or(now).max(now);
+		(slot, present)
+	}
+}
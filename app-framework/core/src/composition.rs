@@ -0,0 +1,46 @@
+//! Key to text composition behind `Config::enable_text_composition`.
+
+#[cfg(feature = "text-composition")]
+use tab_app_framework_xkb::XkbEngine;
+
+use crate::{CharEvent, FrameworkError};
+
+/// Feeds key events through XKB; holds no engine unless composition is enabled.
+pub(crate) struct TextComposer {
+	#[cfg(feature = "text-composition")]
+	engine: Option<XkbEngine>,
+}
+
+impl TextComposer {
+	/// Creates the XKB engine when `enabled`; fails without the `text-composition` feature.
+	#[cfg(feature = "text-composition")]
+	pub(crate) fn new(enabled: bool) -> Result<Self, FrameworkError> {
+		let engine = enabled
+			.then(XkbEngine::new)
+			.transpose()
+			.map_err(|err| FrameworkError::Config(format!("failed to initialize xkb engine: {err}")))?;
+		Ok(Self { engine })
+	}
+
+	#[cfg(not(feature = "text-composition"))]
+	pub(crate) fn new(enabled: bool) -> Result<Self, FrameworkError> {
+		if enabled {
+			return Err(FrameworkError::Config(
+				"text composition requires the `text-composition` feature".to_string(),
+			));
+		}
+		Ok(Self {})
+	}
+
+	/// Updates the keyboard state and returns the text the key completes, if any.
+	#[cfg(feature = "text-composition")]
+	pub(crate) fn process_key(&mut self, key: u32, pressed: bool) -> Option<CharEvent> {
+		let text = self.engine.as_mut()?.process_key(key, pressed).text?;
+		Some(CharEvent { text })
+	}
+
+	#[cfg(not(feature = "text-composition"))]
+	pub(crate) fn process_key(&mut self, _key: u32, _pressed: bool) -> Option<CharEvent> {
+		None
+	}
+}
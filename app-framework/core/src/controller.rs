@@ -0,0 +1,217 @@
+//! Running the event loop on its own thread, controlled from others.
+
+use std::any::Any;
+use std::fmt;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::sync::{Arc, mpsc};
+use std::thread::JoinHandle;
+
+use crate::{Application, Config, FrameworkError, TabAppFramework};
+
+/// Value injected from another thread with [`FrameworkController::send_user_event`].
+///
+/// Delivered to [`Application::on_user_event`] on the loop thread; user events are never
+/// part of an [`crate::Event`] batch.
+pub struct UserEvent {
+	payload: Box<dyn Any + Send>,
+}
+
+impl UserEvent {
+	/// Wraps a value for delivery to the application.
+	pub fn new<T: Any + Send>(value: T) -> Self {
+		Self {
+			payload: Box::new(value),
+		}
+	}
+
+	/// Returns true if the payload is a `T`.
+	pub fn is<T: Any>(&self) -> bool {
+		self.payload.is::<T>()
+	}
+
+	/// Borrows the payload if it is a `T`.
+	pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+		self.payload.downcast_ref()
+	}
+
+	/// Takes the payload if it is a `T`, otherwise returns the event unchanged.
+	pub fn downcast<T: Any>(self) -> Result<T, Self> {
+		self
+			.payload
+			.downcast()
+			.map(|value| *value)
+			.map_err(|payload| Self { payload })
+	}
+}
+
+impl fmt::Debug for UserEvent {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("UserEvent").finish_non_exhaustive()
+	}
+}
+
+enum ControlMessage {
+	ScheduleFrame(String),
+	ScheduleAllFrames,
+	RequestExit,
+	User(UserEvent),
+}
+
+/// Loop-side end of a [`FrameworkController`].
+pub(crate) struct ControlReceiver {
+	rx: mpsc::Receiver<ControlMessage>,
+	wake: Arc<OwnedFd>,
+}
+
+impl ControlReceiver {
+	pub(crate) fn fd(&self) -> RawFd {
+		self.wake.as_raw_fd()
+	}
+}
+
+/// Handle to a framework loop running on a dedicated thread.
+///
+/// Returned by [`TabAppFramework::spawn`]. The handle is `Send + Sync`; share it behind
+/// an `Arc` to control the loop from several threads. Requests are queued and handled on
+/// the next loop iteration, and are ignored once the loop has exited. Dropping the handle
+/// detaches the thread.
+pub struct FrameworkController {
+	tx: mpsc::Sender<ControlMessage>,
+	wake: Arc<OwnedFd>,
+	thread: JoinHandle<Result<(), FrameworkError>>,
+}
+
+impl FrameworkController {
+	/// Schedules a frame for `monitor_id`, as [`crate::Context::schedule_frame`] does.
+	pub fn schedule_frame(&self, monitor_id: impl Into<String>) {
+		self.send(ControlMessage::ScheduleFrame(monitor_id.into()));
+	}
+
+	/// Schedules a frame for every known monitor.
+	pub fn schedule_all_frames(&self) {
+		self.send(ControlMessage::ScheduleAllFrames);
+	}
+
+	/// Asks the loop to exit after the current iteration.
+	pub fn request_exit(&self) {
+		self.send(ControlMessage::RequestExit);
+	}
+
+	/// Delivers `value` to [`Application::on_user_event`] on the loop thread.
+	pub fn send_user_event<T: Any + Send>(&self, value: T) {
+		self.send(ControlMessage::User(UserEvent::new(value)));
+	}
+
+	/// Returns false once the loop has exited, by request, error or server disconnect.
+	pub fn is_running(&self) -> bool {
+		!self.thread.is_finished()
+	}
+
+	/// Waits for the loop to exit and returns its result.
+	pub fn join(self) -> Result<(), FrameworkError> {
+		self
+			.thread
+			.join()
+			.unwrap_or_else(|_| Err(FrameworkError::Config("framework thread panicked".into())))
+	}
+
+	fn send(&self, message: ControlMessage) {
+		if self.tx.send(message).is_ok() {
+			wake(&self.wake);
+		}
+	}
+}
+
+impl<A: Application> TabAppFramework<A> {
+	/// Initializes the framework on a new thread and runs its loop there.
+	///
+	/// The framework holds `Rc` state and must stay on the thread that created it; this
+	/// creates it on the loop thread, waits for [`TabAppFramework::init`] to finish and
+	/// returns a [`FrameworkController`] for the other threads. Initialization errors are
+	/// returned from here.
+	pub fn spawn<F>(configure: F) -> Result<FrameworkController, FrameworkError>
+	where
+		F: FnOnce(&mut Config) + Send + 'static,
+	{
+		let wake = Arc::new(create_eventfd()?);
+		let (tx, rx) = mpsc::channel();
+		let (init_tx, init_rx) = mpsc::sync_channel(1);
+		let control = ControlReceiver {
+			rx,
+			wake: Arc::clone(&wake),
+		};
+		let thread = std::thread::Builder::new()
+			.name("tab-app-framework".into())
+			.spawn(move || {
+				let mut framework = match Self::init(configure) {
+					Ok(framework) => framework,
+					Err(err) => {
+						let _ = init_tx.send(Err(err));
+						return Ok(());
+					}
+				};
+				framework.control = Some(control);
+				let _ = init_tx.send(Ok(()));
+				framework.run()
+			})
+			.map_err(|err| FrameworkError::Config(format!("failed to spawn framework thread: {err}")))?;
+		match init_rx.recv() {
+			Ok(Ok(())) => Ok(FrameworkController { tx, wake, thread }),
+			Ok(Err(err)) => Err(err),
+			Err(_) => Err(FrameworkError::Config(
+				"framework thread panicked during init".into(),
+			)),
+		}
+	}
+
+	/// Applies requests queued by the [`FrameworkController`].
+	pub(crate) fn drain_control(&mut self) {
+		let Some(control) = &self.control else {
+			return;
+		};
+		drain_eventfd(&control.wake);
+		let messages: Vec<_> = control.rx.try_iter().collect();
+		for message in messages {
+			match message {
+				ControlMessage::ScheduleFrame(monitor_id) => {
+					self.scheduled.insert(monitor_id);
+				}
+				ControlMessage::ScheduleAllFrames => {
+					self.scheduled.extend(self.monitors.keys().cloned());
+				}
+				ControlMessage::RequestExit => self.exiting = true,
+				ControlMessage::User(ev) => self.call_app(|app, ctx| app.on_user_event(ctx, ev)),
+			}
+		}
+	}
+}
+
+fn create_eventfd() -> Result<OwnedFd, FrameworkError> {
+	let fd = unsafe { libc::eventfd(0, libc::EFD_CLOEXEC | libc::EFD_NONBLOCK) };
+	if fd < 0 {
+		return Err(FrameworkError::Poll(std::io::Error::last_os_error()));
+	}
+	Ok(unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+fn wake(fd: &OwnedFd) {
+	let value: u64 = 1;
+	unsafe {
+		libc::write(
+			fd.as_raw_fd(),
+			(&value as *const u64).cast(),
+			std::mem::size_of::<u64>(),
+		);
+	}
+}
+
+fn drain_eventfd(fd: &OwnedFd) {
+	let mut value: u64 = 0;
+	unsafe {
+		libc::read(
+			fd.as_raw_fd(),
+			(&mut value as *mut u64).cast(),
+			std::mem::size_of::<u64>(),
+		);
+	}
+}
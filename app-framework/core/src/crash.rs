@@ -0,0 +1,75 @@
+use std::backtrace::Backtrace;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::panic::PanicHookInfo;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread::ThreadId;
+
+use tab_client::CrashReporter;
+
+/// Panic hook installed for [`crate::Config::set_crash_reports`].
+///
+/// Reports panics of the thread that installed it, which runs the event loop, and then
+/// runs the previous hook. Dropping the guard disarms the hook and closes its socket.
+pub(crate) struct CrashHook {
+	reporter: Arc<Mutex<Option<CrashReporter>>>,
+}
+
+impl CrashHook {
+	pub(crate) fn install(reporter: CrashReporter, app_version: Option<String>) -> Self {
+		let reporter = Arc::new(Mutex::new(Some(reporter)));
+		let hook_reporter = Arc::clone(&reporter);
+		let thread = std::thread::current().id();
+		let previous = std::panic::take_hook();
+		std::panic::set_hook(Box::new(move |info| {
+			report(&hook_reporter, thread, app_version.as_deref(), info);
+			previous(info);
+		}));
+		Self { reporter }
+	}
+}
+
+impl Drop for CrashHook {
+	fn drop(&mut self) {
+		self
+			.reporter
+			.lock()
+			.unwrap_or_else(PoisonError::into_inner)
+			.take();
+	}
+}
+
+fn report(
+	reporter: &Mutex<Option<CrashReporter>>,
+	thread: ThreadId,
+	app_version: Option<&str>,
+	info: &PanicHookInfo<'_>,
+) {
+	if std::thread::current().id() != thread {
+		return;
+	}
+	let Ok(reporter) = reporter.try_lock() else {
+		return;
+	};
+	let Some(reporter) = reporter.as_ref() else {
+		return;
+	};
+	let payload = info.payload();
+	let message = payload
+		.downcast_ref::<&str>()
+		.copied()
+		.or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+		.unwrap_or("Box<dyn Any>");
+	let location = info.location().map(ToString::to_string);
+	let message = match &location {
+		Some(location) => format!("{message} at {location}"),
+		None => message.to_string(),
+	};
+	// The rendered backtrace holds symbol names and source locations but no addresses, so
+	// the hash stays the same across runs of a build; the message may vary and is left out.
+	let mut hasher = DefaultHasher::new();
+	Backtrace::force_capture().to_string().hash(&mut hasher);
+	location.hash(&mut hasher);
+	if let Err(err) = reporter.send(&message, hasher.finish(), app_version) {
+		tracing::debug!(target: "tab_app_framework.core", error = %err, "failed to send crash report");
+	}
+}
@@ -0,0 +1,122 @@
+//! Deduplication of errors reported to `Application::on_error`.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::FrameworkError;
+
+/// Occurrences of one error message since the first was delivered.
+struct Burst {
+	started: Instant,
+	suppressed: u32,
+	last: Option<FrameworkError>,
+}
+
+impl Burst {
+	fn into_repeated(self, window: Duration) -> Option<FrameworkError> {
+		let error = self.last?;
+		Some(FrameworkError::Repeated {
+			error: Box::new(error),
+			count: self.suppressed,
+			window,
+		})
+	}
+}
+
+/// Collapses errors with the same message reported within a window.
+///
+/// The first error of a burst is delivered right away; the ones following it within the
+/// window are counted and delivered once, as [`FrameworkError::Repeated`], when the
+/// window ends.
+pub(crate) struct ErrorDedup {
+	window: Option<Duration>,
+	bursts: HashMap<String, Burst>,
+}
+
+impl ErrorDedup {
+	/// Creates a deduplicator; `None` delivers every error.
+	pub(crate) fn new(window: Option<Duration>) -> Self {
+		Self {
+			window,
+			bursts: HashMap::new(),
+		}
+	}
+
+	/// Changes the window, returning the summaries of the bursts it ends.
+	pub(crate) fn set_window(&mut self, window: Option<Duration>) -> Vec<FrameworkError> {
+		let flushed = match self.window {
+			Some(old) if window != self.window => self.drain(old, |_| true),
+			_ => Vec::new(),
+		};
+		self.window = window;
+		flushed
+	}
+
+	/// Returns the errors to deliver now that `error` was reported at `now`.
+	pub(crate) fn report(&mut self, error: FrameworkError, now: Instant) -> Vec<FrameworkError> {
+		let Some(window) = self.window else {
+			return vec![error];
+		};
+		let key = error.to_string();
+		let mut deliver = Vec::new();
+		if let Some(burst) = self.bursts.get_mut(&key) {
+			if now.saturating_duration_since(burst.started) < window {
+				burst.suppressed += 1;
+				burst.last = Some(error);
+				return deliver;
+			}
+			let ended = self.bursts.remove(&key);
+			deliver.extend(ended.and_then(|burst| burst.into_repeated(window)));
+		}
+		self.bursts.insert(
+			key,
+			Burst {
+				started: now,
+				suppressed: 0,
+				last: None,
+			},
+		);
+		deliver.push(error);
+		deliver
+	}
+
+	/// Returns the summaries of the bursts whose window ended by `now`.
+	pub(crate) fn expire(&mut self, now: Instant) -> Vec<FrameworkError> {
+		let Some(window) = self.window else {
+			return Vec::new();
+		};
+		self.drain(window, |burst| {
+			now.saturating_duration_since(burst.started) >= window
+		})
+	}
+
+	/// When the earliest burst with suppressed errors ends.
+	pub(crate) fn next_deadline(&self) -> Option<Instant> {
+		let window = self.window?;
+		self
+			.bursts
+			.values()
+			.filter(|burst| burst.suppressed > 0)
+			.map(|burst| burst.started + window)
+			.min()
+	}
+
+	fn drain(&mut self, window: Duration, ended: impl Fn(&Burst) -> bool) -> Vec<FrameworkError> {
+		let keys: Vec<String> = self
+			.bursts
+			.iter()
+			.filter(|(_, burst)| ended(burst))
+			.map(|(key, _)| key.clone())
+			.collect();
+		let mut summaries: Vec<(Instant, FrameworkError)> = keys
+			.into_iter()
+			.filter_map(|key| {
+				let burst = self.bursts.remove(&key)?;
+				let started = burst.started;
+				Some((started, burst.into_repeated(window)?))
+			})
+			.collect();
+		summaries.sort_by_key(|(started, _)| *started);
+		summaries.into_iter().map(|(_, error)| error).collect()
+	}
+}
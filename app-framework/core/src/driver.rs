@@ -0,0 +1,132 @@
+//! Driving several framework instances from one event loop.
+
+use std::os::fd::RawFd;
+use std::time::Duration;
+
+use crate::{Application, FrameworkError, TabAppFramework};
+
+/// A framework instance whose loop is driven from outside.
+///
+/// [`TabAppFramework::run`] owns the thread until exit; this trait splits one iteration
+/// into waiting and dispatching so a [`MultiFrameworkDriver`] (or an external event
+/// loop) can wait on several instances at once.
+pub trait DrivenFramework {
+	/// File descriptors whose readiness means [`DrivenFramework::dispatch`] has work to do.
+	fn poll_fds(&self) -> Vec<RawFd>;
+
+	/// Longest time the instance may wait for its fds; `None` waits indefinitely.
+	fn poll_timeout(&self) -> Option<Duration>;
+
+	/// Handles ready input and renders scheduled frames without blocking.
+	fn dispatch(&mut self) -> Result<(), FrameworkError>;
+
+	/// Whether the application requested exit or the server closed the connection.
+	fn exit_requested(&self) -> bool;
+}
+
+impl<A: Application> DrivenFramework for TabAppFramework<A> {
+	fn poll_fds(&self) -> Vec<RawFd> {
+		let mut fds = vec![self.client.socket_fd()];
+		fds.extend(self.watched_fds.iter().copied());
+		fds.extend(self.pending_release_fds());
+		fds.extend(self.control.as_ref().map(|control| control.fd()));
+		fds
+	}
+
+	fn poll_timeout(&self) -> Option<Duration> {
+		let timeout_ms = self.poll_timeout_ms();
+		(timeout_ms >= 0).then(|| Duration::from_millis(timeout_ms as u64))
+	}
+
+	fn dispatch(&mut self) -> Result<(), FrameworkError> {
+		self.iterate(0)
+	}
+
+	fn exit_requested(&self) -> bool {
+		self.exiting
+	}
+}
+
+/// Polls several framework instances, e.g. one per server or token, from a single thread.
+///
+/// Each instance keeps its own connection, GPU context and buffers; the driver only
+/// multiplexes waiting. Instances that exit are skipped until every one has exited.
+#[derive(Default)]
+pub struct MultiFrameworkDriver<'a> {
+	instances: Vec<&'a mut dyn DrivenFramework>,
+}
+
+impl<'a> MultiFrameworkDriver<'a> {
+	/// Creates a driver without instances.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Adds an instance to drive.
+	pub fn add(&mut self, instance: &'a mut dyn DrivenFramework) {
+		self.instances.push(instance);
+	}
+
+	/// Number of instances that have not exited yet.
+	pub fn running(&self) -> usize {
+		self
+			.instances
+			.iter()
+			.filter(|instance| !instance.exit_requested())
+			.count()
+	}
+
+	/// Waits until any running instance has work or `timeout` elapses, then dispatches
+	/// every running instance once.
+	///
+	/// `timeout` is shortened to the earliest deadline of any instance; `None` waits
+	/// indefinitely.
+	pub fn run_once(&mut self, timeout: Option<Duration>) -> Result<(), FrameworkError> {
+		let mut pollfds = Vec::new();
+		let mut timeout = timeout;
+		for instance in self.instances.iter().filter(|i| !i.exit_requested()) {
+			pollfds.extend(instance.poll_fds().into_iter().map(|fd| libc::pollfd {
+				fd,
+				events: libc::POLLIN,
+				revents: 0,
+			}));
+			if let Some(instance_timeout) = instance.poll_timeout() {
+				timeout = Some(timeout.map_or(instance_timeout, |t| t.min(instance_timeout)));
+			}
+		}
+		if pollfds.is_empty() && timeout.is_none() {
+			return Ok(());
+		}
+		let timeout_ms = timeout.map_or(-1, |t| t.as_millis().min(i32::MAX as u128) as i32);
+		let rc = unsafe {
+			libc::poll(
+				pollfds.as_mut_ptr(),
+				pollfds.len() as libc::nfds_t,
+				timeout_ms,
+			)
+		};
+		if rc < 0 {
+			let err = std::io::Error::last_os_error();
+			if err.kind() == std::io::ErrorKind::Interrupted {
+				return Ok(());
+			}
+			return Err(FrameworkError::Poll(err));
+		}
+		for instance in self.instances.iter_mut() {
+			if !instance.exit_requested() {
+				instance.dispatch()?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Drives all instances until every one of them has exited.
+	///
+	/// Stops at the first error returned by any instance.
+	pub fn run(&mut self) -> Result<(), FrameworkError> {
+		while self.running() > 0 {
+			self.run_once(None)?;
+		}
+		Ok(())
+	}
+}
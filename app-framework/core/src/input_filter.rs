@@ -0,0 +1,75 @@
+//! Application-defined transformations of raw input before it reaches callbacks.
+
+use std::collections::VecDeque;
+
+use tab_protocol::{InputEventPayload, SeatId};
+
+use crate::{QueuedEvent, TabInputEvent};
+
+/// Transforms raw input events before the framework turns them into callbacks.
+///
+/// Filters registered with [`Context::add_input_filter`](crate::Context::add_input_filter)
+/// form a chain in registration order; each filter sees what the previous one passed on.
+/// Pointer, touch and gesture state is tracked from the filtered events, so a filter that
+/// remaps a button also changes the button of the matching pointer events.
+///
+/// Closures with the signature of [`InputFilter::filter`] are filters too.
+pub trait InputFilter {
+	/// Filters one event from `seat`.
+	///
+	/// Pushes the events to pass on, in order, to `out`: pushing nothing consumes the
+	/// event, pushing a modified copy replaces it, and further pushes emit new events
+	/// from the same seat.
+	fn filter(&mut self, seat: &SeatId, event: InputEventPayload, out: &mut Vec<InputEventPayload>);
+}
+
+impl<F> InputFilter for F
+where
+	F: FnMut(&SeatId, InputEventPayload, &mut Vec<InputEventPayload>),
+{
+	fn filter(&mut self, seat: &SeatId, event: InputEventPayload, out: &mut Vec<InputEventPayload>) {
+		self(seat, event, out)
+	}
+}
+
+/// Ordered chain of registered filters.
+#[derive(Default)]
+pub(crate) struct InputFilters {
+	chain: Vec<Box<dyn InputFilter>>,
+}
+
+impl InputFilters {
+	pub(crate) fn push(&mut self, filter: Box<dyn InputFilter>) {
+		self.chain.push(filter);
+	}
+
+	/// Runs every queued input event through the chain, keeping other events in place.
+	pub(crate) fn apply(&mut self, queue: &mut VecDeque<QueuedEvent>) {
+		if self.chain.is_empty() {
+			return;
+		}
+		let mut filtered = VecDeque::with_capacity(queue.len());
+		let mut batch = Vec::new();
+		let mut next = Vec::new();
+		for event in queue.drain(..) {
+			let QueuedEvent::Input(TabInputEvent::Event { seat, payload }) = event else {
+				filtered.push_back(event);
+				continue;
+			};
+			batch.push(payload);
+			for filter in &mut self.chain {
+				for payload in batch.drain(..) {
+					filter.filter(&seat, payload, &mut next);
+				}
+				std::mem::swap(&mut batch, &mut next);
+			}
+			filtered.extend(batch.drain(..).map(|payload| {
+				QueuedEvent::Input(TabInputEvent::Event {
+					seat: seat.clone(),
+					payload,
+				})
+			}));
+		}
+		*queue = filtered;
+	}
+}
@@ -1,26 +1,122 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::hash::BuildHasher;
 use std::marker::PhantomData;
-use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd, RawFd};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
+pub use monitor_layout_engine::{EdgeBehavior, LayoutChange, WrapMode};
 use monitor_layout_engine::{
-	MonitorPlacement, MonitorSpec, clamp_point_to_layout, is_valid_edge_contiguous_layout,
-	layout_horizontal, move_cursor_no_tunnel,
+	EdgeState, MonitorPlacement, MonitorSpec, clamp_point_to_layout, diff_layouts,
+	is_valid_edge_contiguous_layout, layout_horizontal, monitor_at, move_cursor_with_wrap,
 };
+use tab_client::{
+	ClockSync, TabBuffer, TabClient, TabClientConfig, TabClientError, TabEvent, TabSwapchain,
+};
+pub use tab_client::{DrmFourcc, ExtensionEvent, ServerError};
 use tab_client::{
 	InputEvent as TabInputEvent, MonitorEvent as TabMonitorEvent, RenderEvent as TabRenderEvent,
 };
-use tab_client::{TabClient, TabClientConfig, TabClientError, TabSwapchain};
-use tab_protocol::{BufferIndex, ButtonState, InputEventPayload, KeyState, TouchContact};
+pub use tab_protocol::message_frame::TabMessageFrame;
+pub use tab_protocol::{
+	AccessibilitySettings, CaptureSource, Chromaticity, Colorspace, GammaRamps,
+	InputDeviceCapability, InputDeviceInfo, InputEventPayload, MonitorColorInfo, MonitorMode,
+	MonitorPower, ProtocolError, Rect, SeatId, ServerErrorCode, SessionCreatedPayload, SessionInfo,
+	SessionRole,
+};
+use tab_protocol::{
+	BufferIndex, ButtonState, GlobalShortcutRegisterPayload, KeyState, SessionMessageSendPayload,
+	TouchContact, unix_socket_utils,
+};
 use thiserror::Error;
-use tracing::{debug, info};
-pub use tab_protocol::{SessionCreatedPayload, SessionInfo, SessionRole};
+use tracing::{debug, error, info};
+
+mod animation;
+#[cfg(feature = "c-bindings")]
+mod c_bindings;
+mod capture;
+#[cfg(feature = "cli")]
+mod cli;
+mod clock;
+mod composition;
+mod controller;
+mod crash;
+mod dedup;
+mod driver;
+mod input_filter;
+#[cfg(feature = "json-logs")]
+mod logging;
+mod mapped;
+#[cfg(feature = "nested")]
+mod nested;
+mod overlay;
+#[cfg(feature = "python")]
+mod python;
+mod snapshot;
+mod switcher;
+#[cfg(feature = "testing")]
+mod testing;
+#[cfg(feature = "testing")]
+pub use testing::{
+	MockCapture, MockOverlay, MockSessionMessage, MockTabAppFramework, SubmittedFrame,
+};
+
+pub use animation::{Easing, Lerp, Timeline, Tween};
+pub use capture::CaptureId;
+use capture::Captures;
+pub use clock::PresentationClock;
+use composition::TextComposer;
+use controller::ControlReceiver;
+pub use controller::{FrameworkController, UserEvent};
+use crash::CrashHook;
+use dedup::ErrorDedup;
+pub use driver::{DrivenFramework, MultiFrameworkDriver};
+pub use input_filter::InputFilter;
+use input_filter::InputFilters;
+#[cfg(feature = "json-logs")]
+pub use logging::init_json_logging;
+pub use mapped::MappedTarget;
+use overlay::Overlays;
+pub use overlay::{OverlayFrame, OverlayId};
+pub use snapshot::{
+	BufferState, DebugSnapshot, MonitorSnapshot, SeatSnapshot, SwapchainSnapshot,
+	TouchContactSnapshot,
+};
+pub use switcher::{SessionSwitch, SessionSwitchController, SwitchGesture};
 
 const BTN_LEFT: u32 = 272;
 
+/// Emits a `TRACE` span tagged with the current frame id when frame tracing is enabled.
+macro_rules! frame_span {
+	($tracer:expr, $name:literal $(, $field:ident = $value:expr)*) => {
+		$tracer.enabled.then(|| {
+			tracing::trace_span!(
+				target: "tab_app_framework.core",
+				$name,
+				frame = $tracer.current.frame_id
+				$(, $field = $value)*
+			)
+			.entered()
+		})
+	};
+}
+
+/// Emits a `DEBUG` event per render, request or release when `TAB_APP_FRAMEWORK_TRACE` is set.
+///
+/// Fields are recorded as structured values, so nothing is formatted or allocated unless a
+/// subscriber takes the event. The `strip-instant-log` feature compiles the events out.
+macro_rules! instant_log {
+	($stats:expr, $msg:literal $(, $($field:tt)+)?) => {
+		if cfg!(not(feature = "strip-instant-log")) && $stats.enabled {
+			tracing::debug!(target: "tab_app_framework.core", $($($field)+,)? $msg);
+		}
+	};
+}
+
 /// Frame scheduling policy used by the runtime.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum RenderMode {
@@ -30,14 +126,195 @@ pub enum RenderMode {
 	Scheduled,
 }
 
+impl std::str::FromStr for RenderMode {
+	type Err = String;
+
+	/// Parses `eager` or `scheduled`, ignoring case.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"eager" => Ok(Self::Eager),
+			"scheduled" => Ok(Self::Scheduled),
+			_ => Err(format!(
+				"invalid render mode {s:?}, expected eager or scheduled"
+			)),
+		}
+	}
+}
+
+/// How GL frames are synchronized with the server's reads of their buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncMode {
+	/// Explicit sync when the driver exports native fences, implicit sync otherwise.
+	#[default]
+	Auto,
+	/// Attach a native fence to every frame; frames fail without
+	/// `EGL_ANDROID_native_fence_sync`.
+	Explicit,
+	/// Never create fences; wait for the GPU with `glFinish` before each submit and
+	/// rely on the dmabuf's implicit fencing.
+	Implicit,
+}
+
+impl std::str::FromStr for SyncMode {
+	type Err = String;
+
+	/// Parses `auto`, `explicit` or `implicit`, ignoring case.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_ascii_lowercase().as_str() {
+			"auto" => Ok(Self::Auto),
+			"explicit" => Ok(Self::Explicit),
+			"implicit" => Ok(Self::Implicit),
+			_ => Err(format!(
+				"invalid sync mode {s:?}, expected auto, explicit or implicit"
+			)),
+		}
+	}
+}
+
+/// What happens to events beyond [`Config::set_event_queue_capacity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum QueueOverflow {
+	/// Drop the oldest continuous input (pointer motion, scroll and axis updates); keys,
+	/// buttons, touch down/up and every non-input event are always kept.
+	///
+	/// When only such state transitions remain, the queue stays over capacity and the
+	/// overflow is still reported.
+	#[default]
+	DropOldestInput,
+	/// Merge relative pointer motion per device, as [`Config::coalesce_pointer_motion`]
+	/// does, and drop the oldest continuous input if that is not enough.
+	CoalesceMotion,
+	/// Deliver at most the capacity per loop iteration and stop reading the server
+	/// socket until the backlog is delivered; nothing is dropped.
+	BlockDispatch,
+}
+
+/// When the framework sends `session_ready` on its own; see [`Config::auto_ready`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AutoReady {
+	/// Once the server presented the first frame on any monitor.
+	AfterFirstPresent,
+	/// At the start of the first loop iteration, right after [`Application::init`].
+	AfterInit,
+	/// Never; the application calls [`Context::session_ready`] itself.
+	#[default]
+	Manual,
+}
+
+/// Color encoding requested for GL rendering.
+///
+/// Monitor swapchains are allocated in the matching pixel format, see
+/// [`GlSurfaceFormat::swapchain_format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlSurfaceFormat {
+	/// 8-bit-per-channel linear RGBA.
+	#[default]
+	Rgba8888,
+	/// 8-bit-per-channel RGBA with sRGB encoding on framebuffer writes.
+	Srgb8888,
+	/// 10-bit color with 2-bit alpha.
+	Rgba1010102,
+	/// Half-float RGBA for HDR pipelines.
+	RgbaF16,
+}
+
+impl GlSurfaceFormat {
+	/// Returns true when framebuffer writes are sRGB-encoded.
+	pub fn is_srgb(self) -> bool {
+		matches!(self, Self::Srgb8888)
+	}
+
+	/// Returns true for floating-point color formats.
+	pub fn is_float(self) -> bool {
+		matches!(self, Self::RgbaF16)
+	}
+
+	/// DRM format of monitor swapchain buffers holding this format; alpha is unused.
+	pub fn swapchain_format(self) -> DrmFourcc {
+		match self {
+			Self::Rgba8888 | Self::Srgb8888 => DrmFourcc::Xrgb8888,
+			Self::Rgba1010102 => DrmFourcc::Xrgb2101010,
+			Self::RgbaF16 => DrmFourcc::Xbgr16161616f,
+		}
+	}
+}
+
+/// GPU scheduling priority requested for the GL context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlContextPriority {
+	/// Yields the GPU to other clients, e.g. for background work.
+	Low,
+	/// Driver default.
+	#[default]
+	Medium,
+	/// Preferred by the GPU scheduler, e.g. for latency-sensitive shells.
+	High,
+}
+
+/// Flags requested when creating the GL context.
+///
+/// Flags the driver does not support are dropped with a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct GlContextOptions {
+	/// Requests a debug context and forwards `KHR_debug` messages into `tracing`.
+	pub debug: bool,
+	/// Requests a robust context that reports GPU resets instead of terminating.
+	pub robustness: bool,
+	/// Scheduling priority hint, honored with `EGL_IMG_context_priority`.
+	pub priority: GlContextPriority,
+}
+
+/// Attachments added to the framebuffers a GL application renders monitor frames into.
+///
+/// The swapchain buffers only hold color; depth, stencil and multisampled color live in
+/// GPU-private renderbuffers shared by a monitor's buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderTargetOptions {
+	/// Attaches a 24-bit depth and 8-bit stencil buffer.
+	pub depth_stencil: bool,
+	/// Multisample count; above 1, frames are rendered multisampled and resolved into the
+	/// swapchain buffer before they are submitted. Clamped to the driver's maximum.
+	pub samples: u8,
+}
+
 /// Runtime configuration used during framework initialization.
 #[derive(Debug, Clone)]
 pub struct Config {
 	token: String,
 	socket_path: PathBuf,
 	render_node_path: Option<PathBuf>,
+	detected_render_node: Option<PathBuf>,
 	render_mode: RenderMode,
 	opengl_version: (u8, u8),
+	surface_format: GlSurfaceFormat,
+	gl_context_options: GlContextOptions,
+	render_target_options: RenderTargetOptions,
+	frame_tracing: bool,
+	stats_interval: Duration,
+	batch_events: bool,
+	coalesce_pointer_motion: bool,
+	event_queue_capacity: Option<(usize, QueueOverflow)>,
+	text_composition: bool,
+	edge_behavior: EdgeBehavior,
+	wrap_mode: WrapMode,
+	explicit_submit: bool,
+	parallel_render: bool,
+	linear_buffers: bool,
+	shm_buffers: bool,
+	debug_overlay: bool,
+	sync_mode: SyncMode,
+	clear_policies: HashMap<String, ClearPolicy>,
+	retry_policies: HashMap<String, RetryPolicy>,
+	default_retry_policy: RetryPolicy,
+	buffer_request_ack_timeout: Duration,
+	ping_interval: Option<Duration>,
+	watchdog_threshold: u32,
+	starvation_threshold: Option<Duration>,
+	error_dedup_window: Option<Duration>,
+	dump_state_on_error: bool,
+	auto_ready: AutoReady,
+	crash_reports: bool,
+	app_version: Option<String>,
 }
 
 impl Config {
@@ -47,18 +324,121 @@ impl Config {
 			token: token.into(),
 			socket_path: tab_protocol::DEFAULT_SOCKET_PATH.into(),
 			render_node_path: None,
+			detected_render_node: None,
 			render_mode: RenderMode::Scheduled,
 			opengl_version: (3, 3),
+			surface_format: GlSurfaceFormat::Rgba8888,
+			gl_context_options: GlContextOptions::default(),
+			render_target_options: RenderTargetOptions::default(),
+			frame_tracing: false,
+			stats_interval: Duration::from_secs(1),
+			batch_events: false,
+			coalesce_pointer_motion: false,
+			event_queue_capacity: None,
+			text_composition: false,
+			edge_behavior: EdgeBehavior::Free,
+			wrap_mode: WrapMode::None,
+			explicit_submit: false,
+			parallel_render: false,
+			linear_buffers: false,
+			shm_buffers: false,
+			debug_overlay: false,
+			sync_mode: SyncMode::Auto,
+			clear_policies: HashMap::new(),
+			retry_policies: HashMap::new(),
+			default_retry_policy: RetryPolicy::default(),
+			buffer_request_ack_timeout: TabClientConfig::DEFAULT_BUFFER_REQUEST_ACK_TIMEOUT,
+			ping_interval: Some(Duration::from_secs(1)),
+			watchdog_threshold: 3,
+			starvation_threshold: Some(Duration::from_millis(500)),
+			error_dedup_window: Some(Duration::from_secs(1)),
+			dump_state_on_error: false,
+			auto_ready: AutoReady::Manual,
+			crash_reports: false,
+			app_version: None,
 		}
 	}
 
+	/// Creates a configuration reading the session token from the file at `path`.
+	///
+	/// Unlike environment variables and arguments, the file does not expose the token
+	/// through `/proc`.
+	pub fn from_token_file(path: impl AsRef<Path>) -> Result<Self, FrameworkError> {
+		let client = TabClientConfig::token_from_file(path)?;
+		Ok(Self::from_token(client.token()))
+	}
+
+	/// Creates a configuration reading the session token from `fd`, e.g. a pipe, which is
+	/// closed afterwards.
+	pub fn from_token_fd(fd: OwnedFd) -> Result<Self, FrameworkError> {
+		let client = TabClientConfig::token_from_fd(fd)?;
+		Ok(Self::from_token(client.token()))
+	}
+
 	/// Creates a configuration from process environment.
 	///
-	/// Requires `SHIFT_SESSION_TOKEN`.
+	/// Requires a session token: the fd in `SHIFT_SESSION_TOKEN_FD`, which shift uses for
+	/// the sessions it spawns, the file named by `SHIFT_SESSION_TOKEN_FILE`, or
+	/// `SHIFT_SESSION_TOKEN`. The socket is discovered as described in
+	/// [`Config::socket_candidates`].
 	pub fn from_env() -> Result<Self, FrameworkError> {
-		let token = std::env::var("SHIFT_SESSION_TOKEN")
-			.map_err(|_| FrameworkError::Config("missing SHIFT_SESSION_TOKEN".into()))?;
-		Ok(Self::from_token(token))
+		let token = tab_client::session_token_from_env()
+			.map_err(|err| FrameworkError::Config(err.to_string()))?;
+		let mut cfg = Self::from_token(token);
+		cfg.socket_path = unix_socket_utils::discover_socket(&env_seat());
+		Ok(cfg)
+	}
+
+	/// Lists the shift sockets this process could connect to, most preferred first, e.g. to
+	/// let the user choose between the seats of a multi-seat system.
+	///
+	/// `SHIFT_SOCKET` comes first if set, then `$XDG_RUNTIME_DIR/shift/<seat>.sock` for the
+	/// seat named by `SHIFT_SEAT` (`default` if unset), the sockets of other seats, and
+	/// finally the legacy `/tmp/shift.sock`. Only existing sockets are listed, apart from
+	/// `SHIFT_SOCKET`. [`Config::from_env`] picks `SHIFT_SOCKET`, else the seat's own
+	/// socket, else the legacy path.
+	pub fn socket_candidates() -> Vec<PathBuf> {
+		unix_socket_utils::socket_candidates(&env_seat())
+	}
+
+	/// Creates a configuration from every supported environment variable.
+	///
+	/// Requires a session token as for [`Config::from_env`] and honors `SHIFT_SOCKET`,
+	/// `SHIFT_RENDER_NODE`, `SHIFT_RENDER_MODE` (`eager`/`scheduled`), `SHIFT_GL_VERSION`
+	/// (`major.minor`) and `SHIFT_SYNC_MODE` (`auto`/`explicit`/`implicit`).
+	/// Malformed values are reported instead of ignored.
+	pub fn from_env_full() -> Result<Self, FrameworkError> {
+		let mut cfg = Self::from_env()?;
+		cfg.apply_env()?;
+		Ok(cfg)
+	}
+
+	fn apply_env(&mut self) -> Result<(), FrameworkError> {
+		let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+		if let Some(path) = var("SHIFT_SOCKET") {
+			self.set_socket_path(path);
+		}
+		if let Some(path) = var("SHIFT_RENDER_NODE") {
+			self.set_render_node_path(path);
+		}
+		if let Some(mode) = var("SHIFT_RENDER_MODE") {
+			let mode = mode
+				.parse()
+				.map_err(|err| FrameworkError::Config(format!("SHIFT_RENDER_MODE: {err}")))?;
+			self.set_render_mode(mode);
+		}
+		if let Some(version) = var("SHIFT_GL_VERSION") {
+			let (major, minor) = parse_gl_version(&version)
+				.map_err(|err| FrameworkError::Config(format!("SHIFT_GL_VERSION: {err}")))?;
+			self.opengl_version(major, minor);
+		}
+		if let Some(mode) = var("SHIFT_SYNC_MODE") {
+			let mode = mode
+				.parse()
+				.map_err(|err| FrameworkError::Config(format!("SHIFT_SYNC_MODE: {err}")))?;
+			self.set_sync_mode(mode);
+		}
+		Ok(())
 	}
 
 	/// Sets the session token used for authentication.
@@ -91,952 +471,4487 @@ impl Config {
 		self
 	}
 
-	/// Returns the configured render mode.
-	pub fn render_mode(&self) -> RenderMode {
-		self.render_mode
+	/// Requests a GL surface color format.
+	///
+	/// Unsupported formats, including wide formats the render node cannot allocate
+	/// swapchains for, fall back to [`GlSurfaceFormat::Rgba8888`].
+	pub fn set_surface_format(&mut self, format: GlSurfaceFormat) -> &mut Self {
+		self.surface_format = format;
+		self
 	}
 
-	/// Returns the requested OpenGL/OpenGL ES version.
-	pub fn requested_opengl_version(&self) -> (u8, u8) {
-		self.opengl_version
+	/// Sets debug, robustness and priority flags for the GL context.
+	pub fn set_gl_context_options(&mut self, options: GlContextOptions) -> &mut Self {
+		self.gl_context_options = options;
+		self
 	}
 
-	/// Returns the configured session token.
-	pub fn token(&self) -> &str {
-		&self.token
+	/// Sets the depth/stencil and MSAA attachments of every monitor's render targets.
+	///
+	/// GL applications can override them per monitor through `GlContext`.
+	pub fn set_render_target_options(&mut self, options: RenderTargetOptions) -> &mut Self {
+		self.render_target_options = options;
+		self
 	}
 
-	/// Returns the configured render node path, if set.
-	pub fn render_node_path(&self) -> Option<&Path> {
-		self.render_node_path.as_deref()
+	/// Enables per-frame `tracing` spans for Tracy/Perfetto-style profilers.
+	///
+	/// Spans are emitted at `TRACE` level on `tab_app_framework.core`, each
+	/// carrying the loop iteration as its `frame` field.
+	pub fn set_frame_tracing(&mut self, enabled: bool) -> &mut Self {
+		self.frame_tracing = enabled;
+		self
 	}
-}
 
-/// Top-level framework errors.
-#[derive(Debug, Error)]
-pub enum FrameworkError {
-	#[error("framework config error: {0}")]
-	Config(String),
-	#[error("tab client error: {0}")]
-	Client(#[from] TabClientError),
-	#[error("poll failed: {0}")]
-	Poll(std::io::Error),
-	#[error("monitor not found: {0}")]
-	MonitorNotFound(String),
-}
+	/// Sets how often loop statistics are rolled over and reported via `on_stats`.
+	pub fn set_stats_interval(&mut self, interval: Duration) -> &mut Self {
+		self.stats_interval = interval;
+		self
+	}
 
-/// Logical monitor metadata exposed to applications.
-#[derive(Debug, Clone)]
-pub struct Monitor {
-	/// Stable monitor identifier used by the protocol.
-	pub id: String,
-	/// Human-readable monitor name.
-	pub name: String,
-	/// Logical width in pixels.
-	pub width: i32,
-	/// Logical height in pixels.
-	pub height: i32,
-	/// Nominal refresh rate in Hz.
-	pub refresh_rate: i32,
-	/// Monitor origin X in global layout space.
-	pub x: i32,
-	/// Monitor origin Y in global layout space.
-	pub y: i32,
-	/// Scale factor for logical-to-physical mapping.
-	pub scale: f64,
-}
+	/// Delivers events in one [`Application::on_events`] call per loop iteration.
+	///
+	/// When enabled, the per-event callbacks covered by [`Event`] are not invoked.
+	pub fn set_batch_events(&mut self, enabled: bool) -> &mut Self {
+		self.batch_events = enabled;
+		self
+	}
 
-impl Monitor {
-	fn from_tab_monitor(state: &tab_client::MonitorState) -> Self {
-		Self {
-			id: state.info.id.clone(),
-			name: state.info.name.clone(),
-			width: state.info.width,
-			height: state.info.height,
-			refresh_rate: state.info.refresh_rate,
-			x: 0,
-			y: 0,
-			scale: 1.0,
-		}
+	/// Merges consecutive relative pointer motion per device before dispatch.
+	///
+	/// Deltas are summed and the newest timestamp and absolute position kept, so
+	/// high-rate mice produce at most one move per device between other events.
+	pub fn coalesce_pointer_motion(&mut self, enabled: bool) -> &mut Self {
+		self.coalesce_pointer_motion = enabled;
+		self
 	}
 
-	/// Converts a global cursor position into monitor-local coordinates.
+	/// Bounds the events queued for delivery in one loop iteration (default unbounded).
 	///
-	/// The returned coordinates are not clamped to monitor bounds.
-	pub fn cursor_relative_position(&self, global_position: (f64, f64)) -> (f64, f64) {
-		(
-			global_position.0 - self.x as f64,
-			global_position.1 - self.y as f64,
-		)
+	/// A stalled application otherwise buffers every event that arrives in the meantime.
+	/// Once more than `capacity` events are queued, `overflow` applies and
+	/// [`FrameworkError::EventQueueOverflow`] is reported through [`Application::on_error`].
+	pub fn set_event_queue_capacity(
+		&mut self,
+		capacity: Option<usize>,
+		overflow: QueueOverflow,
+	) -> &mut Self {
+		self.event_queue_capacity = capacity.map(|capacity| (capacity.max(1), overflow));
+		self
 	}
-}
 
-fn recompute_layout(monitors: &mut HashMap<String, MonitorRuntime>) {
-	let specs: Vec<_> = monitors
-		.values()
-		.map(|m| MonitorSpec {
-			id: m.monitor.id.clone(),
-			width: m.monitor.width,
-			height: m.monitor.height,
-		})
-		.collect();
-	let placements = layout_horizontal(&specs);
-	for p in placements {
-		if let Some(m) = monitors.get_mut(&p.id) {
-			m.monitor.x = p.x;
-			m.monitor.y = p.y;
-		}
+	/// Turns key presses into text through XKB, delivered to [`Application::on_char`] after
+	/// the matching [`Application::on_key`].
+	///
+	/// Needs the `text-composition` feature; without it, [`TabAppFramework::init`] fails.
+	/// The GL and Skia frameworks always enable it.
+	pub fn enable_text_composition(&mut self, enabled: bool) -> &mut Self {
+		self.text_composition = enabled;
+		self
 	}
-}
 
-fn current_layout(monitors: &HashMap<String, MonitorRuntime>) -> Vec<MonitorPlacement> {
-	monitors
-		.values()
-		.map(|m| MonitorPlacement {
-			id: m.monitor.id.clone(),
-			x: m.monitor.x,
-			y: m.monitor.y,
-			width: m.monitor.width,
-			height: m.monitor.height,
-		})
-		.collect()
-}
+	/// Sets how the cursor crosses edges between monitors (default [`EdgeBehavior::Free`]).
+	///
+	/// [`EdgeBehavior::Resistance`] holds the cursor at an edge until the given distance
+	/// was pushed against it; [`EdgeBehavior::StickyCorners`] blocks crossings within the
+	/// given distance of a monitor corner.
+	pub fn set_edge_behavior(&mut self, behavior: EdgeBehavior) -> &mut Self {
+		self.edge_behavior = behavior;
+		self
+	}
 
-/// Render callback payload containing the acquired client buffer.
-#[derive(Debug, Clone)]
-pub struct RenderEvent {
-	/// Target monitor id.
-	pub monitor_id: String,
-	/// Acquired swapchain buffer index.
-	pub buffer_index: BufferIndex,
-	/// DMA-BUF file descriptor for the render target.
-	pub dmabuf_fd: RawFd,
-	/// Buffer width in pixels.
-	pub width: i32,
-	/// Buffer height in pixels.
-	pub height: i32,
-	/// Buffer stride in bytes.
-	pub stride: i32,
-	/// Buffer offset in bytes.
-	pub offset: i32,
-	/// DRM fourcc pixel format.
-	pub fourcc: i32,
-}
+	/// Sets the outer layout edges the cursor wraps around (default [`WrapMode::None`]).
+	///
+	/// With [`WrapMode::Horizontal`], pushing past the right edge of the rightmost monitor
+	/// brings the cursor in at the left edge of the leftmost one, e.g. for kiosks.
+	pub fn set_wrap_mode(&mut self, mode: WrapMode) -> &mut Self {
+		self.wrap_mode = mode;
+		self
+	}
 
-/// Present callback payload emitted after a rendered buffer is released.
-#[derive(Debug, Clone)]
-pub struct PresentEvent {
-	/// Target monitor id.
-	pub monitor_id: String,
-	/// Buffer index that reached presentation completion.
-	pub buffer_index: BufferIndex,
-}
+	/// Lets the application acquire and submit frames itself.
+	///
+	/// Scheduled monitors are then reported through [`Application::on_frame_requested`]
+	/// instead of `on_render`; see [`Context::acquire_target`].
+	pub fn set_explicit_submit(&mut self, enabled: bool) -> &mut Self {
+		self.explicit_submit = enabled;
+		self
+	}
 
-/// Emitted when a monitor is added.
-#[derive(Debug, Clone)]
-pub struct MonitorAddedEvent {
-	/// Added monitor metadata.
-	pub monitor: Monitor,
-}
+	/// Offers all monitors due in an iteration to [`Application::on_render_parallel`] at once,
+	/// so GL bridges can render them concurrently on shared contexts.
+	///
+	/// Only used when more than one monitor is due; monitors the hook does not submit are
+	/// rendered through `on_render` as usual. Ignored in explicit submit mode.
+	pub fn set_parallel_render(&mut self, enabled: bool) -> &mut Self {
+		self.parallel_render = enabled;
+		self
+	}
 
-/// Emitted when a monitor is removed.
-#[derive(Debug, Clone)]
-pub struct MonitorRemovedEvent {
-	/// Removed monitor id.
-	pub monitor_id: String,
-	/// Removed monitor name.
-	pub name: String,
-}
+	/// Allocates linear swapchain buffers the CPU can write through [`RenderTarget::map`].
+	///
+	/// Meant for software rendering; linear buffers are slower for the GPU to render into.
+	pub fn set_linear_buffers(&mut self, enabled: bool) -> &mut Self {
+		self.linear_buffers = enabled;
+		self
+	}
 
-/// Session state update payload.
-#[derive(Debug, Clone)]
-pub struct SessionEvent {
-	/// Full session state snapshot.
-	pub session: SessionInfo,
-}
+	/// Allocates every swapchain in shared memory and opens no render node, so core
+	/// applications run without GPU access, e.g. in headless containers.
+	///
+	/// Draw through [`RenderTarget::map`]. The server uploads each submitted frame, which
+	/// suits small or rarely updated content. Overlays and captures are unavailable; see
+	/// [`Context::create_shm_surface`] to move single monitors to shared memory.
+	pub fn set_shm_buffers(&mut self, enabled: bool) -> &mut Self {
+		self.shm_buffers = enabled;
+		self
+	}
 
-/// Emitted when a watched file descriptor becomes readable.
-#[derive(Debug, Clone)]
-pub struct FdReadyEvent {
-	/// File descriptor that is ready.
-	pub fd: RawFd,
-}
+	/// Starts GL apps with the FPS and frame-timing overlay shown.
+	///
+	/// It can be toggled later through `GlEventContext::set_debug_overlay`.
+	pub fn set_debug_overlay(&mut self, enabled: bool) -> &mut Self {
+		self.debug_overlay = enabled;
+		self
+	}
 
-/// Raw input payload forwarded from the server.
-#[derive(Debug, Clone)]
-pub struct InputEvent {
-	/// Protocol input event payload.
-	pub payload: InputEventPayload,
-}
+	/// Sets how GL apps synchronize frames with the server, [`SyncMode::Auto`] by default.
+	///
+	/// Without `EGL_ANDROID_native_fence_sync` no acquire fence can be attached to a
+	/// frame; [`SyncMode::Auto`] then falls back to implicit sync, while
+	/// [`SyncMode::Explicit`] fails every frame with `GlError::MissingEglDupNativeFenceFd`.
+	pub fn set_sync_mode(&mut self, mode: SyncMode) -> &mut Self {
+		self.sync_mode = mode;
+		self
+	}
 
-/// Keyboard event payload.
-#[derive(Debug, Clone)]
-pub struct KeyEvent {
-	/// Input device id.
-	pub device: u32,
-	/// Event timestamp in microseconds.
-	pub time_usec: u64,
-	/// Linux keycode.
-	pub key: u32,
-	/// Key state.
-	pub state: KeyState,
-}
+	/// Sets the [`ClearPolicy`] a monitor starts with whenever it appears.
+	///
+	/// It can be changed later through [`Context::set_clear_policy`].
+	pub fn set_clear_policy(
+		&mut self,
+		monitor_id: impl Into<String>,
+		policy: ClearPolicy,
+	) -> &mut Self {
+		self.clear_policies.insert(monitor_id.into(), policy);
+		self
+	}
 
-impl KeyEvent {
-	/// Returns `true` when this event is a key press.
-	pub fn is_pressed(&self) -> bool {
-		matches!(self.state, KeyState::Pressed)
+	/// Sets how long a submitted frame may wait for the server's acknowledgement before
+	/// it counts as timed out. Defaults to 250 ms; raise it on heavily loaded systems.
+	pub fn set_buffer_request_ack_timeout(&mut self, timeout: Duration) -> &mut Self {
+		self.buffer_request_ack_timeout = timeout;
+		self
 	}
-}
 
-/// Text composition event payload.
-#[derive(Debug, Clone)]
-pub struct CharEvent {
-	/// Composed UTF-8 text.
-	pub text: String,
-}
+	/// Sets how often the server is pinged to measure [`Context::server_latency`] and detect a
+	/// stalled server. Defaults to 1 s; `None` disables pings and the liveness callbacks.
+	pub fn set_ping_interval(&mut self, interval: Option<Duration>) -> &mut Self {
+		self.ping_interval = interval;
+		self
+	}
 
-/// Pointer device class for pointer-style events.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PointerType {
-	/// Mouse-like pointer device (mouse/touchpad/trackpoint).
-	Mouse,
-	/// Pen/stylus input device.
-	Pen,
-	/// Touch contact input device.
-	Touch,
-	/// Device class could not be determined.
-	Unknown,
-}
+	/// Sets how many consecutive ack timeouts on one monitor raise
+	/// [`FrameworkError::Watchdog`]. Defaults to 3; single timeouts are only logged.
+	pub fn set_watchdog_threshold(&mut self, timeouts: u32) -> &mut Self {
+		self.watchdog_threshold = timeouts.max(1);
+		self
+	}
 
-/// Generic pointer movement event (browser-like `pointermove` semantics).
-#[derive(Debug, Clone)]
-pub struct PointerMoveEvent {
-	/// Source input device id.
-	pub device: u32,
-	/// Event timestamp in microseconds.
-	pub time_usec: u64,
-	/// Pointer class that moved the cursor.
-	pub pointer_type: PointerType,
-	/// Previous cursor position in global layout space.
-	pub old_position: (f64, f64),
-	/// New cursor position in global layout space.
-	pub new_position: (f64, f64),
-}
+	/// Sets how long a monitor may go without a free buffer before
+	/// [`Application::on_starved`] fires; `None` disables the callback. Defaults to 500 ms.
+	pub fn set_starvation_threshold(&mut self, threshold: Option<Duration>) -> &mut Self {
+		self.starvation_threshold = threshold;
+		self
+	}
 
-impl PointerMoveEvent {
-	/// Returns `(dx, dy)` in global layout space.
-	pub fn delta(&self) -> (f64, f64) {
-		(
-			self.new_position.0 - self.old_position.0,
-			self.new_position.1 - self.old_position.1,
-		)
+	/// Sets the window within which errors with the same message reach
+	/// [`Application::on_error`] only once; `None` delivers every error.
+	///
+	/// The repeats are counted and reported as one [`FrameworkError::Repeated`] when the
+	/// window ends. Defaults to one second.
+	pub fn set_error_dedup_window(&mut self, window: Option<Duration>) -> &mut Self {
+		self.error_dedup_window = window;
+		self
 	}
-}
 
-/// Mouse-only movement event (browser-like `mousemove` semantics).
-#[derive(Debug, Clone)]
-pub struct MouseMoveEvent {
-	/// Source input device id.
-	pub device: u32,
-	/// Event timestamp in microseconds.
-	pub time_usec: u64,
-	/// Previous cursor position in global layout space.
-	pub old_position: (f64, f64),
-	/// New cursor position in global layout space.
-	pub new_position: (f64, f64),
-}
+	/// Logs a [`DebugSnapshot`] as JSON at `ERROR` on `tab_app_framework.core` before each
+	/// error reaches [`Application::on_error`], e.g. for bug reports from the field.
+	pub fn set_dump_state_on_error(&mut self, enabled: bool) -> &mut Self {
+		self.dump_state_on_error = enabled;
+		self
+	}
 
-impl MouseMoveEvent {
-	/// Returns `(dx, dy)` in global layout space.
-	pub fn delta(&self) -> (f64, f64) {
-		(
-			self.new_position.0 - self.old_position.0,
-			self.new_position.1 - self.old_position.1,
-		)
+	/// Sends `session_ready` without the application calling [`Context::session_ready`]
+	/// (default [`AutoReady::Manual`]).
+	///
+	/// [`AutoReady::AfterFirstPresent`] keeps the session loading until the first frame is
+	/// on screen. [`Application::on_session_ready_sent`] confirms the send.
+	pub fn auto_ready(&mut self, mode: AutoReady) -> &mut Self {
+		self.auto_ready = mode;
+		self
 	}
-}
 
-/// Pointer down event (browser-like `pointerdown` semantics).
-#[derive(Debug, Clone)]
-pub struct PointerDownEvent {
-	/// Source input device id.
-	pub device: u32,
-	/// Event timestamp in microseconds.
-	pub time_usec: u64,
-	/// Pointer class for this event.
-	pub pointer_type: PointerType,
-	/// Logical button code.
-	pub button: u32,
-	/// Cursor position in global layout space.
-	pub position: (f64, f64),
-}
+	/// Sends a crash report to shift when the event loop's thread panics.
+	///
+	/// The report carries the panic message, a hash of the backtrace and the version from
+	/// [`Config::set_app_version`]; admin sessions receive it through
+	/// [`Application::on_session_crashed`]. Off by default.
+	pub fn set_crash_reports(&mut self, enabled: bool) -> &mut Self {
+		self.crash_reports = enabled;
+		self
+	}
 
-/// Pointer up event (browser-like `pointerup` semantics).
-#[derive(Debug, Clone)]
-pub struct PointerUpEvent {
-	/// Source input device id.
-	pub device: u32,
-	/// Event timestamp in microseconds.
-	pub time_usec: u64,
-	/// Pointer class for this event.
-	pub pointer_type: PointerType,
-	/// Logical button code.
-	pub button: u32,
-	/// Cursor position in global layout space.
-	pub position: (f64, f64),
-}
+	/// Sets the application version included in crash reports.
+	pub fn set_app_version(&mut self, version: impl Into<String>) -> &mut Self {
+		self.app_version = Some(version.into());
+		self
+	}
 
-/// Mouse down event (browser-like `mousedown` semantics).
-#[derive(Debug, Clone)]
-pub struct MouseDownEvent {
-	/// Source input device id.
-	pub device: u32,
-	/// Event timestamp in microseconds.
-	pub time_usec: u64,
-	/// Mouse button code.
-	pub button: u32,
-	/// Cursor position in global layout space.
-	pub position: (f64, f64),
-}
+	/// Sets the [`RetryPolicy`] of monitors without one from [`Config::set_retry_policy`].
+	pub fn set_default_retry_policy(&mut self, policy: RetryPolicy) -> &mut Self {
+		self.default_retry_policy = policy;
+		self
+	}
 
-/// Mouse up event (browser-like `mouseup` semantics).
-#[derive(Debug, Clone)]
-pub struct MouseUpEvent {
-	/// Source input device id.
-	pub device: u32,
-	/// Event timestamp in microseconds.
-	pub time_usec: u64,
-	/// Mouse button code.
-	pub button: u32,
-	/// Cursor position in global layout space.
-	pub position: (f64, f64),
-}
+	/// Sets the [`RetryPolicy`] a monitor starts with whenever it appears.
+	///
+	/// It can be changed later through [`Context::set_retry_policy`].
+	pub fn set_retry_policy(
+		&mut self,
+		monitor_id: impl Into<String>,
+		policy: RetryPolicy,
+	) -> &mut Self {
+		self.retry_policies.insert(monitor_id.into(), policy);
+		self
+	}
 
-/// High-level touch event stream preserving contact ids for multitouch.
-#[derive(Debug, Clone)]
-pub enum TouchEvent {
-	/// New touch contact.
-	Down {
-		device: u32,
-		time_usec: u64,
-		contact: TouchContact,
-	},
-	/// Updated touch contact.
-	Motion {
-		device: u32,
-		time_usec: u64,
-		contact: TouchContact,
-	},
-	/// Touch contact ended.
-	Up {
-		device: u32,
-		time_usec: u64,
-		contact_id: i32,
-	},
-	/// End of touch event frame batch.
-	Frame {
-		time_usec: u64,
-	},
-	/// Touch sequence cancelled.
-	Cancel {
-		time_usec: u64,
-	},
-}
+	/// Returns the configured render mode.
+	pub fn render_mode(&self) -> RenderMode {
+		self.render_mode
+	}
 
-/// High-level multi-finger gesture event stream.
-#[derive(Debug, Clone)]
-pub enum GestureEvent {
-	SwipeBegin {
-		device: u32,
-		time_usec: u64,
-		fingers: u32,
-	},
-	SwipeUpdate {
-		device: u32,
-		time_usec: u64,
-		fingers: u32,
-		dx: f64,
-		dy: f64,
-	},
-	SwipeEnd {
-		device: u32,
-		time_usec: u64,
-		cancelled: bool,
-	},
-	PinchBegin {
-		device: u32,
-		time_usec: u64,
-		fingers: u32,
-	},
-	PinchUpdate {
-		device: u32,
-		time_usec: u64,
-		fingers: u32,
-		dx: f64,
-		dy: f64,
-		scale: f64,
-		rotation: f64,
-	},
-	PinchEnd {
-		device: u32,
-		time_usec: u64,
-		cancelled: bool,
-	},
-	HoldBegin {
-		device: u32,
-		time_usec: u64,
-		fingers: u32,
-	},
-	HoldEnd {
-		device: u32,
-		time_usec: u64,
-		cancelled: bool,
-	},
-}
+	/// Returns the requested OpenGL/OpenGL ES version.
+	pub fn requested_opengl_version(&self) -> (u8, u8) {
+		self.opengl_version
+	}
 
-/// Initialization context passed to [`Application::init`].
-pub struct InitContext<A: Application> {
-	config: Config,
-	_marker: PhantomData<A>,
-}
+	/// Returns the requested GL surface color format.
+	pub fn surface_format(&self) -> GlSurfaceFormat {
+		self.surface_format
+	}
 
-impl<A: Application> InitContext<A> {
-	fn new(config: Config) -> Self {
-		Self {
-			config,
-			_marker: PhantomData,
-		}
+	/// Returns the requested GL context flags.
+	pub fn gl_context_options(&self) -> GlContextOptions {
+		self.gl_context_options
 	}
 
-	/// Returns the current runtime configuration.
-	pub fn config(&self) -> &Config {
-		&self.config
+	/// Returns the default render target attachments.
+	pub fn render_target_options(&self) -> RenderTargetOptions {
+		self.render_target_options
 	}
 
-	/// Returns mutable runtime configuration.
-	pub fn config_mut(&mut self) -> &mut Config {
-		&mut self.config
+	/// Returns true when per-frame tracing spans are enabled.
+	pub fn frame_tracing(&self) -> bool {
+		self.frame_tracing
 	}
-}
 
-/// Core application trait implemented by framework users.
-pub trait Application: Sized + 'static {
-	/// Constructs the application instance.
-	fn init(ctx: &mut InitContext<Self>) -> anyhow::Result<Self>;
+	/// Returns the loop statistics reporting period.
+	pub fn stats_interval(&self) -> Duration {
+		self.stats_interval
+	}
 
-	/// Called after a buffer is acquired and ready to be rendered into.
-	fn on_render(&mut self, _ctx: &mut Context<Self>, _ev: RenderEvent) {}
-	/// Called when a previously rendered buffer is presented/released.
-	fn on_present(&mut self, _ctx: &mut Context<Self>, _ev: PresentEvent) {}
-	/// Called when a monitor becomes available.
-	fn on_monitor_added(&mut self, _ctx: &mut Context<Self>, _ev: MonitorAddedEvent) {}
-	/// Called when a monitor is removed.
-	fn on_monitor_removed(&mut self, _ctx: &mut Context<Self>, _ev: MonitorRemovedEvent) {}
-	/// Called when session state changes.
-	fn on_session_state(&mut self, _ctx: &mut Context<Self>, _ev: SessionEvent) {}
-	/// Called for every raw input event.
-	fn on_input(&mut self, _ctx: &mut Context<Self>, _ev: InputEvent) {}
-	/// Called for key events.
-	fn on_key(&mut self, _ctx: &mut Context<Self>, _ev: KeyEvent) {}
-	/// Called for composed text events.
-	fn on_char(&mut self, _ctx: &mut Context<Self>, _ev: CharEvent) {}
-	/// Called when any pointer device moves the cursor.
-	fn on_pointer_move(&mut self, _ctx: &mut Context<Self>, _ev: PointerMoveEvent) {}
-	/// Called when a mouse-like device moves the cursor.
-	fn on_mouse_move(&mut self, _ctx: &mut Context<Self>, _ev: MouseMoveEvent) {}
-	/// Called when any pointer device produces a down transition.
-	fn on_pointer_down(&mut self, _ctx: &mut Context<Self>, _ev: PointerDownEvent) {}
-	/// Called when any pointer device produces an up transition.
-	fn on_pointer_up(&mut self, _ctx: &mut Context<Self>, _ev: PointerUpEvent) {}
-	/// Called when a mouse-like device produces a down transition.
-	fn on_mouse_down(&mut self, _ctx: &mut Context<Self>, _ev: MouseDownEvent) {}
-	/// Called when a mouse-like device produces an up transition.
-	fn on_mouse_up(&mut self, _ctx: &mut Context<Self>, _ev: MouseUpEvent) {}
-	/// Called for multitouch contact lifecycle events.
-	fn on_touch(&mut self, _ctx: &mut Context<Self>, _ev: TouchEvent) {}
-	/// Called for high-level multi-finger gesture events.
-	fn on_gesture(&mut self, _ctx: &mut Context<Self>, _ev: GestureEvent) {}
-	/// Called when a watched file descriptor is readable.
-	fn on_fd_ready(&mut self, _ctx: &mut Context<Self>, _ev: FdReadyEvent) {}
-	/// Called when the framework surfaces an error.
-	fn on_error(&mut self, _ctx: &mut Context<Self>, _error: &FrameworkError) {}
-}
+	/// Returns how GL apps synchronize frames with the server.
+	pub fn sync_mode(&self) -> SyncMode {
+		self.sync_mode
+	}
 
-/// Mutable runtime context passed into application callbacks.
-pub struct Context<'a, A: Application> {
-	client: &'a mut TabClient,
-	monitors: &'a mut HashMap<String, MonitorRuntime>,
-	scheduled: &'a mut HashSet<String>,
-	watched_fds: &'a mut HashSet<RawFd>,
-	next_acquire_fence: &'a mut Option<OwnedFd>,
-	cursor_position: &'a mut (f64, f64),
-	exiting: &'a mut bool,
-	_marker: PhantomData<A>,
-}
+	/// Returns true when GL apps start with the debug overlay shown.
+	pub fn debug_overlay(&self) -> bool {
+		self.debug_overlay
+	}
 
-impl<'a, A: Application> Context<'a, A> {
-	/// Schedules a frame for a specific monitor.
-	pub fn schedule_frame(&mut self, monitor_id: impl Into<String>) {
-		self.scheduled.insert(monitor_id.into());
+	/// Returns the [`ClearPolicy`] a monitor starts with.
+	pub fn clear_policy(&self, monitor_id: &str) -> ClearPolicy {
+		self
+			.clear_policies
+			.get(monitor_id)
+			.copied()
+			.unwrap_or_default()
 	}
 
-	/// Schedules a frame for every known monitor.
-	pub fn schedule_all_frames(&mut self) {
-		self.scheduled.extend(self.monitors.keys().cloned());
+	/// Returns the [`RetryPolicy`] a monitor starts with.
+	pub fn retry_policy(&self, monitor_id: &str) -> RetryPolicy {
+		self
+			.retry_policies
+			.get(monitor_id)
+			.copied()
+			.unwrap_or(self.default_retry_policy)
 	}
 
-	/// Returns an iterator over all known monitors.
-	pub fn monitors(&self) -> impl Iterator<Item = &Monitor> {
-		self.monitors.values().map(|m| &m.monitor)
+	/// Returns true when events are delivered in batches.
+	pub fn batch_events(&self) -> bool {
+		self.batch_events
 	}
 
-	/// Returns a monitor by id.
-	pub fn monitor(&self, monitor_id: &str) -> Option<&Monitor> {
-		self.monitors.get(monitor_id).map(|m| &m.monitor)
+	/// Returns true when frames are acquired and submitted by the application.
+	pub fn explicit_submit(&self) -> bool {
+		self.explicit_submit
 	}
 
-	/// Sets monitor position in global layout space.
-	///
-	/// The resulting layout must remain edge-contiguous and non-overlapping.
-	pub fn set_monitor_position(
-		&mut self,
-		monitor_id: &str,
-		x: i32,
-		y: i32,
-	) -> Result<(), FrameworkError> {
-		let old = {
-			let Some(m) = self.monitors.get(monitor_id) else {
-				return Err(FrameworkError::MonitorNotFound(monitor_id.to_string()));
-			};
-			(m.monitor.x, m.monitor.y)
-		};
-		if let Some(m) = self.monitors.get_mut(monitor_id) {
-			m.monitor.x = x;
-			m.monitor.y = y;
-		}
-		let placements = current_layout(self.monitors);
-		if !is_valid_edge_contiguous_layout(&placements) {
-			if let Some(m) = self.monitors.get_mut(monitor_id) {
-				m.monitor.x = old.0;
-				m.monitor.y = old.1;
-			}
-			return Err(FrameworkError::Config(
-				"invalid monitor layout: monitors must edge-touch, must not overlap, and cannot form islands"
-					.into(),
-			));
-		}
-		let (cx, cy) = clamp_point_to_layout(&placements, self.cursor_position.0, self.cursor_position.1);
-		*self.cursor_position = (cx, cy);
-		Ok(())
+	/// Returns true when due monitors are offered to [`Application::on_render_parallel`].
+	pub fn parallel_render(&self) -> bool {
+		self.parallel_render
 	}
 
-	/// Recomputes monitor positions using default horizontal packing.
-	pub fn apply_horizontal_layout(&mut self) {
-		recompute_layout(self.monitors);
-		let placements = current_layout(self.monitors);
-		let (cx, cy) = clamp_point_to_layout(&placements, self.cursor_position.0, self.cursor_position.1);
-		*self.cursor_position = (cx, cy);
+	/// Returns true when relative pointer motion is coalesced.
+	pub fn pointer_motion_coalescing(&self) -> bool {
+		self.coalesce_pointer_motion
 	}
 
-	/// Returns current cursor position in global layout space.
-	pub fn cursor_position(&self) -> (f64, f64) {
-		*self.cursor_position
+	/// Returns the event queue capacity and its overflow policy, if bounded.
+	pub fn event_queue_capacity(&self) -> Option<(usize, QueueOverflow)> {
+		self.event_queue_capacity
 	}
 
-	/// Adds a file descriptor to the readable watch set.
-	pub fn watch_fd(&mut self, fd: RawFd) {
-		self.watched_fds.insert(fd);
+	/// Returns true when key presses are composed into [`CharEvent`]s.
+	pub fn text_composition(&self) -> bool {
+		self.text_composition
 	}
 
-	/// Removes a file descriptor from the watch set.
-	pub fn unwatch_fd(&mut self, fd: RawFd) {
-		self.watched_fds.remove(&fd);
+	/// Returns how the cursor crosses edges between monitors.
+	pub fn edge_behavior(&self) -> EdgeBehavior {
+		self.edge_behavior
 	}
 
-	/// Requests graceful termination of the main loop.
-	pub fn request_exit(&mut self) {
-		*self.exiting = true;
+	/// Returns the outer layout edges the cursor wraps around.
+	pub fn wrap_mode(&self) -> WrapMode {
+		self.wrap_mode
 	}
 
-	/// Sets an acquire fence to be sent with the next buffer request.
-	pub fn set_next_acquire_fence(&mut self, fence_fd: OwnedFd) {
-		*self.next_acquire_fence = Some(fence_fd);
+	/// Returns how often the server is pinged.
+	pub fn ping_interval(&self) -> Option<Duration> {
+		self.ping_interval
 	}
 
-	/// Returns current authenticated session information.
-	pub fn session(&self) -> &SessionInfo {
-		self.client.session()
+	/// Returns how long a monitor may starve before [`Application::on_starved`] fires.
+	pub fn starvation_threshold(&self) -> Option<Duration> {
+		self.starvation_threshold
 	}
 
-	/// Sends `session_ready` for the current session.
-	pub fn session_ready(&mut self) -> Result<(), FrameworkError> {
-		self.client.send_ready().map_err(FrameworkError::from)
+	/// Returns the window within which identical errors are deduplicated.
+	pub fn error_dedup_window(&self) -> Option<Duration> {
+		self.error_dedup_window
 	}
 
-	/// Backward-compatible alias for [`Context::session_ready`].
-	pub fn send_ready(&mut self) -> Result<(), FrameworkError> {
-		self.session_ready()
+	/// Returns true when a state snapshot is logged with every error.
+	pub fn dump_state_on_error(&self) -> bool {
+		self.dump_state_on_error
 	}
 
-	/// Requests creation of a new session and waits for server response.
-	pub fn create_session(
-		&mut self,
-		role: SessionRole,
-		display_name: Option<String>,
-	) -> Result<SessionCreatedPayload, FrameworkError> {
-		self.client
-			.create_session(role, display_name)
-			.map_err(FrameworkError::from)
+	/// Returns when `session_ready` is sent automatically.
+	pub fn auto_ready_mode(&self) -> AutoReady {
+		self.auto_ready
 	}
 
-	/// Requests switching to another session.
-	pub fn switch_session(
-		&mut self,
-		session_id: &str,
-		animation: Option<String>,
-		duration: Duration,
-	) -> Result<(), FrameworkError> {
-		self.client
-			.switch_session(session_id, animation, duration)
-			.map_err(FrameworkError::from)
+	/// Returns true when panics are reported to shift.
+	pub fn crash_reports(&self) -> bool {
+		self.crash_reports
 	}
 
-	/// Returns direct mutable access to the underlying tab client.
-	///
-	/// Prefer high-level methods when possible.
-	pub fn raw_client(&mut self) -> &mut TabClient {
-		self.client
+	/// Returns the application version included in crash reports.
+	pub fn app_version(&self) -> Option<&str> {
+		self.app_version.as_deref()
 	}
-}
 
-/// Main application runtime.
-pub struct TabAppFramework<A: Application> {
-	app: A,
-	client: TabClient,
-	render_mode: RenderMode,
-	monitors: HashMap<String, MonitorRuntime>,
-	scheduled: HashSet<String>,
-	watched_fds: HashSet<RawFd>,
-	event_queue: Rc<RefCell<VecDeque<QueuedEvent>>>,
-	exiting: bool,
-	next_acquire_fence: Option<OwnedFd>,
-	stats: LoopStats,
-	cursor_position: (f64, f64),
-	touch_contacts: HashMap<i32, (f64, f64)>,
-	primary_touch_id: Option<i32>,
-}
+	/// Returns the configured session token.
+	pub fn token(&self) -> &str {
+		&self.token
+	}
 
-impl<A: Application> TabAppFramework<A> {
-	/// Initializes the framework and application state.
-	pub fn init(configure: impl FnOnce(&mut Config)) -> Result<Self, FrameworkError> {
-		let mut init_ctx = InitContext::<A>::new(Config::from_env()?);
-		configure(init_ctx.config_mut());
-		let app = A::init(&mut init_ctx)
-			.map_err(|e| FrameworkError::Config(format!("app init failed: {e:#}")))?;
+	/// Returns the configured render node path, if set.
+	///
+	/// Without one, [`TabAppFramework::init`] looks up the render node of the server's GPU
+	/// and returns it here, so GL contexts created in `init` use the same device as the
+	/// swapchains.
+	pub fn render_node_path(&self) -> Option<&Path> {
+		self
+			.render_node_path
+			.as_deref()
+			.or(self.detected_render_node.as_deref())
+	}
 
-		let cfg = init_ctx.config().clone();
-		let mut client_cfg = TabClientConfig::new(cfg.token()).socket_path(cfg.socket_path.clone());
-		if let Some(render_node) = cfg.render_node_path {
-			client_cfg = client_cfg.render_node(render_node);
+	/// Asks the server which GPU it composites on and remembers the matching render node.
+	fn detect_render_node(&mut self) {
+		if self.render_node_path.is_some() || std::env::var_os("TAB_CLIENT_RENDER_NODE").is_some() {
+			return;
 		}
-		let mut client = TabClient::connect(client_cfg)?;
-		let queue = Rc::new(RefCell::new(VecDeque::new()));
-		Self::attach_event_queue(&mut client, Rc::clone(&queue));
-
-		let mut monitors = HashMap::new();
-		for tab_monitor in client.monitors() {
-			let monitor = Monitor::from_tab_monitor(tab_monitor);
-			let swapchain = client.create_swapchain(&monitor.id)?;
-			monitors.insert(monitor.id.clone(), MonitorRuntime::new(monitor, swapchain));
+		match TabClient::probe_drm_device(&self.socket_path) {
+			Ok(Some(device)) => {
+				self.detected_render_node = tab_client::matching_render_node(&device);
+				debug!(?device, render_node = ?self.detected_render_node, "server GPU detected");
+			}
+			Ok(None) => {}
+			Err(err) => debug!(error = %err, "failed to query the server GPU"),
 		}
-			recompute_layout(&mut monitors);
-			let initial_cursor = {
-				let placements = current_layout(&monitors);
-				let seed = placements
-					.iter()
-					.min_by(|a, b| {
-						(a.x, a.y, a.id.as_str()).cmp(&(b.x, b.y, b.id.as_str()))
-					})
-					.map(|m| {
-						(
-							m.x as f64 + (m.width.max(1) as f64 / 2.0),
-							m.y as f64 + (m.height.max(1) as f64 / 2.0),
-						)
-					})
-					.unwrap_or((0.0, 0.0));
-				clamp_point_to_layout(&placements, seed.0, seed.1)
-			};
-			let scheduled = if cfg.render_mode == RenderMode::Eager {
-				monitors.keys().cloned().collect()
-			} else {
-				HashSet::new()
-			};
+	}
 
-		Ok(Self {
-			app,
-			client,
-			render_mode: cfg.render_mode,
-			monitors,
-			scheduled,
-			watched_fds: HashSet::new(),
-				event_queue: queue,
-				exiting: false,
-				next_acquire_fence: None,
-				stats: LoopStats::new(),
-				cursor_position: initial_cursor,
-				touch_contacts: HashMap::new(),
-				primary_touch_id: None,
-			})
-		}
+	/// Fields whose values differ between `self` and `other`.
+	fn changed_fields(&self, other: &Config) -> Vec<ConfigField> {
+		let mut changed = Vec::new();
+		let mut check = |field, differs: bool| {
+			if differs {
+				changed.push(field);
+			}
+		};
+		check(ConfigField::Token, self.token != other.token);
+		check(
+			ConfigField::SocketPath,
+			self.socket_path != other.socket_path,
+		);
+		check(
+			ConfigField::RenderNode,
+			self.render_node_path != other.render_node_path,
+		);
+		check(
+			ConfigField::RenderMode,
+			self.render_mode != other.render_mode,
+		);
+		check(
+			ConfigField::OpenglVersion,
+			self.opengl_version != other.opengl_version,
+		);
+		check(
+			ConfigField::SurfaceFormat,
+			self.surface_format != other.surface_format,
+		);
+		check(
+			ConfigField::GlContextOptions,
+			self.gl_context_options != other.gl_context_options,
+		);
+		check(
+			ConfigField::RenderTargetOptions,
+			self.render_target_options != other.render_target_options,
+		);
+		check(
+			ConfigField::FrameTracing,
+			self.frame_tracing != other.frame_tracing,
+		);
+		check(
+			ConfigField::StatsInterval,
+			self.stats_interval != other.stats_interval,
+		);
+		check(
+			ConfigField::BatchEvents,
+			self.batch_events != other.batch_events,
+		);
+		check(
+			ConfigField::PointerMotionCoalescing,
+			self.coalesce_pointer_motion != other.coalesce_pointer_motion,
+		);
+		check(
+			ConfigField::EventQueueCapacity,
+			self.event_queue_capacity != other.event_queue_capacity,
+		);
+		check(
+			ConfigField::TextComposition,
+			self.text_composition != other.text_composition,
+		);
+		check(
+			ConfigField::EdgeBehavior,
+			self.edge_behavior != other.edge_behavior,
+		);
+		check(ConfigField::WrapMode, self.wrap_mode != other.wrap_mode);
+		check(
+			ConfigField::ExplicitSubmit,
+			self.explicit_submit != other.explicit_submit,
+		);
+		check(
+			ConfigField::ParallelRender,
+			self.parallel_render != other.parallel_render,
+		);
+		check(
+			ConfigField::LinearBuffers,
+			self.linear_buffers != other.linear_buffers,
+		);
+		check(
+			ConfigField::ShmBuffers,
+			self.shm_buffers != other.shm_buffers,
+		);
+		check(
+			ConfigField::DebugOverlay,
+			self.debug_overlay != other.debug_overlay,
+		);
+		check(ConfigField::SyncMode, self.sync_mode != other.sync_mode);
+		check(
+			ConfigField::ClearPolicies,
+			self.clear_policies != other.clear_policies,
+		);
+		check(
+			ConfigField::RetryPolicies,
+			self.retry_policies != other.retry_policies
+				|| self.default_retry_policy != other.default_retry_policy,
+		);
+		check(
+			ConfigField::BufferRequestAckTimeout,
+			self.buffer_request_ack_timeout != other.buffer_request_ack_timeout,
+		);
+		check(
+			ConfigField::PingInterval,
+			self.ping_interval != other.ping_interval,
+		);
+		check(
+			ConfigField::WatchdogThreshold,
+			self.watchdog_threshold != other.watchdog_threshold,
+		);
+		check(
+			ConfigField::StarvationThreshold,
+			self.starvation_threshold != other.starvation_threshold,
+		);
+		check(
+			ConfigField::ErrorDedupWindow,
+			self.error_dedup_window != other.error_dedup_window,
+		);
+		check(
+			ConfigField::DumpStateOnError,
+			self.dump_state_on_error != other.dump_state_on_error,
+		);
+		check(ConfigField::AutoReady, self.auto_ready != other.auto_ready);
+		check(
+			ConfigField::CrashReports,
+			self.crash_reports != other.crash_reports || self.app_version != other.app_version,
+		);
+		changed
+	}
 
-	/// Runs the main event/render loop until exit is requested.
-	pub fn run(&mut self) -> Result<(), FrameworkError> {
-		while !self.exiting {
-			let has_queued_events = !self.event_queue.borrow().is_empty();
-			let timeout_ms = if self.scheduled.is_empty() && !has_queued_events {
-				-1
-			} else {
-				0
-			};
-			let (tab_ready, ready_fds) = self.poll_once(timeout_ms)?;
-			if tab_ready {
-				self.client.dispatch_events()?;
+	/// Copies a field for which [`ConfigField::is_live`] holds from `other`.
+	fn copy_live_field(&mut self, other: &Config, field: ConfigField) {
+		match field {
+			ConfigField::RenderMode => self.render_mode = other.render_mode,
+			ConfigField::FrameTracing => self.frame_tracing = other.frame_tracing,
+			ConfigField::StatsInterval => self.stats_interval = other.stats_interval,
+			ConfigField::BatchEvents => self.batch_events = other.batch_events,
+			ConfigField::PointerMotionCoalescing => {
+				self.coalesce_pointer_motion = other.coalesce_pointer_motion;
 			}
-			self.flush_pending_releases();
-			for fd in ready_fds {
-				let ev = FdReadyEvent { fd };
-				self.call_app(|app, ctx| app.on_fd_ready(ctx, ev));
+			ConfigField::EventQueueCapacity => self.event_queue_capacity = other.event_queue_capacity,
+			ConfigField::EdgeBehavior => self.edge_behavior = other.edge_behavior,
+			ConfigField::WrapMode => self.wrap_mode = other.wrap_mode,
+			ConfigField::ClearPolicies => self.clear_policies = other.clear_policies.clone(),
+			ConfigField::RetryPolicies => {
+				self.retry_policies = other.retry_policies.clone();
+				self.default_retry_policy = other.default_retry_policy;
 			}
-			self.drain_tab_events()?;
-			self.flush_pending_releases();
-			self.render_scheduled()?;
-			self.stats.maybe_log();
+			ConfigField::BufferRequestAckTimeout => {
+				self.buffer_request_ack_timeout = other.buffer_request_ack_timeout;
+			}
+			ConfigField::WatchdogThreshold => self.watchdog_threshold = other.watchdog_threshold,
+			ConfigField::StarvationThreshold => {
+				self.starvation_threshold = other.starvation_threshold;
+			}
+			ConfigField::ErrorDedupWindow => self.error_dedup_window = other.error_dedup_window,
+			ConfigField::DumpStateOnError => self.dump_state_on_error = other.dump_state_on_error,
+			_ => {}
 		}
-		Ok(())
 	}
+}
 
-	fn attach_event_queue(client: &mut TabClient, queue: Rc<RefCell<VecDeque<QueuedEvent>>>) {
-		let q = Rc::clone(&queue);
-		client.on_monitor_event(move |ev| {
-			q.borrow_mut().push_back(QueuedEvent::Monitor(ev.clone()));
-		});
-		let q = Rc::clone(&queue);
-		client.on_render_event(move |ev| {
-			q.borrow_mut().push_back(QueuedEvent::Render(ev.clone()));
-		});
-		let q = Rc::clone(&queue);
-		client.on_input_event(move |ev| {
-			q.borrow_mut().push_back(QueuedEvent::Input(ev.clone()));
-		});
-		let q = Rc::clone(&queue);
-		client.on_session_event(move |ev| {
-			q.borrow_mut().push_back(QueuedEvent::Session(ev.clone()));
-		});
-	}
+/// A [`Config`] setting, as reported by [`Context::update_config`] and
+/// [`ConfigChangedEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConfigField {
+	Token,
+	SocketPath,
+	RenderNode,
+	RenderMode,
+	OpenglVersion,
+	SurfaceFormat,
+	GlContextOptions,
+	RenderTargetOptions,
+	FrameTracing,
+	StatsInterval,
+	BatchEvents,
+	PointerMotionCoalescing,
+	/// Event queue capacity and overflow policy.
+	EventQueueCapacity,
+	TextComposition,
+	EdgeBehavior,
+	WrapMode,
+	ExplicitSubmit,
+	ParallelRender,
+	LinearBuffers,
+	ShmBuffers,
+	DebugOverlay,
+	SyncMode,
+	ClearPolicies,
+	/// Per-monitor and default retry policies.
+	RetryPolicies,
+	BufferRequestAckTimeout,
+	PingInterval,
+	WatchdogThreshold,
+	StarvationThreshold,
+	ErrorDedupWindow,
+	DumpStateOnError,
+	AutoReady,
+	/// Crash reporting and the reported application version.
+	CrashReports,
+}
 
-	fn poll_once(&self, timeout_ms: i32) -> Result<(bool, Vec<RawFd>), FrameworkError> {
-		let mut pending_release_fds = Vec::new();
-		for monitor in self.monitors.values() {
-			for fence in &monitor.pending_release_fences {
-				if let Some(fd) = fence {
-					pending_release_fds.push(std::os::fd::AsRawFd::as_raw_fd(fd));
-				}
+impl ConfigField {
+	/// True when a running framework applies changes to the field; changes to the others
+	/// take effect on a new connection only.
+	pub fn is_live(self) -> bool {
+		matches!(
+			self,
+			Self::RenderMode
+				| Self::FrameTracing
+				| Self::StatsInterval
+				| Self::BatchEvents
+				| Self::PointerMotionCoalescing
+				| Self::EventQueueCapacity
+				| Self::EdgeBehavior
+				| Self::WrapMode
+				| Self::ClearPolicies
+				| Self::RetryPolicies
+				| Self::BufferRequestAckTimeout
+				| Self::WatchdogThreshold
+				| Self::StarvationThreshold
+				| Self::ErrorDedupWindow
+				| Self::DumpStateOnError
+		)
+	}
+}
+
+/// Seat named by `SHIFT_SEAT`, or [`tab_protocol::DEFAULT_SEAT`].
+fn env_seat() -> String {
+	std::env::var("SHIFT_SEAT")
+		.ok()
+		.filter(|seat| !seat.is_empty())
+		.unwrap_or_else(|| tab_protocol::DEFAULT_SEAT.into())
+}
+
+/// Parses a `major.minor` OpenGL version such as `3.3`.
+fn parse_gl_version(value: &str) -> Result<(u8, u8), String> {
+	let invalid = || format!("invalid GL version {value:?}, expected major.minor");
+	let (major, minor) = value.trim().split_once('.').ok_or_else(invalid)?;
+	let major: u8 = major.parse().map_err(|_| invalid())?;
+	let minor: u8 = minor.parse().map_err(|_| invalid())?;
+	if major < 2 {
+		return Err(format!(
+			"unsupported GL version {value:?}, need 2.0 or newer"
+		));
+	}
+	Ok((major, minor))
+}
+
+/// Top-level framework errors.
+#[derive(Debug, Error)]
+pub enum FrameworkError {
+	#[error("framework config error: {0}")]
+	Config(String),
+	#[error("tab client error: {0}")]
+	Client(#[from] TabClientError),
+	#[error("poll failed: {0}")]
+	Poll(std::io::Error),
+	#[error("monitor not found: {0}")]
+	MonitorNotFound(String),
+	#[error("overlay not found: {0}")]
+	OverlayNotFound(OverlayId),
+	#[error("capture not found: {0}")]
+	CaptureNotFound(CaptureId),
+	#[error("forbidden: {0}")]
+	Forbidden(String),
+	/// Another registration already holds the keys of a global shortcut; the shortcut
+	/// was dropped.
+	#[error("global shortcut {0} conflicts with an existing registration")]
+	ShortcutConflict(GlobalShortcutId),
+	/// Another session holds the keyboard grab asked for with
+	/// [`Context::request_keyboard_grab`].
+	#[error("another session holds the keyboard grab")]
+	KeyboardGrabUnavailable,
+	/// A message sent with [`Context::send_to_session`] was refused: the target session
+	/// is gone or may not receive messages from this session.
+	#[error("message to session {0} is undeliverable")]
+	SessionMessageUndeliverable(String),
+	/// No frame can be acquired or submitted for the monitor right now.
+	///
+	/// A frame is already acquired, no buffer is free, or a previous frame awaits the
+	/// server's acknowledgement.
+	#[error("no frame available for monitor {0}")]
+	FrameUnavailable(String),
+	/// The server rejected a frame submitted for `monitor_id`.
+	#[error("buffer request for {monitor_id} failed: {error}")]
+	BufferRequestFailed {
+		monitor_id: String,
+		buffer: BufferIndex,
+		error: ServerError,
+	},
+	/// The server rejected an admin request; `request` names the [`Context`] method that
+	/// sent it, such as `set_monitor_brightness`.
+	#[error("{request} failed: {error}")]
+	AdminRequestFailed {
+		request: &'static str,
+		error: ServerError,
+	},
+	/// The server acknowledged none of the frames for `monitor_id` within
+	/// `consecutive_timeouts` ack timeouts of `ack_timeout` each.
+	///
+	/// The frame stays in flight, so a late acknowledgement still completes it. See
+	/// [`FrameworkError::recovery_advice`].
+	#[error(
+		"watchdog: {consecutive_timeouts} consecutive buffer_request_ack timeouts of \
+		 {ack_timeout:?} on {monitor_id}"
+	)]
+	Watchdog {
+		monitor_id: String,
+		consecutive_timeouts: u32,
+		ack_timeout: Duration,
+	},
+	/// GPU work in a rendering layer failed; `context` names the step that failed.
+	///
+	/// `source` is that layer's error type; the GL and Skia layers report `GlError`s,
+	/// recovered with `GlError::from_framework_error`.
+	#[error("{context}: {source}")]
+	Gpu {
+		context: &'static str,
+		source: Box<dyn std::error::Error + Send + Sync>,
+	},
+	/// More events were queued than [`Config::set_event_queue_capacity`] allows; `dropped`
+	/// continuous input events were discarded under `overflow`.
+	#[error("event queue exceeded its capacity of {capacity}, dropped {dropped} input events")]
+	EventQueueOverflow {
+		capacity: usize,
+		overflow: QueueOverflow,
+		dropped: usize,
+	},
+	/// `error` was reported `count` more times within `window` after its first delivery.
+	///
+	/// See [`Config::set_error_dedup_window`].
+	#[error("{error} (repeated {count} times in {window:?})")]
+	Repeated {
+		/// The last of the repeats.
+		error: Box<FrameworkError>,
+		count: u32,
+		window: Duration,
+	},
+}
+
+impl FrameworkError {
+	/// Server error code behind this error, if the server rejected a request.
+	///
+	/// Prefer this over inspecting the message; codes are stable across releases.
+	pub fn server_code(&self) -> Option<&ServerErrorCode> {
+		match self {
+			Self::Client(err) => err.server_code(),
+			Self::BufferRequestFailed { error, .. } | Self::AdminRequestFailed { error, .. } => {
+				Some(&error.code)
 			}
+			Self::Repeated { error, .. } => error.server_code(),
+			_ => None,
 		}
+	}
 
-		let watched_count = self.watched_fds.len();
-		let mut pollfds = Vec::with_capacity(1 + watched_count + pending_release_fds.len());
-		pollfds.push(libc::pollfd {
-			fd: self.client.socket_fd(),
-			events: libc::POLLIN,
-			revents: 0,
-		});
-		for fd in &self.watched_fds {
-			pollfds.push(libc::pollfd {
-				fd: *fd,
-				events: libc::POLLIN,
-				revents: 0,
-			});
-		}
-		for fd in pending_release_fds {
-			pollfds.push(libc::pollfd {
-				fd,
-				events: libc::POLLIN | libc::POLLERR | libc::POLLHUP,
-				revents: 0,
-			});
+	/// Suggested way to recover, for errors that have one.
+	pub fn recovery_advice(&self) -> Option<&'static str> {
+		match self {
+			Self::Watchdog { .. } | Self::Client(TabClientError::AckTimeout(_)) => Some(
+				"the server is overloaded or stalled: raise Config::set_buffer_request_ack_timeout \
+				 on a busy system, or reconnect if frames stay unacknowledged",
+			),
+			Self::Repeated { error, .. } => error.recovery_advice(),
+			_ => None,
 		}
-		let rc = unsafe {
-			libc::poll(
-				pollfds.as_mut_ptr(),
-				pollfds.len() as libc::nfds_t,
-				timeout_ms,
-			)
+	}
+}
+
+/// Logical monitor metadata exposed to applications.
+#[derive(Debug, Clone)]
+pub struct Monitor {
+	/// Stable monitor identifier used by the protocol.
+	pub id: String,
+	/// Human-readable monitor name.
+	pub name: String,
+	/// Logical width: the physical width divided by [`Monitor::scale`], rounded.
+	pub width: i32,
+	/// Logical height: the physical height divided by [`Monitor::scale`], rounded.
+	pub height: i32,
+	/// Width of the monitor's buffers in physical pixels.
+	pub physical_width: i32,
+	/// Height of the monitor's buffers in physical pixels.
+	pub physical_height: i32,
+	/// Nominal refresh rate in Hz.
+	pub refresh_rate: i32,
+	/// Monitor origin X in global layout space.
+	pub x: i32,
+	/// Monitor origin Y in global layout space.
+	pub y: i32,
+	/// Physical pixels per logical pixel, as advertised by the server; may be fractional.
+	pub scale: f64,
+	/// Modes the monitor can be switched to; empty if the server lists none.
+	pub modes: Vec<MonitorMode>,
+	/// Gamut, gamma and supported colorspaces from the monitor's EDID, if the server read it.
+	pub color: Option<MonitorColorInfo>,
+	/// Whether an admin session made this the primary monitor.
+	///
+	/// See [`Context::primary_monitor`] for the fallback when no monitor is flagged.
+	pub primary: bool,
+}
+
+impl Monitor {
+	fn from_tab_monitor(state: &tab_client::MonitorState) -> Self {
+		let mut monitor = Self {
+			id: state.info.id.clone(),
+			name: state.info.name.clone(),
+			width: 0,
+			height: 0,
+			physical_width: 0,
+			physical_height: 0,
+			refresh_rate: 0,
+			x: 0,
+			y: 0,
+			scale: 1.0,
+			modes: Vec::new(),
+			color: None,
+			primary: false,
 		};
-		if rc < 0 {
-			return Err(FrameworkError::Poll(std::io::Error::last_os_error()));
+		monitor.apply_tab_monitor(state);
+		monitor
+	}
+
+	/// Updates server-reported properties, keeping layout position.
+	fn apply_tab_monitor(&mut self, state: &tab_client::MonitorState) {
+		let scale = if state.info.scale.is_finite() && state.info.scale > 0.0 {
+			state.info.scale
+		} else {
+			1.0
+		};
+		self.name = state.info.name.clone();
+		self.physical_width = state.info.width;
+		self.physical_height = state.info.height;
+		self.width = (state.info.width as f64 / scale).round() as i32;
+		self.height = (state.info.height as f64 / scale).round() as i32;
+		self.scale = scale;
+		self.refresh_rate = state.info.refresh_rate;
+		self.modes = state.info.modes.clone();
+		self.color = state.info.color.clone();
+		self.primary = state.info.primary;
+	}
+
+	/// Mode the monitor is currently driven at.
+	pub fn current_mode(&self) -> MonitorMode {
+		MonitorMode {
+			width: self.physical_width,
+			height: self.physical_height,
+			refresh_rate: self.refresh_rate,
 		}
-		if rc == 0 {
-			return Ok((false, Vec::new()));
+	}
+
+	/// Converts a global layout position into buffer pixels of this monitor.
+	///
+	/// The result is fractional and not clamped to the buffer; floor it to address a pixel.
+	pub fn to_buffer_coords(&self, global_position: (f64, f64)) -> (f64, f64) {
+		let (x, y) = self.cursor_relative_position(global_position);
+		(x * self.scale, y * self.scale)
+	}
+
+	/// Converts a position in buffer pixels of this monitor into global layout space.
+	pub fn to_logical_coords(&self, buffer_position: (f64, f64)) -> (f64, f64) {
+		(
+			buffer_position.0 / self.scale + self.x as f64,
+			buffer_position.1 / self.scale + self.y as f64,
+		)
+	}
+
+	/// Converts a global cursor position into monitor-local coordinates.
+	///
+	/// The returned coordinates are not clamped to monitor bounds.
+	pub fn cursor_relative_position(&self, global_position: (f64, f64)) -> (f64, f64) {
+		self.to_local(global_position)
+	}
+
+	/// Returns true when a global layout position lies on this monitor.
+	///
+	/// The left and top edges belong to the monitor, the right and bottom edges to its
+	/// neighbours.
+	pub fn contains(&self, global_position: (f64, f64)) -> bool {
+		let (x, y) = self.to_local(global_position);
+		x >= 0.0 && x < self.width.max(0) as f64 && y >= 0.0 && y < self.height.max(0) as f64
+	}
+
+	/// Converts a global layout position into logical coordinates relative to this
+	/// monitor's top-left corner, without clamping to its bounds.
+	pub fn to_local(&self, global_position: (f64, f64)) -> (f64, f64) {
+		(
+			global_position.0 - self.x as f64,
+			global_position.1 - self.y as f64,
+		)
+	}
+
+	/// Converts logical coordinates relative to this monitor into global layout space.
+	pub fn from_local(&self, local_position: (f64, f64)) -> (f64, f64) {
+		(
+			local_position.0 + self.x as f64,
+			local_position.1 + self.y as f64,
+		)
+	}
+}
+
+/// Monitors ordered by layout position, then id.
+fn sorted_monitors(monitors: &HashMap<String, MonitorRuntime>) -> Vec<&Monitor> {
+	let mut sorted: Vec<_> = monitors.values().map(|m| &m.monitor).collect();
+	sorted.sort_by(|a, b| (a.x, a.y, &a.id).cmp(&(b.x, b.y, &b.id)));
+	sorted
+}
+
+fn recompute_layout(monitors: &mut HashMap<String, MonitorRuntime>) {
+	let specs: Vec<_> = monitors
+		.values()
+		.map(|m| MonitorSpec {
+			id: m.monitor.id.clone(),
+			width: m.monitor.width,
+			height: m.monitor.height,
+		})
+		.collect();
+	let placements = layout_horizontal(&specs);
+	for p in placements {
+		if let Some(m) = monitors.get_mut(&p.id) {
+			m.monitor.x = p.x;
+			m.monitor.y = p.y;
 		}
-		let tab_ready = (pollfds[0].revents & libc::POLLIN) != 0;
-		let mut ready_fds = Vec::new();
-		for pfd in pollfds.iter().skip(1).take(watched_count) {
-			if (pfd.revents & libc::POLLIN) != 0 {
-				ready_fds.push(pfd.fd);
-			}
+	}
+}
+
+fn current_layout(monitors: &HashMap<String, MonitorRuntime>) -> Vec<MonitorPlacement> {
+	let mut placements = Vec::with_capacity(monitors.len());
+	fill_layout(monitors, &mut placements);
+	placements
+}
+
+/// Refills `placements` in place, reusing its allocation.
+fn fill_layout(monitors: &HashMap<String, MonitorRuntime>, placements: &mut Vec<MonitorPlacement>) {
+	placements.clear();
+	placements.extend(monitors.values().map(|m| MonitorPlacement {
+		id: m.monitor.id.clone(),
+		x: m.monitor.x,
+		y: m.monitor.y,
+		width: m.monitor.width,
+		height: m.monitor.height,
+	}));
+}
+
+/// Borrowed handle to the swapchain buffer acquired for the current frame.
+///
+/// The handle is only valid for the duration of the render callback that
+/// received it; the underlying buffer is handed back to the server afterwards.
+/// Use [`RenderTarget::try_clone_fd`] when an owned descriptor is really needed.
+#[derive(Debug)]
+pub struct RenderTarget<'a> {
+	buffer_index: BufferIndex,
+	dmabuf_fd: BorrowedFd<'a>,
+	width: i32,
+	height: i32,
+	stride: i32,
+	offset: i32,
+	fourcc: i32,
+	modifier: u64,
+}
+
+impl<'a> RenderTarget<'a> {
+	fn new(buffer: &'a TabBuffer, buffer_index: BufferIndex) -> Self {
+		Self {
+			buffer_index,
+			dmabuf_fd: buffer.as_fd(),
+			width: buffer.width(),
+			height: buffer.height(),
+			stride: buffer.stride(),
+			offset: buffer.offset(),
+			fourcc: buffer.fourcc(),
+			modifier: buffer.modifier(),
 		}
-		Ok((tab_ready, ready_fds))
 	}
 
-	fn drain_tab_events(&mut self) -> Result<(), FrameworkError> {
-		loop {
-			let maybe_event = self.event_queue.borrow_mut().pop_front();
-			let Some(event) = maybe_event else {
-				break;
-			};
-			match event {
-				QueuedEvent::Monitor(ev) => match ev {
-					TabMonitorEvent::Added(state) => {
-						let monitor = Monitor::from_tab_monitor(&state);
-						let swapchain = self.client.create_swapchain(&monitor.id)?;
-						if self.render_mode == RenderMode::Eager {
-							self.scheduled.insert(monitor.id.clone());
-						}
-						self.monitors.insert(
-							monitor.id.clone(),
-							MonitorRuntime::new(monitor.clone(), swapchain),
-						);
-						recompute_layout(&mut self.monitors);
-						let placements = current_layout(&self.monitors);
-						self.cursor_position =
-							clamp_point_to_layout(&placements, self.cursor_position.0, self.cursor_position.1);
-						let monitor = self
-							.monitors
-							.get(&state.info.id)
-							.map(|m| m.monitor.clone())
-							.unwrap_or(monitor);
-						self.call_app(|app, ctx| {
-							app.on_monitor_added(
-								ctx,
-								MonitorAddedEvent {
-									monitor: monitor.clone(),
-								},
-							)
-						});
-					}
-					TabMonitorEvent::Removed { monitor_id, name } => {
-						self.monitors.remove(&monitor_id);
-						recompute_layout(&mut self.monitors);
-						let placements = current_layout(&self.monitors);
-						self.cursor_position =
-							clamp_point_to_layout(&placements, self.cursor_position.0, self.cursor_position.1);
-						self.scheduled.remove(&monitor_id);
-						self.call_app(|app, ctx| {
-							app.on_monitor_removed(
-								ctx,
-								MonitorRemovedEvent {
-									monitor_id: monitor_id.clone(),
-									name: name.clone(),
-								},
-							)
-						});
-					}
-				},
-				QueuedEvent::Render(ev) => {
-					self.stats.buffer_release_events += 1;
-					let TabRenderEvent::BufferReleased {
-						monitor_id,
-						buffer,
-						release_fence_fd,
-					} = ev;
-					self.stats.instant_log(&format!(
-						"buffer_release event monitor={monitor_id} buffer={} fence={}",
-						buffer as u8,
-						if release_fence_fd.is_some() { "yes" } else { "no" }
-					));
-					let mut should_emit_present = false;
-					if let Some(monitor) = self.monitors.get_mut(&monitor_id) {
-						if let Some(fd) = release_fence_fd {
-							monitor.pending_release_fences[buffer as usize] =
-								Some(unsafe { OwnedFd::from_raw_fd(fd) });
-						} else {
-							if monitor.pending_present[buffer as usize] {
-								monitor.pending_present[buffer as usize] = false;
-								should_emit_present = true;
-							}
-							monitor.swapchain.mark_released(buffer);
-							if self.render_mode == RenderMode::Eager {
-								self.scheduled.insert(monitor_id.clone());
-							}
-						}
-					}
-					if should_emit_present {
-						self.stats.present_callbacks += 1;
-						self.call_app(|app, ctx| {
-							app.on_present(
-								ctx,
-								PresentEvent {
-									monitor_id: monitor_id.clone(),
-									buffer_index: buffer,
-								},
-							)
-						});
-					}
-				}
-				QueuedEvent::Input(ev) => {
-					let TabInputEvent::Event(payload) = ev;
-					self.call_app(|app, ctx| {
-						app.on_input(
-							ctx,
-							InputEvent {
-								payload: payload.clone(),
-							},
-						)
-					});
-						match payload {
-							InputEventPayload::Key {
-								device,
-								time_usec,
-								key,
-								state,
-							} => {
-								self.call_app(|app, ctx| {
-									app.on_key(
-										ctx,
-										KeyEvent {
-											device,
-											time_usec,
-											key,
-											state,
-										},
-									)
-								});
-							}
-							InputEventPayload::PointerMotion {
-								device,
-								time_usec,
-								dx,
-								dy,
-								..
-							} => {
-								let old_position = self.cursor_position;
-								let placements = current_layout(&self.monitors);
-								self.cursor_position = move_cursor_no_tunnel(
-									&placements,
-									self.cursor_position.0,
-									self.cursor_position.1,
-									dx,
-									dy,
-								);
-								self.emit_cursor_move(
-									PointerMoveEvent {
-										device,
-										time_usec,
-										pointer_type: PointerType::Mouse,
-										old_position,
-										new_position: self.cursor_position,
-									},
+	/// Acquired swapchain buffer index.
+	pub fn buffer_index(&self) -> BufferIndex {
+		self.buffer_index
+	}
+
+	/// DMA-BUF file descriptor for the render target.
+	pub fn dmabuf_fd(&self) -> BorrowedFd<'a> {
+		self.dmabuf_fd
+	}
+
+	/// Duplicates the DMA-BUF descriptor into an owned fd.
+	///
+	/// The duplicate stays open after the callback returns, but the buffer
+	/// contents are only client-owned while the frame is being rendered.
+	pub fn try_clone_fd(&self) -> std::io::Result<OwnedFd> {
+		self.dmabuf_fd().try_clone_to_owned()
+	}
+
+	/// Buffer width in pixels.
+	pub fn width(&self) -> i32 {
+		self.width
+	}
+
+	/// Buffer height in pixels.
+	pub fn height(&self) -> i32 {
+		self.height
+	}
+
+	/// Buffer stride in bytes.
+	pub fn stride(&self) -> i32 {
+		self.stride
+	}
+
+	/// Buffer offset in bytes.
+	pub fn offset(&self) -> i32 {
+		self.offset
+	}
+
+	/// DRM fourcc pixel format.
+	pub fn fourcc(&self) -> i32 {
+		self.fourcc
+	}
+
+	/// DRM format modifier, `DRM_FORMAT_MOD_INVALID` when the driver implies the layout.
+	pub fn modifier(&self) -> u64 {
+		self.modifier
+	}
+}
+
+/// What happens to a monitor's buffer before [`Application::on_render`] is called.
+///
+/// Acquired buffers hold an older frame, or undefined contents right after the swapchain
+/// is created. The GL and Skia layers apply the policy themselves; core-only applications
+/// read it from [`RenderEvent::clear_policy`] and clear the buffer on their own.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ClearPolicy {
+	/// Hand the buffer over as acquired; see [`RenderEvent::buffer_age`].
+	#[default]
+	None,
+	/// Clear the whole buffer to this RGBA color, components in `0.0..=1.0`.
+	///
+	/// Nothing of the previous frame survives, so [`RenderEvent::buffer_age`] is 0.
+	Color([f32; 4]),
+}
+
+/// How eager rendering retries a monitor whose frame could not be acquired or submitted.
+///
+/// Buffer requests rejected by the socket or the server are retried on the next loop
+/// iteration up to `max_immediate_retries` times in a row. Later retries wait a delay that
+/// starts at `initial_backoff` and doubles per failure up to `max_backoff`, shifted by up
+/// to `jitter` of itself so monitors failing together do not retry in lockstep. Acquire
+/// misses and ownership errors cannot succeed before the server releases a buffer, so with
+/// `reschedule_on_release` they wait for that release instead. A frame the server
+/// acknowledges resets the count.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+	pub max_immediate_retries: u32,
+	pub initial_backoff: Duration,
+	pub max_backoff: Duration,
+	/// Fraction of each delay, `0.0..=1.0`, randomly added or taken away.
+	pub jitter: f64,
+	/// Retry as soon as the server releases a buffer, even while a delay is pending.
+	pub reschedule_on_release: bool,
+}
+
+impl Default for RetryPolicy {
+	fn default() -> Self {
+		Self {
+			max_immediate_retries: 2,
+			initial_backoff: Duration::from_millis(4),
+			max_backoff: Duration::from_millis(500),
+			jitter: 0.2,
+			reschedule_on_release: true,
+		}
+	}
+}
+
+impl RetryPolicy {
+	/// Delay before the retry following `failures` consecutive failures, without jitter.
+	///
+	/// `None` means retrying right away.
+	pub fn backoff(&self, failures: u32) -> Option<Duration> {
+		let doublings = failures.checked_sub(self.max_immediate_retries + 1)?;
+		let factor = 1u32.checked_shl(doublings).unwrap_or(u32::MAX);
+		Some(
+			self
+				.initial_backoff
+				.saturating_mul(factor)
+				.min(self.max_backoff),
+		)
+	}
+
+	/// Shifts `delay` by a random amount of up to `jitter` of itself.
+	fn jittered(&self, delay: Duration) -> Duration {
+		let jitter = self.jitter.clamp(0.0, 1.0);
+		// Map a randomly seeded hash to -1.0..=1.0; std offers no other randomness.
+		let unit = RandomState::new().hash_one(Instant::now()) as f64 / u64::MAX as f64;
+		delay.mul_f64(1.0 + jitter * (unit * 2.0 - 1.0))
+	}
+}
+
+/// Retry bookkeeping of a monitor under its [`RetryPolicy`].
+#[derive(Debug, Default)]
+struct RetryState {
+	failures: u32,
+	/// When a delayed retry is due.
+	retry_at: Option<Instant>,
+}
+
+/// Render callback payload containing the acquired client buffer.
+#[derive(Debug)]
+pub struct RenderEvent<'a> {
+	monitor_id: &'a str,
+	frame_id: u64,
+	target: RenderTarget<'a>,
+	resized: bool,
+	buffer_age: u32,
+	scale: f64,
+	clear_policy: ClearPolicy,
+}
+
+impl<'a> RenderEvent<'a> {
+	/// Target monitor id.
+	pub fn monitor_id(&self) -> &'a str {
+		self.monitor_id
+	}
+
+	/// Id of this frame, increasing across all monitors and repeated in its
+	/// [`PresentEvent`].
+	pub fn frame_id(&self) -> u64 {
+		self.frame_id
+	}
+
+	/// Borrowed render target for this frame.
+	pub fn target(&self) -> &RenderTarget<'a> {
+		&self.target
+	}
+
+	/// Buffer width in pixels.
+	pub fn width(&self) -> i32 {
+		self.target.width
+	}
+
+	/// Buffer height in pixels.
+	pub fn height(&self) -> i32 {
+		self.target.height
+	}
+
+	/// True on the first frame after the swapchain was recreated at a new size.
+	///
+	/// Anything cached per buffer (framebuffers, imported images) must be rebuilt.
+	pub fn resized(&self) -> bool {
+		self.resized
+	}
+
+	/// Number of frames since this buffer's contents were presented, or 0 when undefined.
+	///
+	/// Follows `EGL_EXT_buffer_age`: with age `n`, only the damage of the last `n`
+	/// frames needs repainting; with 0, everything does. Always 0 while the monitor's
+	/// [`ClearPolicy`] clears the buffer.
+	pub fn buffer_age(&self) -> u32 {
+		self.buffer_age
+	}
+
+	/// Clear policy of the target monitor; see [`Context::set_clear_policy`].
+	pub fn clear_policy(&self) -> ClearPolicy {
+		self.clear_policy
+	}
+
+	/// Buffer pixels per logical pixel of the target monitor.
+	///
+	/// [`RenderEvent::width`] and [`RenderEvent::height`] are already in buffer pixels;
+	/// multiply logical sizes by this to draw them at the monitor's scale.
+	pub fn scale(&self) -> f64 {
+		self.scale
+	}
+}
+
+/// Monitors due for a frame in explicit submit mode.
+#[derive(Debug, Clone)]
+pub struct FrameRequestEvent {
+	/// Scheduled monitors that can accept a frame now.
+	pub monitor_ids: Vec<String>,
+}
+
+/// Swapchain buffer acquired with [`Context::acquire_target`].
+///
+/// Hand it back with [`Context::submit_frame`] or [`Context::discard_frame`]; until
+/// then no other frame can be acquired for the monitor.
+#[derive(Debug)]
+#[must_use = "acquired frames must be submitted or discarded"]
+pub struct AcquiredFrame {
+	monitor_id: String,
+	frame_id: u64,
+	user_data: Option<u64>,
+	buffer_index: BufferIndex,
+	buffer_age: u32,
+	resized: bool,
+}
+
+impl AcquiredFrame {
+	/// Target monitor id.
+	pub fn monitor_id(&self) -> &str {
+		&self.monitor_id
+	}
+
+	/// Same as [`RenderEvent::frame_id`].
+	pub fn frame_id(&self) -> u64 {
+		self.frame_id
+	}
+
+	/// Attaches an opaque value handed back in the frame's [`PresentEvent::user_data`].
+	pub fn set_user_data(&mut self, user_data: u64) {
+		self.user_data = Some(user_data);
+	}
+
+	/// Acquired swapchain buffer index.
+	pub fn buffer_index(&self) -> BufferIndex {
+		self.buffer_index
+	}
+
+	/// Same as [`RenderEvent::buffer_age`].
+	pub fn buffer_age(&self) -> u32 {
+		self.buffer_age
+	}
+
+	/// Same as [`RenderEvent::resized`].
+	pub fn resized(&self) -> bool {
+		self.resized
+	}
+}
+
+/// Present callback payload emitted after a rendered buffer is released.
+#[derive(Debug, Clone)]
+pub struct PresentEvent {
+	/// Target monitor id.
+	pub monitor_id: String,
+	/// Buffer index that reached presentation completion.
+	pub buffer_index: BufferIndex,
+	/// Id of the presented frame, see [`RenderEvent::frame_id`].
+	pub frame_id: u64,
+	/// Value attached with [`Context::set_frame_user_data`] or
+	/// [`AcquiredFrame::set_user_data`].
+	pub user_data: Option<u64>,
+	/// When the framework observed the presentation, on the monotonic clock.
+	///
+	/// Feed it to a [`PresentationClock`] to predict upcoming presents.
+	pub timestamp: Instant,
+}
+
+/// Emitted when a monitor is added.
+#[derive(Debug, Clone)]
+pub struct MonitorAddedEvent {
+	/// Added monitor metadata.
+	pub monitor: Monitor,
+}
+
+/// Emitted when a connected monitor changes mode or properties in place.
+///
+/// The monitor keeps its id and layout position. When the size changed, the framework
+/// has already recreated the monitor's swapchain.
+#[derive(Debug, Clone)]
+pub struct MonitorChangedEvent {
+	/// Updated monitor metadata.
+	pub monitor: Monitor,
+	/// Metadata before the change.
+	pub previous: Monitor,
+}
+
+/// Parts of a monitor covered by compositor chrome, in buffer pixels relative to the
+/// monitor's top-left corner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SafeArea {
+	/// Rectangles the compositor draws over; empty when nothing covers the monitor.
+	pub occluded: Vec<Rect>,
+	/// What is left of the monitor once bars along its edges are cut off.
+	///
+	/// Only occluded rectangles spanning a whole edge shrink it, so floating chrome may
+	/// still cover parts of it; check [`SafeArea::occluded`] for those.
+	pub safe: Rect,
+}
+
+impl SafeArea {
+	fn new(occluded: &[Rect], width: i32, height: i32) -> Self {
+		let (mut left, mut top, mut right, mut bottom) = (0, 0, width, height);
+		for rect in occluded {
+			let rect_right = rect.x.saturating_add(rect.width);
+			let rect_bottom = rect.y.saturating_add(rect.height);
+			let full_width = rect.x <= 0 && rect_right >= width;
+			let full_height = rect.y <= 0 && rect_bottom >= height;
+			if full_width && rect.y <= top {
+				top = top.max(rect_bottom);
+			} else if full_width && rect_bottom >= bottom {
+				bottom = bottom.min(rect.y);
+			} else if full_height && rect.x <= left {
+				left = left.max(rect_right);
+			} else if full_height && rect_right >= right {
+				right = right.min(rect.x);
+			}
+		}
+		Self {
+			occluded: occluded.to_vec(),
+			safe: Rect {
+				x: left,
+				y: top,
+				width: (right - left).max(0),
+				height: (bottom - top).max(0),
+			},
+		}
+	}
+}
+
+/// Emitted when the compositor chrome over a monitor changes.
+#[derive(Debug, Clone)]
+pub struct SafeAreaChangedEvent {
+	/// Monitor the chrome is drawn over.
+	pub monitor_id: String,
+	/// Updated safe area.
+	pub safe_area: SafeArea,
+}
+
+/// Emitted when a monitor's display is switched on, to standby or off.
+#[derive(Debug, Clone)]
+pub struct MonitorPowerChangedEvent {
+	/// Monitor whose display changed.
+	pub monitor_id: String,
+	/// New power state.
+	pub power: MonitorPower,
+}
+
+/// Emitted when the server hands a monitor's buffer back to the client.
+#[derive(Debug, Clone)]
+pub struct BufferAvailableEvent {
+	/// Monitor the buffer belongs to.
+	pub monitor_id: String,
+	/// Buffer that can be acquired again.
+	pub buffer_index: BufferIndex,
+}
+
+/// Emitted when no buffer of a monitor could be acquired for
+/// [`Config::set_starvation_threshold`].
+#[derive(Debug, Clone)]
+pub struct StarvedEvent {
+	/// Monitor whose buffers are all held by the server.
+	pub monitor_id: String,
+	/// Time since the first failed acquire.
+	pub duration: Duration,
+}
+
+/// Emitted once [`Context::recreate_swapchain`] linked new buffers for a monitor.
+#[derive(Debug, Clone)]
+pub struct SwapchainRecreatedEvent {
+	/// Monitor whose swapchain was replaced.
+	pub monitor_id: String,
+}
+
+/// Emitted when monitors were added, removed, moved or resized in the global layout.
+#[derive(Debug, Clone)]
+pub struct LayoutChangedEvent {
+	/// Changes since the previous event, ordered by monitor id.
+	pub changes: Vec<LayoutChange>,
+}
+
+/// Emitted when the system-wide accessibility settings change.
+#[derive(Debug, Clone)]
+pub struct AccessibilityChangedEvent {
+	/// Settings now in effect, also available from [`Context::accessibility`].
+	pub settings: AccessibilitySettings,
+	/// Settings in effect before.
+	pub previous: AccessibilitySettings,
+}
+
+/// Emitted when a monitor is removed.
+#[derive(Debug, Clone)]
+pub struct MonitorRemovedEvent {
+	/// Removed monitor id.
+	pub monitor_id: String,
+	/// Removed monitor name.
+	pub name: String,
+}
+
+/// Emitted when an input device is plugged in.
+#[derive(Debug, Clone)]
+pub struct InputDeviceAddedEvent {
+	/// Device name, capabilities and mapped output.
+	pub device: InputDeviceInfo,
+}
+
+/// Emitted when an input device is unplugged.
+#[derive(Debug, Clone)]
+pub struct InputDeviceRemovedEvent {
+	/// Last known metadata of the removed device.
+	pub device: InputDeviceInfo,
+}
+
+/// Session state update payload.
+#[derive(Debug, Clone)]
+pub struct SessionEvent {
+	/// Full session state snapshot.
+	pub session: SessionInfo,
+}
+
+/// Emitted when the server activates a session, if that changes whether this session
+/// has focus.
+#[derive(Debug, Clone)]
+pub struct FocusEvent {
+	/// Session the server made active.
+	pub active_session_id: String,
+}
+
+/// Snapshot of another session's frame, requested with
+/// [`Context::request_session_preview`].
+#[derive(Debug, Clone)]
+pub struct SessionPreviewEvent {
+	/// Session the preview was requested for.
+	pub session_id: String,
+	/// The snapshot, or `None` when the session had no frame on screen.
+	pub image: Option<PreviewImage>,
+}
+
+/// Downscaled copy of a session's frame.
+#[derive(Debug, Clone)]
+pub struct PreviewImage {
+	/// Monitor the frame was shown on.
+	pub monitor_id: String,
+	pub width: i32,
+	pub height: i32,
+	/// Bytes per row of `pixels`.
+	pub stride: i32,
+	/// DRM fourcc of `pixels`; shift sends `ARGB8888`.
+	pub fourcc: i32,
+	/// `height` rows of `stride` bytes.
+	pub pixels: Arc<[u8]>,
+}
+
+/// A capture buffer holds a new frame of a capture started with [`Context::start_capture`].
+///
+/// Read it through [`Context::capture_target`] once `ready_fence` signaled, then hand the
+/// buffer back with [`Context::release_capture_frame`].
+#[derive(Debug, Clone)]
+pub struct CaptureFrameEvent {
+	pub capture_id: CaptureId,
+	pub buffer: BufferIndex,
+	/// `CLOCK_MONOTONIC` time the source frame was composited, in microseconds.
+	pub time_usec: u64,
+	/// Signals once shift finished writing the buffer; `None` when it already has.
+	pub ready_fence: Option<Arc<OwnedFd>>,
+}
+
+/// Emitted when shift stopped a capture on its own, e.g. because its monitor was unplugged
+/// or its source session ended. The capture's buffers are freed.
+#[derive(Debug, Clone)]
+pub struct CaptureEndedEvent {
+	pub capture_id: CaptureId,
+	pub reason: String,
+}
+
+/// Emitted when another session sent this session a message, see
+/// [`Context::send_to_session`].
+#[derive(Debug, Clone)]
+pub struct SessionMessageEvent {
+	/// Session that sent the message.
+	pub from_session_id: String,
+	/// Set when the sender accepts a reply through [`Context::reply_to_session`].
+	pub correlation_id: Option<u64>,
+	/// Correlation id of this session's message that this one answers.
+	pub reply_to: Option<u64>,
+	pub data: Vec<u8>,
+}
+
+/// Emitted in admin sessions when another session panicked, see
+/// [`Config::set_crash_reports`].
+#[derive(Debug, Clone)]
+pub struct SessionCrashedEvent {
+	/// Session that crashed; it disconnects right after the report.
+	pub session_id: String,
+	/// Panic message and location.
+	pub message: String,
+	/// Hash of the panic backtrace; equal hashes usually mean the same crash.
+	pub backtrace_hash: u64,
+	/// Version the crashed application set with [`Config::set_app_version`].
+	pub app_version: Option<String>,
+}
+
+/// Handle to a request sent with [`Context::create_session_async`] or
+/// [`Context::switch_session_async`], repeated in the event answering it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId(u64);
+
+/// Answer to [`Context::create_session_async`].
+#[derive(Debug, Clone)]
+pub struct SessionCreateResultEvent {
+	pub request_id: RequestId,
+	/// The pending session and its token, or the server's refusal.
+	pub result: Result<SessionCreatedPayload, ServerError>,
+}
+
+/// Answer to [`Context::switch_session_async`].
+#[derive(Debug, Clone)]
+pub struct SessionSwitchResultEvent {
+	pub request_id: RequestId,
+	/// Session the request asked to activate.
+	pub session_id: String,
+	/// `Ok` once the session became active.
+	pub result: Result<(), ServerError>,
+}
+
+/// Emitted once the framework sent `session_ready` for [`Config::auto_ready`].
+#[derive(Debug, Clone)]
+pub struct SessionReadySentEvent {
+	/// Monitor whose first presented frame triggered it; `None` for [`AutoReady::AfterInit`].
+	pub monitor_id: Option<String>,
+}
+
+/// Emitted when a keepalive ping went unanswered for [`Config::set_ping_interval`].
+#[derive(Debug, Clone)]
+pub struct ServerUnresponsiveEvent {
+	/// When the unanswered ping was sent.
+	pub since: Instant,
+}
+
+/// Emitted when the server answers again after [`Application::on_server_unresponsive`].
+#[derive(Debug, Clone)]
+pub struct ServerRecoveredEvent {
+	/// Time between the unanswered ping and its answer.
+	pub unresponsive_for: Duration,
+	/// Round trip of the answered ping, also available from [`Context::server_latency`].
+	pub latency: Duration,
+}
+
+/// Emitted once [`Context::update_config`] changes took effect.
+#[derive(Debug, Clone)]
+pub struct ConfigChangedEvent {
+	/// Applied fields, in the order they were first changed.
+	pub fields: Vec<ConfigField>,
+}
+
+/// Handle to a shortcut registered with [`Context::register_global_shortcut`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GlobalShortcutId(u32);
+
+impl GlobalShortcutId {
+	/// Parses the id sent to the server back into a handle.
+	fn from_wire(raw: &str) -> Option<Self> {
+		raw.strip_prefix("shortcut-")?.parse().ok().map(Self)
+	}
+}
+
+impl std::fmt::Display for GlobalShortcutId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "shortcut-{}", self.0)
+	}
+}
+
+/// Emitted when a registered global shortcut is pressed, whichever session has focus.
+///
+/// The key press that completed the shortcut, and its release, are not delivered as
+/// input events.
+#[derive(Debug, Clone)]
+pub struct GlobalShortcutEvent {
+	pub shortcut_id: GlobalShortcutId,
+	/// Seat the keys were pressed on.
+	pub seat: SeatId,
+	/// Input timestamp of the completing key press in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+}
+
+/// Emitted once no input arrived for the timeout given to
+/// [`Context::request_idle_notification`].
+#[derive(Debug, Clone, Copy)]
+pub struct IdleEvent {
+	/// Time since the last input event.
+	pub idle_for: Duration,
+}
+
+/// Emitted on the first input event after [`IdleEvent`].
+#[derive(Debug, Clone, Copy)]
+pub struct ResumeActivityEvent {
+	/// How long the user was inactive.
+	pub idle_for: Duration,
+}
+
+/// Emitted when a watched file descriptor becomes readable.
+#[derive(Debug, Clone)]
+pub struct FdReadyEvent {
+	/// File descriptor that is ready.
+	pub fd: RawFd,
+}
+
+/// Raw input payload forwarded from the server.
+#[derive(Debug, Clone)]
+pub struct InputEvent {
+	/// Seat the source device belongs to.
+	pub seat: SeatId,
+	/// Protocol input event payload.
+	pub payload: InputEventPayload,
+	/// The payload's `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+}
+
+/// Keyboard event payload.
+#[derive(Debug, Clone)]
+pub struct KeyEvent {
+	/// Input device id.
+	pub device: u32,
+	/// Event timestamp in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+	/// Linux keycode.
+	pub key: u32,
+	/// Key state.
+	pub state: KeyState,
+}
+
+impl KeyEvent {
+	/// Returns `true` when this event is a key press.
+	pub fn is_pressed(&self) -> bool {
+		matches!(self.state, KeyState::Pressed)
+	}
+}
+
+/// Text composition event payload.
+#[derive(Debug, Clone)]
+pub struct CharEvent {
+	/// Composed UTF-8 text.
+	pub text: String,
+}
+
+/// Pointer device class for pointer-style events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointerType {
+	/// Mouse-like pointer device (mouse/touchpad/trackpoint).
+	Mouse,
+	/// Pen/stylus input device.
+	Pen,
+	/// Touch contact input device.
+	Touch,
+	/// Device class could not be determined.
+	Unknown,
+}
+
+/// Generic pointer movement event (browser-like `pointermove` semantics).
+#[derive(Debug, Clone)]
+pub struct PointerMoveEvent {
+	/// Seat the source device belongs to.
+	pub seat: SeatId,
+	/// Source input device id.
+	pub device: u32,
+	/// Event timestamp in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+	/// Pointer class that moved the cursor.
+	pub pointer_type: PointerType,
+	/// Previous cursor position in global layout space.
+	pub old_position: (f64, f64),
+	/// New cursor position in global layout space.
+	pub new_position: (f64, f64),
+	/// Unaccelerated relative motion along x reported by the device, before the cursor is
+	/// clamped to the layout; 0 for absolute pointers, pens and touch.
+	///
+	/// Keeps moving while the cursor rests at a layout edge, e.g. for camera control.
+	pub raw_dx: f64,
+	/// Unaccelerated relative motion along y; see [`PointerMoveEvent::raw_dx`].
+	pub raw_dy: f64,
+}
+
+impl PointerMoveEvent {
+	/// Returns `(dx, dy)` in global layout space.
+	pub fn delta(&self) -> (f64, f64) {
+		(
+			self.new_position.0 - self.old_position.0,
+			self.new_position.1 - self.old_position.1,
+		)
+	}
+}
+
+/// Mouse-only movement event (browser-like `mousemove` semantics).
+#[derive(Debug, Clone)]
+pub struct MouseMoveEvent {
+	/// Seat the source device belongs to.
+	pub seat: SeatId,
+	/// Source input device id.
+	pub device: u32,
+	/// Event timestamp in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+	/// Previous cursor position in global layout space.
+	pub old_position: (f64, f64),
+	/// New cursor position in global layout space.
+	pub new_position: (f64, f64),
+}
+
+impl MouseMoveEvent {
+	/// Returns `(dx, dy)` in global layout space.
+	pub fn delta(&self) -> (f64, f64) {
+		(
+			self.new_position.0 - self.old_position.0,
+			self.new_position.1 - self.old_position.1,
+		)
+	}
+}
+
+/// Pointer down event (browser-like `pointerdown` semantics).
+#[derive(Debug, Clone)]
+pub struct PointerDownEvent {
+	/// Seat the source device belongs to.
+	pub seat: SeatId,
+	/// Source input device id.
+	pub device: u32,
+	/// Event timestamp in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+	/// Pointer class for this event.
+	pub pointer_type: PointerType,
+	/// Logical button code.
+	pub button: u32,
+	/// Cursor position in global layout space.
+	pub position: (f64, f64),
+}
+
+/// Pointer up event (browser-like `pointerup` semantics).
+#[derive(Debug, Clone)]
+pub struct PointerUpEvent {
+	/// Seat the source device belongs to.
+	pub seat: SeatId,
+	/// Source input device id.
+	pub device: u32,
+	/// Event timestamp in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+	/// Pointer class for this event.
+	pub pointer_type: PointerType,
+	/// Logical button code.
+	pub button: u32,
+	/// Cursor position in global layout space.
+	pub position: (f64, f64),
+}
+
+/// Mouse down event (browser-like `mousedown` semantics).
+#[derive(Debug, Clone)]
+pub struct MouseDownEvent {
+	/// Seat the source device belongs to.
+	pub seat: SeatId,
+	/// Source input device id.
+	pub device: u32,
+	/// Event timestamp in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+	/// Mouse button code.
+	pub button: u32,
+	/// Cursor position in global layout space.
+	pub position: (f64, f64),
+}
+
+/// Mouse up event (browser-like `mouseup` semantics).
+#[derive(Debug, Clone)]
+pub struct MouseUpEvent {
+	/// Seat the source device belongs to.
+	pub seat: SeatId,
+	/// Source input device id.
+	pub device: u32,
+	/// Event timestamp in microseconds.
+	pub time_usec: u64,
+	/// `time_usec` on this process's clock, comparable with `Instant::now()`.
+	pub timestamp: Instant,
+	/// Mouse button code.
+	pub button: u32,
+	/// Cursor position in global layout space.
+	pub position: (f64, f64),
+}
+
+/// High-level touch event stream preserving contact ids for multitouch.
+#[derive(Debug, Clone)]
+pub enum TouchEvent {
+	/// New touch contact.
+	Down {
+		seat: SeatId,
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		contact: TouchContact,
+	},
+	/// Updated touch contact.
+	Motion {
+		seat: SeatId,
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		contact: TouchContact,
+	},
+	/// Touch contact ended.
+	Up {
+		seat: SeatId,
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		contact_id: i32,
+	},
+	/// End of touch event frame batch.
+	Frame {
+		seat: SeatId,
+		time_usec: u64,
+		timestamp: Instant,
+	},
+	/// Touch sequence cancelled.
+	Cancel {
+		seat: SeatId,
+		time_usec: u64,
+		timestamp: Instant,
+	},
+}
+
+impl TouchEvent {
+	/// Returns the seat the touch device belongs to.
+	pub fn seat(&self) -> &SeatId {
+		match self {
+			Self::Down { seat, .. }
+			| Self::Motion { seat, .. }
+			| Self::Up { seat, .. }
+			| Self::Frame { seat, .. }
+			| Self::Cancel { seat, .. } => seat,
+		}
+	}
+
+	/// Returns the event time on this process's clock, comparable with `Instant::now()`.
+	pub fn timestamp(&self) -> Instant {
+		match *self {
+			Self::Down { timestamp, .. }
+			| Self::Motion { timestamp, .. }
+			| Self::Up { timestamp, .. }
+			| Self::Frame { timestamp, .. }
+			| Self::Cancel { timestamp, .. } => timestamp,
+		}
+	}
+}
+
+/// High-level multi-finger gesture event stream.
+///
+/// `position` is where the gesture happens in global layout space: the centroid of the
+/// active touch contacts, or the cursor for touchpad gestures. `monitor_id` names the
+/// monitor holding it.
+#[derive(Debug, Clone)]
+pub enum GestureEvent {
+	SwipeBegin {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		fingers: u32,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+	SwipeUpdate {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		fingers: u32,
+		dx: f64,
+		dy: f64,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+	SwipeEnd {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		cancelled: bool,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+	PinchBegin {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		fingers: u32,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+	PinchUpdate {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		fingers: u32,
+		dx: f64,
+		dy: f64,
+		scale: f64,
+		rotation: f64,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+	PinchEnd {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		cancelled: bool,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+	HoldBegin {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		fingers: u32,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+	HoldEnd {
+		device: u32,
+		time_usec: u64,
+		timestamp: Instant,
+		cancelled: bool,
+		position: (f64, f64),
+		monitor_id: Option<String>,
+	},
+}
+
+impl GestureEvent {
+	/// Returns the event time on this process's clock, comparable with `Instant::now()`.
+	pub fn timestamp(&self) -> Instant {
+		match *self {
+			Self::SwipeBegin { timestamp, .. }
+			| Self::SwipeUpdate { timestamp, .. }
+			| Self::SwipeEnd { timestamp, .. }
+			| Self::PinchBegin { timestamp, .. }
+			| Self::PinchUpdate { timestamp, .. }
+			| Self::PinchEnd { timestamp, .. }
+			| Self::HoldBegin { timestamp, .. }
+			| Self::HoldEnd { timestamp, .. } => timestamp,
+		}
+	}
+
+	/// Returns where the gesture happens in global layout space.
+	pub fn position(&self) -> (f64, f64) {
+		match *self {
+			Self::SwipeBegin { position, .. }
+			| Self::SwipeUpdate { position, .. }
+			| Self::SwipeEnd { position, .. }
+			| Self::PinchBegin { position, .. }
+			| Self::PinchUpdate { position, .. }
+			| Self::PinchEnd { position, .. }
+			| Self::HoldBegin { position, .. }
+			| Self::HoldEnd { position, .. } => position,
+		}
+	}
+
+	/// Returns the monitor the gesture happens on; `None` without monitors.
+	pub fn monitor_id(&self) -> Option<&str> {
+		match self {
+			Self::SwipeBegin { monitor_id, .. }
+			| Self::SwipeUpdate { monitor_id, .. }
+			| Self::SwipeEnd { monitor_id, .. }
+			| Self::PinchBegin { monitor_id, .. }
+			| Self::PinchUpdate { monitor_id, .. }
+			| Self::PinchEnd { monitor_id, .. }
+			| Self::HoldBegin { monitor_id, .. }
+			| Self::HoldEnd { monitor_id, .. } => monitor_id.as_deref(),
+		}
+	}
+}
+
+/// Initialization context passed to [`Application::init`].
+pub struct InitContext<A: Application> {
+	config: Config,
+	_marker: PhantomData<A>,
+}
+
+impl<A: Application> InitContext<A> {
+	fn new(config: Config) -> Self {
+		Self {
+			config,
+			_marker: PhantomData,
+		}
+	}
+
+	/// Returns the current runtime configuration.
+	pub fn config(&self) -> &Config {
+		&self.config
+	}
+
+	/// Returns mutable runtime configuration.
+	pub fn config_mut(&mut self) -> &mut Config {
+		&mut self.config
+	}
+}
+
+/// Typed event delivered in batches to [`Application::on_events`].
+#[derive(Debug, Clone)]
+pub enum Event {
+	/// See [`Application::on_present`].
+	Present(PresentEvent),
+	/// See [`Application::on_monitor_added`].
+	MonitorAdded(MonitorAddedEvent),
+	/// See [`Application::on_monitor_changed`].
+	MonitorChanged(MonitorChangedEvent),
+	/// See [`Application::on_monitor_removed`].
+	MonitorRemoved(MonitorRemovedEvent),
+	/// See [`Application::on_safe_area_changed`].
+	SafeAreaChanged(SafeAreaChangedEvent),
+	/// See [`Application::on_monitor_power_changed`].
+	MonitorPowerChanged(MonitorPowerChangedEvent),
+	/// See [`Application::on_buffer_available`].
+	BufferAvailable(BufferAvailableEvent),
+	/// See [`Application::on_starved`].
+	Starved(StarvedEvent),
+	/// See [`Application::on_swapchain_recreated`].
+	SwapchainRecreated(SwapchainRecreatedEvent),
+	/// See [`Application::on_layout_changed`].
+	LayoutChanged(LayoutChangedEvent),
+	/// See [`Application::on_accessibility_changed`].
+	AccessibilityChanged(AccessibilityChangedEvent),
+	/// See [`Application::on_session_state`].
+	Session(SessionEvent),
+	/// See [`Application::on_focus_gained`].
+	FocusGained(FocusEvent),
+	/// See [`Application::on_focus_lost`].
+	FocusLost(FocusEvent),
+	/// See [`Application::on_session_preview`].
+	SessionPreview(SessionPreviewEvent),
+	/// See [`Application::on_capture_frame`].
+	CaptureFrame(CaptureFrameEvent),
+	/// See [`Application::on_capture_ended`].
+	CaptureEnded(CaptureEndedEvent),
+	/// See [`Application::on_session_message`].
+	SessionMessage(SessionMessageEvent),
+	/// See [`Application::on_session_crashed`].
+	SessionCrashed(SessionCrashedEvent),
+	/// See [`Application::on_session_create_result`].
+	SessionCreateResult(SessionCreateResultEvent),
+	/// See [`Application::on_session_switch_result`].
+	SessionSwitchResult(SessionSwitchResultEvent),
+	/// See [`Application::on_session_ready_sent`].
+	SessionReadySent(SessionReadySentEvent),
+	/// See [`Application::on_server_unresponsive`].
+	ServerUnresponsive(ServerUnresponsiveEvent),
+	/// See [`Application::on_server_recovered`].
+	ServerRecovered(ServerRecoveredEvent),
+	/// See [`Application::on_config_changed`].
+	ConfigChanged(ConfigChangedEvent),
+	/// See [`Application::on_input`].
+	Input(InputEvent),
+	/// See [`Application::on_global_shortcut`].
+	GlobalShortcut(GlobalShortcutEvent),
+	/// See [`Application::on_input_device_added`].
+	InputDeviceAdded(InputDeviceAddedEvent),
+	/// See [`Application::on_input_device_removed`].
+	InputDeviceRemoved(InputDeviceRemovedEvent),
+	/// See [`Application::on_key`].
+	Key(KeyEvent),
+	/// See [`Application::on_char`].
+	Char(CharEvent),
+	/// See [`Application::on_pointer_move`].
+	PointerMove(PointerMoveEvent),
+	/// See [`Application::on_mouse_move`].
+	MouseMove(MouseMoveEvent),
+	/// See [`Application::on_pointer_down`].
+	PointerDown(PointerDownEvent),
+	/// See [`Application::on_pointer_up`].
+	PointerUp(PointerUpEvent),
+	/// See [`Application::on_mouse_down`].
+	MouseDown(MouseDownEvent),
+	/// See [`Application::on_mouse_up`].
+	MouseUp(MouseUpEvent),
+	/// See [`Application::on_touch`].
+	Touch(TouchEvent),
+	/// See [`Application::on_gesture`].
+	Gesture(GestureEvent),
+	/// See [`Application::on_fd_ready`].
+	FdReady(FdReadyEvent),
+	/// See [`Application::on_idle`].
+	Idle(IdleEvent),
+	/// See [`Application::on_resume_activity`].
+	ResumeActivity(ResumeActivityEvent),
+	/// See [`Application::on_extension_event`].
+	Extension(ExtensionEvent),
+}
+
+impl Event {
+	fn dispatch<A: Application>(self, app: &mut A, ctx: &mut Context<A>) {
+		match self {
+			Self::Present(ev) => app.on_present(ctx, ev),
+			Self::MonitorAdded(ev) => app.on_monitor_added(ctx, ev),
+			Self::MonitorChanged(ev) => app.on_monitor_changed(ctx, ev),
+			Self::MonitorRemoved(ev) => app.on_monitor_removed(ctx, ev),
+			Self::SafeAreaChanged(ev) => app.on_safe_area_changed(ctx, ev),
+			Self::MonitorPowerChanged(ev) => app.on_monitor_power_changed(ctx, ev),
+			Self::BufferAvailable(ev) => app.on_buffer_available(ctx, ev),
+			Self::Starved(ev) => app.on_starved(ctx, ev),
+			Self::SwapchainRecreated(ev) => app.on_swapchain_recreated(ctx, ev),
+			Self::LayoutChanged(ev) => app.on_layout_changed(ctx, ev),
+			Self::AccessibilityChanged(ev) => app.on_accessibility_changed(ctx, ev),
+			Self::Session(ev) => app.on_session_state(ctx, ev),
+			Self::FocusGained(ev) => app.on_focus_gained(ctx, ev),
+			Self::FocusLost(ev) => app.on_focus_lost(ctx, ev),
+			Self::SessionPreview(ev) => app.on_session_preview(ctx, ev),
+			Self::CaptureFrame(ev) => app.on_capture_frame(ctx, ev),
+			Self::CaptureEnded(ev) => app.on_capture_ended(ctx, ev),
+			Self::SessionMessage(ev) => app.on_session_message(ctx, ev),
+			Self::SessionCrashed(ev) => app.on_session_crashed(ctx, ev),
+			Self::SessionCreateResult(ev) => app.on_session_create_result(ctx, ev),
+			Self::SessionSwitchResult(ev) => app.on_session_switch_result(ctx, ev),
+			Self::SessionReadySent(ev) => app.on_session_ready_sent(ctx, ev),
+			Self::ServerUnresponsive(ev) => app.on_server_unresponsive(ctx, ev),
+			Self::ServerRecovered(ev) => app.on_server_recovered(ctx, ev),
+			Self::ConfigChanged(ev) => app.on_config_changed(ctx, ev),
+			Self::Input(ev) => app.on_input(ctx, &ev),
+			Self::GlobalShortcut(ev) => app.on_global_shortcut(ctx, ev),
+			Self::InputDeviceAdded(ev) => app.on_input_device_added(ctx, ev),
+			Self::InputDeviceRemoved(ev) => app.on_input_device_removed(ctx, ev),
+			Self::Key(ev) => app.on_key(ctx, ev),
+			Self::Char(ev) => app.on_char(ctx, ev),
+			Self::PointerMove(ev) => app.on_pointer_move(ctx, ev),
+			Self::MouseMove(ev) => app.on_mouse_move(ctx, ev),
+			Self::PointerDown(ev) => app.on_pointer_down(ctx, ev),
+			Self::PointerUp(ev) => app.on_pointer_up(ctx, ev),
+			Self::MouseDown(ev) => app.on_mouse_down(ctx, ev),
+			Self::MouseUp(ev) => app.on_mouse_up(ctx, ev),
+			Self::Touch(ev) => app.on_touch(ctx, ev),
+			Self::Gesture(ev) => app.on_gesture(ctx, ev),
+			Self::FdReady(ev) => app.on_fd_ready(ctx, ev),
+			Self::Idle(ev) => app.on_idle(ctx, ev),
+			Self::ResumeActivity(ev) => app.on_resume_activity(ctx, ev),
+			Self::Extension(ev) => app.on_extension_event(ctx, ev),
+		}
+	}
+}
+
+/// Core application trait implemented by framework users.
+pub trait Application: Sized + 'static {
+	/// Constructs the application instance.
+	fn init(ctx: &mut InitContext<Self>) -> anyhow::Result<Self>;
+
+	/// Called once per loop iteration before the [`Application::on_render`] calls it issues,
+	/// with the monitors due for a frame sorted by id.
+	///
+	/// `on_render` follows in the same order, skipping monitors without a free buffer.
+	/// Not called in explicit submit mode.
+	fn on_begin_frame(&mut self, _ctx: &mut Context<Self>, _monitor_ids: &[String]) {}
+	/// Called after an iteration's last [`Application::on_render`] call.
+	fn on_end_frame(&mut self, _ctx: &mut Context<Self>) {}
+	/// Called after a buffer is acquired and ready to be rendered into.
+	fn on_render(&mut self, _ctx: &mut Context<Self>, _ev: RenderEvent<'_>) {}
+	/// Called after [`Application::on_begin_frame`] with the monitors due for a frame when
+	/// [`Config::set_parallel_render`] is on and more than one is due.
+	///
+	/// Frames acquired and submitted here through [`Context::acquire_target`] and
+	/// [`Context::submit_frame`] are done; the other monitors get `on_render` as usual.
+	fn on_render_parallel(&mut self, _ctx: &mut Context<Self>, _ev: FrameRequestEvent) {}
+	/// Called with the monitors due for a frame when [`Config::set_explicit_submit`] is on.
+	fn on_frame_requested(&mut self, _ctx: &mut Context<Self>, _ev: FrameRequestEvent) {}
+	/// Called when a previously rendered buffer is presented/released.
+	fn on_present(&mut self, _ctx: &mut Context<Self>, _ev: PresentEvent) {}
+	/// Called when a monitor becomes available.
+	fn on_monitor_added(&mut self, _ctx: &mut Context<Self>, _ev: MonitorAddedEvent) {}
+	/// Called when a monitor's mode or properties change without reconnecting.
+	fn on_monitor_changed(&mut self, _ctx: &mut Context<Self>, _ev: MonitorChangedEvent) {}
+	/// Called when a monitor is removed.
+	fn on_monitor_removed(&mut self, _ctx: &mut Context<Self>, _ev: MonitorRemovedEvent) {}
+	/// Called when compositor chrome starts or stops covering parts of a monitor.
+	fn on_safe_area_changed(&mut self, _ctx: &mut Context<Self>, _ev: SafeAreaChangedEvent) {}
+	/// Called when a monitor's display is switched on, to standby or off.
+	///
+	/// Shift does not draw monitors that are not [`MonitorPower::On`], but frames may still
+	/// be submitted to them.
+	fn on_monitor_power_changed(&mut self, _ctx: &mut Context<Self>, _ev: MonitorPowerChangedEvent) {}
+	/// Called when a buffer of a monitor becomes free for rendering, once its release fence
+	/// signaled.
+	///
+	/// Lets [`RenderMode::Scheduled`] applications call [`Context::schedule_frame`] as soon
+	/// as a frame can be acquired instead of missing. Also fires for buffers whose request
+	/// the server rejected.
+	fn on_buffer_available(&mut self, _ctx: &mut Context<Self>, _ev: BufferAvailableEvent) {}
+	/// Called once per starvation period when the server held every buffer of a monitor
+	/// for [`Config::set_starvation_threshold`].
+	///
+	/// The monitor keeps rendering at whatever rate buffers come back; see
+	/// [`Context::buffer_ownership`] for who holds them.
+	fn on_starved(&mut self, _ctx: &mut Context<Self>, _ev: StarvedEvent) {}
+	/// Called after [`Context::recreate_swapchain`] replaced a monitor's buffers.
+	///
+	/// Renderers drop state cached for the old buffers, such as imported framebuffers.
+	fn on_swapchain_recreated(&mut self, _ctx: &mut Context<Self>, _ev: SwapchainRecreatedEvent) {}
+	/// Called once per loop iteration in which the monitor layout changed, after the
+	/// monitor events that caused it.
+	///
+	/// Covers hotplug, mode changes and [`Context::set_monitor_position`]; monitors that
+	/// are already connected at startup are not reported.
+	fn on_layout_changed(&mut self, _ctx: &mut Context<Self>, _ev: LayoutChangedEvent) {}
+	/// Called when reduced motion, text scale or high contrast preferences change, e.g. to
+	/// shorten animations or re-layout text.
+	fn on_accessibility_changed(&mut self, _ctx: &mut Context<Self>, _ev: AccessibilityChangedEvent) {
+	}
+	/// Called when session state changes.
+	fn on_session_state(&mut self, _ctx: &mut Context<Self>, _ev: SessionEvent) {}
+	/// Called when this session becomes the active one and starts receiving input.
+	fn on_focus_gained(&mut self, _ctx: &mut Context<Self>, _ev: FocusEvent) {}
+	/// Called when another session becomes active.
+	fn on_focus_lost(&mut self, _ctx: &mut Context<Self>, _ev: FocusEvent) {}
+	/// Called with a snapshot requested through [`Context::request_session_preview`].
+	fn on_session_preview(&mut self, _ctx: &mut Context<Self>, _ev: SessionPreviewEvent) {}
+	/// Called when a capture from [`Context::start_capture`] has a new frame.
+	///
+	/// The default implementation hands the buffer straight back.
+	fn on_capture_frame(&mut self, ctx: &mut Context<Self>, ev: CaptureFrameEvent) {
+		let _ = ctx.release_capture_frame(&ev);
+	}
+	/// Called when shift stopped a capture on its own.
+	fn on_capture_ended(&mut self, _ctx: &mut Context<Self>, _ev: CaptureEndedEvent) {}
+	/// Called when another session sends this session a message.
+	fn on_session_message(&mut self, _ctx: &mut Context<Self>, _ev: SessionMessageEvent) {}
+	/// Called in admin sessions when another session reported a crash.
+	fn on_session_crashed(&mut self, _ctx: &mut Context<Self>, _ev: SessionCrashedEvent) {}
+	/// Called with the answer to [`Context::create_session_async`].
+	fn on_session_create_result(&mut self, _ctx: &mut Context<Self>, _ev: SessionCreateResultEvent) {}
+	/// Called with the outcome of [`Context::switch_session_async`].
+	fn on_session_switch_result(&mut self, _ctx: &mut Context<Self>, _ev: SessionSwitchResultEvent) {}
+	/// Called once the framework sent `session_ready` for [`Config::auto_ready`].
+	fn on_session_ready_sent(&mut self, _ctx: &mut Context<Self>, _ev: SessionReadySentEvent) {}
+	/// Called when the server stopped answering keepalive pings, e.g. to show a stall notice.
+	fn on_server_unresponsive(&mut self, _ctx: &mut Context<Self>, _ev: ServerUnresponsiveEvent) {}
+	/// Called when the server answers again after [`Application::on_server_unresponsive`].
+	fn on_server_recovered(&mut self, _ctx: &mut Context<Self>, _ev: ServerRecoveredEvent) {}
+	/// Called once changes made through [`Context::update_config`] took effect.
+	fn on_config_changed(&mut self, _ctx: &mut Context<Self>, _ev: ConfigChangedEvent) {}
+	/// Called for every raw input event.
+	fn on_input(&mut self, _ctx: &mut Context<Self>, _ev: &InputEvent) {}
+	/// Called when a shortcut from [`Context::register_global_shortcut`] is pressed.
+	fn on_global_shortcut(&mut self, _ctx: &mut Context<Self>, _ev: GlobalShortcutEvent) {}
+	/// Called when an input device is plugged in.
+	fn on_input_device_added(&mut self, _ctx: &mut Context<Self>, _ev: InputDeviceAddedEvent) {}
+	/// Called when an input device is unplugged.
+	fn on_input_device_removed(&mut self, _ctx: &mut Context<Self>, _ev: InputDeviceRemovedEvent) {}
+	/// Called for key events.
+	fn on_key(&mut self, _ctx: &mut Context<Self>, _ev: KeyEvent) {}
+	/// Called for composed text events.
+	fn on_char(&mut self, _ctx: &mut Context<Self>, _ev: CharEvent) {}
+	/// Called when any pointer device moves the cursor.
+	fn on_pointer_move(&mut self, _ctx: &mut Context<Self>, _ev: PointerMoveEvent) {}
+	/// Called when a mouse-like device moves the cursor.
+	fn on_mouse_move(&mut self, _ctx: &mut Context<Self>, _ev: MouseMoveEvent) {}
+	/// Called when any pointer device produces a down transition.
+	fn on_pointer_down(&mut self, _ctx: &mut Context<Self>, _ev: PointerDownEvent) {}
+	/// Called when any pointer device produces an up transition.
+	fn on_pointer_up(&mut self, _ctx: &mut Context<Self>, _ev: PointerUpEvent) {}
+	/// Called when a mouse-like device produces a down transition.
+	fn on_mouse_down(&mut self, _ctx: &mut Context<Self>, _ev: MouseDownEvent) {}
+	/// Called when a mouse-like device produces an up transition.
+	fn on_mouse_up(&mut self, _ctx: &mut Context<Self>, _ev: MouseUpEvent) {}
+	/// Called for multitouch contact lifecycle events.
+	fn on_touch(&mut self, _ctx: &mut Context<Self>, _ev: TouchEvent) {}
+	/// Called for high-level multi-finger gesture events.
+	fn on_gesture(&mut self, _ctx: &mut Context<Self>, _ev: GestureEvent) {}
+	/// Called when a watched file descriptor is readable.
+	fn on_fd_ready(&mut self, _ctx: &mut Context<Self>, _ev: FdReadyEvent) {}
+	/// Called when the idle timeout requested through the context elapses without input.
+	fn on_idle(&mut self, _ctx: &mut Context<Self>, _ev: IdleEvent) {}
+	/// Called on the first input event after [`Application::on_idle`].
+	fn on_resume_activity(&mut self, _ctx: &mut Context<Self>, _ev: ResumeActivityEvent) {}
+	/// Called once per loop iteration with all events when batching is enabled.
+	fn on_events(&mut self, _ctx: &mut Context<Self>, _events: &[Event]) {}
+	/// Called once per stats interval with the loop counters it accumulated.
+	fn on_stats(&mut self, _ctx: &mut Context<Self>, _stats: LoopStatsSnapshot) {}
+	/// Called with server messages decoded by an extension registered through
+	/// [`Context::register_extension`].
+	fn on_extension_event(&mut self, _ctx: &mut Context<Self>, _ev: ExtensionEvent) {}
+	/// Called with values sent through [`FrameworkController::send_user_event`].
+	fn on_user_event(&mut self, _ctx: &mut Context<Self>, _ev: UserEvent) {}
+	/// Called when the framework surfaces an error.
+	fn on_error(&mut self, _ctx: &mut Context<Self>, _error: &FrameworkError) {}
+}
+
+/// Mutable runtime context passed into application callbacks.
+pub struct Context<'a, A: Application> {
+	client: &'a mut Connection,
+	monitors: &'a mut HashMap<String, MonitorRuntime>,
+	placements: &'a mut Vec<MonitorPlacement>,
+	scheduled: &'a mut HashSet<String>,
+	watched_fds: &'a mut HashSet<RawFd>,
+	next_acquire_fence: &'a mut Option<OwnedFd>,
+	frame_cancelled: &'a mut bool,
+	frame_user_data: &'a mut Option<u64>,
+	next_frame_id: &'a mut u64,
+	overlays: &'a mut Overlays,
+	captures: &'a mut Captures,
+	cursor_position: &'a mut (f64, f64),
+	touch_contacts: &'a HashMap<i32, (f64, f64)>,
+	active_seat: &'a SeatId,
+	idle_seats: &'a mut HashMap<SeatId, SeatState>,
+	input_devices: &'a HashMap<u32, InputDeviceInfo>,
+	layout_dirty: &'a mut bool,
+	exiting: &'a mut bool,
+	frame_timings: &'a FrameTimings,
+	loop_stats: &'a LoopStatsSnapshot,
+	idle: &'a mut IdleTracker,
+	idle_inhibitors: &'a Rc<RefCell<IdleInhibitRegistry>>,
+	global_shortcuts: &'a mut GlobalShortcuts,
+	next_correlation_id: &'a mut u64,
+	session_requests: &'a mut SessionRequests,
+	focus: &'a mut FocusState,
+	accessibility: &'a AccessibilitySettings,
+	input_filters: &'a mut InputFilters,
+	event_queue: &'a RefCell<VecDeque<QueuedEvent>>,
+	config: &'a mut Config,
+	config_changes: &'a mut Vec<ConfigField>,
+	recreated_swapchains: &'a mut Vec<String>,
+	ready_sent: &'a mut bool,
+	_marker: PhantomData<A>,
+}
+
+impl<'a, A: Application> Context<'a, A> {
+	/// Schedules a frame for a specific monitor.
+	pub fn schedule_frame(&mut self, monitor_id: impl Into<String>) {
+		self.scheduled.insert(monitor_id.into());
+	}
+
+	/// Schedules a frame for every known monitor.
+	pub fn schedule_all_frames(&mut self) {
+		self.scheduled.extend(self.monitors.keys().cloned());
+	}
+
+	/// Schedules a frame for a monitor once `delay` has passed, e.g. to redraw a blinking
+	/// caret in [`RenderMode::Scheduled`].
+	///
+	/// Requests made before the frame is scheduled coalesce to the earliest deadline.
+	/// Unknown monitors are ignored.
+	pub fn request_redraw_after(&mut self, delay: Duration, monitor_id: &str) {
+		let Some(monitor_rt) = self.monitors.get_mut(monitor_id) else {
+			return;
+		};
+		let at = Instant::now() + delay;
+		monitor_rt.redraw_at = Some(monitor_rt.redraw_at.map_or(at, |pending| pending.min(at)));
+	}
+
+	/// Stops rendering to a monitor until [`Context::resume_monitor`], keeping its swapchain.
+	///
+	/// Scheduled frames, redraw requests, retries and eager rendering on buffer release
+	/// all skip the monitor; the server keeps showing its last presented frame. A frame
+	/// already in flight still completes. Returns false for unknown monitors.
+	pub fn pause_monitor(&mut self, monitor_id: &str) -> bool {
+		let Some(monitor_rt) = self.monitors.get_mut(monitor_id) else {
+			return false;
+		};
+		monitor_rt.paused = true;
+		monitor_rt.redraw_at = None;
+		monitor_rt.retry = RetryState::default();
+		self.scheduled.remove(monitor_id);
+		true
+	}
+
+	/// Resumes rendering to a monitor paused with [`Context::pause_monitor`] and schedules
+	/// a frame for it. Returns false for unknown monitors.
+	pub fn resume_monitor(&mut self, monitor_id: &str) -> bool {
+		let Some(monitor_rt) = self.monitors.get_mut(monitor_id) else {
+			return false;
+		};
+		if std::mem::take(&mut monitor_rt.paused) {
+			self.scheduled.insert(monitor_id.to_string());
+		}
+		true
+	}
+
+	/// Returns true while a monitor is paused with [`Context::pause_monitor`].
+	pub fn is_monitor_paused(&self, monitor_id: &str) -> bool {
+		self.monitors.get(monitor_id).is_some_and(|m| m.paused)
+	}
+
+	/// Returns an iterator over all known monitors, ordered by layout position, then id.
+	pub fn monitors(&self) -> impl Iterator<Item = &Monitor> {
+		sorted_monitors(self.monitors).into_iter()
+	}
+
+	/// Returns the primary monitor.
+	///
+	/// That is the monitor an admin session chose with [`Context::set_primary_monitor`], or
+	/// the first one in [`Context::monitors`] order while none is chosen.
+	pub fn primary_monitor(&self) -> Option<&Monitor> {
+		let sorted = sorted_monitors(self.monitors);
+		let first = sorted.first().copied();
+		sorted.into_iter().find(|m| m.primary).or(first)
+	}
+
+	/// Makes a monitor the primary one for every session.
+	///
+	/// Admin sessions only. The change is reported through
+	/// [`Application::on_monitor_changed`] for the old and new primary monitor, a rejection
+	/// through [`Application::on_error`] as [`FrameworkError::AdminRequestFailed`].
+	pub fn set_primary_monitor(&mut self, monitor_id: &str) -> Result<(), FrameworkError> {
+		self.check_output_control(monitor_id)?;
+		let request_id = self.session_requests.admin("set_primary_monitor");
+		self
+			.client
+			.set_primary_monitor(monitor_id, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Returns a monitor by id.
+	pub fn monitor(&self, monitor_id: &str) -> Option<&Monitor> {
+		self.monitors.get(monitor_id).map(|m| &m.monitor)
+	}
+
+	/// Returns the monitor under a global layout position, e.g. to route pointer input.
+	///
+	/// Edges shared by two monitors belong to the right or lower one, as for the cursor;
+	/// the outer right and bottom edges of the layout still hit their monitor.
+	pub fn monitor_at(&self, global_position: (f64, f64)) -> Option<&Monitor> {
+		let placement = monitor_at(self.placements, global_position.0, global_position.1)?;
+		self.monitor(&placement.id)
+	}
+
+	/// Returns which parts of a monitor compositor chrome covers, in buffer pixels.
+	///
+	/// Keep UI that must stay visible inside [`SafeArea::safe`]. Changes are reported
+	/// through [`Application::on_safe_area_changed`].
+	pub fn safe_area(&self, monitor_id: &str) -> Option<SafeArea> {
+		self.monitors.get(monitor_id).map(MonitorRuntime::safe_area)
+	}
+
+	/// Returns the power state of a monitor's display, as last reported by the server.
+	pub fn monitor_power(&self, monitor_id: &str) -> Option<MonitorPower> {
+		self
+			.monitors
+			.get(monitor_id)
+			.map(|monitor_rt| monitor_rt.power)
+	}
+
+	/// Returns how a monitor's buffers are prepared before [`Application::on_render`].
+	pub fn clear_policy(&self, monitor_id: &str) -> Option<ClearPolicy> {
+		self.monitors.get(monitor_id).map(|m| m.clear_policy)
+	}
+
+	/// Changes how a monitor's buffers are prepared before [`Application::on_render`].
+	///
+	/// Takes effect from the next frame and lasts until the monitor goes away; use
+	/// [`Config::set_clear_policy`] to have it applied whenever the monitor appears.
+	pub fn set_clear_policy(
+		&mut self,
+		monitor_id: &str,
+		policy: ClearPolicy,
+	) -> Result<(), FrameworkError> {
+		let monitor_rt = self
+			.monitors
+			.get_mut(monitor_id)
+			.ok_or_else(|| FrameworkError::MonitorNotFound(monitor_id.to_string()))?;
+		monitor_rt.clear_policy = policy;
+		Ok(())
+	}
+
+	/// Returns how eager rendering retries a monitor's failed frames.
+	pub fn retry_policy(&self, monitor_id: &str) -> Option<RetryPolicy> {
+		self.monitors.get(monitor_id).map(|m| m.retry_policy)
+	}
+
+	/// Changes how eager rendering retries a monitor's failed frames.
+	///
+	/// Lasts until the monitor goes away; use [`Config::set_retry_policy`] to have it
+	/// applied whenever the monitor appears.
+	pub fn set_retry_policy(
+		&mut self,
+		monitor_id: &str,
+		policy: RetryPolicy,
+	) -> Result<(), FrameworkError> {
+		let monitor_rt = self
+			.monitors
+			.get_mut(monitor_id)
+			.ok_or_else(|| FrameworkError::MonitorNotFound(monitor_id.to_string()))?;
+		monitor_rt.retry_policy = policy;
+		Ok(())
+	}
+
+	/// Returns the configuration in effect.
+	pub fn config(&self) -> &Config {
+		self.config
+	}
+
+	/// Changes the configuration while the application runs.
+	///
+	/// Fields for which [`ConfigField::is_live`] holds take effect by the end of the loop
+	/// iteration and are reported through [`Application::on_config_changed`]; changed
+	/// clear and retry policies also replace those of present monitors. Other changes are
+	/// dropped and their fields returned, as they need a new connection.
+	pub fn update_config(&mut self, update: impl FnOnce(&mut Config)) -> Vec<ConfigField> {
+		let mut updated = self.config.clone();
+		update(&mut updated);
+		let (live, reconnect): (Vec<_>, Vec<_>) = self
+			.config
+			.changed_fields(&updated)
+			.into_iter()
+			.partition(|field| field.is_live());
+		for (monitor_id, monitor_rt) in self.monitors.iter_mut() {
+			if self.config.clear_policy(monitor_id) != updated.clear_policy(monitor_id) {
+				monitor_rt.clear_policy = updated.clear_policy(monitor_id);
+			}
+			if self.config.retry_policy(monitor_id) != updated.retry_policy(monitor_id) {
+				monitor_rt.retry_policy = updated.retry_policy(monitor_id);
+			}
+		}
+		if self.config.render_mode != RenderMode::Eager && updated.render_mode == RenderMode::Eager {
+			self.scheduled.extend(self.monitors.keys().cloned());
+		}
+		for field in live {
+			self.config.copy_live_field(&updated, field);
+			if !self.config_changes.contains(&field) {
+				self.config_changes.push(field);
+			}
+		}
+		reconnect
+	}
+
+	/// Moves a monitor's swapchain to shared memory, for content the CPU draws through
+	/// [`RenderTarget::map`].
+	///
+	/// Shm buffers need no GPU access, but the server uploads every submitted frame, so
+	/// they suit small or rarely updated content. Only core applications can draw into
+	/// them. The monitor keeps shm buffers across resizes, and a frame is scheduled.
+	pub fn create_shm_surface(&mut self, monitor_id: &str) -> Result<(), FrameworkError> {
+		let monitor_rt = self
+			.monitors
+			.get_mut(monitor_id)
+			.ok_or_else(|| FrameworkError::MonitorNotFound(monitor_id.to_string()))?;
+		if monitor_rt.shm_surface {
+			return Ok(());
+		}
+		let swapchain = self.client.create_shm_swapchain(monitor_id)?;
+		monitor_rt.replace_swapchain(swapchain);
+		monitor_rt.shm_surface = true;
+		self.scheduled.insert(monitor_id.to_string());
+		Ok(())
+	}
+
+	/// Replaces a monitor's swapchain with freshly allocated and linked buffers.
+	///
+	/// Waits up to [`Config::set_buffer_request_ack_timeout`] for pending release fences, so
+	/// the server is done reading the old buffers. A pending buffer request is forgotten and
+	/// frames acquired from the old swapchain can no longer be submitted. Shm surfaces stay
+	/// in shared memory. [`Application::on_swapchain_recreated`] follows by the end of the
+	/// loop iteration, and a frame is scheduled.
+	pub fn recreate_swapchain(&mut self, monitor_id: &str) -> Result<(), FrameworkError> {
+		let monitor_rt = self
+			.monitors
+			.get_mut(monitor_id)
+			.ok_or_else(|| FrameworkError::MonitorNotFound(monitor_id.to_string()))?;
+		let deadline = Instant::now() + self.config.buffer_request_ack_timeout;
+		for fence in monitor_rt.pending_release_fences.iter().flatten() {
+			let remaining = deadline.saturating_duration_since(Instant::now());
+			if !fd_readable(fence, remaining)? {
+				tracing::warn!(
+					monitor_id,
+					"release fence still pending, recreating swapchain anyway"
+				);
+				break;
+			}
+		}
+		let swapchain = if monitor_rt.shm_surface {
+			self.client.create_shm_swapchain(monitor_id)?
+		} else {
+			self.client.create_swapchain(monitor_id)?
+		};
+		monitor_rt.replace_swapchain(swapchain);
+		self.scheduled.insert(monitor_id.to_string());
+		if !self.recreated_swapchains.iter().any(|id| id == monitor_id) {
+			self.recreated_swapchains.push(monitor_id.to_string());
+		}
+		Ok(())
+	}
+
+	/// Returns the modes a monitor advertises.
+	pub fn monitor_modes(&self, monitor_id: &str) -> Option<&[MonitorMode]> {
+		self
+			.monitors
+			.get(monitor_id)
+			.map(|m| m.monitor.modes.as_slice())
+	}
+
+	/// Asks the server to switch a monitor to one of its advertised modes.
+	///
+	/// Admin sessions only. The change is reported through
+	/// [`Application::on_monitor_changed`] once the server has applied it, a rejection
+	/// through [`Application::on_error`] as [`FrameworkError::AdminRequestFailed`].
+	pub fn request_mode(
+		&mut self,
+		monitor_id: &str,
+		mode: MonitorMode,
+	) -> Result<(), FrameworkError> {
+		self.check_output_control(monitor_id)?;
+		if !self.monitors[monitor_id].monitor.modes.contains(&mode) {
+			return Err(FrameworkError::Config(format!(
+				"monitor {monitor_id} does not support {}x{}@{}",
+				mode.width, mode.height, mode.refresh_rate
+			)));
+		}
+		let request_id = self.session_requests.admin("request_mode");
+		self
+			.client
+			.set_monitor_mode(monitor_id, mode, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Sets monitor position in global layout space.
+	///
+	/// The resulting layout must remain edge-contiguous and non-overlapping.
+	pub fn set_monitor_position(
+		&mut self,
+		monitor_id: &str,
+		x: i32,
+		y: i32,
+	) -> Result<(), FrameworkError> {
+		let old = {
+			let Some(m) = self.monitors.get(monitor_id) else {
+				return Err(FrameworkError::MonitorNotFound(monitor_id.to_string()));
+			};
+			(m.monitor.x, m.monitor.y)
+		};
+		if let Some(m) = self.monitors.get_mut(monitor_id) {
+			m.monitor.x = x;
+			m.monitor.y = y;
+		}
+		fill_layout(self.monitors, self.placements);
+		if !is_valid_edge_contiguous_layout(self.placements) {
+			if let Some(m) = self.monitors.get_mut(monitor_id) {
+				m.monitor.x = old.0;
+				m.monitor.y = old.1;
+			}
+			fill_layout(self.monitors, self.placements);
+			return Err(FrameworkError::Config(
+				"invalid monitor layout: monitors must edge-touch, must not overlap, and cannot form islands"
+					.into(),
+			));
+		}
+		let (cx, cy) = clamp_point_to_layout(self.placements, self.cursor_position.0, self.cursor_position.1);
+		*self.cursor_position = (cx, cy);
+		clamp_idle_seats(self.placements, self.idle_seats);
+		*self.layout_dirty = true;
+		Ok(())
+	}
+
+	/// Recomputes monitor positions using default horizontal packing.
+	pub fn apply_horizontal_layout(&mut self) {
+		recompute_layout(self.monitors);
+		fill_layout(self.monitors, self.placements);
+		let (cx, cy) = clamp_point_to_layout(self.placements, self.cursor_position.0, self.cursor_position.1);
+		*self.cursor_position = (cx, cy);
+		clamp_idle_seats(self.placements, self.idle_seats);
+		*self.layout_dirty = true;
+	}
+
+	/// Returns current cursor position in global layout space.
+	///
+	/// This is the cursor of [`Context::seat`]; other seats are queried with
+	/// [`Context::seat_cursor_position`].
+	pub fn cursor_position(&self) -> (f64, f64) {
+		*self.cursor_position
+	}
+
+	/// Returns the seat that produced the most recent input event.
+	pub fn seat(&self) -> &SeatId {
+		self.active_seat
+	}
+
+	/// Returns the cursor position of a seat that has produced input.
+	pub fn seat_cursor_position(&self, seat: &SeatId) -> Option<(f64, f64)> {
+		if seat == self.active_seat {
+			return Some(*self.cursor_position);
+		}
+		self.idle_seats.get(seat).map(|state| state.cursor_position)
+	}
+
+	/// Returns every seat that has produced input so far.
+	pub fn seats(&self) -> impl Iterator<Item = &SeatId> {
+		std::iter::once(self.active_seat).chain(self.idle_seats.keys())
+	}
+
+	/// Returns all currently plugged input devices.
+	pub fn input_devices(&self) -> impl Iterator<Item = &InputDeviceInfo> {
+		self.input_devices.values()
+	}
+
+	/// Returns metadata for the device id carried by input events.
+	pub fn input_device(&self, device: u32) -> Option<&InputDeviceInfo> {
+		self.input_devices.get(&device)
+	}
+
+	/// Returns the monitor a device is mapped to, such as a touchscreen's panel.
+	pub fn input_device_monitor(&self, device: u32) -> Option<&Monitor> {
+		let output = self.input_devices.get(&device)?.output_name.as_deref()?;
+		self
+			.monitors
+			.values()
+			.map(|m| &m.monitor)
+			.find(|m| m.name == output)
+	}
+
+	/// Appends `filter` to the chain raw input passes through before any input callback.
+	///
+	/// See [`InputFilter`]; events already delivered are not filtered again.
+	pub fn add_input_filter(&mut self, filter: impl InputFilter + 'static) {
+		self.input_filters.push(Box::new(filter));
+	}
+
+	/// Queues `event` as input from the current seat, e.g. for automated UI tests.
+	///
+	/// It is processed in the next loop iteration like device input: through the input
+	/// filters, cursor and touch tracking, and every matching input callback.
+	pub fn inject_input(&mut self, event: InputEventPayload) {
+		self
+			.event_queue
+			.borrow_mut()
+			.push_back(QueuedEvent::Input(TabInputEvent::Event {
+				seat: self.active_seat.clone(),
+				payload: event,
+			}));
+	}
+
+	/// Delivers `event` to another session as input from `seat`, e.g. for remote control.
+	///
+	/// Admin sessions only. The target receives it like device input, bypassing global
+	/// shortcuts and keyboard grabs. Shift rejects unknown sessions, reported through
+	/// [`Application::on_error`] as [`FrameworkError::AdminRequestFailed`].
+	pub fn inject_session_input(
+		&mut self,
+		session_id: &str,
+		seat: SeatId,
+		event: InputEventPayload,
+	) -> Result<(), FrameworkError> {
+		if self.client.session().role != SessionRole::Admin {
+			return Err(FrameworkError::Forbidden(
+				"input injection requires an admin session".into(),
+			));
+		}
+		let request_id = self.session_requests.admin("inject_session_input");
+		self
+			.client
+			.inject_input(session_id, seat, event, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Returns phase timings of the last completed loop iteration.
+	pub fn frame_timings(&self) -> &FrameTimings {
+		self.frame_timings
+	}
+
+	/// Returns loop counters for the last completed reporting interval.
+	pub fn loop_stats(&self) -> &LoopStatsSnapshot {
+		self.loop_stats
+	}
+
+	/// Returns who holds a monitor's swapchain buffers, for back-pressure diagnostics.
+	///
+	/// A monitor whose buffers all stay with the server drops frames; it counts as starved
+	/// from the first failed acquire until a buffer is free again.
+	pub fn buffer_ownership(&self, monitor_id: &str) -> Option<BufferOwnership> {
+		self
+			.monitors
+			.get(monitor_id)
+			.map(MonitorRuntime::buffer_ownership)
+	}
+
+	/// Captures monitors, swapchain states, seats and the scheduled set for a bug report.
+	///
+	/// See [`Config::set_dump_state_on_error`] to log one with every error.
+	pub fn debug_snapshot(&self) -> DebugSnapshot {
+		DebugSnapshot::capture(
+			self.monitors,
+			self.scheduled,
+			self.active_seat,
+			*self.cursor_position,
+			self.touch_contacts,
+			self.idle_seats,
+		)
+	}
+
+	/// Requests [`Application::on_idle`] once no input arrives for `timeout`.
+	///
+	/// The countdown starts now and restarts on every input event; after firing,
+	/// the next input event delivers [`Application::on_resume_activity`]. Replaces any
+	/// previous request.
+	pub fn request_idle_notification(&mut self, timeout: Duration) {
+		self.idle.request(timeout);
+	}
+
+	/// Stops idle tracking started by [`Context::request_idle_notification`].
+	pub fn cancel_idle_notification(&mut self) {
+		self.idle.cancel();
+	}
+
+	/// Returns true between [`Application::on_idle`] and the next input event.
+	pub fn is_idle(&self) -> bool {
+		self.idle.idle
+	}
+
+	/// Asks the server to keep this session awake until the returned guard is dropped.
+	///
+	/// While inhibited the session is not put to sleep when it loses focus. The request
+	/// is sent at the end of the current loop iteration.
+	pub fn inhibit_idle(&mut self, reason: impl Into<String>) -> IdleInhibitor {
+		let mut registry = self.idle_inhibitors.borrow_mut();
+		let id = registry.next_id;
+		registry.next_id += 1;
+		registry.active.push((id, reason.into()));
+		IdleInhibitor {
+			id,
+			registry: Rc::clone(self.idle_inhibitors),
+		}
+	}
+
+	/// Adds a file descriptor to the readable watch set.
+	pub fn watch_fd(&mut self, fd: RawFd) {
+		self.watched_fds.insert(fd);
+	}
+
+	/// Removes a file descriptor from the watch set.
+	pub fn unwatch_fd(&mut self, fd: RawFd) {
+		self.watched_fds.remove(&fd);
+	}
+
+	/// Requests graceful termination of the main loop.
+	pub fn request_exit(&mut self) {
+		*self.exiting = true;
+	}
+
+	/// Sets an acquire fence to be sent with the next buffer request.
+	pub fn set_next_acquire_fence(&mut self, fence_fd: OwnedFd) {
+		*self.next_acquire_fence = Some(fence_fd);
+	}
+
+	/// Drops the frame being rendered instead of presenting it.
+	///
+	/// Only meaningful inside `on_render`. The acquired buffer goes back to the
+	/// swapchain unsent and keeps its [`RenderEvent::buffer_age`], so leave its
+	/// contents untouched when cancelling.
+	pub fn cancel_frame(&mut self) {
+		*self.frame_cancelled = true;
+	}
+
+	/// Returns true once [`Context::cancel_frame`] was called for the current frame.
+	pub fn frame_cancelled(&self) -> bool {
+		*self.frame_cancelled
+	}
+
+	/// Attaches an opaque value to the frame being rendered, handed back in its
+	/// [`PresentEvent::user_data`].
+	///
+	/// Only meaningful inside `on_render`; use [`AcquiredFrame::set_user_data`] for
+	/// explicitly submitted frames.
+	pub fn set_frame_user_data(&mut self, user_data: u64) {
+		*self.frame_user_data = Some(user_data);
+	}
+
+	/// Acquires a buffer of `monitor_id` for explicit rendering.
+	///
+	/// Intended for [`Config::set_explicit_submit`], but usable from any callback.
+	pub fn acquire_target(&mut self, monitor_id: &str) -> Result<AcquiredFrame, FrameworkError> {
+		let monitor_rt = self
+			.monitors
+			.get_mut(monitor_id)
+			.ok_or_else(|| FrameworkError::MonitorNotFound(monitor_id.to_string()))?;
+		let unavailable = || FrameworkError::FrameUnavailable(monitor_id.to_string());
+		if monitor_rt.inflight_request.is_some() || monitor_rt.acquired.is_some() {
+			return Err(unavailable());
+		}
+		let Some((_, buffer_index)) = monitor_rt.swapchain.acquire_next() else {
+			monitor_rt.acquire_missed();
+			return Err(unavailable());
+		};
+		monitor_rt.end_starvation();
+		monitor_rt.acquired = Some(buffer_index);
+		*self.next_frame_id += 1;
+		Ok(AcquiredFrame {
+			monitor_id: monitor_id.to_string(),
+			frame_id: *self.next_frame_id,
+			user_data: None,
+			buffer_index,
+			buffer_age: monitor_rt.swapchain.buffer_age(buffer_index),
+			resized: std::mem::take(&mut monitor_rt.resized),
+		})
+	}
+
+	/// Returns both buffers of a monitor's swapchain, indexed by [`BufferIndex`].
+	///
+	/// Lets external renderers import every buffer up front. The targets stay valid until
+	/// the swapchain is recreated, which [`RenderEvent::resized`] and
+	/// [`Application::on_swapchain_recreated`] report; only the buffer of the frame being
+	/// rendered may be written.
+	pub fn swapchain_targets(&self, monitor_id: &str) -> Option<[RenderTarget<'_>; 2]> {
+		let swapchain = &self.monitors.get(monitor_id)?.swapchain;
+		Some(
+			[BufferIndex::Zero, BufferIndex::One]
+				.map(|index| RenderTarget::new(swapchain.buffer(index), index)),
+		)
+	}
+
+	/// Returns the render target behind an acquired frame.
+	pub fn render_target(&self, frame: &AcquiredFrame) -> Result<RenderTarget<'_>, FrameworkError> {
+		let monitor_rt = self.acquired_monitor(frame)?;
+		let buffer = &monitor_rt.swapchain.buffers[frame.buffer_index as usize];
+		Ok(RenderTarget::new(buffer, frame.buffer_index))
+	}
+
+	/// Sends an acquired frame to the server for presentation.
+	///
+	/// `acquire_fence` signals when rendering into the buffer has finished. On
+	/// failure the buffer returns to the swapchain.
+	pub fn submit_frame(
+		&mut self,
+		frame: AcquiredFrame,
+		acquire_fence: Option<OwnedFd>,
+	) -> Result<(), FrameworkError> {
+		let _frame = tracing::debug_span!(
+			target: "tab_app_framework.core",
+			"frame",
+			monitor_id = %frame.monitor_id,
+			buffer = frame.buffer_index as u8,
+			frame_id = frame.frame_id
+		)
+		.entered();
+		self.acquired_monitor(&frame)?;
+		let monitor_rt = self
+			.monitors
+			.get_mut(&frame.monitor_id)
+			.expect("monitor checked above");
+		monitor_rt.acquired = None;
+		let fence = acquire_fence.as_ref().map(|fd| fd.as_raw_fd());
+		let buffer = frame.buffer_index;
+		match self
+			.client
+			.send_buffer_request(&frame.monitor_id, buffer, fence)
+		{
+			Ok(()) => {
+				monitor_rt.swapchain.mark_busy(buffer);
+				monitor_rt.swapchain.mark_submitted(buffer);
+				monitor_rt.pending_present[buffer as usize] = true;
+				monitor_rt.frame_tags[buffer as usize] = FrameTag {
+					frame_id: frame.frame_id,
+					user_data: frame.user_data,
+				};
+				monitor_rt.start_request(buffer);
+				Ok(())
+			}
+			Err(err) => {
+				monitor_rt.swapchain.rollback();
+				monitor_rt.swapchain.invalidate_contents(buffer);
+				Err(err.into())
+			}
+		}
+	}
+
+	/// Returns an acquired frame to the swapchain without presenting it.
+	pub fn discard_frame(&mut self, frame: AcquiredFrame) {
+		if let Some(monitor_rt) = self.monitors.get_mut(&frame.monitor_id)
+			&& monitor_rt.acquired == Some(frame.buffer_index)
+		{
+			monitor_rt.acquired = None;
+			monitor_rt.swapchain.rollback();
+			if std::mem::take(&mut monitor_rt.redraw_after_ack) {
+				self.scheduled.insert(frame.monitor_id);
+			}
+		}
+	}
+
+	/// Monitor runtime still holding `frame`; fails once its swapchain was replaced.
+	fn acquired_monitor(&self, frame: &AcquiredFrame) -> Result<&MonitorRuntime, FrameworkError> {
+		let monitor_rt = self
+			.monitors
+			.get(&frame.monitor_id)
+			.ok_or_else(|| FrameworkError::MonitorNotFound(frame.monitor_id.clone()))?;
+		if monitor_rt.acquired != Some(frame.buffer_index) {
+			return Err(FrameworkError::FrameUnavailable(frame.monitor_id.clone()));
+		}
+		Ok(monitor_rt)
+	}
+
+	/// Returns current authenticated session information.
+	pub fn session(&self) -> &SessionInfo {
+		self.client.session()
+	}
+
+	/// Returns the round trip of the last answered keepalive ping; `None` before the first
+	/// answer or with pings disabled through [`Config::set_ping_interval`].
+	pub fn server_latency(&self) -> Option<Duration> {
+		self.client.server_latency()
+	}
+
+	/// Returns true while this session is the active one.
+	pub fn has_focus(&self) -> bool {
+		self.focus.focused
+	}
+
+	/// Returns the system-wide accessibility settings; defaults until an admin session
+	/// sets them.
+	pub fn accessibility(&self) -> &AccessibilitySettings {
+		self.accessibility
+	}
+
+	/// Changes the system-wide accessibility settings.
+	///
+	/// Admin sessions only. Every session, this one included, sees the new settings
+	/// through [`Application::on_accessibility_changed`]; a rejection reaches
+	/// [`Application::on_error`] as [`FrameworkError::AdminRequestFailed`].
+	pub fn set_accessibility(
+		&mut self,
+		settings: AccessibilitySettings,
+	) -> Result<(), FrameworkError> {
+		if self.client.session().role != SessionRole::Admin {
+			return Err(FrameworkError::Forbidden(
+				"accessibility settings require an admin session".into(),
+			));
+		}
+		if !settings.is_valid() {
+			return Err(FrameworkError::Config(format!(
+				"text scale {} must be finite and positive",
+				settings.text_scale
+			)));
+		}
+		let request_id = self.session_requests.admin("set_accessibility");
+		self
+			.client
+			.set_accessibility(settings, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Routes key events to this session while another session stays active and visible.
+	///
+	/// Admin sessions only, for overlays that intercept keys temporarily; pointer and touch
+	/// input keep following the active session. Only one session holds the grab; when
+	/// another one does, [`FrameworkError::KeyboardGrabUnavailable`] is reported through
+	/// [`Application::on_error`].
+	pub fn request_keyboard_grab(&mut self) -> Result<(), FrameworkError> {
+		if self.client.session().role != SessionRole::Admin {
+			return Err(FrameworkError::Forbidden(
+				"keyboard grabs require an admin session".into(),
+			));
+		}
+		if self.focus.keyboard_grab {
+			return Ok(());
+		}
+		self.client.set_keyboard_grab(true)?;
+		self.focus.keyboard_grab = true;
+		Ok(())
+	}
+
+	/// Ends a grab started with [`Context::request_keyboard_grab`].
+	pub fn release_keyboard_grab(&mut self) -> Result<(), FrameworkError> {
+		if !self.focus.keyboard_grab {
+			return Ok(());
+		}
+		self.focus.keyboard_grab = false;
+		self
+			.client
+			.set_keyboard_grab(false)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Returns true between [`Context::request_keyboard_grab`] and its release, unless the
+	/// server refused the grab.
+	pub fn has_keyboard_grab(&self) -> bool {
+		self.focus.keyboard_grab
+	}
+
+	/// Sends `session_ready` for the current session.
+	///
+	/// [`Config::auto_ready`] does not send it again afterwards.
+	pub fn session_ready(&mut self) -> Result<(), FrameworkError> {
+		self.client.send_ready()?;
+		*self.ready_sent = true;
+		Ok(())
+	}
+
+	/// Backward-compatible alias for [`Context::session_ready`].
+	pub fn send_ready(&mut self) -> Result<(), FrameworkError> {
+		self.session_ready()
+	}
+
+	/// Renames this session, e.g. after a terminal title change, for admin switcher UIs.
+	///
+	/// The server confirms through [`Application::on_session_state`], after which
+	/// [`Context::session`] carries the new name.
+	pub fn set_session_display_name(
+		&mut self,
+		name: impl Into<String>,
+	) -> Result<(), FrameworkError> {
+		let name = name.into();
+		if name.trim().is_empty() {
+			return Err(FrameworkError::Config(
+				"session display name must not be empty".into(),
+			));
+		}
+		self
+			.client
+			.update_session(Some(name), BTreeMap::new())
+			.map_err(FrameworkError::from)
+	}
+
+	/// Sets one of this session's metadata entries, or removes it with `None`.
+	///
+	/// Metadata is free-form and shown to admin sessions alongside the display name;
+	/// see [`Context::set_session_display_name`].
+	pub fn set_session_metadata(
+		&mut self,
+		key: impl Into<String>,
+		value: Option<String>,
+	) -> Result<(), FrameworkError> {
+		self
+			.client
+			.update_session(None, BTreeMap::from([(key.into(), value)]))
+			.map_err(FrameworkError::from)
+	}
+
+	/// Requests creation of a new session and waits for server response.
+	///
+	/// Blocks the loop for up to 500 ms; prefer [`Context::create_session_async`] from
+	/// render and input callbacks.
+	pub fn create_session(
+		&mut self,
+		role: SessionRole,
+		display_name: Option<String>,
+	) -> Result<SessionCreatedPayload, FrameworkError> {
+		self.client
+			.create_session(role, display_name)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Requests switching to another session.
+	pub fn switch_session(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+	) -> Result<(), FrameworkError> {
+		self.client
+			.switch_session(session_id, animation, duration)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Requests creation of a new session without waiting for the server.
+	///
+	/// The answer arrives through [`Application::on_session_create_result`] with the
+	/// returned id.
+	pub fn create_session_async(
+		&mut self,
+		role: SessionRole,
+		display_name: Option<String>,
+	) -> Result<RequestId, FrameworkError> {
+		let request_id = self.session_requests.next_id();
+		self
+			.client
+			.request_session_create(role, display_name, request_id.0)?;
+		self.session_requests.creates.insert(request_id);
+		Ok(request_id)
+	}
+
+	/// Requests switching to another session and reports the outcome.
+	///
+	/// [`Application::on_session_switch_result`] fires with the returned id once the
+	/// session became active, or with the server's error when it refused the switch.
+	pub fn switch_session_async(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+	) -> Result<RequestId, FrameworkError> {
+		let request_id = self.session_requests.next_id();
+		self
+			.client
+			.request_session_switch(session_id, animation, duration, request_id.0)?;
+		self
+			.session_requests
+			.switches
+			.insert(request_id, session_id.to_string());
+		Ok(request_id)
+	}
+
+	/// Asks for a snapshot of another session's latest frame, scaled down to fit
+	/// `max_size`, e.g. for an alt-tab switcher.
+	///
+	/// Admin sessions only. The snapshot arrives through
+	/// [`Application::on_session_preview`]; request again for periodic thumbnails.
+	pub fn request_session_preview(
+		&mut self,
+		session_id: &str,
+		max_size: (i32, i32),
+	) -> Result<(), FrameworkError> {
+		if self.client.session().role != SessionRole::Admin {
+			return Err(FrameworkError::Forbidden(
+				"session previews require an admin session".into(),
+			));
+		}
+		let (max_width, max_height) = max_size;
+		if max_width <= 0 || max_height <= 0 {
+			return Err(FrameworkError::Config(format!(
+				"preview size {max_width}x{max_height} must be positive"
+			)));
+		}
+		self
+			.client
+			.request_session_preview(session_id, max_width, max_height)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Sends `data` to another session, e.g. a launcher asking an editor to open a file.
+	///
+	/// Admin sessions may message any session; other sessions may message admin sessions
+	/// and reply to messages they received. The target receives the data through
+	/// [`Application::on_session_message`]. Returns the message's correlation id, which
+	/// replies carry as [`SessionMessageEvent::reply_to`]. Messages the server refuses are
+	/// reported as [`FrameworkError::SessionMessageUndeliverable`] through
+	/// [`Application::on_error`].
+	pub fn send_to_session(&mut self, session_id: &str, data: &[u8]) -> Result<u64, FrameworkError> {
+		self.send_session_message(session_id, data, None)
+	}
+
+	/// Answers a message received through [`Application::on_session_message`].
+	///
+	/// Fails when the sender did not ask for replies.
+	pub fn reply_to_session(
+		&mut self,
+		message: &SessionMessageEvent,
+		data: &[u8],
+	) -> Result<u64, FrameworkError> {
+		let Some(reply_to) = message.correlation_id else {
+			return Err(FrameworkError::Config(format!(
+				"message from session {} accepts no reply",
+				message.from_session_id
+			)));
+		};
+		self.send_session_message(&message.from_session_id, data, Some(reply_to))
+	}
+
+	fn send_session_message(
+		&mut self,
+		session_id: &str,
+		data: &[u8],
+		reply_to: Option<u64>,
+	) -> Result<u64, FrameworkError> {
+		if data.len() > SessionMessageSendPayload::MAX_DATA_LEN {
+			return Err(FrameworkError::Config(format!(
+				"session messages carry at most {} bytes",
+				SessionMessageSendPayload::MAX_DATA_LEN
+			)));
+		}
+		*self.next_correlation_id += 1;
+		let correlation_id = *self.next_correlation_id;
+		self
+			.client
+			.send_session_message(session_id, data.to_vec(), Some(correlation_id), reply_to)?;
+		Ok(correlation_id)
+	}
+
+	/// Reserves a key combination, given as evdev key codes, whichever session has focus.
+	///
+	/// Admin sessions only. The shortcut fires [`Application::on_global_shortcut`] when its
+	/// last key is pressed while exactly its keys are held; left and right modifiers are
+	/// distinct keys. When another registration already holds the keys, the shortcut is
+	/// dropped and [`FrameworkError::ShortcutConflict`] is reported through
+	/// [`Application::on_error`]; other rejections arrive there as
+	/// [`FrameworkError::AdminRequestFailed`].
+	pub fn register_global_shortcut(
+		&mut self,
+		keys: &[u32],
+	) -> Result<GlobalShortcutId, FrameworkError> {
+		if self.client.session().role != SessionRole::Admin {
+			return Err(FrameworkError::Forbidden(
+				"global shortcuts require an admin session".into(),
+			));
+		}
+		let distinct = keys.iter().collect::<HashSet<_>>().len() == keys.len();
+		if keys.is_empty() || !distinct || keys.len() > GlobalShortcutRegisterPayload::MAX_KEYS {
+			return Err(FrameworkError::Config(format!(
+				"global shortcuts need 1 to {} distinct keys",
+				GlobalShortcutRegisterPayload::MAX_KEYS
+			)));
+		}
+		let id = self.global_shortcuts.next_id();
+		let request_id = self.session_requests.admin("register_global_shortcut");
+		self
+			.client
+			.register_global_shortcut(&id.to_string(), keys.to_vec(), request_id)?;
+		self.global_shortcuts.registered.insert(id);
+		Ok(id)
+	}
+
+	/// Releases a shortcut registered with [`Context::register_global_shortcut`].
+	pub fn unregister_global_shortcut(
+		&mut self,
+		shortcut_id: GlobalShortcutId,
+	) -> Result<(), FrameworkError> {
+		if !self.global_shortcuts.registered.remove(&shortcut_id) {
+			return Err(FrameworkError::Config(format!(
+				"unknown global shortcut {shortcut_id}"
+			)));
+		}
+		let request_id = self.session_requests.admin("unregister_global_shortcut");
+		self
+			.client
+			.unregister_global_shortcut(&shortcut_id.to_string(), request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Scales a monitor's output brightness; `1.0` is unmodified and `0.0` black.
+	///
+	/// Only admin sessions may change output color; the server enforces this too. Shift
+	/// dims the composited image like [`Context::set_gamma_lut`] does, leaving the
+	/// backlight alone. A rejection reaches [`Application::on_error`] as
+	/// [`FrameworkError::AdminRequestFailed`].
+	pub fn set_monitor_brightness(
+		&mut self,
+		monitor_id: &str,
+		brightness: f32,
+	) -> Result<(), FrameworkError> {
+		self.check_output_control(monitor_id)?;
+		if !(0.0..=1.0).contains(&brightness) {
+			return Err(FrameworkError::Config(format!(
+				"brightness {brightness} is outside 0..=1"
+			)));
+		}
+		let request_id = self.session_requests.admin("set_monitor_brightness");
+		self
+			.client
+			.set_monitor_brightness(monitor_id, brightness, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Switches a monitor's display on, to standby or off through DPMS.
+	///
+	/// Admin sessions only. Every session sees the new state through
+	/// [`Application::on_monitor_power_changed`] once the display switched; a rejection
+	/// reaches [`Application::on_error`] as [`FrameworkError::AdminRequestFailed`].
+	pub fn set_monitor_power(
+		&mut self,
+		monitor_id: &str,
+		power: MonitorPower,
+	) -> Result<(), FrameworkError> {
+		self.check_output_control(monitor_id)?;
+		let request_id = self.session_requests.admin("set_monitor_power");
+		self
+			.client
+			.set_monitor_power(monitor_id, power, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Replaces a monitor's gamma ramps; `None` restores the identity ramp.
+	///
+	/// Admin sessions only. All channels must have the same length, between 1 and
+	/// [`GammaRamps::MAX_LEN`] entries. Shift applies them as an 8-bit lookup table while
+	/// compositing, so the 16-bit ramps are quantized and the CRTC gamma stays untouched.
+	/// A rejection reaches [`Application::on_error`] as [`FrameworkError::AdminRequestFailed`].
+	pub fn set_gamma_lut(
+		&mut self,
+		monitor_id: &str,
+		ramps: Option<GammaRamps>,
+	) -> Result<(), FrameworkError> {
+		self.check_output_control(monitor_id)?;
+		if ramps.as_ref().is_some_and(|ramps| !ramps.is_valid()) {
+			return Err(FrameworkError::Config(
+				"gamma ramps must share a length between 1 and GammaRamps::MAX_LEN".into(),
+			));
+		}
+		let request_id = self.session_requests.admin("set_gamma_lut");
+		self
+			.client
+			.set_gamma_lut(monitor_id, ramps, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Declares the parts of a monitor this session draws chrome over, in buffer pixels.
+	///
+	/// Admin sessions only. Every session, this one included, sees the change through
+	/// [`Application::on_safe_area_changed`]; an empty list clears the monitor. A rejection
+	/// reaches [`Application::on_error`] as [`FrameworkError::AdminRequestFailed`].
+	pub fn set_occluded_areas(
+		&mut self,
+		monitor_id: &str,
+		occluded: Vec<Rect>,
+	) -> Result<(), FrameworkError> {
+		self.check_output_control(monitor_id)?;
+		if occluded
+			.iter()
+			.any(|rect| rect.width <= 0 || rect.height <= 0)
+		{
+			return Err(FrameworkError::Config(
+				"occluded rectangles must not be empty".into(),
+			));
+		}
+		let request_id = self.session_requests.admin("set_occluded_areas");
+		self
+			.client
+			.set_safe_area(monitor_id, occluded, request_id)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Creates an overlay composited by shift above this session's frames on a monitor.
+	///
+	/// The overlay has its own `size` swapchain with an alpha channel, updated independently of
+	/// the monitor with [`Context::acquire_overlay`] and [`Context::submit_overlay`]. It starts
+	/// at the monitor's top-left corner; higher `z` overlays are drawn on top. Overlays are
+	/// dropped together with their monitor.
+	pub fn create_overlay(
+		&mut self,
+		monitor_id: &str,
+		size: (i32, i32),
+		z: i32,
+	) -> Result<OverlayId, FrameworkError> {
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(FrameworkError::MonitorNotFound(monitor_id.to_string()));
+		}
+		let id = self.overlays.next_id();
+		let swapchain = self
+			.client
+			.create_overlay(&id.to_string(), monitor_id, size, (0, 0), z)?;
+		self.overlays.insert(id, monitor_id.to_string(), swapchain);
+		Ok(id)
+	}
+
+	/// Moves an overlay, relative to its monitor's top-left corner.
+	pub fn set_overlay_position(
+		&mut self,
+		overlay_id: OverlayId,
+		position: (i32, i32),
+	) -> Result<(), FrameworkError> {
+		self.overlays.get(overlay_id)?;
+		self
+			.client
+			.move_overlay(&overlay_id.to_string(), position)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Removes an overlay from the screen and frees its swapchain.
+	pub fn destroy_overlay(&mut self, overlay_id: OverlayId) -> Result<(), FrameworkError> {
+		self
+			.overlays
+			.remove(overlay_id)
+			.ok_or(FrameworkError::OverlayNotFound(overlay_id))?;
+		self
+			.client
+			.destroy_overlay(&overlay_id.to_string())
+			.map_err(FrameworkError::from)
+	}
+
+	/// Acquires a free buffer of an overlay for rendering.
+	pub fn acquire_overlay(&mut self, overlay_id: OverlayId) -> Result<OverlayFrame, FrameworkError> {
+		let overlay = self.overlays.get_mut(overlay_id)?;
+		let unavailable = || FrameworkError::FrameUnavailable(overlay_id.to_string());
+		if overlay.acquired.is_some() {
+			return Err(unavailable());
+		}
+		let (_, buffer_index) = overlay.swapchain.acquire_next().ok_or_else(unavailable)?;
+		overlay.acquired = Some(buffer_index);
+		Ok(OverlayFrame {
+			overlay_id,
+			buffer_index,
+			buffer_age: overlay.swapchain.buffer_age(buffer_index),
+		})
+	}
+
+	/// Returns the render target behind an acquired overlay frame.
+	pub fn overlay_target(&self, frame: &OverlayFrame) -> Result<RenderTarget<'_>, FrameworkError> {
+		let overlay = self.overlays.get(frame.overlay_id)?;
+		if overlay.acquired != Some(frame.buffer_index) {
+			return Err(FrameworkError::FrameUnavailable(
+				frame.overlay_id.to_string(),
+			));
+		}
+		let buffer = &overlay.swapchain.buffers[frame.buffer_index as usize];
+		Ok(RenderTarget::new(buffer, frame.buffer_index))
+	}
+
+	/// Shows an acquired overlay frame once `acquire_fence` signals.
+	///
+	/// The previously shown buffer returns to the swapchain when shift releases it. On
+	/// failure the buffer returns to the swapchain right away.
+	pub fn submit_overlay(
+		&mut self,
+		frame: OverlayFrame,
+		acquire_fence: Option<OwnedFd>,
+	) -> Result<(), FrameworkError> {
+		let overlay = self.overlays.get_mut(frame.overlay_id)?;
+		if overlay.acquired != Some(frame.buffer_index) {
+			return Err(FrameworkError::FrameUnavailable(
+				frame.overlay_id.to_string(),
+			));
+		}
+		overlay.acquired = None;
+		let fence = acquire_fence.as_ref().map(|fd| fd.as_raw_fd());
+		let buffer = frame.buffer_index;
+		match self
+			.client
+			.present_overlay(&frame.overlay_id.to_string(), buffer, fence)
+		{
+			Ok(()) => {
+				overlay.swapchain.mark_busy(buffer);
+				overlay.swapchain.mark_submitted(buffer);
+				Ok(())
+			}
+			Err(err) => {
+				overlay.swapchain.rollback();
+				overlay.swapchain.invalidate_contents(buffer);
+				Err(err.into())
+			}
+		}
+	}
+
+	/// Returns an acquired overlay frame to its swapchain without showing it.
+	pub fn discard_overlay(&mut self, frame: OverlayFrame) {
+		if let Ok(overlay) = self.overlays.get_mut(frame.overlay_id)
+			&& overlay.acquired == Some(frame.buffer_index)
+		{
+			overlay.acquired = None;
+			overlay.swapchain.rollback();
+		}
+	}
+
+	/// Starts copying `source` into a capture swapchain of `size`, e.g. for screen recording.
+	///
+	/// Admin sessions only. Every new source frame is scaled into a free buffer and delivered
+	/// through [`Application::on_capture_frame`]; frames arriving while both buffers are held
+	/// by the application are dropped.
+	pub fn start_capture(
+		&mut self,
+		source: CaptureSource,
+		size: (i32, i32),
+	) -> Result<CaptureId, FrameworkError> {
+		if self.client.session().role != SessionRole::Admin {
+			return Err(FrameworkError::Forbidden(
+				"captures require an admin session".into(),
+			));
+		}
+		let (CaptureSource::Monitor { monitor_id } | CaptureSource::Session { monitor_id, .. }) =
+			&source;
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(FrameworkError::MonitorNotFound(monitor_id.clone()));
+		}
+		let id = self.captures.next_id();
+		let swapchain = self.client.start_capture(&id.to_string(), source, size)?;
+		self.captures.insert(id, swapchain);
+		Ok(id)
+	}
+
+	/// Returns the buffer holding a captured frame, until it is released.
+	pub fn capture_target(
+		&self,
+		frame: &CaptureFrameEvent,
+	) -> Result<RenderTarget<'_>, FrameworkError> {
+		let capture = self.captures.get(frame.capture_id)?;
+		if !capture.held[frame.buffer as usize] {
+			return Err(FrameworkError::FrameUnavailable(
+				frame.capture_id.to_string(),
+			));
+		}
+		let buffer = &capture.swapchain.buffers[frame.buffer as usize];
+		Ok(RenderTarget::new(buffer, frame.buffer))
+	}
+
+	/// Hands a captured frame's buffer back to shift for following frames.
+	pub fn release_capture_frame(&mut self, frame: &CaptureFrameEvent) -> Result<(), FrameworkError> {
+		let capture = self.captures.get_mut(frame.capture_id)?;
+		if !std::mem::take(&mut capture.held[frame.buffer as usize]) {
+			return Ok(());
+		}
+		self
+			.client
+			.release_capture_frame(&frame.capture_id.to_string(), frame.buffer)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Stops a capture and frees its swapchain.
+	pub fn stop_capture(&mut self, capture_id: CaptureId) -> Result<(), FrameworkError> {
+		self
+			.captures
+			.remove(capture_id)
+			.ok_or(FrameworkError::CaptureNotFound(capture_id))?;
+		self
+			.client
+			.stop_capture(&capture_id.to_string())
+			.map_err(FrameworkError::from)
+	}
+
+	/// Marks frames rendered to a monitor as fullscreen and opaque.
+	///
+	/// While set, the server may scan the buffers out directly instead of compositing them.
+	/// It falls back to composition whenever that is not possible, for example during session
+	/// transitions or with a brightness or gamma adjustment active.
+	pub fn set_scanout_candidate(
+		&mut self,
+		monitor_id: &str,
+		fullscreen_opaque: bool,
+	) -> Result<(), FrameworkError> {
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(FrameworkError::MonitorNotFound(monitor_id.to_string()));
+		}
+		self
+			.client
+			.set_scanout_hint(monitor_id, fullscreen_opaque)
+			.map_err(FrameworkError::from)
+	}
+
+	/// Tags the color space of this session's frames on a monitor.
+	///
+	/// The server converts tagged frames while compositing. Frames are sRGB until tagged;
+	/// see [`Monitor::color`] for what the monitor itself supports.
+	pub fn set_surface_colorspace(
+		&mut self,
+		monitor_id: &str,
+		colorspace: Colorspace,
+	) -> Result<(), FrameworkError> {
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(FrameworkError::MonitorNotFound(monitor_id.to_string()));
+		}
+		self
+			.client
+			.set_surface_colorspace(monitor_id, colorspace)
+			.map_err(FrameworkError::from)
+	}
+
+	fn check_output_control(&self, monitor_id: &str) -> Result<(), FrameworkError> {
+		if self.client.session().role != SessionRole::Admin {
+			return Err(FrameworkError::Forbidden(
+				"output configuration requires an admin session".into(),
+			));
+		}
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(FrameworkError::MonitorNotFound(monitor_id.to_string()));
+		}
+		Ok(())
+	}
+
+	/// Decodes server messages whose header starts with `header_prefix` and delivers them
+	/// to [`Application::on_extension_event`].
+	///
+	/// See [`TabClient::register_extension`]. The mock and nested backends never receive
+	/// such messages, so this does nothing there.
+	pub fn register_extension<T, F>(&mut self, header_prefix: impl Into<String>, decoder: F)
+	where
+		T: std::any::Any,
+		F: Fn(&TabMessageFrame) -> Result<T, ProtocolError> + 'static,
+	{
+		self.client.register_extension(header_prefix, decoder);
+	}
+
+	/// Returns direct mutable access to the underlying tab client.
+	///
+	/// Prefer high-level methods when possible.
+	///
+	/// Fails when running on the in-memory test or nested backend, which have no client.
+	pub fn raw_client(&mut self) -> Result<&mut TabClient, FrameworkError> {
+		match self.client {
+			Connection::Tab(client) => Ok(client),
+			#[cfg(feature = "testing")]
+			Connection::Mock(_) => Err(FrameworkError::Client(TabClientError::Unexpected(
+				"raw_client is unavailable on the mock backend",
+			))),
+			#[cfg(feature = "nested")]
+			Connection::Nested(_) => Err(FrameworkError::Client(TabClientError::Unexpected(
+				"raw_client is unavailable on the nested backend",
+			))),
+		}
+	}
+}
+
+/// Main application runtime.
+pub struct TabAppFramework<A: Application> {
+	app: A,
+	client: Connection,
+	render_mode: RenderMode,
+	monitors: HashMap<String, MonitorRuntime>,
+	scheduled: HashSet<String>,
+	watched_fds: HashSet<RawFd>,
+	event_queue: Rc<RefCell<VecDeque<QueuedEvent>>>,
+	exiting: bool,
+	next_acquire_fence: Option<OwnedFd>,
+	frame_cancelled: bool,
+	/// Set through [`Context::set_frame_user_data`] for the frame being rendered.
+	frame_user_data: Option<u64>,
+	next_frame_id: u64,
+	overlays: Overlays,
+	captures: Captures,
+	explicit_submit: bool,
+	parallel_render: bool,
+	/// Policies from [`Config::set_clear_policy`], applied to monitors as they appear.
+	clear_policies: HashMap<String, ClearPolicy>,
+	/// Per-monitor and default [`RetryPolicy`] from the config.
+	retry_policies: HashMap<String, RetryPolicy>,
+	default_retry_policy: RetryPolicy,
+	/// Ack timeout and watchdog threshold from the config.
+	ack_timeout: Duration,
+	watchdog_threshold: u32,
+	starvation_threshold: Option<Duration>,
+	errors: ErrorDedup,
+	stats: LoopStats,
+	tracer: FrameTracer,
+	idle: IdleTracker,
+	idle_inhibitors: Rc<RefCell<IdleInhibitRegistry>>,
+	global_shortcuts: GlobalShortcuts,
+	next_correlation_id: u64,
+	session_requests: SessionRequests,
+	focus: FocusState,
+	accessibility: AccessibilitySettings,
+	/// Configuration in effect, including live changes from [`Context::update_config`].
+	config: Config,
+	/// Fields changed since the last [`Event::ConfigChanged`].
+	config_changes: Vec<ConfigField>,
+	/// Monitors whose swapchain [`Context::recreate_swapchain`] replaced, not yet reported.
+	recreated_swapchains: Vec<String>,
+	/// Whether `session_ready` was sent, by the application or for [`Config::auto_ready`].
+	ready_sent: bool,
+	batch_events: bool,
+	event_batch: Vec<Event>,
+	input_filters: InputFilters,
+	text_composer: TextComposer,
+	coalesce_pointer_motion: bool,
+	edge_behavior: EdgeBehavior,
+	wrap_mode: WrapMode,
+	/// Pressure pushed against the edge the cursor rests on.
+	edge_state: EdgeState,
+	// Pointer and touch state of `active_seat`; other seats are parked in
+	// `idle_seats` until their next input event.
+	cursor_position: (f64, f64),
+	placements: Vec<MonitorPlacement>,
+	layout_dirty: bool,
+	/// Layout as of the last [`Event::LayoutChanged`].
+	reported_layout: Vec<MonitorPlacement>,
+	touch_contacts: HashMap<i32, (f64, f64)>,
+	primary_touch_id: Option<i32>,
+	active_seat: SeatId,
+	idle_seats: HashMap<SeatId, SeatState>,
+	input_devices: HashMap<u32, InputDeviceInfo>,
+	control: Option<ControlReceiver>,
+	/// Panic hook from [`Config::set_crash_reports`], disarmed on drop.
+	crash_hook: Option<CrashHook>,
+}
+
+/// Pointer and touch state of a seat that is not currently active.
+struct SeatState {
+	cursor_position: (f64, f64),
+	touch_contacts: HashMap<i32, (f64, f64)>,
+	primary_touch_id: Option<i32>,
+}
+
+impl<A: Application> TabAppFramework<A> {
+	/// Initializes the framework and application state.
+	///
+	/// The configuration starts from [`Config::from_env_full`]; `configure` runs afterwards
+	/// and overrides it.
+	///
+	/// With the `nested` feature and `SHIFT_NESTED` set, this opens a desktop window
+	/// instead, see [`TabAppFramework::init_nested`].
+	pub fn init(configure: impl FnOnce(&mut Config)) -> Result<Self, FrameworkError> {
+		#[cfg(feature = "nested")]
+		if std::env::var_os("SHIFT_NESTED").is_some() {
+			return Self::init_nested(configure);
+		}
+		let mut config = Config::from_env_full()?;
+		configure(&mut config);
+		Self::init_with_config(config)
+	}
+
+	/// Initializes the framework from an explicit configuration.
+	///
+	/// Unlike [`TabAppFramework::init`], no settings are read from the environment, so
+	/// several instances with different tokens or sockets can be created in one process
+	/// and driven together by a [`MultiFrameworkDriver`].
+	pub fn init_with_config(config: Config) -> Result<Self, FrameworkError> {
+		let mut init_ctx = InitContext::<A>::new(config);
+		init_ctx.config_mut().detect_render_node();
+		let app = A::init(&mut init_ctx)
+			.map_err(|e| FrameworkError::Config(format!("app init failed: {e:#}")))?;
+
+		let cfg = init_ctx.config().clone();
+		let mut client_cfg = TabClientConfig::new(cfg.token())
+			.socket_path(cfg.socket_path.clone())
+			.linear_buffers(cfg.linear_buffers)
+			.shm_buffers(cfg.shm_buffers)
+			.swapchain_format(cfg.surface_format.swapchain_format())
+			.buffer_request_ack_timeout(cfg.buffer_request_ack_timeout)
+			.ping_interval(cfg.ping_interval);
+		if let Some(render_node) = cfg.render_node_path.clone() {
+			client_cfg = client_cfg.render_node(render_node);
+		}
+		let mut client = TabClient::connect(client_cfg)?;
+		client.set_event_queue(true);
+		let queue = Rc::new(RefCell::new(VecDeque::new()));
+		let initial_monitors: Vec<_> = client.monitors().cloned().collect();
+		let input_devices = client
+			.input_devices()
+			.map(|info| (info.id, info.clone()))
+			.collect();
+		let crash_reporter = cfg
+			.crash_reports
+			.then(|| client.crash_reporter())
+			.transpose()?;
+		let mut framework =
+			Self::with_connection(app, &cfg, Connection::Tab(client), queue, &initial_monitors)?;
+		framework.input_devices = input_devices;
+		framework.crash_hook =
+			crash_reporter.map(|reporter| CrashHook::install(reporter, cfg.app_version.clone()));
+		Ok(framework)
+	}
+
+	fn with_connection(
+		app: A,
+		cfg: &Config,
+		mut client: Connection,
+		queue: Rc<RefCell<VecDeque<QueuedEvent>>>,
+		initial_monitors: &[tab_client::MonitorState],
+	) -> Result<Self, FrameworkError> {
+		let mut monitors = HashMap::new();
+		for tab_monitor in initial_monitors {
+			let monitor = Monitor::from_tab_monitor(tab_monitor);
+			let swapchain = client.create_swapchain(&monitor.id)?;
+			let clear_policy = cfg.clear_policy(&monitor.id);
+			monitors.insert(
+				monitor.id.clone(),
+				MonitorRuntime::new(monitor, swapchain, clear_policy),
+			);
+		}
+			recompute_layout(&mut monitors);
+			let reported_layout = current_layout(&monitors);
+			let initial_cursor = initial_cursor(&reported_layout);
+			let scheduled = if cfg.render_mode == RenderMode::Eager {
+				monitors.keys().cloned().collect()
+			} else {
+				HashSet::new()
+			};
+
+		Ok(Self {
+			app,
+			client,
+			render_mode: cfg.render_mode,
+			monitors,
+			scheduled,
+			watched_fds: HashSet::new(),
+				event_queue: queue,
+				exiting: false,
+				next_acquire_fence: None,
+				frame_cancelled: false,
+				frame_user_data: None,
+				next_frame_id: 0,
+				overlays: Overlays::default(),
+				captures: Captures::default(),
+				explicit_submit: cfg.explicit_submit,
+				parallel_render: cfg.parallel_render,
+				clear_policies: cfg.clear_policies.clone(),
+				retry_policies: cfg.retry_policies.clone(),
+				default_retry_policy: cfg.default_retry_policy,
+				ack_timeout: cfg.buffer_request_ack_timeout,
+				watchdog_threshold: cfg.watchdog_threshold,
+				starvation_threshold: cfg.starvation_threshold,
+				errors: ErrorDedup::new(cfg.error_dedup_window),
+				stats: LoopStats::new(cfg.stats_interval),
+				tracer: FrameTracer::new(cfg.frame_tracing),
+				idle: IdleTracker::new(),
+				idle_inhibitors: Rc::default(),
+				global_shortcuts: GlobalShortcuts::default(),
+				next_correlation_id: 0,
+				session_requests: SessionRequests::default(),
+				config: cfg.clone(),
+				config_changes: Vec::new(),
+				recreated_swapchains: Vec::new(),
+				ready_sent: false,
+				focus: FocusState::default(),
+				accessibility: AccessibilitySettings::default(),
+				batch_events: cfg.batch_events,
+				event_batch: Vec::new(),
+				input_filters: InputFilters::default(),
+				text_composer: TextComposer::new(cfg.text_composition)?,
+				coalesce_pointer_motion: cfg.coalesce_pointer_motion,
+				edge_behavior: cfg.edge_behavior,
+				wrap_mode: cfg.wrap_mode,
+				edge_state: EdgeState::default(),
+				cursor_position: initial_cursor,
+				placements: Vec::new(),
+				layout_dirty: true,
+				reported_layout,
+				touch_contacts: HashMap::new(),
+				primary_touch_id: None,
+				active_seat: SeatId::default(),
+				idle_seats: HashMap::new(),
+				input_devices: HashMap::new(),
+				control: None,
+				crash_hook: None,
+			})
+		}
+
+	/// Runs the main event/render loop until exit is requested.
+	pub fn run(&mut self) -> Result<(), FrameworkError> {
+		while !self.exiting {
+			self.iterate(self.poll_timeout_ms())?;
+		}
+		Ok(())
+	}
+
+	/// Returns the application instance.
+	pub fn app(&self) -> &A {
+		&self.app
+	}
+
+	/// Returns the application instance mutably.
+	pub fn app_mut(&mut self) -> &mut A {
+		&mut self.app
+	}
+
+	fn poll_timeout_ms(&self) -> i32 {
+		let has_queued_events =
+			!self.event_queue.borrow().is_empty() || self.client.queued_events() > 0;
+		if !self.scheduled.is_empty() || has_queued_events {
+			return 0;
+		}
+		let idle_timeout = self.idle.poll_timeout_ms();
+		let Some(wake_at) = self
+			.monitors
+			.values()
+			.flat_map(|m| {
+				let ack_deadline = m.inflight_since.map(|since| since + self.ack_timeout);
+				let starvation_deadline = m
+					.starved_since
+					.filter(|_| !m.starvation_reported)
+					.zip(self.starvation_threshold)
+					.map(|(since, threshold)| since + threshold);
+				[
+					m.retry.retry_at,
+					ack_deadline,
+					m.redraw_at,
+					starvation_deadline,
+				]
+			})
+			.flatten()
+			.chain(self.errors.next_deadline())
+			.chain(self.client.keepalive_deadline())
+			.min()
+		else {
+			return idle_timeout;
+		};
+		let wake_in = wake_at.saturating_duration_since(Instant::now());
+		let wake_timeout = wake_in.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32;
+		if idle_timeout < 0 {
+			wake_timeout
+		} else {
+			idle_timeout.min(wake_timeout)
+		}
+	}
+
+	/// Runs one loop iteration, waiting at most `timeout_ms` (-1: indefinitely) for input.
+	fn iterate(&mut self, timeout_ms: i32) -> Result<(), FrameworkError> {
+		let _session = tracing::debug_span!(
+			target: "tab_app_framework.core",
+			"session",
+			session_id = %self.client.session().id
+		)
+		.entered();
+		let _frame = frame_span!(self.tracer, "frame");
+		if self.config.auto_ready == AutoReady::AfterInit {
+			self.send_auto_ready(None);
+		}
+		let (tab_ready, ready_fds) = {
+			let _span = frame_span!(self.tracer, "poll");
+			let started = Instant::now();
+			let polled = self.poll_once(timeout_ms)?;
+			self.tracer.current.poll += started.elapsed();
+			polled
+		};
+		// Under `QueueOverflow::BlockDispatch`, the socket is left unread while a backlog remains.
+		if tab_ready && self.client.queued_events() == 0 {
+			let _span = frame_span!(self.tracer, "dispatch");
+			let started = Instant::now();
+			self.client.dispatch_events()?;
+			self.tracer.current.dispatch += started.elapsed();
+			if self.client.close_requested() {
+				self.exiting = true;
+			}
+		}
+		self.client.poll_keepalive()?;
+		self.flush_pending_releases();
+		self.drain_control();
+		for fd in ready_fds {
+			let ev = FdReadyEvent { fd };
+			self.emit(Event::FdReady(ev));
+		}
+		{
+			let _span = frame_span!(self.tracer, "dispatch");
+			let started = Instant::now();
+			self.drain_tab_events()?;
+			self.tracer.current.dispatch += started.elapsed();
+		}
+		self.check_idle();
+		self.flush_pending_releases();
+		self.apply_config_changes();
+		self.report_recreated_swapchains();
+		self.report_layout_changes();
+		self.flush_event_batch();
+		self.sync_idle_inhibit()?;
+		self.check_ack_timeouts();
+		self.check_starvation();
+		self.flush_repeated_errors();
+		self.schedule_due_retries();
+		self.render_scheduled()?;
+		self.tracer.finish_frame();
+		self.report_stats();
+		Ok(())
+	}
+
+	fn pending_release_fds(&self) -> Vec<RawFd> {
+		let mut pending_release_fds = Vec::new();
+		for monitor in self.monitors.values() {
+			for fence in &monitor.pending_release_fences {
+				if let Some(fd) = fence {
+					pending_release_fds.push(std::os::fd::AsRawFd::as_raw_fd(fd));
+				}
+			}
+		}
+		pending_release_fds
+	}
+
+	fn poll_once(&self, timeout_ms: i32) -> Result<(bool, Vec<RawFd>), FrameworkError> {
+		let pending_release_fds = self.pending_release_fds();
+		let watched_count = self.watched_fds.len();
+		let mut pollfds = Vec::with_capacity(1 + watched_count + pending_release_fds.len());
+		pollfds.push(libc::pollfd {
+			fd: self.client.socket_fd(),
+			events: libc::POLLIN,
+			revents: 0,
+		});
+		for fd in &self.watched_fds {
+			pollfds.push(libc::pollfd {
+				fd: *fd,
+				events: libc::POLLIN,
+				revents: 0,
+			});
+		}
+		for fd in pending_release_fds {
+			pollfds.push(libc::pollfd {
+				fd,
+				events: libc::POLLIN | libc::POLLERR | libc::POLLHUP,
+				revents: 0,
+			});
+		}
+		if let Some(control) = &self.control {
+			pollfds.push(libc::pollfd {
+				fd: control.fd(),
+				events: libc::POLLIN,
+				revents: 0,
+			});
+		}
+		let rc = unsafe {
+			libc::poll(
+				pollfds.as_mut_ptr(),
+				pollfds.len() as libc::nfds_t,
+				timeout_ms,
+			)
+		};
+		if rc < 0 {
+			return Err(FrameworkError::Poll(std::io::Error::last_os_error()));
+		}
+		if rc == 0 {
+			return Ok((false, Vec::new()));
+		}
+		let tab_ready = (pollfds[0].revents & libc::POLLIN) != 0;
+		let mut ready_fds = Vec::new();
+		for pfd in pollfds.iter().skip(1).take(watched_count) {
+			if (pfd.revents & libc::POLLIN) != 0 {
+				ready_fds.push(pfd.fd);
+			}
+		}
+		Ok((tab_ready, ready_fds))
+	}
+
+	/// Moves the events the connection queued, in `dispatch_events` or while a callback
+	/// waited on the server, into the framework queue and applies
+	/// [`Config::set_event_queue_capacity`] to the result.
+	fn take_client_events(&mut self) -> Option<FrameworkError> {
+		let mut queue = self.event_queue.borrow_mut();
+		let Some((capacity, overflow)) = self.config.event_queue_capacity else {
+			self.client.take_events(&mut queue, usize::MAX);
+			return None;
+		};
+		let dropped = match overflow {
+			QueueOverflow::BlockDispatch => {
+				let room = capacity.saturating_sub(queue.len());
+				self.client.take_events(&mut queue, room);
+				if self.client.queued_events() == 0 {
+					return None;
+				}
+				0
+			}
+			QueueOverflow::DropOldestInput | QueueOverflow::CoalesceMotion => {
+				self.client.take_events(&mut queue, usize::MAX);
+				if queue.len() <= capacity {
+					return None;
+				}
+				if overflow == QueueOverflow::CoalesceMotion {
+					coalesce_pointer_motion(&mut queue);
+				}
+				let excess = queue.len() - capacity;
+				drop_oldest_input(&mut queue, excess)
+			}
+		};
+		Some(FrameworkError::EventQueueOverflow {
+			capacity,
+			overflow,
+			dropped,
+		})
+	}
+
+	fn drain_tab_events(&mut self) -> Result<(), FrameworkError> {
+		if let Some(overflow) = self.take_client_events() {
+			self.report_error(overflow);
+		}
+		self.input_filters.apply(&mut self.event_queue.borrow_mut());
+		if self.coalesce_pointer_motion {
+			coalesce_pointer_motion(&mut self.event_queue.borrow_mut());
+		}
+		loop {
+			let maybe_event = self.event_queue.borrow_mut().pop_front();
+			let Some(event) = maybe_event else {
+				break;
+			};
+			match event {
+				QueuedEvent::Monitor(ev) => match ev {
+					TabMonitorEvent::Added(state) => {
+						let monitor = Monitor::from_tab_monitor(&state);
+						let swapchain = self.client.create_swapchain(&monitor.id)?;
+						if self.render_mode == RenderMode::Eager {
+							self.scheduled.insert(monitor.id.clone());
+						}
+						let clear_policy = self
+							.clear_policies
+							.get(&monitor.id)
+							.copied()
+							.unwrap_or_default();
+						let mut monitor_rt = MonitorRuntime::new(monitor.clone(), swapchain, clear_policy);
+						monitor_rt.retry_policy = self
+							.retry_policies
+							.get(&monitor.id)
+							.copied()
+							.unwrap_or(self.default_retry_policy);
+						self.monitors.insert(monitor.id.clone(), monitor_rt);
+						recompute_layout(&mut self.monitors);
+						self.layout_dirty = true;
+						self.clamp_seats_to_layout();
+						let monitor = self
+							.monitors
+							.get(&state.info.id)
+							.map(|m| m.monitor.clone())
+							.unwrap_or(monitor);
+						self.emit(Event::MonitorAdded(MonitorAddedEvent { monitor }));
+					}
+					TabMonitorEvent::Changed(state) => {
+						let Some(monitor_rt) = self.monitors.get_mut(&state.info.id) else {
+							continue;
+						};
+						let previous = monitor_rt.monitor.clone();
+						monitor_rt.monitor.apply_tab_monitor(&state);
+						self.sync_swapchain_size(&state.info.id)?;
+						recompute_layout(&mut self.monitors);
+						self.layout_dirty = true;
+						self.clamp_seats_to_layout();
+						if self.render_mode == RenderMode::Eager {
+							self.scheduled.insert(state.info.id.clone());
+						}
+						let monitor = self.monitors[&state.info.id].monitor.clone();
+						self.emit(Event::MonitorChanged(MonitorChangedEvent {
+							monitor,
+							previous,
+						}));
+					}
+					TabMonitorEvent::SafeArea {
+						monitor_id,
+						occluded,
+					} => {
+						let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) else {
+							continue;
+						};
+						monitor_rt.occluded = occluded;
+						let safe_area = monitor_rt.safe_area();
+						self.emit(Event::SafeAreaChanged(SafeAreaChangedEvent {
+							monitor_id,
+							safe_area,
+						}));
+					}
+					TabMonitorEvent::Power { monitor_id, power } => {
+						let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) else {
+							continue;
+						};
+						monitor_rt.power = power;
+						self.emit(Event::MonitorPowerChanged(MonitorPowerChangedEvent {
+							monitor_id,
+							power,
+						}));
+					}
+					TabMonitorEvent::Removed { monitor_id, name } => {
+						self.monitors.remove(&monitor_id);
+						self.overlays.remove_monitor(&monitor_id);
+						recompute_layout(&mut self.monitors);
+						self.layout_dirty = true;
+						self.clamp_seats_to_layout();
+						self.scheduled.remove(&monitor_id);
+						self.emit(Event::MonitorRemoved(MonitorRemovedEvent {
+							monitor_id,
+							name,
+						}));
+					}
+				},
+				QueuedEvent::Render(TabRenderEvent::BufferRequestAcked { monitor_id, buffer }) => {
+					self.stats.request_ok += 1;
+					instant_log!(
+						self.stats,
+						"request_buffer ack",
+						%monitor_id,
+						buffer = buffer as u8
+					);
+					if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+						monitor_rt.finish_request();
+						monitor_rt.retry = RetryState::default();
+						// In eager mode keep requesting while another client-owned buffer exists.
+						// This avoids deadlocking on the first frame in double-buffering.
+						if std::mem::take(&mut monitor_rt.redraw_after_ack)
+							|| self.render_mode == RenderMode::Eager
+						{
+							self.scheduled.insert(monitor_id);
+						}
+					}
+				}
+				QueuedEvent::Render(TabRenderEvent::BufferRequestFailed {
+					monitor_id,
+					buffer,
+					error,
+				}) => {
+					self.stats.request_err += 1;
+					instant_log!(
+						self.stats,
+						"request_buffer err",
+						%monitor_id,
+						buffer = buffer as u8,
+						%error
+					);
+					if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+						monitor_rt.finish_request();
+						monitor_rt.pending_present[buffer as usize] = false;
+						monitor_rt.swapchain.mark_released(buffer);
+						monitor_rt.swapchain.invalidate_contents(buffer);
+						if std::mem::take(&mut monitor_rt.redraw_after_ack) {
+							self.scheduled.insert(monitor_id.clone());
+						} else {
+							// Ownership errors last until the server hands a buffer back.
+							self.schedule_retry(&monitor_id, error.is_ownership_related());
+						}
+						self.emit(Event::BufferAvailable(BufferAvailableEvent {
+							monitor_id: monitor_id.clone(),
+							buffer_index: buffer,
+						}));
+					}
+					let ferr = FrameworkError::BufferRequestFailed {
+						monitor_id,
+						buffer,
+						error,
+					};
+					self.report_error(ferr);
+				}
+				QueuedEvent::Render(TabRenderEvent::BufferReleased {
+					monitor_id,
+					buffer,
+					release_fence_fd,
+				}) => {
+					self.stats.buffer_release_events += 1;
+					instant_log!(
+						self.stats,
+						"buffer_release event",
+						%monitor_id,
+						buffer = buffer as u8,
+						fence = release_fence_fd.is_some()
+					);
+					let mut should_emit_present = false;
+					let mut released = false;
+					let mut frame_tag = FrameTag::default();
+					if let Some(monitor) = self.monitors.get_mut(&monitor_id) {
+						frame_tag = monitor.frame_tags[buffer as usize];
+						if let Some(fd) = release_fence_fd {
+							monitor.pending_release_fences[buffer as usize] =
+								Some(unsafe { OwnedFd::from_raw_fd(fd) });
+						} else {
+							if monitor.pending_present[buffer as usize] {
+								monitor.pending_present[buffer as usize] = false;
+								should_emit_present = true;
+							}
+							monitor.swapchain.mark_released(buffer);
+							released = true;
+							if self.render_mode == RenderMode::Eager && monitor.take_release_retry() {
+								self.scheduled.insert(monitor_id.clone());
+							}
+						}
+					}
+					self.sync_swapchain_size(&monitor_id)?;
+					if should_emit_present {
+						self.emit_present(PresentEvent {
+							monitor_id: monitor_id.clone(),
+							buffer_index: buffer,
+							frame_id: frame_tag.frame_id,
+							user_data: frame_tag.user_data,
+							timestamp: Instant::now(),
+						});
+					}
+					if released {
+						self.emit(Event::BufferAvailable(BufferAvailableEvent {
+							monitor_id,
+							buffer_index: buffer,
+						}));
+					}
+				}
+				QueuedEvent::Render(TabRenderEvent::OverlayReleased {
+					overlay_id,
+					buffer,
+					release_fence_fd,
+				}) => {
+					let release_fence = release_fence_fd.map(|fd| unsafe { OwnedFd::from_raw_fd(fd) });
+					self.overlays.release(&overlay_id, buffer, release_fence);
+				}
+				QueuedEvent::Render(TabRenderEvent::CaptureFrame {
+					capture_id,
+					buffer,
+					time_usec,
+					ready_fence_fd,
+				}) => {
+					let ready_fence = ready_fence_fd.map(|fd| Arc::new(unsafe { OwnedFd::from_raw_fd(fd) }));
+					let Some(capture_id) = self.captures.frame(&capture_id, buffer) else {
+						continue;
+					};
+					self.emit(Event::CaptureFrame(CaptureFrameEvent {
+						capture_id,
+						buffer,
+						time_usec,
+						ready_fence,
+					}));
+				}
+				QueuedEvent::Render(TabRenderEvent::CaptureEnded { capture_id, reason }) => {
+					let Some(capture_id) =
+						CaptureId::from_wire(&capture_id).filter(|id| self.captures.remove(*id).is_some())
+					else {
+						continue;
+					};
+					self.emit(Event::CaptureEnded(CaptureEndedEvent {
+						capture_id,
+						reason,
+					}));
+				}
+				QueuedEvent::Input(TabInputEvent::GlobalShortcut {
+					seat,
+					shortcut_id,
+					time_usec,
+				}) => {
+					let Some(shortcut_id) = GlobalShortcutId::from_wire(&shortcut_id)
+						.filter(|id| self.global_shortcuts.registered.contains(id))
+					else {
+						continue;
+					};
+					self.emit(Event::GlobalShortcut(GlobalShortcutEvent {
+						shortcut_id,
+						seat,
+						time_usec,
+						timestamp: self.client.clock().to_instant(time_usec),
+					}));
+				}
+				QueuedEvent::Input(TabInputEvent::GlobalShortcutConflict { shortcut_id }) => {
+					let Some(shortcut_id) = GlobalShortcutId::from_wire(&shortcut_id) else {
+						continue;
+					};
+					if self.global_shortcuts.registered.remove(&shortcut_id) {
+						let err = FrameworkError::ShortcutConflict(shortcut_id);
+						self.report_error(err);
+					}
+				}
+				QueuedEvent::Input(TabInputEvent::KeyboardGrabUnavailable) => {
+					if std::mem::take(&mut self.focus.keyboard_grab) {
+						let err = FrameworkError::KeyboardGrabUnavailable;
+						self.report_error(err);
+					}
+				}
+				QueuedEvent::Input(TabInputEvent::DeviceAdded(device)) => {
+					self.input_devices.insert(device.id, device.clone());
+					self.emit(Event::InputDeviceAdded(InputDeviceAddedEvent { device }));
+				}
+				QueuedEvent::Input(TabInputEvent::DeviceRemoved(device)) => {
+					self.input_devices.remove(&device.id);
+					self.emit(Event::InputDeviceRemoved(InputDeviceRemovedEvent {
+						device,
+					}));
+				}
+				QueuedEvent::Input(TabInputEvent::Event { seat, payload }) => {
+					let _input = tracing::debug_span!(
+						target: "tab_app_framework.core",
+						"input",
+						%seat,
+						device = payload.device()
+					)
+					.entered();
+					if let Some(idle_for) = self.idle.record_activity() {
+						self.emit(Event::ResumeActivity(ResumeActivityEvent { idle_for }));
+					}
+					self.activate_seat(&seat);
+					let timestamp = self.client.clock().to_instant(payload.time_usec());
+					let input = InputEvent {
+						seat,
+						payload,
+						timestamp,
+					};
+					self.emit_input(&input);
+					let InputEvent { seat, payload, .. } = input;
+						match payload {
+							InputEventPayload::Key {
+								device,
+								time_usec,
+								key,
+								state,
+							} => {
+								let pressed = state == KeyState::Pressed;
+								let composed = self.text_composer.process_key(key, pressed);
+								self.emit(Event::Key(KeyEvent {
+									device,
+									time_usec,
+									timestamp,
+									key,
+									state,
+								}));
+								if let Some(ev) = composed {
+									self.emit(Event::Char(ev));
+								}
+							}
+							InputEventPayload::PointerMotion {
+								device,
+								time_usec,
+								dx,
+								dy,
+								unaccel_dx,
+								unaccel_dy,
+								..
+							} => {
+								let old_position = self.cursor_position;
+								self.refresh_layout();
+								let placements = &self.placements;
+								self.cursor_position = move_cursor_with_wrap(
+									placements,
+									self.cursor_position.0,
+									self.cursor_position.1,
+									dx,
+									dy,
+									self.edge_behavior,
+									self.wrap_mode,
+									&mut self.edge_state,
+								);
+								self.emit_cursor_move(
+									PointerMoveEvent {
+										seat: seat.clone(),
+										device,
+										time_usec,
+										timestamp,
+										pointer_type: PointerType::Mouse,
+										old_position,
+										new_position: self.cursor_position,
+										raw_dx: unaccel_dx,
+										raw_dy: unaccel_dy,
+									},
 									true,
 								);
 							}
@@ -1048,8 +4963,10 @@ impl<A: Application> TabAppFramework<A> {
 							} => match state {
 								ButtonState::Pressed => self.emit_pointer_down(
 									PointerDownEvent {
+										seat: seat.clone(),
 										device,
 										time_usec,
+										timestamp,
 										pointer_type: PointerType::Mouse,
 										button,
 										position: self.cursor_position,
@@ -1058,8 +4975,10 @@ impl<A: Application> TabAppFramework<A> {
 								),
 								ButtonState::Released => self.emit_pointer_up(
 									PointerUpEvent {
+										seat: seat.clone(),
 										device,
 										time_usec,
+										timestamp,
 										pointer_type: PointerType::Mouse,
 										button,
 										position: self.cursor_position,
@@ -1075,16 +4994,21 @@ impl<A: Application> TabAppFramework<A> {
 								..
 							} => {
 								let old_position = self.cursor_position;
-								let placements = current_layout(&self.monitors);
+								self.refresh_layout();
+								let placements = &self.placements;
 								self.cursor_position =
-									clamp_point_to_layout(&placements, x_transformed, y_transformed);
+									clamp_point_to_layout(placements, x_transformed, y_transformed);
 								self.emit_cursor_move(
 									PointerMoveEvent {
+										seat: seat.clone(),
 										device,
 										time_usec,
+										timestamp,
 										pointer_type: PointerType::Mouse,
 										old_position,
 										new_position: self.cursor_position,
+										raw_dx: 0.0,
+										raw_dy: 0.0,
 									},
 									true,
 								);
@@ -1096,7 +5020,8 @@ impl<A: Application> TabAppFramework<A> {
 								..
 							} => {
 								let old_position = self.cursor_position;
-								let placements = current_layout(&self.monitors);
+								self.refresh_layout();
+								let placements = &self.placements;
 								let (mut x, mut y) = (axes.x, axes.y);
 								if (0.0..=1.0).contains(&x) && (0.0..=1.0).contains(&y) {
 									let max_x = placements
@@ -1114,14 +5039,18 @@ impl<A: Application> TabAppFramework<A> {
 									x *= max_x;
 									y *= max_y;
 								}
-								self.cursor_position = clamp_point_to_layout(&placements, x, y);
+								self.cursor_position = clamp_point_to_layout(placements, x, y);
 								self.emit_cursor_move(
 									PointerMoveEvent {
+										seat: seat.clone(),
 										device,
 										time_usec,
+										timestamp,
 										pointer_type: PointerType::Pen,
 										old_position,
 										new_position: self.cursor_position,
+										raw_dx: 0.0,
+										raw_dy: 0.0,
 									},
 									false,
 								);
@@ -1131,7 +5060,8 @@ impl<A: Application> TabAppFramework<A> {
 								time_usec,
 								contact,
 							} => {
-								let placements = current_layout(&self.monitors);
+								self.refresh_layout();
+								let placements = &self.placements;
 								let mut x = contact.x_transformed;
 								let mut y = contact.y_transformed;
 								if x > 1.0 || y > 1.0 {
@@ -1152,30 +5082,38 @@ impl<A: Application> TabAppFramework<A> {
 									.max(1) as f64;
 								let old_position = self.cursor_position;
 								self.cursor_position =
-									clamp_point_to_layout(&placements, x * max_x, y * max_y);
+									clamp_point_to_layout(placements, x * max_x, y * max_y);
 								self.touch_contacts
 									.insert(contact.id, self.cursor_position);
 								self.emit_touch(TouchEvent::Down {
+									seat: seat.clone(),
 									device,
 									time_usec,
+									timestamp,
 									contact: contact.clone(),
 								});
 								if self.primary_touch_id.is_none() {
 									self.primary_touch_id = Some(contact.id);
 									self.emit_cursor_move(
 										PointerMoveEvent {
+											seat: seat.clone(),
 											device,
 											time_usec,
+											timestamp,
 											pointer_type: PointerType::Touch,
 											old_position,
 											new_position: self.cursor_position,
+											raw_dx: 0.0,
+											raw_dy: 0.0,
 										},
 										false,
 									);
 									self.emit_pointer_down(
 										PointerDownEvent {
+											seat: seat.clone(),
 											device,
 											time_usec,
+											timestamp,
 											pointer_type: PointerType::Touch,
 											button: BTN_LEFT,
 											position: self.cursor_position,
@@ -1189,7 +5127,8 @@ impl<A: Application> TabAppFramework<A> {
 								time_usec,
 								contact,
 							} => {
-								let placements = current_layout(&self.monitors);
+								self.refresh_layout();
+								let placements = &self.placements;
 								let mut x = contact.x_transformed;
 								let mut y = contact.y_transformed;
 								if x > 1.0 || y > 1.0 {
@@ -1209,11 +5148,13 @@ impl<A: Application> TabAppFramework<A> {
 									.unwrap_or(0)
 									.max(1) as f64;
 								let next =
-									clamp_point_to_layout(&placements, x * max_x, y * max_y);
+									clamp_point_to_layout(placements, x * max_x, y * max_y);
 								self.touch_contacts.insert(contact.id, next);
 								self.emit_touch(TouchEvent::Motion {
+									seat: seat.clone(),
 									device,
 									time_usec,
+									timestamp,
 									contact: contact.clone(),
 								});
 								if self.primary_touch_id == Some(contact.id) {
@@ -1221,11 +5162,15 @@ impl<A: Application> TabAppFramework<A> {
 									self.cursor_position = next;
 									self.emit_cursor_move(
 										PointerMoveEvent {
+											seat: seat.clone(),
 											device,
 											time_usec,
+											timestamp,
 											pointer_type: PointerType::Touch,
 											old_position,
 											new_position: self.cursor_position,
+											raw_dx: 0.0,
+											raw_dy: 0.0,
 										},
 										false,
 									);
@@ -1238,15 +5183,19 @@ impl<A: Application> TabAppFramework<A> {
 							} => {
 								self.touch_contacts.remove(&contact_id);
 								self.emit_touch(TouchEvent::Up {
+									seat: seat.clone(),
 									device,
 									time_usec,
+									timestamp,
 									contact_id,
 								});
 								if self.primary_touch_id == Some(contact_id) {
 									self.emit_pointer_up(
 										PointerUpEvent {
+											seat: seat.clone(),
 											device,
 											time_usec,
+											timestamp,
 											pointer_type: PointerType::Touch,
 											button: BTN_LEFT,
 											position: self.cursor_position,
@@ -1257,15 +5206,25 @@ impl<A: Application> TabAppFramework<A> {
 								}
 							}
 							InputEventPayload::TouchFrame { time_usec } => {
-								self.emit_touch(TouchEvent::Frame { time_usec });
+								self.emit_touch(TouchEvent::Frame {
+									seat: seat.clone(),
+									time_usec,
+									timestamp,
+								});
 							}
 							InputEventPayload::TouchCancel { time_usec } => {
-								self.emit_touch(TouchEvent::Cancel { time_usec });
+								self.emit_touch(TouchEvent::Cancel {
+									seat: seat.clone(),
+									time_usec,
+									timestamp,
+								});
 								if self.primary_touch_id.take().is_some() {
 									self.emit_pointer_up(
 										PointerUpEvent {
+											seat: seat.clone(),
 											device: 0,
 											time_usec,
+											timestamp,
 											pointer_type: PointerType::Touch,
 											button: BTN_LEFT,
 											position: self.cursor_position,
@@ -1279,42 +5238,66 @@ impl<A: Application> TabAppFramework<A> {
 								device,
 								time_usec,
 								fingers,
-							} => self.emit_gesture(GestureEvent::SwipeBegin {
-								device,
-								time_usec,
-								fingers,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::SwipeBegin {
+									device,
+									time_usec,
+									timestamp,
+									fingers,
+									position,
+									monitor_id,
+								});
+							}
 							InputEventPayload::GestureSwipeUpdate {
 								device,
 								time_usec,
 								fingers,
 								dx,
 								dy,
-							} => self.emit_gesture(GestureEvent::SwipeUpdate {
-								device,
-								time_usec,
-								fingers,
-								dx,
-								dy,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::SwipeUpdate {
+									device,
+									time_usec,
+									timestamp,
+									fingers,
+									dx,
+									dy,
+									position,
+									monitor_id,
+								});
+							}
 							InputEventPayload::GestureSwipeEnd {
 								device,
 								time_usec,
 								cancelled,
-							} => self.emit_gesture(GestureEvent::SwipeEnd {
-								device,
-								time_usec,
-								cancelled,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::SwipeEnd {
+									device,
+									time_usec,
+									timestamp,
+									cancelled,
+									position,
+									monitor_id,
+								});
+							}
 							InputEventPayload::GesturePinchBegin {
 								device,
 								time_usec,
 								fingers,
-							} => self.emit_gesture(GestureEvent::PinchBegin {
-								device,
-								time_usec,
-								fingers,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::PinchBegin {
+									device,
+									time_usec,
+									timestamp,
+									fingers,
+									position,
+									monitor_id,
+								});
+							}
 							InputEventPayload::GesturePinchUpdate {
 								device,
 								time_usec,
@@ -1323,327 +5306,1934 @@ impl<A: Application> TabAppFramework<A> {
 								dy,
 								scale,
 								rotation,
-							} => self.emit_gesture(GestureEvent::PinchUpdate {
-								device,
-								time_usec,
-								fingers,
-								dx,
-								dy,
-								scale,
-								rotation,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::PinchUpdate {
+									device,
+									time_usec,
+									timestamp,
+									fingers,
+									dx,
+									dy,
+									scale,
+									rotation,
+									position,
+									monitor_id,
+								});
+							}
 							InputEventPayload::GesturePinchEnd {
 								device,
 								time_usec,
 								cancelled,
-							} => self.emit_gesture(GestureEvent::PinchEnd {
-								device,
-								time_usec,
-								cancelled,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::PinchEnd {
+									device,
+									time_usec,
+									timestamp,
+									cancelled,
+									position,
+									monitor_id,
+								});
+							}
 							InputEventPayload::GestureHoldBegin {
 								device,
 								time_usec,
 								fingers,
-							} => self.emit_gesture(GestureEvent::HoldBegin {
-								device,
-								time_usec,
-								fingers,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::HoldBegin {
+									device,
+									time_usec,
+									timestamp,
+									fingers,
+									position,
+									monitor_id,
+								});
+							}
 							InputEventPayload::GestureHoldEnd {
 								device,
 								time_usec,
 								cancelled,
-							} => self.emit_gesture(GestureEvent::HoldEnd {
-								device,
-								time_usec,
-								cancelled,
-							}),
+							} => {
+								let (position, monitor_id) = self.gesture_location();
+								self.emit_gesture(GestureEvent::HoldEnd {
+									device,
+									time_usec,
+									timestamp,
+									cancelled,
+									position,
+									monitor_id,
+								});
+							}
 							_ => (),
 						}
 					}
-				QueuedEvent::Session(ev) => {
-					if let tab_client::SessionEvent::State(session) = ev {
-						self.call_app(|app, ctx| {
-							app.on_session_state(
-								ctx,
-								SessionEvent {
-									session: session.clone(),
-								},
-							)
+				QueuedEvent::Session(tab_client::SessionEvent::Active(active_session_id)) => {
+					for request_id in self.session_requests.complete_switches(&active_session_id) {
+						self.emit(Event::SessionSwitchResult(SessionSwitchResultEvent {
+							request_id,
+							session_id: active_session_id.clone(),
+							result: Ok(()),
+						}));
+					}
+					let focused = active_session_id == self.client.session().id;
+					if focused == self.focus.focused {
+						continue;
+					}
+					self.focus.focused = focused;
+					let ev = FocusEvent { active_session_id };
+					self.emit(if focused {
+						Event::FocusGained(ev)
+					} else {
+						Event::FocusLost(ev)
+					});
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::State(session)) => {
+					self.emit(Event::Session(SessionEvent { session }));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::Preview {
+					session_id,
+					monitor_id,
+					width,
+					height,
+					stride,
+					fourcc,
+					pixels_fd,
+				}) => {
+					let pixels = unsafe { OwnedFd::from_raw_fd(pixels_fd) };
+					let image = match read_preview_pixels(pixels, stride as usize * height as usize) {
+						Ok(pixels) => Some(PreviewImage {
+							monitor_id,
+							width,
+							height,
+							stride,
+							fourcc,
+							pixels,
+						}),
+						Err(e) => {
+							tracing::warn!(%session_id, error = %e, "failed to read session preview");
+							None
+						}
+					};
+					self.emit(Event::SessionPreview(SessionPreviewEvent {
+						session_id,
+						image,
+					}));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::PreviewUnavailable { session_id }) => {
+					self.emit(Event::SessionPreview(SessionPreviewEvent {
+						session_id,
+						image: None,
+					}));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::Message {
+					from_session_id,
+					correlation_id,
+					reply_to,
+					data,
+				}) => {
+					self.emit(Event::SessionMessage(SessionMessageEvent {
+						from_session_id,
+						correlation_id,
+						reply_to,
+						data,
+					}));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::Crashed {
+					session_id,
+					message,
+					backtrace_hash,
+					app_version,
+				}) => {
+					self.emit(Event::SessionCrashed(SessionCrashedEvent {
+						session_id,
+						message,
+						backtrace_hash,
+						app_version,
+					}));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::MessageUndeliverable { session_id }) => {
+					let err = FrameworkError::SessionMessageUndeliverable(session_id);
+					self.report_error(err);
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::Created {
+					session,
+					token,
+					request_id: Some(request_id),
+				}) => {
+					self.session_requests.creates.remove(&RequestId(request_id));
+					self.emit(Event::SessionCreateResult(SessionCreateResultEvent {
+						request_id: RequestId(request_id),
+						result: Ok(SessionCreatedPayload {
+							session,
+							token,
+							request_id: Some(request_id),
+						}),
+					}));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::RequestFailed { request_id, error }) => {
+					let request_id = RequestId(request_id);
+					if let Some(session_id) = self.session_requests.switches.remove(&request_id) {
+						self.emit(Event::SessionSwitchResult(SessionSwitchResultEvent {
+							request_id,
+							session_id,
+							result: Err(error),
+						}));
+					} else if self.session_requests.creates.remove(&request_id) {
+						self.emit(Event::SessionCreateResult(SessionCreateResultEvent {
+							request_id,
+							result: Err(error),
+						}));
+					} else {
+						let request = self.session_requests.admin.remove(&request_id);
+						self.report_error(FrameworkError::AdminRequestFailed {
+							request: request.unwrap_or("admin request"),
+							error,
+						});
+					}
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::Error(error)) => {
+					self.report_error(FrameworkError::Client(TabClientError::Server(error)));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::Accessibility(settings)) => {
+					if settings == self.accessibility {
+						continue;
+					}
+					let previous = std::mem::replace(&mut self.accessibility, settings);
+					self.emit(Event::AccessibilityChanged(AccessibilityChangedEvent {
+						settings,
+						previous,
+					}));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::ServerUnresponsive { since }) => {
+					let ev = ServerUnresponsiveEvent { since };
+					self.emit(Event::ServerUnresponsive(ev));
+				}
+				QueuedEvent::Session(tab_client::SessionEvent::ServerRecovered { unresponsive_for }) => {
+					let ev = ServerRecoveredEvent {
+						unresponsive_for,
+						latency: self.client.server_latency().unwrap_or_default(),
+					};
+					self.emit(Event::ServerRecovered(ev));
+				}
+				QueuedEvent::Session(_) => {}
+				QueuedEvent::Extension(ev) => self.emit(Event::Extension(ev)),
+			}
+		}
+		Ok(())
+	}
+
+	/// Recreates and relinks a monitor's swapchain when its buffers no longer match the
+	/// monitor size.
+	fn sync_swapchain_size(&mut self, monitor_id: &str) -> Result<(), FrameworkError> {
+		let Some(monitor_rt) = self.monitors.get_mut(monitor_id) else {
+			return Ok(());
+		};
+		let size = (
+			monitor_rt.monitor.physical_width,
+			monitor_rt.monitor.physical_height,
+		);
+		if monitor_rt.swapchain.size() == size {
+			return Ok(());
+		}
+		info!(
+			monitor_id,
+			width = size.0,
+			height = size.1,
+			"recreating swapchain for resized monitor"
+		);
+		let swapchain = if monitor_rt.shm_surface {
+			self.client.create_shm_swapchain(monitor_id)?
+		} else {
+			self.client.create_swapchain(monitor_id)?
+		};
+		monitor_rt.replace_swapchain(swapchain);
+		Ok(())
+	}
+
+	fn request_explicit_frames(&mut self) -> Result<(), FrameworkError> {
+		let mut monitor_ids = Vec::new();
+		for monitor_id in self.scheduled.drain().collect::<Vec<_>>() {
+			let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) else {
+				continue;
+			};
+			if monitor_rt.inflight_request.is_some() {
+				monitor_rt.redraw_after_ack = true;
+				continue;
+			}
+			self.sync_swapchain_size(&monitor_id)?;
+			monitor_ids.push(monitor_id);
+		}
+		if monitor_ids.is_empty() {
+			return Ok(());
+		}
+		monitor_ids.sort();
+		let ev = FrameRequestEvent { monitor_ids };
+		let _span = frame_span!(self.tracer, "render");
+		let started = Instant::now();
+		self.call_app(|app, ctx| app.on_frame_requested(ctx, ev));
+		self.tracer.current.render += started.elapsed();
+		Ok(())
+	}
+
+	fn render_scheduled(&mut self) -> Result<(), FrameworkError> {
+		let monitors = &self.monitors;
+		self
+			.scheduled
+			.retain(|monitor_id| !monitors.get(monitor_id).is_some_and(|m| m.paused));
+		if self.explicit_submit {
+			return self.request_explicit_frames();
+		}
+		let mut targets: Vec<_> = self.scheduled.drain().collect();
+		targets.retain(|monitor_id| {
+			let Some(monitor_rt) = self.monitors.get_mut(monitor_id) else {
+				return false;
+			};
+			if monitor_rt.inflight_request.is_some() || monitor_rt.acquired.is_some() {
+				// The server rejects a second request per monitor; retry once acked.
+				// A frame the application acquired itself counts as in flight.
+				monitor_rt.redraw_after_ack = true;
+				return false;
+			}
+			true
+		});
+		if targets.is_empty() {
+			return Ok(());
+		}
+		targets.sort();
+		self.call_app(|app, ctx| app.on_begin_frame(ctx, &targets));
+		let result = self
+			.render_parallel(&mut targets)
+			.and_then(|()| self.render_targets(&targets));
+		self.call_app(|app, ctx| app.on_end_frame(ctx));
+		result
+	}
+
+	/// Offers `targets` to [`Application::on_render_parallel`] and drops the monitors it
+	/// submitted or still holds a frame of.
+	fn render_parallel(&mut self, targets: &mut Vec<String>) -> Result<(), FrameworkError> {
+		if !self.parallel_render || targets.len() < 2 {
+			return Ok(());
+		}
+		for monitor_id in targets.iter() {
+			self.sync_swapchain_size(monitor_id)?;
+		}
+		let ev = FrameRequestEvent {
+			monitor_ids: targets.clone(),
+		};
+		{
+			let _span = frame_span!(self.tracer, "render_parallel");
+			let started = Instant::now();
+			self.call_app(|app, ctx| app.on_render_parallel(ctx, ev));
+			self.tracer.current.render += started.elapsed();
+		}
+		targets.retain(|monitor_id| {
+			self.monitors.get(monitor_id).is_some_and(|monitor_rt| {
+				monitor_rt.inflight_request.is_none() && monitor_rt.acquired.is_none()
+			})
+		});
+		Ok(())
+	}
+
+	/// Renders and submits one frame per monitor, in the order given.
+	fn render_targets(&mut self, targets: &[String]) -> Result<(), FrameworkError> {
+		for monitor_id in targets {
+			let monitor_id = monitor_id.clone();
+			instant_log!(self.stats, "render_scheduled begin", %monitor_id);
+			self.sync_swapchain_size(&monitor_id)?;
+			let acquired = self.monitors.get_mut(&monitor_id).and_then(|monitor_rt| {
+				let resized = std::mem::take(&mut monitor_rt.resized);
+				let (buffer, buffer_idx) = monitor_rt.swapchain.acquire_next()?;
+				// `on_render` may replace the swapchain, so the target borrows its own handle.
+				let buffer = buffer.try_clone();
+				let clear_policy = monitor_rt.clear_policy;
+				let buffer_age = match clear_policy {
+					ClearPolicy::None => monitor_rt.swapchain.buffer_age(buffer_idx),
+					ClearPolicy::Color(_) => 0,
+				};
+				let scale = monitor_rt.monitor.scale;
+				Some((buffer, buffer_idx, resized, buffer_age, scale, clear_policy))
+			});
+			let Some((buffer, buffer_idx, resized, buffer_age, scale, clear_policy)) = acquired else {
+				self.stats.acquire_miss += 1;
+				if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+					monitor_rt.acquire_missed();
+				}
+				self.schedule_retry(&monitor_id, true);
+				continue;
+			};
+			let buffer = match buffer {
+				Ok(buffer) => buffer,
+				Err(err) => {
+					if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+						monitor_rt.swapchain.rollback();
+					}
+					return Err(FrameworkError::Client(err.into()));
+				}
+			};
+			self.next_frame_id += 1;
+			let frame_id = self.next_frame_id;
+			let render_ev = RenderEvent {
+				monitor_id: &monitor_id,
+				frame_id,
+				target: RenderTarget::new(&buffer, buffer_idx),
+				resized,
+				buffer_age,
+				scale,
+				clear_policy,
+			};
+			let _frame = tracing::debug_span!(
+				target: "tab_app_framework.core",
+				"frame",
+				%monitor_id,
+				buffer = buffer_idx as u8,
+				frame_id
+			)
+			.entered();
+			self.stats.acquire_ok += 1;
+			if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+				monitor_rt.end_starvation();
+			}
+			self.next_acquire_fence = None;
+			self.frame_user_data = None;
+			{
+				let _span = frame_span!(self.tracer, "render", monitor = monitor_id.as_str());
+				let started = Instant::now();
+				self.call_app(|app, ctx| app.on_render(ctx, render_ev));
+				self.tracer.current.render += started.elapsed();
+			}
+			if std::mem::take(&mut self.frame_cancelled) {
+				self.next_acquire_fence = None;
+				self.stats.frames_cancelled += 1;
+				instant_log!(
+					self.stats,
+					"render cancelled",
+					%monitor_id,
+					buffer = buffer_idx as u8
+				);
+				if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+					monitor_rt.swapchain.rollback();
+				}
+				continue;
+			}
+			let acquire_fence = self
+				.next_acquire_fence
+				.as_ref()
+				.map(|fd| fd.as_raw_fd());
+			instant_log!(
+				self.stats,
+				"request_buffer send",
+				%monitor_id,
+				buffer = buffer_idx as u8,
+				fence = ?acquire_fence
+			);
+
+				let request_started = Instant::now();
+				let request_result = {
+					let _span =
+						frame_span!(self.tracer, "request_buffer", monitor = monitor_id.as_str());
+					self.client.send_buffer_request(&monitor_id, buffer_idx, acquire_fence)
+				};
+				self.tracer.current.request_buffer += request_started.elapsed();
+				match request_result {
+					Ok(()) => {
+						if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+							monitor_rt.swapchain.mark_busy(buffer_idx);
+							monitor_rt.swapchain.mark_submitted(buffer_idx);
+							monitor_rt.pending_present[buffer_idx as usize] = true;
+							monitor_rt.frame_tags[buffer_idx as usize] = FrameTag {
+								frame_id,
+								user_data: self.frame_user_data.take(),
+							};
+							monitor_rt.start_request(buffer_idx);
+						}
+					}
+				Err(err) => {
+					self.stats.request_err += 1;
+					instant_log!(
+						self.stats,
+						"request_buffer err",
+						%monitor_id,
+						buffer = buffer_idx as u8,
+						error = %err
+					);
+					if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
+						monitor_rt.swapchain.rollback();
+						monitor_rt.swapchain.invalidate_contents(buffer_idx);
+					}
+					self.schedule_retry(&monitor_id, false);
+					let ferr: FrameworkError = err.into();
+					self.report_error(ferr);
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Reschedules an eager monitor after a failed frame, following its [`RetryPolicy`].
+	///
+	/// `needs_release` is set when only a buffer release lets the next attempt succeed.
+	fn schedule_retry(&mut self, monitor_id: &str, needs_release: bool) {
+		if self.render_mode != RenderMode::Eager {
+			return;
+		}
+		let Some(monitor_rt) = self.monitors.get_mut(monitor_id) else {
+			return;
+		};
+		let policy = monitor_rt.retry_policy;
+		let retry = &mut monitor_rt.retry;
+		retry.failures = retry.failures.saturating_add(1);
+		if needs_release && policy.reschedule_on_release {
+			return;
+		}
+		match policy.backoff(retry.failures) {
+			None => {
+				self.scheduled.insert(monitor_id.to_string());
+			}
+			Some(delay) => retry.retry_at = Some(Instant::now() + policy.jittered(delay)),
+		}
+	}
+
+	/// Counts frames the server did not acknowledge within the ack timeout, and raises
+	/// [`FrameworkError::Watchdog`] when a monitor reaches the watchdog threshold.
+	fn check_ack_timeouts(&mut self) {
+		let now = Instant::now();
+		let mut tripped = Vec::new();
+		for monitor_rt in self.monitors.values_mut() {
+			let Some(since) = monitor_rt.inflight_since else {
+				continue;
+			};
+			if now.duration_since(since) < self.ack_timeout {
+				continue;
+			}
+			// The request stays in flight; the next timeout counts from now.
+			monitor_rt.inflight_since = Some(now);
+			monitor_rt.ack_timeouts += 1;
+			tracing::warn!(
+				monitor_id = %monitor_rt.monitor.id,
+				timeouts = monitor_rt.ack_timeouts,
+				"buffer_request_ack timeout"
+			);
+			if monitor_rt.ack_timeouts == self.watchdog_threshold {
+				tripped.push(FrameworkError::Watchdog {
+					monitor_id: monitor_rt.monitor.id.clone(),
+					consecutive_timeouts: monitor_rt.ack_timeouts,
+					ack_timeout: self.ack_timeout,
+				});
+			}
+		}
+		for err in tripped {
+			self.report_error(err);
+		}
+	}
+
+	/// Ends starvation periods of monitors with a free buffer and reports those that
+	/// lasted for the starvation threshold.
+	fn check_starvation(&mut self) {
+		let Some(threshold) = self.starvation_threshold else {
+			return;
+		};
+		let mut starved = Vec::new();
+		for monitor_rt in self.monitors.values_mut() {
+			let Some(since) = monitor_rt.starved_since else {
+				continue;
+			};
+			let free = [BufferIndex::Zero, BufferIndex::One]
+				.into_iter()
+				.any(|buffer| !monitor_rt.swapchain.is_busy(buffer));
+			if free {
+				monitor_rt.end_starvation();
+				continue;
+			}
+			let duration = since.elapsed();
+			if duration < threshold || monitor_rt.starvation_reported {
+				continue;
+			}
+			monitor_rt.starvation_reported = true;
+			tracing::warn!(
+				monitor_id = %monitor_rt.monitor.id,
+				?duration,
+				"no buffer could be acquired"
+			);
+			starved.push(StarvedEvent {
+				monitor_id: monitor_rt.monitor.id.clone(),
+				duration,
+			});
+		}
+		for ev in starved {
+			self.emit(Event::Starved(ev));
+		}
+	}
+
+	/// Schedules monitors whose retry delay or redraw deadline has passed.
+	fn schedule_due_retries(&mut self) {
+		let now = Instant::now();
+		for monitor_rt in self.monitors.values_mut() {
+			if monitor_rt.retry.retry_at.is_some_and(|at| at <= now) {
+				monitor_rt.retry.retry_at = None;
+				self.scheduled.insert(monitor_rt.monitor.id.clone());
+			}
+			if monitor_rt.redraw_at.is_some_and(|at| at <= now) {
+				monitor_rt.redraw_at = None;
+				self.scheduled.insert(monitor_rt.monitor.id.clone());
+			}
+		}
+	}
+
+	fn flush_pending_releases(&mut self) {
+		let span = frame_span!(self.tracer, "fence_wait");
+		let started = Instant::now();
+		let mut errors = Vec::new();
+		let mut presents = Vec::new();
+		let mut available = Vec::new();
+		let mut ready_monitors = Vec::new();
+		for monitor_rt in self.monitors.values_mut() {
+			for buffer_idx in 0..monitor_rt.pending_release_fences.len() {
+				let Some(fence) = monitor_rt.pending_release_fences[buffer_idx].as_ref() else {
+					continue;
+				};
+				let signaled = match fd_readable_now(fence) {
+					Ok(v) => v,
+					Err(err) => {
+						errors.push(err);
+						true
+					}
+				};
+				if signaled {
+					monitor_rt.pending_release_fences[buffer_idx] = None;
+					self.stats.release_fence_signaled += 1;
+					let buffer = match buffer_idx {
+						0 => BufferIndex::Zero,
+						1 => BufferIndex::One,
+						_ => continue,
+					};
+					instant_log!(
+						self.stats,
+						"release_fence signaled",
+						monitor_id = %monitor_rt.monitor.id,
+						buffer = buffer_idx as u8
+					);
+					monitor_rt.swapchain.mark_released(buffer);
+					available.push(BufferAvailableEvent {
+						monitor_id: monitor_rt.monitor.id.clone(),
+						buffer_index: buffer,
+					});
+					let retry = monitor_rt.take_release_retry();
+					if monitor_rt.pending_present[buffer_idx] {
+						monitor_rt.pending_present[buffer_idx] = false;
+						let frame_tag = monitor_rt.frame_tags[buffer_idx];
+						presents.push(PresentEvent {
+							monitor_id: monitor_rt.monitor.id.clone(),
+							buffer_index: buffer,
+							frame_id: frame_tag.frame_id,
+							user_data: frame_tag.user_data,
+							timestamp: Instant::now(),
 						});
 					}
+					if self.render_mode == RenderMode::Eager && retry {
+						ready_monitors.push(monitor_rt.monitor.id.clone());
+					}
+				}
+			}
+		}
+		self.tracer.current.fence_wait += started.elapsed();
+		drop(span);
+		for monitor_id in ready_monitors {
+			self.scheduled.insert(monitor_id);
+		}
+		for ev in presents {
+			self.emit_present(ev);
+		}
+		for ev in available {
+			self.emit(Event::BufferAvailable(ev));
+		}
+		for err in errors {
+			self.report_error(err);
+		}
+	}
+
+	fn emit_cursor_move(&mut self, ev: PointerMoveEvent, also_mouse: bool) {
+		let moved = ev.old_position != ev.new_position;
+		// Raw motion against a layout edge still reaches on_pointer_move.
+		if !moved && ev.raw_dx == 0.0 && ev.raw_dy == 0.0 {
+			return;
+		}
+		let mouse_ev = MouseMoveEvent {
+			seat: ev.seat.clone(),
+			device: ev.device,
+			time_usec: ev.time_usec,
+			timestamp: ev.timestamp,
+			old_position: ev.old_position,
+			new_position: ev.new_position,
+		};
+		self.emit(Event::PointerMove(ev));
+		if also_mouse && moved {
+			self.emit(Event::MouseMove(mouse_ev));
+		}
+	}
+
+	fn emit_pointer_down(&mut self, ev: PointerDownEvent, also_mouse: bool) {
+		let mouse_ev = MouseDownEvent {
+			seat: ev.seat.clone(),
+			device: ev.device,
+			time_usec: ev.time_usec,
+			timestamp: ev.timestamp,
+			button: ev.button,
+			position: ev.position,
+		};
+		self.emit(Event::PointerDown(ev));
+		if also_mouse {
+			self.emit(Event::MouseDown(mouse_ev));
+		}
+	}
+
+	fn emit_pointer_up(&mut self, ev: PointerUpEvent, also_mouse: bool) {
+		let mouse_ev = MouseUpEvent {
+			seat: ev.seat.clone(),
+			device: ev.device,
+			time_usec: ev.time_usec,
+			timestamp: ev.timestamp,
+			button: ev.button,
+			position: ev.position,
+		};
+		self.emit(Event::PointerUp(ev));
+		if also_mouse {
+			self.emit(Event::MouseUp(mouse_ev));
+		}
+	}
+
+	fn emit_touch(&mut self, ev: TouchEvent) {
+		self.emit(Event::Touch(ev));
+	}
+
+	fn emit_gesture(&mut self, ev: GestureEvent) {
+		self.emit(Event::Gesture(ev));
+	}
+
+	/// Centroid of the active seat's touch contacts, or its cursor without contacts, and
+	/// the monitor holding it.
+	fn gesture_location(&mut self) -> ((f64, f64), Option<String>) {
+		let position = if self.touch_contacts.is_empty() {
+			self.cursor_position
+		} else {
+			let (sum_x, sum_y) = self
+				.touch_contacts
+				.values()
+				.fold((0.0, 0.0), |(sum_x, sum_y), (x, y)| (sum_x + x, sum_y + y));
+			let count = self.touch_contacts.len() as f64;
+			(sum_x / count, sum_y / count)
+		};
+		self.refresh_layout();
+		let monitor_id = monitor_at(&self.placements, position.0, position.1).map(|m| m.id.clone());
+		(position, monitor_id)
+	}
+
+	/// Makes `seat` the active seat, parking the previous seat's pointer and touch state.
+	///
+	/// A seat seen for the first time starts with its cursor at the initial position.
+	fn activate_seat(&mut self, seat: &SeatId) {
+		if *seat == self.active_seat {
+			return;
+		}
+		let next = match self.idle_seats.remove(seat) {
+			Some(state) => state,
+			None => {
+				self.refresh_layout();
+				SeatState {
+					cursor_position: initial_cursor(&self.placements),
+					touch_contacts: HashMap::new(),
+					primary_touch_id: None,
 				}
 			}
+		};
+		let previous = SeatState {
+			cursor_position: std::mem::replace(&mut self.cursor_position, next.cursor_position),
+			touch_contacts: std::mem::replace(&mut self.touch_contacts, next.touch_contacts),
+			primary_touch_id: std::mem::replace(&mut self.primary_touch_id, next.primary_touch_id),
+		};
+		let previous_seat = std::mem::replace(&mut self.active_seat, seat.clone());
+		self.idle_seats.insert(previous_seat, previous);
+	}
+
+	/// Clamps every seat's cursor into the current layout.
+	fn clamp_seats_to_layout(&mut self) {
+		self.refresh_layout();
+		self.cursor_position = clamp_point_to_layout(
+			&self.placements,
+			self.cursor_position.0,
+			self.cursor_position.1,
+		);
+		clamp_idle_seats(&self.placements, &mut self.idle_seats);
+	}
+
+	/// Emits the layout changes since the last report, if any.
+	fn report_layout_changes(&mut self) {
+		self.refresh_layout();
+		let changes = diff_layouts(&self.reported_layout, &self.placements);
+		if changes.is_empty() {
+			return;
+		}
+		self.reported_layout.clone_from(&self.placements);
+		self.emit(Event::LayoutChanged(LayoutChangedEvent { changes }));
+	}
+
+	/// Rebuilds the cached placements if the layout changed since the last call.
+	fn refresh_layout(&mut self) {
+		if self.layout_dirty {
+			fill_layout(&self.monitors, &mut self.placements);
+			self.layout_dirty = false;
+		}
+	}
+
+	fn emit(&mut self, event: Event) {
+		if self.batch_events {
+			self.event_batch.push(event);
+		} else {
+			self.call_app(|app, ctx| event.dispatch(app, ctx));
+		}
+	}
+
+	fn emit_present(&mut self, ev: PresentEvent) {
+		self.stats.present_callbacks += 1;
+		let monitor_id = (self.config.auto_ready == AutoReady::AfterFirstPresent && !self.ready_sent)
+			.then(|| ev.monitor_id.clone());
+		self.emit(Event::Present(ev));
+		if monitor_id.is_some() {
+			self.send_auto_ready(monitor_id);
+		}
+	}
+
+	/// Sends `session_ready` for [`Config::auto_ready`] unless it was sent already.
+	///
+	/// A failed send is reported and retried on the next trigger.
+	fn send_auto_ready(&mut self, monitor_id: Option<String>) {
+		if self.ready_sent {
+			return;
+		}
+		match self.client.send_ready() {
+			Ok(()) => {
+				self.ready_sent = true;
+				self.emit(Event::SessionReadySent(SessionReadySentEvent {
+					monitor_id,
+				}));
+			}
+			Err(err) => self.report_error(err.into()),
+		}
+	}
+
+	/// Passes `input` to [`Application::on_input`], cloning it only into a batch.
+	fn emit_input(&mut self, input: &InputEvent) {
+		if self.batch_events {
+			self.event_batch.push(Event::Input(input.clone()));
+		} else {
+			self.call_app(|app, ctx| app.on_input(ctx, input));
+		}
+	}
+
+	/// Takes over live changes from [`Context::update_config`] and reports them.
+	fn apply_config_changes(&mut self) {
+		if self.config_changes.is_empty() {
+			return;
+		}
+		let fields = std::mem::take(&mut self.config_changes);
+		let cfg = &self.config;
+		self.render_mode = cfg.render_mode;
+		self.tracer.enabled = cfg.frame_tracing;
+		self.stats.interval = cfg.stats_interval;
+		self.batch_events = cfg.batch_events;
+		self.coalesce_pointer_motion = cfg.coalesce_pointer_motion;
+		self.edge_behavior = cfg.edge_behavior;
+		self.wrap_mode = cfg.wrap_mode;
+		self.clear_policies = cfg.clear_policies.clone();
+		self.retry_policies = cfg.retry_policies.clone();
+		self.default_retry_policy = cfg.default_retry_policy;
+		self.ack_timeout = cfg.buffer_request_ack_timeout;
+		self.watchdog_threshold = cfg.watchdog_threshold;
+		self.starvation_threshold = cfg.starvation_threshold;
+		for err in self.errors.set_window(cfg.error_dedup_window) {
+			self.call_app(|app, ctx| app.on_error(ctx, &err));
+		}
+		self.emit(Event::ConfigChanged(ConfigChangedEvent { fields }));
+	}
+
+	fn report_recreated_swapchains(&mut self) {
+		for monitor_id in std::mem::take(&mut self.recreated_swapchains) {
+			// A monitor removed in the meantime was reported through `on_monitor_removed`.
+			if self.monitors.contains_key(&monitor_id) {
+				self.emit(Event::SwapchainRecreated(SwapchainRecreatedEvent {
+					monitor_id,
+				}));
+			}
+		}
+	}
+
+	/// Passes `err` to [`Application::on_error`] unless it repeats a recent error.
+	fn report_error(&mut self, err: FrameworkError) {
+		for err in self.errors.report(err, Instant::now()) {
+			if self.config.dump_state_on_error {
+				self.dump_state(&err);
+			}
+			self.call_app(|app, ctx| app.on_error(ctx, &err));
+		}
+	}
+
+	/// Logs a [`DebugSnapshot`] for [`Config::set_dump_state_on_error`].
+	fn dump_state(&self, err: &FrameworkError) {
+		let snapshot = DebugSnapshot::capture(
+			&self.monitors,
+			&self.scheduled,
+			&self.active_seat,
+			self.cursor_position,
+			&self.touch_contacts,
+			&self.idle_seats,
+		);
+		match serde_json::to_string(&snapshot) {
+			Ok(snapshot) => error!(
+				target: "tab_app_framework.core",
+				error = %err,
+				%snapshot,
+				"framework state at error"
+			),
+			Err(ser_err) => debug!(
+				target: "tab_app_framework.core",
+				error = %ser_err,
+				"state snapshot not serializable"
+			),
+		}
+	}
+
+	/// Reports the repeat counts of error bursts that ended.
+	fn flush_repeated_errors(&mut self) {
+		for err in self.errors.expire(Instant::now()) {
+			self.call_app(|app, ctx| app.on_error(ctx, &err));
+		}
+	}
+
+	fn flush_event_batch(&mut self) {
+		if self.event_batch.is_empty() {
+			return;
+		}
+		let mut batch = std::mem::take(&mut self.event_batch);
+		self.call_app(|app, ctx| app.on_events(ctx, &batch));
+		batch.clear();
+		// Keep the allocation for the next iteration.
+		self.event_batch = batch;
+	}
+
+	fn sync_idle_inhibit(&mut self) -> Result<(), FrameworkError> {
+		let Some(reason) = self.idle_inhibitors.borrow_mut().take_change() else {
+			return Ok(());
+		};
+		self.client.set_idle_inhibit(reason.is_some(), reason)?;
+		Ok(())
+	}
+
+	fn check_idle(&mut self) {
+		if let Some(idle_for) = self.idle.poll() {
+			self.emit(Event::Idle(IdleEvent { idle_for }));
+		}
+	}
+
+	fn report_stats(&mut self) {
+		if let Some(snapshot) = self.stats.rollover() {
+			self.call_app(|app, ctx| app.on_stats(ctx, snapshot));
+		}
+	}
+
+	fn call_app<F>(&mut self, f: F)
+	where
+		F: FnOnce(&mut A, &mut Context<A>),
+	{
+		// Layout queries hit-test the cached placements instead of rebuilding them.
+		self.refresh_layout();
+		let mut ctx = Context::<A> {
+			client: &mut self.client,
+			monitors: &mut self.monitors,
+			placements: &mut self.placements,
+			scheduled: &mut self.scheduled,
+			watched_fds: &mut self.watched_fds,
+			next_acquire_fence: &mut self.next_acquire_fence,
+			frame_cancelled: &mut self.frame_cancelled,
+			frame_user_data: &mut self.frame_user_data,
+			next_frame_id: &mut self.next_frame_id,
+			overlays: &mut self.overlays,
+			captures: &mut self.captures,
+			cursor_position: &mut self.cursor_position,
+			touch_contacts: &self.touch_contacts,
+			active_seat: &self.active_seat,
+			idle_seats: &mut self.idle_seats,
+			input_devices: &self.input_devices,
+			layout_dirty: &mut self.layout_dirty,
+			exiting: &mut self.exiting,
+			frame_timings: &self.tracer.last,
+			loop_stats: &self.stats.last,
+			idle: &mut self.idle,
+			idle_inhibitors: &self.idle_inhibitors,
+			global_shortcuts: &mut self.global_shortcuts,
+			next_correlation_id: &mut self.next_correlation_id,
+			session_requests: &mut self.session_requests,
+			focus: &mut self.focus,
+			accessibility: &self.accessibility,
+			input_filters: &mut self.input_filters,
+			event_queue: &self.event_queue,
+			config: &mut self.config,
+			config_changes: &mut self.config_changes,
+			recreated_swapchains: &mut self.recreated_swapchains,
+			ready_sent: &mut self.ready_sent,
+			_marker: PhantomData,
+		};
+		f(&mut self.app, &mut ctx);
+	}
+}
+
+/// Main-loop counters accumulated over one reporting interval.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoopStatsSnapshot {
+	/// Wall-clock length of the interval.
+	pub interval: Duration,
+	/// Main-loop iterations.
+	pub iterations: u64,
+	/// Buffers acquired for rendering.
+	pub acquire_ok: u64,
+	/// Render attempts skipped because no buffer was free.
+	pub acquire_miss: u64,
+	/// Frames dropped by the application via [`Context::cancel_frame`].
+	pub frames_cancelled: u64,
+	/// Buffer requests accepted by the server.
+	pub request_ok: u64,
+	/// Buffer requests that failed.
+	pub request_err: u64,
+	/// Buffer release events received from the server.
+	pub buffer_releases: u64,
+	/// Release fences observed as signaled.
+	pub release_fences_signaled: u64,
+	/// `on_present` callbacks delivered.
+	pub presents: u64,
+}
+
+impl LoopStatsSnapshot {
+	/// Returns presented frames per second over the interval.
+	pub fn presents_per_second(&self) -> f64 {
+		let secs = self.interval.as_secs_f64();
+		if secs <= 0.0 {
+			return 0.0;
+		}
+		self.presents as f64 / secs
+	}
+}
+
+#[derive(Debug)]
+struct LoopStats {
+	enabled: bool,
+	interval: Duration,
+	last_log: Instant,
+	last: LoopStatsSnapshot,
+	iterations: u64,
+	acquire_ok: u64,
+	acquire_miss: u64,
+	frames_cancelled: u64,
+	request_ok: u64,
+	request_err: u64,
+	buffer_release_events: u64,
+	release_fence_signaled: u64,
+	present_callbacks: u64,
+}
+
+impl LoopStats {
+	fn new(interval: Duration) -> Self {
+		let enabled = std::env::var("TAB_APP_FRAMEWORK_TRACE")
+			.ok()
+			.map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+			.unwrap_or(false);
+		Self {
+			enabled,
+			interval,
+			last_log: Instant::now(),
+			last: LoopStatsSnapshot::default(),
+			iterations: 0,
+			acquire_ok: 0,
+			acquire_miss: 0,
+			frames_cancelled: 0,
+			request_ok: 0,
+			request_err: 0,
+			buffer_release_events: 0,
+			release_fence_signaled: 0,
+			present_callbacks: 0,
+		}
+	}
+
+	/// Closes the current interval once it has elapsed and returns its counters.
+	fn rollover(&mut self) -> Option<LoopStatsSnapshot> {
+		self.iterations += 1;
+		let elapsed = self.last_log.elapsed();
+		if elapsed < self.interval {
+			return None;
+		}
+		let snapshot = LoopStatsSnapshot {
+			interval: elapsed,
+			iterations: self.iterations,
+			acquire_ok: self.acquire_ok,
+			acquire_miss: self.acquire_miss,
+			frames_cancelled: self.frames_cancelled,
+			request_ok: self.request_ok,
+			request_err: self.request_err,
+			buffer_releases: self.buffer_release_events,
+			release_fences_signaled: self.release_fence_signaled,
+			presents: self.present_callbacks,
+		};
+		if self.enabled {
+			info!(
+				target: "tab_app_framework.core",
+				acquire_ok = self.acquire_ok,
+				acquire_miss = self.acquire_miss,
+				cancelled = self.frames_cancelled,
+				request_ok = self.request_ok,
+				request_err = self.request_err,
+				releases = self.buffer_release_events,
+				fence_ready = self.release_fence_signaled,
+				present = self.present_callbacks,
+				"taf stats"
+			);
+		}
+		self.last_log = Instant::now();
+		self.last = snapshot;
+		self.iterations = 0;
+		self.acquire_ok = 0;
+		self.acquire_miss = 0;
+		self.frames_cancelled = 0;
+		self.request_ok = 0;
+		self.request_err = 0;
+		self.buffer_release_events = 0;
+		self.release_fence_signaled = 0;
+		self.present_callbacks = 0;
+		Some(snapshot)
+	}
+}
+
+/// Time spent in each phase of one main-loop iteration.
+///
+/// Phases that did not run in an iteration stay at zero; `render` and
+/// `request_buffer` accumulate across every monitor rendered in it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameTimings {
+	/// Loop iteration this sample belongs to; matches the `frame` field on trace spans.
+	pub frame_id: u64,
+	/// Waiting in `poll(2)` for server messages, watched fds and release fences.
+	pub poll: Duration,
+	/// Reading server messages and delivering queued events to the application.
+	pub dispatch: Duration,
+	/// Application `on_render` callbacks.
+	pub render: Duration,
+	/// Sending `request_buffer` to the server.
+	pub request_buffer: Duration,
+	/// Checking release fences and recycling released buffers.
+	pub fence_wait: Duration,
+}
+
+#[derive(Debug)]
+struct FrameTracer {
+	enabled: bool,
+	current: FrameTimings,
+	last: FrameTimings,
+}
+
+impl FrameTracer {
+	fn new(enabled: bool) -> Self {
+		Self {
+			enabled,
+			current: FrameTimings::default(),
+			last: FrameTimings::default(),
+		}
+	}
+
+	fn finish_frame(&mut self) {
+		let next_id = self.current.frame_id + 1;
+		self.last = std::mem::replace(
+			&mut self.current,
+			FrameTimings {
+				frame_id: next_id,
+				..FrameTimings::default()
+			},
+		);
+	}
+}
+
+/// Input inactivity tracking behind [`Context::request_idle_notification`].
+#[derive(Debug)]
+struct IdleTracker {
+	timeout: Option<Duration>,
+	last_activity: Instant,
+	idle: bool,
+}
+
+impl IdleTracker {
+	fn new() -> Self {
+		Self {
+			timeout: None,
+			last_activity: Instant::now(),
+			idle: false,
+		}
+	}
+
+	fn request(&mut self, timeout: Duration) {
+		self.timeout = Some(timeout);
+		self.last_activity = Instant::now();
+		self.idle = false;
+	}
+
+	fn cancel(&mut self) {
+		self.timeout = None;
+		self.idle = false;
+	}
+
+	/// Notes an input event; returns the idle duration if this ends an idle period.
+	fn record_activity(&mut self) -> Option<Duration> {
+		let now = Instant::now();
+		let idle_for = now.duration_since(self.last_activity);
+		self.last_activity = now;
+		std::mem::take(&mut self.idle).then_some(idle_for)
+	}
+
+	/// Returns the idle duration when the timeout has just elapsed.
+	fn poll(&mut self) -> Option<Duration> {
+		let timeout = self.timeout?;
+		let idle_for = self.last_activity.elapsed();
+		if self.idle || idle_for < timeout {
+			return None;
+		}
+		self.idle = true;
+		Some(idle_for)
+	}
+
+	/// Poll timeout that wakes the loop when the idle deadline passes.
+	fn poll_timeout_ms(&self) -> i32 {
+		match self.timeout {
+			Some(timeout) if !self.idle => {
+				let remaining = timeout.saturating_sub(self.last_activity.elapsed());
+				remaining.as_micros().div_ceil(1000).min(i32::MAX as u128) as i32
+			}
+			_ => -1,
+		}
+	}
+}
+
+/// Keeps the session awake while alive; returned by [`Context::inhibit_idle`].
+///
+/// The inhibition is lifted once every guard has been dropped.
+#[derive(Debug)]
+pub struct IdleInhibitor {
+	id: u64,
+	registry: Rc<RefCell<IdleInhibitRegistry>>,
+}
+
+impl Drop for IdleInhibitor {
+	fn drop(&mut self) {
+		self
+			.registry
+			.borrow_mut()
+			.active
+			.retain(|(id, _)| *id != self.id);
+	}
+}
+
+/// Whether the session is the active one, and whether it holds the keyboard grab.
+#[derive(Debug, Default)]
+struct FocusState {
+	focused: bool,
+	keyboard_grab: bool,
+}
+
+/// Session requests sent with a [`RequestId`].
+#[derive(Debug, Default)]
+struct SessionRequests {
+	next_id: u64,
+	/// Switches not answered yet, with the session each one activates.
+	switches: BTreeMap<RequestId, String>,
+	/// Session creations not answered yet.
+	creates: BTreeSet<RequestId>,
+	/// Admin requests by the [`Context`] method that sent them.
+	///
+	/// The server only answers their rejections, so past [`SessionRequests::ADMIN_HISTORY`]
+	/// entries the oldest ones are forgotten and reported without a name.
+	admin: BTreeMap<RequestId, &'static str>,
+}
+
+impl SessionRequests {
+	const ADMIN_HISTORY: usize = 64;
+
+	fn next_id(&mut self) -> RequestId {
+		self.next_id += 1;
+		RequestId(self.next_id)
+	}
+
+	/// Allocates the id tagging an admin request sent by the `request` method.
+	fn admin(&mut self, request: &'static str) -> u64 {
+		let request_id = self.next_id();
+		self.admin.insert(request_id, request);
+		if self.admin.len() > Self::ADMIN_HISTORY {
+			self.admin.pop_first();
+		}
+		request_id.0
+	}
+
+	/// Takes the pending switches answered by `session_id` becoming active.
+	fn complete_switches(&mut self, session_id: &str) -> Vec<RequestId> {
+		let done = self
+			.switches
+			.iter()
+			.filter(|(_, target)| *target == session_id)
+			.map(|(request_id, _)| *request_id)
+			.collect::<Vec<_>>();
+		for request_id in &done {
+			self.switches.remove(request_id);
+		}
+		done
+	}
+}
+
+/// Global shortcuts the application registered and not yet released.
+#[derive(Debug, Default)]
+struct GlobalShortcuts {
+	next_id: u32,
+	registered: HashSet<GlobalShortcutId>,
+}
+
+impl GlobalShortcuts {
+	fn next_id(&mut self) -> GlobalShortcutId {
+		self.next_id += 1;
+		GlobalShortcutId(self.next_id)
+	}
+}
+
+/// Live [`IdleInhibitor`] guards and the inhibit state last sent to the server.
+#[derive(Debug, Default)]
+struct IdleInhibitRegistry {
+	next_id: u64,
+	active: Vec<(u64, String)>,
+	sent: Option<String>,
+}
+
+impl IdleInhibitRegistry {
+	/// Returns the reason to send when the combined state changed; `Some(None)` releases.
+	fn take_change(&mut self) -> Option<Option<String>> {
+		let wanted = (!self.active.is_empty()).then(|| {
+			self
+				.active
+				.iter()
+				.map(|(_, reason)| reason.as_str())
+				.collect::<Vec<_>>()
+				.join(", ")
+		});
+		if wanted == self.sent {
+			return None;
+		}
+		self.sent = wanted.clone();
+		Some(wanted)
+	}
+}
+
+/// Copies a preview out of the shm fd it arrived in.
+fn read_preview_pixels(fd: OwnedFd, len: usize) -> std::io::Result<Arc<[u8]>> {
+	use std::os::unix::fs::FileExt;
+	let mut pixels = vec![0; len];
+	// The sender's writes left the shared file offset at the end.
+	std::fs::File::from(fd).read_exact_at(&mut pixels, 0)?;
+	Ok(pixels.into())
+}
+
+/// Applies a `session_update` locally, for backends without a server.
+#[cfg(any(feature = "testing", feature = "nested"))]
+fn apply_session_update(
+	session: &mut SessionInfo,
+	display_name: Option<String>,
+	metadata: BTreeMap<String, Option<String>>,
+) {
+	if let Some(display_name) = display_name {
+		session.display_name = Some(display_name);
+	}
+	for (key, value) in metadata {
+		match value {
+			Some(value) => session.metadata.insert(key, value),
+			None => session.metadata.remove(&key),
+		};
+	}
+}
+
+/// Transport behind the runtime: a live server connection or the in-memory test backend.
+enum Connection {
+	Tab(TabClient),
+	#[cfg(feature = "testing")]
+	Mock(testing::MockConnection),
+	#[cfg(feature = "nested")]
+	Nested(Box<nested::NestedConnection>),
+}
+
+impl Connection {
+	fn session(&self) -> &SessionInfo {
+		match self {
+			Self::Tab(client) => client.session(),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.session(),
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => nested.session(),
+		}
+	}
+
+	fn clock(&self) -> &ClockSync {
+		match self {
+			Self::Tab(client) => client.clock(),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.clock(),
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => nested.clock(),
+		}
+	}
+
+	fn socket_fd(&self) -> RawFd {
+		match self {
+			Self::Tab(client) => client.socket_fd(),
+			// poll(2) ignores negative descriptors.
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => -1,
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => nested.socket_fd(),
+		}
+	}
+
+	fn register_extension<T, F>(&mut self, header_prefix: impl Into<String>, decoder: F)
+	where
+		T: std::any::Any,
+		F: Fn(&TabMessageFrame) -> Result<T, ProtocolError> + 'static,
+	{
+		match self {
+			Self::Tab(client) => client.register_extension(header_prefix, decoder),
+			// Extensions are server messages; the other backends have none to decode.
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => {}
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => {}
+		}
+	}
+
+	fn dispatch_events(&mut self) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.dispatch_events(),
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => Ok(()),
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => nested.dispatch_events(),
+		}
+	}
+
+	/// Moves up to `limit` events the Tab connection queued into `queue`.
+	fn take_events(&mut self, queue: &mut VecDeque<QueuedEvent>, limit: usize) {
+		match self {
+			Self::Tab(client) => {
+				let events = std::iter::from_fn(|| client.poll_event()).take(limit);
+				queue.extend(events.map(QueuedEvent::from));
+			}
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => {}
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => {}
+		}
+	}
+
+	/// Events left behind by [`Connection::take_events`].
+	fn queued_events(&self) -> usize {
+		match self {
+			Self::Tab(client) => client.queued_events(),
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => 0,
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => 0,
+		}
+	}
+
+	/// Sends a due keepalive ping; only a live server is pinged.
+	fn poll_keepalive(&mut self) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.poll_keepalive(),
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => Ok(()),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => Ok(()),
+		}
+	}
+
+	fn keepalive_deadline(&self) -> Option<Instant> {
+		match self {
+			Self::Tab(client) => client.keepalive_deadline(),
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => None,
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => None,
+		}
+	}
+
+	fn server_latency(&self) -> Option<Duration> {
+		match self {
+			Self::Tab(client) => client.server_latency(),
+			#[cfg(feature = "testing")]
+			Self::Mock(_) => None,
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => None,
+		}
+	}
+
+	/// True once the nested window was closed.
+	fn close_requested(&self) -> bool {
+		match self {
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => nested.close_requested(),
+			_ => false,
+		}
+	}
+
+	fn create_swapchain(&mut self, monitor_id: &str) -> Result<TabSwapchain, TabClientError> {
+		match self {
+			Self::Tab(client) => client.create_swapchain(monitor_id),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.create_swapchain(monitor_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => nested.create_swapchain(monitor_id),
+		}
+	}
+
+	fn create_shm_swapchain(&mut self, monitor_id: &str) -> Result<TabSwapchain, TabClientError> {
+		match self {
+			Self::Tab(client) => client.create_shm_swapchain(monitor_id),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.create_swapchain(monitor_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn send_buffer_request(
+		&mut self,
+		monitor_id: &str,
+		buffer: BufferIndex,
+		acquire_fence: Option<RawFd>,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.send_buffer_request(monitor_id, buffer, acquire_fence),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.send_buffer_request(monitor_id, buffer, acquire_fence),
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => nested.send_buffer_request(monitor_id, buffer, acquire_fence),
+		}
+	}
+
+	fn send_ready(&mut self) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.send_ready(),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.send_ready(),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => Ok(()),
+		}
+	}
+
+	fn update_session(
+		&mut self,
+		display_name: Option<String>,
+		metadata: BTreeMap<String, Option<String>>,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.update_session(display_name, metadata),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.update_session(display_name, metadata),
+			#[cfg(feature = "nested")]
+			Self::Nested(nested) => {
+				nested.update_session(display_name, metadata);
+				Ok(())
+			}
+		}
+	}
+
+	fn inject_input(
+		&mut self,
+		session_id: &str,
+		seat: SeatId,
+		event: InputEventPayload,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.inject_input(session_id, seat, event, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.inject_input(session_id, seat, event),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn set_keyboard_grab(&mut self, grab: bool) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_keyboard_grab(grab),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_keyboard_grab(grab),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn register_global_shortcut(
+		&mut self,
+		shortcut_id: &str,
+		keys: Vec<u32>,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.register_global_shortcut(shortcut_id, keys, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.register_global_shortcut(shortcut_id, keys),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn send_session_message(
+		&mut self,
+		session_id: &str,
+		data: Vec<u8>,
+		correlation_id: Option<u64>,
+		reply_to: Option<u64>,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.send_session_message(session_id, data, correlation_id, reply_to),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.send_session_message(session_id, data, correlation_id, reply_to),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn unregister_global_shortcut(
+		&mut self,
+		shortcut_id: &str,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.unregister_global_shortcut(shortcut_id, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.unregister_global_shortcut(shortcut_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn set_idle_inhibit(
+		&mut self,
+		inhibit: bool,
+		reason: Option<String>,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_idle_inhibit(inhibit, reason),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_idle_inhibit(inhibit, reason),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => Ok(()),
+		}
+	}
+
+	fn set_monitor_mode(
+		&mut self,
+		monitor_id: &str,
+		mode: MonitorMode,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_monitor_mode(monitor_id, mode, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_monitor_mode(monitor_id, mode),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn set_primary_monitor(
+		&mut self,
+		monitor_id: &str,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_primary_monitor(monitor_id, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_primary_monitor(monitor_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn set_monitor_brightness(
+		&mut self,
+		monitor_id: &str,
+		brightness: f32,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_monitor_brightness(monitor_id, brightness, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_monitor_brightness(monitor_id, brightness),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn set_gamma_lut(
+		&mut self,
+		monitor_id: &str,
+		ramps: Option<GammaRamps>,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_gamma_lut(monitor_id, ramps, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_gamma_lut(monitor_id, ramps),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
+
+	fn set_monitor_power(
+		&mut self,
+		monitor_id: &str,
+		power: MonitorPower,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_monitor_power(monitor_id, power, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_monitor_power(monitor_id, power),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
-		Ok(())
 	}
 
-	fn render_scheduled(&mut self) -> Result<(), FrameworkError> {
-		let targets: Vec<_> = self.scheduled.drain().collect();
-		for monitor_id in targets {
-			self.stats
-				.instant_log(&format!("render_scheduled begin monitor={monitor_id}"));
-			let Some((buffer_idx, render_ev)) = (|| {
-				let monitor_rt = self.monitors.get_mut(&monitor_id)?;
-				let (buffer, buffer_idx) = monitor_rt.swapchain.acquire_next()?;
-				self.stats.acquire_ok += 1;
-				let render_ev = RenderEvent {
-					monitor_id: monitor_id.clone(),
-					buffer_index: buffer_idx,
-					dmabuf_fd: buffer.fd(),
-					width: buffer.width(),
-					height: buffer.height(),
-					stride: buffer.stride(),
-					offset: buffer.offset(),
-					fourcc: buffer.fourcc(),
-				};
-				Some((buffer_idx, render_ev))
-			})() else {
-				self.stats.acquire_miss += 1;
-				continue;
-			};
-			self.next_acquire_fence = None;
-			self.call_app(|app, ctx| app.on_render(ctx, render_ev.clone()));
-			let acquire_fence = self
-				.next_acquire_fence
-				.as_ref()
-				.map(|fd| fd.as_raw_fd());
-			self.stats.instant_log(&format!(
-				"request_buffer send monitor={monitor_id} buffer={} fence={}",
-				buffer_idx as u8,
-				acquire_fence
-					.map(|fd| fd.to_string())
-					.unwrap_or_else(|| "none".to_string())
-			));
-
-				match self.client.request_buffer(&monitor_id, buffer_idx, acquire_fence) {
-					Ok(()) => {
-						self.stats.request_ok += 1;
-						self.stats.instant_log(&format!(
-							"request_buffer ack monitor={monitor_id} buffer={}",
-							buffer_idx as u8
-						));
-						if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
-							monitor_rt.swapchain.mark_busy(buffer_idx);
-							monitor_rt.pending_present[buffer_idx as usize] = true;
-						}
-						if self.render_mode == RenderMode::Eager {
-							// Keep requesting while another client-owned buffer exists.
-							// This avoids deadlocking on the first frame in double-buffering.
-							self.scheduled.insert(monitor_id.clone());
-						}
-					}
-				Err(err) => {
-					self.stats.request_err += 1;
-					self.stats.instant_log(&format!(
-						"request_buffer err monitor={monitor_id} buffer={} err={}",
-						buffer_idx as u8,
-						err
-					));
-					if let Some(monitor_rt) = self.monitors.get_mut(&monitor_id) {
-						monitor_rt.swapchain.rollback();
-					}
-					if self.render_mode == RenderMode::Eager {
-						let err_text = err.to_string();
-						let ownership_related = err_text.contains("ownership_violation")
-							|| err_text.contains("buffer_request_inflight")
-							|| err_text.contains("session_sleeping")
-							|| err_text.contains("not client-owned");
-						if !ownership_related {
-							self.scheduled.insert(monitor_id.clone());
-						}
-					}
-					let ferr: FrameworkError = err.into();
-					self.call_app(|app, ctx| app.on_error(ctx, &ferr));
-				}
-			}
+	fn set_accessibility(
+		&mut self,
+		settings: AccessibilitySettings,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_accessibility(settings, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_accessibility(settings),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
-		Ok(())
 	}
 
-	fn flush_pending_releases(&mut self) {
-		let mut errors = Vec::new();
-		let mut presents = Vec::new();
-		let mut ready_monitors = Vec::new();
-		for monitor_rt in self.monitors.values_mut() {
-			for buffer_idx in 0..monitor_rt.pending_release_fences.len() {
-				let Some(fence) = monitor_rt.pending_release_fences[buffer_idx].as_ref() else {
-					continue;
-				};
-				let signaled = match fd_readable_now(fence) {
-					Ok(v) => v,
-					Err(err) => {
-						errors.push(err);
-						true
-					}
-				};
-				if signaled {
-					monitor_rt.pending_release_fences[buffer_idx] = None;
-					self.stats.release_fence_signaled += 1;
-					let buffer = match buffer_idx {
-						0 => BufferIndex::Zero,
-						1 => BufferIndex::One,
-						_ => continue,
-					};
-					self.stats.instant_log(&format!(
-						"release_fence signaled monitor={} buffer={}",
-						monitor_rt.monitor.id, buffer_idx
-					));
-					monitor_rt.swapchain.mark_released(buffer);
-					if monitor_rt.pending_present[buffer_idx] {
-						monitor_rt.pending_present[buffer_idx] = false;
-						presents.push(PresentEvent {
-							monitor_id: monitor_rt.monitor.id.clone(),
-							buffer_index: buffer,
-						});
-					}
-					if self.render_mode == RenderMode::Eager {
-						ready_monitors.push(monitor_rt.monitor.id.clone());
-					}
-				}
-			}
+	fn set_safe_area(
+		&mut self,
+		monitor_id: &str,
+		occluded: Vec<Rect>,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_safe_area(monitor_id, occluded, Some(request_id)),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_safe_area(monitor_id, occluded),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
-		for monitor_id in ready_monitors {
-			self.scheduled.insert(monitor_id);
+	}
+
+	fn create_overlay(
+		&mut self,
+		overlay_id: &str,
+		monitor_id: &str,
+		size: (i32, i32),
+		position: (i32, i32),
+		z: i32,
+	) -> Result<TabSwapchain, TabClientError> {
+		match self {
+			Self::Tab(client) => client.create_overlay(overlay_id, monitor_id, size, position, z),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.create_overlay(overlay_id, monitor_id, size, position, z),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
-		for ev in presents {
-			self.stats.present_callbacks += 1;
-			self.call_app(|app, ctx| app.on_present(ctx, ev));
+	}
+
+	fn start_capture(
+		&mut self,
+		capture_id: &str,
+		source: CaptureSource,
+		size: (i32, i32),
+	) -> Result<TabSwapchain, TabClientError> {
+		match self {
+			Self::Tab(client) => client.start_capture(capture_id, source, size),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.start_capture(capture_id, source, size),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
-		for err in errors {
-			self.call_app(|app, ctx| app.on_error(ctx, &err));
+	}
+
+	fn release_capture_frame(
+		&mut self,
+		capture_id: &str,
+		buffer: BufferIndex,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.release_capture_frame(capture_id, buffer),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.release_capture_frame(capture_id, buffer),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
 	}
 
-	fn emit_cursor_move(&mut self, ev: PointerMoveEvent, also_mouse: bool) {
-		if ev.old_position == ev.new_position {
-			return;
+	fn stop_capture(&mut self, capture_id: &str) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.stop_capture(capture_id),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.stop_capture(capture_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
-		let mouse_ev = MouseMoveEvent {
-			device: ev.device,
-			time_usec: ev.time_usec,
-			old_position: ev.old_position,
-			new_position: ev.new_position,
-		};
-		self.call_app(|app, ctx| app.on_pointer_move(ctx, ev.clone()));
-		if also_mouse {
-			self.call_app(|app, ctx| app.on_mouse_move(ctx, mouse_ev));
+	}
+
+	fn move_overlay(&mut self, overlay_id: &str, position: (i32, i32)) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.move_overlay(overlay_id, position),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.move_overlay(overlay_id, position),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
 	}
 
-	fn emit_pointer_down(&mut self, ev: PointerDownEvent, also_mouse: bool) {
-		let mouse_ev = MouseDownEvent {
-			device: ev.device,
-			time_usec: ev.time_usec,
-			button: ev.button,
-			position: ev.position,
-		};
-		self.call_app(|app, ctx| app.on_pointer_down(ctx, ev));
-		if also_mouse {
-			self.call_app(|app, ctx| app.on_mouse_down(ctx, mouse_ev));
+	fn present_overlay(
+		&mut self,
+		overlay_id: &str,
+		buffer: BufferIndex,
+		acquire_fence: Option<RawFd>,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.present_overlay(overlay_id, buffer, acquire_fence),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.present_overlay(overlay_id, buffer, acquire_fence),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
 	}
 
-	fn emit_pointer_up(&mut self, ev: PointerUpEvent, also_mouse: bool) {
-		let mouse_ev = MouseUpEvent {
-			device: ev.device,
-			time_usec: ev.time_usec,
-			button: ev.button,
-			position: ev.position,
-		};
-		self.call_app(|app, ctx| app.on_pointer_up(ctx, ev));
-		if also_mouse {
-			self.call_app(|app, ctx| app.on_mouse_up(ctx, mouse_ev));
+	fn destroy_overlay(&mut self, overlay_id: &str) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.destroy_overlay(overlay_id),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.destroy_overlay(overlay_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
 	}
 
-	fn emit_touch(&mut self, ev: TouchEvent) {
-		self.call_app(|app, ctx| app.on_touch(ctx, ev));
+	fn set_scanout_hint(
+		&mut self,
+		monitor_id: &str,
+		fullscreen_opaque: bool,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_scanout_hint(monitor_id, fullscreen_opaque),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_scanout_hint(monitor_id, fullscreen_opaque),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => Ok(()),
+		}
 	}
 
-	fn emit_gesture(&mut self, ev: GestureEvent) {
-		self.call_app(|app, ctx| app.on_gesture(ctx, ev));
+	fn set_surface_colorspace(
+		&mut self,
+		monitor_id: &str,
+		colorspace: Colorspace,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.set_surface_colorspace(monitor_id, colorspace),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.set_surface_colorspace(monitor_id, colorspace),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => Ok(()),
+		}
 	}
 
-	fn call_app<F>(&mut self, f: F)
-	where
-		F: FnOnce(&mut A, &mut Context<A>),
-	{
-		let mut ctx = Context::<A> {
-			client: &mut self.client,
-			monitors: &mut self.monitors,
-			scheduled: &mut self.scheduled,
-			watched_fds: &mut self.watched_fds,
-			next_acquire_fence: &mut self.next_acquire_fence,
-			cursor_position: &mut self.cursor_position,
-			exiting: &mut self.exiting,
-			_marker: PhantomData,
-		};
-		f(&mut self.app, &mut ctx);
+	fn create_session(
+		&mut self,
+		role: SessionRole,
+		display_name: Option<String>,
+	) -> Result<SessionCreatedPayload, TabClientError> {
+		match self {
+			Self::Tab(client) => client.create_session(role, display_name),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.create_session(role, display_name),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
 	}
-}
 
-#[derive(Debug)]
-struct LoopStats {
-	enabled: bool,
-	last_log: Instant,
-	acquire_ok: u64,
-	acquire_miss: u64,
-	request_ok: u64,
-	request_err: u64,
-	buffer_release_events: u64,
-	release_fence_signaled: u64,
-	present_callbacks: u64,
-}
+	fn switch_session(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.switch_session(session_id, animation, duration),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.switch_session(session_id, animation, duration),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
+		}
+	}
 
-impl LoopStats {
-	fn new() -> Self {
-		let enabled = std::env::var("TAB_APP_FRAMEWORK_TRACE")
-			.ok()
-			.map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-			.unwrap_or(false);
-		Self {
-			enabled,
-			last_log: Instant::now(),
-			acquire_ok: 0,
-			acquire_miss: 0,
-			request_ok: 0,
-			request_err: 0,
-			buffer_release_events: 0,
-			release_fence_signaled: 0,
-			present_callbacks: 0,
+	fn request_session_create(
+		&mut self,
+		role: SessionRole,
+		display_name: Option<String>,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.request_session_create(role, display_name, request_id),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.request_session_create(role, display_name, request_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
 	}
 
-	fn maybe_log(&mut self) {
-		if !self.enabled || self.last_log.elapsed() < Duration::from_secs(1) {
-			return;
+	fn request_session_switch(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => {
+				client.request_session_switch(session_id, animation, duration, request_id)
+			}
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.request_session_switch(session_id, animation, duration, request_id),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
-		info!(
-			target: "tab_app_framework.core",
-			acquire_ok = self.acquire_ok,
-			acquire_miss = self.acquire_miss,
-			request_ok = self.request_ok,
-			request_err = self.request_err,
-			releases = self.buffer_release_events,
-			fence_ready = self.release_fence_signaled,
-			present = self.present_callbacks,
-			"taf stats"
-		);
-		self.last_log = Instant::now();
-		self.acquire_ok = 0;
-		self.acquire_miss = 0;
-		self.request_ok = 0;
-		self.request_err = 0;
-		self.buffer_release_events = 0;
-		self.release_fence_signaled = 0;
-		self.present_callbacks = 0;
 	}
 
-	fn instant_log(&self, msg: &str) {
-		if self.enabled {
-			debug!(target: "tab_app_framework.core", "{msg}");
+	fn request_session_preview(
+		&mut self,
+		session_id: &str,
+		max_width: i32,
+		max_height: i32,
+	) -> Result<(), TabClientError> {
+		match self {
+			Self::Tab(client) => client.request_session_preview(session_id, None, max_width, max_height),
+			#[cfg(feature = "testing")]
+			Self::Mock(mock) => mock.request_session_preview(session_id, max_width, max_height),
+			#[cfg(feature = "nested")]
+			Self::Nested(_) => nested::unsupported(),
 		}
 	}
 }
@@ -1654,16 +7244,157 @@ struct MonitorRuntime {
 	swapchain: TabSwapchain,
 	pending_release_fences: [Option<OwnedFd>; 2],
 	pending_present: [bool; 2],
+	inflight_request: Option<BufferIndex>,
+	/// When the in-flight request was sent, or last timed out.
+	inflight_since: Option<Instant>,
+	/// Consecutive ack timeouts, reset by the server's next answer.
+	ack_timeouts: u32,
+	redraw_after_ack: bool,
+	resized: bool,
+	/// Buffer held by the application in explicit submit mode.
+	acquired: Option<BufferIndex>,
+	/// Compositor chrome over the monitor, in physical pixels.
+	occluded: Vec<Rect>,
+	power: MonitorPower,
+	clear_policy: ClearPolicy,
+	retry_policy: RetryPolicy,
+	retry: RetryState,
+	/// Deadline set by [`Context::request_redraw_after`].
+	redraw_at: Option<Instant>,
+	/// Set by [`Context::create_shm_surface`]; resizes keep shm buffers.
+	shm_surface: bool,
+	/// Frame last submitted in each buffer, reported when that buffer is presented.
+	frame_tags: [FrameTag; 2],
+	/// First failed acquire since a buffer was last free.
+	starved_since: Option<Instant>,
+	/// Whether [`Application::on_starved`] fired for the current starvation period.
+	starvation_reported: bool,
+	/// Set by [`Context::pause_monitor`].
+	paused: bool,
+}
+
+/// Who holds a monitor's swapchain buffers, from [`Context::buffer_ownership`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BufferOwnership {
+	/// Buffers the client can render into, including an acquired one.
+	pub client_owned: u8,
+	/// Buffers submitted and not yet released by the server.
+	pub server_owned: u8,
+	/// Buffers released by the server whose release fence has not signaled yet.
+	pub awaiting_fence: u8,
+	/// Whether the application holds a buffer from [`Context::acquire_target`].
+	pub acquired: bool,
+	/// Time since the first failed acquire, while no buffer was free since.
+	pub starved_for: Option<Duration>,
+}
+
+/// Identity of a submitted frame, carried until its [`PresentEvent`].
+#[derive(Debug, Clone, Copy, Default)]
+struct FrameTag {
+	frame_id: u64,
+	user_data: Option<u64>,
 }
 
 impl MonitorRuntime {
-	fn new(monitor: Monitor, swapchain: TabSwapchain) -> Self {
+	fn new(monitor: Monitor, swapchain: TabSwapchain, clear_policy: ClearPolicy) -> Self {
 		Self {
 			monitor,
 			swapchain,
 			pending_release_fences: [None, None],
 			pending_present: [false, false],
+			inflight_request: None,
+			inflight_since: None,
+			ack_timeouts: 0,
+			redraw_after_ack: false,
+			resized: false,
+			acquired: None,
+			occluded: Vec::new(),
+			power: MonitorPower::On,
+			clear_policy,
+			retry_policy: RetryPolicy::default(),
+			retry: RetryState::default(),
+			redraw_at: None,
+			shm_surface: false,
+			frame_tags: [FrameTag::default(); 2],
+			starved_since: None,
+			starvation_reported: false,
+			paused: false,
+		}
+	}
+
+	/// Whether a buffer release should start the next eager frame.
+	///
+	/// Cuts a pending retry delay short when the policy allows it.
+	fn take_release_retry(&mut self) -> bool {
+		if self.paused {
+			return false;
+		}
+		if self.retry.retry_at.is_some() && !self.retry_policy.reschedule_on_release {
+			return false;
+		}
+		self.retry.retry_at = None;
+		true
+	}
+
+	/// Starts a starvation period on a failed acquire.
+	fn acquire_missed(&mut self) {
+		self.starved_since.get_or_insert_with(Instant::now);
+	}
+
+	/// Ends the starvation period once a buffer is free again.
+	fn end_starvation(&mut self) {
+		self.starved_since = None;
+		self.starvation_reported = false;
+	}
+
+	fn buffer_ownership(&self) -> BufferOwnership {
+		let mut ownership = BufferOwnership {
+			acquired: self.acquired.is_some(),
+			starved_for: self.starved_since.map(|since| since.elapsed()),
+			..BufferOwnership::default()
+		};
+		for buffer in [BufferIndex::Zero, BufferIndex::One] {
+			if self.pending_release_fences[buffer as usize].is_some() {
+				ownership.awaiting_fence += 1;
+			} else if self.swapchain.is_busy(buffer) {
+				ownership.server_owned += 1;
+			} else {
+				ownership.client_owned += 1;
+			}
 		}
+		ownership
+	}
+
+	fn safe_area(&self) -> SafeArea {
+		SafeArea::new(
+			&self.occluded,
+			self.monitor.physical_width,
+			self.monitor.physical_height,
+		)
+	}
+
+	fn start_request(&mut self, buffer: BufferIndex) {
+		self.inflight_request = Some(buffer);
+		self.inflight_since = Some(Instant::now());
+	}
+
+	/// Clears the in-flight request once the server answered it.
+	fn finish_request(&mut self) {
+		self.inflight_request = None;
+		self.inflight_since = None;
+		self.ack_timeouts = 0;
+	}
+
+	/// Swaps in a freshly linked swapchain and forgets state tied to the old buffers.
+	fn replace_swapchain(&mut self, swapchain: TabSwapchain) {
+		self.swapchain = swapchain;
+		self.pending_release_fences = [None, None];
+		self.pending_present = [false, false];
+		self.inflight_request = None;
+		self.inflight_since = None;
+		self.redraw_after_ack = false;
+		self.resized = true;
+		self.acquired = None;
 	}
 }
 
@@ -1673,16 +7404,154 @@ enum QueuedEvent {
 	Render(TabRenderEvent),
 	Input(TabInputEvent),
 	Session(tab_client::SessionEvent),
+	Extension(ExtensionEvent),
+}
+
+impl From<TabEvent> for QueuedEvent {
+	fn from(event: TabEvent) -> Self {
+		match event {
+			TabEvent::Monitor(ev) => Self::Monitor(ev),
+			TabEvent::Render(ev) => Self::Render(ev),
+			TabEvent::Input(ev) => Self::Input(ev),
+			TabEvent::Session(ev) => Self::Session(ev),
+			TabEvent::Extension(ev) => Self::Extension(ev),
+		}
+	}
+}
+
+/// Cursor position of a fresh seat: the center of the top-left monitor.
+fn initial_cursor(placements: &[MonitorPlacement]) -> (f64, f64) {
+	let seed = placements
+		.iter()
+		.min_by(|a, b| (a.x, a.y, a.id.as_str()).cmp(&(b.x, b.y, b.id.as_str())))
+		.map(|m| {
+			(
+				m.x as f64 + (m.width.max(1) as f64 / 2.0),
+				m.y as f64 + (m.height.max(1) as f64 / 2.0),
+			)
+		})
+		.unwrap_or((0.0, 0.0));
+	clamp_point_to_layout(placements, seed.0, seed.1)
+}
+
+fn clamp_idle_seats(placements: &[MonitorPlacement], seats: &mut HashMap<SeatId, SeatState>) {
+	for state in seats.values_mut() {
+		state.cursor_position =
+			clamp_point_to_layout(placements, state.cursor_position.0, state.cursor_position.1);
+	}
+}
+
+/// Removes up to `count` continuous input events, oldest first, and returns how many it
+/// removed.
+///
+/// Presses, releases and other state transitions are never dropped: losing a release
+/// would leave its key or button down.
+fn drop_oldest_input(queue: &mut VecDeque<QueuedEvent>, count: usize) -> usize {
+	let mut dropped = 0;
+	queue.retain(|event| {
+		let remove = dropped < count && is_continuous_input(event);
+		dropped += usize::from(remove);
+		!remove
+	});
+	dropped
+}
+
+/// Input a later event of the same kind supersedes: motion, scroll and axis updates.
+fn is_continuous_input(event: &QueuedEvent) -> bool {
+	let QueuedEvent::Input(TabInputEvent::Event { payload, .. }) = event else {
+		return false;
+	};
+	matches!(
+		payload,
+		InputEventPayload::PointerMotion { .. }
+			| InputEventPayload::PointerMotionAbsolute { .. }
+			| InputEventPayload::PointerAxis { .. }
+			| InputEventPayload::TouchMotion { .. }
+			| InputEventPayload::TabletToolAxis { .. }
+			| InputEventPayload::TablePadRing { .. }
+			| InputEventPayload::TablePadStrip { .. }
+			| InputEventPayload::GestureSwipeUpdate { .. }
+			| InputEventPayload::GesturePinchUpdate { .. }
+	)
+}
+
+/// Folds runs of relative pointer motion into one event per device.
+///
+/// Any other event ends a run, so motion is never reordered across buttons,
+/// keys or hotplug.
+fn coalesce_pointer_motion(queue: &mut VecDeque<QueuedEvent>) {
+	let mut merged = VecDeque::with_capacity(queue.len());
+	// Index into `merged` of each device's motion event in the current run.
+	let mut run: Vec<(u32, usize)> = Vec::new();
+	for event in queue.drain(..) {
+		let QueuedEvent::Input(TabInputEvent::Event {
+			payload:
+				InputEventPayload::PointerMotion {
+					device,
+					time_usec,
+					x,
+					y,
+					dx,
+					dy,
+					unaccel_dx,
+					unaccel_dy,
+				},
+			..
+		}) = event
+		else {
+			run.clear();
+			merged.push_back(event);
+			continue;
+		};
+		let existing = run
+			.iter()
+			.find(|(run_device, _)| *run_device == device)
+			.map(|(_, idx)| *idx);
+		if let Some(idx) = existing
+			&& let Some(QueuedEvent::Input(TabInputEvent::Event {
+				payload:
+					InputEventPayload::PointerMotion {
+						time_usec: prev_time,
+						x: prev_x,
+						y: prev_y,
+						dx: prev_dx,
+						dy: prev_dy,
+						unaccel_dx: prev_unaccel_dx,
+						unaccel_dy: prev_unaccel_dy,
+						..
+					},
+				..
+			})) = merged.get_mut(idx)
+		{
+			*prev_time = (*prev_time).max(time_usec);
+			*prev_x = x;
+			*prev_y = y;
+			*prev_dx += dx;
+			*prev_dy += dy;
+			*prev_unaccel_dx += unaccel_dx;
+			*prev_unaccel_dy += unaccel_dy;
+			continue;
+		}
+		run.push((device, merged.len()));
+		merged.push_back(event);
+	}
+	*queue = merged;
 }
 
 fn fd_readable_now(fd: &OwnedFd) -> Result<bool, FrameworkError> {
+	fd_readable(fd, Duration::ZERO)
+}
+
+/// Waits up to `timeout` for `fd` to become readable or hang up.
+fn fd_readable(fd: &OwnedFd, timeout: Duration) -> Result<bool, FrameworkError> {
+	let timeout_ms = timeout.as_millis().min(i32::MAX as u128) as i32;
 	let mut pfd = libc::pollfd {
 		fd: std::os::fd::AsRawFd::as_raw_fd(fd),
 		events: libc::POLLIN | libc::POLLERR | libc::POLLHUP,
 		revents: 0,
 	};
 	loop {
-		let rc = unsafe { libc::poll(&mut pfd as *mut libc::pollfd, 1, 0) };
+		let rc = unsafe { libc::poll(&mut pfd as *mut libc::pollfd, 1, timeout_ms) };
 		if rc > 0 {
 			return Ok(
 				(pfd.revents & (libc::POLLIN | libc::POLLERR | libc::POLLHUP | libc::POLLNVAL))
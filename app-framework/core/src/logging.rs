@@ -0,0 +1,21 @@
+use tracing_subscriber::util::{SubscriberInitExt, TryInitError};
+use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt};
+
+/// Installs a global subscriber that writes one JSON object per event to stdout.
+///
+/// Mirrors the shift server's subscriber: `RUST_LOG` selects the levels (default `info`), and
+/// each line carries the event's fields plus the enclosing `session`, `frame` and `input`
+/// spans, ready for a log ingestion pipeline. Fails if a global subscriber is already set.
+pub fn init_json_logging() -> Result<(), TryInitError> {
+	let env_filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+	Registry::default()
+		.with(env_filter)
+		.with(
+			tracing_subscriber::fmt::layer()
+				.json()
+				.with_current_span(true)
+				.with_span_list(true)
+				.with_ansi(false),
+		)
+		.try_init()
+}
@@ -0,0 +1,89 @@
+//! CPU access to render targets, for apps that render in software.
+
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::os::fd::{AsRawFd, BorrowedFd};
+
+use crate::RenderTarget;
+
+// `DMA_BUF_IOCTL_SYNC` and its flags from `linux/dma-buf.h`.
+const DMA_BUF_IOCTL_SYNC: u64 = 0x4008_6200;
+const DMA_BUF_SYNC_RW: u64 = 3;
+const DMA_BUF_SYNC_START: u64 = 0;
+const DMA_BUF_SYNC_END: u64 = 4;
+
+/// Render target pixels mapped into memory; unmapped on drop.
+///
+/// Dereferences to `stride * height` bytes starting at the first row. CPU caches are
+/// synchronized with the buffer when the mapping is created and dropped, so keep it
+/// only for the duration of the frame.
+pub struct MappedTarget<'a> {
+	map: *mut libc::c_void,
+	map_len: usize,
+	offset: usize,
+	len: usize,
+	fd: BorrowedFd<'a>,
+	_target: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> RenderTarget<'a> {
+	/// Maps the buffer for reading and writing.
+	///
+	/// Only linear buffers have a meaningful CPU layout; enable them with
+	/// [`crate::Config::set_linear_buffers`].
+	pub fn map(&self) -> std::io::Result<MappedTarget<'a>> {
+		let offset = self.offset as usize;
+		let len = self.stride as usize * self.height as usize;
+		let fd = self.dmabuf_fd();
+		let map_len = offset + len;
+		let map = unsafe {
+			libc::mmap(
+				std::ptr::null_mut(),
+				map_len,
+				libc::PROT_READ | libc::PROT_WRITE,
+				libc::MAP_SHARED,
+				fd.as_raw_fd(),
+				0,
+			)
+		};
+		if map == libc::MAP_FAILED {
+			return Err(std::io::Error::last_os_error());
+		}
+		sync(fd, DMA_BUF_SYNC_START);
+		Ok(MappedTarget {
+			map,
+			map_len,
+			offset,
+			len,
+			fd,
+			_target: PhantomData,
+		})
+	}
+}
+
+impl Deref for MappedTarget<'_> {
+	type Target = [u8];
+
+	fn deref(&self) -> &[u8] {
+		unsafe { std::slice::from_raw_parts(self.map.cast::<u8>().add(self.offset), self.len) }
+	}
+}
+
+impl DerefMut for MappedTarget<'_> {
+	fn deref_mut(&mut self) -> &mut [u8] {
+		unsafe { std::slice::from_raw_parts_mut(self.map.cast::<u8>().add(self.offset), self.len) }
+	}
+}
+
+impl Drop for MappedTarget<'_> {
+	fn drop(&mut self) {
+		sync(self.fd, DMA_BUF_SYNC_END);
+		unsafe { libc::munmap(self.map, self.map_len) };
+	}
+}
+
+/// Brackets CPU access; exporters without cache maintenance ignore it.
+fn sync(fd: BorrowedFd<'_>, flags: u64) {
+	let flags = flags | DMA_BUF_SYNC_RW;
+	unsafe { libc::ioctl(fd.as_raw_fd(), DMA_BUF_IOCTL_SYNC as _, &flags) };
+}
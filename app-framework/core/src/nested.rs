@@ -0,0 +1,471 @@
+//! Desktop-window backend for developing apps without a DRM seat.
+//!
+//! A single virtual monitor is presented into a winit window. Frames are rendered
+//! into linear GBM buffers as usual, then copied into the window with softbuffer;
+//! window input is translated into Tab input events.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, VecDeque};
+use std::num::NonZeroU32;
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use tab_client::{ClockSync, GbmAllocator, MonitorState, TabClientError, TabSwapchain};
+use tab_protocol::{
+	AxisOrientation, AxisSource, BufferIndex, ButtonState, InputEventPayload, KeyState, MonitorInfo,
+	SeatId, SessionInfo, SessionLifecycle, SessionRole,
+};
+use winit::application::ApplicationHandler;
+use winit::dpi::PhysicalSize;
+use winit::event::{ElementState, MouseButton, MouseScrollDelta, WindowEvent};
+use winit::event_loop::{ActiveEventLoop, EventLoop};
+use winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+use winit::platform::scancode::PhysicalKeyExtScancode;
+use winit::window::{Window, WindowId};
+
+use crate::{
+	Application, Config, Connection, FrameworkError, InitContext, QueuedEvent, TabAppFramework,
+	TabInputEvent, TabMonitorEvent, TabRenderEvent,
+};
+
+const NESTED_MONITOR_ID: &str = "nested-0";
+/// Device id reported for window pointer and keyboard input.
+const NESTED_DEVICE: u32 = 0;
+/// Pixels scrolled per wheel detent, matching libinput's default.
+const WHEEL_STEP: f64 = 15.0;
+/// Upper bound on how long a frame waits for its acquire fence.
+const FENCE_TIMEOUT_MS: i32 = 1000;
+
+impl<A: Application> TabAppFramework<A> {
+	/// Initializes the application inside a desktop window instead of a Tab server.
+	///
+	/// The window acts as one monitor; resizing it resizes the monitor. No session
+	/// token is needed and session management calls fail.
+	pub fn init_nested(configure: impl FnOnce(&mut Config)) -> Result<Self, FrameworkError> {
+		let mut init_ctx = InitContext::<A>::new(Config::from_token("nested"));
+		configure(init_ctx.config_mut());
+		let app = A::init(&mut init_ctx)
+			.map_err(|e| FrameworkError::Config(format!("app init failed: {e:#}")))?;
+		let cfg = init_ctx.config().clone();
+		let queue = Rc::new(RefCell::new(VecDeque::new()));
+		let nested = NestedConnection::open(&cfg, Rc::clone(&queue))?;
+		let initial_monitors = [nested.window.monitor.clone()];
+		Self::with_connection(
+			app,
+			&cfg,
+			Connection::Nested(Box::new(nested)),
+			queue,
+			&initial_monitors,
+		)
+	}
+}
+
+pub(crate) struct NestedConnection {
+	session: SessionInfo,
+	allocator: GbmAllocator,
+	linked: Option<[LinkedBuffer; 2]>,
+	event_loop: EventLoop<()>,
+	window: NestedWindow,
+	/// Window input is stamped with this process's own clock.
+	clock: ClockSync,
+}
+
+/// Duplicated descriptor and layout of a swapchain buffer, as the server would keep it.
+struct LinkedBuffer {
+	fd: OwnedFd,
+	width: i32,
+	height: i32,
+	stride: i32,
+	offset: i32,
+}
+
+impl NestedConnection {
+	fn open(
+		cfg: &Config,
+		events: Rc<RefCell<VecDeque<QueuedEvent>>>,
+	) -> Result<Self, FrameworkError> {
+		let allocator = GbmAllocator::new(cfg.render_node_path.as_deref(), None)?.with_linear_buffers();
+		let mut event_loop = EventLoop::new()
+			.map_err(|err| FrameworkError::Config(format!("nested event loop failed: {err}")))?;
+		let mut window = NestedWindow {
+			monitor: MonitorState::new(MonitorInfo {
+				id: NESTED_MONITOR_ID.into(),
+				width: 1280,
+				height: 720,
+				refresh_rate: 60,
+				name: "Nested".into(),
+				modes: Vec::new(),
+				color: None,
+				scale: 1.0,
+				primary: false,
+			}),
+			window: None,
+			surface: None,
+			events,
+			close_requested: false,
+		};
+		// Windows are created from the first `resumed` callback.
+		let deadline = Instant::now() + Duration::from_secs(5);
+		while window.surface.is_none() {
+			if Instant::now() > deadline {
+				return Err(FrameworkError::Config("nested window did not open".into()));
+			}
+			if let PumpStatus::Exit(code) =
+				event_loop.pump_app_events(Some(Duration::from_millis(10)), &mut window)
+			{
+				return Err(FrameworkError::Config(format!(
+					"nested event loop exited during startup ({code})"
+				)));
+			}
+		}
+		// The initial size is already part of the monitor; drop the startup resize.
+		window.events.borrow_mut().clear();
+		Ok(Self {
+			session: SessionInfo {
+				id: "nested-session".into(),
+				role: SessionRole::Admin,
+				display_name: Some("nested".into()),
+				state: SessionLifecycle::Occupied,
+				metadata: BTreeMap::new(),
+			},
+			allocator,
+			linked: None,
+			event_loop,
+			window,
+			clock: ClockSync::new(),
+		})
+	}
+
+	pub(crate) fn session(&self) -> &SessionInfo {
+		&self.session
+	}
+
+	pub(crate) fn clock(&self) -> &ClockSync {
+		&self.clock
+	}
+
+	/// There is no admin session to tell; the change only shows in `session()`.
+	pub(crate) fn update_session(
+		&mut self,
+		display_name: Option<String>,
+		metadata: BTreeMap<String, Option<String>>,
+	) {
+		crate::apply_session_update(&mut self.session, display_name, metadata);
+	}
+
+	pub(crate) fn socket_fd(&self) -> RawFd {
+		self.event_loop.as_raw_fd()
+	}
+
+	pub(crate) fn dispatch_events(&mut self) -> Result<(), TabClientError> {
+		if let PumpStatus::Exit(_) = self
+			.event_loop
+			.pump_app_events(Some(Duration::ZERO), &mut self.window)
+		{
+			self.window.close_requested = true;
+		}
+		Ok(())
+	}
+
+	/// True once the window was closed; the runtime then exits its loop.
+	pub(crate) fn close_requested(&self) -> bool {
+		self.window.close_requested
+	}
+
+	pub(crate) fn create_swapchain(
+		&mut self,
+		monitor_id: &str,
+	) -> Result<TabSwapchain, TabClientError> {
+		if monitor_id != NESTED_MONITOR_ID {
+			return Err(TabClientError::UnknownMonitor(monitor_id.to_string()));
+		}
+		let swapchain = self.allocator.create_swapchain(&self.window.monitor)?;
+		let link = |index: usize| -> Result<LinkedBuffer, TabClientError> {
+			let buffer = &swapchain.buffers[index];
+			let raw = unsafe { libc::fcntl(buffer.fd(), libc::F_DUPFD_CLOEXEC, 0) };
+			if raw < 0 {
+				return Err(std::io::Error::last_os_error().into());
+			}
+			Ok(LinkedBuffer {
+				fd: unsafe { OwnedFd::from_raw_fd(raw) },
+				width: buffer.width(),
+				height: buffer.height(),
+				stride: buffer.stride(),
+				offset: buffer.offset(),
+			})
+		};
+		self.linked = Some([link(0)?, link(1)?]);
+		Ok(swapchain)
+	}
+
+	/// Copies the buffer into the window, then acknowledges and releases it at once.
+	pub(crate) fn send_buffer_request(
+		&mut self,
+		monitor_id: &str,
+		buffer: BufferIndex,
+		acquire_fence: Option<RawFd>,
+	) -> Result<(), TabClientError> {
+		if monitor_id != NESTED_MONITOR_ID {
+			return Err(TabClientError::UnknownMonitor(monitor_id.to_string()));
+		}
+		let Some(linked) = &self.linked else {
+			return Err(TabClientError::Unexpected(
+				"buffer request before framebuffer link",
+			));
+		};
+		if let Some(fence) = acquire_fence {
+			wait_fence(fence)?;
+		}
+		self.window.present(&linked[buffer as usize])?;
+		let mut events = self.window.events.borrow_mut();
+		events.push_back(QueuedEvent::Render(TabRenderEvent::BufferRequestAcked {
+			monitor_id: monitor_id.to_string(),
+			buffer,
+		}));
+		events.push_back(QueuedEvent::Render(TabRenderEvent::BufferReleased {
+			monitor_id: monitor_id.to_string(),
+			buffer,
+			release_fence_fd: None,
+		}));
+		Ok(())
+	}
+}
+
+/// Error returned for session and output control, which only a real server provides.
+pub(crate) fn unsupported<T>() -> Result<T, TabClientError> {
+	Err(TabClientError::Unexpected(
+		"not supported by the nested backend",
+	))
+}
+
+struct NestedWindow {
+	monitor: MonitorState,
+	window: Option<Rc<Window>>,
+	surface: Option<softbuffer::Surface<Rc<Window>, Rc<Window>>>,
+	events: Rc<RefCell<VecDeque<QueuedEvent>>>,
+	close_requested: bool,
+}
+
+impl NestedWindow {
+	fn present(&mut self, buffer: &LinkedBuffer) -> Result<(), TabClientError> {
+		let Some(surface) = self.surface.as_mut() else {
+			return Ok(());
+		};
+		let len = buffer.offset as usize + buffer.stride as usize * buffer.height as usize;
+		let map = unsafe {
+			libc::mmap(
+				std::ptr::null_mut(),
+				len,
+				libc::PROT_READ,
+				libc::MAP_SHARED,
+				buffer.fd.as_raw_fd(),
+				0,
+			)
+		};
+		if map == libc::MAP_FAILED {
+			return Err(std::io::Error::last_os_error().into());
+		}
+		let pixels = unsafe { std::slice::from_raw_parts(map as *const u8, len) };
+		let result = copy_to_surface(surface, &self.monitor.info, buffer, pixels);
+		unsafe { libc::munmap(map, len) };
+		result
+	}
+
+	fn push_input(&self, payload: InputEventPayload) {
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Input(TabInputEvent::Event {
+				seat: SeatId::default(),
+				payload,
+			}));
+	}
+
+	fn time_usec(&self) -> u64 {
+		tab_client::monotonic_usec()
+	}
+
+	fn resize(&mut self, size: PhysicalSize<u32>) {
+		let (Some(width), Some(height)) = (NonZeroU32::new(size.width), NonZeroU32::new(size.height))
+		else {
+			return;
+		};
+		if let Some(surface) = self.surface.as_mut() {
+			let _ = surface.resize(width, height);
+		}
+		let info = &mut self.monitor.info;
+		if (info.width, info.height) == (size.width as i32, size.height as i32) {
+			return;
+		}
+		info.width = size.width as i32;
+		info.height = size.height as i32;
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Monitor(TabMonitorEvent::Changed(
+				self.monitor.clone(),
+			)));
+	}
+}
+
+impl ApplicationHandler for NestedWindow {
+	fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+		if self.window.is_some() {
+			return;
+		}
+		let attributes = Window::default_attributes()
+			.with_title("tab app (nested)")
+			.with_inner_size(PhysicalSize::new(
+				self.monitor.info.width as u32,
+				self.monitor.info.height as u32,
+			));
+		let window = match event_loop.create_window(attributes) {
+			Ok(window) => Rc::new(window),
+			Err(err) => {
+				tracing::error!(error = %err, "failed to create nested window");
+				event_loop.exit();
+				return;
+			}
+		};
+		let surface = softbuffer::Context::new(Rc::clone(&window))
+			.and_then(|context| softbuffer::Surface::new(&context, Rc::clone(&window)));
+		match surface {
+			Ok(surface) => self.surface = Some(surface),
+			Err(err) => {
+				tracing::error!(error = %err, "failed to create nested window surface");
+				event_loop.exit();
+				return;
+			}
+		}
+		let size = window.inner_size();
+		self.window = Some(window);
+		self.resize(size);
+	}
+
+	fn window_event(&mut self, _event_loop: &ActiveEventLoop, _id: WindowId, event: WindowEvent) {
+		let time_usec = self.time_usec();
+		match event {
+			WindowEvent::CloseRequested => self.close_requested = true,
+			WindowEvent::Resized(size) => self.resize(size),
+			WindowEvent::CursorMoved { position, .. } => {
+				self.push_input(InputEventPayload::PointerMotionAbsolute {
+					device: NESTED_DEVICE,
+					time_usec,
+					x: position.x,
+					y: position.y,
+					x_transformed: position.x,
+					y_transformed: position.y,
+				});
+			}
+			WindowEvent::MouseInput { state, button, .. } => {
+				let Some(button) = evdev_button(button) else {
+					return;
+				};
+				self.push_input(InputEventPayload::PointerButton {
+					device: NESTED_DEVICE,
+					time_usec,
+					button,
+					state: match state {
+						ElementState::Pressed => ButtonState::Pressed,
+						ElementState::Released => ButtonState::Released,
+					},
+				});
+			}
+			WindowEvent::MouseWheel { delta, .. } => {
+				// winit reports scrolling up as positive; libinput as negative.
+				let (dx, dy, discrete, source) = match delta {
+					MouseScrollDelta::LineDelta(x, y) => (
+						-x as f64 * WHEEL_STEP,
+						-y as f64 * WHEEL_STEP,
+						Some((-x as i32, -y as i32)),
+						AxisSource::Wheel,
+					),
+					MouseScrollDelta::PixelDelta(pos) => (-pos.x, -pos.y, None, AxisSource::Finger),
+				};
+				for (orientation, delta, delta_discrete) in [
+					(AxisOrientation::Horizontal, dx, discrete.map(|d| d.0)),
+					(AxisOrientation::Vertical, dy, discrete.map(|d| d.1)),
+				] {
+					if delta != 0.0 {
+						self.push_input(InputEventPayload::PointerAxis {
+							device: NESTED_DEVICE,
+							time_usec,
+							orientation,
+							delta,
+							delta_discrete,
+							source: source.clone(),
+						});
+					}
+				}
+			}
+			WindowEvent::KeyboardInput { event, .. } => {
+				// libinput reports no repeats; keyboard handling synthesizes them.
+				if event.repeat {
+					return;
+				}
+				let Some(key) = event.physical_key.to_scancode() else {
+					return;
+				};
+				self.push_input(InputEventPayload::Key {
+					device: NESTED_DEVICE,
+					time_usec,
+					key,
+					state: match event.state {
+						ElementState::Pressed => KeyState::Pressed,
+						ElementState::Released => KeyState::Released,
+					},
+				});
+			}
+			_ => {}
+		}
+	}
+}
+
+fn evdev_button(button: MouseButton) -> Option<u32> {
+	match button {
+		MouseButton::Left => Some(0x110),
+		MouseButton::Right => Some(0x111),
+		MouseButton::Middle => Some(0x112),
+		MouseButton::Back => Some(0x113),
+		MouseButton::Forward => Some(0x114),
+		MouseButton::Other(_) => None,
+	}
+}
+
+/// Copies XRGB8888 rows into the window surface, which is sized like the monitor.
+fn copy_to_surface(
+	surface: &mut softbuffer::Surface<Rc<Window>, Rc<Window>>,
+	surface_size: &MonitorInfo,
+	buffer: &LinkedBuffer,
+	pixels: &[u8],
+) -> Result<(), TabClientError> {
+	let mut target = surface
+		.buffer_mut()
+		.map_err(|err| TabClientError::Io(std::io::Error::other(err.to_string())))?;
+	let width = buffer.width.min(surface_size.width) as usize;
+	let height = buffer.height.min(surface_size.height) as usize;
+	for y in 0..height {
+		let row = buffer.offset as usize + y * buffer.stride as usize;
+		let dst = &mut target[y * surface_size.width as usize..][..width];
+		for (x, px) in dst.iter_mut().enumerate() {
+			let src = &pixels[row + x * 4..][..4];
+			*px = u32::from_le_bytes([src[0], src[1], src[2], 0]);
+		}
+	}
+	target
+		.present()
+		.map_err(|err| TabClientError::Io(std::io::Error::other(err.to_string())))
+}
+
+fn wait_fence(fence: RawFd) -> Result<(), TabClientError> {
+	let mut pollfd = libc::pollfd {
+		fd: fence,
+		events: libc::POLLIN,
+		revents: 0,
+	};
+	if unsafe { libc::poll(&mut pollfd, 1, FENCE_TIMEOUT_MS) } < 0 {
+		return Err(std::io::Error::last_os_error().into());
+	}
+	Ok(())
+}
@@ -0,0 +1,153 @@
+//! Overlay surfaces composited by shift above the session's frames.
+//!
+//! Each overlay owns a small swapchain that is updated independently of the monitor's
+//! main swapchain, e.g. for a volume OSD.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::os::fd::OwnedFd;
+
+use tab_client::TabSwapchain;
+use tab_protocol::BufferIndex;
+
+use crate::{FrameworkError, fd_readable_now};
+
+/// Handle to an overlay created with [`Context::create_overlay`](crate::Context::create_overlay).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct OverlayId(u32);
+
+impl fmt::Display for OverlayId {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "overlay-{}", self.0)
+	}
+}
+
+/// Overlay buffer acquired with [`Context::acquire_overlay`](crate::Context::acquire_overlay).
+///
+/// Hand it back with `submit_overlay` or `discard_overlay`; until then no other buffer
+/// of the overlay can be acquired.
+#[derive(Debug)]
+#[must_use = "acquired overlay frames must be submitted or discarded"]
+pub struct OverlayFrame {
+	pub(crate) overlay_id: OverlayId,
+	pub(crate) buffer_index: BufferIndex,
+	pub(crate) buffer_age: u32,
+}
+
+impl OverlayFrame {
+	/// Overlay the buffer belongs to.
+	pub fn overlay_id(&self) -> OverlayId {
+		self.overlay_id
+	}
+
+	/// Acquired swapchain buffer index.
+	pub fn buffer_index(&self) -> BufferIndex {
+		self.buffer_index
+	}
+
+	/// Same as [`RenderEvent::buffer_age`](crate::RenderEvent::buffer_age).
+	pub fn buffer_age(&self) -> u32 {
+		self.buffer_age
+	}
+}
+
+pub(crate) struct OverlayRuntime {
+	pub(crate) monitor_id: String,
+	pub(crate) swapchain: TabSwapchain,
+	pub(crate) acquired: Option<BufferIndex>,
+	release_fences: [Option<OwnedFd>; 2],
+}
+
+impl OverlayRuntime {
+	/// Hands buffers whose release fence signaled back to the swapchain.
+	fn collect_released(&mut self) {
+		for (idx, fence) in self.release_fences.iter_mut().enumerate() {
+			let signaled = fence
+				.as_ref()
+				.is_some_and(|fd| fd_readable_now(fd).unwrap_or(true));
+			if signaled {
+				*fence = None;
+				let buffer = if idx == 0 {
+					BufferIndex::Zero
+				} else {
+					BufferIndex::One
+				};
+				self.swapchain.mark_released(buffer);
+			}
+		}
+	}
+}
+
+/// Overlays of the connection, keyed by their framework-assigned id.
+#[derive(Default)]
+pub(crate) struct Overlays {
+	next_id: u32,
+	entries: HashMap<OverlayId, OverlayRuntime>,
+}
+
+impl Overlays {
+	pub(crate) fn next_id(&mut self) -> OverlayId {
+		self.next_id += 1;
+		OverlayId(self.next_id)
+	}
+
+	pub(crate) fn insert(&mut self, id: OverlayId, monitor_id: String, swapchain: TabSwapchain) {
+		self.entries.insert(
+			id,
+			OverlayRuntime {
+				monitor_id,
+				swapchain,
+				acquired: None,
+				release_fences: [None, None],
+			},
+		);
+	}
+
+	pub(crate) fn remove(&mut self, id: OverlayId) -> Option<OverlayRuntime> {
+		self.entries.remove(&id)
+	}
+
+	/// Forgets overlays of an unplugged monitor; shift drops them on its side.
+	pub(crate) fn remove_monitor(&mut self, monitor_id: &str) {
+		self
+			.entries
+			.retain(|_, overlay| overlay.monitor_id != monitor_id);
+	}
+
+	pub(crate) fn get(&self, id: OverlayId) -> Result<&OverlayRuntime, FrameworkError> {
+		self
+			.entries
+			.get(&id)
+			.ok_or(FrameworkError::OverlayNotFound(id))
+	}
+
+	pub(crate) fn get_mut(&mut self, id: OverlayId) -> Result<&mut OverlayRuntime, FrameworkError> {
+		let overlay = self
+			.entries
+			.get_mut(&id)
+			.ok_or(FrameworkError::OverlayNotFound(id))?;
+		overlay.collect_released();
+		Ok(overlay)
+	}
+
+	/// Handles a release sent by the server for the overlay with wire id `overlay_id`.
+	pub(crate) fn release(
+		&mut self,
+		overlay_id: &str,
+		buffer: BufferIndex,
+		release_fence: Option<OwnedFd>,
+	) {
+		let Some(overlay) = self
+			.entries
+			.iter_mut()
+			.find(|(id, _)| id.to_string() == overlay_id)
+			.map(|(_, overlay)| overlay)
+		else {
+			return;
+		};
+		match release_fence {
+			Some(fence) => overlay.release_fences[buffer as usize] = Some(fence),
+			None => overlay.swapchain.mark_released(buffer),
+		}
+	}
+}
@@ -0,0 +1,434 @@
+//! Python bindings for prototyping apps, built with the `python` feature.
+//!
+//! The module is named `tab_app_framework`; copy `libtab_app_framework_python.so`, built by
+//! the `tab-app-framework-python` crate, to `tab_app_framework.so` on the Python path. Apps
+//! are plain Python objects with optional `on_*` methods:
+//!
+//! ```python
+//! import tab_app_framework as taf
+//!
+//! class App:
+//!     def on_monitor_added(self, ctx, monitor):
+//!         ctx.schedule_frame(monitor.id)
+//!
+//!     def on_render(self, ctx, ev, pixels):
+//!         pixels[:] = b"\x20" * len(pixels)
+//!
+//! taf.Framework(App()).run()
+//! ```
+
+use std::ffi::c_char;
+
+use pyo3::IntoPyObjectExt;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+use crate::{
+	Application, Config, Context, FrameworkError, InitContext, KeyEvent, Monitor, MonitorAddedEvent,
+	MonitorRemovedEvent, PointerDownEvent, PointerMoveEvent, PointerType, PointerUpEvent,
+	RenderEvent, TabAppFramework,
+};
+
+/// Forwards framework callbacks to the Python app object.
+struct PyBridge {
+	app: Option<Py<PyAny>>,
+}
+
+impl PyBridge {
+	/// Returns true when the app defines `name`.
+	fn defines(&self, name: &str) -> bool {
+		self
+			.app
+			.as_ref()
+			.is_some_and(|app| Python::with_gil(|py| app.bind(py).hasattr(name).unwrap_or(false)))
+	}
+
+	/// Calls `name` on the app with a context valid for the duration of the call.
+	fn call(
+		&self,
+		ctx: &mut Context<Self>,
+		name: &str,
+		args: impl FnOnce(Python<'_>) -> PyResult<Vec<PyObject>>,
+	) {
+		let Some(app) = &self.app else {
+			return;
+		};
+		Python::with_gil(|py| {
+			let app = app.bind(py);
+			if !app.hasattr(name).unwrap_or(false) {
+				return;
+			}
+			let py_ctx = match Bound::new(py, PyContext::new(ctx)) {
+				Ok(py_ctx) => py_ctx,
+				Err(err) => return err.print(py),
+			};
+			let mut call_args = vec![py_ctx.clone().into_any().unbind()];
+			let result = args(py)
+				.and_then(|args| {
+					call_args.extend(args);
+					PyTuple::new(py, call_args)
+				})
+				.and_then(|args| app.call_method1(name, args));
+			if let Err(err) = result {
+				err.print(py);
+			}
+			py_ctx.borrow_mut().ctx = std::ptr::null_mut();
+		});
+	}
+}
+
+impl Application for PyBridge {
+	fn init(_ctx: &mut InitContext<Self>) -> anyhow::Result<Self> {
+		Ok(Self { app: None })
+	}
+
+	fn on_render(&mut self, ctx: &mut Context<Self>, ev: RenderEvent<'_>) {
+		// Mapping syncs the buffer with the GPU, which apps without `on_render` do not need.
+		if !self.defines("on_render") {
+			return;
+		}
+		let target = ev.target();
+		let event = PyRenderEvent {
+			monitor_id: ev.monitor_id().to_string(),
+			frame_id: ev.frame_id(),
+			width: ev.width(),
+			height: ev.height(),
+			stride: target.stride(),
+			fourcc: target.fourcc() as u32,
+			buffer_age: ev.buffer_age(),
+			resized: ev.resized(),
+		};
+		let mut mapped = match target.map() {
+			Ok(mapped) => mapped,
+			Err(err) => {
+				let err = FrameworkError::Client(err.into());
+				return self.on_error(ctx, &err);
+			}
+		};
+		let mut view = None;
+		self.call(ctx, "on_render", |py| {
+			let pixels = memory_view(py, &mut mapped);
+			view = pixels.as_ref().map(|pixels| pixels.clone_ref(py));
+			Ok(vec![
+				event.into_py_any(py)?,
+				pixels.unwrap_or_else(|| py.None()),
+			])
+		});
+		// The mapping goes away with `mapped`; later access raises instead of crashing.
+		if let Some(view) = view {
+			Python::with_gil(|py| {
+				let _ = view.call_method0(py, "release");
+			});
+		}
+	}
+
+	fn on_monitor_added(&mut self, ctx: &mut Context<Self>, ev: MonitorAddedEvent) {
+		self.call(ctx, "on_monitor_added", |py| {
+			Ok(vec![PyMonitor::from(&ev.monitor).into_py_any(py)?])
+		});
+	}
+
+	fn on_monitor_removed(&mut self, ctx: &mut Context<Self>, ev: MonitorRemovedEvent) {
+		self.call(ctx, "on_monitor_removed", |py| {
+			Ok(vec![ev.monitor_id.into_py_any(py)?])
+		});
+	}
+
+	fn on_key(&mut self, ctx: &mut Context<Self>, ev: KeyEvent) {
+		let event = PyKeyEvent {
+			device: ev.device,
+			time_usec: ev.time_usec,
+			key: ev.key,
+			pressed: ev.is_pressed(),
+		};
+		self.call(ctx, "on_key", |py| Ok(vec![event.into_py_any(py)?]));
+	}
+
+	fn on_pointer_move(&mut self, ctx: &mut Context<Self>, ev: PointerMoveEvent) {
+		let (dx, dy) = ev.delta();
+		let event = PyPointerEvent {
+			seat: ev.seat.0,
+			device: ev.device,
+			time_usec: ev.time_usec,
+			pointer_type: pointer_type_name(ev.pointer_type),
+			button: 0,
+			x: ev.new_position.0,
+			y: ev.new_position.1,
+			dx,
+			dy,
+		};
+		self.call(ctx, "on_pointer_move", |py| {
+			Ok(vec![event.into_py_any(py)?])
+		});
+	}
+
+	fn on_pointer_down(&mut self, ctx: &mut Context<Self>, ev: PointerDownEvent) {
+		let event = PyPointerEvent::button(
+			ev.seat.0,
+			ev.device,
+			ev.time_usec,
+			ev.pointer_type,
+			ev.button,
+			ev.position,
+		);
+		self.call(ctx, "on_pointer_down", |py| {
+			Ok(vec![event.into_py_any(py)?])
+		});
+	}
+
+	fn on_pointer_up(&mut self, ctx: &mut Context<Self>, ev: PointerUpEvent) {
+		let event = PyPointerEvent::button(
+			ev.seat.0,
+			ev.device,
+			ev.time_usec,
+			ev.pointer_type,
+			ev.button,
+			ev.position,
+		);
+		self.call(ctx, "on_pointer_up", |py| Ok(vec![event.into_py_any(py)?]));
+	}
+
+	fn on_error(&mut self, ctx: &mut Context<Self>, error: &FrameworkError) {
+		let message = error.to_string();
+		self.call(ctx, "on_error", |py| Ok(vec![message.into_py_any(py)?]));
+	}
+}
+
+/// Wraps the mapped pixels in a writable memoryview without copying.
+fn memory_view(py: Python<'_>, pixels: &mut [u8]) -> Option<PyObject> {
+	let view = unsafe {
+		pyo3::ffi::PyMemoryView_FromMemory(
+			pixels.as_mut_ptr() as *mut c_char,
+			pixels.len() as pyo3::ffi::Py_ssize_t,
+			pyo3::ffi::PyBUF_WRITE,
+		)
+	};
+	unsafe { PyObject::from_owned_ptr_or_opt(py, view) }
+}
+
+fn pointer_type_name(pointer_type: PointerType) -> &'static str {
+	match pointer_type {
+		PointerType::Mouse => "mouse",
+		PointerType::Pen => "pen",
+		PointerType::Touch => "touch",
+		PointerType::Unknown => "unknown",
+	}
+}
+
+/// Framework access passed to every callback; raises once the callback returned.
+#[pyclass(name = "Context", unsendable)]
+struct PyContext {
+	ctx: *mut Context<'static, PyBridge>,
+}
+
+impl PyContext {
+	fn new(ctx: &mut Context<PyBridge>) -> Self {
+		Self {
+			ctx: (ctx as *mut Context<PyBridge>).cast(),
+		}
+	}
+
+	fn get(&mut self) -> PyResult<&mut Context<'static, PyBridge>> {
+		unsafe { self.ctx.as_mut() }
+			.ok_or_else(|| PyRuntimeError::new_err("context used outside of its callback"))
+	}
+}
+
+#[pymethods]
+impl PyContext {
+	fn schedule_frame(&mut self, monitor_id: String) -> PyResult<()> {
+		self.get()?.schedule_frame(monitor_id);
+		Ok(())
+	}
+
+	fn schedule_all_frames(&mut self) -> PyResult<()> {
+		self.get()?.schedule_all_frames();
+		Ok(())
+	}
+
+	fn request_exit(&mut self) -> PyResult<()> {
+		self.get()?.request_exit();
+		Ok(())
+	}
+
+	fn cancel_frame(&mut self) -> PyResult<()> {
+		self.get()?.cancel_frame();
+		Ok(())
+	}
+
+	fn send_ready(&mut self) -> PyResult<()> {
+		self
+			.get()?
+			.session_ready()
+			.map_err(|err| PyRuntimeError::new_err(err.to_string()))
+	}
+
+	fn monitors(&mut self) -> PyResult<Vec<PyMonitor>> {
+		Ok(self.get()?.monitors().map(PyMonitor::from).collect())
+	}
+
+	fn cursor_position(&mut self) -> PyResult<(f64, f64)> {
+		Ok(self.get()?.cursor_position())
+	}
+}
+
+/// Runs a Python app object; see the module docs for the callbacks.
+#[pyclass(name = "Framework", unsendable)]
+struct PyFramework {
+	inner: TabAppFramework<PyBridge>,
+}
+
+#[pymethods]
+impl PyFramework {
+	/// Connects with `token`, or with the environment like `TabAppFramework::init` when
+	/// omitted. Buffers are always linear so `on_render` can write pixels.
+	#[new]
+	#[pyo3(signature = (app, token = None, socket_path = None))]
+	fn new(app: Py<PyAny>, token: Option<String>, socket_path: Option<String>) -> PyResult<Self> {
+		let configure = |config: &mut Config| {
+			config.set_linear_buffers(true);
+			if let Some(path) = &socket_path {
+				config.set_socket_path(path);
+			}
+		};
+		let result = match token {
+			Some(token) => {
+				let mut config = Config::from_token(token);
+				configure(&mut config);
+				TabAppFramework::<PyBridge>::init_with_config(config)
+			}
+			None => TabAppFramework::init(configure),
+		};
+		let mut inner = result.map_err(|err| PyValueError::new_err(err.to_string()))?;
+		inner.app_mut().app = Some(app);
+		Ok(Self { inner })
+	}
+
+	/// Runs the loop until an app requests exit; the GIL stays held.
+	fn run(&mut self) -> PyResult<()> {
+		self
+			.inner
+			.run()
+			.map_err(|err| PyRuntimeError::new_err(err.to_string()))
+	}
+}
+
+#[pyclass(name = "RenderEvent", get_all, frozen)]
+#[derive(Debug)]
+struct PyRenderEvent {
+	monitor_id: String,
+	frame_id: u64,
+	width: i32,
+	height: i32,
+	/// Bytes per row of the pixel buffer.
+	stride: i32,
+	/// DRM fourcc, e.g. XRGB8888 (`XR24`) stored as little-endian B, G, R, X bytes.
+	fourcc: u32,
+	buffer_age: u32,
+	resized: bool,
+}
+
+#[pyclass(name = "Monitor", get_all, frozen)]
+#[derive(Debug)]
+struct PyMonitor {
+	id: String,
+	name: String,
+	width: i32,
+	height: i32,
+	refresh_rate: i32,
+	x: i32,
+	y: i32,
+	scale: f64,
+}
+
+impl From<&Monitor> for PyMonitor {
+	fn from(monitor: &Monitor) -> Self {
+		Self {
+			id: monitor.id.clone(),
+			name: monitor.name.clone(),
+			width: monitor.width,
+			height: monitor.height,
+			refresh_rate: monitor.refresh_rate,
+			x: monitor.x,
+			y: monitor.y,
+			scale: monitor.scale,
+		}
+	}
+}
+
+#[pyclass(name = "KeyEvent", get_all, frozen)]
+#[derive(Debug)]
+struct PyKeyEvent {
+	device: u32,
+	time_usec: u64,
+	/// Linux keycode.
+	key: u32,
+	pressed: bool,
+}
+
+/// Pointer move, down or up; `button` is 0 and `dx`/`dy` are set only for moves.
+#[pyclass(name = "PointerEvent", get_all, frozen)]
+#[derive(Debug)]
+struct PyPointerEvent {
+	seat: String,
+	device: u32,
+	time_usec: u64,
+	/// `"mouse"`, `"pen"`, `"touch"` or `"unknown"`.
+	pointer_type: &'static str,
+	button: u32,
+	x: f64,
+	y: f64,
+	dx: f64,
+	dy: f64,
+}
+
+impl PyPointerEvent {
+	fn button(
+		seat: String,
+		device: u32,
+		time_usec: u64,
+		pointer_type: PointerType,
+		button: u32,
+		position: (f64, f64),
+	) -> Self {
+		Self {
+			seat,
+			device,
+			time_usec,
+			pointer_type: pointer_type_name(pointer_type),
+			button,
+			x: position.0,
+			y: position.1,
+			dx: 0.0,
+			dy: 0.0,
+		}
+	}
+}
+
+macro_rules! py_repr {
+	($($ty:ty),*) => {
+		$(
+			#[pymethods]
+			impl $ty {
+				fn __repr__(&self) -> String {
+					format!("{self:?}").replacen("Py", "", 1)
+				}
+			}
+		)*
+	};
+}
+
+py_repr!(PyRenderEvent, PyMonitor, PyKeyEvent, PyPointerEvent);
+
+#[pymodule]
+#[pyo3(name = "tab_app_framework")]
+fn tab_app_framework(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	m.add_class::<PyFramework>()?;
+	m.add_class::<PyContext>()?;
+	m.add_class::<PyRenderEvent>()?;
+	m.add_class::<PyMonitor>()?;
+	m.add_class::<PyKeyEvent>()?;
+	m.add_class::<PyPointerEvent>()?;
+	Ok(())
+}
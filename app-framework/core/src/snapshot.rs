@@ -0,0 +1,191 @@
+//! Serializable dump of the framework state for bug reports.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+use tab_protocol::{BufferIndex, SeatId};
+
+use crate::{MonitorRuntime, SeatState, sorted_monitors};
+
+/// Framework state returned by [`crate::Context::debug_snapshot`].
+///
+/// Serializes with `serde`, e.g. to JSON for attaching to a bug report.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DebugSnapshot {
+	/// Monitors ordered by layout position, then id.
+	pub monitors: Vec<MonitorSnapshot>,
+	/// Cursor and touch contacts of every seat seen, the active seat first.
+	pub seats: Vec<SeatSnapshot>,
+	/// Monitors with a frame scheduled, sorted by id.
+	pub scheduled: Vec<String>,
+	/// Buffers released by the server whose release fence has not signaled yet.
+	pub pending_fences: usize,
+}
+
+/// One monitor of a [`DebugSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonitorSnapshot {
+	pub id: String,
+	pub name: String,
+	/// Origin in global layout space.
+	pub x: i32,
+	pub y: i32,
+	/// Logical size.
+	pub width: i32,
+	pub height: i32,
+	pub scale: f64,
+	pub refresh_rate: i32,
+	/// Whether rendering is paused with [`crate::Context::pause_monitor`].
+	pub paused: bool,
+	pub swapchain: SwapchainSnapshot,
+}
+
+/// Swapchain and frame state of a [`MonitorSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SwapchainSnapshot {
+	/// Buffer size in physical pixels.
+	pub width: i32,
+	pub height: i32,
+	/// States of buffers 0 and 1.
+	pub buffers: [BufferState; 2],
+	/// Buffer of the request awaiting the server's ack.
+	pub inflight_request: Option<u8>,
+	/// Buffer held through [`crate::Context::acquire_target`].
+	pub acquired: Option<u8>,
+	/// Whether a frame is rendered once the in-flight request is acked.
+	pub redraw_after_ack: bool,
+	/// Consecutive ack timeouts.
+	pub ack_timeouts: u32,
+}
+
+/// Owner of a swapchain buffer in a [`SwapchainSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BufferState {
+	/// Free for the next frame.
+	Free,
+	/// Held by the application through [`crate::Context::acquire_target`].
+	Acquired,
+	/// Submitted and not yet released by the server.
+	Server,
+	/// Released by the server, waiting for its release fence.
+	AwaitingFence,
+}
+
+/// One seat of a [`DebugSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SeatSnapshot {
+	pub seat: SeatId,
+	/// Cursor position in global layout space.
+	pub cursor_position: (f64, f64),
+	/// Touch contacts down, sorted by id.
+	pub touch_contacts: Vec<TouchContactSnapshot>,
+}
+
+/// Touch contact of a [`SeatSnapshot`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TouchContactSnapshot {
+	pub id: i32,
+	/// Position in global layout space.
+	pub position: (f64, f64),
+}
+
+impl DebugSnapshot {
+	/// Captures the state of the active seat, passed in separately, and the other seats.
+	pub(crate) fn capture(
+		monitors: &HashMap<String, MonitorRuntime>,
+		scheduled: &HashSet<String>,
+		active_seat: &SeatId,
+		cursor_position: (f64, f64),
+		touch_contacts: &HashMap<i32, (f64, f64)>,
+		other_seats: &HashMap<SeatId, SeatState>,
+	) -> Self {
+		let mut seats = vec![SeatSnapshot::new(
+			active_seat,
+			cursor_position,
+			touch_contacts,
+		)];
+		let mut others: Vec<_> = other_seats.iter().collect();
+		others.sort_by(|a, b| a.0.cmp(b.0));
+		for (seat, state) in others {
+			seats.push(SeatSnapshot::new(
+				seat,
+				state.cursor_position,
+				&state.touch_contacts,
+			));
+		}
+		let mut scheduled: Vec<_> = scheduled.iter().cloned().collect();
+		scheduled.sort();
+		Self {
+			monitors: sorted_monitors(monitors)
+				.into_iter()
+				.filter_map(|monitor| monitors.get(&monitor.id))
+				.map(MonitorSnapshot::new)
+				.collect(),
+			seats,
+			scheduled,
+			pending_fences: monitors
+				.values()
+				.flat_map(|monitor_rt| &monitor_rt.pending_release_fences)
+				.filter(|fence| fence.is_some())
+				.count(),
+		}
+	}
+}
+
+impl MonitorSnapshot {
+	fn new(monitor_rt: &MonitorRuntime) -> Self {
+		let monitor = &monitor_rt.monitor;
+		let (width, height) = monitor_rt.swapchain.size();
+		let buffer_state = |buffer: BufferIndex| {
+			if monitor_rt.acquired == Some(buffer) {
+				BufferState::Acquired
+			} else if monitor_rt.pending_release_fences[buffer as usize].is_some() {
+				BufferState::AwaitingFence
+			} else if monitor_rt.swapchain.is_busy(buffer) {
+				BufferState::Server
+			} else {
+				BufferState::Free
+			}
+		};
+		Self {
+			id: monitor.id.clone(),
+			name: monitor.name.clone(),
+			x: monitor.x,
+			y: monitor.y,
+			width: monitor.width,
+			height: monitor.height,
+			scale: monitor.scale,
+			refresh_rate: monitor.refresh_rate,
+			paused: monitor_rt.paused,
+			swapchain: SwapchainSnapshot {
+				width,
+				height,
+				buffers: [BufferIndex::Zero, BufferIndex::One].map(buffer_state),
+				inflight_request: monitor_rt.inflight_request.map(|buffer| buffer as u8),
+				acquired: monitor_rt.acquired.map(|buffer| buffer as u8),
+				redraw_after_ack: monitor_rt.redraw_after_ack,
+				ack_timeouts: monitor_rt.ack_timeouts,
+			},
+		}
+	}
+}
+
+impl SeatSnapshot {
+	fn new(
+		seat: &SeatId,
+		cursor_position: (f64, f64),
+		touch_contacts: &HashMap<i32, (f64, f64)>,
+	) -> Self {
+		let mut touch_contacts: Vec<_> = touch_contacts
+			.iter()
+			.map(|(&id, &position)| TouchContactSnapshot { id, position })
+			.collect();
+		touch_contacts.sort_by_key(|contact| contact.id);
+		Self {
+			seat: seat.clone(),
+			cursor_position,
+			touch_contacts,
+		}
+	}
+}
@@ -0,0 +1,202 @@
+//! Touchpad swipe tracking for session switchers in admin shells.
+
+use std::time::Duration;
+
+use crate::{FocusEvent, GestureEvent};
+
+/// Session switch the application should request, e.g. with
+/// [`Context::switch_session_async`](crate::Context::switch_session_async).
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionSwitch {
+	/// Session to switch to.
+	pub session_id: String,
+	/// Server-side switch animation, e.g. `slide_left`.
+	pub animation: Option<String>,
+	/// Remaining animation time: the configured duration scaled by the part of the
+	/// swipe the fingers did not cover.
+	pub duration: Duration,
+}
+
+/// What a gesture passed to [`SessionSwitchController::handle_gesture`] changed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwitchGesture {
+	/// The swipe moved towards `target`; `progress` runs from 0 to 1 over the configured
+	/// swipe distance, for previews that follow the fingers.
+	Progress { target: String, progress: f64 },
+	/// The swipe ended past the commit threshold; request the switch.
+	Commit(SessionSwitch),
+	/// The swipe ended short of the threshold, was cancelled, or lost its target.
+	Cancel,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Swipe {
+	device: u32,
+	/// Horizontal distance travelled since the swipe began.
+	offset: f64,
+}
+
+/// Turns horizontal multi-finger swipes into switches between neighbouring sessions.
+///
+/// The application keeps the controller informed about the sessions to cycle through
+/// ([`SessionSwitchController::set_sessions`]) and the active one
+/// ([`SessionSwitchController::track_focus`]), and feeds it every [`GestureEvent`].
+/// Swiping left moves to the next session in the list, swiping right to the previous
+/// one. Swipes with another finger count are ignored.
+#[derive(Debug, Clone)]
+pub struct SessionSwitchController {
+	sessions: Vec<String>,
+	active: Option<String>,
+	fingers: u32,
+	swipe_distance: f64,
+	commit_threshold: f64,
+	animation: Option<String>,
+	duration: Duration,
+	swipe: Option<Swipe>,
+}
+
+impl Default for SessionSwitchController {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl SessionSwitchController {
+	/// Creates a controller for three-finger swipes over 300 px, committing past half way
+	/// with a 250 ms `slide_left` animation.
+	pub fn new() -> Self {
+		Self {
+			sessions: Vec::new(),
+			active: None,
+			fingers: 3,
+			swipe_distance: 300.0,
+			commit_threshold: 0.5,
+			animation: Some("slide_left".into()),
+			duration: Duration::from_millis(250),
+			swipe: None,
+		}
+	}
+
+	/// Sets the finger count of the swipes that switch sessions.
+	pub fn set_fingers(&mut self, fingers: u32) -> &mut Self {
+		self.fingers = fingers;
+		self
+	}
+
+	/// Sets the swipe distance, in touchpad units, that covers a whole switch.
+	///
+	/// Non-positive distances are ignored.
+	pub fn set_swipe_distance(&mut self, distance: f64) -> &mut Self {
+		if distance > 0.0 {
+			self.swipe_distance = distance;
+		}
+		self
+	}
+
+	/// Sets the progress, between 0 and 1, past which releasing the fingers commits.
+	pub fn set_commit_threshold(&mut self, threshold: f64) -> &mut Self {
+		self.commit_threshold = threshold.clamp(0.0, 1.0);
+		self
+	}
+
+	/// Sets the server-side animation and its full duration used for committed switches.
+	pub fn set_animation(&mut self, animation: Option<String>, duration: Duration) -> &mut Self {
+		self.animation = animation;
+		self.duration = duration;
+		self
+	}
+
+	/// Replaces the sessions to cycle through, in switcher order.
+	pub fn set_sessions<I, S>(&mut self, sessions: I)
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.sessions = sessions.into_iter().map(Into::into).collect();
+	}
+
+	/// Records the active session; call from `on_focus_gained` and `on_focus_lost`.
+	pub fn track_focus(&mut self, ev: &FocusEvent) {
+		self.set_active_session(ev.active_session_id.clone());
+	}
+
+	/// Records the active session.
+	pub fn set_active_session(&mut self, session_id: impl Into<String>) {
+		self.active = Some(session_id.into());
+	}
+
+	/// Returns true while a switching swipe is in progress.
+	pub fn is_tracking(&self) -> bool {
+		self.swipe.is_some()
+	}
+
+	/// Feeds a gesture; returns what changed for the switcher, if anything.
+	pub fn handle_gesture(&mut self, ev: &GestureEvent) -> Option<SwitchGesture> {
+		match *ev {
+			GestureEvent::SwipeBegin {
+				device, fingers, ..
+			} => {
+				self.swipe = (fingers == self.fingers).then_some(Swipe {
+					device,
+					offset: 0.0,
+				});
+				None
+			}
+			GestureEvent::SwipeUpdate {
+				device,
+				fingers,
+				dx,
+				..
+			} => {
+				let swipe = self.swipe.as_mut().filter(|swipe| swipe.device == device)?;
+				if fingers != self.fingers {
+					self.swipe = None;
+					return Some(SwitchGesture::Cancel);
+				}
+				swipe.offset += dx;
+				let offset = swipe.offset;
+				let target = self.target(offset)?;
+				Some(SwitchGesture::Progress {
+					target: target.to_string(),
+					progress: self.progress(offset),
+				})
+			}
+			GestureEvent::SwipeEnd {
+				device, cancelled, ..
+			} => {
+				let swipe = self.swipe.filter(|swipe| swipe.device == device)?;
+				self.swipe = None;
+				let progress = self.progress(swipe.offset);
+				match self.target(swipe.offset) {
+					Some(target) if !cancelled && progress >= self.commit_threshold => {
+						Some(SwitchGesture::Commit(SessionSwitch {
+							session_id: target.to_string(),
+							animation: self.animation.clone(),
+							duration: self.duration.mul_f64(1.0 - progress),
+						}))
+					}
+					_ => Some(SwitchGesture::Cancel),
+				}
+			}
+			_ => None,
+		}
+	}
+
+	fn progress(&self, offset: f64) -> f64 {
+		(offset.abs() / self.swipe_distance).min(1.0)
+	}
+
+	/// Neighbour of the active session in the direction of `offset`.
+	fn target(&self, offset: f64) -> Option<&str> {
+		let active = self.active.as_deref()?;
+		let index = self.sessions.iter().position(|id| id == active)?;
+		let target = if offset < 0.0 {
+			index.checked_add(1)
+		} else if offset > 0.0 {
+			index.checked_sub(1)
+		} else {
+			None
+		}?;
+		self.sessions.get(target).map(String::as_str)
+	}
+}
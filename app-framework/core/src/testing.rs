@@ -0,0 +1,1097 @@
+//! In-memory backend for driving an [`Application`] without a server or GPU.
+//!
+//! Monitors, input, session updates and buffer releases are scripted through
+//! [`MockTabAppFramework`]; swapchain buffers live in memfds.
+
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::fs::File;
+use std::mem::ManuallyDrop;
+use std::os::fd::{FromRawFd, RawFd};
+use std::os::unix::fs::FileExt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use tab_client::{
+	ClockSync, MemfdAllocator, MonitorState, ServerError, TabClientError, TabSwapchain,
+};
+use tab_protocol::{
+	AccessibilitySettings, BufferIndex, CaptureSource, Colorspace, GammaRamps, InputDeviceInfo,
+	InputEventPayload, MonitorInfo, MonitorMode, MonitorPower, Rect, SeatId, SeatInputEventPayload,
+	ServerErrorCode, SessionCreatedPayload, SessionInfo, SessionLifecycle, SessionRole,
+};
+
+use crate::{
+	Application, CaptureId, Config, Connection, FrameworkError, GlobalShortcutId, InitContext,
+	OverlayId, QueuedEvent, TabAppFramework, TabInputEvent, TabMonitorEvent, TabRenderEvent,
+};
+
+/// Buffer handed to the mock server by `request_buffer`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubmittedFrame {
+	/// Monitor the buffer was submitted for.
+	pub monitor_id: String,
+	/// Submitted buffer slot.
+	pub buffer: BufferIndex,
+	/// Whether an acquire fence accompanied the request.
+	pub has_acquire_fence: bool,
+}
+
+/// Overlay created through the mock connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockOverlay {
+	/// Monitor the overlay is shown on.
+	pub monitor_id: String,
+	/// Overlay size in pixels.
+	pub size: (i32, i32),
+	/// Position relative to the monitor's top-left corner.
+	pub position: (i32, i32),
+	/// Stacking order; higher is drawn on top.
+	pub z: i32,
+	/// Buffer currently shown, if any was presented.
+	pub shown: Option<BufferIndex>,
+}
+
+/// Capture started through the mock connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockCapture {
+	/// What the capture copies.
+	pub source: CaptureSource,
+	/// Capture buffer size in pixels.
+	pub size: (i32, i32),
+	/// Buffers the application handed back, in order.
+	pub released: Vec<BufferIndex>,
+}
+
+/// Message sent to another session through the mock connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockSessionMessage {
+	/// Target session.
+	pub session_id: String,
+	pub correlation_id: Option<u64>,
+	pub reply_to: Option<u64>,
+	pub data: Vec<u8>,
+}
+
+pub(crate) struct MockConnection {
+	session: SessionInfo,
+	monitors: HashMap<String, MonitorState>,
+	allocator: MemfdAllocator,
+	submitted: Vec<SubmittedFrame>,
+	ready_sent: bool,
+	idle_inhibit: Option<String>,
+	brightness: HashMap<String, f32>,
+	power: HashMap<String, MonitorPower>,
+	gamma: HashMap<String, GammaRamps>,
+	scanout_hints: HashMap<String, bool>,
+	colorspaces: HashMap<String, Colorspace>,
+	overlays: HashMap<String, MockOverlay>,
+	captures: HashMap<String, MockCapture>,
+	switch_requests: Vec<String>,
+	preview_requests: Vec<(String, (i32, i32))>,
+	global_shortcuts: HashMap<String, BTreeSet<u32>>,
+	keyboard_grab: bool,
+	session_messages: Vec<MockSessionMessage>,
+	injected_input: Vec<(String, SeatInputEventPayload)>,
+	next_session: u32,
+	clock: ClockSync,
+	events: Rc<RefCell<VecDeque<QueuedEvent>>>,
+}
+
+impl MockConnection {
+	fn new(events: Rc<RefCell<VecDeque<QueuedEvent>>>) -> Self {
+		Self {
+			session: SessionInfo {
+				id: "mock-session".into(),
+				role: SessionRole::Admin,
+				display_name: Some("mock".into()),
+				state: SessionLifecycle::Occupied,
+				metadata: BTreeMap::new(),
+			},
+			monitors: HashMap::new(),
+			allocator: MemfdAllocator::new(),
+			submitted: Vec::new(),
+			ready_sent: false,
+			idle_inhibit: None,
+			brightness: HashMap::new(),
+			power: HashMap::new(),
+			gamma: HashMap::new(),
+			scanout_hints: HashMap::new(),
+			colorspaces: HashMap::new(),
+			overlays: HashMap::new(),
+			captures: HashMap::new(),
+			switch_requests: Vec::new(),
+			preview_requests: Vec::new(),
+			global_shortcuts: HashMap::new(),
+			keyboard_grab: false,
+			session_messages: Vec::new(),
+			injected_input: Vec::new(),
+			next_session: 0,
+			clock: ClockSync::new(),
+			events,
+		}
+	}
+
+	pub(crate) fn session(&self) -> &SessionInfo {
+		&self.session
+	}
+
+	pub(crate) fn clock(&self) -> &ClockSync {
+		&self.clock
+	}
+
+	pub(crate) fn create_swapchain(
+		&mut self,
+		monitor_id: &str,
+	) -> Result<TabSwapchain, TabClientError> {
+		let monitor = self
+			.monitors
+			.get(monitor_id)
+			.ok_or_else(|| TabClientError::UnknownMonitor(monitor_id.to_string()))?;
+		self.allocator.create_swapchain(monitor)
+	}
+
+	/// Records the submission and queues an immediate acknowledgement.
+	pub(crate) fn send_buffer_request(
+		&mut self,
+		monitor_id: &str,
+		buffer: BufferIndex,
+		acquire_fence: Option<RawFd>,
+	) -> Result<(), TabClientError> {
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(TabClientError::UnknownMonitor(monitor_id.to_string()));
+		}
+		self.submitted.push(SubmittedFrame {
+			monitor_id: monitor_id.to_string(),
+			buffer,
+			has_acquire_fence: acquire_fence.is_some(),
+		});
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Render(TabRenderEvent::BufferRequestAcked {
+				monitor_id: monitor_id.to_string(),
+				buffer,
+			}));
+		Ok(())
+	}
+
+	pub(crate) fn send_ready(&mut self) -> Result<(), TabClientError> {
+		self.ready_sent = true;
+		Ok(())
+	}
+
+	/// Applies the update right away and queues the state the server would send back.
+	pub(crate) fn update_session(
+		&mut self,
+		display_name: Option<String>,
+		metadata: BTreeMap<String, Option<String>>,
+	) -> Result<(), TabClientError> {
+		crate::apply_session_update(&mut self.session, display_name, metadata);
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Session(tab_client::SessionEvent::State(
+				self.session.clone(),
+			)));
+		Ok(())
+	}
+
+	pub(crate) fn set_idle_inhibit(
+		&mut self,
+		inhibit: bool,
+		reason: Option<String>,
+	) -> Result<(), TabClientError> {
+		self.idle_inhibit = if inhibit {
+			Some(reason.unwrap_or_default())
+		} else {
+			None
+		};
+		Ok(())
+	}
+
+	/// Applies the mode right away and queues the resulting monitor change.
+	pub(crate) fn set_monitor_mode(
+		&mut self,
+		monitor_id: &str,
+		mode: MonitorMode,
+	) -> Result<(), TabClientError> {
+		self.check_admin_monitor(monitor_id)?;
+		let state = self
+			.monitors
+			.get_mut(monitor_id)
+			.expect("monitor checked above");
+		if !state.info.modes.contains(&mode) {
+			return Err(TabClientError::Server(ServerError::new(
+				ServerErrorCode::InvalidArgument,
+			)));
+		}
+		state.info.width = mode.width;
+		state.info.height = mode.height;
+		state.info.refresh_rate = mode.refresh_rate;
+		let state = state.clone();
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Monitor(TabMonitorEvent::Changed(state)));
+		Ok(())
+	}
+
+	/// Moves the primary flag right away and queues a change for each affected monitor.
+	pub(crate) fn set_primary_monitor(&mut self, monitor_id: &str) -> Result<(), TabClientError> {
+		self.check_admin_monitor(monitor_id)?;
+		for state in self.monitors.values_mut() {
+			let primary = state.info.id == monitor_id;
+			if state.info.primary == primary {
+				continue;
+			}
+			state.info.primary = primary;
+			self
+				.events
+				.borrow_mut()
+				.push_back(QueuedEvent::Monitor(TabMonitorEvent::Changed(
+					state.clone(),
+				)));
+		}
+		Ok(())
+	}
+
+	pub(crate) fn set_monitor_brightness(
+		&mut self,
+		monitor_id: &str,
+		brightness: f32,
+	) -> Result<(), TabClientError> {
+		self.check_admin_monitor(monitor_id)?;
+		self.brightness.insert(monitor_id.to_string(), brightness);
+		Ok(())
+	}
+
+	/// Reports the new state right back, as shift does once the display switched.
+	pub(crate) fn set_monitor_power(
+		&mut self,
+		monitor_id: &str,
+		power: MonitorPower,
+	) -> Result<(), TabClientError> {
+		self.check_admin_monitor(monitor_id)?;
+		self.power.insert(monitor_id.to_string(), power);
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Monitor(TabMonitorEvent::Power {
+				monitor_id: monitor_id.to_string(),
+				power,
+			}));
+		Ok(())
+	}
+
+	pub(crate) fn set_gamma_lut(
+		&mut self,
+		monitor_id: &str,
+		ramps: Option<GammaRamps>,
+	) -> Result<(), TabClientError> {
+		self.check_admin_monitor(monitor_id)?;
+		match ramps {
+			Some(ramps) => self.gamma.insert(monitor_id.to_string(), ramps),
+			None => self.gamma.remove(monitor_id),
+		};
+		Ok(())
+	}
+
+	/// Forwards the settings right back, as shift does to every client.
+	pub(crate) fn set_accessibility(
+		&mut self,
+		settings: AccessibilitySettings,
+	) -> Result<(), TabClientError> {
+		if self.session.role != SessionRole::Admin {
+			return Err(TabClientError::Server(ServerError::new(
+				ServerErrorCode::Forbidden,
+			)));
+		}
+		self.events.borrow_mut().push_back(QueuedEvent::Session(
+			tab_client::SessionEvent::Accessibility(settings),
+		));
+		Ok(())
+	}
+
+	/// Records the injection; input for the mock session itself is queued right back.
+	pub(crate) fn inject_input(
+		&mut self,
+		session_id: &str,
+		seat: SeatId,
+		event: InputEventPayload,
+	) -> Result<(), TabClientError> {
+		if self.session.role != SessionRole::Admin {
+			return Err(TabClientError::Server(ServerError::new(
+				ServerErrorCode::Forbidden,
+			)));
+		}
+		if session_id == self.session.id {
+			self
+				.events
+				.borrow_mut()
+				.push_back(QueuedEvent::Input(TabInputEvent::Event {
+					seat: seat.clone(),
+					payload: event.clone(),
+				}));
+		}
+		self.injected_input.push((
+			session_id.to_string(),
+			SeatInputEventPayload { seat, event },
+		));
+		Ok(())
+	}
+
+	/// Forwards the chrome right back, as shift does to every client.
+	pub(crate) fn set_safe_area(
+		&mut self,
+		monitor_id: &str,
+		occluded: Vec<Rect>,
+	) -> Result<(), TabClientError> {
+		self.check_admin_monitor(monitor_id)?;
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Monitor(TabMonitorEvent::SafeArea {
+				monitor_id: monitor_id.to_string(),
+				occluded,
+			}));
+		Ok(())
+	}
+
+	pub(crate) fn set_scanout_hint(
+		&mut self,
+		monitor_id: &str,
+		fullscreen_opaque: bool,
+	) -> Result<(), TabClientError> {
+		self
+			.scanout_hints
+			.insert(monitor_id.to_string(), fullscreen_opaque);
+		Ok(())
+	}
+
+	pub(crate) fn set_surface_colorspace(
+		&mut self,
+		monitor_id: &str,
+		colorspace: Colorspace,
+	) -> Result<(), TabClientError> {
+		self.colorspaces.insert(monitor_id.to_string(), colorspace);
+		Ok(())
+	}
+
+	pub(crate) fn create_overlay(
+		&mut self,
+		overlay_id: &str,
+		monitor_id: &str,
+		size: (i32, i32),
+		position: (i32, i32),
+		z: i32,
+	) -> Result<TabSwapchain, TabClientError> {
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(TabClientError::UnknownMonitor(monitor_id.to_string()));
+		}
+		let swapchain = self
+			.allocator
+			.create_overlay_swapchain(overlay_id, size.0, size.1)?;
+		self.overlays.insert(
+			overlay_id.to_string(),
+			MockOverlay {
+				monitor_id: monitor_id.to_string(),
+				size,
+				position,
+				z,
+				shown: None,
+			},
+		);
+		Ok(swapchain)
+	}
+
+	pub(crate) fn move_overlay(
+		&mut self,
+		overlay_id: &str,
+		position: (i32, i32),
+	) -> Result<(), TabClientError> {
+		if let Some(overlay) = self.overlays.get_mut(overlay_id) {
+			overlay.position = position;
+		}
+		Ok(())
+	}
+
+	/// Shows the buffer right away and releases the previously shown one.
+	pub(crate) fn present_overlay(
+		&mut self,
+		overlay_id: &str,
+		buffer: BufferIndex,
+		_acquire_fence: Option<RawFd>,
+	) -> Result<(), TabClientError> {
+		let Some(overlay) = self.overlays.get_mut(overlay_id) else {
+			return Ok(());
+		};
+		if let Some(previous) = overlay.shown.replace(buffer)
+			&& previous != buffer
+		{
+			self
+				.events
+				.borrow_mut()
+				.push_back(QueuedEvent::Render(TabRenderEvent::OverlayReleased {
+					overlay_id: overlay_id.to_string(),
+					buffer: previous,
+					release_fence_fd: None,
+				}));
+		}
+		Ok(())
+	}
+
+	pub(crate) fn destroy_overlay(&mut self, overlay_id: &str) -> Result<(), TabClientError> {
+		self.overlays.remove(overlay_id);
+		Ok(())
+	}
+
+	/// Mirrors the server's admin and monitor checks; frames are queued by the test.
+	pub(crate) fn start_capture(
+		&mut self,
+		capture_id: &str,
+		source: CaptureSource,
+		size: (i32, i32),
+	) -> Result<TabSwapchain, TabClientError> {
+		if self.session.role != SessionRole::Admin {
+			return Err(TabClientError::Server(ServerError::new(
+				ServerErrorCode::Forbidden,
+			)));
+		}
+		let (CaptureSource::Monitor { monitor_id } | CaptureSource::Session { monitor_id, .. }) =
+			&source;
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(TabClientError::UnknownMonitor(monitor_id.clone()));
+		}
+		let swapchain = self
+			.allocator
+			.create_overlay_swapchain(capture_id, size.0, size.1)?;
+		self.captures.insert(
+			capture_id.to_string(),
+			MockCapture {
+				source,
+				size,
+				released: Vec::new(),
+			},
+		);
+		Ok(swapchain)
+	}
+
+	pub(crate) fn release_capture_frame(
+		&mut self,
+		capture_id: &str,
+		buffer: BufferIndex,
+	) -> Result<(), TabClientError> {
+		if let Some(capture) = self.captures.get_mut(capture_id) {
+			capture.released.push(buffer);
+		}
+		Ok(())
+	}
+
+	pub(crate) fn stop_capture(&mut self, capture_id: &str) -> Result<(), TabClientError> {
+		self.captures.remove(capture_id);
+		Ok(())
+	}
+
+	/// Mirrors the server's admin and monitor checks for output color requests.
+	fn check_admin_monitor(&self, monitor_id: &str) -> Result<(), TabClientError> {
+		if self.session.role != SessionRole::Admin {
+			return Err(TabClientError::Server(ServerError::new(
+				ServerErrorCode::Forbidden,
+			)));
+		}
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(TabClientError::UnknownMonitor(monitor_id.to_string()));
+		}
+		Ok(())
+	}
+
+	pub(crate) fn create_session(
+		&mut self,
+		role: SessionRole,
+		display_name: Option<String>,
+	) -> Result<SessionCreatedPayload, TabClientError> {
+		self.next_session += 1;
+		Ok(SessionCreatedPayload {
+			session: SessionInfo {
+				id: format!("mock-session-{}", self.next_session),
+				role,
+				display_name,
+				state: SessionLifecycle::Pending,
+				metadata: BTreeMap::new(),
+			},
+			token: format!("mock-token-{}", self.next_session),
+			request_id: None,
+		})
+	}
+
+	/// Queues the answer right away, as shift sends it once the session is created.
+	pub(crate) fn request_session_create(
+		&mut self,
+		role: SessionRole,
+		display_name: Option<String>,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		let created = self.create_session(role, display_name)?;
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Session(tab_client::SessionEvent::Created {
+				session: created.session,
+				token: created.token,
+				request_id: Some(request_id),
+			}));
+		Ok(())
+	}
+
+	pub(crate) fn switch_session(
+		&mut self,
+		session_id: &str,
+		_animation: Option<String>,
+		_duration: Duration,
+	) -> Result<(), TabClientError> {
+		self.switch_requests.push(session_id.to_string());
+		Ok(())
+	}
+
+	/// Records the request and activates the session, as shift does when it accepts.
+	pub(crate) fn request_session_switch(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+		_request_id: u64,
+	) -> Result<(), TabClientError> {
+		self.switch_session(session_id, animation, duration)?;
+		self
+			.events
+			.borrow_mut()
+			.push_back(QueuedEvent::Session(tab_client::SessionEvent::Active(
+				session_id.to_string(),
+			)));
+		Ok(())
+	}
+
+	/// Records the request; no other session exists to answer it.
+	pub(crate) fn request_session_preview(
+		&mut self,
+		session_id: &str,
+		max_width: i32,
+		max_height: i32,
+	) -> Result<(), TabClientError> {
+		self
+			.preview_requests
+			.push((session_id.to_string(), (max_width, max_height)));
+		Ok(())
+	}
+
+	/// Mirrors the server's conflict check against this session's own registrations.
+	pub(crate) fn register_global_shortcut(
+		&mut self,
+		shortcut_id: &str,
+		keys: Vec<u32>,
+	) -> Result<(), TabClientError> {
+		if self.session.role != SessionRole::Admin {
+			return Err(TabClientError::Server(ServerError::new(
+				ServerErrorCode::Forbidden,
+			)));
+		}
+		let keys: BTreeSet<u32> = keys.into_iter().collect();
+		let conflict = self
+			.global_shortcuts
+			.iter()
+			.any(|(id, held)| id != shortcut_id && *held == keys);
+		if conflict {
+			self.events.borrow_mut().push_back(QueuedEvent::Input(
+				TabInputEvent::GlobalShortcutConflict {
+					shortcut_id: shortcut_id.to_string(),
+				},
+			));
+		} else {
+			self.global_shortcuts.insert(shortcut_id.to_string(), keys);
+		}
+		Ok(())
+	}
+
+	pub(crate) fn set_keyboard_grab(&mut self, grab: bool) -> Result<(), TabClientError> {
+		if self.session.role != SessionRole::Admin {
+			return Err(TabClientError::Server(ServerError::new(
+				ServerErrorCode::Forbidden,
+			)));
+		}
+		self.keyboard_grab = grab;
+		Ok(())
+	}
+
+	/// Records the message; no other session exists to receive it.
+	pub(crate) fn send_session_message(
+		&mut self,
+		session_id: &str,
+		data: Vec<u8>,
+		correlation_id: Option<u64>,
+		reply_to: Option<u64>,
+	) -> Result<(), TabClientError> {
+		self.session_messages.push(MockSessionMessage {
+			session_id: session_id.to_string(),
+			correlation_id,
+			reply_to,
+			data,
+		});
+		Ok(())
+	}
+
+	pub(crate) fn unregister_global_shortcut(
+		&mut self,
+		shortcut_id: &str,
+	) -> Result<(), TabClientError> {
+		self.global_shortcuts.remove(shortcut_id);
+		Ok(())
+	}
+}
+
+/// Test driver that runs an [`Application`] against scripted, in-memory server state.
+///
+/// Nothing happens until [`MockTabAppFramework::dispatch`] is called; each call
+/// performs one loop iteration (events, releases, scheduled renders).
+pub struct MockTabAppFramework<A: Application> {
+	inner: TabAppFramework<A>,
+}
+
+impl<A: Application> MockTabAppFramework<A> {
+	/// Initializes the application against an empty mock server.
+	///
+	/// The environment is not consulted; a placeholder token is configured.
+	pub fn init(configure: impl FnOnce(&mut Config)) -> Result<Self, FrameworkError> {
+		let mut init_ctx = InitContext::<A>::new(Config::from_token("mock-token"));
+		configure(init_ctx.config_mut());
+		let app = A::init(&mut init_ctx)
+			.map_err(|e| FrameworkError::Config(format!("app init failed: {e:#}")))?;
+		let cfg = init_ctx.config().clone();
+		let queue = Rc::new(RefCell::new(VecDeque::new()));
+		let inner = TabAppFramework::with_connection(
+			app,
+			&cfg,
+			Connection::Mock(MockConnection::new(Rc::clone(&queue))),
+			queue,
+			&[],
+		)?;
+		Ok(Self { inner })
+	}
+
+	/// Queues a monitor hotplug.
+	pub fn add_monitor(&mut self, info: MonitorInfo) {
+		let state = MonitorState::new(info);
+		self
+			.mock_mut()
+			.monitors
+			.insert(state.info.id.clone(), state.clone());
+		self.push(QueuedEvent::Monitor(TabMonitorEvent::Added(state)));
+	}
+
+	/// Replaces a monitor's properties and queues an in-place change.
+	pub fn change_monitor(&mut self, info: MonitorInfo) {
+		let state = MonitorState::new(info);
+		self
+			.mock_mut()
+			.monitors
+			.insert(state.info.id.clone(), state.clone());
+		self.push(QueuedEvent::Monitor(TabMonitorEvent::Changed(state)));
+	}
+
+	/// Queues a monitor unplug.
+	pub fn remove_monitor(&mut self, monitor_id: &str) {
+		let Some(state) = self.mock_mut().monitors.remove(monitor_id) else {
+			return;
+		};
+		self.push(QueuedEvent::Monitor(TabMonitorEvent::Removed {
+			monitor_id: state.info.id,
+			name: state.info.name,
+		}));
+	}
+
+	/// Queues compositor chrome covering `occluded` on a monitor; empty clears it.
+	pub fn set_safe_area(&mut self, monitor_id: &str, occluded: Vec<Rect>) {
+		self.push(QueuedEvent::Monitor(TabMonitorEvent::SafeArea {
+			monitor_id: monitor_id.to_string(),
+			occluded,
+		}));
+	}
+
+	/// Queues new accessibility settings, as if an admin session changed them.
+	pub fn set_accessibility(&mut self, settings: AccessibilitySettings) {
+		self.push(QueuedEvent::Session(
+			tab_client::SessionEvent::Accessibility(settings),
+		));
+	}
+
+	/// Queues an input device hotplug.
+	pub fn add_input_device(&mut self, device: InputDeviceInfo) {
+		self.push(QueuedEvent::Input(TabInputEvent::DeviceAdded(device)));
+	}
+
+	/// Queues an input device unplug.
+	pub fn remove_input_device(&mut self, device: InputDeviceInfo) {
+		self.push(QueuedEvent::Input(TabInputEvent::DeviceRemoved(device)));
+	}
+
+	/// Queues a raw input event from the default seat.
+	pub fn push_input(&mut self, payload: InputEventPayload) {
+		self.push_seat_input(SeatId::default(), payload);
+	}
+
+	/// Queues a raw input event from a specific seat.
+	pub fn push_seat_input(&mut self, seat: SeatId, payload: InputEventPayload) {
+		self.push(QueuedEvent::Input(TabInputEvent::Event { seat, payload }));
+	}
+
+	/// Queues a press of a registered global shortcut from the default seat.
+	pub fn trigger_global_shortcut(&mut self, shortcut_id: GlobalShortcutId) {
+		self.push(QueuedEvent::Input(TabInputEvent::GlobalShortcut {
+			seat: SeatId::default(),
+			shortcut_id: shortcut_id.to_string(),
+			time_usec: 0,
+		}));
+	}
+
+	/// Queues the server making `session_id` the active session.
+	pub fn activate_session(&mut self, session_id: &str) {
+		self.push(QueuedEvent::Session(tab_client::SessionEvent::Active(
+			session_id.to_string(),
+		)));
+	}
+
+	/// Queues the server refusing a keyboard grab held by another session.
+	pub fn deny_keyboard_grab(&mut self) {
+		self.mock_mut().keyboard_grab = false;
+		self.push(QueuedEvent::Input(TabInputEvent::KeyboardGrabUnavailable));
+	}
+
+	/// Queues a message from another session.
+	pub fn push_session_message(
+		&mut self,
+		from_session_id: &str,
+		data: &[u8],
+		correlation_id: Option<u64>,
+	) {
+		self.push(QueuedEvent::Session(tab_client::SessionEvent::Message {
+			from_session_id: from_session_id.to_string(),
+			correlation_id,
+			reply_to: None,
+			data: data.to_vec(),
+		}));
+	}
+
+	/// Queues the crash report of another session, as admin sessions receive it.
+	pub fn push_session_crashed(&mut self, session_id: &str, message: &str, backtrace_hash: u64) {
+		self.push(QueuedEvent::Session(tab_client::SessionEvent::Crashed {
+			session_id: session_id.to_string(),
+			message: message.to_string(),
+			backtrace_hash,
+			app_version: None,
+		}));
+	}
+
+	/// Queues the server refusing a message to `session_id`.
+	pub fn refuse_session_message(&mut self, session_id: &str) {
+		self.push(QueuedEvent::Session(
+			tab_client::SessionEvent::MessageUndeliverable {
+				session_id: session_id.to_string(),
+			},
+		));
+	}
+
+	/// Queues a new frame in a capture buffer, without a ready fence.
+	pub fn push_capture_frame(&mut self, capture_id: CaptureId, buffer: BufferIndex) {
+		self.push(QueuedEvent::Render(TabRenderEvent::CaptureFrame {
+			capture_id: capture_id.to_string(),
+			buffer,
+			time_usec: 0,
+			ready_fence_fd: None,
+		}));
+	}
+
+	/// Drops a capture on the mock server and queues its end, as if its source went away.
+	pub fn end_capture(&mut self, capture_id: CaptureId, reason: &str) {
+		self.mock_mut().captures.remove(&capture_id.to_string());
+		self.push(QueuedEvent::Render(TabRenderEvent::CaptureEnded {
+			capture_id: capture_id.to_string(),
+			reason: reason.to_string(),
+		}));
+	}
+
+	/// Replaces the current session and queues a session state update.
+	pub fn set_session_state(&mut self, session: SessionInfo) {
+		self.mock_mut().session = session.clone();
+		self.push(QueuedEvent::Session(tab_client::SessionEvent::State(
+			session,
+		)));
+	}
+
+	/// Queues a buffer release without a fence, as if the server finished scanout.
+	pub fn release_buffer(&mut self, monitor_id: &str, buffer: BufferIndex) {
+		self.push(QueuedEvent::Render(TabRenderEvent::BufferReleased {
+			monitor_id: monitor_id.to_string(),
+			buffer,
+			release_fence_fd: None,
+		}));
+	}
+
+	/// Runs one loop iteration without blocking.
+	///
+	/// Buffer requests are acknowledged right away, but the acknowledgement is
+	/// only processed by the next call.
+	pub fn dispatch(&mut self) -> Result<(), FrameworkError> {
+		self.inner.flush_pending_releases();
+		self.inner.drain_tab_events()?;
+		self.inner.check_idle();
+		self.inner.flush_pending_releases();
+		self.inner.flush_event_batch();
+		self.inner.sync_idle_inhibit()?;
+		self.inner.schedule_due_retries();
+		self.inner.render_scheduled()?;
+		self.inner.tracer.finish_frame();
+		self.inner.report_stats();
+		Ok(())
+	}
+
+	/// Returns buffers submitted so far.
+	pub fn submitted_frames(&self) -> &[SubmittedFrame] {
+		&self.mock().submitted
+	}
+
+	/// Drains and returns buffers submitted so far.
+	pub fn take_submitted_frames(&mut self) -> Vec<SubmittedFrame> {
+		std::mem::take(&mut self.mock_mut().submitted)
+	}
+
+	/// Reads the raw contents of a monitor's swapchain buffer.
+	pub fn read_buffer(
+		&self,
+		monitor_id: &str,
+		buffer: BufferIndex,
+	) -> Result<Vec<u8>, FrameworkError> {
+		let monitor = self
+			.inner
+			.monitors
+			.get(monitor_id)
+			.ok_or_else(|| FrameworkError::MonitorNotFound(monitor_id.to_string()))?;
+		let tab_buffer = &monitor.swapchain.buffers[buffer as usize];
+		let mut data = vec![0u8; tab_buffer.stride() as usize * tab_buffer.height() as usize];
+		// The swapchain keeps ownership of the descriptor.
+		let file = ManuallyDrop::new(unsafe { File::from_raw_fd(tab_buffer.fd()) });
+		file
+			.read_exact_at(&mut data, tab_buffer.offset() as u64)
+			.map_err(|err| FrameworkError::Client(err.into()))?;
+		Ok(data)
+	}
+
+	/// Returns true once the application has sent `session_ready`.
+	pub fn ready_sent(&self) -> bool {
+		self.mock().ready_sent
+	}
+
+	/// Returns the reason sent with the active idle inhibit, if any.
+	pub fn idle_inhibit_reason(&self) -> Option<&str> {
+		self.mock().idle_inhibit.as_deref()
+	}
+
+	/// Returns the brightness last set for a monitor, `1.0` if untouched.
+	pub fn monitor_brightness(&self, monitor_id: &str) -> f32 {
+		self
+			.mock()
+			.brightness
+			.get(monitor_id)
+			.copied()
+			.unwrap_or(1.0)
+	}
+
+	/// Returns the power state last set for a monitor, [`MonitorPower::On`] if untouched.
+	pub fn monitor_power(&self, monitor_id: &str) -> MonitorPower {
+		self
+			.mock()
+			.power
+			.get(monitor_id)
+			.copied()
+			.unwrap_or_default()
+	}
+
+	/// Returns the gamma ramps last set for a monitor, if any.
+	pub fn gamma_lut(&self, monitor_id: &str) -> Option<&GammaRamps> {
+		self.mock().gamma.get(monitor_id)
+	}
+
+	/// Returns whether the application marked a monitor as a scanout candidate.
+	pub fn scanout_candidate(&self, monitor_id: &str) -> bool {
+		self
+			.mock()
+			.scanout_hints
+			.get(monitor_id)
+			.copied()
+			.unwrap_or(false)
+	}
+
+	/// Returns the colorspace the application tagged a monitor's frames with.
+	pub fn surface_colorspace(&self, monitor_id: &str) -> Colorspace {
+		self
+			.mock()
+			.colorspaces
+			.get(monitor_id)
+			.copied()
+			.unwrap_or_default()
+	}
+
+	/// Returns the state of an overlay the application created, if it still exists.
+	pub fn overlay(&self, overlay_id: OverlayId) -> Option<&MockOverlay> {
+		self.mock().overlays.get(&overlay_id.to_string())
+	}
+
+	/// Returns the state of a capture the application started, if it is still running.
+	pub fn capture(&self, capture_id: CaptureId) -> Option<&MockCapture> {
+		self.mock().captures.get(&capture_id.to_string())
+	}
+
+	/// Returns session ids the application asked to switch to.
+	pub fn switch_requests(&self) -> &[String] {
+		&self.mock().switch_requests
+	}
+
+	/// Returns session ids and maximum sizes of requested session previews.
+	pub fn preview_requests(&self) -> &[(String, (i32, i32))] {
+		&self.mock().preview_requests
+	}
+
+	/// Returns the messages the application sent to other sessions, in order.
+	pub fn sent_session_messages(&self) -> &[MockSessionMessage] {
+		&self.mock().session_messages
+	}
+
+	/// Returns input the application injected into sessions, with the target session ids.
+	pub fn injected_input(&self) -> &[(String, SeatInputEventPayload)] {
+		&self.mock().injected_input
+	}
+
+	/// Returns true while the application holds the keyboard grab on the mock server.
+	pub fn keyboard_grabbed(&self) -> bool {
+		self.mock().keyboard_grab
+	}
+
+	/// Returns the keys held by a global shortcut, if the server side accepted it.
+	pub fn global_shortcut_keys(&self, shortcut_id: GlobalShortcutId) -> Option<Vec<u32>> {
+		let keys = self.mock().global_shortcuts.get(&shortcut_id.to_string())?;
+		Some(keys.iter().copied().collect())
+	}
+
+	/// Returns true once the application requested exit.
+	pub fn exit_requested(&self) -> bool {
+		self.inner.exiting
+	}
+
+	/// Returns the application instance.
+	pub fn app(&self) -> &A {
+		&self.inner.app
+	}
+
+	/// Returns the application instance mutably.
+	pub fn app_mut(&mut self) -> &mut A {
+		&mut self.inner.app
+	}
+
+	fn push(&mut self, event: QueuedEvent) {
+		self.inner.event_queue.borrow_mut().push_back(event);
+	}
+
+	fn mock(&self) -> &MockConnection {
+		match &self.inner.client {
+			Connection::Mock(mock) => mock,
+			_ => unreachable!("mock framework always uses the mock connection"),
+		}
+	}
+
+	fn mock_mut(&mut self) -> &mut MockConnection {
+		match &mut self.inner.client {
+			Connection::Mock(mock) => mock,
+			_ => unreachable!("mock framework always uses the mock connection"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs::File;
+	use std::os::unix::fs::FileExt;
+
+	use tab_protocol::MonitorInfo;
+
+	use super::MockTabAppFramework;
+	use crate::{Application, Context, InitContext, MonitorAddedEvent, PresentEvent, RenderEvent};
+
+	#[derive(Default)]
+	struct Fill {
+		rendered: Vec<(String, u64)>,
+		presented: Vec<u64>,
+	}
+
+	impl Application for Fill {
+		fn init(_ctx: &mut InitContext<Self>) -> anyhow::Result<Self> {
+			Ok(Self::default())
+		}
+
+		fn on_monitor_added(&mut self, ctx: &mut Context<Self>, ev: MonitorAddedEvent) {
+			ctx.schedule_frame(ev.monitor.id);
+		}
+
+		fn on_render(&mut self, _ctx: &mut Context<Self>, ev: RenderEvent<'_>) {
+			let target = ev.target();
+			let file = File::from(target.try_clone_fd().expect("clone buffer fd"));
+			let pixels = vec![0xab; (target.stride() * target.height()) as usize];
+			file
+				.write_all_at(&pixels, target.offset() as u64)
+				.expect("write buffer");
+			self
+				.rendered
+				.push((ev.monitor_id().to_string(), ev.frame_id()));
+		}
+
+		fn on_present(&mut self, _ctx: &mut Context<Self>, ev: PresentEvent) {
+			self.presented.push(ev.frame_id);
+		}
+	}
+
+	fn monitor() -> MonitorInfo {
+		MonitorInfo {
+			id: "mock-0".into(),
+			width: 4,
+			height: 2,
+			refresh_rate: 60,
+			name: "Mock".into(),
+			modes: Vec::new(),
+			color: None,
+			scale: 1.0,
+			primary: true,
+		}
+	}
+
+	#[test]
+	fn renders_and_presents_a_frame() {
+		let mut framework = MockTabAppFramework::<Fill>::init(|_| {}).expect("init");
+		framework.add_monitor(monitor());
+		framework.dispatch().expect("dispatch");
+
+		let frames = framework.take_submitted_frames();
+		assert_eq!(frames.len(), 1);
+		assert_eq!(frames[0].monitor_id, "mock-0");
+		let frame_id = framework.app().rendered[0].1;
+		assert_eq!(
+			framework.app().rendered,
+			vec![("mock-0".to_string(), frame_id)]
+		);
+		let pixels = framework
+			.read_buffer("mock-0", frames[0].buffer)
+			.expect("read buffer");
+		assert!(pixels.iter().all(|&byte| byte == 0xab));
+
+		framework.release_buffer("mock-0", frames[0].buffer);
+		framework.dispatch().expect("dispatch");
+		assert_eq!(framework.app().presented, vec![frame_id]);
+	}
+}
@@ -3,7 +3,7 @@ use std::time::Instant;
 use glow::HasContext;
 use tab_app_framework::{
 	Config, GlApplication, GlEventContext, GlInitContext, GlTabAppFramework, MouseDownEvent,
-	MouseUpEvent, RenderEvent, RenderMode,
+	MouseUpEvent, QuadBatcher, QuadRect, QuadTexture, QuadTransform, RenderEvent, RenderMode,
 };
 use tracing::{error, info};
 use tracing_subscriber::{EnvFilter, fmt};
@@ -13,19 +13,28 @@ struct App {
 	last_log: Instant,
 	frames: u64,
 	left_down: bool,
+	quads: QuadBatcher,
+	cursor: QuadTexture,
 }
 
+/// Diameter of the cursor texture in pixels.
+const CURSOR_SIZE: u32 = 64;
+
 impl GlApplication for App {
-	fn init(_ctx: &mut GlInitContext) -> anyhow::Result<Self> {
+	fn init(ctx: &mut GlInitContext) -> anyhow::Result<Self> {
+		let quads = QuadBatcher::new(ctx.gl())?;
+		let cursor = quads.upload_rgba(ctx.gl(), CURSOR_SIZE, CURSOR_SIZE, &cursor_pixels())?;
 		Ok(Self {
 			start: Instant::now(),
 			last_log: Instant::now(),
 			frames: 0,
 			left_down: false,
+			quads,
+			cursor,
 		})
 	}
 
-	fn on_render(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: RenderEvent) {
+	fn on_render(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: RenderEvent<'_>) {
 		let _ = ctx.session();
 		self.frames = self.frames.saturating_add(1);
 		if self.last_log.elapsed().as_secs_f32() >= 1.0 {
@@ -46,13 +55,24 @@ impl GlApplication for App {
 			gl.clear(glow::COLOR_BUFFER_BIT);
 		}
 
-		let Some(monitor) = ctx.monitor(&ev.monitor_id) else {
+		let Some(monitor) = ctx.monitor(ev.monitor_id()) else {
 			return;
 		};
 		let (cursor_x, cursor_y) = ctx.cursor_position();
 		let (local_x, local_y) = monitor.cursor_relative_position((cursor_x, cursor_y));
-		let radius = if self.left_down { 6 } else { 10 };
-		draw_cursor_circle(gl, ev.width, ev.height, local_x as _, local_y as _, radius);
+		let radius = if self.left_down { 6.0 } else { 10.0 };
+		self.quads.begin(ev.width(), ev.height());
+		self.quads.draw(
+			&self.cursor,
+			QuadRect::new(
+				local_x as f32 - radius,
+				local_y as f32 - radius,
+				radius * 2.0,
+				radius * 2.0,
+			),
+			QuadTransform::IDENTITY,
+		);
+		self.quads.flush(ctx.gl());
 	}
 
 	fn on_mouse_down(
@@ -71,32 +91,19 @@ impl GlApplication for App {
 		self.left_down = false;
 	}
 }
-// This is not really the best way but it's the approach that requires the least lines of code
-fn draw_cursor_circle(gl: &glow::Context, width: i32, height: i32, cx: i32, cy: i32, radius: i32) {
-	if width <= 0 || height <= 0 || radius <= 0 {
-		return;
-	}
-	unsafe {
-		gl.enable(glow::SCISSOR_TEST);
-		gl.clear_color(1.0, 0.0, 0.0, 1.0);
-		for dy in -radius..=radius {
-			let y = cy + dy;
-			if y < 0 || y >= height {
-				continue;
-			}
-			let span = ((radius * radius - dy * dy) as f64).sqrt().floor() as i32;
-			let mut x0 = cx - span;
-			let mut x1 = cx + span;
-			if x1 < 0 || x0 >= width {
-				continue;
-			}
-			x0 = x0.max(0);
-			x1 = x1.min(width - 1);
-			gl.scissor(x0, y, x1 - x0 + 1, 1);
-			gl.clear(glow::COLOR_BUFFER_BIT);
+
+/// Red disc with an antialiased edge, scaled down to the cursor radius when drawn.
+fn cursor_pixels() -> Vec<u8> {
+	let center = CURSOR_SIZE as f32 / 2.0;
+	let mut pixels = Vec::with_capacity((CURSOR_SIZE * CURSOR_SIZE * 4) as usize);
+	for y in 0..CURSOR_SIZE {
+		for x in 0..CURSOR_SIZE {
+			let distance = (x as f32 + 0.5 - center).hypot(y as f32 + 0.5 - center);
+			let alpha = (center - distance).clamp(0.0, 1.0);
+			pixels.extend_from_slice(&[255, 0, 0, (alpha * 255.0) as u8]);
 		}
-		gl.disable(glow::SCISSOR_TEST);
 	}
+	pixels
 }
 
 fn main() -> anyhow::Result<()> {
@@ -22,6 +22,7 @@ fn main() {
 			"EGL_KHR_fence_sync",
 			"EGL_KHR_image_base",
 			"EGL_EXT_image_dma_buf_import",
+			"EGL_EXT_image_dma_buf_import_modifiers",
 			"EGL_ANDROID_native_fence_sync",
 		],
 	)
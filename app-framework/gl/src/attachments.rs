@@ -0,0 +1,333 @@
+//! Depth/stencil and multisampled color attachments of monitor render targets.
+
+use glow::HasContext;
+use tracing::warn;
+
+use crate::{GlContext, GlError, RenderTargetOptions, offscreen};
+
+/// Renderbuffers shared by the imported swapchain buffers of one monitor.
+pub(crate) struct MonitorAttachments {
+	options: RenderTargetOptions,
+	size: (i32, i32),
+	depth_stencil: Option<glow::NativeRenderbuffer>,
+	msaa: Option<MsaaBuffer>,
+}
+
+/// Multisampled framebuffer rendered into and resolved into the swapchain buffer.
+#[derive(Clone, Copy)]
+struct MsaaBuffer {
+	framebuffer: glow::NativeFramebuffer,
+	color: glow::NativeRenderbuffer,
+}
+
+impl GlContext {
+	/// Sets the attachments of render targets for monitors without their own options.
+	///
+	/// Takes effect on the next [`GlContext::prepare_render_target`] of each monitor.
+	pub fn set_default_render_target_options(&mut self, options: RenderTargetOptions) {
+		self.default_target_options = options;
+	}
+
+	/// Overrides the attachments of a monitor's render targets; `None` restores the default.
+	pub fn set_render_target_options(
+		&mut self,
+		monitor_id: &str,
+		options: Option<RenderTargetOptions>,
+	) {
+		match options {
+			Some(options) => {
+				self.target_options.insert(monitor_id.to_string(), options);
+			}
+			None => {
+				self.target_options.remove(monitor_id);
+			}
+		}
+	}
+
+	/// Returns the attachments requested for a monitor's render targets.
+	pub fn render_target_options(&self, monitor_id: &str) -> RenderTargetOptions {
+		self
+			.target_options
+			.get(monitor_id)
+			.copied()
+			.unwrap_or(self.default_target_options)
+	}
+
+	/// Resolves a multisampled monitor target into its swapchain buffer and binds the buffer.
+	///
+	/// Runs from [`GlContext::create_frame_fence`]; call it earlier to read back the frame.
+	/// Drawing after the resolve goes to the single-sampled buffer. A no-op for targets
+	/// without MSAA.
+	pub fn resolve_current_target(&mut self) -> Result<(), GlError> {
+		let mut bound = self.current_target.ok_or(GlError::NoRenderTarget)?;
+		let Some(resolved) = bound.resolve_into else {
+			return Ok(());
+		};
+		unsafe {
+			// Blits honor the scissor test.
+			let scissor = self.glow.is_enabled(glow::SCISSOR_TEST);
+			self.glow.disable(glow::SCISSOR_TEST);
+			self
+				.glow
+				.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(bound.framebuffer));
+			self
+				.glow
+				.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(resolved));
+			self.glow.blit_framebuffer(
+				0,
+				0,
+				bound.width,
+				bound.height,
+				0,
+				0,
+				bound.width,
+				bound.height,
+				glow::COLOR_BUFFER_BIT,
+				glow::NEAREST,
+			);
+			self
+				.glow
+				.bind_framebuffer(glow::FRAMEBUFFER, Some(resolved));
+			if scissor {
+				self.glow.enable(glow::SCISSOR_TEST);
+			}
+		}
+		bound.framebuffer = resolved;
+		bound.resolve_into = None;
+		bound.depth_stencil = false;
+		self.current_target = Some(bound);
+		Ok(())
+	}
+
+	/// Creates a monitor's attachments for a target size, dropping imports made for others.
+	pub(crate) fn prepare_attachments(
+		&mut self,
+		monitor_id: &str,
+		width: i32,
+		height: i32,
+	) -> Result<(), GlError> {
+		let options = self.render_target_options(monitor_id);
+		if self
+			.monitor_attachments
+			.get(monitor_id)
+			.is_some_and(|attachments| {
+				attachments.options == options && attachments.size == (width, height)
+			}) {
+			return Ok(());
+		}
+		self.release_monitor_dmabuf_targets(monitor_id);
+		let attachments = self.create_attachments(options, width, height)?;
+		self
+			.monitor_attachments
+			.insert(monitor_id.to_string(), attachments);
+		Ok(())
+	}
+
+	/// Returns the framebuffer to draw into instead of the swapchain buffer, if multisampled.
+	pub(crate) fn msaa_framebuffer(&self, monitor_id: &str) -> Option<glow::NativeFramebuffer> {
+		let msaa = self.monitor_attachments.get(monitor_id)?.msaa?;
+		Some(msaa.framebuffer)
+	}
+
+	/// Returns true when the framebuffer drawn into has a depth/stencil attachment.
+	pub(crate) fn has_depth_stencil(&self, monitor_id: &str) -> bool {
+		self
+			.monitor_attachments
+			.get(monitor_id)
+			.is_some_and(|attachments| attachments.depth_stencil.is_some())
+	}
+
+	/// Returns a monitor's depth/stencil renderbuffer when its frames are single-sampled.
+	pub(crate) fn depth_stencil_renderbuffer(
+		&self,
+		monitor_id: &str,
+	) -> Option<glow::NativeRenderbuffer> {
+		let attachments = self.monitor_attachments.get(monitor_id)?;
+		if attachments.msaa.is_some() {
+			return None;
+		}
+		attachments.depth_stencil
+	}
+
+	/// Attaches a monitor's single-sampled depth/stencil buffer to an imported swapchain buffer.
+	pub(crate) fn attach_depth_stencil(
+		&self,
+		monitor_id: &str,
+		framebuffer: glow::NativeFramebuffer,
+	) {
+		let Some(attachments) = self.monitor_attachments.get(monitor_id) else {
+			return;
+		};
+		let Some(depth_stencil) = attachments.depth_stencil else {
+			return;
+		};
+		if attachments.msaa.is_some() {
+			return;
+		}
+		unsafe {
+			self
+				.glow
+				.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+			self.glow.framebuffer_renderbuffer(
+				glow::FRAMEBUFFER,
+				glow::DEPTH_STENCIL_ATTACHMENT,
+				glow::RENDERBUFFER,
+				Some(depth_stencil),
+			);
+			self.glow.bind_framebuffer(glow::FRAMEBUFFER, None);
+		}
+	}
+
+	pub(crate) fn release_monitor_attachments(&mut self, monitor_id: &str) {
+		if let Some(attachments) = self.monitor_attachments.remove(monitor_id) {
+			if let Some(msaa) = attachments.msaa
+				&& self
+					.current_target
+					.is_some_and(|bound| bound.framebuffer == msaa.framebuffer)
+			{
+				self.current_target = None;
+			}
+			self.delete_attachments(attachments);
+		}
+	}
+
+	pub(crate) fn delete_attachments(&self, attachments: MonitorAttachments) {
+		unsafe {
+			if let Some(msaa) = attachments.msaa {
+				self.glow.delete_framebuffer(msaa.framebuffer);
+				self.glow.delete_renderbuffer(msaa.color);
+			}
+			if let Some(depth_stencil) = attachments.depth_stencil {
+				self.glow.delete_renderbuffer(depth_stencil);
+			}
+		}
+	}
+
+	fn create_attachments(
+		&self,
+		options: RenderTargetOptions,
+		width: i32,
+		height: i32,
+	) -> Result<MonitorAttachments, GlError> {
+		let mut attachments = MonitorAttachments {
+			options,
+			size: (width, height),
+			depth_stencil: None,
+			msaa: None,
+		};
+		let samples = self.msaa_samples(options.samples);
+		if options.depth_stencil {
+			let depth_stencil =
+				self.create_renderbuffer(glow::DEPTH24_STENCIL8, samples, width, height)?;
+			attachments.depth_stencil = Some(depth_stencil);
+		}
+		if samples > 1 {
+			match self.create_msaa_buffer(attachments.depth_stencil, samples, width, height) {
+				Ok(msaa) => attachments.msaa = Some(msaa),
+				Err(err) => {
+					self.delete_attachments(attachments);
+					return Err(err);
+				}
+			}
+		}
+		Ok(attachments)
+	}
+
+	/// Clamps a requested sample count to what the context supports; 0 disables MSAA.
+	fn msaa_samples(&self, requested: u8) -> i32 {
+		if requested <= 1 {
+			return 0;
+		}
+		// Resolving needs glBlitFramebuffer from GL 3 / GLES 3.
+		if self.glow.version().major < 3 {
+			warn!(target: "tab_app_framework.gl", "MSAA render targets need GL 3, ignoring");
+			return 0;
+		}
+		let max = unsafe { self.glow.get_parameter_i32(glow::MAX_SAMPLES) };
+		i32::from(requested).min(max)
+	}
+
+	fn create_msaa_buffer(
+		&self,
+		depth_stencil: Option<glow::NativeRenderbuffer>,
+		samples: i32,
+		width: i32,
+		height: i32,
+	) -> Result<MsaaBuffer, GlError> {
+		let (internal_format, _, _) = offscreen::texture_format(self.surface_format);
+		let color = self.create_renderbuffer(internal_format, samples, width, height)?;
+		let framebuffer = match unsafe { self.glow.create_framebuffer() } {
+			Ok(framebuffer) => framebuffer,
+			Err(err) => {
+				unsafe { self.glow.delete_renderbuffer(color) };
+				return Err(GlError::CreateRenderTargetFailed(err));
+			}
+		};
+		let status = unsafe {
+			self
+				.glow
+				.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+			self.glow.framebuffer_renderbuffer(
+				glow::FRAMEBUFFER,
+				glow::COLOR_ATTACHMENT0,
+				glow::RENDERBUFFER,
+				Some(color),
+			);
+			if depth_stencil.is_some() {
+				self.glow.framebuffer_renderbuffer(
+					glow::FRAMEBUFFER,
+					glow::DEPTH_STENCIL_ATTACHMENT,
+					glow::RENDERBUFFER,
+					depth_stencil,
+				);
+			}
+			let status = self.glow.check_framebuffer_status(glow::FRAMEBUFFER);
+			self.glow.bind_framebuffer(
+				glow::FRAMEBUFFER,
+				self.current_target.map(|bound| bound.framebuffer),
+			);
+			status
+		};
+		if status != glow::FRAMEBUFFER_COMPLETE {
+			unsafe {
+				self.glow.delete_framebuffer(framebuffer);
+				self.glow.delete_renderbuffer(color);
+			}
+			return Err(GlError::CreateRenderTargetFailed(format!(
+				"multisampled framebuffer incomplete (status={status:#X})"
+			)));
+		}
+		Ok(MsaaBuffer { framebuffer, color })
+	}
+
+	fn create_renderbuffer(
+		&self,
+		internal_format: u32,
+		samples: i32,
+		width: i32,
+		height: i32,
+	) -> Result<glow::NativeRenderbuffer, GlError> {
+		let renderbuffer =
+			unsafe { self.glow.create_renderbuffer() }.map_err(GlError::CreateRenderTargetFailed)?;
+		unsafe {
+			self
+				.glow
+				.bind_renderbuffer(glow::RENDERBUFFER, Some(renderbuffer));
+			if samples > 1 {
+				self.glow.renderbuffer_storage_multisample(
+					glow::RENDERBUFFER,
+					samples,
+					internal_format,
+					width,
+					height,
+				);
+			} else {
+				self
+					.glow
+					.renderbuffer_storage(glow::RENDERBUFFER, internal_format, width, height);
+			}
+			self.glow.bind_renderbuffer(glow::RENDERBUFFER, None);
+		}
+		Ok(renderbuffer)
+	}
+}
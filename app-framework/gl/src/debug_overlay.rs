@@ -0,0 +1,282 @@
+//! Per-monitor diagnostics drawn over the application's frame.
+//!
+//! Everything is drawn with scissored clears, so the overlay needs no shaders; the
+//! scissor and clear color state it changes is restored afterwards.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::time::{Duration, Instant};
+
+use glow::HasContext;
+
+use crate::GlContext;
+
+/// Frame intervals kept for the bar graph.
+const HISTORY: usize = 64;
+/// How often the FPS figure is refreshed.
+const FPS_WINDOW: Duration = Duration::from_millis(500);
+/// Screen pixels per font pixel.
+const SCALE: i32 = 3;
+const MARGIN: i32 = 4 * SCALE;
+const LINE_HEIGHT: i32 = 7 * SCALE;
+const BAR_WIDTH: i32 = SCALE;
+const GRAPH_HEIGHT: i32 = 16 * SCALE;
+/// Bar height of one refresh period.
+const BUDGET_HEIGHT: i32 = GRAPH_HEIGHT / 2;
+
+// Clears do not blend, so every color is opaque.
+const BACKGROUND: [f32; 4] = [0.05, 0.05, 0.05, 1.0];
+const TEXT: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const BAR_OK: [f32; 4] = [0.2, 0.8, 0.3, 1.0];
+const BAR_LATE: [f32; 4] = [0.9, 0.2, 0.2, 1.0];
+const BUDGET_LINE: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
+
+/// Loop-wide figures shown next to the per-monitor ones.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct OverlayStats {
+	/// Render attempts without a free buffer over the last stats interval.
+	pub acquire_miss: u64,
+	/// Time the last loop iteration spent on release fences.
+	pub fence_wait: Duration,
+	/// Nominal refresh rate of the monitor being drawn, in Hz.
+	pub refresh_rate: i32,
+}
+
+#[derive(Debug)]
+struct MonitorHistory {
+	last_frame: Option<Instant>,
+	intervals: VecDeque<Duration>,
+	window_start: Instant,
+	window_frames: u32,
+	fps: f64,
+	render_time: Duration,
+}
+
+impl MonitorHistory {
+	fn new(now: Instant) -> Self {
+		Self {
+			last_frame: None,
+			intervals: VecDeque::with_capacity(HISTORY),
+			window_start: now,
+			window_frames: 0,
+			fps: 0.0,
+			render_time: Duration::ZERO,
+		}
+	}
+}
+
+/// Toggleable FPS and frame-timing overlay; see [`crate::GlEventContext::set_debug_overlay`].
+#[derive(Debug, Default)]
+pub(crate) struct DebugOverlay {
+	enabled: bool,
+	monitors: HashMap<String, MonitorHistory>,
+	text: String,
+}
+
+impl DebugOverlay {
+	pub(crate) fn new(enabled: bool) -> Self {
+		Self {
+			enabled,
+			..Self::default()
+		}
+	}
+
+	pub(crate) fn enabled(&self) -> bool {
+		self.enabled
+	}
+
+	pub(crate) fn set_enabled(&mut self, enabled: bool) {
+		if !enabled {
+			self.monitors.clear();
+		}
+		self.enabled = enabled;
+	}
+
+	/// Records a frame rendered for `monitor_id` whose `on_render` took `render_time`.
+	pub(crate) fn record_frame(&mut self, monitor_id: &str, render_time: Duration) {
+		let now = Instant::now();
+		let history = self
+			.monitors
+			.entry(monitor_id.to_string())
+			.or_insert_with(|| MonitorHistory::new(now));
+		if let Some(last) = history.last_frame.replace(now) {
+			if history.intervals.len() == HISTORY {
+				history.intervals.pop_front();
+			}
+			history.intervals.push_back(now - last);
+		}
+		history.render_time = render_time;
+		history.window_frames += 1;
+		let window = now - history.window_start;
+		if window >= FPS_WINDOW {
+			history.fps = history.window_frames as f64 / window.as_secs_f64();
+			history.window_start = now;
+			history.window_frames = 0;
+		}
+	}
+
+	pub(crate) fn remove_monitor(&mut self, monitor_id: &str) {
+		self.monitors.remove(monitor_id);
+	}
+
+	/// Draws the overlay for `monitor_id` into the bound render target.
+	pub(crate) fn draw(&mut self, gl: &GlContext, monitor_id: &str, stats: OverlayStats) {
+		let Some(history) = self.monitors.get(monitor_id) else {
+			return;
+		};
+		self.text.clear();
+		let _ = write!(
+			self.text,
+			"FPS {:.1}\nCPU {:.2}MS\nMISS {}\nFENCE {:.2}MS",
+			history.fps,
+			millis(history.render_time),
+			stats.acquire_miss,
+			millis(stats.fence_wait),
+		);
+		let lines = self.text.lines().count() as i32;
+		let text_width = self.text.lines().map(str::len).max().unwrap_or(0) as i32 * 4 * SCALE;
+		let width = text_width.max(HISTORY as i32 * BAR_WIDTH) + 2 * MARGIN;
+		let height = lines * LINE_HEIGHT + GRAPH_HEIGHT + 3 * MARGIN;
+		let budget = if stats.refresh_rate > 0 {
+			Duration::from_secs_f64(1.0 / stats.refresh_rate as f64)
+		} else {
+			Duration::from_secs_f64(1.0 / 60.0)
+		};
+
+		let mut painter = Painter::begin(gl.glow());
+		painter.fill(MARGIN, MARGIN, width, height, BACKGROUND);
+		for (line, text) in self.text.lines().enumerate() {
+			painter.text(
+				2 * MARGIN,
+				2 * MARGIN + line as i32 * LINE_HEIGHT,
+				text,
+				TEXT,
+			);
+		}
+		let graph_bottom = height;
+		for (i, interval) in history.intervals.iter().enumerate() {
+			let ratio = interval.as_secs_f64() / budget.as_secs_f64();
+			let bar = ((ratio * BUDGET_HEIGHT as f64) as i32).clamp(1, GRAPH_HEIGHT);
+			// Frames are late once they take noticeably longer than one refresh period.
+			let color = if ratio > 1.5 { BAR_LATE } else { BAR_OK };
+			let x = 2 * MARGIN + i as i32 * BAR_WIDTH;
+			painter.fill(x, graph_bottom - bar, BAR_WIDTH - 1, bar, color);
+		}
+		let graph_width = HISTORY as i32 * BAR_WIDTH;
+		painter.fill(
+			2 * MARGIN,
+			graph_bottom - BUDGET_HEIGHT,
+			graph_width,
+			1,
+			BUDGET_LINE,
+		);
+		painter.end();
+	}
+}
+
+fn millis(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
+
+/// Draws rectangles with scissored clears, restoring the touched state on [`Painter::end`].
+///
+/// Coordinates are in framebuffer rows, which start at the first scanline of the target.
+struct Painter<'a> {
+	gl: &'a glow::Context,
+	scissor_enabled: bool,
+	scissor_box: [i32; 4],
+	clear_color: [f32; 4],
+}
+
+impl<'a> Painter<'a> {
+	fn begin(gl: &'a glow::Context) -> Self {
+		let mut scissor_box = [0; 4];
+		let mut clear_color = [0.0; 4];
+		let scissor_enabled = unsafe {
+			gl.get_parameter_i32_slice(glow::SCISSOR_BOX, &mut scissor_box);
+			gl.get_parameter_f32_slice(glow::COLOR_CLEAR_VALUE, &mut clear_color);
+			let enabled = gl.is_enabled(glow::SCISSOR_TEST);
+			gl.enable(glow::SCISSOR_TEST);
+			enabled
+		};
+		Self {
+			gl,
+			scissor_enabled,
+			scissor_box,
+			clear_color,
+		}
+	}
+
+	fn fill(&mut self, x: i32, y: i32, width: i32, height: i32, color: [f32; 4]) {
+		if width <= 0 || height <= 0 {
+			return;
+		}
+		unsafe {
+			self.gl.scissor(x, y, width, height);
+			self.gl.clear_color(color[0], color[1], color[2], color[3]);
+			self.gl.clear(glow::COLOR_BUFFER_BIT);
+		}
+	}
+
+	/// Draws `text` in the built-in 3x5 font; unsupported characters are left blank.
+	fn text(&mut self, x: i32, y: i32, text: &str, color: [f32; 4]) {
+		for (index, ch) in text.chars().enumerate() {
+			let glyph_x = x + index as i32 * 4 * SCALE;
+			for (row, bits) in glyph(ch).iter().enumerate() {
+				let row_y = y + row as i32 * SCALE;
+				// One clear per run of lit pixels in the row.
+				let mut col = 0;
+				while col < 3 {
+					if bits & (0b100 >> col) == 0 {
+						col += 1;
+						continue;
+					}
+					let start = col;
+					while col < 3 && bits & (0b100 >> col) != 0 {
+						col += 1;
+					}
+					let run_x = glyph_x + start * SCALE;
+					self.fill(run_x, row_y, (col - start) * SCALE, SCALE, color);
+				}
+			}
+		}
+	}
+
+	fn end(self) {
+		let [x, y, width, height] = self.scissor_box;
+		let [r, g, b, a] = self.clear_color;
+		unsafe {
+			self.gl.scissor(x, y, width, height);
+			self.gl.clear_color(r, g, b, a);
+			if !self.scissor_enabled {
+				self.gl.disable(glow::SCISSOR_TEST);
+			}
+		}
+	}
+}
+
+/// Rows of a 3x5 glyph, top first, most significant of the three bits leftmost.
+fn glyph(ch: char) -> [u8; 5] {
+	match ch.to_ascii_uppercase() {
+		'0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+		'1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+		'2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+		'3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+		'4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+		'5' | 'S' => [0b111, 0b100, 0b111, 0b001, 0b111],
+		'6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+		'7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+		'8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+		'9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+		'.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+		'C' => [0b111, 0b100, 0b100, 0b100, 0b111],
+		'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+		'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+		'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+		'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+		'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+		'P' => [0b111, 0b101, 0b111, 0b100, 0b100],
+		'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+		_ => [0; 5],
+	}
+}
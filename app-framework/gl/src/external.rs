@@ -0,0 +1,465 @@
+//! Sampling externally produced DMA-BUFs, such as decoded video frames, without a CPU copy.
+
+use std::cell::RefCell;
+use std::os::fd::{AsRawFd, BorrowedFd};
+use std::rc::Rc;
+
+use glow::HasContext;
+
+use crate::{GlContext, GlError, egl};
+
+/// `GL_TEXTURE_EXTERNAL_OES` from `GL_OES_EGL_image_external`.
+pub const TEXTURE_EXTERNAL_OES: u32 = 0x8D65;
+/// `DRM_FORMAT_MOD_INVALID`: the buffer layout is implied by the driver.
+pub const DRM_FORMAT_MOD_INVALID: u64 = 0x00ff_ffff_ffff_ffff;
+
+const FOURCC_R8: u32 = fourcc(b"R8  ");
+const FOURCC_GR88: u32 = fourcc(b"GR88");
+const FOURCC_R16: u32 = fourcc(b"R16 ");
+const FOURCC_GR1616: u32 = fourcc(b"GR32");
+const FOURCC_ARGB8888: u32 = fourcc(b"AR24");
+const FOURCC_XRGB8888: u32 = fourcc(b"XR24");
+
+const PLANE_ATTRS: [[u32; 5]; 3] = [
+	[
+		egl::DMA_BUF_PLANE0_FD_EXT,
+		egl::DMA_BUF_PLANE0_OFFSET_EXT,
+		egl::DMA_BUF_PLANE0_PITCH_EXT,
+		egl::DMA_BUF_PLANE0_MODIFIER_LO_EXT,
+		egl::DMA_BUF_PLANE0_MODIFIER_HI_EXT,
+	],
+	[
+		egl::DMA_BUF_PLANE1_FD_EXT,
+		egl::DMA_BUF_PLANE1_OFFSET_EXT,
+		egl::DMA_BUF_PLANE1_PITCH_EXT,
+		egl::DMA_BUF_PLANE1_MODIFIER_LO_EXT,
+		egl::DMA_BUF_PLANE1_MODIFIER_HI_EXT,
+	],
+	[
+		egl::DMA_BUF_PLANE2_FD_EXT,
+		egl::DMA_BUF_PLANE2_OFFSET_EXT,
+		egl::DMA_BUF_PLANE2_PITCH_EXT,
+		egl::DMA_BUF_PLANE2_MODIFIER_LO_EXT,
+		egl::DMA_BUF_PLANE2_MODIFIER_HI_EXT,
+	],
+];
+
+const fn fourcc(code: &[u8; 4]) -> u32 {
+	(code[0] as u32) | ((code[1] as u32) << 8) | ((code[2] as u32) << 16) | ((code[3] as u32) << 24)
+}
+
+/// One memory plane of an external DMA-BUF.
+#[derive(Debug, Clone, Copy)]
+pub struct DmabufPlane<'a> {
+	/// DMA-BUF holding the plane; only borrowed for the duration of the import.
+	pub fd: BorrowedFd<'a>,
+	/// Byte offset of the plane within `fd`.
+	pub offset: u32,
+	/// Bytes per row.
+	pub stride: u32,
+}
+
+/// Pixel layout of an external DMA-BUF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExternalFormat {
+	/// 8-bit Y plane followed by an interleaved, half-resolution UV plane.
+	Nv12,
+	/// 10-bit NV12 variant, samples stored in the high bits of 16-bit words.
+	P010,
+	/// 8-bit Y, U and V planes, chroma at half resolution.
+	Yuv420,
+	/// Packed 8-bit BGRA in memory, with alpha.
+	Argb8888,
+	/// Packed 8-bit BGRX in memory, alpha ignored.
+	Xrgb8888,
+}
+
+impl ExternalFormat {
+	/// DRM fourcc code of the format.
+	pub fn fourcc(self) -> u32 {
+		match self {
+			Self::Nv12 => fourcc(b"NV12"),
+			Self::P010 => fourcc(b"P010"),
+			Self::Yuv420 => fourcc(b"YU12"),
+			Self::Argb8888 => fourcc(b"AR24"),
+			Self::Xrgb8888 => fourcc(b"XR24"),
+		}
+	}
+
+	/// Number of planes the format expects.
+	pub fn plane_count(self) -> usize {
+		match self {
+			Self::Nv12 | Self::P010 => 2,
+			Self::Yuv420 => 3,
+			Self::Argb8888 | Self::Xrgb8888 => 1,
+		}
+	}
+
+	/// Returns true for YUV formats.
+	pub fn is_yuv(self) -> bool {
+		matches!(self, Self::Nv12 | Self::P010 | Self::Yuv420)
+	}
+
+	/// Per-plane fourcc and subsampling divisor used when planes are imported separately.
+	fn plane_layouts(self) -> &'static [(u32, i32)] {
+		match self {
+			Self::Nv12 => &[(FOURCC_R8, 1), (FOURCC_GR88, 2)],
+			Self::P010 => &[(FOURCC_R16, 1), (FOURCC_GR1616, 2)],
+			Self::Yuv420 => &[(FOURCC_R8, 1), (FOURCC_R8, 2), (FOURCC_R8, 2)],
+			Self::Argb8888 => &[(FOURCC_ARGB8888, 1)],
+			Self::Xrgb8888 => &[(FOURCC_XRGB8888, 1)],
+		}
+	}
+}
+
+/// YUV encoding matrix of a video frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum YuvColorSpace {
+	/// ITU-R BT.601, standard definition video.
+	#[default]
+	Bt601,
+	/// ITU-R BT.709, high definition video.
+	Bt709,
+	/// ITU-R BT.2020, UHD and HDR video.
+	Bt2020,
+}
+
+/// Value range of YUV samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum YuvRange {
+	/// Y in 16..=235 and chroma in 16..=240 (8-bit), the usual video range.
+	#[default]
+	Limited,
+	/// Samples use the whole value range.
+	Full,
+}
+
+/// Color encoding of a YUV DMA-BUF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct YuvColor {
+	/// Encoding matrix.
+	pub space: YuvColorSpace,
+	/// Sample range.
+	pub range: YuvRange,
+}
+
+/// Returns a column-major 4x4 matrix converting normalized `vec4(y, u, v, 1.0)` to RGB.
+///
+/// Upload it with `uniform_matrix_4_f32_slice(.., false, ..)` and multiply in the fragment
+/// shader: `vec3 rgb = (yuv_to_rgb * vec4(y, uv, 1.0)).rgb;`. 10-bit samples from
+/// [`ExternalFormat::P010`] planes are normalized the same way and can use the same matrix.
+pub fn yuv_to_rgb_matrix(color: YuvColor) -> [f32; 16] {
+	let (kr, kb) = match color.space {
+		YuvColorSpace::Bt601 => (0.299, 0.114),
+		YuvColorSpace::Bt709 => (0.2126, 0.0722),
+		YuvColorSpace::Bt2020 => (0.2627, 0.0593),
+	};
+	let kg = 1.0 - kr - kb;
+	let (y_scale, y_offset, c_scale) = match color.range {
+		YuvRange::Limited => (255.0 / 219.0, 16.0 / 255.0, 255.0 / 224.0),
+		YuvRange::Full => (1.0, 0.0, 1.0),
+	};
+	let c_offset = 128.0 / 255.0;
+
+	let y_col = [y_scale, y_scale, y_scale];
+	let u_col = [
+		0.0,
+		-c_scale * 2.0 * kb * (1.0 - kb) / kg,
+		c_scale * 2.0 * (1.0 - kb),
+	];
+	let v_col = [
+		c_scale * 2.0 * (1.0 - kr),
+		-c_scale * 2.0 * kr * (1.0 - kr) / kg,
+		0.0,
+	];
+	let offset = |i: usize| -(y_col[i] * y_offset + (u_col[i] + v_col[i]) * c_offset);
+
+	#[rustfmt::skip]
+	let matrix = [
+		y_col[0], y_col[1], y_col[2], 0.0,
+		u_col[0], u_col[1], u_col[2], 0.0,
+		v_col[0], v_col[1], v_col[2], 0.0,
+		offset(0), offset(1), offset(2), 1.0,
+	];
+	matrix
+}
+
+/// Texture imported with [`GlContext::import_external_dmabuf`].
+///
+/// Driver-converted imports are a single texture bound to [`ExternalTexture::target`] and
+/// sample as RGB (`samplerExternalOES` for `GL_TEXTURE_EXTERNAL_OES`). Otherwise each plane
+/// is a separate `GL_TEXTURE_2D` holding raw Y, UV (or U and V) samples; combine them with
+/// [`yuv_to_rgb_matrix`].
+///
+/// GL objects are reclaimed by the context after the handle is dropped, on the next import.
+/// The DMA-BUF must stay unmodified while the texture is sampled.
+#[derive(Debug)]
+pub struct ExternalTexture {
+	id: u64,
+	width: i32,
+	height: i32,
+	format: ExternalFormat,
+	target: u32,
+	converted: bool,
+	released: Rc<RefCell<Vec<u64>>>,
+}
+
+impl ExternalTexture {
+	/// Frame width in pixels.
+	pub fn width(&self) -> i32 {
+		self.width
+	}
+
+	/// Frame height in pixels.
+	pub fn height(&self) -> i32 {
+		self.height
+	}
+
+	/// Pixel layout of the imported DMA-BUF.
+	pub fn format(&self) -> ExternalFormat {
+		self.format
+	}
+
+	/// Texture target to bind the plane textures to.
+	pub fn target(&self) -> u32 {
+		self.target
+	}
+
+	/// Returns true when sampling yields RGB, i.e. no YUV conversion is needed in the shader.
+	pub fn is_converted(&self) -> bool {
+		self.converted
+	}
+}
+
+impl Drop for ExternalTexture {
+	fn drop(&mut self) {
+		self.released.borrow_mut().push(self.id);
+	}
+}
+
+pub(crate) struct ExternalEntry {
+	textures: Vec<glow::NativeTexture>,
+	images: Vec<egl::types::EGLImageKHR>,
+}
+
+impl GlContext {
+	/// Imports a DMA-BUF for sampling, independent of the swapchain render targets.
+	///
+	/// YUV formats use a single `GL_TEXTURE_EXTERNAL_OES` texture converted by the driver
+	/// when `GL_OES_EGL_image_external` is available, with `color` passed as the EGL
+	/// conversion hint. Otherwise every plane is imported as its own texture. Pass
+	/// [`DRM_FORMAT_MOD_INVALID`] as `modifier` for buffers allocated without one.
+	pub fn import_external_dmabuf(
+		&mut self,
+		width: i32,
+		height: i32,
+		planes: &[DmabufPlane<'_>],
+		format: ExternalFormat,
+		modifier: u64,
+		color: YuvColor,
+	) -> Result<ExternalTexture, GlError> {
+		self.collect_released_external_textures();
+		if planes.len() != format.plane_count() {
+			return Err(GlError::InvalidDmabufPlanes {
+				expected: format.plane_count(),
+				got: planes.len(),
+			});
+		}
+
+		let use_external = format.is_yuv()
+			&& self
+				.glow
+				.supported_extensions()
+				.contains("GL_OES_EGL_image_external");
+		let entry = if use_external || !format.is_yuv() {
+			let mut attrs = vec![
+				egl::LINUX_DRM_FOURCC_EXT as i32,
+				format.fourcc() as i32,
+				egl::WIDTH as i32,
+				width,
+				egl::HEIGHT as i32,
+				height,
+			];
+			for (idx, plane) in planes.iter().enumerate() {
+				push_plane_attrs(&mut attrs, idx, plane, modifier);
+			}
+			if format.is_yuv() {
+				attrs.extend(color_hint_attrs(color));
+			}
+			attrs.push(egl::NONE as i32);
+			let target = if format.is_yuv() {
+				TEXTURE_EXTERNAL_OES
+			} else {
+				glow::TEXTURE_2D
+			};
+			self.import_external_images(target, &[attrs])?
+		} else {
+			let attrs: Vec<Vec<i32>> = format
+				.plane_layouts()
+				.iter()
+				.zip(planes)
+				.map(|((plane_fourcc, divisor), plane)| {
+					let mut attrs = vec![
+						egl::LINUX_DRM_FOURCC_EXT as i32,
+						*plane_fourcc as i32,
+						egl::WIDTH as i32,
+						(width + divisor - 1) / divisor,
+						egl::HEIGHT as i32,
+						(height + divisor - 1) / divisor,
+					];
+					push_plane_attrs(&mut attrs, 0, plane, modifier);
+					attrs.push(egl::NONE as i32);
+					attrs
+				})
+				.collect();
+			self.import_external_images(glow::TEXTURE_2D, &attrs)?
+		};
+
+		let id = self.next_external_id;
+		self.next_external_id += 1;
+		self.external_textures.insert(id, entry);
+		Ok(ExternalTexture {
+			id,
+			width,
+			height,
+			format,
+			target: if use_external {
+				TEXTURE_EXTERNAL_OES
+			} else {
+				glow::TEXTURE_2D
+			},
+			converted: use_external || !format.is_yuv(),
+			released: Rc::clone(&self.released_external),
+		})
+	}
+
+	/// Returns the textures of an imported DMA-BUF, one per plane unless driver-converted.
+	pub fn external_texture_planes(
+		&self,
+		texture: &ExternalTexture,
+	) -> Result<&[glow::NativeTexture], GlError> {
+		self
+			.external_textures
+			.get(&texture.id)
+			.map(|entry| entry.textures.as_slice())
+			.ok_or(GlError::ExternalTextureReleased)
+	}
+
+	pub(crate) fn collect_released_external_textures(&mut self) {
+		let released: Vec<u64> = self.released_external.borrow_mut().drain(..).collect();
+		for id in released {
+			if let Some(entry) = self.external_textures.remove(&id) {
+				self.delete_external_entry(entry);
+			}
+		}
+	}
+
+	/// Drops every import after a context loss, releasing only the EGL images.
+	pub(crate) fn forget_external_textures(&mut self) {
+		let external: Vec<_> = self.external_textures.drain().map(|(_, e)| e).collect();
+		for entry in external {
+			for image in entry.images {
+				self.destroy_egl_image(image);
+			}
+		}
+	}
+
+	pub(crate) fn delete_external_entry(&self, entry: ExternalEntry) {
+		for texture in entry.textures {
+			unsafe { self.glow.delete_texture(texture) };
+		}
+		for image in entry.images {
+			self.destroy_egl_image(image);
+		}
+	}
+
+	fn import_external_images(
+		&self,
+		target: u32,
+		plane_attrs: &[Vec<i32>],
+	) -> Result<ExternalEntry, GlError> {
+		let mut entry = ExternalEntry {
+			textures: Vec::with_capacity(plane_attrs.len()),
+			images: Vec::with_capacity(plane_attrs.len()),
+		};
+		for attrs in plane_attrs {
+			let image = match self.create_egl_image(attrs) {
+				Ok(image) if image != egl::NO_IMAGE_KHR => image,
+				Ok(_) => {
+					let error = unsafe { self.device.egl.GetError() };
+					self.delete_external_entry(entry);
+					return Err(GlError::CreateImageFailed(error));
+				}
+				Err(err) => {
+					self.delete_external_entry(entry);
+					return Err(err);
+				}
+			};
+			entry.images.push(image);
+			let texture = match unsafe { self.glow.create_texture() } {
+				Ok(texture) => texture,
+				Err(err) => {
+					self.delete_external_entry(entry);
+					return Err(GlError::ImportExternalFailed(err));
+				}
+			};
+			entry.textures.push(texture);
+			unsafe {
+				self.glow.bind_texture(target, Some(texture));
+				self
+					.glow
+					.tex_parameter_i32(target, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+				self
+					.glow
+					.tex_parameter_i32(target, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+				self
+					.glow
+					.tex_parameter_i32(target, glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE as i32);
+				self
+					.glow
+					.tex_parameter_i32(target, glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE as i32);
+				(self.egl_image_target_texture_2d_oes)(target, image.cast());
+				self.glow.bind_texture(target, None);
+			}
+		}
+		Ok(entry)
+	}
+}
+
+fn push_plane_attrs(attrs: &mut Vec<i32>, idx: usize, plane: &DmabufPlane<'_>, modifier: u64) {
+	let [fd, offset, pitch, modifier_lo, modifier_hi] = PLANE_ATTRS[idx];
+	attrs.extend([
+		fd as i32,
+		plane.fd.as_raw_fd(),
+		offset as i32,
+		plane.offset as i32,
+		pitch as i32,
+		plane.stride as i32,
+	]);
+	if modifier != DRM_FORMAT_MOD_INVALID {
+		attrs.extend([
+			modifier_lo as i32,
+			(modifier & 0xffff_ffff) as i32,
+			modifier_hi as i32,
+			(modifier >> 32) as i32,
+		]);
+	}
+}
+
+fn color_hint_attrs(color: YuvColor) -> [i32; 4] {
+	let space = match color.space {
+		YuvColorSpace::Bt601 => egl::ITU_REC601_EXT,
+		YuvColorSpace::Bt709 => egl::ITU_REC709_EXT,
+		YuvColorSpace::Bt2020 => egl::ITU_REC2020_EXT,
+	};
+	let range = match color.range {
+		YuvRange::Limited => egl::YUV_NARROW_RANGE_EXT,
+		YuvRange::Full => egl::YUV_FULL_RANGE_EXT,
+	};
+	[
+		egl::YUV_COLOR_SPACE_HINT_EXT as i32,
+		space as i32,
+		egl::SAMPLE_RANGE_HINT_EXT as i32,
+		range as i32,
+	]
+}
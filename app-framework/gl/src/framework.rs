@@ -1,12 +1,16 @@
+use std::collections::HashSet;
 use std::os::fd::RawFd;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use anyhow::Context as _;
-use tab_app_framework_core as core;
-use tab_app_framework_xkb::XkbEngine;
-use tracing::error;
+use tab_app_framework_core::{self as core, DrivenFramework};
+use tracing::{error, info, warn};
 
-use crate::{GlContext, GlError, GlVersion};
+use crate::debug_overlay::{DebugOverlay, OverlayStats};
+use crate::parallel::RenderPool;
+use crate::{
+	FrameCaptureEvent, GlContext, GlError, GlResetStatus, GlVersion, PixelRect, RenderJob,
+};
 
 /// GL-specialized application trait.
 ///
@@ -16,10 +20,33 @@ pub trait GlApplication: Sized + 'static {
 	/// Constructs the application with access to GL initialization state.
 	fn init(ctx: &mut GlInitContext) -> anyhow::Result<Self>;
 
+	/// Called before the iteration's `on_render` calls with the monitors due for a frame,
+	/// sorted by id; see [`core::Application::on_begin_frame`].
+	fn on_begin_frame(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _monitor_ids: &[String]) {}
+	/// Called after the iteration's last `on_render` call.
+	fn on_end_frame(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>) {}
 	/// Called after a buffer is acquired and bound as current render target.
-	fn on_render(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::RenderEvent) {}
+	fn on_render(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::RenderEvent<'_>) {}
+	/// Returns the work for a monitor's frame when [`core::Config::set_parallel_render`] is on
+	/// and several monitors are due at once.
+	///
+	/// The job runs on a render worker whose context shares textures and buffers, but not
+	/// vertex arrays or framebuffers, with this one; the monitor's buffer is bound and
+	/// cleared per its clear policy. Returning `None` renders the monitor through
+	/// `on_render` instead, as happens for multisampled targets, pending captures and while
+	/// the debug overlay is shown.
+	fn render_job(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_monitor_id: &str,
+	) -> Option<RenderJob> {
+		None
+	}
 	/// Called when a rendered buffer is presented/released.
 	fn on_present(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::PresentEvent) {}
+	/// Called with the pixels of a frame requested via [`GlEventContext::capture_frame`].
+	fn on_frame_captured(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: FrameCaptureEvent) {
+	}
 	/// Called when a monitor is added.
 	fn on_monitor_added(
 		&mut self,
@@ -34,11 +61,146 @@ pub trait GlApplication: Sized + 'static {
 		_ev: core::MonitorRemovedEvent,
 	) {
 	}
+	/// Called when compositor chrome starts or stops covering parts of a monitor.
+	fn on_safe_area_changed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SafeAreaChangedEvent,
+	) {
+	}
+	/// Called when a monitor's display is switched on, to standby or off.
+	fn on_monitor_power_changed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::MonitorPowerChangedEvent,
+	) {
+	}
+	/// Called when the server hands a monitor's buffer back for rendering.
+	fn on_buffer_available(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::BufferAvailableEvent,
+	) {
+	}
+	/// Called when the server held every buffer of a monitor for the starvation threshold.
+	fn on_starved(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::StarvedEvent) {}
+	/// Called after a monitor's swapchain was recreated; its GL imports are already dropped.
+	fn on_swapchain_recreated(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SwapchainRecreatedEvent,
+	) {
+	}
+	/// Called when monitors were added, removed, moved or resized in the global layout.
+	fn on_layout_changed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::LayoutChangedEvent,
+	) {
+	}
+	/// Called when reduced motion, text scale or high contrast preferences change.
+	fn on_accessibility_changed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::AccessibilityChangedEvent,
+	) {
+	}
 	/// Called when session state updates arrive.
 	fn on_session_state(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::SessionEvent) {
 	}
+	/// Called when this session becomes the active one.
+	fn on_focus_gained(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::FocusEvent) {}
+	/// Called when another session becomes active.
+	fn on_focus_lost(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::FocusEvent) {}
+	/// Called with a snapshot requested through [`GlEventContext::request_session_preview`].
+	fn on_session_preview(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SessionPreviewEvent,
+	) {
+	}
+	/// Called when a capture from [`GlEventContext::start_capture`] has a new frame.
+	///
+	/// The default implementation hands the buffer straight back.
+	fn on_capture_frame(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::CaptureFrameEvent,
+	) {
+		let _ = ctx.release_capture_frame(&ev);
+	}
+	/// Called when shift stopped a capture on its own.
+	fn on_capture_ended(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::CaptureEndedEvent,
+	) {
+	}
+	/// Called when another session sends this session a message.
+	fn on_session_message(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SessionMessageEvent,
+	) {
+	}
+	/// Called in admin sessions when another session reported a crash.
+	fn on_session_crashed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SessionCrashedEvent,
+	) {
+	}
+	/// Called with the answer to a `create_session_async` request.
+	fn on_session_create_result(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SessionCreateResultEvent,
+	) {
+	}
+	/// Called with the outcome of a `switch_session_async` request.
+	fn on_session_switch_result(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SessionSwitchResultEvent,
+	) {
+	}
+	/// Called once the framework sent `session_ready` for `Config::auto_ready`.
+	fn on_session_ready_sent(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::SessionReadySentEvent,
+	) {
+	}
+	/// Called when the server stopped answering keepalive pings.
+	fn on_server_unresponsive(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::ServerUnresponsiveEvent,
+	) {
+	}
+	/// Called when the server answers keepalive pings again.
+	fn on_server_recovered(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::ServerRecoveredEvent,
+	) {
+	}
+	/// Called once changes made through [`GlEventContext::update_config`] took effect.
+	fn on_config_changed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::ConfigChangedEvent,
+	) {
+	}
 	/// Called for every raw input payload.
-	fn on_input(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::InputEvent) {}
+	fn on_input(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: &core::InputEvent) {}
+	/// Called when a shortcut from [`GlEventContext::register_global_shortcut`] is pressed.
+	fn on_global_shortcut(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::GlobalShortcutEvent,
+	) {
+	}
 	/// Called for key events.
 	fn on_key(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::KeyEvent) {}
 	/// Called for composed text events.
@@ -91,6 +253,71 @@ pub trait GlApplication: Sized + 'static {
 	fn on_gesture(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::GestureEvent) {}
 	/// Called when a watched FD is readable.
 	fn on_fd_ready(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::FdReadyEvent) {}
+	/// Called once per stats interval with the loop counters it accumulated.
+	fn on_stats(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _stats: core::LoopStatsSnapshot) {
+	}
+	/// Called once per loop iteration with all events when batching is enabled.
+	fn on_events(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _events: &[core::Event]) {}
+	/// Called with server messages decoded by a registered protocol extension.
+	fn on_extension_event(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::ExtensionEvent,
+	) {
+	}
+	/// Called with values sent through [`core::FrameworkController::send_user_event`].
+	fn on_user_event(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::UserEvent) {}
+	/// Called when an input device is plugged in.
+	fn on_input_device_added(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::InputDeviceAddedEvent,
+	) {
+	}
+	/// Called when an input device is unplugged.
+	fn on_input_device_removed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::InputDeviceRemovedEvent,
+	) {
+	}
+	/// Called when the idle timeout requested through the context elapses without input.
+	fn on_idle(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, _ev: core::IdleEvent) {}
+	/// Called on the first input event after `on_idle`.
+	fn on_resume_activity(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::ResumeActivityEvent,
+	) {
+	}
+	/// Called when a monitor's mode or properties change without reconnecting.
+	fn on_monitor_changed(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::MonitorChangedEvent,
+	) {
+	}
+	/// Called with the monitors due for a frame in explicit submit mode.
+	fn on_frame_requested(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_ev: core::FrameRequestEvent,
+	) {
+	}
+	/// Called when a GPU reset destroyed the GL context, before it is recreated.
+	///
+	/// Every GL object created so far is gone; drop the handles without deleting them and
+	/// issue no GL calls until [`Self::on_gl_context_restored`].
+	fn on_gl_context_lost(
+		&mut self,
+		_ctx: &mut GlEventContext<'_, '_, Self>,
+		_status: GlResetStatus,
+	) {
+	}
+	/// Called once a new context replaced the lost one; recreate GL resources here.
+	///
+	/// Every monitor has a frame scheduled to redraw its contents.
+	fn on_gl_context_restored(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>) {}
 	/// Called when framework errors are surfaced.
 	fn on_error(&mut self, _ctx: &mut GlEventContext<'_, '_, Self>, error: &core::FrameworkError) {
 		error!(target: "tab_app_framework.gl", error = %error, "tab-app-framework-gl error");
@@ -126,6 +353,8 @@ impl GlInitContext {
 pub struct GlEventContext<'c, 'g, A: GlApplication> {
 	core: &'g mut core::Context<'c, GlBridge<A>>,
 	gl: &'g mut GlContext,
+	captures: &'g mut HashSet<String>,
+	debug_overlay: &'g mut DebugOverlay,
 }
 
 impl<'c, 'g, A: GlApplication> GlEventContext<'c, 'g, A> {
@@ -139,6 +368,26 @@ impl<'c, 'g, A: GlApplication> GlEventContext<'c, 'g, A> {
 		self.core.schedule_all_frames();
 	}
 
+	/// Schedules a frame for a monitor once `delay` has passed; earlier requests win.
+	pub fn request_redraw_after(&mut self, delay: Duration, monitor_id: &str) {
+		self.core.request_redraw_after(delay, monitor_id);
+	}
+
+	/// Stops rendering to a monitor until `resume_monitor`; see `Context::pause_monitor`.
+	pub fn pause_monitor(&mut self, monitor_id: &str) -> bool {
+		self.core.pause_monitor(monitor_id)
+	}
+
+	/// Resumes rendering to a paused monitor and schedules a frame for it.
+	pub fn resume_monitor(&mut self, monitor_id: &str) -> bool {
+		self.core.resume_monitor(monitor_id)
+	}
+
+	/// Returns true while a monitor is paused.
+	pub fn is_monitor_paused(&self, monitor_id: &str) -> bool {
+		self.core.is_monitor_paused(monitor_id)
+	}
+
 	/// Adds a file descriptor to the readable watch set.
 	pub fn watch_fd(&mut self, fd: RawFd) {
 		self.core.watch_fd(fd);
@@ -159,7 +408,32 @@ impl<'c, 'g, A: GlApplication> GlEventContext<'c, 'g, A> {
 		self.core.session()
 	}
 
-	/// Returns all monitors.
+	/// Returns the round trip of the last answered keepalive ping.
+	pub fn server_latency(&self) -> Option<Duration> {
+		self.core.server_latency()
+	}
+
+	/// Returns true while this session is the active one.
+	pub fn has_focus(&self) -> bool {
+		self.core.has_focus()
+	}
+
+	/// Admin-only: routes key events to this session while another stays active.
+	pub fn request_keyboard_grab(&mut self) -> Result<(), core::FrameworkError> {
+		self.core.request_keyboard_grab()
+	}
+
+	/// Ends a grab started with [`Self::request_keyboard_grab`].
+	pub fn release_keyboard_grab(&mut self) -> Result<(), core::FrameworkError> {
+		self.core.release_keyboard_grab()
+	}
+
+	/// Returns true while this session holds the keyboard grab.
+	pub fn has_keyboard_grab(&self) -> bool {
+		self.core.has_keyboard_grab()
+	}
+
+	/// Returns all monitors, ordered by layout position, then id.
 	pub fn monitors(&self) -> impl Iterator<Item = &core::Monitor> {
 		self.core.monitors()
 	}
@@ -169,6 +443,49 @@ impl<'c, 'g, A: GlApplication> GlEventContext<'c, 'g, A> {
 		self.core.monitor(monitor_id)
 	}
 
+	/// Returns the monitor under a global layout position.
+	pub fn monitor_at(&self, global_position: (f64, f64)) -> Option<&core::Monitor> {
+		self.core.monitor_at(global_position)
+	}
+
+	/// Returns how a monitor's buffers are prepared before `on_render`.
+	pub fn clear_policy(&self, monitor_id: &str) -> Option<core::ClearPolicy> {
+		self.core.clear_policy(monitor_id)
+	}
+
+	/// Clears a monitor's buffers to a color before each `on_render`, or stops doing so.
+	pub fn set_clear_policy(
+		&mut self,
+		monitor_id: &str,
+		policy: core::ClearPolicy,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_clear_policy(monitor_id, policy)
+	}
+
+	/// Returns how eager rendering retries a monitor's failed frames.
+	pub fn retry_policy(&self, monitor_id: &str) -> Option<core::RetryPolicy> {
+		self.core.retry_policy(monitor_id)
+	}
+
+	/// Changes how eager rendering retries a monitor's failed frames.
+	pub fn set_retry_policy(
+		&mut self,
+		monitor_id: &str,
+		policy: core::RetryPolicy,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_retry_policy(monitor_id, policy)
+	}
+
+	/// Returns the primary monitor.
+	pub fn primary_monitor(&self) -> Option<&core::Monitor> {
+		self.core.primary_monitor()
+	}
+
+	/// Makes a monitor the primary one; admin sessions only.
+	pub fn set_primary_monitor(&mut self, monitor_id: &str) -> Result<(), core::FrameworkError> {
+		self.core.set_primary_monitor(monitor_id)
+	}
+
 	/// Sets monitor position in the global monitor layout.
 	pub fn set_monitor_position(
 		&mut self,
@@ -179,6 +496,11 @@ impl<'c, 'g, A: GlApplication> GlEventContext<'c, 'g, A> {
 		self.core.set_monitor_position(monitor_id, x, y)
 	}
 
+	/// Replaces a monitor's swapchain, see [`core::Context::recreate_swapchain`].
+	pub fn recreate_swapchain(&mut self, monitor_id: &str) -> Result<(), core::FrameworkError> {
+		self.core.recreate_swapchain(monitor_id)
+	}
+
 	/// Applies default horizontal monitor layout.
 	pub fn apply_horizontal_layout(&mut self) {
 		self.core.apply_horizontal_layout();
@@ -189,178 +511,1188 @@ impl<'c, 'g, A: GlApplication> GlEventContext<'c, 'g, A> {
 		self.core.cursor_position()
 	}
 
-	/// Returns immutable access to GL context.
-	pub fn gl(&self) -> &GlContext {
-		self.gl
+	/// Returns phase timings of the last completed loop iteration.
+	pub fn frame_timings(&self) -> &core::FrameTimings {
+		self.core.frame_timings()
 	}
 
-	/// Returns mutable access to GL context.
-	pub fn gl_mut(&mut self) -> &mut GlContext {
-		self.gl
+	/// Returns loop counters for the last completed reporting interval.
+	pub fn loop_stats(&self) -> &core::LoopStatsSnapshot {
+		self.core.loop_stats()
 	}
 
-	/// Resolves a GL procedure by name.
-	pub fn load_proc(&self, name: &str) -> Result<*const std::ffi::c_void, GlError> {
-		self.gl.load_proc(name)
+	/// Returns who holds a monitor's swapchain buffers.
+	pub fn buffer_ownership(&self, monitor_id: &str) -> Option<core::BufferOwnership> {
+		self.core.buffer_ownership(monitor_id)
 	}
 
-	/// Returns currently bound framebuffer object id.
-	pub fn current_fbo(&self) -> i32 {
-		self.gl.current_fbo()
+	/// Returns the seat that produced the most recent input event.
+	pub fn seat(&self) -> &core::SeatId {
+		self.core.seat()
 	}
 
-	/// Sends `session_ready` for the current session.
-	pub fn session_ready(&mut self) -> Result<(), core::FrameworkError> {
-		self.core.session_ready()
+	/// Returns the cursor position of a seat that has produced input.
+	pub fn seat_cursor_position(&self, seat: &core::SeatId) -> Option<(f64, f64)> {
+		self.core.seat_cursor_position(seat)
 	}
 
-	/// Backward-compatible alias for [`GlEventContext::session_ready`].
-	pub fn send_ready(&mut self) -> Result<(), core::FrameworkError> {
-		self.session_ready()
+	/// Appends a filter to the raw input chain; see [`core::Context::add_input_filter`].
+	pub fn add_input_filter(&mut self, filter: impl core::InputFilter + 'static) {
+		self.core.add_input_filter(filter)
 	}
 
-	/// Requests creation of a new session and waits for completion.
-	pub fn create_session(
-		&mut self,
-		role: core::SessionRole,
-		display_name: Option<String>,
-	) -> Result<core::SessionCreatedPayload, core::FrameworkError> {
-		self.core.create_session(role, display_name)
+	/// Queues input from the current seat; see [`core::Context::inject_input`].
+	pub fn inject_input(&mut self, event: core::InputEventPayload) {
+		self.core.inject_input(event)
 	}
 
-	/// Requests switching to another session.
-	pub fn switch_session(
+	/// Delivers input to another session; admin sessions only.
+	pub fn inject_session_input(
 		&mut self,
 		session_id: &str,
-		animation: Option<String>,
-		duration: Duration,
+		seat: core::SeatId,
+		event: core::InputEventPayload,
 	) -> Result<(), core::FrameworkError> {
-		self.core.switch_session(session_id, animation, duration)
+		self.core.inject_session_input(session_id, seat, event)
 	}
-}
 
-/// High-level GL framework wrapper around the core runtime.
-pub struct GlTabAppFramework<A: GlApplication> {
-	inner: core::TabAppFramework<GlBridge<A>>,
-}
+	/// Returns all currently plugged input devices.
+	pub fn input_devices(&self) -> impl Iterator<Item = &core::InputDeviceInfo> {
+		self.core.input_devices()
+	}
 
-impl<A: GlApplication> GlTabAppFramework<A> {
-	/// Initializes a GL application runtime.
-	pub fn init(configure: impl FnOnce(&mut core::Config)) -> Result<Self, core::FrameworkError> {
-		let inner = core::TabAppFramework::<GlBridge<A>>::init(configure)?;
-		Ok(Self { inner })
+	/// Returns metadata for the device id carried by input events.
+	pub fn input_device(&self, device: u32) -> Option<&core::InputDeviceInfo> {
+		self.core.input_device(device)
 	}
 
-	/// Runs the application loop until exit.
-	pub fn run(&mut self) -> Result<(), core::FrameworkError> {
-		self.inner.run()
+	/// Returns the monitor a device is mapped to, such as a touchscreen's panel.
+	pub fn input_device_monitor(&self, device: u32) -> Option<&core::Monitor> {
+		self.core.input_device_monitor(device)
 	}
-}
 
-struct GlBridge<A: GlApplication> {
-	app: A,
-	gl: GlContext,
-	xkb: XkbEngine,
-}
+	/// Requests `on_idle` once no input arrives for `timeout`.
+	pub fn request_idle_notification(&mut self, timeout: Duration) {
+		self.core.request_idle_notification(timeout)
+	}
 
-impl<A: GlApplication> core::Application for GlBridge<A> {
-	fn init(ctx: &mut core::InitContext<Self>) -> anyhow::Result<Self> {
-		let (major, minor) = ctx.config().requested_opengl_version();
-		let version = GlVersion { major, minor };
-		let gl = GlContext::new(version, ctx.config().render_node_path())
-			.context("failed to create GL context")?;
-		let mut init = GlInitContext::new(gl);
-		let app = A::init(&mut init)?;
-		let xkb = XkbEngine::new().context("failed to initialize xkb engine")?;
-		Ok(Self {
-			app,
-			gl: init.into_parts(),
-			xkb,
-		})
+	/// Stops idle tracking started by `request_idle_notification`.
+	pub fn cancel_idle_notification(&mut self) {
+		self.core.cancel_idle_notification()
 	}
 
-	fn on_render(&mut self, ctx: &mut core::Context<Self>, ev: core::RenderEvent) {
-		if let Err(err) = self.gl.make_current() {
-			let ferr = core::FrameworkError::Config(format!("gl make current failed: {err}"));
-			self.on_error(ctx, &ferr);
-			return;
-		}
-		if let Err(err) = self.gl.prepare_render_target(&ev) {
-			let ferr = core::FrameworkError::Config(format!("prepare render target failed: {err}"));
-			self.on_error(ctx, &ferr);
-			return;
-		}
-		let mut ctx = GlEventContext {
-			core: ctx,
-			gl: &mut self.gl,
-		};
-		self.app.on_render(&mut ctx, ev);
-		match ctx.gl.create_acquire_fence_fd() {
-			Ok(fence_fd) => ctx.core.set_next_acquire_fence(fence_fd),
-			Err(err) => {
-				let ferr = core::FrameworkError::Config(format!("create acquire fence failed: {err}"));
-				self.app.on_error(&mut ctx, &ferr);
-			}
-		}
+	/// Returns true between `on_idle` and the next input event.
+	pub fn is_idle(&self) -> bool {
+		self.core.is_idle()
 	}
 
-	fn on_present(&mut self, ctx: &mut core::Context<Self>, ev: core::PresentEvent) {
-		let mut ctx = GlEventContext {
-			core: ctx,
-			gl: &mut self.gl,
-		};
-		self.app.on_present(&mut ctx, ev);
+	/// Asks the server to keep this session awake until the returned guard is dropped.
+	pub fn inhibit_idle(&mut self, reason: impl Into<String>) -> core::IdleInhibitor {
+		self.core.inhibit_idle(reason)
 	}
 
-	fn on_monitor_added(&mut self, ctx: &mut core::Context<Self>, ev: core::MonitorAddedEvent) {
-		let mut ctx = GlEventContext {
-			core: ctx,
-			gl: &mut self.gl,
-		};
-		self.app.on_monitor_added(&mut ctx, ev);
+	/// Scales a monitor's output brightness; admin sessions only.
+	pub fn set_monitor_brightness(
+		&mut self,
+		monitor_id: &str,
+		brightness: f32,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_monitor_brightness(monitor_id, brightness)
 	}
 
-	fn on_monitor_removed(&mut self, ctx: &mut core::Context<Self>, ev: core::MonitorRemovedEvent) {
-		self.gl.release_monitor_targets(&ev.monitor_id);
-		let mut ctx = GlEventContext {
-			core: ctx,
-			gl: &mut self.gl,
-		};
-		self.app.on_monitor_removed(&mut ctx, ev);
+	/// Switches a monitor's display on, to standby or off; admin sessions only.
+	pub fn set_monitor_power(
+		&mut self,
+		monitor_id: &str,
+		power: core::MonitorPower,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_monitor_power(monitor_id, power)
 	}
 
-	fn on_session_state(&mut self, ctx: &mut core::Context<Self>, ev: core::SessionEvent) {
-		let mut ctx = GlEventContext {
-			core: ctx,
-			gl: &mut self.gl,
-		};
-		self.app.on_session_state(&mut ctx, ev);
+	/// Returns the power state of a monitor's display.
+	pub fn monitor_power(&self, monitor_id: &str) -> Option<core::MonitorPower> {
+		self.core.monitor_power(monitor_id)
 	}
 
-	fn on_input(&mut self, ctx: &mut core::Context<Self>, ev: core::InputEvent) {
-		let mut ctx = GlEventContext {
-			core: ctx,
-			gl: &mut self.gl,
-		};
-		self.app.on_input(&mut ctx, ev);
+	/// Returns the system-wide accessibility settings.
+	pub fn accessibility(&self) -> &core::AccessibilitySettings {
+		self.core.accessibility()
 	}
 
-	fn on_key(&mut self, ctx: &mut core::Context<Self>, ev: core::KeyEvent) {
+	/// Changes the system-wide accessibility settings; admin sessions only.
+	pub fn set_accessibility(
+		&mut self,
+		settings: core::AccessibilitySettings,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_accessibility(settings)
+	}
+
+	/// Replaces a monitor's gamma ramps; admin sessions only.
+	pub fn set_gamma_lut(
+		&mut self,
+		monitor_id: &str,
+		ramps: Option<core::GammaRamps>,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_gamma_lut(monitor_id, ramps)
+	}
+
+	/// Returns the modes a monitor advertises.
+	pub fn monitor_modes(&self, monitor_id: &str) -> Option<&[core::MonitorMode]> {
+		self.core.monitor_modes(monitor_id)
+	}
+
+	/// Returns which parts of a monitor compositor chrome covers, in buffer pixels.
+	pub fn safe_area(&self, monitor_id: &str) -> Option<core::SafeArea> {
+		self.core.safe_area(monitor_id)
+	}
+
+	/// Declares the parts of a monitor this session draws chrome over; admin sessions only.
+	pub fn set_occluded_areas(
+		&mut self,
+		monitor_id: &str,
+		occluded: Vec<core::Rect>,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_occluded_areas(monitor_id, occluded)
+	}
+
+	/// Asks the server to switch a monitor's mode; admin sessions only.
+	pub fn request_mode(
+		&mut self,
+		monitor_id: &str,
+		mode: core::MonitorMode,
+	) -> Result<(), core::FrameworkError> {
+		self.core.request_mode(monitor_id, mode)
+	}
+
+	/// Drops the frame being rendered instead of presenting it.
+	pub fn cancel_frame(&mut self) {
+		self.core.cancel_frame()
+	}
+
+	/// Marks frames rendered to a monitor as fullscreen and opaque.
+	pub fn set_scanout_candidate(
+		&mut self,
+		monitor_id: &str,
+		fullscreen_opaque: bool,
+	) -> Result<(), core::FrameworkError> {
+		self
+			.core
+			.set_scanout_candidate(monitor_id, fullscreen_opaque)
+	}
+
+	/// Tags the color space of this session's frames on a monitor.
+	pub fn set_surface_colorspace(
+		&mut self,
+		monitor_id: &str,
+		colorspace: core::Colorspace,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_surface_colorspace(monitor_id, colorspace)
+	}
+
+	/// Decodes server messages whose header starts with `header_prefix`; see
+	/// [`core::Context::register_extension`].
+	pub fn register_extension<T, F>(&mut self, header_prefix: impl Into<String>, decoder: F)
+	where
+		T: std::any::Any,
+		F: Fn(&core::TabMessageFrame) -> Result<T, core::ProtocolError> + 'static,
+	{
+		self.core.register_extension(header_prefix, decoder)
+	}
+
+	/// Returns immutable access to GL context.
+	pub fn gl(&self) -> &GlContext {
+		self.gl
+	}
+
+	/// Returns mutable access to GL context.
+	pub fn gl_mut(&mut self) -> &mut GlContext {
+		self.gl
+	}
+
+	/// Resolves a GL procedure by name.
+	pub fn load_proc(&self, name: &str) -> Result<*const std::ffi::c_void, GlError> {
+		self.gl.load_proc(name)
+	}
+
+	/// Returns currently bound framebuffer object id.
+	pub fn current_fbo(&self) -> i32 {
+		self.gl.current_fbo()
+	}
+
+	/// Acquires a buffer of `monitor_id` for explicit rendering, see [`Self::bind_frame`].
+	pub fn acquire_target(
+		&mut self,
+		monitor_id: &str,
+	) -> Result<core::AcquiredFrame, core::FrameworkError> {
+		self.core.acquire_target(monitor_id)
+	}
+
+	/// Binds an acquired frame as the current render target.
+	pub fn bind_frame(&mut self, frame: &core::AcquiredFrame) -> Result<(), core::FrameworkError> {
+		if frame.resized() {
+			self.gl.release_monitor_dmabuf_targets(frame.monitor_id());
+		}
+		let target = self.core.render_target(frame)?;
+		self
+			.gl
+			.prepare_render_target(frame.monitor_id(), &target)
+			.map_err(|err| err.into_framework_error("prepare render target failed"))
+	}
+
+	/// Fences the GL commands issued so far and submits the frame for presentation.
+	///
+	/// If no fence can be created the frame is discarded and the error returned.
+	pub fn submit_frame(&mut self, frame: core::AcquiredFrame) -> Result<(), core::FrameworkError> {
+		match self.gl.create_frame_fence() {
+			Ok(fence_fd) => self.core.submit_frame(frame, fence_fd),
+			Err(err) => {
+				self.core.discard_frame(frame);
+				Err(err.into_framework_error("create acquire fence failed"))
+			}
+		}
+	}
+
+	/// Returns an acquired frame to the swapchain without presenting it.
+	pub fn discard_frame(&mut self, frame: core::AcquiredFrame) {
+		self.core.discard_frame(frame);
+	}
+
+	/// Creates an overlay above this session's frames on a monitor.
+	pub fn create_overlay(
+		&mut self,
+		monitor_id: &str,
+		size: (i32, i32),
+		z: i32,
+	) -> Result<core::OverlayId, core::FrameworkError> {
+		self.core.create_overlay(monitor_id, size, z)
+	}
+
+	/// Moves an overlay, relative to its monitor's top-left corner.
+	pub fn set_overlay_position(
+		&mut self,
+		overlay_id: core::OverlayId,
+		position: (i32, i32),
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_overlay_position(overlay_id, position)
+	}
+
+	/// Destroys an overlay and the GL framebuffers imported for it.
+	pub fn destroy_overlay(
+		&mut self,
+		overlay_id: core::OverlayId,
+	) -> Result<(), core::FrameworkError> {
+		self
+			.gl
+			.release_monitor_dmabuf_targets(&overlay_id.to_string());
+		self.core.destroy_overlay(overlay_id)
+	}
+
+	/// Acquires an overlay buffer for rendering, see [`Self::bind_overlay`].
+	pub fn acquire_overlay(
+		&mut self,
+		overlay_id: core::OverlayId,
+	) -> Result<core::OverlayFrame, core::FrameworkError> {
+		self.core.acquire_overlay(overlay_id)
+	}
+
+	/// Makes the GL context current and binds an acquired overlay frame as the render target.
+	pub fn bind_overlay(&mut self, frame: &core::OverlayFrame) -> Result<(), core::FrameworkError> {
+		self
+			.gl
+			.make_current()
+			.map_err(|err| err.into_framework_error("make_current failed"))?;
+		let target = self.core.overlay_target(frame)?;
+		self
+			.gl
+			.prepare_render_target(&frame.overlay_id().to_string(), &target)
+			.map_err(|err| err.into_framework_error("prepare render target failed"))
+	}
+
+	/// Fences the GL commands issued so far and shows the overlay frame.
+	///
+	/// If no fence can be created the frame is discarded and the error returned.
+	pub fn submit_overlay(&mut self, frame: core::OverlayFrame) -> Result<(), core::FrameworkError> {
+		match self.gl.create_frame_fence() {
+			Ok(fence_fd) => self.core.submit_overlay(frame, fence_fd),
+			Err(err) => {
+				self.core.discard_overlay(frame);
+				Err(err.into_framework_error("create acquire fence failed"))
+			}
+		}
+	}
+
+	/// Returns an acquired overlay frame to its swapchain without showing it.
+	pub fn discard_overlay(&mut self, frame: core::OverlayFrame) {
+		self.core.discard_overlay(frame);
+	}
+
+	/// Schedules a frame for a monitor and reads it back once rendered.
+	///
+	/// Pixels are delivered to [`GlApplication::on_frame_captured`] after the
+	/// next `on_render` for that monitor returns.
+	pub fn capture_frame(&mut self, monitor_id: impl Into<String>) {
+		let monitor_id = monitor_id.into();
+		self.captures.insert(monitor_id.clone());
+		self.core.schedule_frame(monitor_id);
+	}
+
+	/// Returns true when frames are submitted with explicit acquire fences.
+	///
+	/// False when the driver cannot export native fences; see [`core::SyncMode`].
+	pub fn explicit_sync_supported(&self) -> bool {
+		self.gl.explicit_sync_supported()
+	}
+
+	/// Shows or hides the FPS and frame-timing overlay drawn after each `on_render`.
+	///
+	/// The initial state comes from [`core::Config::set_debug_overlay`].
+	pub fn set_debug_overlay(&mut self, enabled: bool) {
+		self.debug_overlay.set_enabled(enabled);
+	}
+
+	/// Returns true while the debug overlay is shown.
+	pub fn debug_overlay(&self) -> bool {
+		self.debug_overlay.enabled()
+	}
+
+	/// Sends `session_ready` for the current session.
+	pub fn session_ready(&mut self) -> Result<(), core::FrameworkError> {
+		self.core.session_ready()
+	}
+
+	/// Renames this session for admin switcher UIs.
+	pub fn set_session_display_name(
+		&mut self,
+		name: impl Into<String>,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_session_display_name(name)
+	}
+
+	/// Sets one of this session's metadata entries, or removes it with `None`.
+	pub fn set_session_metadata(
+		&mut self,
+		key: impl Into<String>,
+		value: Option<String>,
+	) -> Result<(), core::FrameworkError> {
+		self.core.set_session_metadata(key, value)
+	}
+
+	/// Backward-compatible alias for [`GlEventContext::session_ready`].
+	pub fn send_ready(&mut self) -> Result<(), core::FrameworkError> {
+		self.session_ready()
+	}
+
+	/// Requests creation of a new session and waits for completion.
+	pub fn create_session(
+		&mut self,
+		role: core::SessionRole,
+		display_name: Option<String>,
+	) -> Result<core::SessionCreatedPayload, core::FrameworkError> {
+		self.core.create_session(role, display_name)
+	}
+
+	/// Requests switching to another session.
+	pub fn switch_session(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+	) -> Result<(), core::FrameworkError> {
+		self.core.switch_session(session_id, animation, duration)
+	}
+
+	/// Requests creation of a new session without blocking the loop.
+	pub fn create_session_async(
+		&mut self,
+		role: core::SessionRole,
+		display_name: Option<String>,
+	) -> Result<core::RequestId, core::FrameworkError> {
+		self.core.create_session_async(role, display_name)
+	}
+
+	/// Requests switching to another session and reports the outcome.
+	pub fn switch_session_async(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+	) -> Result<core::RequestId, core::FrameworkError> {
+		self
+			.core
+			.switch_session_async(session_id, animation, duration)
+	}
+
+	/// Admin-only: asks for a downscaled snapshot of another session's latest frame.
+	pub fn request_session_preview(
+		&mut self,
+		session_id: &str,
+		max_size: (i32, i32),
+	) -> Result<(), core::FrameworkError> {
+		self.core.request_session_preview(session_id, max_size)
+	}
+
+	/// Sends `data` to another session; returns the message's correlation id.
+	pub fn send_to_session(
+		&mut self,
+		session_id: &str,
+		data: &[u8],
+	) -> Result<u64, core::FrameworkError> {
+		self.core.send_to_session(session_id, data)
+	}
+
+	/// Answers a message received through [`GlApplication::on_session_message`].
+	pub fn reply_to_session(
+		&mut self,
+		message: &core::SessionMessageEvent,
+		data: &[u8],
+	) -> Result<u64, core::FrameworkError> {
+		self.core.reply_to_session(message, data)
+	}
+
+	/// Returns the configuration in effect.
+	pub fn config(&self) -> &core::Config {
+		self.core.config()
+	}
+
+	/// Changes live configuration fields; returns the changed fields that need a new
+	/// connection, see [`core::Context::update_config`].
+	pub fn update_config(
+		&mut self,
+		update: impl FnOnce(&mut core::Config),
+	) -> Vec<core::ConfigField> {
+		self.core.update_config(update)
+	}
+
+	/// Admin-only: reserves a key combination, given as evdev key codes, across sessions.
+	pub fn register_global_shortcut(
+		&mut self,
+		keys: &[u32],
+	) -> Result<core::GlobalShortcutId, core::FrameworkError> {
+		self.core.register_global_shortcut(keys)
+	}
+
+	/// Releases a shortcut registered with [`Self::register_global_shortcut`].
+	pub fn unregister_global_shortcut(
+		&mut self,
+		shortcut_id: core::GlobalShortcutId,
+	) -> Result<(), core::FrameworkError> {
+		self.core.unregister_global_shortcut(shortcut_id)
+	}
+
+	/// Admin-only: starts copying `source` into a capture swapchain of `size`.
+	pub fn start_capture(
+		&mut self,
+		source: core::CaptureSource,
+		size: (i32, i32),
+	) -> Result<core::CaptureId, core::FrameworkError> {
+		self.core.start_capture(source, size)
+	}
+
+	/// Returns the buffer holding a captured frame, until it is released.
+	pub fn capture_target(
+		&self,
+		frame: &core::CaptureFrameEvent,
+	) -> Result<core::RenderTarget<'_>, core::FrameworkError> {
+		self.core.capture_target(frame)
+	}
+
+	/// Hands a captured frame's buffer back to shift for following frames.
+	pub fn release_capture_frame(
+		&mut self,
+		frame: &core::CaptureFrameEvent,
+	) -> Result<(), core::FrameworkError> {
+		self.core.release_capture_frame(frame)
+	}
+
+	/// Stops a capture started with [`Self::start_capture`].
+	pub fn stop_capture(&mut self, capture_id: core::CaptureId) -> Result<(), core::FrameworkError> {
+		self.core.stop_capture(capture_id)
+	}
+}
+
+/// High-level GL framework wrapper around the core runtime.
+pub struct GlTabAppFramework<A: GlApplication> {
+	inner: core::TabAppFramework<GlBridge<A>>,
+}
+
+impl<A: GlApplication> GlTabAppFramework<A> {
+	/// Initializes a GL application runtime.
+	pub fn init(configure: impl FnOnce(&mut core::Config)) -> Result<Self, core::FrameworkError> {
+		let inner = core::TabAppFramework::<GlBridge<A>>::init(configure)?;
+		Ok(Self { inner })
+	}
+
+	/// Initializes a GL application runtime from an explicit configuration.
+	///
+	/// Every instance gets its own EGL display and context, so several can share a
+	/// process; see [`core::MultiFrameworkDriver`].
+	pub fn init_with_config(config: core::Config) -> Result<Self, core::FrameworkError> {
+		let inner = core::TabAppFramework::<GlBridge<A>>::init_with_config(config)?;
+		Ok(Self { inner })
+	}
+
+	/// Initializes a GL application runtime on a new thread and runs it there.
+	///
+	/// The GL context is created and made current on that thread.
+	pub fn spawn<F>(configure: F) -> Result<core::FrameworkController, core::FrameworkError>
+	where
+		F: FnOnce(&mut core::Config) + Send + 'static,
+	{
+		core::TabAppFramework::<GlBridge<A>>::spawn(configure)
+	}
+
+	/// Runs the application loop until exit.
+	pub fn run(&mut self) -> Result<(), core::FrameworkError> {
+		self.inner.run()
+	}
+}
+
+impl<A: GlApplication> DrivenFramework for GlTabAppFramework<A> {
+	fn poll_fds(&self) -> Vec<RawFd> {
+		self.inner.poll_fds()
+	}
+
+	fn poll_timeout(&self) -> Option<Duration> {
+		self.inner.poll_timeout()
+	}
+
+	fn dispatch(&mut self) -> Result<(), core::FrameworkError> {
+		// The current context is per thread; another instance may have rendered since.
+		// A lost context is recreated before the next frame.
+		match self.inner.app().gl.make_current() {
+			Ok(()) | Err(GlError::ContextLost(_)) => {}
+			Err(err) => return Err(err.into_framework_error("make_current failed")),
+		}
+		self.inner.dispatch()
+	}
+
+	fn exit_requested(&self) -> bool {
+		self.inner.exit_requested()
+	}
+}
+
+struct GlBridge<A: GlApplication> {
+	app: A,
+	gl: GlContext,
+	captures: HashSet<String>,
+	debug_overlay: DebugOverlay,
+	context_lost: bool,
+	/// Workers of [`core::Config::set_parallel_render`], started on first use.
+	render_pool: Option<RenderPool>,
+	render_pool_failed: bool,
+}
+
+impl<A: GlApplication> GlBridge<A> {
+	/// Makes the GL context current, recreating it first if a GPU reset destroyed it.
+	fn ensure_current(&mut self, ctx: &mut core::Context<Self>) -> Result<(), core::FrameworkError> {
+		let status = match self.gl.make_current() {
+			Ok(()) => match self.gl.reset_status() {
+				Some(status) => status,
+				None => return Ok(()),
+			},
+			Err(GlError::ContextLost(status)) => status,
+			Err(err) => return Err(err.into_framework_error("gl make current failed")),
+		};
+		// Recreation may fail and be retried; the application hears about the loss once.
+		if !self.context_lost {
+			self.context_lost = true;
+			warn!(target: "tab_app_framework.gl", ?status, "GL context lost, recreating");
+			let mut ctx = GlEventContext {
+				core: ctx,
+				gl: &mut self.gl,
+				captures: &mut self.captures,
+				debug_overlay: &mut self.debug_overlay,
+			};
+			self.app.on_gl_context_lost(&mut ctx, status);
+		}
+		// Worker contexts share the lost context's objects and go with it.
+		self.render_pool = None;
+		self.render_pool_failed = false;
+		self
+			.gl
+			.recreate()
+			.map_err(|err| err.into_framework_error("gl context recreation failed"))?;
+		self.context_lost = false;
+		info!(target: "tab_app_framework.gl", "GL context recreated");
+		ctx.schedule_all_frames();
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_gl_context_restored(&mut ctx);
+		Ok(())
+	}
+
+	/// Starts the render workers on first use; `None` once they failed to start.
+	fn render_pool(&mut self) -> Option<&mut RenderPool> {
+		if self.render_pool.is_none() && !self.render_pool_failed {
+			match RenderPool::new(&self.gl) {
+				Ok(pool) => self.render_pool = Some(pool),
+				Err(err) => {
+					warn!(
+						target: "tab_app_framework.gl",
+						error = %err,
+						"render workers unavailable, rendering serially"
+					);
+					self.render_pool_failed = true;
+				}
+			}
+		}
+		self.render_pool.as_mut()
+	}
+}
+
+impl<A: GlApplication> core::Application for GlBridge<A> {
+	fn init(ctx: &mut core::InitContext<Self>) -> anyhow::Result<Self> {
+		let (major, minor) = ctx.config().requested_opengl_version();
+		let version = GlVersion { major, minor };
+		let mut gl = GlContext::with_options(
+			version,
+			ctx.config().render_node_path(),
+			ctx.config().surface_format(),
+			ctx.config().gl_context_options(),
+		)
+		.context("failed to create GL context")?;
+		// Swapchains are allocated for the negotiated format.
+		ctx.config_mut().set_surface_format(gl.surface_format());
+		gl.set_sync_mode(ctx.config().sync_mode());
+		gl.set_default_render_target_options(ctx.config().render_target_options());
+		if ctx.config().frame_tracing() {
+			let device = gl.device_info();
+			info!(
+				target: "tab_app_framework.gl",
+				renderer = %device.renderer,
+				vendor = %device.vendor,
+				version = %device.version,
+				render_node = %device.render_node.display(),
+				egl_extensions = %device.egl_extensions.join(" "),
+				"GL device selected"
+			);
+		}
+		// GL apps always receive `on_char`; core composes the text.
+		ctx.config_mut().enable_text_composition(true);
+		let mut init = GlInitContext::new(gl);
+		let app = A::init(&mut init)?;
+		Ok(Self {
+			app,
+			gl: init.into_parts(),
+			captures: HashSet::new(),
+			debug_overlay: DebugOverlay::new(ctx.config().debug_overlay()),
+			context_lost: false,
+			render_pool: None,
+			render_pool_failed: false,
+		})
+	}
+
+	fn on_begin_frame(&mut self, ctx: &mut core::Context<Self>, monitor_ids: &[String]) {
+		if let Err(ferr) = self.ensure_current(ctx) {
+			self.on_error(ctx, &ferr);
+			return;
+		}
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_begin_frame(&mut ctx, monitor_ids);
+	}
+
+	fn on_end_frame(&mut self, ctx: &mut core::Context<Self>) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_end_frame(&mut ctx);
+	}
+
+	fn on_render(&mut self, ctx: &mut core::Context<Self>, ev: core::RenderEvent<'_>) {
+		if let Err(ferr) = self.ensure_current(ctx) {
+			self.on_error(ctx, &ferr);
+			return;
+		}
+		if ev.resized() {
+			// The swapchain was recreated; cached imports point at the old buffers.
+			self.gl.release_monitor_dmabuf_targets(ev.monitor_id());
+		}
+		if let Err(err) = self.gl.prepare_render_target(ev.monitor_id(), ev.target()) {
+			let ferr = err.into_framework_error("prepare render target failed");
+			self.on_error(ctx, &ferr);
+			return;
+		}
+		if let core::ClearPolicy::Color(color) = ev.clear_policy()
+			&& let Err(err) = self.gl.clear_current_target(color)
+		{
+			let ferr = err.into_framework_error("clear render target failed");
+			self.on_error(ctx, &ferr);
+			return;
+		}
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		let monitor_id = ev.monitor_id();
+		let size = (ev.width(), ev.height());
+		let render_start = Instant::now();
+		self.app.on_render(&mut ctx, ev);
+		if ctx.core.frame_cancelled() {
+			// Nothing is presented; a pending capture waits for the next frame.
+			return;
+		}
+		if ctx.debug_overlay.enabled() {
+			ctx
+				.debug_overlay
+				.record_frame(monitor_id, render_start.elapsed());
+			let stats = OverlayStats {
+				acquire_miss: ctx.core.loop_stats().acquire_miss,
+				fence_wait: ctx.core.frame_timings().fence_wait,
+				refresh_rate: ctx.core.monitor(monitor_id).map_or(0, |m| m.refresh_rate),
+			};
+			match ctx.gl.bind_current_target() {
+				Ok(()) => ctx.debug_overlay.draw(ctx.gl, monitor_id, stats),
+				Err(err) => {
+					let ferr = err.into_framework_error("debug overlay failed");
+					self.app.on_error(&mut ctx, &ferr);
+				}
+			}
+		}
+		if ctx.captures.remove(monitor_id) {
+			let image = ctx
+				.gl
+				.bind_current_target()
+				.and_then(|()| ctx.gl.resolve_current_target())
+				.and_then(|()| ctx.gl.read_pixels(PixelRect::from_size(size.0, size.1)));
+			match image {
+				Ok(image) => {
+					let ev = FrameCaptureEvent {
+						monitor_id: monitor_id.to_string(),
+						image,
+					};
+					self.app.on_frame_captured(&mut ctx, ev);
+				}
+				Err(err) => {
+					let ferr = err.into_framework_error("frame capture failed");
+					self.app.on_error(&mut ctx, &ferr);
+				}
+			}
+		}
+		match ctx.gl.create_frame_fence() {
+			Ok(Some(fence_fd)) => ctx.core.set_next_acquire_fence(fence_fd),
+			Ok(None) => {}
+			Err(err) => {
+				let ferr = err.into_framework_error("create acquire fence failed");
+				self.app.on_error(&mut ctx, &ferr);
+			}
+		}
+	}
+
+	fn on_render_parallel(&mut self, ctx: &mut core::Context<Self>, ev: core::FrameRequestEvent) {
+		// The debug overlay is drawn on the render context; leave every monitor to on_render.
+		if self.debug_overlay.enabled() {
+			return;
+		}
+		if let Err(ferr) = self.ensure_current(ctx) {
+			self.on_error(ctx, &ferr);
+			return;
+		}
+		// Without workers every monitor goes to on_render; no job is asked for and lost.
+		if self.render_pool().is_none() {
+			return;
+		}
+		let srgb = self.gl.surface_format().is_srgb();
+		let mut pending = Vec::new();
+		for monitor_id in &ev.monitor_ids {
+			if self.captures.contains(monitor_id) || self.gl.render_target_options(monitor_id).samples > 1
+			{
+				continue;
+			}
+			let mut gl_ctx = GlEventContext {
+				core: ctx,
+				gl: &mut self.gl,
+				captures: &mut self.captures,
+				debug_overlay: &mut self.debug_overlay,
+			};
+			let Some(job) = self.app.render_job(&mut gl_ctx, monitor_id) else {
+				continue;
+			};
+			// Without a free buffer on_render takes the monitor and schedules the retry.
+			let Ok(frame) = ctx.acquire_target(monitor_id) else {
+				continue;
+			};
+			if frame.resized() {
+				self.gl.release_monitor_dmabuf_targets(monitor_id);
+			}
+			let target = ctx.render_target(&frame).and_then(|target| {
+				self
+					.gl
+					.prepare_shared_target(monitor_id, &target)
+					.map_err(|err| err.into_framework_error("prepare render target failed"))
+			});
+			let target = match target {
+				Ok(target) => target,
+				Err(ferr) => {
+					ctx.discard_frame(frame);
+					self.on_error(ctx, &ferr);
+					continue;
+				}
+			};
+			let clear = match ctx.clear_policy(monitor_id) {
+				Some(core::ClearPolicy::Color(color)) => Some(color),
+				_ => None,
+			};
+			let buffer_age = if clear.is_some() {
+				0
+			} else {
+				frame.buffer_age()
+			};
+			let pool = self
+				.render_pool
+				.as_mut()
+				.expect("render pool started above");
+			let queued = pool.dispatch(target, monitor_id, buffer_age, clear, srgb, job);
+			pending.push((frame, queued));
+		}
+		for (frame, queued) in pending {
+			let fence = queued
+				.join()
+				.and_then(|fence| self.gl.join_frame_fence(&fence));
+			match fence {
+				Ok(fence_fd) => {
+					if let Err(ferr) = ctx.submit_frame(frame, fence_fd) {
+						self.on_error(ctx, &ferr);
+					}
+				}
+				Err(err) => {
+					if matches!(err, GlError::RenderWorkerExited) {
+						// Restart the workers next frame; this one falls back to on_render.
+						self.render_pool = None;
+					}
+					ctx.discard_frame(frame);
+					let ferr = err.into_framework_error("parallel render failed");
+					self.on_error(ctx, &ferr);
+				}
+			}
+		}
+	}
+
+	fn on_present(&mut self, ctx: &mut core::Context<Self>, ev: core::PresentEvent) {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
-		let compose = self.xkb.process_key(ev.key, ev.is_pressed());
-		self.app.on_key(&mut ctx, ev.clone());
-		if let Some(text) = compose.text {
-			self.app.on_char(&mut ctx, core::CharEvent { text });
-		}
+		self.app.on_present(&mut ctx, ev);
+	}
+
+	fn on_monitor_added(&mut self, ctx: &mut core::Context<Self>, ev: core::MonitorAddedEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_monitor_added(&mut ctx, ev);
+	}
+
+	fn on_monitor_removed(&mut self, ctx: &mut core::Context<Self>, ev: core::MonitorRemovedEvent) {
+		self.gl.release_monitor_targets(&ev.monitor_id);
+		self.captures.remove(&ev.monitor_id);
+		self.debug_overlay.remove_monitor(&ev.monitor_id);
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_monitor_removed(&mut ctx, ev);
+	}
+
+	fn on_safe_area_changed(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::SafeAreaChangedEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_safe_area_changed(&mut ctx, ev);
+	}
+
+	fn on_monitor_power_changed(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::MonitorPowerChangedEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_monitor_power_changed(&mut ctx, ev);
+	}
+
+	fn on_buffer_available(&mut self, ctx: &mut core::Context<Self>, ev: core::BufferAvailableEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_buffer_available(&mut ctx, ev);
+	}
+
+	fn on_starved(&mut self, ctx: &mut core::Context<Self>, ev: core::StarvedEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_starved(&mut ctx, ev);
+	}
+
+	fn on_swapchain_recreated(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::SwapchainRecreatedEvent,
+	) {
+		self.gl.release_monitor_dmabuf_targets(&ev.monitor_id);
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_swapchain_recreated(&mut ctx, ev);
+	}
+
+	fn on_layout_changed(&mut self, ctx: &mut core::Context<Self>, ev: core::LayoutChangedEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_layout_changed(&mut ctx, ev);
+	}
+
+	fn on_accessibility_changed(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::AccessibilityChangedEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_accessibility_changed(&mut ctx, ev);
+	}
+
+	fn on_session_state(&mut self, ctx: &mut core::Context<Self>, ev: core::SessionEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_session_state(&mut ctx, ev);
+	}
+
+	fn on_focus_gained(&mut self, ctx: &mut core::Context<Self>, ev: core::FocusEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_focus_gained(&mut ctx, ev);
+	}
+
+	fn on_focus_lost(&mut self, ctx: &mut core::Context<Self>, ev: core::FocusEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_focus_lost(&mut ctx, ev);
+	}
+
+	fn on_session_preview(&mut self, ctx: &mut core::Context<Self>, ev: core::SessionPreviewEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_session_preview(&mut ctx, ev);
+	}
+
+	fn on_capture_frame(&mut self, ctx: &mut core::Context<Self>, ev: core::CaptureFrameEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_capture_frame(&mut ctx, ev);
+	}
+
+	fn on_capture_ended(&mut self, ctx: &mut core::Context<Self>, ev: core::CaptureEndedEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_capture_ended(&mut ctx, ev);
+	}
+
+	fn on_session_message(&mut self, ctx: &mut core::Context<Self>, ev: core::SessionMessageEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_session_message(&mut ctx, ev);
+	}
+
+	fn on_session_crashed(&mut self, ctx: &mut core::Context<Self>, ev: core::SessionCrashedEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_session_crashed(&mut ctx, ev);
+	}
+
+	fn on_session_create_result(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::SessionCreateResultEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_session_create_result(&mut ctx, ev);
+	}
+
+	fn on_session_switch_result(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::SessionSwitchResultEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_session_switch_result(&mut ctx, ev);
+	}
+
+	fn on_session_ready_sent(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::SessionReadySentEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_session_ready_sent(&mut ctx, ev);
+	}
+
+	fn on_server_unresponsive(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::ServerUnresponsiveEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_server_unresponsive(&mut ctx, ev);
+	}
+
+	fn on_server_recovered(&mut self, ctx: &mut core::Context<Self>, ev: core::ServerRecoveredEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_server_recovered(&mut ctx, ev);
+	}
+
+	fn on_config_changed(&mut self, ctx: &mut core::Context<Self>, ev: core::ConfigChangedEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_config_changed(&mut ctx, ev);
+	}
+
+	fn on_input(&mut self, ctx: &mut core::Context<Self>, ev: &core::InputEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_input(&mut ctx, ev);
+	}
+
+	fn on_global_shortcut(&mut self, ctx: &mut core::Context<Self>, ev: core::GlobalShortcutEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_global_shortcut(&mut ctx, ev);
+	}
+
+	fn on_key(&mut self, ctx: &mut core::Context<Self>, ev: core::KeyEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_key(&mut ctx, ev);
 	}
 
 	fn on_char(&mut self, ctx: &mut core::Context<Self>, ev: core::CharEvent) {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_char(&mut ctx, ev);
 	}
@@ -369,6 +1701,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_pointer_move(&mut ctx, ev);
 	}
@@ -377,6 +1711,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_mouse_move(&mut ctx, ev);
 	}
@@ -385,6 +1721,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_pointer_down(&mut ctx, ev);
 	}
@@ -393,6 +1731,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_pointer_up(&mut ctx, ev);
 	}
@@ -401,6 +1741,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_mouse_down(&mut ctx, ev);
 	}
@@ -409,6 +1751,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_mouse_up(&mut ctx, ev);
 	}
@@ -417,6 +1761,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_touch(&mut ctx, ev);
 	}
@@ -425,6 +1771,8 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_gesture(&mut ctx, ev);
 	}
@@ -433,14 +1781,130 @@ impl<A: GlApplication> core::Application for GlBridge<A> {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_fd_ready(&mut ctx, ev);
 	}
 
+	fn on_stats(&mut self, ctx: &mut core::Context<Self>, stats: core::LoopStatsSnapshot) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_stats(&mut ctx, stats);
+	}
+
+	fn on_events(&mut self, ctx: &mut core::Context<Self>, events: &[core::Event]) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_events(&mut ctx, events);
+	}
+
+	fn on_extension_event(&mut self, ctx: &mut core::Context<Self>, ev: core::ExtensionEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_extension_event(&mut ctx, ev);
+	}
+
+	fn on_user_event(&mut self, ctx: &mut core::Context<Self>, ev: core::UserEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_user_event(&mut ctx, ev);
+	}
+
+	fn on_input_device_added(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::InputDeviceAddedEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_input_device_added(&mut ctx, ev);
+	}
+
+	fn on_input_device_removed(
+		&mut self,
+		ctx: &mut core::Context<Self>,
+		ev: core::InputDeviceRemovedEvent,
+	) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_input_device_removed(&mut ctx, ev);
+	}
+
+	fn on_idle(&mut self, ctx: &mut core::Context<Self>, ev: core::IdleEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_idle(&mut ctx, ev);
+	}
+
+	fn on_resume_activity(&mut self, ctx: &mut core::Context<Self>, ev: core::ResumeActivityEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_resume_activity(&mut ctx, ev);
+	}
+
+	fn on_monitor_changed(&mut self, ctx: &mut core::Context<Self>, ev: core::MonitorChangedEvent) {
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_monitor_changed(&mut ctx, ev);
+	}
+
+	fn on_frame_requested(&mut self, ctx: &mut core::Context<Self>, ev: core::FrameRequestEvent) {
+		if let Err(ferr) = self.ensure_current(ctx) {
+			self.on_error(ctx, &ferr);
+			return;
+		}
+		let mut ctx = GlEventContext {
+			core: ctx,
+			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
+		};
+		self.app.on_frame_requested(&mut ctx, ev);
+	}
+
 	fn on_error(&mut self, ctx: &mut core::Context<Self>, error: &core::FrameworkError) {
 		let mut ctx = GlEventContext {
 			core: ctx,
 			gl: &mut self.gl,
+			captures: &mut self.captures,
+			debug_overlay: &mut self.debug_overlay,
 		};
 		self.app.on_error(&mut ctx, error);
 	}
@@ -1,23 +1,50 @@
 //! OpenGL renderer integration for `tab-app-framework`.
 //! Provides EGL/GBM context setup and DMA-BUF import helpers.
 
+mod attachments;
+mod debug_overlay;
 mod egl;
+mod external;
 mod framework;
+mod offscreen;
+mod parallel;
+mod program;
+mod quad;
+mod readback;
+mod shared;
 
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
-use std::ffi::{CString, c_void};
+use std::ffi::{CStr, CString, c_void};
 use std::fs::OpenOptions;
-use std::os::fd::{FromRawFd, OwnedFd};
+use std::os::fd::{AsRawFd, FromRawFd, OwnedFd};
 use std::path::{Path, PathBuf};
 use std::ptr;
+use std::rc::Rc;
+use std::sync::Arc;
 
 use gbm::AsRaw as _;
 use gbm::Device as GbmDevice;
 use glow::HasContext;
+use tab_app_framework_core::{FrameworkError, RenderTarget};
 use thiserror::Error;
+use tracing::warn;
 
+pub use external::{
+	DRM_FORMAT_MOD_INVALID, DmabufPlane, ExternalFormat, ExternalTexture, TEXTURE_EXTERNAL_OES,
+	YuvColor, YuvColorSpace, YuvRange, yuv_to_rgb_matrix,
+};
 pub use framework::{GlApplication, GlEventContext, GlInitContext, GlTabAppFramework};
-pub use tab_app_framework_core::{SessionCreatedPayload, SessionInfo, SessionRole};
+pub use offscreen::OffscreenTarget;
+pub use parallel::{RenderJob, RenderJobContext};
+pub use program::{Program, UniformValue};
+pub use quad::{NinePatch, QuadBatcher, QuadRect, QuadTexture, QuadTransform};
+pub use readback::{FrameCaptureEvent, ImageBuffer, PixelRect};
+pub use shared::{GlFence, GlSharedContext, TextureUpload};
+pub use tab_app_framework_core::{
+	GlContextOptions, GlContextPriority, GlSurfaceFormat, RenderTargetOptions, SessionCreatedPayload,
+	SessionInfo, SessionRole, SyncMode,
+};
 
 /// Requested OpenGL/OpenGL ES version.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -28,6 +55,21 @@ pub struct GlVersion {
 	pub minor: u8,
 }
 
+/// GPU and driver the context ended up on, for diagnostics and bug reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlDeviceInfo {
+	/// `GL_RENDERER`, usually the GPU model.
+	pub renderer: String,
+	/// `GL_VENDOR`.
+	pub vendor: String,
+	/// `GL_VERSION`, including the driver version.
+	pub version: String,
+	/// Render node backing the EGL display.
+	pub render_node: PathBuf,
+	/// Extensions advertised by the EGL display.
+	pub egl_extensions: Vec<String>,
+}
+
 /// Errors produced by GL/EGL initialization and rendering helpers.
 #[derive(Debug, Error)]
 pub enum GlError {
@@ -74,27 +116,164 @@ pub enum GlError {
 	DupNativeFenceFdFailed(i32),
 	#[error("eglCreateImageKHR failed (error={0:#X})")]
 	CreateImageFailed(i32),
+	#[error("eglWaitSync failed (error={0:#X})")]
+	WaitSyncFailed(i32),
+	#[error("eglClientWaitSync timed out")]
+	WaitSyncTimeout,
+	#[error("offscreen target creation failed: {0}")]
+	CreateOffscreenTargetFailed(String),
+	#[error("offscreen target was released")]
+	OffscreenTargetReleased,
+	#[error("expected {expected} dmabuf planes, got {got}")]
+	InvalidDmabufPlanes { expected: usize, got: usize },
+	#[error("external dmabuf import failed: {0}")]
+	ImportExternalFailed(String),
+	#[error("external texture was released")]
+	ExternalTextureReleased,
+	#[error("no render target is bound")]
+	NoRenderTarget,
+	#[error("invalid readback rect {0:?}")]
+	InvalidReadRect(PixelRect),
+	#[error("glReadPixels failed (error={0:#X})")]
+	ReadPixelsFailed(u32),
+	#[error("GL object creation failed: {0}")]
+	CreateObjectFailed(String),
+	#[error("expected {expected} bytes of RGBA8 pixels, got {got}")]
+	InvalidImageData { expected: usize, got: usize },
+	#[error("{stage} shader compilation failed:\n{log}")]
+	CompileShaderFailed { stage: &'static str, log: String },
+	#[error("program link failed:\n{0}")]
+	LinkProgramFailed(String),
+	#[error("render target attachment creation failed: {0}")]
+	CreateRenderTargetFailed(String),
+	#[error("GL context lost after a GPU reset ({0:?})")]
+	ContextLost(GlResetStatus),
+	#[error("parallel render worker exited")]
+	RenderWorkerExited,
+}
+
+/// Cause of a GPU reset that destroyed a [`GlContext`], as reported by the driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlResetStatus {
+	/// This context caused the reset.
+	Guilty,
+	/// Another context caused the reset.
+	Innocent,
+	/// The cause is unknown, also used when EGL reports `EGL_CONTEXT_LOST`.
+	Unknown,
+}
+
+impl GlError {
+	/// Wraps the error as [`FrameworkError::Gpu`], `context` naming the step that failed.
+	pub fn into_framework_error(self, context: &'static str) -> FrameworkError {
+		FrameworkError::Gpu {
+			context,
+			source: Box::new(self),
+		}
+	}
+
+	/// Returns the GL error behind a [`FrameworkError::Gpu`] from the GL or Skia layer.
+	pub fn from_framework_error(err: &FrameworkError) -> Option<&GlError> {
+		match err {
+			FrameworkError::Gpu { source, .. } => source.downcast_ref(),
+			_ => None,
+		}
+	}
 }
 
 type GlEglImageTargetTexture2DOes = unsafe extern "system" fn(u32, *const c_void);
+type GlGetGraphicsResetStatus = unsafe extern "system" fn() -> u32;
 
-/// OpenGL/EGL context and DMA-BUF render-target cache.
-pub struct GlContext {
+/// EGL display state shared by every context in one share group.
+///
+/// The display is terminated once the last context referencing it is dropped.
+struct EglDevice {
 	egl: egl::Egl,
 	display: egl::types::EGLDisplay,
-	context: egl::types::EGLContext,
 	_gbm_device: GbmDevice<std::fs::File>,
+	render_node: PathBuf,
 	egl_lib: libloading::Library,
 	gl_lib: libloading::Library,
+}
+
+// SAFETY: EGL display handles and loaded entrypoints are thread-safe per the EGL spec;
+// per-thread state (current context, bound API) lives in the EGL implementation.
+unsafe impl Send for EglDevice {}
+unsafe impl Sync for EglDevice {}
+
+impl Drop for EglDevice {
+	fn drop(&mut self) {
+		if !self.display.is_null() {
+			unsafe {
+				self.egl.Terminate(self.display);
+			}
+		}
+	}
+}
+
+/// OpenGL/EGL context and DMA-BUF render-target cache.
+pub struct GlContext {
+	device: Arc<EglDevice>,
+	config: egl::types::EGLConfig,
+	context: egl::types::EGLContext,
+	api: u32,
+	context_attribs: Vec<i32>,
 	glow: glow::Context,
 	version: GlVersion,
+	surface_format: GlSurfaceFormat,
+	requested_surface_format: GlSurfaceFormat,
+	options: GlContextOptions,
 	egl_image_target_texture_2d_oes: GlEglImageTargetTexture2DOes,
+	get_graphics_reset_status: Option<GlGetGraphicsResetStatus>,
+	lost: Cell<Option<GlResetStatus>>,
 	dmabuf_targets: HashMap<RenderTargetKey, DmabufTarget>,
+	current_target: Option<BoundTarget>,
+	default_target_options: RenderTargetOptions,
+	target_options: HashMap<String, RenderTargetOptions>,
+	monitor_attachments: HashMap<String, attachments::MonitorAttachments>,
+	offscreen_targets: HashMap<u64, offscreen::OffscreenEntry>,
+	next_offscreen_id: u64,
+	released_offscreen: Rc<RefCell<Vec<u64>>>,
+	external_textures: HashMap<u64, external::ExternalEntry>,
+	next_external_id: u64,
+	released_external: Rc<RefCell<Vec<u64>>>,
+	sync_mode: SyncMode,
 }
 
 impl GlContext {
 	/// Creates a surfaceless EGL context backed by a GBM render node.
 	pub fn new(version: GlVersion, render_node: Option<&Path>) -> Result<Self, GlError> {
+		Self::with_surface_format(version, render_node, GlSurfaceFormat::default())
+	}
+
+	/// Creates a surfaceless EGL context requesting a specific surface color format.
+	///
+	/// Falls back to [`GlSurfaceFormat::Rgba8888`] when the requested format is
+	/// unsupported; see [`GlContext::surface_format`] for the negotiated result.
+	pub fn with_surface_format(
+		version: GlVersion,
+		render_node: Option<&Path>,
+		surface_format: GlSurfaceFormat,
+	) -> Result<Self, GlError> {
+		Self::with_options(
+			version,
+			render_node,
+			surface_format,
+			GlContextOptions::default(),
+		)
+	}
+
+	/// Creates a surfaceless EGL context with a surface color format and context flags.
+	///
+	/// With [`GlContextOptions::debug`], driver messages are logged on
+	/// `tab_app_framework.gl.debug` at a level matching their severity.
+	pub fn with_options(
+		version: GlVersion,
+		render_node: Option<&Path>,
+		surface_format: GlSurfaceFormat,
+		options: GlContextOptions,
+	) -> Result<Self, GlError> {
+		let requested_surface_format = surface_format;
 		let egl_lib = unsafe { libloading::Library::new("libEGL.so.1") }
 			.map_err(|e| GlError::LoadEglLibrary(e.to_string()))?;
 		let gl_lib = unsafe { libloading::Library::new("libGL.so.1") }
@@ -104,7 +283,20 @@ impl GlContext {
 		let egl_boot =
 			egl::Egl::load_with(|name| load_symbol(&egl_lib, name).unwrap_or(ptr::null()));
 
-		let gbm_device = open_render_node_gbm_device(render_node)?;
+		let (gbm_device, render_node) = open_render_node_gbm_device(render_node)?;
+		// Monitor swapchains are allocated in the surface format's fourcc on this device.
+		let surface_format = if gbm_device.is_format_supported(
+			surface_format.swapchain_format(),
+			gbm::BufferObjectFlags::RENDERING,
+		) {
+			surface_format
+		} else {
+			warn!(
+				target: "tab_app_framework.gl",
+				"{surface_format:?} swapchains are unsupported, falling back to Rgba8888"
+			);
+			GlSurfaceFormat::Rgba8888
+		};
 		const EGL_PLATFORM_GBM_KHR: u32 = 0x31D7;
 		let display = if egl_boot.GetPlatformDisplay.is_loaded() {
 			unsafe {
@@ -154,16 +346,19 @@ impl GlContext {
 		const EGL_OPENGL_ES2_BIT: i32 = 0x0004;
 		const EGL_OPENGL_ES3_BIT_KHR: i32 = 0x0040;
 
+		let flag_attribs = context_flag_attribs(&egl, display, (major, minor), options);
 		let mut last_error = String::new();
-		let (config, context) = if unsafe { egl.BindAPI(egl::OPENGL_API as u32) } != 0 {
-			let gl_config = choose_config(&egl, display, egl::OPENGL_BIT as i32)?;
-			let gl_ctx_attribs = [
-				EGL_CONTEXT_MAJOR_VERSION,
-				version.major as i32,
-				EGL_CONTEXT_MINOR_VERSION,
-				version.minor as i32,
-				egl::NONE as i32,
-			];
+		let mut gl_ctx_attribs = vec![
+			EGL_CONTEXT_MAJOR_VERSION,
+			version.major as i32,
+			EGL_CONTEXT_MINOR_VERSION,
+			version.minor as i32,
+		];
+		gl_ctx_attribs.extend(&flag_attribs);
+		gl_ctx_attribs.push(egl::NONE as i32);
+		let (config, context, format) = if unsafe { egl.BindAPI(egl::OPENGL_API as u32) } != 0 {
+			let (gl_config, gl_format) =
+				choose_config(&egl, display, egl::OPENGL_BIT as i32, surface_format)?;
 			let context = unsafe {
 				egl.CreateContext(
 					display,
@@ -176,18 +371,18 @@ impl GlContext {
 				last_error = format!("OpenGL context failed eglError={:#X}", unsafe {
 					egl.GetError()
 				});
-				(ptr::null(), ptr::null())
+				(ptr::null(), ptr::null(), surface_format)
 			} else {
-				(gl_config, context)
+				(gl_config, context, gl_format)
 			}
 		} else {
 			last_error = format!("OpenGL BindAPI failed eglError={:#X}", unsafe {
 				egl.GetError()
 			});
-			(ptr::null(), ptr::null())
+			(ptr::null(), ptr::null(), surface_format)
 		};
 
-		let (_config, context) = if context.is_null() {
+		let (config, context, api, context_attribs, mut surface_format) = if context.is_null() {
 			if unsafe { egl.BindAPI(egl::OPENGL_ES_API as u32) } == 0 {
 				return Err(GlError::ContextCreationFailed(format!(
 					"{last_error}; OpenGL ES BindAPI failed eglError={:#X}",
@@ -200,15 +395,16 @@ impl GlContext {
 			} else {
 				EGL_OPENGL_ES2_BIT
 			};
-			let es_config = choose_config(&egl, display, es_bits)?;
+			let (es_config, es_format) = choose_config(&egl, display, es_bits, surface_format)?;
 			let es_major = version.major.max(2);
-			let es_ctx_attribs = [
+			let mut es_ctx_attribs = vec![
 				EGL_CONTEXT_MAJOR_VERSION,
 				es_major as i32,
 				EGL_CONTEXT_MINOR_VERSION,
 				version.minor as i32,
-				egl::NONE as i32,
 			];
+			es_ctx_attribs.extend(&flag_attribs);
+			es_ctx_attribs.push(egl::NONE as i32);
 			let es_context = unsafe {
 				egl.CreateContext(
 					display,
@@ -223,9 +419,15 @@ impl GlContext {
 					unsafe { egl.GetError() }
 				)));
 			}
-			(es_config, es_context)
+			(
+				es_config,
+				es_context,
+				egl::OPENGL_ES_API,
+				es_ctx_attribs,
+				es_format,
+			)
 		} else {
-			(config, context)
+			(config, context, egl::OPENGL_API, gl_ctx_attribs, format)
 		};
 
 		let make_current_ok =
@@ -244,24 +446,90 @@ impl GlContext {
 			.ok_or(GlError::MissingGlEglImageTarget)?;
 		let egl_image_target_texture_2d_oes: GlEglImageTargetTexture2DOes =
 			unsafe { std::mem::transmute(image_target_ptr) };
+		// Resets are only reported to contexts created with a reset notification strategy.
+		let get_graphics_reset_status = options
+			.robustness
+			.then(|| {
+				[
+					"glGetGraphicsResetStatus",
+					"glGetGraphicsResetStatusKHR",
+					"glGetGraphicsResetStatusEXT",
+					"glGetGraphicsResetStatusARB",
+				]
+				.into_iter()
+				.find_map(|name| load_proc_raw(&egl, &egl_lib, &gl_lib, name))
+			})
+			.flatten()
+			.map(|ptr| unsafe { std::mem::transmute::<_, GlGetGraphicsResetStatus>(ptr) });
 
-		let glow = unsafe {
+		let mut glow = unsafe {
 			glow::Context::from_loader_function(|name| {
 				load_proc_raw(&egl, &egl_lib, &gl_lib, name).unwrap_or(ptr::null()) as *const _
 			})
 		};
+		if options.debug {
+			install_debug_callback(&mut glow);
+		}
+
+		// GLES only honors GL_FRAMEBUFFER_SRGB with EXT_sRGB_write_control.
+		if surface_format.is_srgb()
+			&& api == egl::OPENGL_ES_API
+			&& !glow
+				.supported_extensions()
+				.contains("GL_EXT_sRGB_write_control")
+		{
+			warn!(
+				target: "tab_app_framework.gl",
+				"sRGB framebuffer writes unsupported, falling back to Rgba8888"
+			);
+			surface_format = GlSurfaceFormat::Rgba8888;
+		}
+		// Swapchain buffers only take sRGB-encoded writes when imported with an sRGB colorspace.
+		if surface_format.is_srgb()
+			&& !egl_extensions(&egl, display)
+				.iter()
+				.any(|ext| ext == "EGL_EXT_image_gl_colorspace")
+		{
+			warn!(
+				target: "tab_app_framework.gl",
+				"sRGB dmabuf images unsupported, falling back to Rgba8888"
+			);
+			surface_format = GlSurfaceFormat::Rgba8888;
+		}
 
 		Ok(Self {
-			egl,
-			display,
+			device: Arc::new(EglDevice {
+				egl,
+				display,
+				_gbm_device: gbm_device,
+				render_node,
+				egl_lib,
+				gl_lib,
+			}),
+			config,
 			context,
-			_gbm_device: gbm_device,
-			egl_lib,
-			gl_lib,
+			api,
+			context_attribs,
 			glow,
 			version,
+			surface_format,
+			requested_surface_format,
+			options,
 			egl_image_target_texture_2d_oes,
+			get_graphics_reset_status,
+			lost: Cell::new(None),
 			dmabuf_targets: HashMap::new(),
+			current_target: None,
+			default_target_options: RenderTargetOptions::default(),
+			target_options: HashMap::new(),
+			monitor_attachments: HashMap::new(),
+			offscreen_targets: HashMap::new(),
+			next_offscreen_id: 0,
+			released_offscreen: Rc::new(RefCell::new(Vec::new())),
+			external_textures: HashMap::new(),
+			next_external_id: 0,
+			released_external: Rc::new(RefCell::new(Vec::new())),
+			sync_mode: SyncMode::Auto,
 		})
 	}
 
@@ -270,25 +538,151 @@ impl GlContext {
 		self.version
 	}
 
+	/// Returns the surface color format negotiated at context creation.
+	pub fn surface_format(&self) -> GlSurfaceFormat {
+		self.surface_format
+	}
+
+	/// Returns the GPU, driver and render node this context runs on.
+	///
+	/// Queries the driver on every call; make the context current first.
+	pub fn device_info(&self) -> GlDeviceInfo {
+		let (renderer, vendor, version) = unsafe {
+			(
+				self.glow.get_parameter_string(glow::RENDERER),
+				self.glow.get_parameter_string(glow::VENDOR),
+				self.glow.get_parameter_string(glow::VERSION),
+			)
+		};
+		GlDeviceInfo {
+			renderer,
+			vendor,
+			version,
+			render_node: self.device.render_node.clone(),
+			egl_extensions: egl_extensions(&self.device.egl, self.device.display),
+		}
+	}
+
 	/// Makes this context current on the calling thread.
+	///
+	/// Fails with [`GlError::ContextLost`] once the context was lost to a GPU reset; see
+	/// [`GlContext::recreate`].
 	pub fn make_current(&self) -> Result<(), GlError> {
+		if let Some(status) = self.lost.get() {
+			return Err(GlError::ContextLost(status));
+		}
 		let ok = unsafe {
-			self
-				.egl
-				.MakeCurrent(self.display, egl::NO_SURFACE, egl::NO_SURFACE, self.context)
+			self.device.egl.MakeCurrent(
+				self.device.display,
+				egl::NO_SURFACE,
+				egl::NO_SURFACE,
+				self.context,
+			)
 		};
 		if ok == 0 {
-			return Err(GlError::MakeCurrentFailed(unsafe { self.egl.GetError() }));
+			let error = unsafe { self.device.egl.GetError() };
+			if error == egl::CONTEXT_LOST as i32 {
+				self.lost.set(Some(GlResetStatus::Unknown));
+				return Err(GlError::ContextLost(GlResetStatus::Unknown));
+			}
+			return Err(GlError::MakeCurrentFailed(error));
 		}
 		Ok(())
 	}
 
+	/// Returns the cause of the GPU reset that destroyed this context, `None` while it is
+	/// usable.
+	///
+	/// The driver only reports resets to contexts created with
+	/// [`GlContextOptions::robustness`]; other contexts notice a reset when
+	/// [`GlContext::make_current`] fails with `EGL_CONTEXT_LOST`. Make the context current
+	/// first.
+	pub fn reset_status(&self) -> Option<GlResetStatus> {
+		if let Some(status) = self.lost.get() {
+			return Some(status);
+		}
+		let query = self.get_graphics_reset_status?;
+		let status = match unsafe { query() } {
+			glow::GUILTY_CONTEXT_RESET => GlResetStatus::Guilty,
+			glow::INNOCENT_CONTEXT_RESET => GlResetStatus::Innocent,
+			glow::UNKNOWN_CONTEXT_RESET => GlResetStatus::Unknown,
+			_ => return None,
+		};
+		self.lost.set(Some(status));
+		Some(status)
+	}
+
+	/// Returns true once the context was lost to a GPU reset; see [`GlContext::reset_status`].
+	pub fn is_context_lost(&self) -> bool {
+		self.reset_status().is_some()
+	}
+
+	/// Replaces a lost context with a new display and context on the same render node.
+	///
+	/// The creation parameters are reused and the sync mode is kept. Every GL object of the
+	/// old context is gone: cached dmabuf targets are dropped, and [`OffscreenTarget`] and
+	/// [`ExternalTexture`] handles created before report released targets. Contexts from
+	/// [`GlContext::create_shared_context`] stay on the old display and must be recreated
+	/// too. On error the context stays lost and the call can be retried.
+	pub fn recreate(&mut self) -> Result<(), GlError> {
+		// The GL objects died with the context; only EGL-side handles need releasing.
+		let targets: Vec<_> = self.dmabuf_targets.drain().map(|(_, t)| t).collect();
+		for target in targets {
+			self.destroy_egl_image(target.egl_image);
+		}
+		self.forget_external_textures();
+		self.monitor_attachments.clear();
+		self.offscreen_targets.clear();
+		self.current_target = None;
+		if self.lost.get().is_none() {
+			self.lost.set(Some(GlResetStatus::Unknown));
+		}
+		if !self.context.is_null() {
+			unsafe {
+				let _ = self.device.egl.MakeCurrent(
+					self.device.display,
+					egl::NO_SURFACE,
+					egl::NO_SURFACE,
+					egl::NO_CONTEXT,
+				);
+				self
+					.device
+					.egl
+					.DestroyContext(self.device.display, self.context);
+			}
+			self.context = ptr::null();
+		}
+
+		let mut fresh = Self::with_options(
+			self.version,
+			Some(&self.device.render_node),
+			self.requested_surface_format,
+			self.options,
+		)?;
+		fresh.sync_mode = self.sync_mode;
+		fresh.default_target_options = self.default_target_options;
+		fresh.target_options = std::mem::take(&mut self.target_options);
+		// Handles of the lost context must not alias targets created from now on.
+		fresh.next_offscreen_id = self.next_offscreen_id;
+		fresh.next_external_id = self.next_external_id;
+		*self = fresh;
+		Ok(())
+	}
+
 	/// Resolves an OpenGL/EGL symbol by name.
 	pub fn load_proc(&self, name: &str) -> Result<*const c_void, GlError> {
 		if name.as_bytes().contains(&0) {
 			return Err(GlError::InvalidFunctionName(name.to_string()));
 		}
-		Ok(load_proc_raw(&self.egl, &self.egl_lib, &self.gl_lib, name).unwrap_or(ptr::null()))
+		Ok(
+			load_proc_raw(
+				&self.device.egl,
+				&self.device.egl_lib,
+				&self.device.gl_lib,
+				name,
+			)
+			.unwrap_or(ptr::null()),
+		)
 	}
 
 	/// Returns the underlying `glow` context.
@@ -301,9 +695,50 @@ impl GlContext {
 		unsafe { self.glow.get_parameter_i32(glow::FRAMEBUFFER_BINDING) }
 	}
 
+	/// Returns true when queued GL work can be exported as a native fence FD.
+	///
+	/// Needs `EGL_ANDROID_native_fence_sync`; without it
+	/// [`GlContext::create_acquire_fence_fd`] fails with
+	/// [`GlError::MissingEglDupNativeFenceFd`].
+	pub fn explicit_sync_supported(&self) -> bool {
+		self.device.egl.DupNativeFenceFDANDROID.is_loaded()
+	}
+
+	/// Returns how [`GlContext::create_frame_fence`] synchronizes frames.
+	pub fn sync_mode(&self) -> SyncMode {
+		self.sync_mode
+	}
+
+	/// Sets how [`GlContext::create_frame_fence`] synchronizes frames.
+	pub fn set_sync_mode(&mut self, mode: SyncMode) {
+		self.sync_mode = mode;
+	}
+
+	/// Fences the GL work queued for a frame about to be submitted.
+	///
+	/// Returns a native fence FD under explicit sync. Under implicit sync, including
+	/// [`SyncMode::Auto`] without [`GlContext::explicit_sync_supported`], waits for the
+	/// GPU with `glFinish` and returns `None`, leaving the rest to the dmabuf's implicit
+	/// fences. A multisampled render target is resolved into its swapchain buffer first.
+	pub fn create_frame_fence(&mut self) -> Result<Option<OwnedFd>, GlError> {
+		if self.current_target.is_some() {
+			self.resolve_current_target()?;
+		}
+		let implicit = match self.sync_mode {
+			SyncMode::Auto => !self.explicit_sync_supported(),
+			SyncMode::Explicit => false,
+			SyncMode::Implicit => true,
+		};
+		if implicit {
+			unsafe { self.glow.finish() };
+			return Ok(None);
+		}
+		self.create_acquire_fence_fd().map(Some)
+	}
+
 	/// Creates an EGL native fence FD representing queued GL work.
 	pub fn create_acquire_fence_fd(&self) -> Result<OwnedFd, GlError> {
-		if !self.egl.DupNativeFenceFDANDROID.is_loaded() {
+		if !self.explicit_sync_supported() {
 			return Err(GlError::MissingEglDupNativeFenceFd);
 		}
 		let attribs: [egl::types::EGLAttrib; 3] = [
@@ -312,56 +747,113 @@ impl GlContext {
 			egl::NONE as egl::types::EGLAttrib,
 		];
 		let sync = unsafe {
-			self.egl.CreateSync(
-				self.display,
+			self.device.egl.CreateSync(
+				self.device.display,
 				egl::SYNC_NATIVE_FENCE_ANDROID,
 				attribs.as_ptr(),
 			)
 		};
 		if sync.is_null() {
-			return Err(GlError::CreateSyncFailed(unsafe { self.egl.GetError() }));
+			return Err(GlError::CreateSyncFailed(unsafe {
+				self.device.egl.GetError()
+			}));
 		}
 
 		unsafe { self.glow.flush() };
-		let fd = unsafe { self.egl.DupNativeFenceFDANDROID(self.display, sync as egl::types::EGLSyncKHR) };
+		let fd = unsafe {
+			self
+				.device
+				.egl
+				.DupNativeFenceFDANDROID(self.device.display, sync as egl::types::EGLSyncKHR)
+		};
 		unsafe {
-			self.egl.DestroySync(self.display, sync);
+			self.device.egl.DestroySync(self.device.display, sync);
 		}
 		if fd < 0 {
 			return Err(GlError::DupNativeFenceFdFailed(unsafe {
-				self.egl.GetError()
+				self.device.egl.GetError()
 			}));
 		}
 
 		Ok(unsafe { OwnedFd::from_raw_fd(fd) })
 	}
 
-	/// Imports/binds the render target for a render event and sets viewport.
+	/// Imports/binds the render target for a monitor frame and sets viewport.
 	pub fn prepare_render_target(
 		&mut self,
-		ev: &tab_app_framework_core::RenderEvent,
+		monitor_id: &str,
+		target: &RenderTarget<'_>,
 	) -> Result<(), GlError> {
-		let key = RenderTargetKey::new(&ev.monitor_id, ev.buffer_index as u8);
+		self.collect_released_offscreen_targets();
+		self.prepare_attachments(monitor_id, target.width(), target.height())?;
+		let key = RenderTargetKey::new(monitor_id, target.buffer_index() as u8);
 		if !self.dmabuf_targets.contains_key(&key) {
-			let target = self.import_target(ev)?;
-			self.dmabuf_targets.insert(key.clone(), target);
+			let imported = self.import_target(target)?;
+			self.attach_depth_stencil(monitor_id, imported.framebuffer);
+			self.dmabuf_targets.insert(key.clone(), imported);
 		}
 
-		let target = self
+		let cached = self
 			.dmabuf_targets
 			.get(&key)
 			.expect("dmabuf target cache unexpectedly missing");
+		let msaa = self.msaa_framebuffer(monitor_id);
+		let framebuffer = msaa.unwrap_or(cached.framebuffer);
 		unsafe {
 			self
 				.glow
-				.bind_framebuffer(glow::FRAMEBUFFER, Some(target.framebuffer));
-			self.glow.viewport(0, 0, ev.width, ev.height);
+				.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+			if self.surface_format.is_srgb() {
+				self.glow.enable(glow::FRAMEBUFFER_SRGB);
+			}
+			self.glow.viewport(0, 0, target.width(), target.height());
 		}
+		self.current_target = Some(BoundTarget {
+			framebuffer,
+			resolve_into: msaa.map(|_| cached.framebuffer),
+			depth_stencil: self.has_depth_stencil(monitor_id),
+			width: target.width(),
+			height: target.height(),
+		});
 		Ok(())
 	}
 
-	/// Releases cached render targets for a monitor.
+	/// Clears the whole bound render target to an RGBA color.
+	///
+	/// Disables the scissor test and leaves the clear color set to `color`. Targets with a
+	/// depth/stencil attachment also get depth 1.0 and stencil 0, with depth writes enabled.
+	pub fn clear_current_target(&self, color: [f32; 4]) -> Result<(), GlError> {
+		let bound = self.current_target.ok_or(GlError::NoRenderTarget)?;
+		let [r, g, b, a] = color;
+		unsafe {
+			self.glow.disable(glow::SCISSOR_TEST);
+			self.glow.color_mask(true, true, true, true);
+			self.glow.clear_color(r, g, b, a);
+			if bound.depth_stencil {
+				self.glow.depth_mask(true);
+				self.glow.stencil_mask(!0);
+				self.glow.clear_depth_f32(1.0);
+				self.glow.clear_stencil(0);
+				self
+					.glow
+					.clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT | glow::STENCIL_BUFFER_BIT);
+			} else {
+				self.glow.clear(glow::COLOR_BUFFER_BIT);
+			}
+		}
+		Ok(())
+	}
+
+	/// Releases cached render targets and monitor-scoped offscreen targets for a monitor.
 	pub fn release_monitor_targets(&mut self, monitor_id: &str) {
+		self.release_monitor_offscreen_targets(monitor_id);
+		self.release_monitor_dmabuf_targets(monitor_id);
+	}
+
+	/// Drops imported swapchain buffers and their attachments for a monitor, keeping its
+	/// offscreen targets.
+	pub fn release_monitor_dmabuf_targets(&mut self, monitor_id: &str) {
+		self.release_monitor_attachments(monitor_id);
 		let keys: Vec<_> = self
 			.dmabuf_targets
 			.keys()
@@ -375,33 +867,41 @@ impl GlContext {
 					self.glow.delete_texture(target.texture);
 				}
 				self.destroy_egl_image(target.egl_image);
+				if self.current_target.is_some_and(|bound| {
+					bound.framebuffer == target.framebuffer || bound.resolve_into == Some(target.framebuffer)
+				}) {
+					self.current_target = None;
+				}
 			}
 		}
 	}
 
-	fn import_target(
-		&self,
-		ev: &tab_app_framework_core::RenderEvent,
-	) -> Result<DmabufTarget, GlError> {
-		let attrs = [
+	fn import_target(&self, target: &RenderTarget<'_>) -> Result<DmabufTarget, GlError> {
+		let mut attrs = vec![
 			egl::LINUX_DRM_FOURCC_EXT as i32,
-			ev.fourcc,
+			target.fourcc(),
 			egl::DMA_BUF_PLANE0_FD_EXT as i32,
-			ev.dmabuf_fd,
+			target.dmabuf_fd().as_raw_fd(),
 			egl::DMA_BUF_PLANE0_OFFSET_EXT as i32,
-			ev.offset,
+			target.offset(),
 			egl::DMA_BUF_PLANE0_PITCH_EXT as i32,
-			ev.stride,
+			target.stride(),
 			egl::WIDTH as i32,
-			ev.width,
+			target.width(),
 			egl::HEIGHT as i32,
-			ev.height,
-			egl::NONE as i32,
+			target.height(),
 		];
+		// GL_FRAMEBUFFER_SRGB only encodes writes to attachments with an sRGB colorspace.
+		if self.surface_format.is_srgb() {
+			attrs.extend([egl::GL_COLORSPACE as i32, egl::GL_COLORSPACE_SRGB as i32]);
+		}
+		attrs.push(egl::NONE as i32);
 
 		let image = self.create_egl_image(&attrs)?;
 		if image == egl::NO_IMAGE_KHR {
-			return Err(GlError::CreateImageFailed(unsafe { self.egl.GetError() }));
+			return Err(GlError::CreateImageFailed(unsafe {
+				self.device.egl.GetError()
+			}));
 		}
 
 		let texture = unsafe {
@@ -463,10 +963,10 @@ impl GlContext {
 	}
 
 	fn create_egl_image(&self, attrs: &[i32]) -> Result<egl::types::EGLImageKHR, GlError> {
-		if self.egl.CreateImageKHR.is_loaded() {
+		if self.device.egl.CreateImageKHR.is_loaded() {
 			let image = unsafe {
-				self.egl.CreateImageKHR(
-					self.display,
+				self.device.egl.CreateImageKHR(
+					self.device.display,
 					egl::NO_CONTEXT,
 					egl::LINUX_DMA_BUF_EXT,
 					ptr::null_mut(),
@@ -476,12 +976,12 @@ impl GlContext {
 			return Ok(image);
 		}
 
-		if self.egl.CreateImage.is_loaded() {
+		if self.device.egl.CreateImage.is_loaded() {
 			let attrs64: Vec<egl::types::EGLAttrib> =
 				attrs.iter().map(|v| *v as egl::types::EGLAttrib).collect();
 			let image = unsafe {
-				self.egl.CreateImage(
-					self.display,
+				self.device.egl.CreateImage(
+					self.device.display,
 					egl::NO_CONTEXT,
 					egl::LINUX_DMA_BUF_EXT,
 					ptr::null_mut(),
@@ -496,12 +996,13 @@ impl GlContext {
 
 	fn destroy_egl_image(&self, image: egl::types::EGLImageKHR) {
 		unsafe {
-			if self.egl.DestroyImageKHR.is_loaded() {
-				self.egl.DestroyImageKHR(self.display, image);
-			} else if self.egl.DestroyImage.is_loaded() {
+			if self.device.egl.DestroyImageKHR.is_loaded() {
+				self.device.egl.DestroyImageKHR(self.device.display, image);
+			} else if self.device.egl.DestroyImage.is_loaded() {
 				self
+					.device
 					.egl
-					.DestroyImage(self.display, image as egl::types::EGLImage);
+					.DestroyImage(self.device.display, image as egl::types::EGLImage);
 			}
 		}
 	}
@@ -517,24 +1018,135 @@ impl Drop for GlContext {
 			}
 			self.destroy_egl_image(target.egl_image);
 		}
-
-		unsafe {
-			let _ = self.egl.MakeCurrent(
-				self.display,
-				egl::NO_SURFACE,
-				egl::NO_SURFACE,
-				egl::NO_CONTEXT,
-			);
-			if !self.context.is_null() {
-				self.egl.DestroyContext(self.display, self.context);
+		let attachments: Vec<_> = self.monitor_attachments.drain().map(|(_, a)| a).collect();
+		for entry in attachments {
+			self.delete_attachments(entry);
+		}
+		for (_, entry) in self.offscreen_targets.drain() {
+			unsafe {
+				self.glow.delete_framebuffer(entry.framebuffer);
+				self.glow.delete_texture(entry.texture);
 			}
-			if !self.display.is_null() {
-				self.egl.Terminate(self.display);
+		}
+		let external: Vec<_> = self.external_textures.drain().map(|(_, e)| e).collect();
+		for entry in external {
+			self.delete_external_entry(entry);
+		}
+
+		// A context torn down by `recreate` must not unbind its replacement.
+		if !self.context.is_null() {
+			unsafe {
+				let _ = self.device.egl.MakeCurrent(
+					self.device.display,
+					egl::NO_SURFACE,
+					egl::NO_SURFACE,
+					egl::NO_CONTEXT,
+				);
+				self
+					.device
+					.egl
+					.DestroyContext(self.device.display, self.context);
 			}
 		}
 	}
 }
 
+/// Builds the `EGL_CONTEXT_*` attributes for `options`, dropping what the display lacks.
+fn context_flag_attribs(
+	egl: &egl::Egl,
+	display: egl::types::EGLDisplay,
+	egl_version: (i32, i32),
+	options: GlContextOptions,
+) -> Vec<i32> {
+	const EGL_CONTEXT_PRIORITY_LEVEL_IMG: i32 = 0x3100;
+	const EGL_CONTEXT_PRIORITY_HIGH_IMG: i32 = 0x3101;
+	const EGL_CONTEXT_PRIORITY_LOW_IMG: i32 = 0x3103;
+
+	let extensions = egl_extensions(egl, display);
+	let has_extension = |name: &str| extensions.iter().any(|ext| ext == name);
+	// Debug and robustness attributes are core in EGL 1.5.
+	let flags_supported = egl_version >= (1, 5);
+
+	let mut attribs = Vec::new();
+	if options.debug {
+		if flags_supported {
+			attribs.extend([egl::CONTEXT_OPENGL_DEBUG as i32, egl::TRUE as i32]);
+		} else {
+			warn!(target: "tab_app_framework.gl", "debug contexts need EGL 1.5, ignoring");
+		}
+	}
+	if options.robustness {
+		if flags_supported {
+			attribs.extend([
+				egl::CONTEXT_OPENGL_ROBUST_ACCESS as i32,
+				egl::TRUE as i32,
+				egl::CONTEXT_OPENGL_RESET_NOTIFICATION_STRATEGY as i32,
+				egl::LOSE_CONTEXT_ON_RESET as i32,
+			]);
+		} else {
+			warn!(target: "tab_app_framework.gl", "robust contexts need EGL 1.5, ignoring");
+		}
+	}
+	let priority = match options.priority {
+		GlContextPriority::Low => Some(EGL_CONTEXT_PRIORITY_LOW_IMG),
+		GlContextPriority::Medium => None,
+		GlContextPriority::High => Some(EGL_CONTEXT_PRIORITY_HIGH_IMG),
+	};
+	if let Some(priority) = priority {
+		if has_extension("EGL_IMG_context_priority") {
+			attribs.extend([EGL_CONTEXT_PRIORITY_LEVEL_IMG, priority]);
+		} else {
+			warn!(
+				target: "tab_app_framework.gl",
+				"EGL_IMG_context_priority unsupported, ignoring context priority"
+			);
+		}
+	}
+	attribs
+}
+
+fn egl_extensions(egl: &egl::Egl, display: egl::types::EGLDisplay) -> Vec<String> {
+	let extensions = unsafe { egl.QueryString(display, egl::EXTENSIONS as i32) };
+	if extensions.is_null() {
+		return Vec::new();
+	}
+	unsafe { CStr::from_ptr(extensions) }
+		.to_string_lossy()
+		.split_ascii_whitespace()
+		.map(str::to_string)
+		.collect()
+}
+
+/// Forwards `KHR_debug` messages of the current context into `tracing`.
+fn install_debug_callback(glow: &mut glow::Context) {
+	if !glow.supported_extensions().contains("GL_KHR_debug") {
+		warn!(target: "tab_app_framework.gl", "GL_KHR_debug unsupported, no debug messages");
+		return;
+	}
+	unsafe {
+		glow.enable(glow::DEBUG_OUTPUT);
+		glow.enable(glow::DEBUG_OUTPUT_SYNCHRONOUS);
+		glow.debug_message_callback(|source, kind, id, severity, message| match severity {
+			glow::DEBUG_SEVERITY_HIGH => tracing::error!(
+				target: "tab_app_framework.gl.debug",
+				source, kind, id, "{message}"
+			),
+			glow::DEBUG_SEVERITY_MEDIUM => tracing::warn!(
+				target: "tab_app_framework.gl.debug",
+				source, kind, id, "{message}"
+			),
+			glow::DEBUG_SEVERITY_LOW => tracing::info!(
+				target: "tab_app_framework.gl.debug",
+				source, kind, id, "{message}"
+			),
+			_ => tracing::debug!(
+				target: "tab_app_framework.gl.debug",
+				source, kind, id, "{message}"
+			),
+		});
+	}
+}
+
 const DEFAULT_RENDER_NODES: &[&str] = &[
 	"/dev/dri/renderD128",
 	"/dev/dri/renderD129",
@@ -548,12 +1160,12 @@ const DEFAULT_RENDER_NODES: &[&str] = &[
 
 fn open_render_node_gbm_device(
 	configured: Option<&Path>,
-) -> Result<GbmDevice<std::fs::File>, GlError> {
+) -> Result<(GbmDevice<std::fs::File>, PathBuf), GlError> {
 	let mut last_error = None;
 	for candidate in render_node_candidates(configured) {
 		match OpenOptions::new().read(true).write(true).open(&candidate) {
 			Ok(file) => match GbmDevice::new(file) {
-				Ok(device) => return Ok(device),
+				Ok(device) => return Ok((device, candidate)),
 				Err(err) => {
 					last_error = Some(GlError::GbmInit(err.to_string()));
 				}
@@ -606,20 +1218,55 @@ fn choose_config(
 	egl: &egl::Egl,
 	display: egl::types::EGLDisplay,
 	renderable_type: i32,
+	format: GlSurfaceFormat,
+) -> Result<(egl::types::EGLConfig, GlSurfaceFormat), GlError> {
+	match choose_config_for_format(egl, display, renderable_type, format) {
+		Err(GlError::MissingConfig) if format != GlSurfaceFormat::Rgba8888 => {
+			warn!(
+				target: "tab_app_framework.gl",
+				"no EGL config for {format:?}, falling back to Rgba8888"
+			);
+			let config =
+				choose_config_for_format(egl, display, renderable_type, GlSurfaceFormat::Rgba8888)?;
+			Ok((config, GlSurfaceFormat::Rgba8888))
+		}
+		result => result.map(|config| (config, format)),
+	}
+}
+
+fn choose_config_for_format(
+	egl: &egl::Egl,
+	display: egl::types::EGLDisplay,
+	renderable_type: i32,
+	format: GlSurfaceFormat,
 ) -> Result<egl::types::EGLConfig, GlError> {
-	let config_attribs = [
+	const EGL_COLOR_COMPONENT_TYPE_EXT: i32 = 0x3339;
+	const EGL_COLOR_COMPONENT_TYPE_FLOAT_EXT: i32 = 0x333B;
+
+	let (color_bits, alpha_bits) = match format {
+		GlSurfaceFormat::Rgba8888 | GlSurfaceFormat::Srgb8888 => (8, 8),
+		GlSurfaceFormat::Rgba1010102 => (10, 2),
+		GlSurfaceFormat::RgbaF16 => (16, 16),
+	};
+	let mut config_attribs = vec![
 		egl::RENDERABLE_TYPE as i32,
 		renderable_type,
 		egl::RED_SIZE as i32,
-		8,
+		color_bits,
 		egl::GREEN_SIZE as i32,
-		8,
+		color_bits,
 		egl::BLUE_SIZE as i32,
-		8,
+		color_bits,
 		egl::ALPHA_SIZE as i32,
-		8,
-		egl::NONE as i32,
+		alpha_bits,
 	];
+	if format.is_float() {
+		config_attribs.extend([
+			EGL_COLOR_COMPONENT_TYPE_EXT,
+			EGL_COLOR_COMPONENT_TYPE_FLOAT_EXT,
+		]);
+	}
+	config_attribs.push(egl::NONE as i32);
 	let mut config: egl::types::EGLConfig = ptr::null();
 	let mut num_config = 0;
 	let choose_ok = unsafe {
@@ -661,6 +1308,16 @@ impl RenderTargetKey {
 	}
 }
 
+#[derive(Debug, Clone, Copy)]
+struct BoundTarget {
+	framebuffer: glow::NativeFramebuffer,
+	/// Swapchain buffer a multisampled `framebuffer` is resolved into.
+	resolve_into: Option<glow::NativeFramebuffer>,
+	depth_stencil: bool,
+	width: i32,
+	height: i32,
+}
+
 struct DmabufTarget {
 	egl_image: egl::types::EGLImageKHR,
 	texture: glow::NativeTexture,
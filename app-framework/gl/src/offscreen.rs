@@ -0,0 +1,298 @@
+//! Offscreen framebuffers for multipass rendering (blur, shadows, caching).
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use glow::HasContext;
+
+use crate::{GlContext, GlError, GlSurfaceFormat};
+
+/// Handle to an offscreen color target owned by a [`GlContext`].
+///
+/// GL objects are reclaimed by the context after the handle is dropped, on the
+/// next render-target preparation or offscreen allocation.
+#[derive(Debug)]
+pub struct OffscreenTarget {
+	id: u64,
+	width: i32,
+	height: i32,
+	format: GlSurfaceFormat,
+	monitor_id: Option<String>,
+	released: Rc<RefCell<Vec<u64>>>,
+}
+
+impl OffscreenTarget {
+	/// Target width in pixels.
+	pub fn width(&self) -> i32 {
+		self.width
+	}
+
+	/// Target height in pixels.
+	pub fn height(&self) -> i32 {
+		self.height
+	}
+
+	/// Color format of the backing texture.
+	pub fn format(&self) -> GlSurfaceFormat {
+		self.format
+	}
+
+	/// Monitor this target is scoped to, if any.
+	pub fn monitor_id(&self) -> Option<&str> {
+		self.monitor_id.as_deref()
+	}
+}
+
+impl Drop for OffscreenTarget {
+	fn drop(&mut self) {
+		self.released.borrow_mut().push(self.id);
+	}
+}
+
+pub(crate) struct OffscreenEntry {
+	pub(crate) texture: glow::NativeTexture,
+	pub(crate) framebuffer: glow::NativeFramebuffer,
+	monitor_id: Option<String>,
+}
+
+impl GlContext {
+	/// Allocates an offscreen color target.
+	pub fn create_offscreen_target(
+		&mut self,
+		width: i32,
+		height: i32,
+		format: GlSurfaceFormat,
+	) -> Result<OffscreenTarget, GlError> {
+		self.allocate_offscreen_target(None, width, height, format)
+	}
+
+	/// Allocates an offscreen color target released together with a monitor's render targets.
+	pub fn create_monitor_offscreen_target(
+		&mut self,
+		monitor_id: &str,
+		width: i32,
+		height: i32,
+		format: GlSurfaceFormat,
+	) -> Result<OffscreenTarget, GlError> {
+		self.allocate_offscreen_target(Some(monitor_id.to_string()), width, height, format)
+	}
+
+	/// Returns the color texture backing an offscreen target.
+	pub fn offscreen_texture(
+		&self,
+		target: &OffscreenTarget,
+	) -> Result<glow::NativeTexture, GlError> {
+		self
+			.offscreen_targets
+			.get(&target.id)
+			.map(|entry| entry.texture)
+			.ok_or(GlError::OffscreenTargetReleased)
+	}
+
+	/// Binds an offscreen target as the draw framebuffer and sets viewport.
+	pub fn bind_offscreen_target(&mut self, target: &OffscreenTarget) -> Result<(), GlError> {
+		let entry = self
+			.offscreen_targets
+			.get(&target.id)
+			.ok_or(GlError::OffscreenTargetReleased)?;
+		unsafe {
+			self
+				.glow
+				.bind_framebuffer(glow::FRAMEBUFFER, Some(entry.framebuffer));
+			self.glow.viewport(0, 0, target.width, target.height);
+		}
+		Ok(())
+	}
+
+	/// Rebinds the monitor render target prepared for the current frame.
+	pub fn bind_current_target(&mut self) -> Result<(), GlError> {
+		let bound = self.current_target.ok_or(GlError::NoRenderTarget)?;
+		unsafe {
+			self
+				.glow
+				.bind_framebuffer(glow::FRAMEBUFFER, Some(bound.framebuffer));
+			self.glow.viewport(0, 0, bound.width, bound.height);
+		}
+		Ok(())
+	}
+
+	/// Blits an offscreen target over the whole current monitor render target.
+	///
+	/// Leaves the monitor render target bound afterwards.
+	pub fn blit_to_current_target(&mut self, target: &OffscreenTarget) -> Result<(), GlError> {
+		let bound = self.current_target.ok_or(GlError::NoRenderTarget)?;
+		let entry = self
+			.offscreen_targets
+			.get(&target.id)
+			.ok_or(GlError::OffscreenTargetReleased)?;
+		let filter = if (target.width, target.height) == (bound.width, bound.height) {
+			glow::NEAREST
+		} else {
+			glow::LINEAR
+		};
+		unsafe {
+			self
+				.glow
+				.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(entry.framebuffer));
+			self
+				.glow
+				.bind_framebuffer(glow::DRAW_FRAMEBUFFER, Some(bound.framebuffer));
+			self.glow.blit_framebuffer(
+				0,
+				0,
+				target.width,
+				target.height,
+				0,
+				0,
+				bound.width,
+				bound.height,
+				glow::COLOR_BUFFER_BIT,
+				filter,
+			);
+		}
+		self.bind_current_target()
+	}
+
+	pub(crate) fn collect_released_offscreen_targets(&mut self) {
+		let released: Vec<u64> = self.released_offscreen.borrow_mut().drain(..).collect();
+		for id in released {
+			if let Some(entry) = self.offscreen_targets.remove(&id) {
+				self.delete_offscreen_entry(entry);
+			}
+		}
+	}
+
+	pub(crate) fn release_monitor_offscreen_targets(&mut self, monitor_id: &str) {
+		let ids: Vec<u64> = self
+			.offscreen_targets
+			.iter()
+			.filter(|(_, entry)| entry.monitor_id.as_deref() == Some(monitor_id))
+			.map(|(id, _)| *id)
+			.collect();
+		for id in ids {
+			if let Some(entry) = self.offscreen_targets.remove(&id) {
+				self.delete_offscreen_entry(entry);
+			}
+		}
+	}
+
+	fn allocate_offscreen_target(
+		&mut self,
+		monitor_id: Option<String>,
+		width: i32,
+		height: i32,
+		format: GlSurfaceFormat,
+	) -> Result<OffscreenTarget, GlError> {
+		self.collect_released_offscreen_targets();
+		let (internal_format, pixel_format, pixel_type) = texture_format(format);
+		let texture =
+			unsafe { self.glow.create_texture() }.map_err(GlError::CreateOffscreenTargetFailed)?;
+		let framebuffer = match unsafe { self.glow.create_framebuffer() } {
+			Ok(framebuffer) => framebuffer,
+			Err(err) => {
+				unsafe { self.glow.delete_texture(texture) };
+				return Err(GlError::CreateOffscreenTargetFailed(err));
+			}
+		};
+
+		let status = unsafe {
+			self.glow.bind_texture(glow::TEXTURE_2D, Some(texture));
+			self.glow.tex_image_2d(
+				glow::TEXTURE_2D,
+				0,
+				internal_format as i32,
+				width,
+				height,
+				0,
+				pixel_format,
+				pixel_type,
+				None,
+			);
+			self.glow.tex_parameter_i32(
+				glow::TEXTURE_2D,
+				glow::TEXTURE_MIN_FILTER,
+				glow::LINEAR as i32,
+			);
+			self.glow.tex_parameter_i32(
+				glow::TEXTURE_2D,
+				glow::TEXTURE_MAG_FILTER,
+				glow::LINEAR as i32,
+			);
+			self.glow.tex_parameter_i32(
+				glow::TEXTURE_2D,
+				glow::TEXTURE_WRAP_S,
+				glow::CLAMP_TO_EDGE as i32,
+			);
+			self.glow.tex_parameter_i32(
+				glow::TEXTURE_2D,
+				glow::TEXTURE_WRAP_T,
+				glow::CLAMP_TO_EDGE as i32,
+			);
+			self
+				.glow
+				.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+			self.glow.framebuffer_texture_2d(
+				glow::FRAMEBUFFER,
+				glow::COLOR_ATTACHMENT0,
+				glow::TEXTURE_2D,
+				Some(texture),
+				0,
+			);
+			let status = self.glow.check_framebuffer_status(glow::FRAMEBUFFER);
+			self.glow.bind_texture(glow::TEXTURE_2D, None);
+			self.glow.bind_framebuffer(
+				glow::FRAMEBUFFER,
+				self.current_target.map(|bound| bound.framebuffer),
+			);
+			status
+		};
+		if status != glow::FRAMEBUFFER_COMPLETE {
+			unsafe {
+				self.glow.delete_framebuffer(framebuffer);
+				self.glow.delete_texture(texture);
+			}
+			return Err(GlError::CreateOffscreenTargetFailed(format!(
+				"framebuffer incomplete (status={status:#X})"
+			)));
+		}
+
+		let id = self.next_offscreen_id;
+		self.next_offscreen_id += 1;
+		self.offscreen_targets.insert(
+			id,
+			OffscreenEntry {
+				texture,
+				framebuffer,
+				monitor_id: monitor_id.clone(),
+			},
+		);
+		Ok(OffscreenTarget {
+			id,
+			width,
+			height,
+			format,
+			monitor_id,
+			released: Rc::clone(&self.released_offscreen),
+		})
+	}
+
+	fn delete_offscreen_entry(&self, entry: OffscreenEntry) {
+		unsafe {
+			self.glow.delete_framebuffer(entry.framebuffer);
+			self.glow.delete_texture(entry.texture);
+		}
+	}
+}
+
+pub(crate) fn texture_format(format: GlSurfaceFormat) -> (u32, u32, u32) {
+	match format {
+		GlSurfaceFormat::Rgba8888 => (glow::RGBA8, glow::RGBA, glow::UNSIGNED_BYTE),
+		GlSurfaceFormat::Srgb8888 => (glow::SRGB8_ALPHA8, glow::RGBA, glow::UNSIGNED_BYTE),
+		GlSurfaceFormat::Rgba1010102 => (
+			glow::RGB10_A2,
+			glow::RGBA,
+			glow::UNSIGNED_INT_2_10_10_10_REV,
+		),
+		GlSurfaceFormat::RgbaF16 => (glow::RGBA16F, glow::RGBA, glow::HALF_FLOAT),
+	}
+}
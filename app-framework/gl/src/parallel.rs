@@ -0,0 +1,262 @@
+//! Worker threads rendering monitor frames concurrently on shared contexts.
+
+use std::os::fd::OwnedFd;
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+
+use glow::HasContext;
+use tab_app_framework_core::RenderTarget;
+use tracing::warn;
+
+use crate::{GlContext, GlError, GlFence, GlSharedContext, RenderTargetKey};
+
+/// Upper bound on render workers, whatever the number of monitors or cores.
+const MAX_RENDER_WORKERS: usize = 4;
+
+/// Work for one monitor's frame, run on a render worker by the parallel render path.
+///
+/// See [`crate::GlApplication::render_job`].
+pub type RenderJob = Box<dyn FnOnce(&RenderJobContext<'_>) + Send>;
+
+/// Monitor frame bound as the framebuffer of a render worker's shared context.
+pub struct RenderJobContext<'a> {
+	gl: &'a glow::Context,
+	monitor_id: &'a str,
+	width: i32,
+	height: i32,
+	buffer_age: u32,
+}
+
+impl RenderJobContext<'_> {
+	/// Returns the worker's `glow` context, current on the calling thread.
+	pub fn glow(&self) -> &glow::Context {
+		self.gl
+	}
+
+	/// Target monitor id.
+	pub fn monitor_id(&self) -> &str {
+		self.monitor_id
+	}
+
+	/// Framebuffer width in pixels.
+	pub fn width(&self) -> i32 {
+		self.width
+	}
+
+	/// Framebuffer height in pixels.
+	pub fn height(&self) -> i32 {
+		self.height
+	}
+
+	/// Same as [`tab_app_framework_core::RenderEvent::buffer_age`].
+	pub fn buffer_age(&self) -> u32 {
+		self.buffer_age
+	}
+}
+
+/// Swapchain buffer of a monitor as seen from the share group.
+pub(crate) struct SharedTarget {
+	texture: glow::NativeTexture,
+	depth_stencil: Option<glow::NativeRenderbuffer>,
+	width: i32,
+	height: i32,
+}
+
+struct Task {
+	target: SharedTarget,
+	monitor_id: String,
+	buffer_age: u32,
+	clear: Option<[f32; 4]>,
+	srgb: bool,
+	job: RenderJob,
+	done: mpsc::Sender<Result<GlFence, GlError>>,
+}
+
+struct Worker {
+	tasks: Option<mpsc::Sender<Task>>,
+	thread: Option<JoinHandle<()>>,
+}
+
+/// Render workers, each owning a context shared with the bridge's [`GlContext`].
+pub(crate) struct RenderPool {
+	workers: Vec<Worker>,
+	next: usize,
+}
+
+/// Frame handed to a worker, collected with [`PendingJob::join`].
+pub(crate) struct PendingJob(mpsc::Receiver<Result<GlFence, GlError>>);
+
+impl RenderPool {
+	/// Starts one worker per available core, up to [`MAX_RENDER_WORKERS`].
+	pub(crate) fn new(gl: &GlContext) -> Result<Self, GlError> {
+		let count = thread::available_parallelism()
+			.map_or(1, |cores| cores.get())
+			.min(MAX_RENDER_WORKERS);
+		let mut workers = Vec::with_capacity(count);
+		for index in 0..count {
+			let shared = gl.create_shared_context()?;
+			let (tasks, queue) = mpsc::channel();
+			let thread = thread::Builder::new()
+				.name(format!("tab-render-{index}"))
+				.spawn(move || run_worker(shared, queue))
+				.map_err(|err| GlError::CreateObjectFailed(format!("render worker: {err}")))?;
+			workers.push(Worker {
+				tasks: Some(tasks),
+				thread: Some(thread),
+			});
+		}
+		Ok(Self { workers, next: 0 })
+	}
+
+	/// Queues a job on the next worker in turn.
+	pub(crate) fn dispatch(
+		&mut self,
+		target: SharedTarget,
+		monitor_id: &str,
+		buffer_age: u32,
+		clear: Option<[f32; 4]>,
+		srgb: bool,
+		job: RenderJob,
+	) -> PendingJob {
+		let (done, result) = mpsc::channel();
+		let worker = &self.workers[self.next % self.workers.len()];
+		self.next = self.next.wrapping_add(1);
+		let task = Task {
+			target,
+			monitor_id: monitor_id.to_string(),
+			buffer_age,
+			clear,
+			srgb,
+			job,
+			done,
+		};
+		// A worker that exited drops the task and with it `done`, which `join` reports.
+		if let Some(tasks) = &worker.tasks {
+			let _ = tasks.send(task);
+		}
+		PendingJob(result)
+	}
+}
+
+impl PendingJob {
+	/// Waits for the job to be queued on its worker's GPU and returns its fence.
+	pub(crate) fn join(self) -> Result<GlFence, GlError> {
+		self.0.recv().unwrap_or(Err(GlError::RenderWorkerExited))
+	}
+}
+
+impl Drop for RenderPool {
+	fn drop(&mut self) {
+		for worker in &mut self.workers {
+			worker.tasks = None;
+		}
+		for worker in &mut self.workers {
+			if let Some(thread) = worker.thread.take() {
+				let _ = thread.join();
+			}
+		}
+	}
+}
+
+fn run_worker(shared: GlSharedContext, queue: mpsc::Receiver<Task>) {
+	if let Err(err) = shared.make_current() {
+		warn!(target: "tab_app_framework.gl", error = %err, "render worker failed to start");
+		return;
+	}
+	while let Ok(task) = queue.recv() {
+		let done = task.done.clone();
+		let _ = done.send(run_task(&shared, task));
+	}
+}
+
+fn run_task(shared: &GlSharedContext, task: Task) -> Result<GlFence, GlError> {
+	let gl = shared.glow();
+	let target = &task.target;
+	let framebuffer = unsafe { gl.create_framebuffer() }.map_err(GlError::CreateObjectFailed)?;
+	unsafe {
+		gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+		gl.framebuffer_texture_2d(
+			glow::FRAMEBUFFER,
+			glow::COLOR_ATTACHMENT0,
+			glow::TEXTURE_2D,
+			Some(target.texture),
+			0,
+		);
+		if let Some(depth_stencil) = target.depth_stencil {
+			gl.framebuffer_renderbuffer(
+				glow::FRAMEBUFFER,
+				glow::DEPTH_STENCIL_ATTACHMENT,
+				glow::RENDERBUFFER,
+				Some(depth_stencil),
+			);
+		}
+		if task.srgb {
+			gl.enable(glow::FRAMEBUFFER_SRGB);
+		}
+		gl.viewport(0, 0, target.width, target.height);
+		if let Some([r, g, b, a]) = task.clear {
+			gl.disable(glow::SCISSOR_TEST);
+			gl.color_mask(true, true, true, true);
+			gl.clear_color(r, g, b, a);
+			let mut mask = glow::COLOR_BUFFER_BIT;
+			if target.depth_stencil.is_some() {
+				gl.depth_mask(true);
+				gl.stencil_mask(!0);
+				gl.clear_depth_f32(1.0);
+				gl.clear_stencil(0);
+				mask |= glow::DEPTH_BUFFER_BIT | glow::STENCIL_BUFFER_BIT;
+			}
+			gl.clear(mask);
+		}
+	}
+	let ctx = RenderJobContext {
+		gl,
+		monitor_id: &task.monitor_id,
+		width: target.width,
+		height: target.height,
+		buffer_age: task.buffer_age,
+	};
+	(task.job)(&ctx);
+	let fence = shared.create_fence();
+	unsafe {
+		gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+		gl.delete_framebuffer(framebuffer);
+	}
+	fence
+}
+
+impl GlContext {
+	/// Imports a single-sampled monitor swapchain buffer for a render worker.
+	pub(crate) fn prepare_shared_target(
+		&mut self,
+		monitor_id: &str,
+		target: &RenderTarget<'_>,
+	) -> Result<SharedTarget, GlError> {
+		self.prepare_render_target(monitor_id, target)?;
+		let key = RenderTargetKey::new(monitor_id, target.buffer_index() as u8);
+		let texture = self
+			.dmabuf_targets
+			.get(&key)
+			.expect("dmabuf target cache unexpectedly missing")
+			.texture;
+		// Objects created here must reach the driver before another context uses them.
+		unsafe { self.glow.flush() };
+		Ok(SharedTarget {
+			texture,
+			depth_stencil: self.depth_stencil_renderbuffer(monitor_id),
+			width: target.width(),
+			height: target.height(),
+		})
+	}
+
+	/// Makes the render context wait for a worker's frame and fences it for submission.
+	///
+	/// Returns the acquire fence for [`tab_app_framework_core::Context::submit_frame`], as
+	/// [`GlContext::create_frame_fence`] does for serially rendered frames.
+	pub(crate) fn join_frame_fence(&mut self, fence: &GlFence) -> Result<Option<OwnedFd>, GlError> {
+		self.wait_fence(fence)?;
+		// The bound target belongs to another monitor; there is nothing to resolve.
+		self.current_target = None;
+		self.create_frame_fence()
+	}
+}
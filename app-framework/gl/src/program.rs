@@ -0,0 +1,268 @@
+//! Shader compilation and linking with cached uniform locations.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use glow::HasContext;
+
+use crate::{GlContext, GlError};
+
+/// Linked shader program with cached uniform locations.
+///
+/// GL objects are not freed on drop: call [`Program::delete`] with the context current, or
+/// just drop the program once the context was lost.
+#[derive(Debug)]
+pub struct Program {
+	program: glow::NativeProgram,
+	uniforms: RefCell<HashMap<String, Option<glow::NativeUniformLocation>>>,
+}
+
+impl Program {
+	/// Compiles and links a vertex and a fragment shader.
+	///
+	/// Sources are used as given, including their `#version` line. Compile errors quote
+	/// the source line each driver message refers to.
+	pub fn from_sources(gl: &GlContext, vertex: &str, fragment: &str) -> Result<Self, GlError> {
+		Self::from_sources_with_attributes(gl, vertex, fragment, &[])
+	}
+
+	/// Like [`Program::from_sources`], binding vertex attributes to fixed locations before
+	/// linking, for GLSL versions without `layout(location = ..)`.
+	pub fn from_sources_with_attributes(
+		gl: &GlContext,
+		vertex: &str,
+		fragment: &str,
+		attributes: &[(&str, u32)],
+	) -> Result<Self, GlError> {
+		let glow = gl.glow();
+		let vertex_shader = compile_shader(glow, glow::VERTEX_SHADER, "vertex", vertex)?;
+		let fragment_shader = match compile_shader(glow, glow::FRAGMENT_SHADER, "fragment", fragment) {
+			Ok(shader) => shader,
+			Err(err) => {
+				unsafe { glow.delete_shader(vertex_shader) };
+				return Err(err);
+			}
+		};
+		let linked = link_program(glow, &[vertex_shader, fragment_shader], attributes);
+		unsafe {
+			glow.delete_shader(vertex_shader);
+			glow.delete_shader(fragment_shader);
+		}
+		Ok(Self {
+			program: linked?,
+			uniforms: RefCell::new(HashMap::new()),
+		})
+	}
+
+	/// Returns the underlying program object.
+	pub fn raw(&self) -> glow::NativeProgram {
+		self.program
+	}
+
+	/// Makes this the current program.
+	pub fn bind(&self, gl: &GlContext) {
+		unsafe { gl.glow().use_program(Some(self.program)) };
+	}
+
+	/// Returns the location of a uniform, `None` if it does not exist or was optimized out.
+	///
+	/// Locations are looked up once per name and cached.
+	pub fn uniform_location(
+		&self,
+		gl: &GlContext,
+		name: &str,
+	) -> Option<glow::NativeUniformLocation> {
+		if let Some(location) = self.uniforms.borrow().get(name) {
+			return *location;
+		}
+		let location = unsafe { gl.glow().get_uniform_location(self.program, name) };
+		self
+			.uniforms
+			.borrow_mut()
+			.insert(name.to_string(), location);
+		location
+	}
+
+	/// Returns the location of a vertex attribute, `None` if it is not active.
+	pub fn attribute_location(&self, gl: &GlContext, name: &str) -> Option<u32> {
+		unsafe { gl.glow().get_attrib_location(self.program, name) }
+	}
+
+	/// Sets a uniform of this program, which must be bound.
+	///
+	/// Uniforms the driver optimized out are skipped, so unused ones need no special casing.
+	pub fn set_uniform(&self, gl: &GlContext, name: &str, value: impl UniformValue) {
+		if let Some(location) = self.uniform_location(gl, name) {
+			value.set(gl.glow(), &location);
+		}
+	}
+
+	/// Deletes the program; the context must be current.
+	pub fn delete(self, gl: &GlContext) {
+		unsafe { gl.glow().delete_program(self.program) };
+	}
+}
+
+/// Value that can be uploaded with [`Program::set_uniform`].
+///
+/// Implemented for scalars and vectors of `f32`, `i32` and `u32`, `bool`, and column-major
+/// `mat3` (`[f32; 9]`) and `mat4` (`[f32; 16]`) matrices such as [`crate::yuv_to_rgb_matrix`].
+pub trait UniformValue {
+	/// Uploads the value to `location` of the bound program.
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation);
+}
+
+impl UniformValue for f32 {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_1_f32(Some(location), *self) };
+	}
+}
+
+impl UniformValue for i32 {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_1_i32(Some(location), *self) };
+	}
+}
+
+impl UniformValue for u32 {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_1_u32(Some(location), *self) };
+	}
+}
+
+impl UniformValue for bool {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_1_i32(Some(location), *self as i32) };
+	}
+}
+
+impl UniformValue for [f32; 2] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_2_f32_slice(Some(location), self) };
+	}
+}
+
+impl UniformValue for [f32; 3] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_3_f32_slice(Some(location), self) };
+	}
+}
+
+impl UniformValue for [f32; 4] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_4_f32_slice(Some(location), self) };
+	}
+}
+
+impl UniformValue for [i32; 2] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_2_i32_slice(Some(location), self) };
+	}
+}
+
+impl UniformValue for [i32; 3] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_3_i32_slice(Some(location), self) };
+	}
+}
+
+impl UniformValue for [i32; 4] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_4_i32_slice(Some(location), self) };
+	}
+}
+
+impl UniformValue for [f32; 9] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_matrix_3_f32_slice(Some(location), false, self) };
+	}
+}
+
+impl UniformValue for [f32; 16] {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		unsafe { gl.uniform_matrix_4_f32_slice(Some(location), false, self) };
+	}
+}
+
+impl<T: UniformValue + ?Sized> UniformValue for &T {
+	fn set(&self, gl: &glow::Context, location: &glow::NativeUniformLocation) {
+		(**self).set(gl, location);
+	}
+}
+
+fn compile_shader(
+	gl: &glow::Context,
+	kind: u32,
+	stage: &'static str,
+	source: &str,
+) -> Result<glow::NativeShader, GlError> {
+	let shader =
+		unsafe { gl.create_shader(kind) }.map_err(|log| GlError::CompileShaderFailed { stage, log })?;
+	unsafe {
+		gl.shader_source(shader, source);
+		gl.compile_shader(shader);
+		if gl.get_shader_compile_status(shader) {
+			return Ok(shader);
+		}
+		let log = annotate_log(source, &gl.get_shader_info_log(shader));
+		gl.delete_shader(shader);
+		Err(GlError::CompileShaderFailed { stage, log })
+	}
+}
+
+fn link_program(
+	gl: &glow::Context,
+	shaders: &[glow::NativeShader],
+	attributes: &[(&str, u32)],
+) -> Result<glow::NativeProgram, GlError> {
+	unsafe {
+		let program = gl.create_program().map_err(GlError::LinkProgramFailed)?;
+		for &shader in shaders {
+			gl.attach_shader(program, shader);
+		}
+		for &(name, index) in attributes {
+			gl.bind_attrib_location(program, index, name);
+		}
+		gl.link_program(program);
+		for &shader in shaders {
+			gl.detach_shader(program, shader);
+		}
+		if gl.get_program_link_status(program) {
+			return Ok(program);
+		}
+		let log = gl.get_program_info_log(program);
+		gl.delete_program(program);
+		Err(GlError::LinkProgramFailed(log.trim_end().to_string()))
+	}
+}
+
+/// Appends the source line each driver message refers to.
+fn annotate_log(source: &str, log: &str) -> String {
+	let lines: Vec<&str> = source.lines().collect();
+	let mut annotated = String::new();
+	for message in log.lines().filter(|line| !line.trim().is_empty()) {
+		let _ = writeln!(annotated, "{}", message.trim_end());
+		if let Some(number) = log_line_number(message)
+			&& let Some(line) = number.checked_sub(1).and_then(|index| lines.get(index))
+		{
+			let _ = writeln!(annotated, "{number:>5} | {line}");
+		}
+	}
+	annotated.trim_end().to_string()
+}
+
+/// Extracts the line number from driver messages such as Mesa's `0:12(5): error`,
+/// ANGLE's `ERROR: 0:12: ..` or NVIDIA's `0(12) : error`.
+fn log_line_number(message: &str) -> Option<usize> {
+	let bytes = message.as_bytes();
+	(0..bytes.len().saturating_sub(2)).find_map(|i| {
+		let starts_token = i == 0 || !bytes[i - 1].is_ascii_alphanumeric();
+		if !starts_token || bytes[i] != b'0' || !matches!(bytes[i + 1], b':' | b'(') {
+			return None;
+		}
+		let rest = &message[i + 2..];
+		let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+		rest[..digits].parse().ok()
+	})
+}
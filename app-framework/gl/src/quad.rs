@@ -0,0 +1,542 @@
+//! Batched drawing of textured quads and nine-patches in pixel coordinates.
+
+use glow::HasContext;
+
+use crate::{GlContext, GlError, ImageBuffer, Program};
+
+/// Bytes per vertex: position, texture coordinate and RGBA tint as `f32`.
+const STRIDE: i32 = 8 * 4;
+/// Name, index, component count and byte offset of each vertex attribute.
+const ATTRIBUTES: [(&str, u32, i32, i32); 3] = [
+	("a_position", 0, 2, 0),
+	("a_uv", 1, 2, 2 * 4),
+	("a_color", 2, 4, 4 * 4),
+];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+const VERTEX_SHADER: &str = "
+ATTRIBUTE vec2 a_position;
+ATTRIBUTE vec2 a_uv;
+ATTRIBUTE vec4 a_color;
+VARYING vec2 v_uv;
+VARYING vec4 v_color;
+uniform vec2 u_viewport;
+void main() {
+	v_uv = a_uv;
+	v_color = a_color;
+	gl_Position = vec4(a_position / u_viewport * 2.0 - 1.0, 0.0, 1.0);
+}
+";
+
+const FRAGMENT_SHADER: &str = "
+VARYING vec2 v_uv;
+VARYING vec4 v_color;
+uniform sampler2D u_texture;
+void main() {
+	FRAG_COLOR = TEXTURE(u_texture, v_uv) * v_color;
+}
+";
+
+/// Rectangle in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct QuadRect {
+	/// Left edge.
+	pub x: f32,
+	/// Top edge.
+	pub y: f32,
+	/// Width.
+	pub width: f32,
+	/// Height.
+	pub height: f32,
+}
+
+impl QuadRect {
+	/// Creates a rect from its top-left corner and size.
+	pub fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+		Self {
+			x,
+			y,
+			width,
+			height,
+		}
+	}
+}
+
+/// 2D affine transform in pixel coordinates.
+///
+/// Maps `(x, y)` to `(a * x + c * y + tx, b * x + d * y + ty)` for `[a, b, c, d, tx, ty]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadTransform(pub [f32; 6]);
+
+impl Default for QuadTransform {
+	fn default() -> Self {
+		Self::IDENTITY
+	}
+}
+
+impl QuadTransform {
+	/// Leaves coordinates unchanged.
+	pub const IDENTITY: Self = Self([1.0, 0.0, 0.0, 1.0, 0.0, 0.0]);
+
+	/// Moves by `(x, y)` pixels.
+	pub fn translation(x: f32, y: f32) -> Self {
+		Self([1.0, 0.0, 0.0, 1.0, x, y])
+	}
+
+	/// Scales around the origin.
+	pub fn scale(sx: f32, sy: f32) -> Self {
+		Self([sx, 0.0, 0.0, sy, 0.0, 0.0])
+	}
+
+	/// Rotates clockwise on screen around the origin, since y points down.
+	pub fn rotation(radians: f32) -> Self {
+		let (sin, cos) = radians.sin_cos();
+		Self([cos, sin, -sin, cos, 0.0, 0.0])
+	}
+
+	/// Returns the transform applying `self` first, then `next`.
+	pub fn then(self, next: Self) -> Self {
+		let [a, b, c, d, tx, ty] = self.0;
+		let [na, nb, nc, nd, ntx, nty] = next.0;
+		Self([
+			na * a + nc * b,
+			nb * a + nd * b,
+			na * c + nc * d,
+			nb * c + nd * d,
+			na * tx + nc * ty + ntx,
+			nb * tx + nd * ty + nty,
+		])
+	}
+
+	/// Transforms a point.
+	pub fn apply(&self, x: f32, y: f32) -> (f32, f32) {
+		let [a, b, c, d, tx, ty] = self.0;
+		(a * x + c * y + tx, b * x + d * y + ty)
+	}
+}
+
+/// Insets of a nine-patch image in texture pixels.
+///
+/// The corners keep their size, the edges stretch along one axis and the center along both.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NinePatch {
+	/// Width of the left column.
+	pub left: f32,
+	/// Height of the top row.
+	pub top: f32,
+	/// Width of the right column.
+	pub right: f32,
+	/// Height of the bottom row.
+	pub bottom: f32,
+}
+
+/// `GL_TEXTURE_2D` texture drawable with a [`QuadBatcher`].
+///
+/// GL objects are not freed on drop; call [`QuadTexture::delete`] with the context current.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuadTexture {
+	texture: glow::NativeTexture,
+	width: u32,
+	height: u32,
+}
+
+impl QuadTexture {
+	/// Wraps an existing texture, e.g. from [`GlContext::offscreen_texture`].
+	pub fn from_raw(texture: glow::NativeTexture, width: u32, height: u32) -> Self {
+		Self {
+			texture,
+			width,
+			height,
+		}
+	}
+
+	/// Returns the underlying texture.
+	pub fn raw(&self) -> glow::NativeTexture {
+		self.texture
+	}
+
+	/// Texture width in pixels.
+	pub fn width(&self) -> u32 {
+		self.width
+	}
+
+	/// Texture height in pixels.
+	pub fn height(&self) -> u32 {
+		self.height
+	}
+
+	/// Replaces the contents with tightly packed RGBA8 pixels of the same size.
+	pub fn update(&self, gl: &GlContext, rgba: &[u8]) -> Result<(), GlError> {
+		check_image_size(self.width, self.height, rgba)?;
+		let glow = gl.glow();
+		unsafe {
+			glow.bind_texture(glow::TEXTURE_2D, Some(self.texture));
+			glow.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+			glow.tex_sub_image_2d(
+				glow::TEXTURE_2D,
+				0,
+				0,
+				0,
+				self.width as i32,
+				self.height as i32,
+				glow::RGBA,
+				glow::UNSIGNED_BYTE,
+				glow::PixelUnpackData::Slice(rgba),
+			);
+			glow.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+			glow.bind_texture(glow::TEXTURE_2D, None);
+		}
+		Ok(())
+	}
+
+	/// Deletes the texture; the context must be current.
+	pub fn delete(self, gl: &GlContext) {
+		unsafe { gl.glow().delete_texture(self.texture) };
+	}
+}
+
+#[derive(Debug)]
+struct Batch {
+	texture: glow::NativeTexture,
+	first: i32,
+	count: i32,
+}
+
+/// Collects textured quads for a frame and draws them with one upload per flush.
+///
+/// Coordinates are in pixels with the origin at the top-left corner of the render target,
+/// i.e. its first scanline, and y pointing down. Consecutive quads sharing a texture are
+/// drawn with a single call. Textures hold straight, not premultiplied, alpha.
+#[derive(Debug)]
+pub struct QuadBatcher {
+	program: Program,
+	vbo: glow::NativeBuffer,
+	vao: Option<glow::NativeVertexArray>,
+	white: QuadTexture,
+	viewport: (f32, f32),
+	vertices: Vec<u8>,
+	batches: Vec<Batch>,
+}
+
+impl QuadBatcher {
+	/// Creates the shader program and buffers; the context must be current.
+	pub fn new(gl: &GlContext) -> Result<Self, GlError> {
+		let (vertex, fragment) = shader_sources(gl.glow().version());
+		let attributes = ATTRIBUTES.map(|(name, index, _, _)| (name, index));
+		let program = Program::from_sources_with_attributes(gl, &vertex, &fragment, &attributes)?;
+		let glow = gl.glow();
+		let vbo = match unsafe { glow.create_buffer() } {
+			Ok(vbo) => vbo,
+			Err(err) => {
+				program.delete(gl);
+				return Err(GlError::CreateObjectFailed(err));
+			}
+		};
+		// Core profiles need a vertex array object; GLES 2 has none.
+		let vao = (glow.version().major >= 3)
+			.then(|| unsafe { glow.create_vertex_array() }.ok())
+			.flatten();
+		let white = match upload(gl, 1, 1, &[255; 4]) {
+			Ok(white) => white,
+			Err(err) => {
+				unsafe {
+					glow.delete_buffer(vbo);
+					if let Some(vao) = vao {
+						glow.delete_vertex_array(vao);
+					}
+				}
+				program.delete(gl);
+				return Err(err);
+			}
+		};
+		Ok(Self {
+			program,
+			vbo,
+			vao,
+			white,
+			viewport: (1.0, 1.0),
+			vertices: Vec::new(),
+			batches: Vec::new(),
+		})
+	}
+
+	/// Uploads tightly packed RGBA8 pixels to a new texture with linear filtering.
+	pub fn upload_rgba(
+		&self,
+		gl: &GlContext,
+		width: u32,
+		height: u32,
+		rgba: &[u8],
+	) -> Result<QuadTexture, GlError> {
+		upload(gl, width, height, rgba)
+	}
+
+	/// Uploads an [`ImageBuffer`], e.g. from [`GlContext::read_pixels`], to a new texture.
+	pub fn upload_image(&self, gl: &GlContext, image: &ImageBuffer) -> Result<QuadTexture, GlError> {
+		upload(gl, image.width, image.height, &image.data)
+	}
+
+	/// Starts a batch for a render target of `width`x`height` pixels, dropping unflushed quads.
+	pub fn begin(&mut self, width: i32, height: i32) {
+		self.viewport = (width.max(1) as f32, height.max(1) as f32);
+		self.vertices.clear();
+		self.batches.clear();
+	}
+
+	/// Draws a whole texture into `dst`.
+	pub fn draw(&mut self, texture: &QuadTexture, dst: QuadRect, transform: QuadTransform) {
+		let src = QuadRect::new(0.0, 0.0, texture.width as f32, texture.height as f32);
+		self.draw_region(texture, src, dst, WHITE, transform);
+	}
+
+	/// Draws the `src` region of a texture, in texture pixels, into `dst` tinted by `color`.
+	pub fn draw_region(
+		&mut self,
+		texture: &QuadTexture,
+		src: QuadRect,
+		dst: QuadRect,
+		color: [f32; 4],
+		transform: QuadTransform,
+	) {
+		let (tw, th) = (texture.width.max(1) as f32, texture.height.max(1) as f32);
+		let (u0, v0) = (src.x / tw, src.y / th);
+		let (u1, v1) = ((src.x + src.width) / tw, (src.y + src.height) / th);
+		let corners = [
+			(dst.x, dst.y, u0, v0),
+			(dst.x + dst.width, dst.y, u1, v0),
+			(dst.x, dst.y + dst.height, u0, v1),
+			(dst.x + dst.width, dst.y + dst.height, u1, v1),
+		];
+		let first = self.vertex_count();
+		for index in [0, 1, 2, 2, 1, 3] {
+			let (x, y, u, v) = corners[index];
+			let (x, y) = transform.apply(x, y);
+			for value in [x, y, u, v].into_iter().chain(color) {
+				self.vertices.extend_from_slice(&value.to_ne_bytes());
+			}
+		}
+		match self.batches.last_mut() {
+			Some(batch) if batch.texture == texture.texture => batch.count += 6,
+			_ => self.batches.push(Batch {
+				texture: texture.texture,
+				first,
+				count: 6,
+			}),
+		}
+	}
+
+	/// Fills `dst` with a solid color.
+	pub fn fill_rect(&mut self, dst: QuadRect, color: [f32; 4], transform: QuadTransform) {
+		let white = self.white;
+		self.draw_region(
+			&white,
+			QuadRect::new(0.0, 0.0, 1.0, 1.0),
+			dst,
+			color,
+			transform,
+		);
+	}
+
+	/// Draws a texture stretched to `dst` as a nine-patch.
+	///
+	/// When `dst` is smaller than the corners, they shrink proportionally.
+	pub fn draw_nine_patch(
+		&mut self,
+		texture: &QuadTexture,
+		patch: NinePatch,
+		dst: QuadRect,
+		color: [f32; 4],
+		transform: QuadTransform,
+	) {
+		let (tw, th) = (texture.width as f32, texture.height as f32);
+		let src_x = [0.0, patch.left, tw - patch.right, tw];
+		let src_y = [0.0, patch.top, th - patch.bottom, th];
+		let dst_x = nine_patch_edges(dst.x, dst.width, patch.left, patch.right);
+		let dst_y = nine_patch_edges(dst.y, dst.height, patch.top, patch.bottom);
+		for row in 0..3 {
+			for column in 0..3 {
+				let dst = QuadRect::new(
+					dst_x[column],
+					dst_y[row],
+					dst_x[column + 1] - dst_x[column],
+					dst_y[row + 1] - dst_y[row],
+				);
+				if dst.width <= 0.0 || dst.height <= 0.0 {
+					continue;
+				}
+				let src = QuadRect::new(
+					src_x[column],
+					src_y[row],
+					src_x[column + 1] - src_x[column],
+					src_y[row + 1] - src_y[row],
+				);
+				self.draw_region(texture, src, dst, color, transform);
+			}
+		}
+	}
+
+	/// Draws the quads collected since [`QuadBatcher::begin`] into the bound framebuffer.
+	///
+	/// Enables alpha blending for the draw, restoring the previous enable state but leaving
+	/// the blend function set; the program, buffers and texture bindings are reset to none.
+	pub fn flush(&mut self, gl: &GlContext) {
+		if self.batches.is_empty() {
+			return;
+		}
+		let glow = gl.glow();
+		self.program.bind(gl);
+		self
+			.program
+			.set_uniform(gl, "u_viewport", [self.viewport.0, self.viewport.1]);
+		self.program.set_uniform(gl, "u_texture", 0);
+		unsafe {
+			let blend = glow.is_enabled(glow::BLEND);
+			glow.enable(glow::BLEND);
+			glow.blend_func_separate(
+				glow::SRC_ALPHA,
+				glow::ONE_MINUS_SRC_ALPHA,
+				glow::ONE,
+				glow::ONE_MINUS_SRC_ALPHA,
+			);
+			glow.active_texture(glow::TEXTURE0);
+			if let Some(vao) = self.vao {
+				glow.bind_vertex_array(Some(vao));
+			}
+			glow.bind_buffer(glow::ARRAY_BUFFER, Some(self.vbo));
+			glow.buffer_data_u8_slice(glow::ARRAY_BUFFER, &self.vertices, glow::STREAM_DRAW);
+			for (_, index, size, offset) in ATTRIBUTES {
+				glow.enable_vertex_attrib_array(index);
+				glow.vertex_attrib_pointer_f32(index, size, glow::FLOAT, false, STRIDE, offset);
+			}
+			for batch in &self.batches {
+				glow.bind_texture(glow::TEXTURE_2D, Some(batch.texture));
+				glow.draw_arrays(glow::TRIANGLES, batch.first, batch.count);
+			}
+			for (_, index, _, _) in ATTRIBUTES {
+				glow.disable_vertex_attrib_array(index);
+			}
+			glow.bind_texture(glow::TEXTURE_2D, None);
+			glow.bind_buffer(glow::ARRAY_BUFFER, None);
+			if self.vao.is_some() {
+				glow.bind_vertex_array(None);
+			}
+			glow.use_program(None);
+			if !blend {
+				glow.disable(glow::BLEND);
+			}
+		}
+		self.vertices.clear();
+		self.batches.clear();
+	}
+
+	/// Deletes the program, buffers and internal texture; the context must be current.
+	///
+	/// Textures from [`QuadBatcher::upload_rgba`] are deleted separately.
+	pub fn delete(self, gl: &GlContext) {
+		let glow = gl.glow();
+		unsafe {
+			glow.delete_buffer(self.vbo);
+			if let Some(vao) = self.vao {
+				glow.delete_vertex_array(vao);
+			}
+		}
+		self.white.delete(gl);
+		self.program.delete(gl);
+	}
+
+	fn vertex_count(&self) -> i32 {
+		(self.vertices.len() / STRIDE as usize) as i32
+	}
+}
+
+fn upload(gl: &GlContext, width: u32, height: u32, rgba: &[u8]) -> Result<QuadTexture, GlError> {
+	check_image_size(width, height, rgba)?;
+	let glow = gl.glow();
+	let texture = unsafe { glow.create_texture() }.map_err(GlError::CreateObjectFailed)?;
+	unsafe {
+		glow.bind_texture(glow::TEXTURE_2D, Some(texture));
+		glow.pixel_store_i32(glow::UNPACK_ALIGNMENT, 1);
+		glow.tex_image_2d(
+			glow::TEXTURE_2D,
+			0,
+			// Unsized, as GLES 2 requires the internal format to match the pixel format.
+			glow::RGBA as i32,
+			width as i32,
+			height as i32,
+			0,
+			glow::RGBA,
+			glow::UNSIGNED_BYTE,
+			Some(rgba),
+		);
+		glow.pixel_store_i32(glow::UNPACK_ALIGNMENT, 4);
+		for (parameter, value) in [
+			(glow::TEXTURE_MIN_FILTER, glow::LINEAR),
+			(glow::TEXTURE_MAG_FILTER, glow::LINEAR),
+			(glow::TEXTURE_WRAP_S, glow::CLAMP_TO_EDGE),
+			(glow::TEXTURE_WRAP_T, glow::CLAMP_TO_EDGE),
+		] {
+			glow.tex_parameter_i32(glow::TEXTURE_2D, parameter, value as i32);
+		}
+		glow.bind_texture(glow::TEXTURE_2D, None);
+	}
+	Ok(QuadTexture {
+		texture,
+		width,
+		height,
+	})
+}
+
+fn check_image_size(width: u32, height: u32, rgba: &[u8]) -> Result<(), GlError> {
+	let expected = width as usize * height as usize * 4;
+	if rgba.len() != expected {
+		return Err(GlError::InvalidImageData {
+			expected,
+			got: rgba.len(),
+		});
+	}
+	Ok(())
+}
+
+/// Splits `[start, start + size]` at the nine-patch insets, shrinking them to fit.
+fn nine_patch_edges(start: f32, size: f32, low: f32, high: f32) -> [f32; 4] {
+	let insets = low + high;
+	let scale = if insets > size && insets > 0.0 {
+		size / insets
+	} else {
+		1.0
+	};
+	[
+		start,
+		start + low * scale,
+		start + size - high * scale,
+		start + size,
+	]
+}
+
+/// Prefixes the shader bodies with the GLSL dialect of the context.
+fn shader_sources(version: &glow::Version) -> (String, String) {
+	let modern = version.major >= 3;
+	let header = match (version.is_embedded, modern) {
+		(true, true) => "#version 300 es\nprecision mediump float;\n",
+		(true, false) => "#version 100\nprecision mediump float;\n",
+		(false, true) if version.minor >= 1 || version.major > 3 => "#version 140\n",
+		(false, true) => "#version 130\n",
+		(false, false) => "#version 120\n",
+	};
+	let (vertex_defines, fragment_defines) = if modern {
+		(
+			"#define ATTRIBUTE in\n#define VARYING out\n",
+			"#define VARYING in\n#define TEXTURE texture\n#define FRAG_COLOR frag_color\n\
+			 out vec4 frag_color;\n",
+		)
+	} else {
+		(
+			"#define ATTRIBUTE attribute\n#define VARYING varying\n",
+			"#define VARYING varying\n#define TEXTURE texture2D\n#define FRAG_COLOR gl_FragColor\n",
+		)
+	};
+	(
+		format!("{header}{vertex_defines}{VERTEX_SHADER}"),
+		format!("{header}{fragment_defines}{FRAGMENT_SHADER}"),
+	)
+}
@@ -0,0 +1,120 @@
+//! Pixel readback from the bound render target for tests and thumbnails.
+
+use glow::HasContext;
+
+use crate::{GlContext, GlError, egl};
+
+/// Region of a framebuffer in pixels, with the origin at row 0 of the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelRect {
+	/// Left edge.
+	pub x: i32,
+	/// First row.
+	pub y: i32,
+	/// Width in pixels.
+	pub width: i32,
+	/// Height in pixels.
+	pub height: i32,
+}
+
+impl PixelRect {
+	/// Creates a rect covering `width`x`height` pixels from the origin.
+	pub fn from_size(width: i32, height: i32) -> Self {
+		Self {
+			x: 0,
+			y: 0,
+			width,
+			height,
+		}
+	}
+}
+
+/// Tightly packed RGBA8 pixels read back from a framebuffer.
+///
+/// Rows are in buffer order: row 0 is the first scanline of the render target.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageBuffer {
+	/// Width in pixels.
+	pub width: u32,
+	/// Height in pixels.
+	pub height: u32,
+	/// RGBA8 pixel data, `width * height * 4` bytes.
+	pub data: Vec<u8>,
+}
+
+impl ImageBuffer {
+	/// Returns the RGBA value at `(x, y)`, if in bounds.
+	pub fn pixel(&self, x: u32, y: u32) -> Option<[u8; 4]> {
+		if x >= self.width || y >= self.height {
+			return None;
+		}
+		let offset = ((y * self.width + x) * 4) as usize;
+		let px = &self.data[offset..offset + 4];
+		Some([px[0], px[1], px[2], px[3]])
+	}
+}
+
+/// Delivered by [`crate::GlApplication::on_frame_captured`] once a requested capture completes.
+#[derive(Debug, Clone)]
+pub struct FrameCaptureEvent {
+	/// Captured monitor id.
+	pub monitor_id: String,
+	/// Rendered frame contents.
+	pub image: ImageBuffer,
+}
+
+impl GlContext {
+	/// Reads RGBA8 pixels from the currently bound framebuffer.
+	///
+	/// On OpenGL ES only `RGBA`/`UNSIGNED_BYTE` plus one implementation-chosen
+	/// format are readable; a BGRA implementation format is used directly and
+	/// swizzled, which avoids a driver-side conversion on most mobile GPUs.
+	pub fn read_pixels(&self, rect: PixelRect) -> Result<ImageBuffer, GlError> {
+		if rect.width <= 0 || rect.height <= 0 {
+			return Err(GlError::InvalidReadRect(rect));
+		}
+		let mut data = vec![0u8; rect.width as usize * rect.height as usize * 4];
+		let read_bgra = self.api == egl::OPENGL_ES_API && self.implementation_reads_bgra();
+		let format = if read_bgra { glow::BGRA } else { glow::RGBA };
+		unsafe {
+			self.glow.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+			self.glow.read_pixels(
+				rect.x,
+				rect.y,
+				rect.width,
+				rect.height,
+				format,
+				glow::UNSIGNED_BYTE,
+				glow::PixelPackData::Slice(&mut data),
+			);
+			self.glow.pixel_store_i32(glow::PACK_ALIGNMENT, 4);
+		}
+		let error = unsafe { self.glow.get_error() };
+		if error != glow::NO_ERROR {
+			return Err(GlError::ReadPixelsFailed(error));
+		}
+		if read_bgra {
+			for px in data.chunks_exact_mut(4) {
+				px.swap(0, 2);
+			}
+		}
+		Ok(ImageBuffer {
+			width: rect.width as u32,
+			height: rect.height as u32,
+			data,
+		})
+	}
+
+	fn implementation_reads_bgra(&self) -> bool {
+		unsafe {
+			self
+				.glow
+				.get_parameter_i32(glow::IMPLEMENTATION_COLOR_READ_FORMAT) as u32
+				== glow::BGRA
+				&& self
+					.glow
+					.get_parameter_i32(glow::IMPLEMENTATION_COLOR_READ_TYPE) as u32
+					== glow::UNSIGNED_BYTE
+		}
+	}
+}
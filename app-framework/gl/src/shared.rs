@@ -0,0 +1,213 @@
+//! Share-group contexts and fence-based handoff for worker-thread GL uploads.
+
+use std::ptr;
+use std::sync::Arc;
+
+use glow::HasContext;
+
+use crate::{EglDevice, GlContext, GlError, egl, load_proc_raw};
+
+/// Secondary GL context in the same share group as a [`GlContext`].
+///
+/// Intended to be moved to a worker thread and made current there. Objects
+/// created through it (textures, buffers) are visible to the render context
+/// once the producing work has been fenced with [`GlSharedContext::finish_upload`]
+/// and waited on with [`GlContext::wait_texture_upload`].
+pub struct GlSharedContext {
+	device: Arc<EglDevice>,
+	context: egl::types::EGLContext,
+	api: u32,
+	glow: glow::Context,
+}
+
+// SAFETY: the context is only made current by the thread that owns this value;
+// EGL allows a context to migrate between threads while it is not current.
+unsafe impl Send for GlSharedContext {}
+
+impl GlContext {
+	/// Creates a context sharing textures and buffers with this one.
+	///
+	/// The returned context is not current; call [`GlSharedContext::make_current`]
+	/// on the thread that will use it.
+	pub fn create_shared_context(&self) -> Result<GlSharedContext, GlError> {
+		let egl = &self.device.egl;
+		if unsafe { egl.BindAPI(self.api) } == 0 {
+			return Err(GlError::BindApiFailed(unsafe { egl.GetError() }));
+		}
+		let context = unsafe {
+			egl.CreateContext(
+				self.device.display,
+				self.config,
+				self.context,
+				self.context_attribs.as_ptr() as *const _,
+			)
+		};
+		if context.is_null() {
+			return Err(GlError::CreateContextFailed(unsafe { egl.GetError() }));
+		}
+
+		let device = Arc::clone(&self.device);
+		let glow = unsafe {
+			glow::Context::from_loader_function(|name| {
+				load_proc_raw(&device.egl, &device.egl_lib, &device.gl_lib, name).unwrap_or(ptr::null())
+					as *const _
+			})
+		};
+		Ok(GlSharedContext {
+			device,
+			context,
+			api: self.api,
+			glow,
+		})
+	}
+
+	/// Makes the render context wait for a worker upload before sampling its texture.
+	///
+	/// The wait is queued on the GPU when `eglWaitSync` is available and falls back
+	/// to a blocking client wait otherwise.
+	pub fn wait_texture_upload(&self, upload: TextureUpload) -> Result<glow::NativeTexture, GlError> {
+		upload.fence.wait()?;
+		Ok(upload.texture)
+	}
+
+	/// Makes the render context wait for an arbitrary fence from a shared context.
+	pub fn wait_fence(&self, fence: &GlFence) -> Result<(), GlError> {
+		fence.wait()
+	}
+}
+
+impl GlSharedContext {
+	/// Makes this context current on the calling thread.
+	pub fn make_current(&self) -> Result<(), GlError> {
+		let egl = &self.device.egl;
+		if unsafe { egl.BindAPI(self.api) } == 0 {
+			return Err(GlError::BindApiFailed(unsafe { egl.GetError() }));
+		}
+		let ok = unsafe {
+			egl.MakeCurrent(
+				self.device.display,
+				egl::NO_SURFACE,
+				egl::NO_SURFACE,
+				self.context,
+			)
+		};
+		if ok == 0 {
+			return Err(GlError::MakeCurrentFailed(unsafe { egl.GetError() }));
+		}
+		Ok(())
+	}
+
+	/// Returns the underlying `glow` context.
+	pub fn glow(&self) -> &glow::Context {
+		&self.glow
+	}
+
+	/// Inserts a fence after all GL work queued so far on this context.
+	pub fn create_fence(&self) -> Result<GlFence, GlError> {
+		let attribs = [egl::NONE as egl::types::EGLAttrib];
+		let sync = unsafe {
+			self
+				.device
+				.egl
+				.CreateSync(self.device.display, egl::SYNC_FENCE, attribs.as_ptr())
+		};
+		if sync.is_null() {
+			return Err(GlError::CreateSyncFailed(unsafe {
+				self.device.egl.GetError()
+			}));
+		}
+		// The fence must reach the GPU before another context can wait on it.
+		unsafe { self.glow.flush() };
+		Ok(GlFence {
+			device: Arc::clone(&self.device),
+			sync,
+		})
+	}
+
+	/// Fences a texture upload so it can be handed to the render thread.
+	pub fn finish_upload(&self, texture: glow::NativeTexture) -> Result<TextureUpload, GlError> {
+		Ok(TextureUpload {
+			texture,
+			fence: self.create_fence()?,
+		})
+	}
+}
+
+impl Drop for GlSharedContext {
+	fn drop(&mut self) {
+		unsafe {
+			if self.device.egl.GetCurrentContext() == self.context {
+				let _ = self.device.egl.MakeCurrent(
+					self.device.display,
+					egl::NO_SURFACE,
+					egl::NO_SURFACE,
+					egl::NO_CONTEXT,
+				);
+			}
+			if !self.context.is_null() {
+				self
+					.device
+					.egl
+					.DestroyContext(self.device.display, self.context);
+			}
+		}
+	}
+}
+
+/// EGL fence marking the end of GL work submitted by one context.
+pub struct GlFence {
+	device: Arc<EglDevice>,
+	sync: egl::types::EGLSync,
+}
+
+// SAFETY: EGL sync objects belong to the display, not to a context or thread.
+unsafe impl Send for GlFence {}
+
+impl GlFence {
+	fn wait(&self) -> Result<(), GlError> {
+		let egl = &self.device.egl;
+		if egl.WaitSync.is_loaded() {
+			let ok = unsafe { egl.WaitSync(self.device.display, self.sync, 0) };
+			if ok == 0 {
+				return Err(GlError::WaitSyncFailed(unsafe { egl.GetError() }));
+			}
+			return Ok(());
+		}
+		let status = unsafe {
+			egl.ClientWaitSync(
+				self.device.display,
+				self.sync,
+				egl::SYNC_FLUSH_COMMANDS_BIT as egl::types::EGLint,
+				egl::FOREVER as egl::types::EGLTime,
+			)
+		};
+		if status == egl::TIMEOUT_EXPIRED as egl::types::EGLint {
+			return Err(GlError::WaitSyncTimeout);
+		}
+		if status == egl::FALSE as egl::types::EGLint {
+			return Err(GlError::WaitSyncFailed(unsafe { egl.GetError() }));
+		}
+		Ok(())
+	}
+}
+
+impl Drop for GlFence {
+	fn drop(&mut self) {
+		unsafe {
+			self.device.egl.DestroySync(self.device.display, self.sync);
+		}
+	}
+}
+
+/// Texture produced on a shared context together with the fence guarding it.
+pub struct TextureUpload {
+	texture: glow::NativeTexture,
+	fence: GlFence,
+}
+
+impl TextureUpload {
+	/// Returns the uploaded texture without waiting for the upload to finish.
+	pub fn texture(&self) -> glow::NativeTexture {
+		self.texture
+	}
+}
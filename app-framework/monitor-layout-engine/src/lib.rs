@@ -28,6 +28,80 @@ pub struct MonitorPlacement {
 	pub height: i32,
 }
 
+/// Difference between two layouts, as reported by [`diff_layouts`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutChange {
+	/// A monitor only present in the new layout.
+	Added(MonitorPlacement),
+	/// A monitor only present in the old layout.
+	Removed(MonitorPlacement),
+	/// A monitor whose top-left corner moved from `from` to `to`.
+	Moved {
+		id: String,
+		from: (i32, i32),
+		to: (i32, i32),
+	},
+	/// A monitor whose `(width, height)` changed from `from` to `to`.
+	Resized {
+		id: String,
+		from: (i32, i32),
+		to: (i32, i32),
+	},
+}
+
+impl LayoutChange {
+	/// Identifier of the monitor the change applies to.
+	pub fn id(&self) -> &str {
+		match self {
+			Self::Added(m) | Self::Removed(m) => &m.id,
+			Self::Moved { id, .. } | Self::Resized { id, .. } => id,
+		}
+	}
+}
+
+/// How the cursor crosses an edge shared by two monitors.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EdgeBehavior {
+	/// Crossing is unimpeded.
+	#[default]
+	Free,
+	/// The cursor stops at the edge until pushed this many pixels further into it.
+	Resistance(f64),
+	/// Crossings within this many pixels of a corner of the monitor being left are blocked,
+	/// so the cursor parks in the corner instead of slipping onto the neighbour.
+	StickyCorners(f64),
+}
+
+/// Outer edges of the layout the cursor wraps around, see [`move_cursor_with_wrap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WrapMode {
+	/// The cursor stops at the outer edges.
+	#[default]
+	None,
+	/// Leaving the layout on the left or right re-enters it at the opposite end of the row.
+	Horizontal,
+	/// Leaving the layout at the top or bottom re-enters it at the opposite end of the column.
+	Vertical,
+	/// Wraps along both axes.
+	Both,
+}
+
+impl WrapMode {
+	fn wraps_x(self) -> bool {
+		matches!(self, Self::Horizontal | Self::Both)
+	}
+
+	fn wraps_y(self) -> bool {
+		matches!(self, Self::Vertical | Self::Both)
+	}
+}
+
+/// Edge pressure carried from one motion to the next by [`move_cursor_with_edges`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EdgeState {
+	pressure: f64,
+}
+
 /// Simple deterministic layout used as a baseline:
 /// monitors are placed left-to-right, all at y=0.
 pub fn layout_horizontal(monitors: &[MonitorSpec]) -> Vec<MonitorPlacement> {
@@ -49,6 +123,159 @@ pub fn layout_horizontal(monitors: &[MonitorSpec]) -> Vec<MonitorPlacement> {
 	out
 }
 
+/// Lists the changes turning layout `old` into `new`, matching monitors by id.
+///
+/// Changes are ordered by monitor id; a monitor that moved and was resized yields
+/// [`LayoutChange::Moved`] followed by [`LayoutChange::Resized`].
+pub fn diff_layouts(old: &[MonitorPlacement], new: &[MonitorPlacement]) -> Vec<LayoutChange> {
+	let mut changes = Vec::new();
+	for before in old {
+		let Some(after) = new.iter().find(|m| m.id == before.id) else {
+			changes.push(LayoutChange::Removed(before.clone()));
+			continue;
+		};
+		if (before.x, before.y) != (after.x, after.y) {
+			changes.push(LayoutChange::Moved {
+				id: before.id.clone(),
+				from: (before.x, before.y),
+				to: (after.x, after.y),
+			});
+		}
+		if (before.width, before.height) != (after.width, after.height) {
+			changes.push(LayoutChange::Resized {
+				id: before.id.clone(),
+				from: (before.width, before.height),
+				to: (after.width, after.height),
+			});
+		}
+	}
+	for after in new {
+		if !old.iter().any(|m| m.id == after.id) {
+			changes.push(LayoutChange::Added(after.clone()));
+		}
+	}
+	// Stable, so a monitor's move stays ahead of its resize.
+	changes.sort_by(|a, b| a.id().cmp(b.id()));
+	changes
+}
+
+/// Generates a random layout of `n` monitors satisfying
+/// [`is_valid_edge_contiguous_layout`], e.g. to property-test code built on this crate.
+///
+/// The same `seed` always yields the same layout. Monitors are named `mon_0` to
+/// `mon_{n-1}`, sized between 320x240 and 3840x2160, and each one after the first shares
+/// part of an edge with an earlier one.
+pub fn random_valid_layout(seed: u64, n: usize) -> Vec<MonitorPlacement> {
+	layout_from_choices(&mut SplitMix64(seed), n)
+}
+
+/// Builds a valid layout of `n` monitors from the choices `rng` makes, as described for
+/// [`random_valid_layout`].
+fn layout_from_choices(rng: &mut impl Choices, n: usize) -> Vec<MonitorPlacement> {
+	let mut layout: Vec<MonitorPlacement> = Vec::with_capacity(n);
+	for i in 0..n {
+		let width = rng.range(320, 3840);
+		let height = rng.range(240, 2160);
+		let id = format!("mon_{i}");
+		if layout.is_empty() {
+			layout.push(MonitorPlacement {
+				id,
+				x: 0,
+				y: 0,
+				width,
+				height,
+			});
+			continue;
+		}
+		let mut placed = None;
+		for _ in 0..32 {
+			let anchor = &layout[rng.range(0, layout.len() as i32 - 1) as usize];
+			let (x, y) = match rng.range(0, 3) {
+				0 => (
+					anchor.x + anchor.width,
+					rng.edge_offset(anchor.y, height, anchor.height),
+				),
+				1 => (
+					anchor.x - width,
+					rng.edge_offset(anchor.y, height, anchor.height),
+				),
+				2 => (
+					rng.edge_offset(anchor.x, width, anchor.width),
+					anchor.y + anchor.height,
+				),
+				_ => (
+					rng.edge_offset(anchor.x, width, anchor.width),
+					anchor.y - height,
+				),
+			};
+			let candidate = MonitorPlacement {
+				id: id.clone(),
+				x,
+				y,
+				width,
+				height,
+			};
+			if !layout.iter().any(|m| monitors_overlap_area(m, &candidate)) {
+				placed = Some(candidate);
+				break;
+			}
+		}
+		// Nothing extends past the rightmost edge, so a monitor placed there cannot overlap.
+		let candidate = placed.unwrap_or_else(|| {
+			let anchor = layout
+				.iter()
+				.max_by_key(|m| m.x + m.width)
+				.expect("layout is non-empty");
+			MonitorPlacement {
+				id,
+				x: anchor.x + anchor.width,
+				y: anchor.y,
+				width,
+				height,
+			}
+		});
+		layout.push(candidate);
+	}
+	layout
+}
+
+/// Source of the choices [`layout_from_choices`] makes.
+trait Choices {
+	/// Value in `lo..=hi`.
+	fn range(&mut self, lo: i32, hi: i32) -> i32;
+
+	/// Start of a `len` long span sharing part of the `anchor_len` long span at `start`:
+	/// aligned with either end, which makes for shared corners, or anywhere overlapping it.
+	fn edge_offset(&mut self, start: i32, len: i32, anchor_len: i32) -> i32 {
+		match self.range(0, 3) {
+			0 => start,
+			1 => start + anchor_len - len,
+			_ => self.range(start - len + 1, start + anchor_len - 1),
+		}
+	}
+}
+
+/// Small deterministic generator behind [`random_valid_layout`].
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+	fn next(&mut self) -> u64 {
+		self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+		let mut z = self.0;
+		z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+		z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+		z ^ (z >> 31)
+	}
+}
+
+impl Choices for SplitMix64 {
+	/// Uniform value in `lo..=hi`.
+	fn range(&mut self, lo: i32, hi: i32) -> i32 {
+		let span = (hi as i64 - lo as i64 + 1) as u64;
+		(lo as i64 + (self.next() % span) as i64) as i32
+	}
+}
+
 #[inline]
 fn rect_contains(m: &MonitorPlacement, x: f64, y: f64) -> bool {
 	let left = m.x as f64;
@@ -96,22 +323,10 @@ pub fn is_valid_edge_contiguous_layout(monitors: &[MonitorPlacement]) -> bool {
 		}
 	}
 
-	// Build edge-touch adjacency.
-	let mut degree = vec![0usize; monitors.len()];
-	let mut adj = vec![Vec::<usize>::new(); monitors.len()];
-	for i in 0..monitors.len() {
-		for j in (i + 1)..monitors.len() {
-			if monitors_touch(&monitors[i], &monitors[j]) {
-				degree[i] += 1;
-				degree[j] += 1;
-				adj[i].push(j);
-				adj[j].push(i);
-			}
-		}
-	}
+	let adj = touch_adjacency(monitors);
 
 	// Every monitor must touch at least one other monitor.
-	if degree.iter().any(|d| *d == 0) {
+	if adj.iter().any(|neighbours| neighbours.is_empty()) {
 		return false;
 	}
 
@@ -130,6 +345,20 @@ pub fn is_valid_edge_contiguous_layout(monitors: &[MonitorPlacement]) -> bool {
 	seen.into_iter().all(|v| v)
 }
 
+/// Builds the edge-touch adjacency lists of `monitors`, by index.
+fn touch_adjacency(monitors: &[MonitorPlacement]) -> Vec<Vec<usize>> {
+	let mut adj = vec![Vec::<usize>::new(); monitors.len()];
+	for i in 0..monitors.len() {
+		for j in (i + 1)..monitors.len() {
+			if monitors_touch(&monitors[i], &monitors[j]) {
+				adj[i].push(j);
+				adj[j].push(i);
+			}
+		}
+	}
+	adj
+}
+
 /// Clamps a point to the nearest valid position inside monitor layout.
 pub fn clamp_point_to_layout(monitors: &[MonitorPlacement], x: f64, y: f64) -> (f64, f64) {
 	if monitors.is_empty() {
@@ -158,41 +387,395 @@ pub fn clamp_point_to_layout(monitors: &[MonitorPlacement], x: f64, y: f64) -> (
 	(cx, cy)
 }
 
+/// Returns the monitor holding `(x, y)`.
+///
+/// Right and bottom edges count as inside when no monitor continues past them, so every
+/// point returned by [`clamp_point_to_layout`] has a monitor.
+pub fn monitor_at(monitors: &[MonitorPlacement], x: f64, y: f64) -> Option<&MonitorPlacement> {
+	monitor_index_at(monitors, (x, y)).map(|idx| &monitors[idx])
+}
+
 /// Move a cursor with clamping while avoiding tunneling across monitor edges.
-/// This uses unit-step integration in screen space.
+///
+/// The motion segment is walked monitor by monitor: it is cut where it leaves
+/// the monitor it is in, continues into a neighbour sharing that edge, and
+/// otherwise slides along the blocking edge with the remaining tangential delta.
 pub fn move_cursor_no_tunnel(
 	monitors: &[MonitorPlacement],
 	start_x: f64,
 	start_y: f64,
 	delta_x: f64,
 	delta_y: f64,
+) -> (f64, f64) {
+	move_cursor_with_edges(
+		monitors,
+		start_x,
+		start_y,
+		delta_x,
+		delta_y,
+		EdgeBehavior::Free,
+		&mut EdgeState::default(),
+	)
+}
+
+/// Like [`move_cursor_no_tunnel`], applying `behavior` where the cursor crosses from one
+/// monitor to another.
+///
+/// `state` accumulates resistance over consecutive motions; keep one per cursor. It
+/// resets once a motion no longer pushes against a resisting edge.
+pub fn move_cursor_with_edges(
+	monitors: &[MonitorPlacement],
+	start_x: f64,
+	start_y: f64,
+	delta_x: f64,
+	delta_y: f64,
+	behavior: EdgeBehavior,
+	state: &mut EdgeState,
+) -> (f64, f64) {
+	move_cursor_with_wrap(
+		monitors,
+		start_x,
+		start_y,
+		delta_x,
+		delta_y,
+		behavior,
+		WrapMode::None,
+		state,
+	)
+}
+
+/// Like [`move_cursor_with_edges`], letting the cursor leave the layout through the outer
+/// edges selected by `wrap`.
+///
+/// A cursor pushed past the right edge of the rightmost monitor in its row re-enters at
+/// the left edge of the leftmost one and keeps the rest of the motion; the other edges
+/// wrap the same way. Outer edges are free of `behavior`.
+#[allow(clippy::too_many_arguments)]
+pub fn move_cursor_with_wrap(
+	monitors: &[MonitorPlacement],
+	start_x: f64,
+	start_y: f64,
+	delta_x: f64,
+	delta_y: f64,
+	behavior: EdgeBehavior,
+	wrap: WrapMode,
+	state: &mut EdgeState,
+) -> (f64, f64) {
+	let mut pushed = false;
+	let position = walk_cursor(
+		monitors,
+		start_x,
+		start_y,
+		delta_x,
+		delta_y,
+		wrap,
+		|m, x, y, d, across_x| {
+			let (dx, dy) = if across_x { (d, 0.0) } else { (0.0, d) };
+			let crosses = monitors
+				.iter()
+				.any(|n| !std::ptr::eq(n, m) && can_move_within(n, x, y, dx, dy));
+			if !crosses {
+				return d;
+			}
+			match behavior {
+				EdgeBehavior::Free => d,
+				EdgeBehavior::Resistance(distance) => {
+					pushed = true;
+					state.pressure += d.abs();
+					if state.pressure < distance {
+						return 0.0;
+					}
+					let excess = state.pressure - distance;
+					state.pressure = 0.0;
+					excess.copysign(d)
+				}
+				EdgeBehavior::StickyCorners(size) => {
+					let (left, top, right, bottom) = rect_bounds(m);
+					let (p, lo, hi) = if across_x {
+						(y, top, bottom)
+					} else {
+						(x, left, right)
+					};
+					if p - lo < size || hi - p < size {
+						0.0
+					} else {
+						d
+					}
+				}
+			}
+		},
+	);
+	if !pushed {
+		state.pressure = 0.0;
+	}
+	position
+}
+
+/// Walks a motion segment through the layout.
+///
+/// `cross` is asked for the delta left to travel once the cursor reached an edge of
+/// monitor `m` at `(x, y)`, with `d` left across it (along x when `across_x`); returning
+/// 0 holds the cursor at the edge. Outer edges of the layout selected by `wrap` move the
+/// cursor to the opposite end of its row or column.
+fn walk_cursor(
+	monitors: &[MonitorPlacement],
+	start_x: f64,
+	start_y: f64,
+	delta_x: f64,
+	delta_y: f64,
+	wrap: WrapMode,
+	mut cross: impl FnMut(&MonitorPlacement, f64, f64, f64, bool) -> f64,
 ) -> (f64, f64) {
 	if monitors.is_empty() {
 		return (start_x + delta_x, start_y + delta_y);
 	}
 	let (mut x, mut y) = clamp_point_to_layout(monitors, start_x, start_y);
-	let steps = delta_x.abs().max(delta_y.abs()).ceil().max(1.0) as i32;
-	let steps = steps.clamp(1, 8192);
-	let step_x = delta_x / steps as f64;
-	let step_y = delta_y / steps as f64;
-	for _ in 0..steps {
-		let nx = x + step_x;
-		let ny = y + step_y;
-		if monitors.iter().any(|m| rect_contains(m, nx, ny)) {
-			x = nx;
-			y = ny;
-		} else {
-			let (cx, cy) = clamp_point_to_layout(monitors, nx, ny);
-			if (cx - x).abs() < f64::EPSILON && (cy - y).abs() < f64::EPSILON {
+	let (mut rx, mut ry) = (delta_x, delta_y);
+	// Each pass either finishes, enters another monitor, or drops an axis.
+	for _ in 0..(4 * monitors.len() + 4) {
+		if rx == 0.0 && ry == 0.0 {
+			break;
+		}
+		let Some(m) = monitors.iter().find(|m| can_move_within(m, x, y, rx, ry)) else {
+			let blocked_x = rx != 0.0 && !monitors.iter().any(|m| can_move_within(m, x, y, rx, 0.0));
+			let blocked_y = ry != 0.0 && !monitors.iter().any(|m| can_move_within(m, x, y, 0.0, ry));
+			if blocked_x && wrap.wraps_x() {
+				x = wrap_target(monitors, y, rx, true);
+				continue;
+			}
+			if blocked_y && wrap.wraps_y() {
+				y = wrap_target(monitors, x, ry, false);
+				continue;
+			}
+			if rx != 0.0 && monitors.iter().any(|m| can_move_within(m, x, y, rx, 0.0)) {
+				ry = 0.0;
+			} else if ry != 0.0 && monitors.iter().any(|m| can_move_within(m, x, y, 0.0, ry)) {
+				rx = 0.0;
+			} else {
 				break;
 			}
-			x = cx;
-			y = cy;
+			continue;
+		};
+		let (left, top, right, bottom) = rect_bounds(m);
+		let (tx, edge_x) = axis_exit(x, rx, left, right);
+		let (ty, edge_y) = axis_exit(y, ry, top, bottom);
+		let t = tx.min(ty).min(1.0);
+		let (end_x, end_y) = (x + rx, y + ry);
+		// Snap to the edge that was hit so the next pass starts exactly on it.
+		x = if t == tx { edge_x } else { x + rx * t };
+		y = if t == ty { edge_y } else { y + ry * t };
+		// Measured from the end point rather than scaled by `1 - t`, so a motion crossing
+		// edges ends on the same sub-pixel position as an unobstructed one.
+		rx = remaining(x, end_x, rx);
+		ry = remaining(y, end_y, ry);
+		if t >= 1.0 {
+			break;
+		}
+		if t == tx && rx != 0.0 {
+			rx = cross(m, x, y, rx, true);
+			if rx == 0.0 && x == right {
+				// The right edge belongs to the neighbour; stay on the last column of `m`.
+				x = x.next_down();
+			}
+		}
+		if t == ty && ry != 0.0 {
+			ry = cross(m, x, y, ry, false);
+			if ry == 0.0 && y == bottom {
+				y = y.next_down();
+			}
 		}
 	}
 	(x, y)
 }
 
+/// Coordinate along x (`along_x`) or y where a cursor leaving the layout in direction `d`
+/// re-enters it: the far edge of the row or column at `p` on the other axis.
+fn wrap_target(monitors: &[MonitorPlacement], p: f64, d: f64, along_x: bool) -> f64 {
+	// Monitors whose span on the other axis holds `p`, counting their far edge if needed.
+	let row = |closed: bool| -> Vec<(f64, f64)> {
+		monitors
+			.iter()
+			.filter_map(|m| {
+				let (left, top, right, bottom) = rect_bounds(m);
+				let (lo, hi, start, end) = if along_x {
+					(top, bottom, left, right)
+				} else {
+					(left, right, top, bottom)
+				};
+				(p >= lo && (p < hi || closed && p <= hi)).then_some((start, end))
+			})
+			.collect()
+	};
+	let mut spans = row(false);
+	if spans.is_empty() {
+		spans = row(true);
+	}
+	if d > 0.0 {
+		spans
+			.iter()
+			.map(|&(start, _)| start)
+			.fold(f64::INFINITY, f64::min)
+	} else {
+		spans
+			.iter()
+			.map(|&(_, end)| end)
+			.fold(f64::NEG_INFINITY, f64::max)
+	}
+}
+
+/// Plans a polyline from `from` to `to` that stays inside the monitors, e.g. to animate
+/// the cursor.
+///
+/// Both points are clamped into the layout first. The route visits the fewest monitors,
+/// passes from one to the next through their shared edge, and heads for `to` along each
+/// edge where it can. The first point is the clamped `from` and the last the clamped `to`.
+/// Returns an empty route when `to` lies on a monitor not connected to the one holding
+/// `from`; without monitors the straight line is returned.
+pub fn route_through_layout(
+	monitors: &[MonitorPlacement],
+	from: (f64, f64),
+	to: (f64, f64),
+) -> Vec<(f64, f64)> {
+	if monitors.is_empty() {
+		return vec![from, to];
+	}
+	let from = clamp_point_to_layout(monitors, from.0, from.1);
+	let to = clamp_point_to_layout(monitors, to.0, to.1);
+	let (Some(start), Some(end)) = (
+		monitor_index_at(monitors, from),
+		monitor_index_at(monitors, to),
+	) else {
+		return Vec::new();
+	};
+
+	// Breadth-first search for the path with the fewest edge crossings.
+	let adj = touch_adjacency(monitors);
+	let mut previous = vec![None::<usize>; monitors.len()];
+	let mut seen = vec![false; monitors.len()];
+	let mut queue = std::collections::VecDeque::from([start]);
+	seen[start] = true;
+	while let Some(i) = queue.pop_front() {
+		if i == end {
+			break;
+		}
+		for &j in &adj[i] {
+			if !seen[j] {
+				seen[j] = true;
+				previous[j] = Some(i);
+				queue.push_back(j);
+			}
+		}
+	}
+	if !seen[end] {
+		return Vec::new();
+	}
+	let mut hops = vec![end];
+	while let Some(i) = previous[*hops.last().expect("hops start non-empty")] {
+		hops.push(i);
+	}
+	hops.reverse();
+
+	let mut route = vec![from];
+	let mut point = from;
+	for pair in hops.windows(2) {
+		let crossing = edge_crossing(&monitors[pair[0]], &monitors[pair[1]], point, to);
+		if crossing != point {
+			route.push(crossing);
+			point = crossing;
+		}
+	}
+	if point != to || route.len() == 1 {
+		route.push(to);
+	}
+	route
+}
+
+/// Index of the monitor holding `(x, y)`, counting right and bottom edges as inside.
+fn monitor_index_at(monitors: &[MonitorPlacement], (x, y): (f64, f64)) -> Option<usize> {
+	monitors
+		.iter()
+		.position(|m| rect_contains(m, x, y))
+		.or_else(|| {
+			monitors.iter().position(|m| {
+				let (left, top, right, bottom) = rect_bounds(m);
+				x >= left && x <= right && y >= top && y <= bottom
+			})
+		})
+}
+
+/// Point on the edge shared by touching monitors `a` and `b` where a route from `point`
+/// in `a` towards `to` crosses, clamped to the shared part of the edge.
+fn edge_crossing(
+	a: &MonitorPlacement,
+	b: &MonitorPlacement,
+	point: (f64, f64),
+	to: (f64, f64),
+) -> (f64, f64) {
+	let (a_left, a_top, a_right, a_bottom) = rect_bounds(a);
+	let (b_left, b_top, b_right, b_bottom) = rect_bounds(b);
+	// Position along the line from `point` to `to` where `axis` reaches `edge`.
+	let along = |edge: f64, p: f64, q: f64, tp: f64, tq: f64| {
+		if tp != p {
+			q + (tq - q) * ((edge - p) / (tp - p)).clamp(0.0, 1.0)
+		} else {
+			tq
+		}
+	};
+	if a_right == b_left || b_right == a_left {
+		let edge = if a_right == b_left { a_right } else { a_left };
+		let y = along(edge, point.0, point.1, to.0, to.1);
+		(edge, y.clamp(a_top.max(b_top), a_bottom.min(b_bottom)))
+	} else {
+		let edge = if a_bottom == b_top { a_bottom } else { a_top };
+		let x = along(edge, point.1, point.0, to.1, to.0);
+		(x.clamp(a_left.max(b_left), a_right.min(b_right)), edge)
+	}
+}
+
+#[inline]
+fn rect_bounds(m: &MonitorPlacement) -> (f64, f64, f64, f64) {
+	(
+		m.x as f64,
+		m.y as f64,
+		(m.x + m.width.max(0)) as f64,
+		(m.y + m.height.max(0)) as f64,
+	)
+}
+
+/// Returns whether moving from `(x, y)` in direction `(dx, dy)` stays inside `m`
+/// for a non-zero distance.
+fn can_move_within(m: &MonitorPlacement, x: f64, y: f64, dx: f64, dy: f64) -> bool {
+	let (left, top, right, bottom) = rect_bounds(m);
+	let along = |p: f64, d: f64, lo: f64, hi: f64| {
+		if d > 0.0 {
+			p >= lo && p < hi
+		} else if d < 0.0 {
+			p > lo && p <= hi
+		} else {
+			p >= lo && p <= hi
+		}
+	};
+	along(x, dx, left, right) && along(y, dy, top, bottom)
+}
+
+/// Delta left from `p` to `end`; zero rather than reversing the direction of `d`.
+fn remaining(p: f64, end: f64, d: f64) -> f64 {
+	let r = end - p;
+	if r * d > 0.0 { r } else { 0.0 }
+}
+
+/// Fraction of `d` travelled from `p` before leaving `[lo, hi]`, and the edge reached.
+#[inline]
+fn axis_exit(p: f64, d: f64, lo: f64, hi: f64) -> (f64, f64) {
+	if d > 0.0 {
+		((hi - p) / d, hi)
+	} else if d < 0.0 {
+		((lo - p) / d, lo)
+	} else {
+		(f64::INFINITY, p)
+	}
+}
+
 fn monitors_touch(a: &MonitorPlacement, b: &MonitorPlacement) -> bool {
 	let ax1 = a.x;
 	let ay1 = a.y;
@@ -224,9 +807,14 @@ fn monitors_overlap_area(a: &MonitorPlacement, b: &MonitorPlacement) -> bool {
 
 #[cfg(test)]
 mod tests {
+	use proptest::collection::vec;
+	use proptest::prelude::*;
+
 	use super::{
-		MonitorPlacement, MonitorSpec, is_contiguous, is_valid_edge_contiguous_layout,
-		layout_horizontal, move_cursor_no_tunnel,
+		Choices, EdgeBehavior, EdgeState, LayoutChange, MonitorPlacement, MonitorSpec, SplitMix64,
+		WrapMode, clamp_point_to_layout, diff_layouts, is_contiguous, is_valid_edge_contiguous_layout,
+		layout_from_choices, layout_horizontal, monitor_at, move_cursor_no_tunnel,
+		move_cursor_with_edges, move_cursor_with_wrap, random_valid_layout, route_through_layout,
 	};
 
 	#[test]
@@ -380,4 +968,543 @@ mod tests {
 		assert!(x <= 200.0);
 		assert_eq!(y, 50.0);
 	}
+
+	fn placement(id: &str, x: i32, y: i32, width: i32, height: i32) -> MonitorPlacement {
+		MonitorPlacement {
+			id: id.into(),
+			x,
+			y,
+			width,
+			height,
+		}
+	}
+
+	#[test]
+	fn no_tunnel_slides_along_blocking_edge() {
+		// "b" is shorter, so the bottom-right corner of "a" is exposed.
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 50),
+		];
+		let (x, y) = move_cursor_no_tunnel(&layout, 50.0, 50.0, 100.0, 100.0);
+		assert_eq!((x, y), (100.0, 100.0));
+		let (x, y) = move_cursor_no_tunnel(&layout, 150.0, 25.0, 0.0, 100.0);
+		assert_eq!((x, y), (150.0, 50.0));
+		let (x, y) = move_cursor_no_tunnel(&layout, 150.0, 40.0, -100.0, 20.0);
+		assert_eq!((x, y), (50.0, 60.0));
+	}
+
+	#[test]
+	fn monitor_at_prefers_the_monitor_past_a_shared_edge() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 50),
+		];
+		let id = |x, y| monitor_at(&layout, x, y).map(|m| m.id.as_str());
+		assert_eq!(id(50.0, 50.0), Some("a"));
+		assert_eq!(id(100.0, 25.0), Some("b"));
+		assert_eq!(id(100.0, 75.0), Some("a"));
+		assert_eq!(id(200.0, 50.0), Some("b"));
+		assert_eq!(id(150.0, 75.0), None);
+	}
+
+	#[test]
+	fn no_tunnel_handles_large_deltas() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+			placement("c", 200, 0, 100, 100),
+		];
+		let (x, y) = move_cursor_no_tunnel(&layout, 10.0, 10.0, 1_000_000.0, 0.0);
+		assert_eq!((x, y), (300.0, 10.0));
+		let (x, y) = move_cursor_no_tunnel(&layout, 250.0, 10.0, -240.0, 50.0);
+		assert_eq!((x, y), (10.0, 60.0));
+	}
+
+	#[test]
+	fn edge_resistance_holds_until_pushed_through() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+		];
+		let behavior = EdgeBehavior::Resistance(15.0);
+		let mut state = EdgeState::default();
+		let (x, y) = move_cursor_with_edges(&layout, 90.0, 50.0, 20.0, 0.0, behavior, &mut state);
+		assert!(x < 100.0 && x > 99.0);
+		assert_eq!(y, 50.0);
+		let (x, _) = move_cursor_with_edges(&layout, x, y, 10.0, 0.0, behavior, &mut state);
+		assert!((x - 105.0).abs() < 1e-6);
+		// Moving back onto "a" meets the resistance again.
+		let (x, _) = move_cursor_with_edges(&layout, x, y, -10.0, 0.0, behavior, &mut state);
+		assert_eq!(x, 100.0);
+	}
+
+	#[test]
+	fn edge_resistance_resets_when_not_pushing() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+		];
+		let behavior = EdgeBehavior::Resistance(15.0);
+		let mut state = EdgeState::default();
+		let (x, y) = move_cursor_with_edges(&layout, 90.0, 50.0, 20.0, 0.0, behavior, &mut state);
+		let (x, y) = move_cursor_with_edges(&layout, x, y, 0.0, 10.0, behavior, &mut state);
+		assert_eq!(state, EdgeState::default());
+		let (x, y) = move_cursor_with_edges(&layout, x, y, 10.0, 0.0, behavior, &mut state);
+		assert!(x < 100.0);
+		assert_eq!(y, 60.0);
+	}
+
+	#[test]
+	fn sticky_corners_block_crossings_near_corners() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 50),
+		];
+		let behavior = EdgeBehavior::StickyCorners(10.0);
+		let mut state = EdgeState::default();
+		let (x, y) = move_cursor_with_edges(&layout, 90.0, 5.0, 20.0, 0.0, behavior, &mut state);
+		assert!(x < 100.0 && x > 99.0);
+		assert_eq!(y, 5.0);
+		let (x, y) = move_cursor_with_edges(&layout, 90.0, 25.0, 20.0, 0.0, behavior, &mut state);
+		assert_eq!((x, y), (110.0, 25.0));
+		// Leaving "b" towards "a" is blocked near the corners of "b".
+		let (x, _) = move_cursor_with_edges(&layout, 110.0, 45.0, -20.0, 0.0, behavior, &mut state);
+		assert_eq!(x, 100.0);
+	}
+
+	#[test]
+	fn free_edges_match_no_tunnel() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 50),
+		];
+		let mut state = EdgeState::default();
+		for (x, y, dx, dy) in [(50.0, 50.0, 100.0, 100.0), (150.0, 40.0, -100.0, 20.0)] {
+			assert_eq!(
+				move_cursor_with_edges(&layout, x, y, dx, dy, EdgeBehavior::Free, &mut state),
+				move_cursor_no_tunnel(&layout, x, y, dx, dy)
+			);
+		}
+	}
+
+	#[test]
+	fn horizontal_wrap_reenters_at_the_first_monitor() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+		];
+		let mut state = EdgeState::default();
+		let mut wrap = |x, y, dx, dy, wrap| {
+			move_cursor_with_wrap(&layout, x, y, dx, dy, EdgeBehavior::Free, wrap, &mut state)
+		};
+		let (horizontal, vertical) = (WrapMode::Horizontal, WrapMode::Vertical);
+		assert_eq!(wrap(175.0, 50.0, 50.0, 0.0, horizontal), (25.0, 50.0));
+		assert_eq!(wrap(25.0, 50.0, -50.0, 0.0, horizontal), (175.0, 50.0));
+		// Diagonal motion keeps its vertical part across the wrap.
+		assert_eq!(wrap(175.0, 50.0, 50.0, 20.0, WrapMode::Both), (25.0, 70.0));
+		// Vertical wrapping leaves the horizontal edges closed.
+		assert_eq!(wrap(175.0, 50.0, 50.0, 0.0, vertical), (200.0, 50.0));
+	}
+
+	#[test]
+	fn vertical_wrap_follows_the_column() {
+		// "b" sits below the right half of "a"; the column at x = 150 spans both.
+		let layout = vec![
+			placement("a", 0, 0, 200, 100),
+			placement("b", 100, 100, 100, 100),
+		];
+		let mut state = EdgeState::default();
+		let (free, vertical) = (EdgeBehavior::Free, WrapMode::Vertical);
+		let mut wrap =
+			|x, y, dx, dy| move_cursor_with_wrap(&layout, x, y, dx, dy, free, vertical, &mut state);
+		assert_eq!(wrap(150.0, 190.0, 0.0, 20.0), (150.0, 10.0));
+		assert_eq!(wrap(150.0, 10.0, 0.0, -20.0), (150.0, 190.0));
+		// Only "a" covers x = 50, so the cursor wraps within it.
+		assert_eq!(wrap(50.0, 90.0, 0.0, 20.0), (50.0, 10.0));
+	}
+
+	#[test]
+	fn no_wrap_matches_edges() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 50),
+		];
+		let mut state = EdgeState::default();
+		for (x, y, dx, dy) in [(50.0, 50.0, 100.0, 100.0), (150.0, 40.0, -100.0, 20.0)] {
+			let wrapped = move_cursor_with_wrap(
+				&layout,
+				x,
+				y,
+				dx,
+				dy,
+				EdgeBehavior::Free,
+				WrapMode::None,
+				&mut state,
+			);
+			assert_eq!(wrapped, move_cursor_no_tunnel(&layout, x, y, dx, dy));
+		}
+	}
+
+	#[test]
+	fn crossings_keep_sub_pixel_precision() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+		];
+		// Fractional motions across the shared edge end exactly where plain addition does.
+		for (x, y, dx, dy) in [
+			(99.9, 50.0, 0.25, 0.0),
+			(99.3, 50.0, 0.7000001, 0.0),
+			(100.15, 50.0, -0.3, 0.0),
+			(99.75, 33.3, 0.5, 0.1),
+			(99.99, 12.34, 0.013, -0.007),
+		] {
+			assert_eq!(
+				move_cursor_no_tunnel(&layout, x, y, dx, dy),
+				(x + dx, y + dy)
+			);
+		}
+	}
+
+	/// Asserts that every segment of `route` lies inside one monitor of `layout`.
+	fn assert_route_inside(layout: &[MonitorPlacement], route: &[(f64, f64)]) {
+		for segment in route.windows(2) {
+			let inside = layout.iter().any(|m| {
+				let contains = |(x, y): (f64, f64)| {
+					x >= m.x as f64
+						&& x <= (m.x + m.width) as f64
+						&& y >= m.y as f64
+						&& y <= (m.y + m.height) as f64
+				};
+				contains(segment[0]) && contains(segment[1])
+			});
+			assert!(inside, "segment {segment:?} leaves the layout");
+		}
+	}
+
+	#[test]
+	fn route_within_one_monitor_is_straight() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+		];
+		let route = route_through_layout(&layout, (10.0, 10.0), (90.0, 80.0));
+		assert_eq!(route, vec![(10.0, 10.0), (90.0, 80.0)]);
+	}
+
+	#[test]
+	fn route_passes_through_shared_edges() {
+		// U-shaped layout: the straight line from "a" to "e" would cut across the gap.
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 0, 100, 100, 100),
+			placement("c", 100, 100, 100, 100),
+			placement("d", 200, 100, 100, 100),
+			placement("e", 200, 0, 100, 100),
+		];
+		let route = route_through_layout(&layout, (50.0, 50.0), (250.0, 50.0));
+		assert_eq!(
+			route,
+			vec![(50.0, 50.0), (100.0, 100.0), (200.0, 100.0), (250.0, 50.0)]
+		);
+		assert_route_inside(&layout, &route);
+	}
+
+	#[test]
+	fn route_clamps_endpoints_and_rejects_islands() {
+		let layout = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 50),
+		];
+		let route = route_through_layout(&layout, (-20.0, 50.0), (250.0, 90.0));
+		assert_eq!(route.first(), Some(&(0.0, 50.0)));
+		assert_eq!(route.last(), Some(&(200.0, 50.0)));
+		assert_route_inside(&layout, &route);
+
+		let island = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 300, 0, 100, 100),
+		];
+		assert!(route_through_layout(&island, (10.0, 10.0), (310.0, 10.0)).is_empty());
+	}
+
+	#[test]
+	fn diff_reports_added_removed_moved_and_resized() {
+		let old = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+			placement("c", 200, 0, 100, 100),
+		];
+		let new = vec![
+			placement("d", 0, 0, 50, 50),
+			placement("c", 150, 0, 100, 80),
+			placement("a", 50, 0, 100, 100),
+		];
+		assert_eq!(
+			diff_layouts(&old, &new),
+			vec![
+				LayoutChange::Moved {
+					id: "a".into(),
+					from: (0, 0),
+					to: (50, 0),
+				},
+				LayoutChange::Removed(placement("b", 100, 0, 100, 100)),
+				LayoutChange::Moved {
+					id: "c".into(),
+					from: (200, 0),
+					to: (150, 0),
+				},
+				LayoutChange::Resized {
+					id: "c".into(),
+					from: (100, 100),
+					to: (100, 80),
+				},
+				LayoutChange::Added(placement("d", 0, 0, 50, 50)),
+			]
+		);
+	}
+
+	#[test]
+	fn diff_ignores_monitor_order() {
+		let old = vec![
+			placement("a", 0, 0, 100, 100),
+			placement("b", 100, 0, 100, 100),
+		];
+		let new = vec![
+			placement("b", 100, 0, 100, 100),
+			placement("a", 0, 0, 100, 100),
+		];
+		assert!(diff_layouts(&old, &new).is_empty());
+	}
+
+	/// Seeded layouts of one to six monitors, with a generator for points and deltas.
+	fn sample_layouts() -> impl Iterator<Item = (Vec<MonitorPlacement>, SplitMix64)> {
+		(0..300u64).map(|seed| {
+			let layout = random_valid_layout(seed, 1 + seed as usize % 6);
+			(layout, SplitMix64(!seed))
+		})
+	}
+
+	/// Replays choices drawn by proptest, taking the low end once they run out, so layouts
+	/// shrink towards monitors of minimal size attached to the right of the first one.
+	struct Replay(std::vec::IntoIter<u32>);
+
+	impl Choices for Replay {
+		fn range(&mut self, lo: i32, hi: i32) -> i32 {
+			let span = (hi as i64 - lo as i64 + 1) as u64;
+			let choice = self.0.next().unwrap_or(0) as u64;
+			(lo as i64 + (choice % span) as i64) as i32
+		}
+	}
+
+	/// Valid layouts of one to six monitors.
+	fn layouts() -> impl Strategy<Value = Vec<MonitorPlacement>> {
+		(1..=6usize, vec(any::<u32>(), 0..128))
+			.prop_map(|(n, choices)| layout_from_choices(&mut Replay(choices.into_iter()), n))
+	}
+
+	/// A layout and a point inside its bounding box grown by `margin` on every side.
+	fn layout_and_point(margin: i32) -> impl Strategy<Value = (Vec<MonitorPlacement>, (f64, f64))> {
+		layouts().prop_flat_map(move |layout| {
+			let left = layout.iter().map(|m| m.x).min().unwrap() - margin;
+			let top = layout.iter().map(|m| m.y).min().unwrap() - margin;
+			let right = layout.iter().map(|m| m.x + m.width).max().unwrap() + margin;
+			let bottom = layout.iter().map(|m| m.y + m.height).max().unwrap() + margin;
+			(
+				Just(layout),
+				(coordinate(left, right), coordinate(top, bottom)),
+			)
+		})
+	}
+
+	/// Coordinate in `lo..=hi`; whole pixels half of the time, so points also land exactly
+	/// on edges and corners.
+	fn coordinate(lo: i32, hi: i32) -> impl Strategy<Value = f64> {
+		prop_oneof![(lo..=hi).prop_map(f64::from), lo as f64..hi as f64]
+	}
+
+	/// Point inside the bounding box of `layout` grown by `margin` on every side.
+	fn random_point(layout: &[MonitorPlacement], rng: &mut SplitMix64, margin: i32) -> (f64, f64) {
+		let left = layout.iter().map(|m| m.x).min().unwrap() - margin;
+		let top = layout.iter().map(|m| m.y).min().unwrap() - margin;
+		let right = layout.iter().map(|m| m.x + m.width).max().unwrap() + margin;
+		let bottom = layout.iter().map(|m| m.y + m.height).max().unwrap() + margin;
+		// Whole pixels half of the time, so points also land exactly on edges and corners.
+		let whole = rng.range(0, 1) == 0;
+		let mut coordinate = |lo: i32, hi: i32| {
+			let fraction = rng.range(0, 1 << 16) as f64 / (1 << 16) as f64;
+			let p = lo as f64 + (hi - lo) as f64 * fraction;
+			if whole { p.round() } else { p }
+		};
+		(coordinate(left, right), coordinate(top, bottom))
+	}
+
+	/// Whether `(x, y)` lies on one monitor of `layout`, edges included.
+	fn on_layout(layout: &[MonitorPlacement], (x, y): (f64, f64)) -> bool {
+		layout.iter().any(|m| {
+			x >= m.x as f64
+				&& x <= (m.x + m.width) as f64
+				&& y >= m.y as f64
+				&& y <= (m.y + m.height) as f64
+		})
+	}
+
+	#[test]
+	fn random_layouts_are_valid_and_reproducible() {
+		for seed in 0..300 {
+			for n in [0, 1, 2, 5, 12] {
+				let layout = random_valid_layout(seed, n);
+				assert_eq!(layout.len(), n);
+				assert!(
+					is_valid_edge_contiguous_layout(&layout),
+					"seed {seed}: {layout:?}"
+				);
+				assert_eq!(layout, random_valid_layout(seed, n));
+			}
+		}
+		assert_ne!(random_valid_layout(1, 4), random_valid_layout(2, 4));
+	}
+
+	#[test]
+	fn random_layouts_break_when_a_monitor_is_isolated() {
+		for (mut layout, _) in sample_layouts().filter(|(layout, _)| layout.len() > 1) {
+			let far = layout.iter().map(|m| m.x + m.width).max().unwrap() + 1;
+			layout[0].x = far;
+			assert!(!is_valid_edge_contiguous_layout(&layout), "{layout:?}");
+		}
+	}
+
+	proptest! {
+		#[test]
+		fn generated_layouts_are_valid(layout in layouts()) {
+			prop_assert!(is_valid_edge_contiguous_layout(&layout), "{layout:?}");
+		}
+
+		#[test]
+		fn clamp_lands_on_layout_and_is_idempotent((layout, point) in layout_and_point(500)) {
+			let clamped = clamp_point_to_layout(&layout, point.0, point.1);
+			prop_assert!(
+				on_layout(&layout, clamped),
+				"{point:?} -> {clamped:?} in {layout:?}"
+			);
+			prop_assert_eq!(
+				clamp_point_to_layout(&layout, clamped.0, clamped.1),
+				clamped
+			);
+		}
+
+		#[test]
+		fn no_tunnel_stays_on_layout_and_within_delta(
+			(layout, start) in layout_and_point(0),
+			dx in -3000..=3000,
+			dy in -3000..=3000,
+		) {
+			let start = clamp_point_to_layout(&layout, start.0, start.1);
+			let (dx, dy) = (f64::from(dx), f64::from(dy));
+			let (x, y) = move_cursor_no_tunnel(&layout, start.0, start.1, dx, dy);
+			prop_assert!(
+				on_layout(&layout, (x, y)),
+				"{start:?} + {dx},{dy} -> {x},{y}"
+			);
+			// Each axis only spends its own delta, give or take the nudge off a right or
+			// bottom edge.
+			prop_assert!((x - start.0).abs() <= dx.abs() + 1e-6);
+			prop_assert!((y - start.1).abs() <= dy.abs() + 1e-6);
+			prop_assert!((x - start.0) * dx >= -1e-6 && (y - start.1) * dy >= -1e-6);
+		}
+
+		#[test]
+		fn no_tunnel_never_crosses_gaps((layout, start) in layout_and_point(0), dx in -3000..=3000) {
+			let (sx, y) = clamp_point_to_layout(&layout, start.0, start.1);
+			let dx = f64::from(dx);
+			let (x, end_y) = move_cursor_no_tunnel(&layout, sx, y, dx, 0.0);
+			prop_assert_eq!(end_y, y);
+			// The monitors spanning row `y`, edges included, must cover the travelled span.
+			let mut spans: Vec<_> = layout
+				.iter()
+				.filter(|m| y >= m.y as f64 && y <= (m.y + m.height) as f64)
+				.map(|m| (m.x as f64, (m.x + m.width) as f64))
+				.collect();
+			spans.sort_by(|a, b| a.0.total_cmp(&b.0));
+			let (lo, hi) = (sx.min(x), sx.max(x));
+			let mut covered = lo;
+			for (left, right) in spans {
+				if left <= covered {
+					covered = covered.max(right);
+				}
+			}
+			prop_assert!(
+				covered >= hi,
+				"{sx},{y} + {dx} -> {x} crosses a gap in {layout:?}"
+			);
+		}
+	}
+
+	#[test]
+	fn edge_behaviors_stay_on_layout() {
+		let behaviors = [
+			EdgeBehavior::Resistance(40.0),
+			EdgeBehavior::StickyCorners(25.0),
+		];
+		for (layout, mut rng) in sample_layouts() {
+			for behavior in behaviors {
+				let mut state = EdgeState::default();
+				let start = random_point(&layout, &mut rng, 0);
+				let mut position = clamp_point_to_layout(&layout, start.0, start.1);
+				for _ in 0..50 {
+					let dx = rng.range(-200, 200) as f64;
+					let dy = rng.range(-200, 200) as f64;
+					position = move_cursor_with_edges(
+						&layout, position.0, position.1, dx, dy, behavior, &mut state,
+					);
+					assert!(on_layout(&layout, position), "{position:?} in {layout:?}");
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn wrapping_stays_on_layout() {
+		for (layout, mut rng) in sample_layouts() {
+			let mut state = EdgeState::default();
+			let start = random_point(&layout, &mut rng, 0);
+			let mut position = clamp_point_to_layout(&layout, start.0, start.1);
+			for _ in 0..50 {
+				let dx = rng.range(-400, 400) as f64;
+				let dy = rng.range(-400, 400) as f64;
+				position = move_cursor_with_wrap(
+					&layout,
+					position.0,
+					position.1,
+					dx,
+					dy,
+					EdgeBehavior::Free,
+					WrapMode::Both,
+					&mut state,
+				);
+				assert!(on_layout(&layout, position), "{position:?} in {layout:?}");
+			}
+		}
+	}
+
+	#[test]
+	fn routes_connect_any_two_points_of_valid_layouts() {
+		for (layout, mut rng) in sample_layouts() {
+			for _ in 0..20 {
+				let from = random_point(&layout, &mut rng, 200);
+				let to = random_point(&layout, &mut rng, 200);
+				let route = route_through_layout(&layout, from, to);
+				assert_eq!(
+					route.first(),
+					Some(&clamp_point_to_layout(&layout, from.0, from.1))
+				);
+				assert_eq!(
+					route.last(),
+					Some(&clamp_point_to_layout(&layout, to.0, to.1))
+				);
+				assert_route_inside(&layout, &route);
+			}
+		}
+	}
 }
@@ -0,0 +1,6 @@
+//! `libtab_app_framework_python.so`, the `tab_app_framework` Python module.
+//!
+//! The module lives behind the core's `python` feature; this crate only links it into an
+//! extension module. Copy the library to `tab_app_framework.so` on the Python path.
+
+extern crate tab_app_framework_core;
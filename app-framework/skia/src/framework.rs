@@ -0,0 +1,1655 @@
+use std::collections::HashMap;
+use std::ffi::c_void;
+use std::os::fd::RawFd;
+use std::ptr;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use skia_safe::{self as skia, gpu};
+use tab_app_framework_core::{self as core, DrivenFramework};
+use tab_app_framework_gl::{
+	FrameCaptureEvent, GlApplication, GlContext, GlEventContext, GlInitContext, GlResetStatus,
+	GlTabAppFramework,
+};
+use tracing::error;
+
+use crate::MonitorSurfaces;
+
+/// Skia-specialized application trait.
+///
+/// This mirrors [`tab_app_framework_gl::GlApplication`] but hands `on_render`
+/// a [`skia::Canvas`] wrapping the monitor's current render target.
+pub trait SkiaApplication: Sized + 'static {
+	/// Constructs the application with access to GL and Skia initialization state.
+	fn init(ctx: &mut SkiaInitContext<'_>) -> anyhow::Result<Self>;
+
+	/// Called before the iteration's `on_render` calls with the monitors due for a frame,
+	/// sorted by id; see [`core::Application::on_begin_frame`].
+	fn on_begin_frame(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_monitor_ids: &[String],
+	) {
+	}
+	/// Called after the iteration's last `on_render` call.
+	fn on_end_frame(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>) {}
+	/// Called with a canvas targeting the acquired buffer.
+	///
+	/// The canvas is flushed and submitted after this returns.
+	fn on_render(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_canvas: &skia::Canvas,
+		_ev: core::RenderEvent<'_>,
+	) {
+	}
+	/// Called when a rendered buffer is presented/released.
+	fn on_present(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::PresentEvent) {
+	}
+	/// Called with the pixels of a frame requested via [`SkiaEventContext::capture_frame`].
+	fn on_frame_captured(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: FrameCaptureEvent,
+	) {
+	}
+	/// Called when a monitor is added.
+	fn on_monitor_added(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::MonitorAddedEvent,
+	) {
+	}
+	/// Called when a monitor is removed.
+	fn on_monitor_removed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::MonitorRemovedEvent,
+	) {
+	}
+	/// Called when compositor chrome starts or stops covering parts of a monitor.
+	fn on_safe_area_changed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SafeAreaChangedEvent,
+	) {
+	}
+	/// Called when a monitor's display is switched on, to standby or off.
+	fn on_monitor_power_changed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::MonitorPowerChangedEvent,
+	) {
+	}
+	/// Called when the server hands a monitor's buffer back for rendering.
+	fn on_buffer_available(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::BufferAvailableEvent,
+	) {
+	}
+	/// Called when the server held every buffer of a monitor for the starvation threshold.
+	fn on_starved(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::StarvedEvent) {
+	}
+	/// Called after a monitor's swapchain was recreated; its surfaces are already dropped.
+	fn on_swapchain_recreated(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SwapchainRecreatedEvent,
+	) {
+	}
+	/// Called when monitors were added, removed, moved or resized in the global layout.
+	fn on_layout_changed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::LayoutChangedEvent,
+	) {
+	}
+	/// Called when reduced motion, text scale or high contrast preferences change.
+	fn on_accessibility_changed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::AccessibilityChangedEvent,
+	) {
+	}
+	/// Called when session state updates arrive.
+	fn on_session_state(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SessionEvent,
+	) {
+	}
+	/// Called when this session becomes the active one.
+	fn on_focus_gained(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::FocusEvent,
+	) {
+	}
+	/// Called when another session becomes active.
+	fn on_focus_lost(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::FocusEvent,
+	) {
+	}
+	/// Called with a snapshot requested through [`SkiaEventContext::request_session_preview`].
+	fn on_session_preview(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SessionPreviewEvent,
+	) {
+	}
+	/// Called when a capture from [`SkiaEventContext::start_capture`] has a new frame.
+	///
+	/// The default implementation hands the buffer straight back.
+	fn on_capture_frame(
+		&mut self,
+		ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		ev: core::CaptureFrameEvent,
+	) {
+		let _ = ctx.release_capture_frame(&ev);
+	}
+	/// Called when shift stopped a capture on its own.
+	fn on_capture_ended(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::CaptureEndedEvent,
+	) {
+	}
+	/// Called when another session sends this session a message.
+	fn on_session_message(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SessionMessageEvent,
+	) {
+	}
+	/// Called in admin sessions when another session reported a crash.
+	fn on_session_crashed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SessionCrashedEvent,
+	) {
+	}
+	/// Called with the answer to a `create_session_async` request.
+	fn on_session_create_result(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SessionCreateResultEvent,
+	) {
+	}
+	/// Called with the outcome of a `switch_session_async` request.
+	fn on_session_switch_result(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SessionSwitchResultEvent,
+	) {
+	}
+	/// Called once the framework sent `session_ready` for `Config::auto_ready`.
+	fn on_session_ready_sent(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::SessionReadySentEvent,
+	) {
+	}
+	/// Called when the server stopped answering keepalive pings.
+	fn on_server_unresponsive(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::ServerUnresponsiveEvent,
+	) {
+	}
+	/// Called when the server answers keepalive pings again.
+	fn on_server_recovered(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::ServerRecoveredEvent,
+	) {
+	}
+	/// Called once changes made through [`SkiaEventContext::update_config`] took effect.
+	fn on_config_changed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::ConfigChangedEvent,
+	) {
+	}
+	/// Called for every raw input payload.
+	fn on_input(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: &core::InputEvent) {}
+	/// Called when a shortcut from [`SkiaEventContext::register_global_shortcut`] is pressed.
+	fn on_global_shortcut(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::GlobalShortcutEvent,
+	) {
+	}
+	/// Called for key events.
+	fn on_key(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::KeyEvent) {}
+	/// Called for composed text events.
+	fn on_char(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::CharEvent) {}
+	/// Called when any pointer device moves the cursor.
+	fn on_pointer_move(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::PointerMoveEvent,
+	) {
+	}
+	/// Called when a mouse-like device moves the cursor.
+	fn on_mouse_move(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::MouseMoveEvent,
+	) {
+	}
+	/// Called when any pointer device produces a down transition.
+	fn on_pointer_down(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::PointerDownEvent,
+	) {
+	}
+	/// Called when any pointer device produces an up transition.
+	fn on_pointer_up(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::PointerUpEvent,
+	) {
+	}
+	/// Called when a mouse-like device produces a down transition.
+	fn on_mouse_down(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::MouseDownEvent,
+	) {
+	}
+	/// Called when a mouse-like device produces an up transition.
+	fn on_mouse_up(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::MouseUpEvent,
+	) {
+	}
+	/// Called for touch events.
+	fn on_touch(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::TouchEvent) {}
+	/// Called for gesture events.
+	fn on_gesture(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::GestureEvent) {
+	}
+	/// Called when a watched fd becomes readable.
+	fn on_fd_ready(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::FdReadyEvent,
+	) {
+	}
+	/// Called once per stats interval with the loop counters it accumulated.
+	fn on_stats(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_stats: core::LoopStatsSnapshot,
+	) {
+	}
+	/// Called once per loop iteration with all events when batching is enabled.
+	fn on_events(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _events: &[core::Event]) {}
+	/// Called with server messages decoded by a registered protocol extension.
+	fn on_extension_event(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::ExtensionEvent,
+	) {
+	}
+	/// Called with values sent through [`core::FrameworkController::send_user_event`].
+	fn on_user_event(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::UserEvent) {
+	}
+	/// Called when an input device is plugged in.
+	fn on_input_device_added(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::InputDeviceAddedEvent,
+	) {
+	}
+	/// Called when an input device is unplugged.
+	fn on_input_device_removed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::InputDeviceRemovedEvent,
+	) {
+	}
+	/// Called when the idle timeout requested through the context elapses without input.
+	fn on_idle(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>, _ev: core::IdleEvent) {}
+	/// Called on the first input event after `on_idle`.
+	fn on_resume_activity(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::ResumeActivityEvent,
+	) {
+	}
+	/// Called when a monitor's mode or properties change without reconnecting.
+	fn on_monitor_changed(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::MonitorChangedEvent,
+	) {
+	}
+	/// Called with the monitors due for a frame in explicit submit mode.
+	fn on_frame_requested(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_ev: core::FrameRequestEvent,
+	) {
+	}
+	/// Called when a GPU reset destroyed the GL context, before it is recreated.
+	///
+	/// The Skia context is abandoned: drop GPU-backed images and surfaces without using
+	/// them until [`Self::on_gl_context_restored`].
+	fn on_gl_context_lost(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		_status: GlResetStatus,
+	) {
+	}
+	/// Called with a new Skia context once the GL context was recreated.
+	fn on_gl_context_restored(&mut self, _ctx: &mut SkiaEventContext<'_, '_, '_, Self>) {}
+	/// Called when framework errors are reported.
+	fn on_error(
+		&mut self,
+		_ctx: &mut SkiaEventContext<'_, '_, '_, Self>,
+		error: &core::FrameworkError,
+	) {
+		error!(target: "tab_app_framework.skia", error = %error, "tab-app-framework-skia error");
+	}
+}
+
+/// Initialization context used by [`SkiaApplication::init`].
+pub struct SkiaInitContext<'a> {
+	gl: &'a mut GlInitContext,
+	gr: &'a mut gpu::DirectContext,
+}
+
+impl SkiaInitContext<'_> {
+	/// Returns immutable access to the GL context.
+	pub fn gl(&self) -> &GlContext {
+		self.gl.gl()
+	}
+
+	/// Returns the Skia GPU context.
+	pub fn direct_context(&mut self) -> &mut gpu::DirectContext {
+		self.gr
+	}
+}
+
+/// Callback context for Skia applications.
+pub struct SkiaEventContext<'e, 'c, 'g, A: SkiaApplication> {
+	gl: &'e mut GlEventContext<'c, 'g, SkiaBridge<A>>,
+	gr: &'e mut gpu::DirectContext,
+}
+
+impl<A: SkiaApplication> SkiaEventContext<'_, '_, '_, A> {
+	/// Schedules a frame for a specific monitor.
+	pub fn schedule_frame(&mut self, monitor_id: impl Into<String>) {
+		self.gl.schedule_frame(monitor_id);
+	}
+
+	/// Schedules a frame for every monitor.
+	pub fn schedule_all_frames(&mut self) {
+		self.gl.schedule_all_frames();
+	}
+
+	/// Schedules a frame for a monitor once `delay` has passed; earlier requests win.
+	pub fn request_redraw_after(&mut self, delay: Duration, monitor_id: &str) {
+		self.gl.request_redraw_after(delay, monitor_id);
+	}
+
+	/// Stops rendering to a monitor until `resume_monitor`; see `Context::pause_monitor`.
+	pub fn pause_monitor(&mut self, monitor_id: &str) -> bool {
+		self.gl.pause_monitor(monitor_id)
+	}
+
+	/// Resumes rendering to a paused monitor and schedules a frame for it.
+	pub fn resume_monitor(&mut self, monitor_id: &str) -> bool {
+		self.gl.resume_monitor(monitor_id)
+	}
+
+	/// Returns true while a monitor is paused.
+	pub fn is_monitor_paused(&self, monitor_id: &str) -> bool {
+		self.gl.is_monitor_paused(monitor_id)
+	}
+
+	/// Adds a file descriptor to the readable watch set.
+	pub fn watch_fd(&mut self, fd: RawFd) {
+		self.gl.watch_fd(fd);
+	}
+
+	/// Removes a file descriptor from the watch set.
+	pub fn unwatch_fd(&mut self, fd: RawFd) {
+		self.gl.unwatch_fd(fd);
+	}
+
+	/// Requests framework shutdown.
+	pub fn request_exit(&mut self) {
+		self.gl.request_exit();
+	}
+
+	/// Returns current session information.
+	pub fn session(&self) -> &core::SessionInfo {
+		self.gl.session()
+	}
+
+	/// Returns the round trip of the last answered keepalive ping.
+	pub fn server_latency(&self) -> Option<Duration> {
+		self.gl.server_latency()
+	}
+
+	/// Returns true while this session is the active one.
+	pub fn has_focus(&self) -> bool {
+		self.gl.has_focus()
+	}
+
+	/// Admin-only: routes key events to this session while another stays active.
+	pub fn request_keyboard_grab(&mut self) -> Result<(), core::FrameworkError> {
+		self.gl.request_keyboard_grab()
+	}
+
+	/// Ends a grab started with [`Self::request_keyboard_grab`].
+	pub fn release_keyboard_grab(&mut self) -> Result<(), core::FrameworkError> {
+		self.gl.release_keyboard_grab()
+	}
+
+	/// Returns true while this session holds the keyboard grab.
+	pub fn has_keyboard_grab(&self) -> bool {
+		self.gl.has_keyboard_grab()
+	}
+
+	/// Returns all monitors, ordered by layout position, then id.
+	pub fn monitors(&self) -> impl Iterator<Item = &core::Monitor> {
+		self.gl.monitors()
+	}
+
+	/// Returns monitor metadata by id.
+	pub fn monitor(&self, monitor_id: &str) -> Option<&core::Monitor> {
+		self.gl.monitor(monitor_id)
+	}
+
+	/// Returns the monitor under a global layout position.
+	pub fn monitor_at(&self, global_position: (f64, f64)) -> Option<&core::Monitor> {
+		self.gl.monitor_at(global_position)
+	}
+
+	/// Returns how a monitor's buffers are prepared before `on_render`.
+	pub fn clear_policy(&self, monitor_id: &str) -> Option<core::ClearPolicy> {
+		self.gl.clear_policy(monitor_id)
+	}
+
+	/// Clears a monitor's buffers to a color before each `on_render`, or stops doing so.
+	pub fn set_clear_policy(
+		&mut self,
+		monitor_id: &str,
+		policy: core::ClearPolicy,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_clear_policy(monitor_id, policy)
+	}
+
+	/// Returns how eager rendering retries a monitor's failed frames.
+	pub fn retry_policy(&self, monitor_id: &str) -> Option<core::RetryPolicy> {
+		self.gl.retry_policy(monitor_id)
+	}
+
+	/// Changes how eager rendering retries a monitor's failed frames.
+	pub fn set_retry_policy(
+		&mut self,
+		monitor_id: &str,
+		policy: core::RetryPolicy,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_retry_policy(monitor_id, policy)
+	}
+
+	/// Returns the primary monitor.
+	pub fn primary_monitor(&self) -> Option<&core::Monitor> {
+		self.gl.primary_monitor()
+	}
+
+	/// Makes a monitor the primary one; admin sessions only.
+	pub fn set_primary_monitor(&mut self, monitor_id: &str) -> Result<(), core::FrameworkError> {
+		self.gl.set_primary_monitor(monitor_id)
+	}
+
+	/// Sets monitor position in the global monitor layout.
+	pub fn set_monitor_position(
+		&mut self,
+		monitor_id: &str,
+		x: i32,
+		y: i32,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_monitor_position(monitor_id, x, y)
+	}
+
+	/// Replaces a monitor's swapchain, see [`core::Context::recreate_swapchain`].
+	pub fn recreate_swapchain(&mut self, monitor_id: &str) -> Result<(), core::FrameworkError> {
+		self.gl.recreate_swapchain(monitor_id)
+	}
+
+	/// Applies default horizontal monitor layout.
+	pub fn apply_horizontal_layout(&mut self) {
+		self.gl.apply_horizontal_layout();
+	}
+
+	/// Returns current cursor position in global layout space.
+	pub fn cursor_position(&self) -> (f64, f64) {
+		self.gl.cursor_position()
+	}
+
+	/// Returns phase timings of the last completed loop iteration.
+	pub fn frame_timings(&self) -> &core::FrameTimings {
+		self.gl.frame_timings()
+	}
+
+	/// Returns loop counters for the last completed reporting interval.
+	pub fn loop_stats(&self) -> &core::LoopStatsSnapshot {
+		self.gl.loop_stats()
+	}
+
+	/// Returns who holds a monitor's swapchain buffers.
+	pub fn buffer_ownership(&self, monitor_id: &str) -> Option<core::BufferOwnership> {
+		self.gl.buffer_ownership(monitor_id)
+	}
+
+	/// Returns the seat that produced the most recent input event.
+	pub fn seat(&self) -> &core::SeatId {
+		self.gl.seat()
+	}
+
+	/// Returns the cursor position of a seat that has produced input.
+	pub fn seat_cursor_position(&self, seat: &core::SeatId) -> Option<(f64, f64)> {
+		self.gl.seat_cursor_position(seat)
+	}
+
+	/// Appends a filter to the raw input chain; see [`core::Context::add_input_filter`].
+	pub fn add_input_filter(&mut self, filter: impl core::InputFilter + 'static) {
+		self.gl.add_input_filter(filter)
+	}
+
+	/// Queues input from the current seat; see [`core::Context::inject_input`].
+	pub fn inject_input(&mut self, event: core::InputEventPayload) {
+		self.gl.inject_input(event)
+	}
+
+	/// Delivers input to another session; admin sessions only.
+	pub fn inject_session_input(
+		&mut self,
+		session_id: &str,
+		seat: core::SeatId,
+		event: core::InputEventPayload,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.inject_session_input(session_id, seat, event)
+	}
+
+	/// Returns all currently plugged input devices.
+	pub fn input_devices(&self) -> impl Iterator<Item = &core::InputDeviceInfo> {
+		self.gl.input_devices()
+	}
+
+	/// Returns metadata for the device id carried by input events.
+	pub fn input_device(&self, device: u32) -> Option<&core::InputDeviceInfo> {
+		self.gl.input_device(device)
+	}
+
+	/// Returns the monitor a device is mapped to, such as a touchscreen's panel.
+	pub fn input_device_monitor(&self, device: u32) -> Option<&core::Monitor> {
+		self.gl.input_device_monitor(device)
+	}
+
+	/// Requests `on_idle` once no input arrives for `timeout`.
+	pub fn request_idle_notification(&mut self, timeout: Duration) {
+		self.gl.request_idle_notification(timeout)
+	}
+
+	/// Stops idle tracking started by `request_idle_notification`.
+	pub fn cancel_idle_notification(&mut self) {
+		self.gl.cancel_idle_notification()
+	}
+
+	/// Returns true between `on_idle` and the next input event.
+	pub fn is_idle(&self) -> bool {
+		self.gl.is_idle()
+	}
+
+	/// Asks the server to keep this session awake until the returned guard is dropped.
+	pub fn inhibit_idle(&mut self, reason: impl Into<String>) -> core::IdleInhibitor {
+		self.gl.inhibit_idle(reason)
+	}
+
+	/// Decodes server messages whose header starts with `header_prefix`; see
+	/// [`core::Context::register_extension`].
+	pub fn register_extension<T, F>(&mut self, header_prefix: impl Into<String>, decoder: F)
+	where
+		T: std::any::Any,
+		F: Fn(&core::TabMessageFrame) -> Result<T, core::ProtocolError> + 'static,
+	{
+		self.gl.register_extension(header_prefix, decoder)
+	}
+
+	/// Scales a monitor's output brightness; admin sessions only.
+	pub fn set_monitor_brightness(
+		&mut self,
+		monitor_id: &str,
+		brightness: f32,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_monitor_brightness(monitor_id, brightness)
+	}
+
+	/// Switches a monitor's display on, to standby or off; admin sessions only.
+	pub fn set_monitor_power(
+		&mut self,
+		monitor_id: &str,
+		power: core::MonitorPower,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_monitor_power(monitor_id, power)
+	}
+
+	/// Returns the power state of a monitor's display.
+	pub fn monitor_power(&self, monitor_id: &str) -> Option<core::MonitorPower> {
+		self.gl.monitor_power(monitor_id)
+	}
+
+	/// Returns the system-wide accessibility settings.
+	pub fn accessibility(&self) -> &core::AccessibilitySettings {
+		self.gl.accessibility()
+	}
+
+	/// Changes the system-wide accessibility settings; admin sessions only.
+	pub fn set_accessibility(
+		&mut self,
+		settings: core::AccessibilitySettings,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_accessibility(settings)
+	}
+
+	/// Replaces a monitor's gamma ramps; admin sessions only.
+	pub fn set_gamma_lut(
+		&mut self,
+		monitor_id: &str,
+		ramps: Option<core::GammaRamps>,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_gamma_lut(monitor_id, ramps)
+	}
+
+	/// Returns the modes a monitor advertises.
+	pub fn monitor_modes(&self, monitor_id: &str) -> Option<&[core::MonitorMode]> {
+		self.gl.monitor_modes(monitor_id)
+	}
+
+	/// Returns which parts of a monitor compositor chrome covers, in buffer pixels.
+	pub fn safe_area(&self, monitor_id: &str) -> Option<core::SafeArea> {
+		self.gl.safe_area(monitor_id)
+	}
+
+	/// Declares the parts of a monitor this session draws chrome over; admin sessions only.
+	pub fn set_occluded_areas(
+		&mut self,
+		monitor_id: &str,
+		occluded: Vec<core::Rect>,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_occluded_areas(monitor_id, occluded)
+	}
+
+	/// Asks the server to switch a monitor's mode; admin sessions only.
+	pub fn request_mode(
+		&mut self,
+		monitor_id: &str,
+		mode: core::MonitorMode,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.request_mode(monitor_id, mode)
+	}
+
+	/// Drops the frame being rendered instead of presenting it.
+	pub fn cancel_frame(&mut self) {
+		self.gl.cancel_frame()
+	}
+
+	/// Acquires a buffer of `monitor_id` for explicit rendering, see [`Self::bind_frame`].
+	pub fn acquire_target(
+		&mut self,
+		monitor_id: &str,
+	) -> Result<core::AcquiredFrame, core::FrameworkError> {
+		self.gl.acquire_target(monitor_id)
+	}
+
+	/// Binds an acquired frame as the current GL framebuffer.
+	///
+	/// Wrap [`GlContext::current_fbo`] in a Skia surface to draw into it.
+	pub fn bind_frame(&mut self, frame: &core::AcquiredFrame) -> Result<(), core::FrameworkError> {
+		self.gr.reset(None);
+		self.gl.bind_frame(frame)
+	}
+
+	/// Flushes pending Skia work and submits the frame for presentation.
+	pub fn submit_frame(&mut self, frame: core::AcquiredFrame) -> Result<(), core::FrameworkError> {
+		self.gr.flush_and_submit();
+		self.gl.submit_frame(frame)
+	}
+
+	/// Returns an acquired frame to the swapchain without presenting it.
+	pub fn discard_frame(&mut self, frame: core::AcquiredFrame) {
+		self.gl.discard_frame(frame);
+	}
+
+	/// Creates an overlay above this session's frames on a monitor.
+	pub fn create_overlay(
+		&mut self,
+		monitor_id: &str,
+		size: (i32, i32),
+		z: i32,
+	) -> Result<core::OverlayId, core::FrameworkError> {
+		self.gl.create_overlay(monitor_id, size, z)
+	}
+
+	/// Moves an overlay, relative to its monitor's top-left corner.
+	pub fn set_overlay_position(
+		&mut self,
+		overlay_id: core::OverlayId,
+		position: (i32, i32),
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_overlay_position(overlay_id, position)
+	}
+
+	/// Destroys an overlay and the GL framebuffers imported for it.
+	pub fn destroy_overlay(
+		&mut self,
+		overlay_id: core::OverlayId,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.destroy_overlay(overlay_id)
+	}
+
+	/// Acquires an overlay buffer for rendering, see [`Self::bind_overlay`].
+	pub fn acquire_overlay(
+		&mut self,
+		overlay_id: core::OverlayId,
+	) -> Result<core::OverlayFrame, core::FrameworkError> {
+		self.gl.acquire_overlay(overlay_id)
+	}
+
+	/// Binds an acquired overlay frame as the current GL framebuffer.
+	///
+	/// Wrap [`GlContext::current_fbo`] in a Skia surface to draw into it.
+	pub fn bind_overlay(&mut self, frame: &core::OverlayFrame) -> Result<(), core::FrameworkError> {
+		self.gl.bind_overlay(frame)?;
+		self.gr.reset(None);
+		Ok(())
+	}
+
+	/// Flushes pending Skia work and shows the overlay frame.
+	pub fn submit_overlay(&mut self, frame: core::OverlayFrame) -> Result<(), core::FrameworkError> {
+		self.gr.flush_and_submit();
+		self.gl.submit_overlay(frame)
+	}
+
+	/// Returns an acquired overlay frame to its swapchain without showing it.
+	pub fn discard_overlay(&mut self, frame: core::OverlayFrame) {
+		self.gl.discard_overlay(frame);
+	}
+
+	/// Marks frames rendered to a monitor as fullscreen and opaque.
+	pub fn set_scanout_candidate(
+		&mut self,
+		monitor_id: &str,
+		fullscreen_opaque: bool,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_scanout_candidate(monitor_id, fullscreen_opaque)
+	}
+
+	/// Tags the color space of this session's frames on a monitor.
+	pub fn set_surface_colorspace(
+		&mut self,
+		monitor_id: &str,
+		colorspace: core::Colorspace,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_surface_colorspace(monitor_id, colorspace)
+	}
+
+	/// Returns immutable access to GL context.
+	pub fn gl(&self) -> &GlContext {
+		self.gl.gl()
+	}
+
+	/// Returns the Skia GPU context.
+	pub fn direct_context(&mut self) -> &mut gpu::DirectContext {
+		self.gr
+	}
+
+	/// Schedules a frame for a monitor and reads it back once rendered.
+	pub fn capture_frame(&mut self, monitor_id: impl Into<String>) {
+		self.gl.capture_frame(monitor_id);
+	}
+
+	/// Returns true when frames are submitted with explicit acquire fences.
+	pub fn explicit_sync_supported(&self) -> bool {
+		self.gl.explicit_sync_supported()
+	}
+
+	/// Shows or hides the FPS and frame-timing overlay drawn over each frame.
+	pub fn set_debug_overlay(&mut self, enabled: bool) {
+		self.gl.set_debug_overlay(enabled);
+	}
+
+	/// Returns true while the debug overlay is shown.
+	pub fn debug_overlay(&self) -> bool {
+		self.gl.debug_overlay()
+	}
+
+	/// Sends `session_ready` for the current session.
+	pub fn session_ready(&mut self) -> Result<(), core::FrameworkError> {
+		self.gl.session_ready()
+	}
+
+	/// Renames this session for admin switcher UIs.
+	pub fn set_session_display_name(
+		&mut self,
+		name: impl Into<String>,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_session_display_name(name)
+	}
+
+	/// Sets one of this session's metadata entries, or removes it with `None`.
+	pub fn set_session_metadata(
+		&mut self,
+		key: impl Into<String>,
+		value: Option<String>,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.set_session_metadata(key, value)
+	}
+
+	/// Requests creation of a new session and waits for completion.
+	pub fn create_session(
+		&mut self,
+		role: core::SessionRole,
+		display_name: Option<String>,
+	) -> Result<core::SessionCreatedPayload, core::FrameworkError> {
+		self.gl.create_session(role, display_name)
+	}
+
+	/// Requests switching to another session.
+	pub fn switch_session(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.switch_session(session_id, animation, duration)
+	}
+
+	/// Requests creation of a new session without blocking the loop.
+	pub fn create_session_async(
+		&mut self,
+		role: core::SessionRole,
+		display_name: Option<String>,
+	) -> Result<core::RequestId, core::FrameworkError> {
+		self.gl.create_session_async(role, display_name)
+	}
+
+	/// Requests switching to another session and reports the outcome.
+	pub fn switch_session_async(
+		&mut self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+	) -> Result<core::RequestId, core::FrameworkError> {
+		self
+			.gl
+			.switch_session_async(session_id, animation, duration)
+	}
+
+	/// Admin-only: asks for a downscaled snapshot of another session's latest frame.
+	pub fn request_session_preview(
+		&mut self,
+		session_id: &str,
+		max_size: (i32, i32),
+	) -> Result<(), core::FrameworkError> {
+		self.gl.request_session_preview(session_id, max_size)
+	}
+
+	/// Sends `data` to another session; returns the message's correlation id.
+	pub fn send_to_session(
+		&mut self,
+		session_id: &str,
+		data: &[u8],
+	) -> Result<u64, core::FrameworkError> {
+		self.gl.send_to_session(session_id, data)
+	}
+
+	/// Answers a message received through [`SkiaApplication::on_session_message`].
+	pub fn reply_to_session(
+		&mut self,
+		message: &core::SessionMessageEvent,
+		data: &[u8],
+	) -> Result<u64, core::FrameworkError> {
+		self.gl.reply_to_session(message, data)
+	}
+
+	/// Returns the configuration in effect.
+	pub fn config(&self) -> &core::Config {
+		self.gl.config()
+	}
+
+	/// Changes live configuration fields; returns the changed fields that need a new
+	/// connection, see [`core::Context::update_config`].
+	pub fn update_config(
+		&mut self,
+		update: impl FnOnce(&mut core::Config),
+	) -> Vec<core::ConfigField> {
+		self.gl.update_config(update)
+	}
+
+	/// Admin-only: reserves a key combination, given as evdev key codes, across sessions.
+	pub fn register_global_shortcut(
+		&mut self,
+		keys: &[u32],
+	) -> Result<core::GlobalShortcutId, core::FrameworkError> {
+		self.gl.register_global_shortcut(keys)
+	}
+
+	/// Releases a shortcut registered with [`Self::register_global_shortcut`].
+	pub fn unregister_global_shortcut(
+		&mut self,
+		shortcut_id: core::GlobalShortcutId,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.unregister_global_shortcut(shortcut_id)
+	}
+
+	/// Admin-only: starts copying `source` into a capture swapchain of `size`.
+	pub fn start_capture(
+		&mut self,
+		source: core::CaptureSource,
+		size: (i32, i32),
+	) -> Result<core::CaptureId, core::FrameworkError> {
+		self.gl.start_capture(source, size)
+	}
+
+	/// Returns the buffer holding a captured frame, until it is released.
+	pub fn capture_target(
+		&self,
+		frame: &core::CaptureFrameEvent,
+	) -> Result<core::RenderTarget<'_>, core::FrameworkError> {
+		self.gl.capture_target(frame)
+	}
+
+	/// Hands a captured frame's buffer back to shift for following frames.
+	pub fn release_capture_frame(
+		&mut self,
+		frame: &core::CaptureFrameEvent,
+	) -> Result<(), core::FrameworkError> {
+		self.gl.release_capture_frame(frame)
+	}
+
+	/// Stops a capture started with [`Self::start_capture`].
+	pub fn stop_capture(&mut self, capture_id: core::CaptureId) -> Result<(), core::FrameworkError> {
+		self.gl.stop_capture(capture_id)
+	}
+}
+
+/// High-level Skia framework wrapper around the GL runtime.
+pub struct SkiaTabAppFramework<A: SkiaApplication> {
+	inner: GlTabAppFramework<SkiaBridge<A>>,
+}
+
+impl<A: SkiaApplication> SkiaTabAppFramework<A> {
+	/// Initializes a Skia application runtime.
+	pub fn init(configure: impl FnOnce(&mut core::Config)) -> Result<Self, core::FrameworkError> {
+		let inner = GlTabAppFramework::<SkiaBridge<A>>::init(configure)?;
+		Ok(Self { inner })
+	}
+
+	/// Initializes a Skia application runtime from an explicit configuration.
+	pub fn init_with_config(config: core::Config) -> Result<Self, core::FrameworkError> {
+		let inner = GlTabAppFramework::<SkiaBridge<A>>::init_with_config(config)?;
+		Ok(Self { inner })
+	}
+
+	/// Initializes a Skia application runtime on a new thread and runs it there.
+	pub fn spawn<F>(configure: F) -> Result<core::FrameworkController, core::FrameworkError>
+	where
+		F: FnOnce(&mut core::Config) + Send + 'static,
+	{
+		GlTabAppFramework::<SkiaBridge<A>>::spawn(configure)
+	}
+
+	/// Runs the application loop until exit.
+	pub fn run(&mut self) -> Result<(), core::FrameworkError> {
+		self.inner.run()
+	}
+}
+
+impl<A: SkiaApplication> DrivenFramework for SkiaTabAppFramework<A> {
+	fn poll_fds(&self) -> Vec<RawFd> {
+		self.inner.poll_fds()
+	}
+
+	fn poll_timeout(&self) -> Option<Duration> {
+		self.inner.poll_timeout()
+	}
+
+	fn dispatch(&mut self) -> Result<(), core::FrameworkError> {
+		self.inner.dispatch()
+	}
+
+	fn exit_requested(&self) -> bool {
+		self.inner.exit_requested()
+	}
+}
+
+struct SkiaBridge<A: SkiaApplication> {
+	app: A,
+	gr: gpu::DirectContext,
+	surfaces: HashMap<String, MonitorSurfaces>,
+}
+
+impl<A: SkiaApplication> GlApplication for SkiaBridge<A> {
+	fn init(ctx: &mut GlInitContext) -> anyhow::Result<Self> {
+		ctx
+			.gl()
+			.make_current()
+			.context("failed to make GL context current")?;
+		let mut gr = make_direct_context(ctx.gl())?;
+		let mut init = SkiaInitContext {
+			gl: ctx,
+			gr: &mut gr,
+		};
+		let app = A::init(&mut init)?;
+		Ok(Self {
+			app,
+			gr,
+			surfaces: HashMap::new(),
+		})
+	}
+
+	fn on_begin_frame(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, monitor_ids: &[String]) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_begin_frame(&mut ctx, monitor_ids);
+	}
+
+	fn on_end_frame(&mut self, ctx: &mut GlEventContext<'_, '_, Self>) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_end_frame(&mut ctx);
+	}
+
+	fn on_render(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::RenderEvent<'_>) {
+		// The GL bridge bound the imported dmabuf FBO; Skia must not trust cached GL state.
+		self.gr.reset(None);
+		if ev.resized() {
+			self.surfaces.remove(ev.monitor_id());
+		}
+		let fbo = ctx.current_fbo();
+		let surfaces = self
+			.surfaces
+			.entry(ev.monitor_id().to_string())
+			.or_insert_with(MonitorSurfaces::new);
+		let surface = match surfaces.surface_for_fbo(&mut self.gr, ev.width(), ev.height(), fbo) {
+			Ok(surface) => surface,
+			Err(err) => {
+				let ferr = core::FrameworkError::Config(format!("skia surface setup failed: {err}"));
+				let mut ctx = SkiaEventContext {
+					gl: ctx,
+					gr: &mut self.gr,
+				};
+				self.app.on_error(&mut ctx, &ferr);
+				return;
+			}
+		};
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_render(&mut ctx, surface.canvas(), ev);
+		self.gr.flush_and_submit();
+	}
+
+	fn on_present(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::PresentEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_present(&mut ctx, ev);
+	}
+
+	fn on_frame_captured(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: FrameCaptureEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_frame_captured(&mut ctx, ev);
+	}
+
+	fn on_monitor_added(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::MonitorAddedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_monitor_added(&mut ctx, ev);
+	}
+
+	fn on_monitor_removed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::MonitorRemovedEvent,
+	) {
+		self.surfaces.remove(&ev.monitor_id);
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_monitor_removed(&mut ctx, ev);
+	}
+
+	fn on_safe_area_changed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SafeAreaChangedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_safe_area_changed(&mut ctx, ev);
+	}
+
+	fn on_monitor_power_changed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::MonitorPowerChangedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_monitor_power_changed(&mut ctx, ev);
+	}
+
+	fn on_buffer_available(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::BufferAvailableEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_buffer_available(&mut ctx, ev);
+	}
+
+	fn on_starved(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::StarvedEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_starved(&mut ctx, ev);
+	}
+
+	fn on_swapchain_recreated(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SwapchainRecreatedEvent,
+	) {
+		self.surfaces.remove(&ev.monitor_id);
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_swapchain_recreated(&mut ctx, ev);
+	}
+
+	fn on_layout_changed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::LayoutChangedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_layout_changed(&mut ctx, ev);
+	}
+
+	fn on_accessibility_changed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::AccessibilityChangedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_accessibility_changed(&mut ctx, ev);
+	}
+
+	fn on_session_state(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::SessionEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_session_state(&mut ctx, ev);
+	}
+
+	fn on_focus_gained(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::FocusEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_focus_gained(&mut ctx, ev);
+	}
+
+	fn on_focus_lost(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::FocusEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_focus_lost(&mut ctx, ev);
+	}
+
+	fn on_session_preview(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SessionPreviewEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_session_preview(&mut ctx, ev);
+	}
+
+	fn on_capture_frame(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::CaptureFrameEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_capture_frame(&mut ctx, ev);
+	}
+
+	fn on_capture_ended(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::CaptureEndedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_capture_ended(&mut ctx, ev);
+	}
+
+	fn on_session_message(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SessionMessageEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_session_message(&mut ctx, ev);
+	}
+
+	fn on_session_crashed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SessionCrashedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_session_crashed(&mut ctx, ev);
+	}
+
+	fn on_session_create_result(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SessionCreateResultEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_session_create_result(&mut ctx, ev);
+	}
+
+	fn on_session_switch_result(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SessionSwitchResultEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_session_switch_result(&mut ctx, ev);
+	}
+
+	fn on_session_ready_sent(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::SessionReadySentEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_session_ready_sent(&mut ctx, ev);
+	}
+
+	fn on_server_unresponsive(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::ServerUnresponsiveEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_server_unresponsive(&mut ctx, ev);
+	}
+
+	fn on_server_recovered(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::ServerRecoveredEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_server_recovered(&mut ctx, ev);
+	}
+
+	fn on_config_changed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::ConfigChangedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_config_changed(&mut ctx, ev);
+	}
+
+	fn on_input(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: &core::InputEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_input(&mut ctx, ev);
+	}
+
+	fn on_global_shortcut(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::GlobalShortcutEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_global_shortcut(&mut ctx, ev);
+	}
+
+	fn on_key(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::KeyEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_key(&mut ctx, ev);
+	}
+
+	fn on_char(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::CharEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_char(&mut ctx, ev);
+	}
+
+	fn on_pointer_move(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::PointerMoveEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_pointer_move(&mut ctx, ev);
+	}
+
+	fn on_mouse_move(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::MouseMoveEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_mouse_move(&mut ctx, ev);
+	}
+
+	fn on_pointer_down(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::PointerDownEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_pointer_down(&mut ctx, ev);
+	}
+
+	fn on_pointer_up(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::PointerUpEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_pointer_up(&mut ctx, ev);
+	}
+
+	fn on_mouse_down(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::MouseDownEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_mouse_down(&mut ctx, ev);
+	}
+
+	fn on_mouse_up(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::MouseUpEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_mouse_up(&mut ctx, ev);
+	}
+
+	fn on_touch(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::TouchEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_touch(&mut ctx, ev);
+	}
+
+	fn on_gesture(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::GestureEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_gesture(&mut ctx, ev);
+	}
+
+	fn on_fd_ready(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::FdReadyEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_fd_ready(&mut ctx, ev);
+	}
+
+	fn on_stats(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, stats: core::LoopStatsSnapshot) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_stats(&mut ctx, stats);
+	}
+
+	fn on_events(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, events: &[core::Event]) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_events(&mut ctx, events);
+	}
+
+	fn on_extension_event(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::ExtensionEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_extension_event(&mut ctx, ev);
+	}
+
+	fn on_user_event(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::UserEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_user_event(&mut ctx, ev);
+	}
+
+	fn on_input_device_added(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::InputDeviceAddedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_input_device_added(&mut ctx, ev);
+	}
+
+	fn on_input_device_removed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::InputDeviceRemovedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_input_device_removed(&mut ctx, ev);
+	}
+
+	fn on_idle(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, ev: core::IdleEvent) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_idle(&mut ctx, ev);
+	}
+
+	fn on_resume_activity(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::ResumeActivityEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_resume_activity(&mut ctx, ev);
+	}
+
+	fn on_monitor_changed(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::MonitorChangedEvent,
+	) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_monitor_changed(&mut ctx, ev);
+	}
+
+	fn on_frame_requested(
+		&mut self,
+		ctx: &mut GlEventContext<'_, '_, Self>,
+		ev: core::FrameRequestEvent,
+	) {
+		self.gr.reset(None);
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_frame_requested(&mut ctx, ev);
+	}
+
+	fn on_gl_context_lost(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, status: GlResetStatus) {
+		// Skia's GPU objects died with the GL context; abandoning skips deleting them.
+		self.gr.abandon();
+		self.surfaces.clear();
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_gl_context_lost(&mut ctx, status);
+	}
+
+	fn on_gl_context_restored(&mut self, ctx: &mut GlEventContext<'_, '_, Self>) {
+		match make_direct_context(ctx.gl()) {
+			Ok(gr) => self.gr = gr,
+			Err(err) => {
+				let ferr = core::FrameworkError::Config(format!("skia context recreation failed: {err:#}"));
+				let mut ctx = SkiaEventContext {
+					gl: ctx,
+					gr: &mut self.gr,
+				};
+				self.app.on_error(&mut ctx, &ferr);
+				return;
+			}
+		}
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_gl_context_restored(&mut ctx);
+	}
+
+	fn on_error(&mut self, ctx: &mut GlEventContext<'_, '_, Self>, error: &core::FrameworkError) {
+		let mut ctx = SkiaEventContext {
+			gl: ctx,
+			gr: &mut self.gr,
+		};
+		self.app.on_error(&mut ctx, error);
+	}
+}
+
+/// Creates a Skia GPU context on the current GL context.
+fn make_direct_context(gl: &GlContext) -> anyhow::Result<gpu::DirectContext> {
+	let interface = gpu::gl::Interface::new_load_with(|name| {
+		gl.load_proc(name).unwrap_or(ptr::null()) as *const c_void
+	})
+	.context("failed to load skia GL interface")?;
+	gpu::direct_contexts::make_gl(interface, None).context("failed to create skia DirectContext")
+}
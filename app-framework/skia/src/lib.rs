@@ -0,0 +1,90 @@
+//! Skia integration for `tab-app-framework`.
+//! Wraps each imported DMA-BUF framebuffer in a cached Skia surface.
+
+mod framework;
+
+use std::collections::HashMap;
+
+use skia_safe::{self as skia, gpu, gpu::gl::FramebufferInfo};
+use thiserror::Error;
+
+pub use framework::{SkiaApplication, SkiaEventContext, SkiaInitContext, SkiaTabAppFramework};
+pub use skia_safe;
+
+/// Errors produced while wrapping render targets in Skia surfaces.
+#[derive(Debug, Error)]
+pub enum SkiaError {
+	#[error("failed to wrap framebuffer {0} in a skia surface")]
+	WrapRenderTarget(i32),
+}
+
+/// Per-monitor Skia surfaces keyed by framebuffer object id.
+struct MonitorSurfaces {
+	surfaces_by_fbo: HashMap<i32, skia::Surface>,
+	width: i32,
+	height: i32,
+}
+
+impl MonitorSurfaces {
+	fn new() -> Self {
+		Self {
+			surfaces_by_fbo: HashMap::new(),
+			width: 0,
+			height: 0,
+		}
+	}
+
+	fn surface_for_fbo(
+		&mut self,
+		gr: &mut gpu::DirectContext,
+		width: i32,
+		height: i32,
+		fbo: i32,
+	) -> Result<&mut skia::Surface, SkiaError> {
+		if self.width != width || self.height != height {
+			self.surfaces_by_fbo.clear();
+			self.width = width;
+			self.height = height;
+		}
+		if !self.surfaces_by_fbo.contains_key(&fbo) {
+			let surface = skia_surface_for_fbo(gr, width, height, fbo)?;
+			self.surfaces_by_fbo.insert(fbo, surface);
+		}
+		Ok(
+			self
+				.surfaces_by_fbo
+				.get_mut(&fbo)
+				.expect("skia surface cache unexpectedly missing"),
+		)
+	}
+}
+
+fn skia_surface_for_fbo(
+	gr: &mut gpu::DirectContext,
+	width: i32,
+	height: i32,
+	fbo: i32,
+) -> Result<skia::Surface, SkiaError> {
+	let fb_info = FramebufferInfo {
+		fboid: fbo as u32,
+		format: gpu::gl::Format::RGBA8.into(),
+		protected: gpu::Protected::No,
+	};
+
+	let backend_rt = gpu::backend_render_targets::make_gl(
+		(width, height),
+		0, // samples
+		0, // stencil
+		fb_info,
+	);
+
+	gpu::surfaces::wrap_backend_render_target(
+		gr,
+		&backend_rt,
+		gpu::SurfaceOrigin::TopLeft,
+		skia::ColorType::RGBA8888,
+		None,
+		None,
+	)
+	.ok_or(SkiaError::WrapRenderTarget(fbo))
+}
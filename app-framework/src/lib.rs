@@ -7,6 +7,9 @@
 pub use tab_app_framework_core as core;
 /// OpenGL integration APIs.
 pub use tab_app_framework_gl as gl;
+/// Skia integration APIs.
+#[cfg(feature = "skia")]
+pub use tab_app_framework_skia as skia;
 /// XKB composition APIs.
 pub use tab_app_framework_xkb as xkb;
 /// Monitor layout utilities.
@@ -14,15 +17,27 @@ pub use monitor_layout_engine as monitor_layout;
 
 /// Re-exported core runtime types.
 pub use tab_app_framework_core::{
-	Application, CharEvent, Config, Context, FdReadyEvent, FrameworkError, GestureEvent,
-	InitContext, InputEvent, KeyEvent, Monitor, MonitorAddedEvent, MonitorRemovedEvent, MouseDownEvent,
-	MouseMoveEvent, MouseUpEvent, PointerDownEvent, PointerMoveEvent, PointerType, PointerUpEvent,
-	PresentEvent, RenderEvent, RenderMode, SessionCreatedPayload, SessionEvent, SessionInfo,
-	SessionRole, TabAppFramework, TouchEvent,
+	AccessibilityChangedEvent, AccessibilitySettings, AcquiredFrame, Application, AutoReady, BufferAvailableEvent, BufferOwnership, BufferState, CaptureEndedEvent, CaptureFrameEvent, CaptureId, CaptureSource, CharEvent, Chromaticity, ClearPolicy, Colorspace, Config, ConfigChangedEvent, ConfigField, Context, DebugSnapshot, DrivenFramework, Easing, EdgeBehavior, Event, ExtensionEvent, FdReadyEvent, FocusEvent, FrameRequestEvent, FrameTimings, FrameworkController, FrameworkError, GammaRamps, GestureEvent, GlobalShortcutEvent, GlobalShortcutId,
+	GlContextOptions, GlContextPriority, GlSurfaceFormat, IdleEvent, IdleInhibitor, InitContext, InputDeviceAddedEvent, InputDeviceCapability, InputDeviceInfo,
+	InputDeviceRemovedEvent, InputEvent, InputEventPayload, InputFilter, KeyEvent, LayoutChange, Lerp, LayoutChangedEvent, LoopStatsSnapshot, Monitor, MonitorAddedEvent, MonitorColorInfo,
+	MonitorChangedEvent, MonitorMode, MonitorPower, MonitorPowerChangedEvent, MonitorRemovedEvent, MonitorSnapshot, MouseDownEvent, MouseMoveEvent, MouseUpEvent, MultiFrameworkDriver, OverlayFrame, OverlayId, PointerDownEvent, PointerMoveEvent, PointerType, PointerUpEvent,
+	PresentEvent, PresentationClock, PreviewImage, ProtocolError, QueueOverflow, Rect, RenderEvent, RequestId, RenderMode, RenderTarget, RenderTargetOptions, ResumeActivityEvent, RetryPolicy, SafeArea, SafeAreaChangedEvent, SeatId, SeatSnapshot, ServerError, ServerErrorCode, ServerRecoveredEvent, ServerUnresponsiveEvent, SessionCreateResultEvent, SessionCrashedEvent, SessionCreatedPayload, SessionEvent,
+	SessionInfo, SessionMessageEvent, SessionPreviewEvent, SessionReadySentEvent, SessionRole, SessionSwitch, SessionSwitchController, SessionSwitchResultEvent, StarvedEvent, SwapchainRecreatedEvent, SwapchainSnapshot, SwitchGesture, SyncMode, TabAppFramework, TabMessageFrame, Timeline, TouchContactSnapshot, TouchEvent, Tween, UserEvent, WrapMode,
+};
+/// Re-exported in-memory test backend.
+#[cfg(feature = "testing")]
+pub use tab_app_framework_core::{
+	MockCapture, MockOverlay, MockSessionMessage, MockTabAppFramework, SubmittedFrame,
 };
 /// Re-exported GL runtime types.
 pub use tab_app_framework_gl::{
-	GlApplication, GlContext, GlError, GlEventContext, GlInitContext, GlTabAppFramework, GlVersion,
+	DmabufPlane, ExternalFormat, ExternalTexture, FrameCaptureEvent, GlApplication, GlContext,
+	GlDeviceInfo, GlError, GlEventContext, GlFence, GlInitContext, GlResetStatus, GlSharedContext, GlTabAppFramework, GlVersion,
+	ImageBuffer, NinePatch, OffscreenTarget, PixelRect, Program, QuadBatcher, QuadRect, QuadTexture,
+	QuadTransform, RenderJob, RenderJobContext, TextureUpload, UniformValue, YuvColor, YuvColorSpace, YuvRange, yuv_to_rgb_matrix,
 };
+/// Re-exported JSON log subscriber.
+#[cfg(feature = "json-logs")]
+pub use tab_app_framework_core::init_json_logging;
 /// Re-exported XKB helper types.
 pub use tab_app_framework_xkb::{KeyComposition, Modifiers, XkbEngine, XkbError};
@@ -1,4 +1,4 @@
-//! XKB keyboard composition helpers used by the GL framework.
+//! XKB keyboard composition helpers behind the core `text-composition` feature.
 
 use thiserror::Error;
 use xkbcommon::xkb;
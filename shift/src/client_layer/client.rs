@@ -5,8 +5,12 @@ use std::{
 };
 
 use tab_protocol::{
-	AuthErrorPayload, AuthOkPayload, ErrorPayload, MonitorAddedPayload, MonitorRemovedPayload,
-	SessionActivePayload, SessionAwakePayload, SessionCreatedPayload, SessionInfo,
+	AccessibilityPayload, AuthErrorPayload, AuthOkPayload, BufferRequestPayload, CaptureEndedPayload,
+	CaptureFramePayload, ClockSyncPayload, ErrorPayload, GlobalShortcutPayload,
+	InputDeviceAddedPayload, InputDeviceInfo, InputDeviceRemovedPayload, MonitorAddedPayload,
+	MonitorChangedPayload, MonitorPowerPayload, MonitorRemovedPayload, OverlayReleasePayload,
+	SafeAreaPayload, SessionActivePayload, SessionAwakePayload, SessionCrashedPayload,
+	SessionCreatedPayload, SessionInfo, SessionMessagePayload, SessionPreviewPayload,
 	SessionSleepPayload, SessionStatePayload, TabMessage, TabMessageFrame, TabMessageFrameReader,
 	message_header,
 };
@@ -22,6 +26,7 @@ use crate::{
 	},
 	define_id_type,
 	monitor::{Monitor, MonitorId},
+	rendering_layer::monotonic_usec,
 	sessions::{Role, Session, SessionId},
 };
 pub type AsyncUnixStream = AsyncFd<UnixStream>;
@@ -34,12 +39,14 @@ pub struct Client {
 	connected_session: Option<Arc<Session>>,
 	shutdown: bool,
 	initial_monitors: Vec<Monitor>,
+	initial_input_devices: Vec<InputDeviceInfo>,
 }
 
 impl Client {
 	pub fn wrap_socket(
 		socket: AsyncUnixStream,
 		initial_monitors: Vec<Monitor>,
+		initial_input_devices: Vec<InputDeviceInfo>,
 	) -> (Self, ClientView) {
 		let channels = client_view::Channels::new();
 		let client = Self {
@@ -50,6 +57,7 @@ impl Client {
 			connected_session: None,
 			shutdown: false,
 			initial_monitors,
+			initial_input_devices,
 		};
 		let client_view = ClientView::from_client(&client, channels.server_end);
 		(client, client_view)
@@ -58,13 +66,20 @@ impl Client {
 		self.id
 	}
 	#[tracing::instrument(level = "error", skip(self), fields(client.id = self.id().to_string()))]
-	async fn send_error(&self, code: &str, error: Option<impl Display + Debug>) {
+	async fn send_error(
+		&self,
+		code: &str,
+		error: Option<impl Display + Debug>,
+		request_id: Option<u64>,
+	) {
 		tracing::warn!("sending error to the client");
 		let tab_message = TabMessageFrame::json(
 			message_header::ERROR,
 			ErrorPayload {
 				code: code.into(),
 				message: error.as_ref().map(|e| e.to_string()),
+				request_id,
+				buffer_request: None,
 			},
 		);
 		let result = tab_message.send_frame_to_async_fd(&self.socket).await;
@@ -75,6 +90,29 @@ impl Client {
 			);
 		}
 	}
+	/// Rejects a `buffer_request`, naming it so the client fails that request alone.
+	#[tracing::instrument(level = "error", skip(self), fields(client.id = self.id().to_string()))]
+	async fn send_buffer_request_error(
+		&self,
+		code: &str,
+		error: Option<&str>,
+		buffer_request: BufferRequestPayload,
+	) {
+		tracing::warn!("sending buffer request error to the client");
+		let tab_message = TabMessageFrame::json(
+			message_header::ERROR,
+			ErrorPayload {
+				code: code.into(),
+				message: error.map(str::to_string),
+				request_id: None,
+				buffer_request: Some(buffer_request),
+			},
+		);
+		let result = tab_message.send_frame_to_async_fd(&self.socket).await;
+		if let Err(e) = result {
+			tracing::warn!("failed to send buffer request error to client: {e}");
+		}
+	}
 	#[tracing::instrument(skip(self), fields(client.id = self.id().to_string()))]
 	async fn send_auth_error(&mut self, cause: impl Display + Debug) {
 		let tab_message = TabMessageFrame::json(
@@ -95,13 +133,18 @@ impl Client {
 
 	#[tracing::instrument(skip(self), fields(client.id = self.id().to_string()))]
 	async fn handle_unknown_msg(&mut self, message_name: impl Display + Debug) {
-		self.send_error("unknown_message", Some(message_name)).await;
+		self
+			.send_error("unknown_message", Some(message_name), None)
+			.await;
 		self.schedule_client_shutdown().await;
 	}
 	#[tracing::instrument(skip(self), fields(client.id = self.id().to_string()))]
 	async fn handle_packet(&mut self, tab_message: TabMessage) {
 		macro_rules! check_admin {
 			($action:literal) => {
+				check_admin!($action, None)
+			};
+			($action:literal, $request_id:expr) => {
 				if !self
 					.connected_session
 					.as_deref()
@@ -114,6 +157,7 @@ impl Client {
 								"you need to authenticate as an admin client before being able to {}",
 								$action
 							)),
+							$request_id,
 						)
 						.await;
 					return;
@@ -131,6 +175,7 @@ impl Client {
 								"you need to authenticate before being able to {}",
 								$action
 							)),
+							None,
 						)
 						.await;
 					return;
@@ -162,7 +207,7 @@ impl Client {
 				send_server_msg!(C2SMsg::Auth(token));
 			}
 			TabMessage::SessionSwitch(session_switch_payload) => {
-				check_admin!("switch session");
+				check_admin!("switch session", session_switch_payload.request_id);
 				send_server_msg!(C2SMsg::SwitchSession(session_switch_payload));
 			}
 			TabMessage::BufferRequest {
@@ -173,11 +218,9 @@ impl Client {
 				let monitor_id = match monitor_id {
 					Ok(monitor_id) => monitor_id,
 					Err(error) => {
+						let error = format!("monitor id parse error: {error:?}");
 						return self
-							.send_error(
-								"unknown_monitor",
-								Some(format!("monitor id parse error: {error:?}")),
-							)
+							.send_buffer_request_error("unknown_monitor", Some(error.as_str()), payload)
 							.await;
 					}
 				};
@@ -188,18 +231,25 @@ impl Client {
 				});
 			}
 			TabMessage::SessionCreate(session_create_req) => {
-				check_admin!("create a session");
+				check_admin!("create a session", session_create_req.request_id);
 				send_server_msg!(C2SMsg::CreateSession(session_create_req));
 			}
 			TabMessage::Ping => {
 				tracing::debug!("received ping");
-
-				let send_result = TabMessageFrame::no_payload(message_header::PONG)
+				// Answered by the server layer, so a pong proves its loop is still running.
+				send_server_msg!(C2SMsg::Ping);
+			}
+			TabMessage::ClockSyncRequest(payload) => {
+				// Answered here rather than by the server layer to keep the round trip short.
+				let reply = ClockSyncPayload {
+					client_time_usec: payload.client_time_usec,
+					server_time_usec: monotonic_usec(),
+				};
+				let send_result = TabMessageFrame::json(message_header::CLOCK_SYNC, reply)
 					.send_frame_to_async_fd(&self.socket)
 					.await;
 				if let Err(e) = send_result {
-					tracing::warn!("failed to send pong message back: {e}");
-					return;
+					tracing::warn!("failed to send clock sync reply: {e}");
 				}
 			}
 			TabMessage::FramebufferLink {
@@ -213,27 +263,145 @@ impl Client {
 					dma_bufs
 				});
 			}
+			TabMessage::OverlayCreate { payload, dma_bufs } => {
+				check_session!("create overlay", _session);
+				send_server_msg!(C2SMsg::OverlayCreate { payload, dma_bufs });
+			}
+			TabMessage::OverlayMove(payload) => {
+				check_session!("move overlay", _session);
+				send_server_msg!(C2SMsg::OverlayMove(payload));
+			}
+			TabMessage::OverlayPresent {
+				payload,
+				acquire_fence,
+			} => {
+				check_session!("present overlay", _session);
+				send_server_msg!(C2SMsg::OverlayPresent {
+					payload,
+					acquire_fence,
+				});
+			}
+			TabMessage::OverlayDestroy(payload) => {
+				check_session!("destroy overlay", _session);
+				send_server_msg!(C2SMsg::OverlayDestroy(payload));
+			}
+			TabMessage::CaptureStart { payload, dma_bufs } => {
+				check_admin!("capture output");
+				send_server_msg!(C2SMsg::CaptureStart { payload, dma_bufs });
+			}
+			TabMessage::CaptureStop(payload) => {
+				check_admin!("stop captures");
+				send_server_msg!(C2SMsg::CaptureStop(payload));
+			}
+			TabMessage::CaptureRelease(payload) => {
+				check_admin!("release capture buffers");
+				send_server_msg!(C2SMsg::CaptureRelease(payload));
+			}
 
 			TabMessage::Hello(_hello_payload) => self.handle_unknown_msg("Hello").await,
 			TabMessage::AuthOk(_auth_ok_payload) => self.handle_unknown_msg("AuthOk").await,
 			TabMessage::AuthError(_auth_error_payload) => self.handle_unknown_msg("AuthError").await,
 			TabMessage::BufferRelease { .. } => self.handle_unknown_msg("BufferRelease").await,
+			TabMessage::OverlayRelease { .. } => self.handle_unknown_msg("OverlayRelease").await,
+			TabMessage::CaptureFrame { .. } => self.handle_unknown_msg("CaptureFrame").await,
+			TabMessage::CaptureEnded(_payload) => self.handle_unknown_msg("CaptureEnded").await,
 			TabMessage::BufferRequestAck(_buffer_request_ack_payload) => {
 				self.handle_unknown_msg("BufferRequestAck").await
 			}
 			TabMessage::InputEvent(_input_event_payload) => self.handle_unknown_msg("InputEvent").await,
+			TabMessage::InputDeviceAdded(_payload) => self.handle_unknown_msg("InputDeviceAdded").await,
+			TabMessage::InputDeviceRemoved(_payload) => {
+				self.handle_unknown_msg("InputDeviceRemoved").await
+			}
 			TabMessage::MonitorAdded(_monitor_added_payload) => {
 				self.handle_unknown_msg("MonitorAdded").await
 			}
 			TabMessage::MonitorRemoved(_monitor_removed_payload) => {
 				self.handle_unknown_msg("MonitorRemoved").await
 			}
+			TabMessage::MonitorChanged(_payload) => self.handle_unknown_msg("MonitorChanged").await,
+			TabMessage::MonitorModeSet(payload) => {
+				check_admin!("change monitor mode", payload.request_id);
+				send_server_msg!(C2SMsg::MonitorModeSet(payload));
+			}
+			TabMessage::MonitorPrimarySet(payload) => {
+				check_admin!("set the primary monitor", payload.request_id);
+				send_server_msg!(C2SMsg::MonitorPrimarySet(payload));
+			}
 			TabMessage::SessionCreated(_session_created_payload) => {
 				self.handle_unknown_msg("SessionCreated").await
 			}
 			TabMessage::SessionReady(_session_ready_payload) => {
 				send_server_msg!(C2SMsg::SessionReady(_session_ready_payload));
 			}
+			TabMessage::MonitorBrightness(payload) => {
+				check_admin!("change monitor brightness", payload.request_id);
+				send_server_msg!(C2SMsg::MonitorBrightness(payload));
+			}
+			TabMessage::MonitorGamma(payload) => {
+				check_admin!("change monitor gamma", payload.request_id);
+				send_server_msg!(C2SMsg::MonitorGamma(payload));
+			}
+			TabMessage::ScanoutHint(payload) => {
+				check_session!("hint scanout", _session);
+				send_server_msg!(C2SMsg::ScanoutHint(payload));
+			}
+			TabMessage::SurfaceColorspace(payload) => {
+				check_session!("tag surface colorspace", _session);
+				send_server_msg!(C2SMsg::SurfaceColorspace(payload));
+			}
+			TabMessage::SafeArea(payload) => {
+				check_admin!("declare compositor chrome", payload.request_id);
+				send_server_msg!(C2SMsg::SafeArea(payload));
+			}
+			TabMessage::MonitorPower(payload) => {
+				check_admin!("change monitor power", payload.request_id);
+				send_server_msg!(C2SMsg::MonitorPower(payload));
+			}
+			TabMessage::Accessibility(payload) => {
+				check_admin!("change accessibility settings", payload.request_id);
+				send_server_msg!(C2SMsg::Accessibility(payload));
+			}
+			TabMessage::IdleInhibit(payload) => {
+				check_session!("inhibit idle", _session);
+				send_server_msg!(C2SMsg::IdleInhibit(payload));
+			}
+			TabMessage::GlobalShortcutRegister(payload) => {
+				check_admin!("register global shortcuts", payload.request_id);
+				send_server_msg!(C2SMsg::GlobalShortcutRegister(payload));
+			}
+			TabMessage::GlobalShortcutUnregister(payload) => {
+				check_admin!("unregister global shortcuts", payload.request_id);
+				send_server_msg!(C2SMsg::GlobalShortcutUnregister(payload));
+			}
+			TabMessage::GlobalShortcut(_payload) => self.handle_unknown_msg("GlobalShortcut").await,
+			TabMessage::SessionMessageSend(payload) => {
+				check_session!("message other sessions", _session);
+				send_server_msg!(C2SMsg::SessionMessageSend(payload));
+			}
+			TabMessage::SessionMessage(_payload) => self.handle_unknown_msg("SessionMessage").await,
+			TabMessage::CrashReport(payload) => {
+				check_session!("report a crash", _session);
+				send_server_msg!(C2SMsg::CrashReport(payload));
+			}
+			TabMessage::SessionCrashed(_payload) => self.handle_unknown_msg("SessionCrashed").await,
+			TabMessage::KeyboardGrab(payload) => {
+				check_admin!("grab the keyboard");
+				send_server_msg!(C2SMsg::KeyboardGrab(payload));
+			}
+			TabMessage::InputInject(payload) => {
+				check_admin!("inject input", payload.request_id);
+				send_server_msg!(C2SMsg::InputInject(payload));
+			}
+			TabMessage::SessionUpdate(payload) => {
+				check_session!("update session info", _session);
+				send_server_msg!(C2SMsg::SessionUpdate(payload));
+			}
+			TabMessage::SessionPreviewRequest(payload) => {
+				check_admin!("request session previews");
+				send_server_msg!(C2SMsg::SessionPreviewRequest(payload));
+			}
+			TabMessage::SessionPreview { .. } => self.handle_unknown_msg("SessionPreview").await,
 			TabMessage::SessionState(_session_state_payload) => {
 				self.handle_unknown_msg("SessionState").await
 			}
@@ -244,6 +412,7 @@ impl Client {
 			TabMessage::SessionSleep(_payload) => self.handle_unknown_msg("SessionSleep").await,
 			TabMessage::Error(_error_payload) => self.handle_unknown_msg("Error").await,
 			TabMessage::Pong => self.handle_unknown_msg("Pong").await,
+			TabMessage::ClockSync(_payload) => self.handle_unknown_msg("ClockSync").await,
 			TabMessage::Unknown(tab_message_frame) => {
 				self.handle_unknown_msg(tab_message_frame.header.0).await
 			}
@@ -276,6 +445,7 @@ impl Client {
 							.iter()
 							.map(|m| m.to_protocol_info())
 							.collect(), // TODO: add monitors,
+						input_devices: self.initial_input_devices.clone(),
 						session: SessionInfo {
 							display_name: Some(session.display_name().to_string()),
 							id: session.id().to_string(),
@@ -285,6 +455,7 @@ impl Client {
 							} else {
 								tab_protocol::SessionLifecycle::Loading
 							},
+							metadata: session.metadata().clone(),
 						},
 					},
 				);
@@ -296,7 +467,7 @@ impl Client {
 					return;
 				}
 			}
-			S2CMsg::SessionCreated(token, session) => {
+			S2CMsg::SessionCreated(token, session, request_id) => {
 				tracing::debug!(
 					?session,
 					?token,
@@ -310,8 +481,10 @@ impl Client {
 							id: session.id().to_string(),
 							role: session.role().into(),
 							state: tab_protocol::SessionLifecycle::Pending,
+							metadata: Default::default(),
 						},
 						token: token.to_string(),
+						request_id,
 					},
 				)
 				.send_frame_to_async_fd(&self.socket)
@@ -325,12 +498,27 @@ impl Client {
 				code,
 				error,
 				shutdown,
+				request_id,
 			} => {
-				self.send_error(&code, error.as_deref()).await;
+				self.send_error(&code, error.as_deref(), request_id).await;
 				if shutdown {
 					self.schedule_client_shutdown().await;
 				}
 			}
+			S2CMsg::BufferRequestError {
+				monitor_id,
+				buffer,
+				code,
+				error,
+			} => {
+				let buffer_request = BufferRequestPayload {
+					monitor_id: monitor_id.to_string(),
+					buffer,
+				};
+				self
+					.send_buffer_request_error(&code, error.as_deref(), buffer_request)
+					.await;
+			}
 			S2CMsg::BufferRelease { buffers } => {
 				for buffer in buffers {
 					let payload = format!("{} {}", buffer.monitor_id, buffer.buffer as u8);
@@ -354,6 +542,54 @@ impl Client {
 					tracing::warn!(%monitor_id, buffer = buffer as u8, "failed to send buffer_request_ack: {e}");
 				}
 			}
+			S2CMsg::OverlayRelease {
+				overlay_id,
+				buffer,
+				release_fence,
+			} => {
+				let payload = OverlayReleasePayload {
+					overlay_id: overlay_id.clone(),
+					buffer,
+				};
+				let mut frame = TabMessageFrame::json(message_header::OVERLAY_RELEASE, payload);
+				if let Some(fd) = release_fence.as_ref() {
+					frame.fds.push(fd.as_raw_fd());
+				}
+				if let Err(e) = frame.send_frame_to_async_fd(&self.socket).await {
+					tracing::warn!(%overlay_id, buffer = buffer as u8, "failed to send overlay_release: {e}");
+				}
+			}
+			S2CMsg::CaptureFrame {
+				capture_id,
+				buffer,
+				time_usec,
+				ready_fence,
+			} => {
+				let payload = CaptureFramePayload {
+					capture_id: capture_id.clone(),
+					buffer,
+					time_usec,
+				};
+				let mut frame = TabMessageFrame::json(message_header::CAPTURE_FRAME, payload);
+				if let Some(fd) = ready_fence.as_ref() {
+					frame.fds.push(fd.as_raw_fd());
+				}
+				if let Err(e) = frame.send_frame_to_async_fd(&self.socket).await {
+					tracing::warn!(%capture_id, buffer = buffer as u8, "failed to send capture_frame: {e}");
+				}
+			}
+			S2CMsg::CaptureEnded { capture_id, reason } => {
+				let payload = CaptureEndedPayload {
+					capture_id: capture_id.clone(),
+					reason: reason.to_string(),
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::CAPTURE_ENDED, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!(%capture_id, "failed to send capture_ended: {e}");
+				}
+			}
 			S2CMsg::SessionAwake { session_id } => {
 				let payload = SessionAwakePayload {
 					session_id: session_id.to_string(),
@@ -404,6 +640,74 @@ impl Client {
 					tracing::warn!("failed to send input event: {e}");
 				}
 			}
+			S2CMsg::GlobalShortcut {
+				shortcut_id,
+				seat,
+				time_usec,
+			} => {
+				let payload = GlobalShortcutPayload {
+					shortcut_id,
+					seat,
+					time_usec,
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::GLOBAL_SHORTCUT, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send global shortcut: {e}");
+				}
+			}
+			S2CMsg::SessionMessage {
+				from,
+				correlation_id,
+				reply_to,
+				data,
+			} => {
+				let payload = SessionMessagePayload {
+					session_id: from.to_string(),
+					correlation_id,
+					reply_to,
+					data,
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::SESSION_MESSAGE, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send session message: {e}");
+				}
+			}
+			S2CMsg::SessionCrashed { session_id, report } => {
+				let payload = SessionCrashedPayload {
+					session_id: session_id.to_string(),
+					message: report.message,
+					backtrace_hash: report.backtrace_hash,
+					app_version: report.app_version,
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::SESSION_CRASHED, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send session crashed: {e}");
+				}
+			}
+			S2CMsg::InputDeviceAdded { device } => {
+				let payload = InputDeviceAddedPayload { device };
+				if let Err(e) = TabMessageFrame::json(message_header::INPUT_DEVICE_ADDED, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send input device added: {e}");
+				}
+			}
+			S2CMsg::InputDeviceRemoved { device } => {
+				let payload = InputDeviceRemovedPayload { device };
+				if let Err(e) = TabMessageFrame::json(message_header::INPUT_DEVICE_REMOVED, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send input device removed: {e}");
+				}
+			}
 			S2CMsg::MonitorAdded { monitor } => {
 				let payload = MonitorAddedPayload {
 					monitor: monitor.to_protocol_info(),
@@ -415,6 +719,17 @@ impl Client {
 					tracing::warn!("failed to send monitor added: {e}");
 				}
 			}
+			S2CMsg::MonitorChanged { monitor } => {
+				let payload = MonitorChangedPayload {
+					monitor: monitor.to_protocol_info(),
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::MONITOR_CHANGED, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send monitor changed: {e}");
+				}
+			}
 			S2CMsg::MonitorRemoved { monitor_id, name } => {
 				let payload = MonitorRemovedPayload {
 					monitor_id: monitor_id.to_string(),
@@ -427,6 +742,73 @@ impl Client {
 					tracing::warn!("failed to send monitor removed: {e}");
 				}
 			}
+			S2CMsg::SessionPreview {
+				session_id,
+				preview,
+			} => {
+				let payload = SessionPreviewPayload {
+					session_id: session_id.to_string(),
+					monitor_id: preview.monitor_id.to_string(),
+					width: preview.width,
+					height: preview.height,
+					stride: preview.stride,
+					fourcc: preview.fourcc,
+				};
+				let mut frame = TabMessageFrame::json(message_header::SESSION_PREVIEW, payload);
+				frame.fds.push(preview.pixels.as_raw_fd());
+				if let Err(e) = frame.send_frame_to_async_fd(&self.socket).await {
+					tracing::warn!(%session_id, "failed to send session preview: {e}");
+				}
+			}
+			S2CMsg::SafeArea {
+				monitor_id,
+				occluded,
+			} => {
+				let payload = SafeAreaPayload {
+					monitor_id: monitor_id.to_string(),
+					occluded: occluded.to_vec(),
+					request_id: None,
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::SAFE_AREA, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send safe area: {e}");
+				}
+			}
+			S2CMsg::MonitorPower { monitor_id, power } => {
+				let payload = MonitorPowerPayload {
+					monitor_id: monitor_id.to_string(),
+					power,
+					request_id: None,
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::MONITOR_POWER, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send monitor power: {e}");
+				}
+			}
+			S2CMsg::Accessibility { settings } => {
+				let payload = AccessibilityPayload {
+					settings,
+					request_id: None,
+				};
+				if let Err(e) = TabMessageFrame::json(message_header::ACCESSIBILITY, payload)
+					.send_frame_to_async_fd(&self.socket)
+					.await
+				{
+					tracing::warn!("failed to send accessibility settings: {e}");
+				}
+			}
+			S2CMsg::Pong => {
+				let send_result = TabMessageFrame::no_payload(message_header::PONG)
+					.send_frame_to_async_fd(&self.socket)
+					.await;
+				if let Err(e) = send_result {
+					tracing::warn!("failed to send pong message back: {e}");
+				}
+			}
 		}
 	}
 	#[tracing::instrument(skip(self), fields(client.id = self.id().to_string()))]
@@ -446,7 +828,7 @@ impl Client {
 					read_frame_result = self.frame_reader.read_frame_from_async_fd(&self.socket) => match read_frame_result.and_then(TabMessage::try_from) {
 							Ok(packet) => self.handle_packet(packet).await,
 							Err(e) => {
-									self.send_error("protocol_violation", Some(e)).await;
+									self.send_error("protocol_violation", Some(e), None).await;
 									self.schedule_client_shutdown().await;
 							}
 					},
@@ -5,12 +5,16 @@ use crate::{
 	client_layer::client::{Client, ClientId},
 	comms::{
 		client2server::{C2SMsg, C2SRx, C2STx, C2SWeakTx},
+		render2server::SessionPreview,
 		server2client::{BufferRelease, S2CMsg, S2CRx, S2CTx},
 	},
 	monitor::{Monitor, MonitorId},
 	sessions::{PendingSession, Session, SessionId},
 };
-use tab_protocol::{InputEventPayload, SessionInfo};
+use tab_protocol::{
+	AccessibilitySettings, CrashReportPayload, InputDeviceInfo, MonitorPower, Rect, SeatId,
+	SeatInputEventPayload, SessionInfo,
+};
 
 #[derive(Debug)]
 pub struct ChannelsServerEnd(C2SRx, S2CTx);
@@ -94,11 +98,16 @@ impl ClientView {
 			.await
 			.is_ok()
 	}
-	pub async fn notify_session_created(&mut self, token: Token, session: PendingSession) -> bool {
+	pub async fn notify_session_created(
+		&mut self,
+		token: Token,
+		session: PendingSession,
+		request_id: Option<u64>,
+	) -> bool {
 		self
 			.channels
 			.1
-			.send(S2CMsg::SessionCreated(token, session))
+			.send(S2CMsg::SessionCreated(token, session, request_id))
 			.await
 			.is_ok()
 	}
@@ -116,6 +125,27 @@ impl ClientView {
 				code,
 				error,
 				shutdown,
+				request_id: None,
+			})
+			.await
+			.is_ok()
+	}
+
+	/// Rejects a request that carried a `request_id`, echoing it back to the client.
+	pub async fn notify_request_error(
+		&mut self,
+		code: Arc<str>,
+		error: Option<Arc<str>>,
+		request_id: Option<u64>,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::Error {
+				code,
+				error,
+				shutdown: false,
+				request_id,
 			})
 			.await
 			.is_ok()
@@ -134,6 +164,74 @@ impl ClientView {
 			.is_ok()
 	}
 
+	pub async fn notify_overlay_release(
+		&mut self,
+		overlay_id: String,
+		buffer: tab_protocol::BufferIndex,
+		release_fence: Option<std::os::fd::OwnedFd>,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::OverlayRelease {
+				overlay_id,
+				buffer,
+				release_fence,
+			})
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_capture_frame(
+		&mut self,
+		capture_id: String,
+		buffer: tab_protocol::BufferIndex,
+		time_usec: u64,
+		ready_fence: Option<std::os::fd::OwnedFd>,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::CaptureFrame {
+				capture_id,
+				buffer,
+				time_usec,
+				ready_fence,
+			})
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_capture_ended(&mut self, capture_id: String, reason: Arc<str>) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::CaptureEnded { capture_id, reason })
+			.await
+			.is_ok()
+	}
+
+	/// Rejects a buffer request, naming it so the client fails only that request.
+	pub async fn notify_buffer_request_error(
+		&mut self,
+		monitor_id: MonitorId,
+		buffer: tab_protocol::BufferIndex,
+		code: Arc<str>,
+		error: Option<Arc<str>>,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::BufferRequestError {
+				monitor_id,
+				buffer,
+				code,
+				error,
+			})
+			.await
+			.is_ok()
+	}
+
 	pub async fn notify_buffer_request_ack(
 		&mut self,
 		monitor_id: MonitorId,
@@ -156,6 +254,15 @@ impl ClientView {
 			.is_ok()
 	}
 
+	pub async fn notify_monitor_changed(&mut self, monitor: Monitor) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::MonitorChanged { monitor })
+			.await
+			.is_ok()
+	}
+
 	pub async fn notify_monitor_removed(&mut self, monitor_id: MonitorId, name: Arc<str>) -> bool {
 		self
 			.channels
@@ -165,6 +272,56 @@ impl ClientView {
 			.is_ok()
 	}
 
+	pub async fn notify_safe_area(&mut self, monitor_id: MonitorId, occluded: Arc<[Rect]>) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::SafeArea {
+				monitor_id,
+				occluded,
+			})
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_monitor_power(&mut self, monitor_id: MonitorId, power: MonitorPower) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::MonitorPower { monitor_id, power })
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_pong(&mut self) -> bool {
+		self.channels.1.send(S2CMsg::Pong).await.is_ok()
+	}
+
+	pub async fn notify_accessibility(&mut self, settings: AccessibilitySettings) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::Accessibility { settings })
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_session_preview(
+		&mut self,
+		session_id: SessionId,
+		preview: SessionPreview,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::SessionPreview {
+				session_id,
+				preview,
+			})
+			.await
+			.is_ok()
+	}
+
 	pub async fn notify_session_awake(&mut self, session_id: SessionId) -> bool {
 		self
 			.channels
@@ -201,7 +358,25 @@ impl ClientView {
 			.is_ok()
 	}
 
-	pub async fn notify_input_event(&mut self, event: InputEventPayload) -> bool {
+	pub async fn notify_input_device_added(&mut self, device: InputDeviceInfo) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::InputDeviceAdded { device })
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_input_device_removed(&mut self, device: u32) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::InputDeviceRemoved { device })
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_input_event(&mut self, event: SeatInputEventPayload) -> bool {
 		self
 			.channels
 			.1
@@ -209,4 +384,55 @@ impl ClientView {
 			.await
 			.is_ok()
 	}
+
+	pub async fn notify_global_shortcut(
+		&mut self,
+		shortcut_id: String,
+		seat: SeatId,
+		time_usec: u64,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::GlobalShortcut {
+				shortcut_id,
+				seat,
+				time_usec,
+			})
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_session_message(
+		&mut self,
+		from: SessionId,
+		correlation_id: Option<u64>,
+		reply_to: Option<u64>,
+		data: Vec<u8>,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::SessionMessage {
+				from,
+				correlation_id,
+				reply_to,
+				data,
+			})
+			.await
+			.is_ok()
+	}
+
+	pub async fn notify_session_crashed(
+		&mut self,
+		session_id: SessionId,
+		report: CrashReportPayload,
+	) -> bool {
+		self
+			.channels
+			.1
+			.send(S2CMsg::SessionCrashed { session_id, report })
+			.await
+			.is_ok()
+	}
 }
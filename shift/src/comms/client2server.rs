@@ -1,18 +1,44 @@
 use std::os::fd::OwnedFd;
 
 use tab_protocol::{
-	BufferIndex, FramebufferLinkPayload, SessionCreatePayload, SessionReadyPayload,
-	SessionSwitchPayload,
+	AccessibilityPayload, BufferIndex, CaptureReleasePayload, CaptureStartPayload,
+	CaptureStopPayload, CrashReportPayload, FramebufferLinkPayload, GlobalShortcutRegisterPayload,
+	GlobalShortcutUnregisterPayload, IdleInhibitPayload, InputInjectPayload, KeyboardGrabPayload,
+	MonitorBrightnessPayload, MonitorGammaPayload, MonitorModeSetPayload, MonitorPowerPayload,
+	MonitorPrimarySetPayload, OverlayCreatePayload, OverlayDestroyPayload, OverlayMovePayload,
+	OverlayPresentPayload, SafeAreaPayload, ScanoutHintPayload, SessionCreatePayload,
+	SessionMessageSendPayload, SessionPreviewRequestPayload, SessionReadyPayload,
+	SessionSwitchPayload, SessionUpdatePayload, SurfaceColorspacePayload,
 };
 
 use crate::{auth::Token, monitor::MonitorId};
 #[derive(Debug)]
 pub enum C2SMsg {
 	Shutdown,
+	/// Answered with `S2CMsg::Pong` from the server loop.
+	Ping,
 	Auth(Token),
 	CreateSession(SessionCreatePayload),
 	SwitchSession(SessionSwitchPayload),
 	SessionReady(SessionReadyPayload),
+	SessionUpdate(SessionUpdatePayload),
+	SessionPreviewRequest(SessionPreviewRequestPayload),
+	IdleInhibit(IdleInhibitPayload),
+	GlobalShortcutRegister(GlobalShortcutRegisterPayload),
+	GlobalShortcutUnregister(GlobalShortcutUnregisterPayload),
+	SessionMessageSend(SessionMessageSendPayload),
+	CrashReport(CrashReportPayload),
+	KeyboardGrab(KeyboardGrabPayload),
+	InputInject(InputInjectPayload),
+	MonitorModeSet(MonitorModeSetPayload),
+	MonitorPrimarySet(MonitorPrimarySetPayload),
+	MonitorBrightness(MonitorBrightnessPayload),
+	MonitorGamma(MonitorGammaPayload),
+	MonitorPower(MonitorPowerPayload),
+	Accessibility(AccessibilityPayload),
+	ScanoutHint(ScanoutHintPayload),
+	SurfaceColorspace(SurfaceColorspacePayload),
+	SafeArea(SafeAreaPayload),
 	BufferRequest {
 		monitor_id: MonitorId,
 		buffer: BufferIndex,
@@ -22,6 +48,22 @@ pub enum C2SMsg {
 		payload: FramebufferLinkPayload,
 		dma_bufs: [OwnedFd; 2],
 	},
+	OverlayCreate {
+		payload: OverlayCreatePayload,
+		dma_bufs: [OwnedFd; 2],
+	},
+	OverlayMove(OverlayMovePayload),
+	OverlayPresent {
+		payload: OverlayPresentPayload,
+		acquire_fence: Option<OwnedFd>,
+	},
+	OverlayDestroy(OverlayDestroyPayload),
+	CaptureStart {
+		payload: CaptureStartPayload,
+		dma_bufs: [OwnedFd; 2],
+	},
+	CaptureStop(CaptureStopPayload),
+	CaptureRelease(CaptureReleasePayload),
 }
 
 pub type C2SRx = tokio::sync::mpsc::Receiver<C2SMsg>;
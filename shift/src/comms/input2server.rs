@@ -1,10 +1,12 @@
 use std::sync::Arc;
 
-use tab_protocol::InputEventPayload;
+use tab_protocol::{InputDeviceInfo, SeatInputEventPayload};
 
 #[derive(Debug, Clone)]
 pub enum InputEvt {
-	Event(InputEventPayload),
+	Event(SeatInputEventPayload),
+	DeviceAdded(InputDeviceInfo),
+	DeviceRemoved { device: u32 },
 	FatalError { reason: Arc<str> },
 }
 
@@ -1,13 +1,25 @@
 use std::os::fd::OwnedFd;
 use std::sync::Arc;
 
-use tab_protocol::BufferIndex;
+use tab_protocol::{BufferIndex, DrmDeviceInfo, MonitorPower};
 
 use crate::{
+	client_layer::client::ClientId,
 	monitor::{Monitor, MonitorId},
 	sessions::SessionId,
 };
 
+/// Downscaled copy of a session's frame, held in a memfd.
+#[derive(Debug)]
+pub struct SessionPreview {
+	pub monitor_id: MonitorId,
+	pub width: i32,
+	pub height: i32,
+	pub stride: i32,
+	pub fourcc: i32,
+	pub pixels: OwnedFd,
+}
+
 /// Events emitted by the rendering layer back into the server core.
 #[derive(Debug)]
 pub enum RenderEvt {
@@ -15,11 +27,20 @@ pub enum RenderEvt {
 	Started {
 		/// Initial monitors when shift started
 		monitors: Vec<Monitor>,
+		/// GPU the renderer composites on, if it could be identified
+		drm_device: Option<DrmDeviceInfo>,
 	},
 	/// The user plugged in a new monitor
 	MonitorOnline { monitor: Monitor },
 	/// The user unplugged a monitor
 	MonitorOffline { monitor_id: MonitorId },
+	/// A monitor's mode or properties changed without reconnecting
+	MonitorChanged { monitor: Monitor },
+	/// A monitor's display changed power state
+	MonitorPowerChanged {
+		monitor_id: MonitorId,
+		power: MonitorPower,
+	},
 	/// Rendering reported an unrecoverable condition.
 	FatalError { reason: Arc<str> },
 	/// Some monitors just page flipped and are ready to be commited to again
@@ -44,6 +65,33 @@ pub enum RenderEvt {
 		buffer: BufferIndex,
 		reason: Arc<str>,
 	},
+	/// An overlay buffer is no longer displayed and can be reused by the client.
+	OverlayReleased {
+		session_id: SessionId,
+		overlay_id: String,
+		buffer: BufferIndex,
+		release_fence: Option<OwnedFd>,
+	},
+	/// A capture buffer holds a new frame, ready once `ready_fence` signals.
+	CaptureFrame {
+		session_id: SessionId,
+		capture_id: String,
+		buffer: BufferIndex,
+		time_usec: u64,
+		ready_fence: Option<OwnedFd>,
+	},
+	/// A capture stream stopped because its source went away.
+	CaptureEnded {
+		session_id: SessionId,
+		capture_id: String,
+		reason: Arc<str>,
+	},
+	/// A preview requested by a client is ready, or could not be taken.
+	SessionPreview {
+		client_id: ClientId,
+		session_id: SessionId,
+		preview: Result<SessionPreview, Arc<str>>,
+	},
 }
 
 pub type RenderEvtRx = tokio::sync::mpsc::Receiver<RenderEvt>;
@@ -1,10 +1,14 @@
 use std::os::fd::OwnedFd;
 use std::sync::Arc;
 
-use tab_protocol::{BufferIndex, InputEventPayload, SessionInfo};
+use tab_protocol::{
+	AccessibilitySettings, BufferIndex, CrashReportPayload, InputDeviceInfo, MonitorPower, Rect,
+	SeatId, SeatInputEventPayload, SessionInfo,
+};
 
 use crate::{
 	auth::{self, Token},
+	comms::render2server::SessionPreview,
 	monitor::{Monitor, MonitorId},
 	sessions::{PendingSession, Session, SessionId},
 };
@@ -20,11 +24,20 @@ pub struct BufferRelease {
 pub enum S2CMsg {
 	BindToSession(Arc<Session>),
 	AuthError(auth::error::Error),
-	SessionCreated(Token, PendingSession),
+	SessionCreated(Token, PendingSession, Option<u64>),
 	Error {
 		code: Arc<str>,
 		error: Option<Arc<str>>,
 		shutdown: bool,
+		/// Id of the client request this error rejects.
+		request_id: Option<u64>,
+	},
+	/// Error rejecting the buffer request for `buffer` on `monitor_id`.
+	BufferRequestError {
+		monitor_id: MonitorId,
+		buffer: BufferIndex,
+		code: Arc<str>,
+		error: Option<Arc<str>>,
 	},
 	BufferRelease {
 		buffers: Vec<BufferRelease>,
@@ -33,6 +46,21 @@ pub enum S2CMsg {
 		monitor_id: MonitorId,
 		buffer: BufferIndex,
 	},
+	OverlayRelease {
+		overlay_id: String,
+		buffer: BufferIndex,
+		release_fence: Option<OwnedFd>,
+	},
+	CaptureFrame {
+		capture_id: String,
+		buffer: BufferIndex,
+		time_usec: u64,
+		ready_fence: Option<OwnedFd>,
+	},
+	CaptureEnded {
+		capture_id: String,
+		reason: Arc<str>,
+	},
 	SessionActive {
 		session_id: SessionId,
 	},
@@ -45,16 +73,57 @@ pub enum S2CMsg {
 	SessionSleep {
 		session_id: SessionId,
 	},
+	SessionPreview {
+		session_id: SessionId,
+		preview: SessionPreview,
+	},
 	InputEvent {
-		event: InputEventPayload,
+		event: SeatInputEventPayload,
+	},
+	GlobalShortcut {
+		shortcut_id: String,
+		seat: SeatId,
+		time_usec: u64,
+	},
+	SessionMessage {
+		from: SessionId,
+		correlation_id: Option<u64>,
+		reply_to: Option<u64>,
+		data: Vec<u8>,
+	},
+	SessionCrashed {
+		session_id: SessionId,
+		report: CrashReportPayload,
+	},
+	InputDeviceAdded {
+		device: InputDeviceInfo,
+	},
+	InputDeviceRemoved {
+		device: u32,
 	},
 	MonitorAdded {
 		monitor: Monitor,
 	},
+	MonitorChanged {
+		monitor: Monitor,
+	},
 	MonitorRemoved {
 		monitor_id: MonitorId,
 		name: Arc<str>,
 	},
+	SafeArea {
+		monitor_id: MonitorId,
+		occluded: Arc<[Rect]>,
+	},
+	MonitorPower {
+		monitor_id: MonitorId,
+		power: MonitorPower,
+	},
+	Accessibility {
+		settings: AccessibilitySettings,
+	},
+	/// Answer to `C2SMsg::Ping`.
+	Pong,
 }
 
 pub type S2CRx = tokio::sync::mpsc::Receiver<S2CMsg>;
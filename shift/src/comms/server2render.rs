@@ -1,9 +1,12 @@
 use std::os::fd::OwnedFd;
 use std::time::Duration;
 
-use tab_protocol::{BufferIndex, FramebufferLinkPayload};
+use tab_protocol::{
+	BufferIndex, CaptureStartPayload, Colorspace, FramebufferLinkPayload, GammaRamps, MonitorMode,
+	MonitorPower, OverlayCreatePayload,
+};
 
-use crate::{monitor::MonitorId, sessions::SessionId};
+use crate::{client_layer::client::ClientId, monitor::MonitorId, sessions::SessionId};
 
 #[derive(Debug, Clone)]
 pub struct SessionTransition {
@@ -36,6 +39,90 @@ pub enum RenderCmd {
 		session_id: SessionId,
 		acquire_fence: Option<OwnedFd>,
 	},
+	/// Switch a monitor to one of its advertised modes.
+	SetMonitorMode {
+		monitor_id: MonitorId,
+		mode: MonitorMode,
+	},
+	/// Switch a monitor's display on or off through DPMS.
+	SetMonitorPower {
+		monitor_id: MonitorId,
+		power: MonitorPower,
+	},
+	/// Scale a monitor's output; 1.0 is unmodified.
+	SetMonitorBrightness {
+		monitor_id: MonitorId,
+		brightness: f32,
+	},
+	/// Replace a monitor's gamma ramps, or reset them with `None`.
+	SetMonitorGamma {
+		monitor_id: MonitorId,
+		ramps: Option<GammaRamps>,
+	},
+	/// Mark a session's frames on a monitor as fullscreen and opaque.
+	SetScanoutHint {
+		monitor_id: MonitorId,
+		session_id: SessionId,
+		fullscreen_opaque: bool,
+	},
+	/// Tag the color space of a session's frames on a monitor.
+	SetSurfaceColorspace {
+		monitor_id: MonitorId,
+		session_id: SessionId,
+		colorspace: Colorspace,
+	},
+	/// Import an overlay's buffers; the monitor id inside the payload is already validated.
+	OverlayCreate {
+		monitor_id: MonitorId,
+		session_id: SessionId,
+		payload: OverlayCreatePayload,
+		dma_bufs: [OwnedFd; 2],
+	},
+	/// Move an overlay on its monitor.
+	OverlayMove {
+		session_id: SessionId,
+		overlay_id: String,
+		position: (i32, i32),
+	},
+	/// Show a new overlay buffer, releasing the previous one after the next commit.
+	OverlayPresent {
+		session_id: SessionId,
+		overlay_id: String,
+		buffer: BufferIndex,
+		acquire_fence: Option<OwnedFd>,
+	},
+	/// Drop an overlay and its GPU resources.
+	OverlayDestroy {
+		session_id: SessionId,
+		overlay_id: String,
+	},
+	/// Import a capture stream's buffers; `monitor_id` and `source_session` are the
+	/// validated ids from the payload's source.
+	CaptureStart {
+		session_id: SessionId,
+		monitor_id: MonitorId,
+		source_session: Option<SessionId>,
+		payload: CaptureStartPayload,
+		dma_bufs: [OwnedFd; 2],
+	},
+	/// Drop a capture stream and its GPU resources.
+	CaptureStop {
+		session_id: SessionId,
+		capture_id: String,
+	},
+	/// The client is done with a capture buffer; it may receive frames again.
+	CaptureRelease {
+		session_id: SessionId,
+		capture_id: String,
+		buffer: BufferIndex,
+	},
+	/// Snapshot a session's latest frame, scaled to fit `max_size`, for a client.
+	SessionPreview {
+		client_id: ClientId,
+		session_id: SessionId,
+		monitor_id: Option<MonitorId>,
+		max_size: (i32, i32),
+	},
 }
 
 pub type RenderCmdRx = tokio::sync::mpsc::Receiver<RenderCmd>;
@@ -12,7 +12,7 @@ use std::{
 };
 
 use input::{
-	DeviceConfigError, Libinput, LibinputInterface, TapButtonMap,
+	DeviceCapability, DeviceConfigError, Libinput, LibinputInterface, TapButtonMap,
 	event::{
 		Event, EventTrait, GestureEvent, KeyboardEvent, PointerEvent, SwitchEvent, TouchEvent,
 		device::DeviceEvent,
@@ -29,9 +29,9 @@ use input::{
 	},
 };
 use tab_protocol::{
-	AxisOrientation, AxisSource, ButtonState, InputEventPayload, KeyState, SwitchState, SwitchType,
-	TabletTool, TabletToolAxes, TabletToolCapability, TabletToolType, TipState as ProtoTipState,
-	TouchContact,
+	AxisOrientation, AxisSource, ButtonState, InputDeviceCapability, InputDeviceInfo,
+	InputEventPayload, KeyState, SeatId, SeatInputEventPayload, SwitchState, SwitchType, TabletTool,
+	TabletToolAxes, TabletToolCapability, TabletToolType, TipState as ProtoTipState, TouchContact,
 };
 use thiserror::Error;
 
@@ -148,13 +148,31 @@ fn run_blocking(
 			return Err(e.into());
 		}
 		for event in &mut input {
-			if let Event::Device(DeviceEvent::Added(added)) = &event {
-				let mut device = added.device();
-				configure_device_tap(&mut device, input_config);
+			if let Event::Device(device_event) = &event {
+				let evt = match device_event {
+					DeviceEvent::Added(added) => {
+						let mut device = added.device();
+						configure_device_tap(&mut device, input_config);
+						InputEvt::DeviceAdded(device_info(&device))
+					}
+					DeviceEvent::Removed(removed) => InputEvt::DeviceRemoved {
+						device: stable_device_id(&removed.device()),
+					},
+					_ => continue,
+				};
+				if event_tx.blocking_send(evt).is_err() {
+					return Ok(());
+				}
+				continue;
 			}
+			let seat = seat_id(&event);
 			let Some(payload) = map_event(event) else {
 				continue;
 			};
+			let payload = SeatInputEventPayload {
+				seat,
+				event: payload,
+			};
 			if event_tx.blocking_send(InputEvt::Event(payload)).is_err() {
 				return Ok(());
 			}
@@ -542,8 +560,44 @@ fn map_tablet_tool_type(tool_type: Option<tablet_tool::TabletToolType>) -> Table
 	}
 }
 
+fn seat_id(event: &impl EventTrait) -> SeatId {
+	SeatId(event.device().seat().logical_name().to_string())
+}
+
+fn device_info(device: &input::Device) -> InputDeviceInfo {
+	const CAPABILITIES: [(DeviceCapability, InputDeviceCapability); 7] = [
+		(DeviceCapability::Keyboard, InputDeviceCapability::Keyboard),
+		(DeviceCapability::Pointer, InputDeviceCapability::Pointer),
+		(DeviceCapability::Touch, InputDeviceCapability::Touch),
+		(
+			DeviceCapability::TabletTool,
+			InputDeviceCapability::TabletTool,
+		),
+		(
+			DeviceCapability::TabletPad,
+			InputDeviceCapability::TabletPad,
+		),
+		(DeviceCapability::Gesture, InputDeviceCapability::Gesture),
+		(DeviceCapability::Switch, InputDeviceCapability::Switch),
+	];
+	InputDeviceInfo {
+		id: stable_device_id(device),
+		name: device.name().to_string(),
+		seat: SeatId(device.seat().logical_name().to_string()),
+		capabilities: CAPABILITIES
+			.into_iter()
+			.filter(|(cap, _)| device.has_capability(*cap))
+			.map(|(_, cap)| cap)
+			.collect(),
+		output_name: device.output_name().map(str::to_string),
+	}
+}
+
 fn device_id(event: &impl EventTrait) -> u32 {
-	let device = event.device();
+	stable_device_id(&event.device())
+}
+
+fn stable_device_id(device: &input::Device) -> u32 {
 	let sysname = device.sysname();
 	let mut hash = 2166136261u32;
 	for b in sysname.as_bytes() {
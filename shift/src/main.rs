@@ -32,9 +32,21 @@ async fn main() {
 		.init();
 
 	// ---- socket path ----
+	// SHIFT_SEAT gives each shift instance of a multi-seat system its own socket.
+	let seat_socket = std::env::var("SHIFT_SEAT")
+		.ok()
+		.filter(|seat| !seat.is_empty())
+		.and_then(|seat| tab_protocol::unix_socket_utils::seat_socket_path(&seat));
 	let socket_path = std::env::var_os("SHIFT_SOCKET")
+		.filter(|path| !path.is_empty())
 		.map(PathBuf::from)
-		.unwrap_or_else(|| "/tmp/shift.sock".into());
+		.or(seat_socket)
+		.unwrap_or_else(|| tab_protocol::DEFAULT_SOCKET_PATH.into());
+	if let Some(dir) = socket_path.parent()
+		&& let Err(e) = std::fs::create_dir_all(dir)
+	{
+		tracing::warn!("failed to create socket directory {:?}: {e}", dir);
+	}
 
 	// ---- create inter-layer channels ----
 	let render_channels = RenderChannels::new();
@@ -1,14 +1,19 @@
 use crate::define_id_type;
-use tab_protocol::MonitorInfo as ProtocolMonitorInfo;
+use tab_protocol::{MonitorColorInfo, MonitorInfo as ProtocolMonitorInfo, MonitorMode};
 
 define_id_type!(Monitor, "mon_");
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Monitor {
 	pub id: MonitorId,
 	pub width: i32,
 	pub height: i32,
 	pub refresh_rate: u32,
 	pub name: String,
+	pub modes: Vec<MonitorMode>,
+	pub color: Option<MonitorColorInfo>,
+	pub scale: f64,
+	/// Set by the server from the primary monitor an admin session chose.
+	pub primary: bool,
 }
 
 impl Monitor {
@@ -19,6 +24,10 @@ impl Monitor {
 			height: self.height,
 			refresh_rate: self.refresh_rate as i32,
 			name: self.name.clone(),
+			modes: self.modes.clone(),
+			color: self.color.clone(),
+			scale: self.scale,
+			primary: self.primary,
 		}
 	}
 }
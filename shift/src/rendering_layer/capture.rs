@@ -0,0 +1,227 @@
+use std::{
+	ffi::c_void,
+	mem,
+	os::fd::{FromRawFd, OwnedFd},
+	sync::Arc,
+};
+
+use easydrm::gl;
+use skia_safe::{FilterMode, MipmapMode, Paint, Rect, SamplingOptions, Surface};
+use tab_protocol::BufferIndex;
+use tracing::warn;
+
+use super::dmabuf_import::SkiaDmaBufTexture;
+use super::state::{SlotKey, SlotOwner};
+use super::{RenderEvt, RenderingLayer, egl};
+use crate::{monitor::MonitorId, sessions::SessionId};
+
+/// Captures are named by their admin session and the client-chosen capture id.
+pub(super) type CaptureKey = (SessionId, String);
+
+/// Stream of a monitor's output, or of one session's frames on it, into client buffers.
+pub(super) struct Capture {
+	pub monitor_id: MonitorId,
+	/// Session whose frames are captured; `None` captures the composited output.
+	pub source_session: Option<SessionId>,
+	// Declared before the textures so the surfaces are dropped first.
+	surfaces: [Surface; 2],
+	_textures: [SkiaDmaBufTexture; 2],
+	/// Buffers handed to the client and not released yet.
+	held: [bool; 2],
+	/// Session frame captured last, so unchanged frames are not sent again.
+	last_frame: Option<SlotKey>,
+}
+
+impl Capture {
+	pub fn new(
+		monitor_id: MonitorId,
+		source_session: Option<SessionId>,
+		surfaces: [Surface; 2],
+		textures: [SkiaDmaBufTexture; 2],
+	) -> Self {
+		Self {
+			monitor_id,
+			source_session,
+			surfaces,
+			_textures: textures,
+			held: [false; 2],
+			last_frame: None,
+		}
+	}
+
+	pub fn release(&mut self, buffer: BufferIndex) {
+		self.held[buffer as usize] = false;
+	}
+
+	fn free_buffer(&self) -> Option<BufferIndex> {
+		[BufferIndex::Zero, BufferIndex::One]
+			.into_iter()
+			.find(|buffer| !self.held[*buffer as usize])
+	}
+}
+
+impl RenderingLayer {
+	/// Copies new source frames into free capture buffers and hands them to their clients.
+	///
+	/// Runs after the monitors were drawn and before they are committed, so composited
+	/// output can still be read from the monitor surfaces.
+	pub(super) async fn capture_frames(&mut self) {
+		let drawn = self
+			.drm
+			.monitors()
+			.filter(|mon| mon.was_drawn())
+			.map(|mon| mon.context().id)
+			.collect::<Vec<_>>();
+		let time_usec = monotonic_usec();
+		let keys = self.captures.keys().cloned().collect::<Vec<_>>();
+		for key in keys {
+			let Some(capture) = self.captures.get_mut(&key) else {
+				continue;
+			};
+			let Some(buffer) = capture.free_buffer() else {
+				continue;
+			};
+			let monitor_id = capture.monitor_id;
+			let Some(mon) = self
+				.drm
+				.monitors_mut()
+				.find(|mon| mon.context().id == monitor_id)
+			else {
+				continue;
+			};
+			if let Err(e) = mon.make_current() {
+				warn!(%monitor_id, "make_current failed for capture: {e:?}");
+				continue;
+			}
+			let image = match capture.source_session {
+				Some(source) => {
+					let Some(slot) = self
+						.ownership
+						.current_slot_key_for_session(monitor_id, source)
+						.filter(|slot| self.ownership.owner(*slot) == Some(SlotOwner::ShiftOwned))
+						.filter(|slot| capture.last_frame != Some(*slot))
+					else {
+						continue;
+					};
+					capture.last_frame = Some(slot);
+					Self::slot_image(&mut self.slots, &mut self.gr, slot)
+				}
+				None if drawn.contains(&monitor_id) => {
+					let context = mon.context_mut();
+					let target_fbo = context.target_fbo;
+					context
+						.surfaces_by_fbo
+						.get_mut(&target_fbo)
+						.map(|surface| surface.image_snapshot())
+				}
+				None => continue,
+			};
+			let Some(image) = image else {
+				continue;
+			};
+
+			let surface = &mut capture.surfaces[buffer as usize];
+			let target = Rect::from_iwh(surface.width(), surface.height());
+			let sampling = SamplingOptions::new(FilterMode::Linear, MipmapMode::None);
+			surface.canvas().draw_image_rect_with_sampling_options(
+				&image,
+				None,
+				target,
+				sampling,
+				&Paint::default(),
+			);
+			self.gr.flush_and_submit();
+			let gl = mon.context().gl.clone();
+			let egl_context = self.drm.egl_context();
+			let proc_loader = |symbol: &str| {
+				egl_context
+					.lock()
+					.map(|ctx| ctx.get_proc_address(symbol))
+					.unwrap_or(std::ptr::null())
+			};
+			let ready_fence = native_fence(&gl, &proc_loader);
+			if ready_fence.is_none() {
+				// Without a fence to hand out, the buffer must be complete before it is sent.
+				unsafe { gl.Finish() };
+			}
+			capture.held[buffer as usize] = true;
+			let (session_id, capture_id) = key;
+			self
+				.emit_event(RenderEvt::CaptureFrame {
+					session_id,
+					capture_id,
+					buffer,
+					time_usec,
+					ready_fence,
+				})
+				.await;
+		}
+
+		for (session_id, capture_id, reason) in mem::take(&mut self.ended_captures) {
+			self
+				.emit_event(RenderEvt::CaptureEnded {
+					session_id,
+					capture_id,
+					reason,
+				})
+				.await;
+		}
+	}
+
+	/// Drops captures whose monitor or source session went away and queues their
+	/// `CaptureEnded` events; captures owned by `removed_session` are dropped silently.
+	pub(super) fn end_captures(
+		&mut self,
+		removed_monitor: Option<MonitorId>,
+		removed_session: Option<SessionId>,
+	) {
+		let ended = &mut self.ended_captures;
+		self.captures.retain(|(owner, capture_id), capture| {
+			if removed_session == Some(*owner) {
+				return false;
+			}
+			let reason: Arc<str> = if removed_monitor == Some(capture.monitor_id) {
+				"monitor removed".into()
+			} else if removed_session.is_some() && capture.source_session == removed_session {
+				"session ended".into()
+			} else {
+				return true;
+			};
+			ended.push((*owner, capture_id.clone(), reason));
+			false
+		});
+	}
+}
+
+/// Inserts a native fence after the commands submitted so far and exports it as a sync
+/// file, if the driver supports `EGL_ANDROID_native_fence_sync`.
+fn native_fence(gl: &gl::Gles2, proc_resolver: &dyn Fn(&str) -> *const c_void) -> Option<OwnedFd> {
+	let egl = egl::Egl::load_with(|name| proc_resolver(name));
+	if !(egl.CreateSyncKHR.is_loaded() && egl.DupNativeFenceFDANDROID.is_loaded()) {
+		return None;
+	}
+	let display = unsafe { egl.GetCurrentDisplay() };
+	if display.is_null() {
+		return None;
+	}
+	let attrs = [egl::NONE as egl::types::EGLint];
+	let sync = unsafe { egl.CreateSyncKHR(display, egl::SYNC_NATIVE_FENCE_ANDROID, attrs.as_ptr()) };
+	if sync.is_null() {
+		return None;
+	}
+	// The fence only gets a file descriptor once it was flushed to the GPU.
+	unsafe { gl.Flush() };
+	let fd = unsafe { egl.DupNativeFenceFDANDROID(display, sync) };
+	unsafe { egl.DestroySyncKHR(display, sync) };
+	(fd >= 0).then(|| unsafe { OwnedFd::from_raw_fd(fd) })
+}
+
+/// `CLOCK_MONOTONIC` in microseconds, the clock of libinput event timestamps.
+pub(crate) fn monotonic_usec() -> u64 {
+	let mut ts = libc::timespec {
+		tv_sec: 0,
+		tv_nsec: 0,
+	};
+	unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+	ts.tv_sec as u64 * 1_000_000 + ts.tv_nsec as u64 / 1_000
+}
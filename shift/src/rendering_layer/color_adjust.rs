@@ -0,0 +1,82 @@
+use skia_safe::{ColorFilter, Paint, color_filters};
+use tab_protocol::GammaRamps;
+
+/// Per-monitor brightness and gamma applied when compositing the frame.
+///
+/// Both fold into one 8-bit table color filter: the 16-bit ramps are quantized to 256
+/// entries, and neither the backlight nor the CRTC `GAMMA_LUT` is programmed. An active
+/// adjustment rules out passing a frame through without composition.
+#[derive(Debug)]
+pub(super) struct ColorAdjustment {
+	brightness: f32,
+	gamma: Option<GammaRamps>,
+	paint: Option<Paint>,
+}
+
+impl Default for ColorAdjustment {
+	fn default() -> Self {
+		Self {
+			brightness: 1.0,
+			gamma: None,
+			paint: None,
+		}
+	}
+}
+
+impl ColorAdjustment {
+	pub fn set_brightness(&mut self, brightness: f32) {
+		self.brightness = brightness.clamp(0.0, 1.0);
+		self.rebuild();
+	}
+
+	pub fn set_gamma(&mut self, gamma: Option<GammaRamps>) {
+		self.gamma = gamma;
+		self.rebuild();
+	}
+
+	/// Layer paint carrying the color filter, `None` when no adjustment is active.
+	pub fn paint(&self) -> Option<&Paint> {
+		self.paint.as_ref()
+	}
+
+	fn rebuild(&mut self) {
+		self.paint = self.color_filter().map(|filter| {
+			let mut paint = Paint::default();
+			paint.set_color_filter(filter);
+			paint
+		});
+	}
+
+	fn color_filter(&self) -> Option<ColorFilter> {
+		if self.brightness >= 1.0 && self.gamma.is_none() {
+			return None;
+		}
+		let (red, green, blue) = match &self.gamma {
+			Some(ramps) => (
+				channel_table(&ramps.red, self.brightness),
+				channel_table(&ramps.green, self.brightness),
+				channel_table(&ramps.blue, self.brightness),
+			),
+			None => {
+				let table = channel_table(&[0, u16::MAX], self.brightness);
+				(table, table, table)
+			}
+		};
+		color_filters::table_argb(None::<&[u8; 256]>, &red, &green, &blue)
+	}
+}
+
+/// Samples a 16-bit ramp of any length into an 8-bit lookup table scaled by `brightness`.
+fn channel_table(ramp: &[u16], brightness: f32) -> [u8; 256] {
+	let mut table = [0u8; 256];
+	let last = ramp.len().saturating_sub(1);
+	for (i, entry) in table.iter_mut().enumerate() {
+		let pos = i as f32 * last as f32 / 255.0;
+		let lo = pos.floor() as usize;
+		let hi = (lo + 1).min(last);
+		let frac = pos - lo as f32;
+		let value = ramp[lo] as f32 * (1.0 - frac) + ramp[hi] as f32 * frac;
+		*entry = (value / u16::MAX as f32 * brightness * 255.0).round() as u8;
+	}
+	table
+}
@@ -0,0 +1,17 @@
+use skia_safe::{ColorSpace, named_gamut, named_transfer_fn};
+use tab_protocol::Colorspace;
+
+/// Skia color space for frames tagged with `colorspace`.
+///
+/// Monitor surfaces are sRGB, so sRGB frames stay untagged and are drawn without a
+/// conversion step.
+pub(super) fn skia_color_space(colorspace: Colorspace) -> Option<ColorSpace> {
+	match colorspace {
+		Colorspace::Srgb => None,
+		Colorspace::DisplayP3 => {
+			ColorSpace::new_rgb(&named_transfer_fn::SRGB, &named_gamut::DISPLAY_P3)
+		}
+		Colorspace::Bt2020Pq => ColorSpace::new_rgb(&named_transfer_fn::PQ, &named_gamut::REC2020),
+		Colorspace::Bt2020Hlg => ColorSpace::new_rgb(&named_transfer_fn::HLG, &named_gamut::REC2020),
+	}
+}
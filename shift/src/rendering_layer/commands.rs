@@ -3,9 +3,16 @@ use std::{
 	sync::Arc,
 };
 
+use skia_safe::{ColorType, gpu};
+use tab_protocol::BufferMemory;
+
 use crate::comms::server2render::RenderCmd;
 
-use super::dmabuf_import::{DmaBufTexture, ImportParams as DmaBufImportParams};
+use super::capture::Capture;
+use super::colorspace::skia_color_space;
+use super::dmabuf_import::{DmaBufTexture, ImportParams as DmaBufImportParams, SkiaDmaBufTexture};
+use super::overlay::Overlay;
+use super::shm_import::{ShmTexture, SlotTexture};
 use super::state::BufferSlot;
 use super::{RenderError, RenderEvt, RenderingLayer, SlotKey};
 
@@ -22,61 +29,221 @@ impl RenderingLayer {
 			return;
 		};
 
+		let imported = match payload.memory {
+			BufferMemory::Dmabuf => self
+				.import_dmabufs(
+					monitor_id,
+					dma_bufs,
+					|fd| DmaBufImportParams {
+						width: payload.width,
+						height: payload.height,
+						stride: payload.stride,
+						offset: payload.offset,
+						fourcc: payload.fourcc,
+						fd,
+					},
+					|idx| format!("session_{session_id}_monitor_{monitor_id}_buffer_{idx}"),
+				)
+				.map(|imported| {
+					imported
+						.into_iter()
+						.map(|(slot, texture)| (slot, SlotTexture::DmaBuf(texture)))
+						.collect::<Vec<_>>()
+				}),
+			BufferMemory::Shm => self
+				.known_monitors
+				.get(&monitor_id)
+				.map(|monitor| Self::import_shm_buffers(&payload, dma_bufs, monitor)),
+		};
+		let Some(imported) = imported else {
+			tracing::warn!(%monitor_id, "framebuffer link for unknown monitor");
+			return;
+		};
+
+		let color_space = self
+			.surface_colorspaces
+			.get(&(monitor_id, session_id))
+			.and_then(|colorspace| skia_color_space(*colorspace));
+		for (slot, mut texture) in imported {
+			let key = SlotKey::new(monitor_id, session_id, slot);
+			texture.set_color_space(color_space.clone());
+			self.slots.insert(key, texture);
+			self.ownership.mark_slot_client_owned(key);
+		}
+	}
+
+	/// Maps a pair of shm buffers; their contents are uploaded when first drawn.
+	fn import_shm_buffers(
+		payload: &tab_protocol::FramebufferLinkPayload,
+		shm_bufs: [OwnedFd; 2],
+		monitor: &crate::monitor::Monitor,
+	) -> Vec<(BufferSlot, SlotTexture)> {
 		let mut imported = Vec::new();
-		let mut found_monitor = false;
-		let egl_context = self.drm.egl_context();
-		for mon in self.drm.monitors_mut() {
-			if mon.context().id != monitor_id {
+		for (idx, fd) in shm_bufs.into_iter().enumerate() {
+			let Some(slot) = BufferSlot::from_index(idx) else {
 				continue;
+			};
+			match ShmTexture::new(
+				fd,
+				payload.width,
+				payload.height,
+				payload.stride,
+				payload.offset,
+				payload.fourcc,
+				(monitor.width, monitor.height),
+			) {
+				Ok(texture) => imported.push((slot, SlotTexture::Shm(texture))),
+				Err(e) => {
+					tracing::warn!(
+						monitor_id = %payload.monitor_id,
+						?slot,
+						"failed to import shm buffer: {e}"
+					);
+				}
 			}
-			found_monitor = true;
-			if let Err(e) = mon.make_current() {
-				tracing::warn!(%monitor_id, "failed to make monitor current: {e:?}");
-				break;
-			}
-			let gl = mon.context().gl.clone();
-			let proc_loader = |symbol: &str| {
-				egl_context
-					.lock()
-					.map(|ctx| ctx.get_proc_address(symbol))
-					.unwrap_or(std::ptr::null())
+		}
+		imported
+	}
+
+	/// Imports a pair of dmabufs on the monitor's GL context.
+	///
+	/// Returns `None` when the monitor is unknown; buffers that fail to import are skipped.
+	fn import_dmabufs(
+		&mut self,
+		monitor_id: crate::monitor::MonitorId,
+		dma_bufs: [OwnedFd; 2],
+		params: impl Fn(OwnedFd) -> DmaBufImportParams,
+		label: impl Fn(usize) -> String,
+	) -> Option<Vec<(BufferSlot, SkiaDmaBufTexture)>> {
+		let egl_context = self.drm.egl_context();
+		let mon = self
+			.drm
+			.monitors_mut()
+			.find(|mon| mon.context().id == monitor_id)?;
+		let mut imported = Vec::new();
+		if let Err(e) = mon.make_current() {
+			tracing::warn!(%monitor_id, "failed to make monitor current: {e:?}");
+			return Some(imported);
+		}
+		let gl = mon.context().gl.clone();
+		let proc_loader = |symbol: &str| {
+			egl_context
+				.lock()
+				.map(|ctx| ctx.get_proc_address(symbol))
+				.unwrap_or(std::ptr::null())
+		};
+		for (idx, fd) in dma_bufs.into_iter().enumerate() {
+			let Some(slot) = BufferSlot::from_index(idx) else {
+				continue;
 			};
-			for (idx, fd) in dma_bufs.into_iter().enumerate() {
-				let Some(slot) = BufferSlot::from_index(idx) else {
-					continue;
-				};
-				let params = DmaBufImportParams {
-					width: payload.width,
-					height: payload.height,
-					stride: payload.stride,
-					offset: payload.offset,
-					fourcc: payload.fourcc,
-					fd,
-				};
-				match DmaBufTexture::import(&gl, &proc_loader, params).and_then(|texture| {
-					texture.to_skia(format!(
-						"session_{}_monitor_{}_buffer_{}",
-						session_id, monitor_id, idx
-					))
-				}) {
-					Ok(texture) => imported.push((slot, texture)),
-					Err(e) => {
-						tracing::warn!(%monitor_id, ?slot, "failed to import dmabuf: {e:?}");
-					}
+			match DmaBufTexture::import(&gl, &proc_loader, params(fd))
+				.and_then(|texture| texture.to_skia(label(idx)))
+			{
+				Ok(texture) => imported.push((slot, texture)),
+				Err(e) => {
+					tracing::warn!(%monitor_id, ?slot, "failed to import dmabuf: {e:?}");
 				}
 			}
-			break;
 		}
+		Some(imported)
+	}
 
-		if !found_monitor {
-			tracing::warn!(%monitor_id, "framebuffer link for unknown monitor");
+	#[tracing::instrument(skip_all, fields(session_id = %session_id, overlay_id = %payload.overlay_id))]
+	fn create_overlay(
+		&mut self,
+		monitor_id: crate::monitor::MonitorId,
+		session_id: crate::sessions::SessionId,
+		payload: tab_protocol::OverlayCreatePayload,
+		dma_bufs: [OwnedFd; 2],
+	) {
+		let label = format!("session_{session_id}_overlay_{}", payload.overlay_id);
+		let imported = self.import_dmabufs(
+			monitor_id,
+			dma_bufs,
+			|fd| DmaBufImportParams {
+				width: payload.width,
+				height: payload.height,
+				stride: payload.stride,
+				offset: payload.offset,
+				fourcc: payload.fourcc,
+				fd,
+			},
+			|idx| format!("{label}_buffer_{idx}"),
+		);
+		let Some(imported) = imported else {
+			tracing::warn!(%monitor_id, "overlay created on unknown monitor");
 			return;
+		};
+		let Ok(textures) = <[_; 2]>::try_from(
+			imported
+				.into_iter()
+				.map(|(_, texture)| texture.with_premultiplied_alpha())
+				.collect::<Vec<_>>(),
+		) else {
+			tracing::warn!(%monitor_id, "overlay buffers failed to import");
+			return;
+		};
+		let overlay = Overlay::new(monitor_id, (payload.x, payload.y), payload.z, textures);
+		let key = (session_id, payload.overlay_id);
+		if let Some(previous) = self.overlays.insert(key, overlay) {
+			tracing::debug!(monitor_id = %previous.monitor_id, "overlay recreated");
 		}
+	}
 
-		for (slot, texture) in imported {
-			let key = SlotKey::new(monitor_id, session_id, slot);
-			self.slots.insert(key, texture);
-			self.ownership.mark_slot_client_owned(key);
+	#[tracing::instrument(skip_all, fields(session_id = %session_id, capture_id = %payload.capture_id))]
+	fn start_capture(
+		&mut self,
+		session_id: crate::sessions::SessionId,
+		monitor_id: crate::monitor::MonitorId,
+		source_session: Option<crate::sessions::SessionId>,
+		payload: tab_protocol::CaptureStartPayload,
+		dma_bufs: [OwnedFd; 2],
+	) {
+		let label = format!("session_{session_id}_capture_{}", payload.capture_id);
+		let imported = self.import_dmabufs(
+			monitor_id,
+			dma_bufs,
+			|fd| DmaBufImportParams {
+				width: payload.width,
+				height: payload.height,
+				stride: payload.stride,
+				offset: payload.offset,
+				fourcc: payload.fourcc,
+				fd,
+			},
+			|idx| format!("{label}_buffer_{idx}"),
+		);
+		let Some(imported) = imported else {
+			tracing::warn!(%monitor_id, "capture started on unknown monitor");
+			return;
+		};
+		let textures = imported
+			.into_iter()
+			.map(|(_, texture)| texture)
+			.collect::<Vec<_>>();
+		let surfaces = textures
+			.iter()
+			.filter_map(|texture| {
+				gpu::surfaces::wrap_backend_texture(
+					&mut self.gr,
+					texture.texture(),
+					gpu::SurfaceOrigin::TopLeft,
+					None,
+					ColorType::RGBA8888,
+					None,
+					None,
+				)
+			})
+			.collect::<Vec<_>>();
+		let (Ok(textures), Ok(surfaces)) = (<[_; 2]>::try_from(textures), <[_; 2]>::try_from(surfaces))
+		else {
+			tracing::warn!(%monitor_id, "capture buffers failed to import");
+			return;
+		};
+		let capture = Capture::new(monitor_id, source_session, surfaces, textures);
+		let key = (session_id, payload.capture_id);
+		if self.captures.insert(key, capture).is_some() {
+			tracing::debug!(%monitor_id, "capture restarted");
 		}
 	}
 
@@ -103,6 +270,22 @@ impl RenderingLayer {
 				})
 				.await;
 		}
+		for (session_id, overlay_id, buffer) in std::mem::take(&mut self.overlay_releases) {
+			let release_fence = if release_fence >= 0 {
+				let dup_fd = unsafe { libc::dup(release_fence) };
+				(dup_fd >= 0).then(|| unsafe { OwnedFd::from_raw_fd(dup_fd) })
+			} else {
+				None
+			};
+			self
+				.emit_event(RenderEvt::OverlayReleased {
+					session_id,
+					overlay_id,
+					buffer,
+					release_fence,
+				})
+				.await;
+		}
 	}
 
 	#[tracing::instrument(skip_all)]
@@ -131,6 +314,133 @@ impl RenderingLayer {
 				}
 				self.ownership.set_current_session(session_id);
 			}
+			RenderCmd::SetMonitorMode { monitor_id, mode } => {
+				self.apply_monitor_mode(monitor_id, mode).await;
+			}
+			RenderCmd::SetMonitorPower { monitor_id, power } => {
+				self.apply_monitor_power(monitor_id, power).await;
+			}
+			RenderCmd::SetMonitorBrightness {
+				monitor_id,
+				brightness,
+			} => {
+				self
+					.color_adjustments
+					.entry(monitor_id)
+					.or_default()
+					.set_brightness(brightness);
+			}
+			RenderCmd::SetMonitorGamma { monitor_id, ramps } => {
+				self
+					.color_adjustments
+					.entry(monitor_id)
+					.or_default()
+					.set_gamma(ramps);
+			}
+			RenderCmd::SetScanoutHint {
+				monitor_id,
+				session_id,
+				fullscreen_opaque,
+			} => {
+				if fullscreen_opaque {
+					self.scanout_candidates.insert((monitor_id, session_id));
+				} else {
+					self.scanout_candidates.remove(&(monitor_id, session_id));
+				}
+			}
+			RenderCmd::SetSurfaceColorspace {
+				monitor_id,
+				session_id,
+				colorspace,
+			} => {
+				self
+					.surface_colorspaces
+					.insert((monitor_id, session_id), colorspace);
+				let color_space = skia_color_space(colorspace);
+				for (key, texture) in &mut self.slots {
+					if key.monitor_id == monitor_id && key.session_id == session_id {
+						texture.set_color_space(color_space.clone());
+					}
+				}
+			}
+			RenderCmd::OverlayCreate {
+				monitor_id,
+				session_id,
+				payload,
+				dma_bufs,
+			} => {
+				self.create_overlay(monitor_id, session_id, payload, dma_bufs);
+			}
+			RenderCmd::OverlayMove {
+				session_id,
+				overlay_id,
+				position,
+			} => {
+				if let Some(overlay) = self.overlays.get_mut(&(session_id, overlay_id)) {
+					overlay.position = position;
+				}
+			}
+			RenderCmd::OverlayPresent {
+				session_id,
+				overlay_id,
+				buffer,
+				acquire_fence,
+			} => {
+				let Some(overlay) = self.overlays.get_mut(&(session_id, overlay_id.clone())) else {
+					tracing::warn!(%session_id, %overlay_id, "present for unknown overlay");
+					return Ok(true);
+				};
+				if let Some(superseded) = overlay.present(buffer, acquire_fence) {
+					self
+						.overlay_releases
+						.push((session_id, overlay_id, superseded));
+				}
+			}
+			RenderCmd::OverlayDestroy {
+				session_id,
+				overlay_id,
+			} => {
+				self.overlays.remove(&(session_id, overlay_id));
+			}
+			RenderCmd::CaptureStart {
+				session_id,
+				monitor_id,
+				source_session,
+				payload,
+				dma_bufs,
+			} => {
+				self.start_capture(session_id, monitor_id, source_session, payload, dma_bufs);
+			}
+			RenderCmd::CaptureStop {
+				session_id,
+				capture_id,
+			} => {
+				self.captures.remove(&(session_id, capture_id));
+			}
+			RenderCmd::CaptureRelease {
+				session_id,
+				capture_id,
+				buffer,
+			} => {
+				if let Some(capture) = self.captures.get_mut(&(session_id, capture_id)) {
+					capture.release(buffer);
+				}
+			}
+			RenderCmd::SessionPreview {
+				client_id,
+				session_id,
+				monitor_id,
+				max_size,
+			} => {
+				let preview = self.session_preview(session_id, monitor_id, max_size);
+				self
+					.emit_event(RenderEvt::SessionPreview {
+						client_id,
+						session_id,
+						preview,
+					})
+					.await;
+			}
 			RenderCmd::SessionRemoved { session_id } => {
 				self.cleanup_session_slots(session_id);
 				if self.ownership.current_session() == Some(session_id) {
@@ -175,6 +485,9 @@ impl RenderingLayer {
 							.ownership
 							.queue_buffer_release(monitor_id, session_id, pending);
 					}
+					if let Some(SlotTexture::Shm(texture)) = self.slots.get_mut(&slot_key) {
+						texture.mark_stale();
+					}
 					if let Some(fence_fd) = acquire_fence {
 						self.spawn_acquire_fence_waiter(slot_key, fence_fd);
 					} else {
@@ -7,11 +7,14 @@ use std::{
 
 use easydrm::gl;
 use nix::unistd::close;
-use skia_safe::{Image, gpu};
+use skia_safe::{ColorSpace, Image, gpu};
 use thiserror::Error;
 
 use crate::rendering_layer::egl;
 
+const FOURCC_XRGB2101010: i32 = 0x3033_5258;
+const FOURCC_XBGR16161616F: i32 = 0x4834_4258;
+
 /// Metadata required to import a client-provided dmabuf as a GL texture.
 #[derive(Debug)]
 pub struct ImportParams {
@@ -165,7 +168,7 @@ impl DmaBufTexture {
 		gpu::gl::TextureInfo {
 			target: gl::TEXTURE_2D as gpu::gl::Enum,
 			id: self.texture_id as gpu::gl::Enum,
-			format: gpu::gl::Format::RGBA8.into(),
+			format: skia_format(self.fourcc).0.into(),
 			protected: gpu::Protected::No,
 		}
 	}
@@ -184,6 +187,8 @@ impl DmaBufTexture {
 			backend_texture,
 			source: self,
 			cached_image: None,
+			alpha_type: skia_safe::AlphaType::Opaque,
+			color_space: None,
 		})
 	}
 }
@@ -199,11 +204,23 @@ impl Drop for DmaBufTexture {
 	}
 }
 
+/// GL texture format and Skia color type of a buffer imported with `fourcc`; formats
+/// other than the wide swapchain ones are 8 bits per channel.
+fn skia_format(fourcc: i32) -> (gpu::gl::Format, skia_safe::ColorType) {
+	match fourcc {
+		FOURCC_XRGB2101010 => (gpu::gl::Format::RGB10_A2, skia_safe::ColorType::RGBA1010102),
+		FOURCC_XBGR16161616F => (gpu::gl::Format::RGBA16F, skia_safe::ColorType::RGBAF16),
+		_ => (gpu::gl::Format::RGBA8, skia_safe::ColorType::RGBA8888),
+	}
+}
+
 /// Helper struct that keeps the GL/EGL resources alive for as long as Skia needs them.
 pub struct SkiaDmaBufTexture {
 	pub backend_texture: gpu::BackendTexture,
 	source: DmaBufTexture,
 	cached_image: Option<Image>,
+	alpha_type: skia_safe::AlphaType,
+	color_space: Option<ColorSpace>,
 }
 
 impl SkiaDmaBufTexture {
@@ -211,15 +228,30 @@ impl SkiaDmaBufTexture {
 		&self.backend_texture
 	}
 
+	/// Samples the alpha channel as premultiplied instead of treating the buffer as opaque.
+	pub fn with_premultiplied_alpha(mut self) -> Self {
+		self.alpha_type = skia_safe::AlphaType::Premul;
+		self.cached_image = None;
+		self
+	}
+
+	/// Tags the buffer contents so Skia converts them to the surface's color space.
+	pub fn set_color_space(&mut self, color_space: Option<ColorSpace>) {
+		if self.color_space != color_space {
+			self.color_space = color_space;
+			self.cached_image = None;
+		}
+	}
+
 	pub fn image<'a>(&'a mut self, gr: &mut gpu::DirectContext) -> Option<&'a Image> {
 		if self.cached_image.is_none() {
 			self.cached_image = Image::from_texture(
 				gr,
 				&self.backend_texture,
 				gpu::SurfaceOrigin::TopLeft,
-				skia_safe::ColorType::RGBA8888,
-				skia_safe::AlphaType::Opaque,
-				None,
+				skia_format(self.source.fourcc).1,
+				self.alpha_type,
+				self.color_space.clone(),
 			);
 		}
 		self.cached_image.as_ref()
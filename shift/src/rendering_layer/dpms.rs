@@ -0,0 +1,121 @@
+//! Display power control through a connector's DPMS property.
+//!
+//! easydrm does not expose connector properties, so the property is set with the legacy
+//! object-property ioctls on the DRM master fd easydrm opened.
+
+use std::{ffi::CStr, io, os::fd::RawFd};
+
+use tab_protocol::MonitorPower;
+
+use super::drm_device;
+
+const DRM_MODE_OBJECT_CONNECTOR: u32 = 0xc0c0_c0c0;
+const DRM_MODE_DPMS_ON: u64 = 0;
+const DRM_MODE_DPMS_STANDBY: u64 = 1;
+const DRM_MODE_DPMS_OFF: u64 = 3;
+
+/// `struct drm_mode_obj_get_properties`.
+#[repr(C)]
+#[derive(Default)]
+struct ObjGetProperties {
+	props_ptr: u64,
+	prop_values_ptr: u64,
+	count_props: u32,
+	obj_id: u32,
+	obj_type: u32,
+}
+
+/// `struct drm_mode_get_property`.
+#[repr(C)]
+#[derive(Default)]
+struct GetProperty {
+	values_ptr: u64,
+	enum_blob_ptr: u64,
+	prop_id: u32,
+	flags: u32,
+	name: [u8; 32],
+	count_values: u32,
+	count_enum_blobs: u32,
+}
+
+/// `struct drm_mode_obj_set_property`.
+#[repr(C)]
+#[derive(Default)]
+struct ObjSetProperty {
+	value: u64,
+	prop_id: u32,
+	obj_id: u32,
+	obj_type: u32,
+}
+
+/// `DRM_IOWR(nr, T)`.
+const fn drm_iowr<T>(nr: u32) -> libc::Ioctl {
+	let size = std::mem::size_of::<T>() as u32;
+	((3 << 30) | (size << 16) | ((b'd' as u32) << 8) | nr) as libc::Ioctl
+}
+
+const DRM_IOCTL_MODE_GETPROPERTY: libc::Ioctl = drm_iowr::<GetProperty>(0xaa);
+const DRM_IOCTL_MODE_OBJ_GETPROPERTIES: libc::Ioctl = drm_iowr::<ObjGetProperties>(0xb9);
+const DRM_IOCTL_MODE_OBJ_SETPROPERTY: libc::Ioctl = drm_iowr::<ObjSetProperty>(0xba);
+
+/// Switches the display on `connector_id` to `power`.
+pub(super) fn set_power(connector_id: u32, power: MonitorPower) -> io::Result<()> {
+	let fd = drm_device::card_fd()
+		.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no DRM device open"))?;
+	let prop_id = connector_property(fd, connector_id, b"DPMS")?
+		.ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "connector has no DPMS property"))?;
+	let value = match power {
+		MonitorPower::On => DRM_MODE_DPMS_ON,
+		MonitorPower::Standby => DRM_MODE_DPMS_STANDBY,
+		MonitorPower::Off => DRM_MODE_DPMS_OFF,
+	};
+	let mut request = ObjSetProperty {
+		value,
+		prop_id,
+		obj_id: connector_id,
+		obj_type: DRM_MODE_OBJECT_CONNECTOR,
+	};
+	ioctl(fd, DRM_IOCTL_MODE_OBJ_SETPROPERTY, &mut request)
+}
+
+/// Looks up the id of the connector property called `name`.
+fn connector_property(fd: RawFd, connector_id: u32, name: &[u8]) -> io::Result<Option<u32>> {
+	let mut query = ObjGetProperties {
+		obj_id: connector_id,
+		obj_type: DRM_MODE_OBJECT_CONNECTOR,
+		..Default::default()
+	};
+	ioctl(fd, DRM_IOCTL_MODE_OBJ_GETPROPERTIES, &mut query)?;
+	let mut props = vec![0u32; query.count_props as usize];
+	let mut values = vec![0u64; props.len()];
+	query.props_ptr = props.as_mut_ptr() as u64;
+	query.prop_values_ptr = values.as_mut_ptr() as u64;
+	ioctl(fd, DRM_IOCTL_MODE_OBJ_GETPROPERTIES, &mut query)?;
+	props.truncate(query.count_props as usize);
+	for prop_id in props {
+		let mut property = GetProperty {
+			prop_id,
+			..Default::default()
+		};
+		ioctl(fd, DRM_IOCTL_MODE_GETPROPERTY, &mut property)?;
+		let prop_name = CStr::from_bytes_until_nul(&property.name)
+			.map(CStr::to_bytes)
+			.unwrap_or(&property.name);
+		if prop_name == name {
+			return Ok(Some(prop_id));
+		}
+	}
+	Ok(None)
+}
+
+fn ioctl<T>(fd: RawFd, request: libc::Ioctl, arg: &mut T) -> io::Result<()> {
+	loop {
+		if unsafe { libc::ioctl(fd, request, arg as *mut T) } == 0 {
+			return Ok(());
+		}
+		let err = io::Error::last_os_error();
+		if !matches!(err.raw_os_error(), Some(libc::EINTR | libc::EAGAIN)) {
+			return Err(err);
+		}
+	}
+}
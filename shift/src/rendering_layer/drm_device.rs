@@ -0,0 +1,74 @@
+use std::{
+	fs,
+	os::{
+		fd::RawFd,
+		unix::fs::{FileTypeExt, MetadataExt},
+	},
+	path::{Path, PathBuf},
+};
+
+use tab_protocol::DrmDeviceInfo;
+
+/// Finds the DRM device easydrm opened, so clients can allocate on the same GPU.
+///
+/// easydrm does not expose its device fd, so the primary node is looked up among the
+/// process' open files and mapped to its render node through sysfs.
+pub(super) fn detect() -> Option<DrmDeviceInfo> {
+	let card = open_card_node()?;
+	let card_rdev = fs::metadata(&card).ok()?.rdev();
+	let sysfs_device = PathBuf::from(format!(
+		"/sys/dev/char/{}:{}/device",
+		libc::major(card_rdev),
+		libc::minor(card_rdev)
+	));
+	let render_node = fs::read_dir(sysfs_device.join("drm"))
+		.ok()?
+		.filter_map(Result::ok)
+		.map(|entry| entry.file_name().to_string_lossy().into_owned())
+		.find(|name| name.starts_with("renderD"))
+		.map(|name| Path::new("/dev/dri").join(name))
+		.filter(|path| path.exists());
+	let rdev = match &render_node {
+		Some(path) => fs::metadata(path).ok()?.rdev(),
+		None => card_rdev,
+	};
+	Some(DrmDeviceInfo {
+		major: libc::major(rdev),
+		minor: libc::minor(rdev),
+		render_node: render_node.map(|path| path.to_string_lossy().into_owned()),
+		pci_id: pci_id(&sysfs_device),
+	})
+}
+
+pub(super) fn open_card_node() -> Option<PathBuf> {
+	open_card().map(|(_, path)| path)
+}
+
+/// Descriptor of the primary node easydrm opened, which holds DRM master.
+pub(super) fn card_fd() -> Option<RawFd> {
+	open_card().map(|(fd, _)| fd)
+}
+
+fn open_card() -> Option<(RawFd, PathBuf)> {
+	fs::read_dir("/proc/self/fd")
+		.ok()?
+		.filter_map(Result::ok)
+		.find_map(|entry| {
+			let target = fs::read_link(entry.path()).ok()?;
+			let is_card = target
+				.file_name()
+				.is_some_and(|name| name.to_string_lossy().starts_with("card"))
+				&& target.starts_with("/dev/dri")
+				&& fs::metadata(&target).is_ok_and(|meta| meta.file_type().is_char_device());
+			let fd = entry.file_name().to_str()?.parse().ok()?;
+			is_card.then_some((fd, target))
+		})
+}
+
+fn pci_id(sysfs_device: &Path) -> Option<String> {
+	let read_id = |name: &str| {
+		let value = fs::read_to_string(sysfs_device.join(name)).ok()?;
+		Some(value.trim().trim_start_matches("0x").to_string())
+	};
+	Some(format!("{}:{}", read_id("vendor")?, read_id("device")?))
+}
@@ -0,0 +1,110 @@
+use std::{fs, path::PathBuf};
+
+use tab_protocol::{Chromaticity, Colorspace, MonitorColorInfo};
+
+use super::drm_device;
+
+const EDID_HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+const BLOCK_LEN: usize = 128;
+const CTA_EXTENSION_TAG: u8 = 0x02;
+const EXTENDED_DATA_BLOCK: u8 = 7;
+const COLORIMETRY_DATA_BLOCK: u8 = 5;
+const HDR_STATIC_METADATA_BLOCK: u8 = 6;
+
+/// Reads a connector's color characteristics from the EDID the kernel exposes in sysfs.
+///
+/// easydrm does not expose connector properties, so the connector is looked up by id
+/// among the sysfs connectors of the card shift opened.
+pub(super) fn color_info(connector_id: u32) -> Option<MonitorColorInfo> {
+	let edid = fs::read(connector_sysfs_dir(connector_id)?.join("edid")).ok()?;
+	parse_color_info(&edid)
+}
+
+fn connector_sysfs_dir(connector_id: u32) -> Option<PathBuf> {
+	let card = drm_device::open_card_node()?;
+	let prefix = format!("{}-", card.file_name()?.to_string_lossy());
+	fs::read_dir("/sys/class/drm")
+		.ok()?
+		.filter_map(Result::ok)
+		.filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+		.map(|entry| entry.path())
+		.find(|path| {
+			fs::read_to_string(path.join("connector_id"))
+				.ok()
+				.and_then(|id| id.trim().parse::<u32>().ok())
+				== Some(connector_id)
+		})
+}
+
+/// Parses chromaticities and gamma from the base block and wide-gamut/HDR support from
+/// the CTA-861 extension.
+fn parse_color_info(edid: &[u8]) -> Option<MonitorColorInfo> {
+	if edid.len() < BLOCK_LEN || edid[..8] != EDID_HEADER {
+		return None;
+	}
+	// Low two bits of each coordinate are packed into bytes 25-26, high eight bits follow.
+	let low = u16::from_be_bytes([edid[25], edid[26]]);
+	let coordinate = |index: usize| {
+		let high = u16::from(edid[27 + index]);
+		let low_bits = (low >> (14 - 2 * index)) & 0b11;
+		f32::from((high << 2) | low_bits) / 1024.0
+	};
+	let chromaticity = |index: usize| Chromaticity {
+		x: coordinate(2 * index),
+		y: coordinate(2 * index + 1),
+	};
+	let gamma = (edid[23] != 0xff).then(|| (f32::from(edid[23]) + 100.0) / 100.0);
+	Some(MonitorColorInfo {
+		red: chromaticity(0),
+		green: chromaticity(1),
+		blue: chromaticity(2),
+		white: chromaticity(3),
+		gamma,
+		colorspaces: cta_colorspaces(edid),
+	})
+}
+
+fn cta_colorspaces(edid: &[u8]) -> Vec<Colorspace> {
+	let mut bt2020 = false;
+	let mut dci_p3 = false;
+	let mut pq = false;
+	let mut hlg = false;
+	for block in edid.chunks_exact(BLOCK_LEN).skip(1) {
+		if block[0] != CTA_EXTENSION_TAG {
+			continue;
+		}
+		// Data blocks run from byte 4 up to the detailed timing descriptors.
+		let end = usize::from(block[2]).clamp(4, BLOCK_LEN - 1);
+		let mut offset = 4;
+		while offset < end {
+			let tag = block[offset] >> 5;
+			let len = usize::from(block[offset] & 0x1f);
+			let payload = &block[(offset + 1).min(end)..(offset + 1 + len).min(end)];
+			if tag == EXTENDED_DATA_BLOCK && payload.len() >= 3 {
+				match payload[0] {
+					COLORIMETRY_DATA_BLOCK => {
+						bt2020 |= payload[1] & 0x80 != 0;
+						dci_p3 |= payload[2] & 0x80 != 0;
+					}
+					HDR_STATIC_METADATA_BLOCK => {
+						pq |= payload[1] & 0x04 != 0;
+						hlg |= payload[1] & 0x08 != 0;
+					}
+					_ => {}
+				}
+			}
+			offset += 1 + len;
+		}
+	}
+	let mut colorspaces = Vec::new();
+	if dci_p3 {
+		colorspaces.push(Colorspace::DisplayP3);
+	}
+	if bt2020 && pq {
+		colorspaces.push(Colorspace::Bt2020Pq);
+	}
+	if bt2020 && hlg {
+		colorspaces.push(Colorspace::Bt2020Hlg);
+	}
+	colorspaces
+}
@@ -1,25 +1,38 @@
 #![allow(dead_code)]
 
 mod animation;
+mod capture;
 pub mod channels;
+mod color_adjust;
+mod colorspace;
 mod commands;
 pub mod dmabuf_import;
+mod dpms;
+mod drm_device;
+mod edid;
 mod egl;
 mod fence_runtime;
 mod fence_scheduler;
+mod overlay;
 mod ownership;
+mod preview;
 mod render_core;
+mod shm_import;
 mod state;
 mod surface_cache;
 
+pub(crate) use capture::monotonic_usec;
+
 use easydrm::EasyDRM;
 use skia_safe::gpu;
 use std::{
-	collections::HashMap,
+	collections::{HashMap, HashSet},
+	sync::Arc,
 	time::{Duration, Instant as StdInstant},
 };
 #[cfg(debug_assertions)]
 use std::{fs, time::Instant};
+use tab_protocol::{Colorspace, MonitorMode, MonitorPower};
 use thiserror::Error;
 use tokio::sync::mpsc;
 use tracing::warn;
@@ -34,10 +47,13 @@ use crate::{
 	sessions::SessionId,
 };
 use animation::AnimationRegistry;
+use capture::{Capture, CaptureKey};
 use channels::RenderingEnd;
-use dmabuf_import::SkiaDmaBufTexture;
+use color_adjust::ColorAdjustment;
 use fence_scheduler::{FenceScheduler, FenceTaskHandle, FenceWaitMode};
+use overlay::{Overlay, OverlayKey};
 use ownership::OwnershipManager;
+use shm_import::SlotTexture;
 use state::{FenceEvent, SlotKey};
 use surface_cache::{MonitorRenderState, current_framebuffer_binding};
 
@@ -67,13 +83,22 @@ pub struct RenderingLayer {
 	event_tx: RenderEvtTx,
 	known_monitors: HashMap<MonitorId, ServerLayerMonitor>,
 	ownership: OwnershipManager,
-	slots: HashMap<SlotKey, SkiaDmaBufTexture>,
+	slots: HashMap<SlotKey, SlotTexture>,
 	fence_event_tx: mpsc::UnboundedSender<FenceEvent>,
 	fence_event_rx: mpsc::UnboundedReceiver<FenceEvent>,
 	fence_scheduler: FenceScheduler,
 	fence_tasks: HashMap<SlotKey, FenceTaskHandle>,
 	animations: AnimationRegistry,
 	active_transition: Option<ActiveTransition>,
+	color_adjustments: HashMap<MonitorId, ColorAdjustment>,
+	scanout_candidates: HashSet<(MonitorId, SessionId)>,
+	surface_colorspaces: HashMap<(MonitorId, SessionId), Colorspace>,
+	overlays: HashMap<OverlayKey, Overlay>,
+	overlay_releases: Vec<(SessionId, String, tab_protocol::BufferIndex)>,
+	captures: HashMap<CaptureKey, Capture>,
+	ended_captures: Vec<(SessionId, String, Arc<str>)>,
+	/// Monitors whose display is not [`MonitorPower::On`]; nothing is drawn on them.
+	monitor_power: HashMap<MonitorId, MonitorPower>,
 	#[cfg(debug_assertions)]
 	fd_guard_limit: usize,
 	#[cfg(debug_assertions)]
@@ -141,6 +166,14 @@ impl RenderingLayer {
 			fence_tasks: HashMap::new(),
 			animations: AnimationRegistry::new(),
 			active_transition: None,
+			color_adjustments: HashMap::new(),
+			scanout_candidates: HashSet::new(),
+			surface_colorspaces: HashMap::new(),
+			overlays: HashMap::new(),
+			overlay_releases: Vec::new(),
+			captures: HashMap::new(),
+			ended_captures: Vec::new(),
+			monitor_power: HashMap::new(),
 			#[cfg(debug_assertions)]
 			fd_guard_limit: std::env::var("SHIFT_MAX_OPEN_FDS")
 				.ok()
@@ -161,6 +194,7 @@ impl RenderingLayer {
 		self
 			.emit_event(RenderEvt::Started {
 				monitors: current.clone(),
+				drm_device: drm_device::detect(),
 			})
 			.await;
 		self.known_monitors = current.into_iter().map(|m| (m.id, m)).collect();
@@ -255,12 +289,22 @@ impl RenderingLayer {
 		let current_list = self.collect_monitors();
 		let mut current_map = HashMap::new();
 		for monitor in current_list {
-			if !self.known_monitors.contains_key(&monitor.id) {
-				self
-					.emit_event(RenderEvt::MonitorOnline {
-						monitor: monitor.clone(),
-					})
-					.await;
+			match self.known_monitors.get(&monitor.id) {
+				None => {
+					self
+						.emit_event(RenderEvt::MonitorOnline {
+							monitor: monitor.clone(),
+						})
+						.await;
+				}
+				Some(known) if *known != monitor => {
+					self
+						.emit_event(RenderEvt::MonitorChanged {
+							monitor: monitor.clone(),
+						})
+						.await;
+				}
+				Some(_) => {}
 			}
 			current_map.insert(monitor.id, monitor);
 		}
@@ -281,8 +325,79 @@ impl RenderingLayer {
 		self.known_monitors = current_map;
 	}
 
+	#[tracing::instrument(skip_all, fields(monitor_id = %monitor_id))]
+	async fn apply_monitor_mode(&mut self, monitor_id: MonitorId, mode: MonitorMode) {
+		let Some(monitor) = self
+			.drm
+			.monitors_mut()
+			.find(|monitor| monitor.context().id == monitor_id)
+		else {
+			tracing::warn!("mode change requested for unknown monitor");
+			return;
+		};
+		let Some(drm_mode) = monitor
+			.modes()
+			.iter()
+			.find(|candidate| {
+				let (width, height) = candidate.size();
+				(width as i32, height as i32, candidate.vrefresh() as i32)
+					== (mode.width, mode.height, mode.refresh_rate)
+			})
+			.copied()
+		else {
+			tracing::warn!(?mode, "monitor does not support requested mode");
+			return;
+		};
+		if let Err(e) = monitor.set_mode(drm_mode) {
+			tracing::error!(?mode, "failed to apply monitor mode: {e}");
+			return;
+		}
+		let updated = MonitorRenderState::get_server_layer_monitor(monitor);
+		tracing::info!(?mode, "monitor mode applied");
+		self.known_monitors.insert(monitor_id, updated.clone());
+		self
+			.emit_event(RenderEvt::MonitorChanged { monitor: updated })
+			.await;
+	}
+
+	async fn apply_monitor_power(&mut self, monitor_id: MonitorId, power: MonitorPower) {
+		let Some(monitor) = self
+			.drm
+			.monitors()
+			.find(|monitor| monitor.context().id == monitor_id)
+		else {
+			tracing::warn!("power change requested for unknown monitor");
+			return;
+		};
+		if let Err(e) = dpms::set_power(u32::from(monitor.connector_id()), power) {
+			tracing::error!(%monitor_id, ?power, "failed to set monitor power: {e}");
+			return;
+		}
+		tracing::info!(%monitor_id, ?power, "monitor power changed");
+		if power == MonitorPower::On {
+			self.monitor_power.remove(&monitor_id);
+		} else {
+			self.monitor_power.insert(monitor_id, power);
+		}
+		self
+			.emit_event(RenderEvt::MonitorPowerChanged { monitor_id, power })
+			.await;
+	}
+
 	fn cleanup_monitor_slots(&mut self, monitor_id: MonitorId) {
 		self.slots.retain(|key, _| key.monitor_id != monitor_id);
+		self.color_adjustments.remove(&monitor_id);
+		self.monitor_power.remove(&monitor_id);
+		self
+			.scanout_candidates
+			.retain(|(candidate, _)| *candidate != monitor_id);
+		self
+			.surface_colorspaces
+			.retain(|(tagged, _), _| *tagged != monitor_id);
+		self
+			.overlays
+			.retain(|_, overlay| overlay.monitor_id != monitor_id);
+		self.end_captures(Some(monitor_id), None);
 		self.ownership.cleanup_monitor(monitor_id);
 		let remove = self
 			.fence_tasks
@@ -297,6 +412,17 @@ impl RenderingLayer {
 
 	fn cleanup_session_slots(&mut self, session_id: SessionId) {
 		self.slots.retain(|key, _| key.session_id != session_id);
+		self
+			.scanout_candidates
+			.retain(|(_, candidate)| *candidate != session_id);
+		self
+			.surface_colorspaces
+			.retain(|(_, tagged), _| *tagged != session_id);
+		self.overlays.retain(|(owner, _), _| *owner != session_id);
+		self
+			.overlay_releases
+			.retain(|(owner, _, _)| *owner != session_id);
+		self.end_captures(None, Some(session_id));
 		self.ownership.cleanup_session(session_id);
 		let remove = self
 			.fence_tasks
@@ -0,0 +1,83 @@
+use std::os::fd::{AsRawFd, OwnedFd};
+
+use tab_protocol::BufferIndex;
+
+use super::dmabuf_import::SkiaDmaBufTexture;
+use crate::{monitor::MonitorId, sessions::SessionId};
+
+/// Overlays are named by their session and the client-chosen overlay id.
+pub(super) type OverlayKey = (SessionId, String);
+
+/// Client overlay surface composited above its session's frames on one monitor.
+pub(super) struct Overlay {
+	pub monitor_id: MonitorId,
+	pub position: (i32, i32),
+	pub z: i32,
+	textures: [SkiaDmaBufTexture; 2],
+	shown: Option<BufferIndex>,
+	pending: Option<(BufferIndex, Option<OwnedFd>)>,
+}
+
+impl Overlay {
+	pub fn new(
+		monitor_id: MonitorId,
+		position: (i32, i32),
+		z: i32,
+		textures: [SkiaDmaBufTexture; 2],
+	) -> Self {
+		Self {
+			monitor_id,
+			position,
+			z,
+			textures,
+			shown: None,
+			pending: None,
+		}
+	}
+
+	/// Queues `buffer` for display once its acquire fence signals.
+	///
+	/// Returns a previously queued buffer that was never shown and can go straight back to the
+	/// client.
+	pub fn present(
+		&mut self,
+		buffer: BufferIndex,
+		acquire_fence: Option<OwnedFd>,
+	) -> Option<BufferIndex> {
+		self
+			.pending
+			.replace((buffer, acquire_fence))
+			.map(|(superseded, _)| superseded)
+			.filter(|superseded| *superseded != buffer)
+	}
+
+	/// Shows the queued buffer if its fence signaled, returning the buffer it replaced.
+	pub fn latch(&mut self) -> Option<BufferIndex> {
+		let (_, fence) = self.pending.as_ref()?;
+		if fence.as_ref().is_some_and(|fence| !fence_signaled(fence)) {
+			return None;
+		}
+		let (buffer, _) = self.pending.take()?;
+		self
+			.shown
+			.replace(buffer)
+			.filter(|previous| *previous != buffer)
+	}
+
+	/// Texture of the buffer currently shown, if any was presented yet.
+	pub fn shown_texture(&mut self) -> Option<&mut SkiaDmaBufTexture> {
+		let shown = self.shown?;
+		Some(&mut self.textures[shown as usize])
+	}
+}
+
+fn fence_signaled(fence: &OwnedFd) -> bool {
+	let mut poll_fd = nix::libc::pollfd {
+		fd: fence.as_raw_fd(),
+		events: nix::libc::POLLIN,
+		revents: 0,
+	};
+	let result = unsafe { nix::libc::poll(&mut poll_fd, 1, 0) };
+	// An errored fence is treated as signaled so the overlay cannot get stuck.
+	result != 0
+}
@@ -0,0 +1,114 @@
+use std::{
+	ffi::CStr,
+	fs::File,
+	io::Write,
+	os::fd::{FromRawFd, OwnedFd},
+	sync::Arc,
+};
+
+use skia_safe::{
+	AlphaType, ColorType, FilterMode, ImageInfo, MipmapMode, Paint, Rect, SamplingOptions, gpu,
+};
+
+use super::RenderingLayer;
+use super::state::SlotOwner;
+use crate::{comms::render2server::SessionPreview, monitor::MonitorId, sessions::SessionId};
+
+/// DRM_FORMAT_ARGB8888, laid out as BGRA bytes in memory.
+const FOURCC_ARGB8888: i32 = 0x3432_5241;
+
+impl RenderingLayer {
+	/// Copies the session's latest frame into a memfd, scaled down to fit `max_size`.
+	///
+	/// Without `monitor_id`, the first monitor the session has a frame on is used.
+	#[tracing::instrument(skip_all, fields(session_id = %session_id))]
+	pub(super) fn session_preview(
+		&mut self,
+		session_id: SessionId,
+		monitor_id: Option<MonitorId>,
+		max_size: (i32, i32),
+	) -> Result<SessionPreview, Arc<str>> {
+		let monitor_ids: Vec<_> = match monitor_id {
+			Some(monitor_id) => vec![monitor_id],
+			None => self.drm.monitors().map(|mon| mon.context().id).collect(),
+		};
+		let (monitor_id, image) = monitor_ids
+			.into_iter()
+			.find_map(|monitor_id| {
+				let key = self
+					.ownership
+					.current_slot_key_for_session(monitor_id, session_id)
+					.filter(|key| self.ownership.owner(*key) == Some(SlotOwner::ShiftOwned))?;
+				let image = Self::slot_image(&mut self.slots, &mut self.gr, key)?;
+				Some((monitor_id, image))
+			})
+			.ok_or("session has no frame on screen")?;
+		if let Some(mon) = self
+			.drm
+			.monitors_mut()
+			.find(|mon| mon.context().id == monitor_id)
+			&& let Err(e) = mon.make_current()
+		{
+			return Err(format!("failed to make monitor current: {e:?}").into());
+		}
+
+		let (max_width, max_height) = max_size;
+		let scale = (max_width as f32 / image.width() as f32)
+			.min(max_height as f32 / image.height() as f32)
+			.min(1.0);
+		let width = ((image.width() as f32 * scale).round() as i32).max(1);
+		let height = ((image.height() as f32 * scale).round() as i32).max(1);
+		let info = ImageInfo::new(
+			(width, height),
+			ColorType::BGRA8888,
+			AlphaType::Premul,
+			None,
+		);
+		let mut surface = gpu::surfaces::render_target(
+			&mut self.gr,
+			gpu::Budgeted::No,
+			&info,
+			None,
+			gpu::SurfaceOrigin::TopLeft,
+			None,
+			false,
+			None,
+		)
+		.ok_or("failed to create preview surface")?;
+		let sampling = SamplingOptions::new(FilterMode::Linear, MipmapMode::None);
+		surface.canvas().draw_image_rect_with_sampling_options(
+			&image,
+			None,
+			Rect::from_iwh(width, height),
+			sampling,
+			&Paint::default(),
+		);
+		self.gr.flush_and_submit();
+
+		let stride = width as usize * 4;
+		let mut pixels = vec![0u8; stride * height as usize];
+		if !surface.read_pixels(&info, &mut pixels, stride, (0, 0)) {
+			return Err("failed to read back preview pixels".into());
+		}
+		let pixels = write_memfd(&pixels).map_err(|e| format!("failed to create memfd: {e}"))?;
+		Ok(SessionPreview {
+			monitor_id,
+			width,
+			height,
+			stride: stride as i32,
+			fourcc: FOURCC_ARGB8888,
+			pixels,
+		})
+	}
+}
+
+fn write_memfd(bytes: &[u8]) -> std::io::Result<OwnedFd> {
+	const NAME: &CStr = c"shift-session-preview";
+	let raw = unsafe { libc::memfd_create(NAME.as_ptr(), libc::MFD_CLOEXEC) };
+	if raw < 0 {
+		return Err(std::io::Error::last_os_error());
+	}
+	let mut file = File::from(unsafe { OwnedFd::from_raw_fd(raw) });
+	file.write_all(bytes)?;
+	Ok(file.into())
+}
@@ -1,15 +1,17 @@
 use easydrm::gl::{COLOR_BUFFER_BIT, DEPTH_BUFFER_BIT};
-use skia_safe::{FilterMode, MipmapMode, Paint, SamplingOptions};
+use skia_safe::{BlendMode, FilterMode, MipmapMode, Paint, SamplingOptions, canvas::SaveLayerRec};
 use std::collections::HashMap;
 use tracing::warn;
 
+use super::overlay::{Overlay, OverlayKey};
 use super::state::SlotOwner;
 use super::{RenderError, RenderEvt, RenderingLayer, current_framebuffer_binding};
-use super::{SkiaDmaBufTexture, SlotKey};
+use super::{SlotKey, SlotTexture};
+use crate::{monitor::MonitorId, sessions::SessionId};
 
 impl RenderingLayer {
-	fn slot_image(
-		slots: &mut HashMap<SlotKey, SkiaDmaBufTexture>,
+	pub(super) fn slot_image(
+		slots: &mut HashMap<SlotKey, SlotTexture>,
 		gr: &mut skia_safe::gpu::DirectContext,
 		key: SlotKey,
 	) -> Option<skia_safe::Image> {
@@ -27,6 +29,60 @@ impl RenderingLayer {
 			.draw_image_rect_with_sampling_options(image, None, rect, sampling, &paint);
 	}
 
+	/// Current slot image of a session that hinted its frames as fullscreen and opaque, as long
+	/// as nothing else needs compositing on top of it.
+	fn passthrough_image(&mut self, monitor_id: MonitorId) -> Option<skia_safe::Image> {
+		let adjusted = self
+			.color_adjustments
+			.get(&monitor_id)
+			.is_some_and(|adjustment| adjustment.paint().is_some());
+		if adjusted {
+			return None;
+		}
+		let session_id = self.ownership.current_session()?;
+		if !self.scanout_candidates.contains(&(monitor_id, session_id)) {
+			return None;
+		}
+		let has_overlays = self
+			.overlays
+			.iter()
+			.any(|((owner, _), overlay)| *owner == session_id && overlay.monitor_id == monitor_id);
+		if has_overlays {
+			return None;
+		}
+		let key = self.ownership.current_slot_key(monitor_id)?;
+		if self.ownership.owner(key) != Some(SlotOwner::ShiftOwned) {
+			return None;
+		}
+		Self::slot_image(&mut self.slots, &mut self.gr, key)
+	}
+
+	/// Draws the session's overlays on a monitor, lowest `z` first.
+	fn draw_overlays(
+		context: &mut super::MonitorRenderState,
+		overlays: &mut HashMap<OverlayKey, Overlay>,
+		gr: &mut skia_safe::gpu::DirectContext,
+		monitor_id: MonitorId,
+		session_id: SessionId,
+	) {
+		let mut visible = overlays
+			.iter_mut()
+			.filter(|((owner, _), overlay)| *owner == session_id && overlay.monitor_id == monitor_id)
+			.map(|(_, overlay)| overlay)
+			.collect::<Vec<_>>();
+		visible.sort_by_key(|overlay| overlay.z);
+		for overlay in visible {
+			let (x, y) = overlay.position;
+			let Some(image) = overlay
+				.shown_texture()
+				.and_then(|texture| texture.image(gr).cloned())
+			else {
+				continue;
+			};
+			context.canvas().draw_image(&image, (x, y), None);
+		}
+	}
+
 	pub(super) fn draw_ready_monitors(&mut self) -> Result<(), RenderError> {
 		let monitor_ids: Vec<_> = self.drm.monitors().map(|mon| mon.context().id).collect();
 		self.ownership.ensure_current_session_monitors(&monitor_ids);
@@ -36,9 +92,24 @@ impl RenderingLayer {
 			.as_ref()
 			.map(|transition| transition.progress(now) >= 1.0)
 			.unwrap_or(false);
+		for ((session_id, overlay_id), overlay) in &mut self.overlays {
+			if let Some(previous) = overlay.latch() {
+				self
+					.overlay_releases
+					.push((*session_id, overlay_id.clone(), previous));
+			}
+		}
+		let mut passthrough_images = HashMap::new();
+		if transition_snapshot.is_none() {
+			for monitor_id in &monitor_ids {
+				if let Some(image) = self.passthrough_image(*monitor_id) {
+					passthrough_images.insert(*monitor_id, image);
+				}
+			}
+		}
 
 		for mon in self.drm.monitors_mut() {
-			if !mon.can_render() {
+			if !mon.can_render() || self.monitor_power.contains_key(&mon.context().id) {
 				continue;
 			}
 			if let Err(e) = mon.make_current() {
@@ -46,17 +117,41 @@ impl RenderingLayer {
 				continue;
 			}
 
-			unsafe {
-				mon.gl().ClearColor(0.0, 0.0, 0.0, 1.0);
-				mon.gl().Clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
-			}
-
 			let monitor_id = mon.context().id;
 			let mode = mon.active_mode();
 			let (w, h) = (mode.size().0 as usize, mode.size().1 as usize);
+			// Fullscreen opaque frames skip composition and are copied straight into the
+			// scanout buffer. Handing the client dmabuf to a KMS plane directly needs plane
+			// assignment from easydrm; until then this is the closest path to it.
+			let passthrough = passthrough_images
+				.remove(&monitor_id)
+				.filter(|image| image.width() as usize == w && image.height() as usize == h);
+			if passthrough.is_none() {
+				unsafe {
+					mon.gl().ClearColor(0.0, 0.0, 0.0, 1.0);
+					mon.gl().Clear(COLOR_BUFFER_BIT | DEPTH_BUFFER_BIT);
+				}
+			}
+
 			let context = mon.context_mut();
 			let target_fbo = current_framebuffer_binding(&context.gl);
 			context.ensure_surface_target(&mut self.gr, w, h, target_fbo)?;
+			if let Some(image) = passthrough {
+				let mut paint = Paint::default();
+				paint.set_blend_mode(BlendMode::Src);
+				context.canvas().draw_image(&image, (0, 0), Some(&paint));
+				context.flush(&mut self.gr);
+				continue;
+			}
+			let adjustment = self
+				.color_adjustments
+				.get(&monitor_id)
+				.and_then(|adjustment| adjustment.paint());
+			if let Some(paint) = adjustment {
+				context
+					.canvas()
+					.save_layer(&SaveLayerRec::default().paint(paint));
+			}
 
 			let mut drew = false;
 			if let Some(transition) = transition_snapshot.as_ref()
@@ -106,6 +201,19 @@ impl RenderingLayer {
 				}
 			}
 
+			if let Some(session_id) = self.ownership.current_session() {
+				Self::draw_overlays(
+					context,
+					&mut self.overlays,
+					&mut self.gr,
+					monitor_id,
+					session_id,
+				);
+			}
+
+			if adjustment.is_some() {
+				context.canvas().restore();
+			}
 			context.flush(&mut self.gr);
 		}
 
@@ -118,6 +226,7 @@ impl RenderingLayer {
 
 	pub(super) async fn render_and_commit(&mut self) -> Result<bool, RenderError> {
 		self.draw_ready_monitors()?;
+		self.capture_frames().await;
 
 		let page_flipped_monitors = self
 			.drm
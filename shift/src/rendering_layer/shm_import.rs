@@ -0,0 +1,186 @@
+use std::{
+	fs::File,
+	os::fd::{AsRawFd, OwnedFd},
+	ptr::NonNull,
+};
+
+use skia_safe::{AlphaType, ColorSpace, ColorType, Image, ImageInfo, gpu, images};
+use thiserror::Error;
+
+use super::dmabuf_import::SkiaDmaBufTexture;
+
+/// DRM_FORMAT_XRGB8888.
+const FOURCC_XRGB8888: i32 = 0x3432_5258;
+/// DRM_FORMAT_ARGB8888.
+const FOURCC_ARGB8888: i32 = 0x3432_5241;
+
+#[derive(Debug, Error)]
+pub enum ShmImportError {
+	#[error("unsupported shm format {0:#x}")]
+	UnsupportedFormat(i32),
+	#[error("invalid shm buffer layout")]
+	InvalidLayout,
+	#[error("shm buffer is not sealed against shrinking and growing")]
+	Unsealed,
+	#[error("shm buffer io error: {0}")]
+	Io(#[from] std::io::Error),
+}
+
+/// Read-only shared mapping of a sealed memfd, unmapped on drop.
+struct ShmMapping {
+	ptr: NonNull<u8>,
+	len: usize,
+}
+
+impl ShmMapping {
+	/// Maps the first `len` bytes of `file`.
+	///
+	/// The file must be sealed against shrinking, so the mapping stays backed for its
+	/// whole lifetime and reading it cannot raise `SIGBUS`.
+	fn new(file: &File, len: usize) -> Result<Self, ShmImportError> {
+		let ptr = unsafe {
+			libc::mmap(
+				std::ptr::null_mut(),
+				len,
+				libc::PROT_READ,
+				libc::MAP_SHARED,
+				file.as_raw_fd(),
+				0,
+			)
+		};
+		if ptr == libc::MAP_FAILED {
+			return Err(std::io::Error::last_os_error().into());
+		}
+		let ptr = NonNull::new(ptr.cast()).ok_or(ShmImportError::InvalidLayout)?;
+		Ok(Self { ptr, len })
+	}
+
+	fn bytes(&self) -> &[u8] {
+		// The client may still write the pixels; a torn frame is the worst outcome.
+		unsafe { std::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+	}
+}
+
+impl Drop for ShmMapping {
+	fn drop(&mut self) {
+		unsafe {
+			libc::munmap(self.ptr.as_ptr().cast(), self.len);
+		}
+	}
+}
+
+/// Client buffer in shared memory, uploaded into a texture whenever it holds a new frame.
+pub struct ShmTexture {
+	mapping: ShmMapping,
+	info: ImageInfo,
+	stride: usize,
+	offset: usize,
+	cached_image: Option<Image>,
+	stale: bool,
+}
+
+impl ShmTexture {
+	/// Maps a client's shm buffer, which must not exceed `max_size`, the monitor's mode.
+	pub fn new(
+		fd: OwnedFd,
+		width: i32,
+		height: i32,
+		stride: i32,
+		offset: i32,
+		fourcc: i32,
+		max_size: (i32, i32),
+	) -> Result<Self, ShmImportError> {
+		let alpha_type = match fourcc {
+			FOURCC_XRGB8888 => AlphaType::Opaque,
+			FOURCC_ARGB8888 => AlphaType::Premul,
+			other => return Err(ShmImportError::UnsupportedFormat(other)),
+		};
+		let (max_width, max_height) = max_size;
+		if !(1..=max_width).contains(&width) || !(1..=max_height).contains(&height) {
+			return Err(ShmImportError::InvalidLayout);
+		}
+		let (Ok(stride), Ok(offset)) = (usize::try_from(stride), usize::try_from(offset)) else {
+			return Err(ShmImportError::InvalidLayout);
+		};
+		if stride < width as usize * 4 {
+			return Err(ShmImportError::InvalidLayout);
+		}
+		let len = stride
+			.checked_mul(height as usize)
+			.and_then(|pixels| pixels.checked_add(offset))
+			.ok_or(ShmImportError::InvalidLayout)?;
+		let file = File::from(fd);
+		let seals = unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GET_SEALS) };
+		if seals < 0 {
+			return Err(std::io::Error::last_os_error().into());
+		}
+		let required = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW;
+		if seals & required != required {
+			return Err(ShmImportError::Unsealed);
+		}
+		if file.metadata()?.len() < len as u64 {
+			return Err(ShmImportError::InvalidLayout);
+		}
+		let mapping = ShmMapping::new(&file, len)?;
+		// DRM formats name the channels of a little-endian word, so XRGB is BGRA in memory.
+		let info = ImageInfo::new((width, height), ColorType::BGRA8888, alpha_type, None);
+		Ok(Self {
+			mapping,
+			info,
+			stride,
+			offset,
+			cached_image: None,
+			stale: true,
+		})
+	}
+
+	/// Re-uploads the buffer on its next use; called when the client submits it.
+	pub fn mark_stale(&mut self) {
+		self.stale = true;
+	}
+
+	pub fn set_color_space(&mut self, color_space: Option<ColorSpace>) {
+		if self.info.color_space() != color_space {
+			self.info = self.info.with_color_space(color_space);
+			self.stale = true;
+		}
+	}
+
+	pub fn image<'a>(&'a mut self, gr: &mut gpu::DirectContext) -> Option<&'a Image> {
+		if self.stale {
+			self.stale = false;
+			self.cached_image = self.upload(gr);
+		}
+		self.cached_image.as_ref()
+	}
+
+	fn upload(&self, gr: &mut gpu::DirectContext) -> Option<Image> {
+		let pixels = &self.mapping.bytes()[self.offset..];
+		// The raster only lives until the texture upload below has copied the pixels.
+		let data = unsafe { skia_safe::Data::new_bytes(pixels) };
+		let raster = images::raster_from_data(&self.info, data, self.stride)?;
+		gpu::images::texture_from_image(gr, &raster, gpu::Mipmapped::No, gpu::Budgeted::Yes)
+	}
+}
+
+/// Texture backing one of a session's buffer slots, imported from a dmabuf or shm.
+pub enum SlotTexture {
+	DmaBuf(SkiaDmaBufTexture),
+	Shm(ShmTexture),
+}
+
+impl SlotTexture {
+	pub fn set_color_space(&mut self, color_space: Option<ColorSpace>) {
+		match self {
+			Self::DmaBuf(texture) => texture.set_color_space(color_space),
+			Self::Shm(texture) => texture.set_color_space(color_space),
+		}
+	}
+
+	pub fn image<'a>(&'a mut self, gr: &mut gpu::DirectContext) -> Option<&'a Image> {
+		match self {
+			Self::DmaBuf(texture) => texture.image(gr),
+			Self::Shm(texture) => texture.image(gr),
+		}
+	}
+}
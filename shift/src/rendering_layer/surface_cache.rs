@@ -5,9 +5,11 @@ use skia_safe::{
 	self as skia, FilterMode, MipmapMode, Paint, SamplingOptions, gpu, gpu::gl::FramebufferInfo,
 };
 
+use tab_protocol::MonitorMode;
+
 use crate::monitor::{Monitor as ServerLayerMonitor, MonitorId};
 
-use super::{RenderError, dmabuf_import::SkiaDmaBufTexture};
+use super::{RenderError, dmabuf_import::SkiaDmaBufTexture, edid};
 
 pub struct MonitorRenderState {
 	pub surfaces_by_fbo: HashMap<i32, skia::Surface>,
@@ -74,7 +76,19 @@ impl MonitorRenderState {
 			width: monitor.size().0 as _,
 			id: monitor.context().id,
 			name: format!("Monitor {}", u32::from(monitor.connector_id())),
+			color: edid::color_info(u32::from(monitor.connector_id())),
+			scale: monitor_scale(u32::from(monitor.connector_id())),
+			primary: false,
 			refresh_rate: monitor.active_mode().vrefresh(),
+			modes: monitor
+				.modes()
+				.iter()
+				.map(|mode| MonitorMode {
+					width: mode.size().0 as i32,
+					height: mode.size().1 as i32,
+					refresh_rate: mode.vrefresh() as i32,
+				})
+				.collect(),
 		}
 	}
 
@@ -98,6 +112,28 @@ impl MonitorRenderState {
 	}
 }
 
+/// Scale advertised for a connector, from `SHIFT_MONITOR_SCALE`.
+///
+/// The variable holds either one scale for every monitor (`1.5`) or a comma-separated
+/// list of `connector_id:scale` pairs (`42:1.5,43:2`); anything unparsable means 1.0.
+fn monitor_scale(connector_id: u32) -> f64 {
+	let Ok(value) = std::env::var("SHIFT_MONITOR_SCALE") else {
+		return 1.0;
+	};
+	let scale = if value.contains(':') {
+		value.split(',').find_map(|entry| {
+			let (id, scale) = entry.split_once(':')?;
+			(id.trim().parse::<u32>().ok()? == connector_id).then_some(scale)
+		})
+	} else {
+		Some(value.as_str())
+	};
+	scale
+		.and_then(|scale| scale.trim().parse::<f64>().ok())
+		.filter(|scale| scale.is_finite() && *scale > 0.0)
+		.unwrap_or(1.0)
+}
+
 fn skia_surface_for_fbo(
 	gr: &mut gpu::DirectContext,
 	width: usize,
@@ -122,7 +158,8 @@ fn skia_surface_for_fbo(
 		&backend_rt,
 		gpu::SurfaceOrigin::TopLeft,
 		skia::ColorType::RGBA8888,
-		None,
+		// Composition happens in sRGB; frames tagged with another colorspace are converted.
+		skia::ColorSpace::new_srgb(),
 		None,
 	)
 	.ok_or(RenderError::SkiaSurface)
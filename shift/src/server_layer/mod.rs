@@ -1,4 +1,5 @@
 mod server;
+mod shortcuts;
 
 pub use server::BindError;
 pub use server::ShiftServer;
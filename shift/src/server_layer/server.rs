@@ -1,9 +1,10 @@
 use std::{
-	collections::{HashMap, HashSet},
+	collections::{BTreeSet, HashMap, HashSet},
 	fs::Permissions,
 	future::pending,
-	io,
-	os::unix::fs::PermissionsExt,
+	io::{self, PipeReader, Write},
+	os::fd::AsRawFd,
+	os::unix::{fs::PermissionsExt, process::CommandExt},
 	path::{Path, PathBuf},
 	process::Command,
 	sync::Arc,
@@ -39,7 +40,13 @@ use crate::{
 	rendering_layer::channels::ServerEnd as RenderServerChannels,
 	sessions::{PendingSession, Role, Session, SessionId},
 };
-use tab_protocol::{InputEventPayload, SessionInfo, SessionLifecycle, SessionRole};
+use tab_protocol::{
+	AccessibilitySettings, CaptureSource, CrashReportPayload, GlobalShortcutRegisterPayload,
+	InputDeviceInfo, InputEventPayload, MonitorPower, SeatId, SeatInputEventPayload, SessionInfo,
+	SessionLifecycle, SessionMessageSendPayload, SessionRole,
+};
+
+use super::shortcuts::{GlobalShortcuts, KeyDisposition};
 
 #[derive(Debug, Clone, Copy)]
 struct PendingFlip {
@@ -72,10 +79,26 @@ impl Drop for ConnectedClient {
 }
 pub struct ShiftServer {
 	listener: Option<UnixListener>,
+	/// Passed to spawned sessions as `SHIFT_SOCKET`.
+	socket_path: PathBuf,
 	current_session: Option<SessionId>,
 	pending_sessions: HashMap<Token, PendingSession>,
 	active_sessions: HashMap<SessionId, Arc<Session>>,
 	loading_sessions: HashSet<SessionId>,
+	idle_inhibitors: HashMap<SessionId, Option<String>>,
+	global_shortcuts: GlobalShortcuts,
+	/// Admin session that receives key events instead of the active session.
+	keyboard_grab: Option<SessionId>,
+	/// Messages from admin sessions that non-admin receivers may still answer, as
+	/// `(receiver, sender, correlation_id)`.
+	session_message_replies: HashSet<(SessionId, SessionId, u64)>,
+	/// Monitor areas admin sessions draw chrome over; monitors without any are absent.
+	safe_areas: HashMap<MonitorId, Arc<[tab_protocol::Rect]>>,
+	/// Monitors whose display is not [`MonitorPower::On`].
+	monitor_power: HashMap<MonitorId, MonitorPower>,
+	/// Accessibility settings last set by an admin session.
+	accessibility: AccessibilitySettings,
+	primary_monitor: Option<MonitorId>,
 	awake_sessions: HashSet<SessionId>,
 	awake_until: HashMap<SessionId, Instant>,
 	connected_clients: HashMap<ClientId, ConnectedClient>,
@@ -83,6 +106,8 @@ pub struct ShiftServer {
 	render_events: RenderEvtRx,
 	input_events: InputEvtRx,
 	monitors: HashMap<MonitorId, Monitor>,
+	input_devices: HashMap<u32, InputDeviceInfo>,
+	drm_device: Option<tab_protocol::DrmDeviceInfo>,
 	pending_buffer_requests: Vec<PendingBufferRequest>,
 	waiting_flip: Vec<PendingFlip>,
 	front_buffers: HashMap<(SessionId, MonitorId), tab_protocol::BufferIndex>,
@@ -94,13 +119,38 @@ pub struct ShiftServer {
 	debug_admin_session_id: Option<SessionId>,
 	debug_second_session_id: Option<SessionId>,
 	debug_auto_switch_interval: Option<Duration>,
-	pending_input_motion: Option<(SessionId, InputEventPayload)>,
+	pending_input_motion: Option<(SessionId, SeatInputEventPayload)>,
 }
 #[derive(Error, Debug)]
 pub enum BindError {
 	#[error("io error: {0}")]
 	IOError(#[from] std::io::Error),
 }
+
+/// Hands `token` to the process spawned by `cmd` through a pipe named by
+/// `SHIFT_SESSION_TOKEN_FD`, so it never shows up in the child's environment or arguments.
+///
+/// The returned read end must stay open until the process is spawned.
+fn pass_token(cmd: &mut Command, token: &Token) -> io::Result<PipeReader> {
+	let (reader, mut writer) = io::pipe()?;
+	// Tokens are far smaller than a pipe buffer, so this does not block.
+	writer.write_all(token.to_string().as_bytes())?;
+	drop(writer);
+	let fd = reader.as_raw_fd();
+	cmd.env("SHIFT_SESSION_TOKEN_FD", fd.to_string());
+	// SAFETY: only calls the async-signal-safe fcntl between fork and exec.
+	unsafe {
+		cmd.pre_exec(move || {
+			// Clear close-on-exec so the child inherits the read end.
+			if libc::fcntl(fd, libc::F_SETFD, 0) == -1 {
+				return Err(io::Error::last_os_error());
+			}
+			Ok(())
+		});
+	}
+	Ok(reader)
+}
+
 impl ShiftServer {
 	#[tracing::instrument(level= "info", skip(path), fields(path = ?path.as_ref().display()))]
 	pub async fn bind(
@@ -131,10 +181,19 @@ impl ShiftServer {
 			});
 		Ok(Self {
 			listener: Some(listener),
+			socket_path: path.as_ref().to_path_buf(),
 			current_session: Default::default(),
 			pending_sessions: Default::default(),
 			active_sessions: Default::default(),
 			loading_sessions: Default::default(),
+			idle_inhibitors: Default::default(),
+			global_shortcuts: Default::default(),
+			keyboard_grab: None,
+			session_message_replies: Default::default(),
+			safe_areas: Default::default(),
+			monitor_power: Default::default(),
+			accessibility: Default::default(),
+			primary_monitor: None,
 			awake_sessions: Default::default(),
 			awake_until: Default::default(),
 			connected_clients: Default::default(),
@@ -142,6 +201,8 @@ impl ShiftServer {
 			render_events,
 			input_events,
 			monitors: Default::default(),
+			input_devices: Default::default(),
+			drm_device: None,
 			pending_buffer_requests: Default::default(),
 			waiting_flip: Default::default(),
 			front_buffers: Default::default(),
@@ -172,8 +233,9 @@ impl ShiftServer {
 		let shell = std::env::var("SHELL").unwrap_or_else(|_| "bash".to_string());
 		let mut cmd = Command::new(shell);
 		cmd.args(["-c", &cmdline]);
-		cmd.env("SHIFT_SESSION_TOKEN", token.to_string());
-		match cmd.spawn() {
+		cmd.env("SHIFT_SOCKET", &self.socket_path);
+		let spawned = pass_token(&mut cmd, &token).and_then(|_pipe| cmd.spawn());
+		match spawned {
 			Ok(child) => {
 				self.debug_second_session_id = Some(session_id);
 				tracing::info!(
@@ -211,6 +273,12 @@ impl ShiftServer {
 		if self.current_session == Some(target) {
 			return;
 		}
+		if let Some(current) = self.current_session
+			&& self.idle_inhibitors.contains_key(&current)
+		{
+			tracing::debug!(%current, "debug auto-switch skipped: session inhibits idle");
+			return;
+		}
 		let previous = self.current_session;
 		tracing::info!(%target, "debug auto-switch session");
 		let transition = previous.and_then(|from_session_id| {
@@ -284,6 +352,9 @@ impl ShiftServer {
 		for session_id in &self.loading_sessions {
 			self.awake_sessions.insert(*session_id);
 		}
+		for session_id in self.idle_inhibitors.keys() {
+			self.awake_sessions.insert(*session_id);
+		}
 		for (session_id, deadline) in &self.awake_until {
 			if *deadline > now {
 				self.awake_sessions.insert(*session_id);
@@ -340,21 +411,34 @@ impl ShiftServer {
 			} else {
 				SessionLifecycle::Loading
 			},
+			metadata: session.metadata().clone(),
 		}
 	}
 
 	async fn notify_admins_session_state(&mut self, session: &Session) {
+		self.notify_session_state_where(session, |_| false).await;
+	}
+
+	/// Sends `session`'s state to admin clients and to clients matching `also`.
+	async fn notify_session_state_where(
+		&mut self,
+		session: &Session,
+		also: impl Fn(SessionId) -> bool,
+	) {
 		let info = Self::session_info_from(session);
-		let admin_client_ids = self
+		let client_ids = self
 			.connected_clients
 			.iter()
 			.filter_map(|(id, client)| {
 				let session_id = client.client_view.authenticated_session()?;
+				if also(session_id) {
+					return Some(*id);
+				}
 				let session = self.active_sessions.get(&session_id)?;
 				(session.role() == Role::Admin).then_some(*id)
 			})
 			.collect::<Vec<_>>();
-		for id in admin_client_ids {
+		for id in client_ids {
 			let Some(client) = self.connected_clients.get_mut(&id) else {
 				continue;
 			};
@@ -375,8 +459,8 @@ impl ShiftServer {
 		if let Some(admin_launch_cmd) = admin_launch_cmd {
 			let mut cmd = Command::new(shell);
 			cmd.args(["-c", &admin_launch_cmd]);
-			cmd.env("SHIFT_SESSION_TOKEN", token.to_string());
-			if let Err(e) = cmd.spawn() {
+			cmd.env("SHIFT_SOCKET", &self.socket_path);
+			if let Err(e) = pass_token(&mut cmd, &token).and_then(|_pipe| cmd.spawn()) {
 				panic!("Failed to start admin session process: {e}");
 			}
 		}
@@ -450,6 +534,11 @@ impl ShiftServer {
 			C2SMsg::Shutdown => {
 				self.disconnect_client(client_id).await;
 			}
+			C2SMsg::Ping => {
+				if let Some(client) = self.connected_clients.get_mut(&client_id) {
+					client.client_view.notify_pong().await;
+				}
+			}
 			C2SMsg::Auth(token) => {
 				let Some(pending_session) = self.pending_sessions.remove(&token) else {
 					if let Some(client) = self.connected_clients.get_mut(&client_id) {
@@ -476,6 +565,26 @@ impl ShiftServer {
 					tracing::warn!("failed to notify auth success, removing client");
 					return;
 				}
+				if let Some(client) = self.connected_clients.get_mut(&client_id) {
+					for (monitor_id, occluded) in &self.safe_areas {
+						client
+							.client_view
+							.notify_safe_area(*monitor_id, Arc::clone(occluded))
+							.await;
+					}
+					for (monitor_id, power) in &self.monitor_power {
+						client
+							.client_view
+							.notify_monitor_power(*monitor_id, *power)
+							.await;
+					}
+					if self.accessibility != AccessibilitySettings::default() {
+						client
+							.client_view
+							.notify_accessibility(self.accessibility)
+							.await;
+					}
+				}
 				self
 					.active_sessions
 					.insert(session.id(), Arc::clone(&session));
@@ -538,14 +647,14 @@ impl ShiftServer {
 					let Some(client_session) = client_session else {
 						connected_client
 							.client_view
-							.notify_error("forbidden".into(), None, false)
+							.notify_request_error("forbidden".into(), None, req.request_id)
 							.await;
 						return;
 					};
 					if client_session.role() != Role::Admin {
 						connected_client
 							.client_view
-							.notify_error("forbidden".into(), None, false)
+							.notify_request_error("forbidden".into(), None, req.request_id)
 							.await;
 						return;
 					}
@@ -561,7 +670,7 @@ impl ShiftServer {
 						.insert(token.clone(), pending_session.clone());
 					if !connected_client
 						.client_view
-						.notify_session_created(token, pending_session)
+						.notify_session_created(token, pending_session, req.request_id)
 						.await
 					{
 						tracing::warn!("failed to notify session created, removing client");
@@ -579,10 +688,10 @@ impl ShiftServer {
 						if let Some(client) = self.connected_clients.get_mut(&client_id) {
 							client
 								.client_view
-								.notify_error(
+								.notify_request_error(
 									"invalid_session_id".into(),
 									Some(Arc::<str>::from(e.to_string())),
-									false,
+									payload.request_id,
 								)
 								.await;
 						}
@@ -602,7 +711,7 @@ impl ShiftServer {
 					if let Some(client) = self.connected_clients.get_mut(&client_id) {
 						client
 							.client_view
-							.notify_error("forbidden".into(), None, false)
+							.notify_request_error("forbidden".into(), None, payload.request_id)
 							.await;
 					}
 					return;
@@ -611,7 +720,7 @@ impl ShiftServer {
 					if let Some(client) = self.connected_clients.get_mut(&client_id) {
 						client
 							.client_view
-							.notify_error("forbidden".into(), None, false)
+							.notify_request_error("forbidden".into(), None, payload.request_id)
 							.await;
 					}
 					return;
@@ -620,10 +729,10 @@ impl ShiftServer {
 					if let Some(client) = self.connected_clients.get_mut(&client_id) {
 						client
 							.client_view
-							.notify_error(
+							.notify_request_error(
 								"unknown_session".into(),
 								Some(Arc::<str>::from("target session is not active")),
-								false,
+								payload.request_id,
 							)
 							.await;
 					}
@@ -636,12 +745,12 @@ impl ShiftServer {
 					if let Some(client) = self.connected_clients.get_mut(&client_id) {
 						client
 							.client_view
-							.notify_error(
+							.notify_request_error(
 								"session_loading".into(),
 								Some(Arc::<str>::from(
 									"target session is still loading and cannot become active",
 								)),
-								false,
+								payload.request_id,
 							)
 							.await;
 					}
@@ -735,222 +844,989 @@ impl ShiftServer {
 					.set_awake_sessions(self.current_session.into_iter())
 					.await;
 			}
-			C2SMsg::BufferRequest {
-				monitor_id,
-				buffer,
-				acquire_fence,
-			} => {
-				let Some(connected_client) = self.connected_clients.get(&client_id) else {
-					tracing::warn!("tried handling message from a non-existing client");
+			C2SMsg::SessionUpdate(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
 					return;
 				};
-				let client_session = connected_client
-					.client_view
-					.authenticated_session()
-					.and_then(|s| self.active_sessions.get(&s))
-					.map(Arc::clone);
-				let Some(client_session) = client_session else {
-					if let Some(client) = self.connected_clients.get_mut(&client_id) {
-						client
-							.client_view
-							.notify_error("forbidden".into(), None, false)
-							.await;
-					}
+				let Some(existing) = self.active_sessions.get(&session_id).cloned() else {
 					return;
 				};
-				if !self.is_session_awake(client_session.id()).await {
-					if let Some(client) = self.connected_clients.get_mut(&client_id) {
-						client
-							.client_view
-							.notify_error(
-								"session_sleeping".into(),
-								Some("session is not awake".into()),
-								false,
-							)
-							.await;
-					}
+				if payload
+					.display_name
+					.as_deref()
+					.is_some_and(|name| name.trim().is_empty())
+				{
+					self
+						.reject_client_request(client_id, "invalid_argument", "empty display name")
+						.await;
 					return;
 				}
-				let owner_key = (client_session.id(), monitor_id, buffer);
-				let current_owner = self
-					.buffer_ownership
-					.get(&owner_key)
-					.copied()
-					.unwrap_or(BufferOwner::Client);
-				if current_owner != BufferOwner::Client {
-					let other_buffer = if buffer == tab_protocol::BufferIndex::Zero {
-						tab_protocol::BufferIndex::One
-					} else {
-						tab_protocol::BufferIndex::Zero
-					};
-					let other_owner = self
-						.buffer_ownership
-						.get(&(client_session.id(), monitor_id, other_buffer))
-						.copied()
-						.unwrap_or(BufferOwner::Client);
-					tracing::warn!(
-						session_id = %client_session.id(),
-						%monitor_id,
-						requested = buffer as u8,
-						requested_owner = ?current_owner,
-						other = other_buffer as u8,
-						other_owner = ?other_owner,
-						"incoming buffer request for non client-owned buffer"
-					);
-					if let Some(client) = self.connected_clients.get_mut(&client_id) {
-						client
-							.client_view
-							.notify_error(
-								"ownership_violation".into(),
-								Some("requested buffer is not client-owned".into()),
-								false,
-							)
+				tracing::info!(
+					%session_id,
+					display_name = ?payload.display_name,
+					metadata = ?payload.metadata,
+					"session info updated"
+				);
+				let updated =
+					Arc::new(existing.with_update(payload.display_name.as_deref(), &payload.metadata));
+				self
+					.active_sessions
+					.insert(session_id, Arc::clone(&updated));
+				// The session's own clients learn their new name too.
+				self
+					.notify_session_state_where(&updated, |id| id == session_id)
+					.await;
+			}
+			C2SMsg::SessionPreviewRequest(payload) => {
+				let session_id = match payload.session_id.parse::<SessionId>() {
+					Ok(session_id) => session_id,
+					Err(e) => {
+						self
+							.reject_client_request(client_id, "invalid_session_id", &e.to_string())
 							.await;
+						return;
 					}
+				};
+				if !self.active_sessions.contains_key(&session_id) {
+					self
+						.reject_client_request(client_id, "unknown_session", &payload.session_id)
+						.await;
 					return;
 				}
-				if self.pending_buffer_requests.iter().any(|pending| {
-					pending.session_id == client_session.id() && pending.monitor_id == monitor_id
-				}) {
-					if let Some(client) = self.connected_clients.get_mut(&client_id) {
-						client
-							.client_view
-							.notify_error(
-								"buffer_request_inflight".into(),
-								Some("monitor already has an in-flight buffer request".into()),
-								false,
-							)
-							.await;
+				let monitor_id = match payload.monitor_id.as_deref() {
+					Some(raw) => {
+						let Some(monitor_id) = self.resolve_client_monitor(client_id, raw).await else {
+							return;
+						};
+						Some(monitor_id)
 					}
+					None => None,
+				};
+				if payload.max_width <= 0 || payload.max_height <= 0 {
+					self
+						.reject_client_request(
+							client_id,
+							"invalid_argument",
+							"preview size must be positive",
+						)
+						.await;
 					return;
 				}
 				if let Err(e) = self
 					.render_commands
-					.send(RenderCmd::SwapBuffers {
-						monitor_id,
-						buffer,
-						session_id: client_session.id(),
-						acquire_fence,
-					})
-					.await
-				{
-					tracing::error!("failed to forward SwapBuffers to renderer: {e}");
-					let code = Arc::<str>::from("render_unavailable");
-					let detail = Some(Arc::<str>::from("renderer unavailable"));
-					if let Some(client) = self.connected_clients.get_mut(&client_id) {
-						client.client_view.notify_error(code, detail, true).await;
-					}
-				} else {
-					self.pending_buffer_requests.push(PendingBufferRequest {
+					.send(RenderCmd::SessionPreview {
 						client_id,
-						session_id: client_session.id(),
-						monitor_id,
-						buffer,
-					});
-				}
-			}
-			C2SMsg::FramebufferLink { payload, dma_bufs } => {
-				let monitor_id_raw = payload.monitor_id.clone();
-				let session_id = {
-					let Some(client) = self.connected_clients.get_mut(&client_id) else {
-						tracing::warn!("tried handling message from a non-existing client");
-						return;
-					};
-					let Some(session_id) = client.client_view.authenticated_session() else {
-						client
-							.client_view
-							.notify_error("forbidden".into(), None, false)
-							.await;
-						return;
-					};
-					session_id
-				};
-				if let Err(e) = self
-					.render_commands
-					.send(RenderCmd::FramebufferLink {
-						payload,
-						dma_bufs,
 						session_id,
+						monitor_id,
+						max_size: (payload.max_width, payload.max_height),
 					})
 					.await
 				{
-					tracing::error!("failed to forward FramebufferLink to renderer: {e}");
-					let code = Arc::<str>::from("render_unavailable");
-					let detail = Some(Arc::<str>::from("renderer unavailable"));
-					if let Some(client) = self.connected_clients.get_mut(&client_id) {
-						client.client_view.notify_error(code, detail, true).await;
-					}
-				} else {
-					let Ok(monitor_id) = monitor_id_raw.parse::<MonitorId>() else {
-						return;
-					};
-					self.waiting_flip.retain(|pending| {
-						!(pending.session_id == session_id && pending.monitor_id == monitor_id)
-					});
-					self.pending_buffer_requests.retain(|pending| {
-						!(pending.session_id == session_id && pending.monitor_id == monitor_id)
-					});
-					self.front_buffers.remove(&(session_id, monitor_id));
-					self.buffer_ownership.insert(
-						(session_id, monitor_id, tab_protocol::BufferIndex::Zero),
-						BufferOwner::Client,
-					);
-					self.buffer_ownership.insert(
-						(session_id, monitor_id, tab_protocol::BufferIndex::One),
-						BufferOwner::Client,
-					);
+					tracing::error!("failed to forward SessionPreview to renderer: {e}");
 				}
 			}
-		}
-	}
-	async fn handle_render_event(&mut self, event: RenderEvt) {
-		match event {
-			RenderEvt::Started { monitors } => {
-				self.monitors = monitors.into_iter().map(|m| (m.id, m)).collect();
-			}
-			RenderEvt::MonitorOnline { monitor } => {
-				tracing::info!(?monitor, "renderer reports monitor online");
-				self.broadcast_monitor_added(&monitor).await;
-				self.monitors.insert(monitor.id, monitor);
-			}
-			RenderEvt::MonitorOffline { monitor_id } => {
-				tracing::info!(%monitor_id, "renderer reports monitor offline");
-				if let Some(monitor) = self.monitors.remove(&monitor_id) {
-					self.broadcast_monitor_removed(&monitor).await;
+			C2SMsg::IdleInhibit(payload) => {
+				let Some(session_id) = self
+					.connected_clients
+					.get(&client_id)
+					.and_then(|client| client.client_view.authenticated_session())
+				else {
+					return;
+				};
+				if payload.inhibit {
+					tracing::info!(%session_id, reason = ?payload.reason, "session inhibits idle");
+					self.idle_inhibitors.insert(session_id, payload.reason);
+				} else if self.idle_inhibitors.remove(&session_id).is_some() {
+					tracing::info!(%session_id, "session released idle inhibit");
 				}
+				self.prune_expired_awake_sessions().await;
 				self
-					.waiting_flip
-					.retain(|pending| pending.monitor_id != monitor_id);
-				self
-					.pending_buffer_requests
-					.retain(|pending| pending.monitor_id != monitor_id);
-				self.front_buffers.retain(|(_, mon), _| *mon != monitor_id);
-				self
-					.buffer_ownership
-					.retain(|(_, mon, _), _| *mon != monitor_id);
+					.set_awake_sessions(self.current_session.into_iter())
+					.await;
 			}
-			RenderEvt::BufferRequestAck {
-				session_id,
-				monitor_id,
-				buffer,
-			} => {
-				let Some(pos) = self.pending_buffer_requests.iter().position(|pending| {
-					pending.session_id == session_id
-						&& pending.monitor_id == monitor_id
-						&& pending.buffer == buffer
-				}) else {
-					tracing::warn!(%session_id, %monitor_id, buffer = buffer as u8, "renderer acked unknown pending request");
+			C2SMsg::GlobalShortcutRegister(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
 					return;
 				};
-				let pending = self.pending_buffer_requests.remove(pos);
-				self
-					.buffer_ownership
-					.insert((session_id, monitor_id, buffer), BufferOwner::Shift);
-				self.swap_buffers_received = self.swap_buffers_received.saturating_add(1);
-
-				let mut should_disconnect = false;
-				if let Some(client) = self.connected_clients.get_mut(&pending.client_id) {
-					if !client
+				let keys = payload.keys.iter().copied().collect::<BTreeSet<_>>();
+				let valid = !payload.shortcut_id.is_empty()
+					&& !keys.is_empty()
+					&& keys.len() == payload.keys.len()
+					&& keys.len() <= GlobalShortcutRegisterPayload::MAX_KEYS;
+				if !valid {
+					self
+						.reject_tagged_request(
+							client_id,
+							"invalid_argument",
+							"shortcuts need an id and up to MAX_KEYS distinct keys",
+							payload.request_id,
+						)
+						.await;
+					return;
+				}
+				if !self
+					.global_shortcuts
+					.register(session_id, payload.shortcut_id.clone(), keys)
+				{
+					// Untagged: clients match the conflict by its code to drop the shortcut.
+					self
+						.reject_client_request(client_id, "shortcut_conflict", &payload.shortcut_id)
+						.await;
+					return;
+				}
+				tracing::info!(
+					%session_id,
+					shortcut_id = %payload.shortcut_id,
+					keys = ?payload.keys,
+					"global shortcut registered"
+				);
+			}
+			C2SMsg::GlobalShortcutUnregister(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				if self
+					.global_shortcuts
+					.unregister(session_id, &payload.shortcut_id)
+				{
+					tracing::info!(
+						%session_id,
+						shortcut_id = %payload.shortcut_id,
+						"global shortcut unregistered"
+					);
+				}
+			}
+			C2SMsg::KeyboardGrab(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				match self.keyboard_grab {
+					Some(holder) if holder != session_id && payload.grab => {
+						self
+							.reject_client_request(
+								client_id,
+								"grab_unavailable",
+								"another session holds the keyboard grab",
+							)
+							.await;
+					}
+					Some(holder) if holder == session_id && !payload.grab => {
+						tracing::info!(%session_id, "session released keyboard grab");
+						self.keyboard_grab = None;
+					}
+					None if payload.grab => {
+						tracing::info!(%session_id, "session grabbed keyboard");
+						self.keyboard_grab = Some(session_id);
+					}
+					_ => {}
+				}
+			}
+			C2SMsg::InputInject(payload) => {
+				let session_id = match payload.session_id.parse::<SessionId>() {
+					Ok(session_id) => session_id,
+					Err(e) => {
+						self
+							.reject_tagged_request(
+								client_id,
+								"invalid_session_id",
+								&e.to_string(),
+								payload.request_id,
+							)
+							.await;
+						return;
+					}
+				};
+				if !self.active_sessions.contains_key(&session_id) {
+					self
+						.reject_tagged_request(
+							client_id,
+							"unknown_session",
+							&payload.session_id,
+							payload.request_id,
+						)
+						.await;
+					return;
+				}
+				tracing::debug!(%session_id, ?payload.input, "injecting input");
+				self
+					.forward_input_event_to_session(session_id, payload.input)
+					.await;
+			}
+			C2SMsg::SessionMessageSend(payload) => {
+				let Some(sender) = self.client_session_id(client_id) else {
+					return;
+				};
+				let Ok(target) = payload.session_id.parse::<SessionId>() else {
+					self
+						.reject_client_request(client_id, "invalid_session_id", &payload.session_id)
+						.await;
+					return;
+				};
+				if payload.data.len() > SessionMessageSendPayload::MAX_DATA_LEN {
+					self
+						.reject_client_request(
+							client_id,
+							"invalid_argument",
+							"session messages carry at most MAX_DATA_LEN bytes",
+						)
+						.await;
+					return;
+				}
+				let is_admin = |session_id: SessionId| {
+					self
+						.active_sessions
+						.get(&session_id)
+						.is_some_and(|session| session.role() == Role::Admin)
+				};
+				let (sender_admin, target_admin) = (is_admin(sender), is_admin(target));
+				let allowed = self.active_sessions.contains_key(&target)
+					&& (sender_admin
+						|| target_admin
+						|| payload.reply_to.is_some_and(|reply_to| {
+							self
+								.session_message_replies
+								.remove(&(sender, target, reply_to))
+						}));
+				if !allowed || !self.deliver_session_message(sender, target, &payload).await {
+					self
+						.reject_client_request(client_id, "message_undeliverable", &payload.session_id)
+						.await;
+					return;
+				}
+				if let Some(correlation_id) = payload.correlation_id
+					&& !target_admin
+				{
+					self
+						.session_message_replies
+						.insert((target, sender, correlation_id));
+				}
+			}
+			C2SMsg::CrashReport(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				if payload.message.len() > CrashReportPayload::MAX_MESSAGE_LEN {
+					self
+						.reject_client_request(
+							client_id,
+							"invalid_argument",
+							"crash reports carry at most MAX_MESSAGE_LEN bytes",
+						)
+						.await;
+					return;
+				}
+				tracing::warn!(
+					%session_id,
+					message = %payload.message,
+					backtrace_hash = payload.backtrace_hash,
+					app_version = ?payload.app_version,
+					"session crashed"
+				);
+				self
+					.notify_admins_session_crashed(session_id, payload)
+					.await;
+			}
+			C2SMsg::MonitorModeSet(payload) => {
+				let Some(monitor_id) = self
+					.resolve_tagged_monitor(client_id, &payload.monitor_id, payload.request_id)
+					.await
+				else {
+					return;
+				};
+				let supported = self
+					.monitors
+					.get(&monitor_id)
+					.is_some_and(|monitor| monitor.modes.contains(&payload.mode));
+				if !supported {
+					self
+						.reject_tagged_request(
+							client_id,
+							"invalid_argument",
+							"mode is not supported by the monitor",
+							payload.request_id,
+						)
+						.await;
+					return;
+				}
+				tracing::info!(%monitor_id, mode = ?payload.mode, "monitor mode change requested");
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::SetMonitorMode {
+						monitor_id,
+						mode: payload.mode,
+					})
+					.await
+				{
+					tracing::error!("failed to forward SetMonitorMode to renderer: {e}");
+				}
+			}
+			C2SMsg::MonitorBrightness(payload) => {
+				let Some(monitor_id) = self
+					.resolve_tagged_monitor(client_id, &payload.monitor_id, payload.request_id)
+					.await
+				else {
+					return;
+				};
+				if !(0.0..=1.0).contains(&payload.brightness) {
+					self
+						.reject_tagged_request(
+							client_id,
+							"invalid_argument",
+							"brightness must be within 0..=1",
+							payload.request_id,
+						)
+						.await;
+					return;
+				}
+				tracing::info!(%monitor_id, brightness = payload.brightness, "monitor brightness changed");
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::SetMonitorBrightness {
+						monitor_id,
+						brightness: payload.brightness,
+					})
+					.await
+				{
+					tracing::error!("failed to forward SetMonitorBrightness to renderer: {e}");
+				}
+			}
+			C2SMsg::ScanoutHint(payload) => {
+				let Some(session_id) = self
+					.connected_clients
+					.get(&client_id)
+					.and_then(|client| client.client_view.authenticated_session())
+				else {
+					return;
+				};
+				let Some(monitor_id) = self
+					.resolve_client_monitor(client_id, &payload.monitor_id)
+					.await
+				else {
+					return;
+				};
+				tracing::debug!(
+					%session_id,
+					%monitor_id,
+					fullscreen_opaque = payload.fullscreen_opaque,
+					"scanout hint changed"
+				);
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::SetScanoutHint {
+						monitor_id,
+						session_id,
+						fullscreen_opaque: payload.fullscreen_opaque,
+					})
+					.await
+				{
+					tracing::error!("failed to forward SetScanoutHint to renderer: {e}");
+				}
+			}
+			C2SMsg::SurfaceColorspace(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				let Some(monitor_id) = self
+					.resolve_client_monitor(client_id, &payload.monitor_id)
+					.await
+				else {
+					return;
+				};
+				tracing::debug!(
+					%session_id,
+					%monitor_id,
+					colorspace = ?payload.colorspace,
+					"surface colorspace changed"
+				);
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::SetSurfaceColorspace {
+						monitor_id,
+						session_id,
+						colorspace: payload.colorspace,
+					})
+					.await
+				{
+					tracing::error!("failed to forward SetSurfaceColorspace to renderer: {e}");
+				}
+			}
+			C2SMsg::OverlayCreate { payload, dma_bufs } => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				let Some(monitor_id) = self
+					.resolve_client_monitor(client_id, &payload.monitor_id)
+					.await
+				else {
+					return;
+				};
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::OverlayCreate {
+						monitor_id,
+						session_id,
+						payload,
+						dma_bufs,
+					})
+					.await
+				{
+					tracing::error!("failed to forward OverlayCreate to renderer: {e}");
+				}
+			}
+			C2SMsg::OverlayMove(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::OverlayMove {
+						session_id,
+						overlay_id: payload.overlay_id,
+						position: (payload.x, payload.y),
+					})
+					.await
+				{
+					tracing::error!("failed to forward OverlayMove to renderer: {e}");
+				}
+			}
+			C2SMsg::OverlayPresent {
+				payload,
+				acquire_fence,
+			} => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::OverlayPresent {
+						session_id,
+						overlay_id: payload.overlay_id,
+						buffer: payload.buffer,
+						acquire_fence,
+					})
+					.await
+				{
+					tracing::error!("failed to forward OverlayPresent to renderer: {e}");
+				}
+			}
+			C2SMsg::OverlayDestroy(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::OverlayDestroy {
+						session_id,
+						overlay_id: payload.overlay_id,
+					})
+					.await
+				{
+					tracing::error!("failed to forward OverlayDestroy to renderer: {e}");
+				}
+			}
+			C2SMsg::CaptureStart { payload, dma_bufs } => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				let (raw_monitor_id, source_session) = match &payload.source {
+					CaptureSource::Monitor { monitor_id } => (monitor_id, None),
+					CaptureSource::Session {
+						session_id,
+						monitor_id,
+					} => (monitor_id, Some(session_id)),
+				};
+				let source_session = match source_session.map(|raw| raw.parse::<SessionId>()) {
+					Some(Ok(source)) if self.active_sessions.contains_key(&source) => Some(source),
+					Some(Ok(_)) => {
+						self
+							.reject_client_request(client_id, "unknown_session", "capture source")
+							.await;
+						return;
+					}
+					Some(Err(e)) => {
+						self
+							.reject_client_request(client_id, "invalid_session_id", &e.to_string())
+							.await;
+						return;
+					}
+					None => None,
+				};
+				let Some(monitor_id) = self.resolve_client_monitor(client_id, raw_monitor_id).await else {
+					return;
+				};
+				if payload.capture_id.is_empty() || payload.width <= 0 || payload.height <= 0 {
+					self
+						.reject_client_request(
+							client_id,
+							"invalid_argument",
+							"captures need an id and a positive size",
+						)
+						.await;
+					return;
+				}
+				tracing::info!(
+					%session_id,
+					capture_id = %payload.capture_id,
+					source = ?payload.source,
+					"capture started"
+				);
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::CaptureStart {
+						session_id,
+						monitor_id,
+						source_session,
+						payload,
+						dma_bufs,
+					})
+					.await
+				{
+					tracing::error!("failed to forward CaptureStart to renderer: {e}");
+				}
+			}
+			C2SMsg::CaptureStop(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::CaptureStop {
+						session_id,
+						capture_id: payload.capture_id,
+					})
+					.await
+				{
+					tracing::error!("failed to forward CaptureStop to renderer: {e}");
+				}
+			}
+			C2SMsg::CaptureRelease(payload) => {
+				let Some(session_id) = self.client_session_id(client_id) else {
+					return;
+				};
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::CaptureRelease {
+						session_id,
+						capture_id: payload.capture_id,
+						buffer: payload.buffer,
+					})
+					.await
+				{
+					tracing::error!("failed to forward CaptureRelease to renderer: {e}");
+				}
+			}
+			C2SMsg::MonitorPrimarySet(payload) => {
+				let Some(monitor_id) = self
+					.resolve_tagged_monitor(client_id, &payload.monitor_id, payload.request_id)
+					.await
+				else {
+					return;
+				};
+				if self.primary_monitor == Some(monitor_id) {
+					return;
+				}
+				tracing::info!(%monitor_id, "primary monitor changed");
+				let previous = self.primary_monitor.replace(monitor_id);
+				for id in previous.into_iter().chain([monitor_id]) {
+					let Some(monitor) = self.monitors.get_mut(&id) else {
+						continue;
+					};
+					monitor.primary = id == monitor_id;
+					let monitor = monitor.clone();
+					self.broadcast_monitor_changed(&monitor).await;
+				}
+			}
+			C2SMsg::SafeArea(payload) => {
+				let Some(monitor_id) = self
+					.resolve_tagged_monitor(client_id, &payload.monitor_id, payload.request_id)
+					.await
+				else {
+					return;
+				};
+				if payload
+					.occluded
+					.iter()
+					.any(|rect| rect.width <= 0 || rect.height <= 0)
+				{
+					self
+						.reject_tagged_request(
+							client_id,
+							"invalid_argument",
+							"empty occluded rect",
+							payload.request_id,
+						)
+						.await;
+					return;
+				}
+				tracing::info!(%monitor_id, occluded = ?payload.occluded, "safe area changed");
+				let occluded: Arc<[tab_protocol::Rect]> = payload.occluded.into();
+				if occluded.is_empty() {
+					self.safe_areas.remove(&monitor_id);
+				} else {
+					self.safe_areas.insert(monitor_id, Arc::clone(&occluded));
+				}
+				self.broadcast_safe_area(monitor_id, occluded).await;
+			}
+			C2SMsg::Accessibility(payload) => {
+				if !payload.settings.is_valid() {
+					self
+						.reject_tagged_request(
+							client_id,
+							"invalid_argument",
+							"invalid text scale",
+							payload.request_id,
+						)
+						.await;
+					return;
+				}
+				tracing::info!(settings = ?payload.settings, "accessibility settings changed");
+				self.accessibility = payload.settings;
+				self.broadcast_accessibility(payload.settings).await;
+			}
+			C2SMsg::MonitorGamma(payload) => {
+				let Some(monitor_id) = self
+					.resolve_tagged_monitor(client_id, &payload.monitor_id, payload.request_id)
+					.await
+				else {
+					return;
+				};
+				if payload
+					.ramps
+					.as_ref()
+					.is_some_and(|ramps| !ramps.is_valid())
+				{
+					self
+						.reject_tagged_request(
+							client_id,
+							"invalid_argument",
+							"malformed gamma ramps",
+							payload.request_id,
+						)
+						.await;
+					return;
+				}
+				tracing::info!(%monitor_id, reset = payload.ramps.is_none(), "monitor gamma changed");
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::SetMonitorGamma {
+						monitor_id,
+						ramps: payload.ramps,
+					})
+					.await
+				{
+					tracing::error!("failed to forward SetMonitorGamma to renderer: {e}");
+				}
+			}
+			C2SMsg::MonitorPower(payload) => {
+				let Some(monitor_id) = self
+					.resolve_tagged_monitor(client_id, &payload.monitor_id, payload.request_id)
+					.await
+				else {
+					return;
+				};
+				tracing::info!(%monitor_id, power = ?payload.power, "monitor power requested");
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::SetMonitorPower {
+						monitor_id,
+						power: payload.power,
+					})
+					.await
+				{
+					tracing::error!("failed to forward SetMonitorPower to renderer: {e}");
+				}
+			}
+			C2SMsg::BufferRequest {
+				monitor_id,
+				buffer,
+				acquire_fence,
+			} => {
+				let Some(connected_client) = self.connected_clients.get(&client_id) else {
+					tracing::warn!("tried handling message from a non-existing client");
+					return;
+				};
+				let client_session = connected_client
+					.client_view
+					.authenticated_session()
+					.and_then(|s| self.active_sessions.get(&s))
+					.map(Arc::clone);
+				let Some(client_session) = client_session else {
+					if let Some(client) = self.connected_clients.get_mut(&client_id) {
+						client
+							.client_view
+							.notify_buffer_request_error(monitor_id, buffer, "forbidden".into(), None)
+							.await;
+					}
+					return;
+				};
+				if !self.is_session_awake(client_session.id()).await {
+					if let Some(client) = self.connected_clients.get_mut(&client_id) {
+						client
+							.client_view
+							.notify_buffer_request_error(
+								monitor_id,
+								buffer,
+								"session_sleeping".into(),
+								Some("session is not awake".into()),
+							)
+							.await;
+					}
+					return;
+				}
+				let owner_key = (client_session.id(), monitor_id, buffer);
+				let current_owner = self
+					.buffer_ownership
+					.get(&owner_key)
+					.copied()
+					.unwrap_or(BufferOwner::Client);
+				if current_owner != BufferOwner::Client {
+					let other_buffer = if buffer == tab_protocol::BufferIndex::Zero {
+						tab_protocol::BufferIndex::One
+					} else {
+						tab_protocol::BufferIndex::Zero
+					};
+					let other_owner = self
+						.buffer_ownership
+						.get(&(client_session.id(), monitor_id, other_buffer))
+						.copied()
+						.unwrap_or(BufferOwner::Client);
+					tracing::warn!(
+						session_id = %client_session.id(),
+						%monitor_id,
+						requested = buffer as u8,
+						requested_owner = ?current_owner,
+						other = other_buffer as u8,
+						other_owner = ?other_owner,
+						"incoming buffer request for non client-owned buffer"
+					);
+					if let Some(client) = self.connected_clients.get_mut(&client_id) {
+						client
+							.client_view
+							.notify_buffer_request_error(
+								monitor_id,
+								buffer,
+								"ownership_violation".into(),
+								Some("requested buffer is not client-owned".into()),
+							)
+							.await;
+					}
+					return;
+				}
+				if self.pending_buffer_requests.iter().any(|pending| {
+					pending.session_id == client_session.id() && pending.monitor_id == monitor_id
+				}) {
+					if let Some(client) = self.connected_clients.get_mut(&client_id) {
+						client
+							.client_view
+							.notify_buffer_request_error(
+								monitor_id,
+								buffer,
+								"buffer_request_inflight".into(),
+								Some("monitor already has an in-flight buffer request".into()),
+							)
+							.await;
+					}
+					return;
+				}
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::SwapBuffers {
+						monitor_id,
+						buffer,
+						session_id: client_session.id(),
+						acquire_fence,
+					})
+					.await
+				{
+					tracing::error!("failed to forward SwapBuffers to renderer: {e}");
+					let code = Arc::<str>::from("render_unavailable");
+					let detail = Some(Arc::<str>::from("renderer unavailable"));
+					if let Some(client) = self.connected_clients.get_mut(&client_id) {
+						client.client_view.notify_error(code, detail, true).await;
+					}
+				} else {
+					self.pending_buffer_requests.push(PendingBufferRequest {
+						client_id,
+						session_id: client_session.id(),
+						monitor_id,
+						buffer,
+					});
+				}
+			}
+			C2SMsg::FramebufferLink { payload, dma_bufs } => {
+				let monitor_id_raw = payload.monitor_id.clone();
+				let session_id = {
+					let Some(client) = self.connected_clients.get_mut(&client_id) else {
+						tracing::warn!("tried handling message from a non-existing client");
+						return;
+					};
+					let Some(session_id) = client.client_view.authenticated_session() else {
+						client
+							.client_view
+							.notify_error("forbidden".into(), None, false)
+							.await;
+						return;
+					};
+					session_id
+				};
+				if let Err(e) = self
+					.render_commands
+					.send(RenderCmd::FramebufferLink {
+						payload,
+						dma_bufs,
+						session_id,
+					})
+					.await
+				{
+					tracing::error!("failed to forward FramebufferLink to renderer: {e}");
+					let code = Arc::<str>::from("render_unavailable");
+					let detail = Some(Arc::<str>::from("renderer unavailable"));
+					if let Some(client) = self.connected_clients.get_mut(&client_id) {
+						client.client_view.notify_error(code, detail, true).await;
+					}
+				} else {
+					let Ok(monitor_id) = monitor_id_raw.parse::<MonitorId>() else {
+						return;
+					};
+					self.waiting_flip.retain(|pending| {
+						!(pending.session_id == session_id && pending.monitor_id == monitor_id)
+					});
+					self.pending_buffer_requests.retain(|pending| {
+						!(pending.session_id == session_id && pending.monitor_id == monitor_id)
+					});
+					self.front_buffers.remove(&(session_id, monitor_id));
+					self.buffer_ownership.insert(
+						(session_id, monitor_id, tab_protocol::BufferIndex::Zero),
+						BufferOwner::Client,
+					);
+					self.buffer_ownership.insert(
+						(session_id, monitor_id, tab_protocol::BufferIndex::One),
+						BufferOwner::Client,
+					);
+				}
+			}
+		}
+	}
+	fn client_session_id(&self, client_id: ClientId) -> Option<SessionId> {
+		self
+			.connected_clients
+			.get(&client_id)
+			.and_then(|client| client.client_view.authenticated_session())
+	}
+	async fn reject_client_request(&mut self, client_id: ClientId, code: &str, detail: &str) {
+		self
+			.reject_tagged_request(client_id, code, detail, None)
+			.await;
+	}
+	/// Rejects a request echoing the `request_id` it carried.
+	async fn reject_tagged_request(
+		&mut self,
+		client_id: ClientId,
+		code: &str,
+		detail: &str,
+		request_id: Option<u64>,
+	) {
+		if let Some(client) = self.connected_clients.get_mut(&client_id) {
+			client
+				.client_view
+				.notify_request_error(code.into(), Some(Arc::<str>::from(detail)), request_id)
+				.await;
+		}
+	}
+	/// Parses a client-supplied monitor id, reporting `unknown_monitor` when it is not connected.
+	async fn resolve_client_monitor(&mut self, client_id: ClientId, raw: &str) -> Option<MonitorId> {
+		self.resolve_tagged_monitor(client_id, raw, None).await
+	}
+	/// Like `resolve_client_monitor`, echoing `request_id` in the rejection.
+	async fn resolve_tagged_monitor(
+		&mut self,
+		client_id: ClientId,
+		raw: &str,
+		request_id: Option<u64>,
+	) -> Option<MonitorId> {
+		match raw.parse::<MonitorId>() {
+			Ok(monitor_id) if self.monitors.contains_key(&monitor_id) => Some(monitor_id),
+			_ => {
+				self
+					.reject_tagged_request(client_id, "unknown_monitor", raw, request_id)
+					.await;
+				None
+			}
+		}
+	}
+	async fn handle_render_event(&mut self, event: RenderEvt) {
+		match event {
+			RenderEvt::Started {
+				monitors,
+				drm_device,
+			} => {
+				tracing::info!(?drm_device, "renderer started");
+				self.monitors = monitors.into_iter().map(|m| (m.id, m)).collect();
+				self.drm_device = drm_device;
+			}
+			RenderEvt::MonitorOnline { mut monitor } => {
+				tracing::info!(?monitor, "renderer reports monitor online");
+				monitor.primary = self.primary_monitor == Some(monitor.id);
+				self.broadcast_monitor_added(&monitor).await;
+				self.monitors.insert(monitor.id, monitor);
+			}
+			RenderEvt::MonitorChanged { mut monitor } => {
+				tracing::info!(?monitor, "renderer reports monitor changed");
+				monitor.primary = self.primary_monitor == Some(monitor.id);
+				self.broadcast_monitor_changed(&monitor).await;
+				self.monitors.insert(monitor.id, monitor);
+			}
+			RenderEvt::MonitorPowerChanged { monitor_id, power } => {
+				if power == MonitorPower::On {
+					self.monitor_power.remove(&monitor_id);
+				} else {
+					self.monitor_power.insert(monitor_id, power);
+				}
+				self.broadcast_monitor_power(monitor_id, power).await;
+			}
+			RenderEvt::MonitorOffline { monitor_id } => {
+				tracing::info!(%monitor_id, "renderer reports monitor offline");
+				if let Some(monitor) = self.monitors.remove(&monitor_id) {
+					self.broadcast_monitor_removed(&monitor).await;
+				}
+				self.safe_areas.remove(&monitor_id);
+				self.monitor_power.remove(&monitor_id);
+				if self.primary_monitor == Some(monitor_id) {
+					self.primary_monitor = None;
+				}
+				self
+					.waiting_flip
+					.retain(|pending| pending.monitor_id != monitor_id);
+				self
+					.pending_buffer_requests
+					.retain(|pending| pending.monitor_id != monitor_id);
+				self.front_buffers.retain(|(_, mon), _| *mon != monitor_id);
+				self
+					.buffer_ownership
+					.retain(|(_, mon, _), _| *mon != monitor_id);
+			}
+			RenderEvt::BufferRequestAck {
+				session_id,
+				monitor_id,
+				buffer,
+			} => {
+				let Some(pos) = self.pending_buffer_requests.iter().position(|pending| {
+					pending.session_id == session_id
+						&& pending.monitor_id == monitor_id
+						&& pending.buffer == buffer
+				}) else {
+					tracing::warn!(%session_id, %monitor_id, buffer = buffer as u8, "renderer acked unknown pending request");
+					return;
+				};
+				let pending = self.pending_buffer_requests.remove(pos);
+				self
+					.buffer_ownership
+					.insert((session_id, monitor_id, buffer), BufferOwner::Shift);
+				self.swap_buffers_received = self.swap_buffers_received.saturating_add(1);
+
+				let mut should_disconnect = false;
+				if let Some(client) = self.connected_clients.get_mut(&pending.client_id) {
+					if !client
 						.client_view
 						.notify_buffer_request_ack(monitor_id, buffer)
 						.await
@@ -980,7 +1856,12 @@ impl ShiftServer {
 				if let Some(client) = self.connected_clients.get_mut(&pending.client_id) {
 					client
 						.client_view
-						.notify_error("buffer_request_rejected".into(), Some(reason), false)
+						.notify_buffer_request_error(
+							monitor_id,
+							buffer,
+							"buffer_request_rejected".into(),
+							Some(reason),
+						)
 						.await;
 				}
 			}
@@ -1014,6 +1895,81 @@ impl ShiftServer {
 					self.frame_done_emitted = self.frame_done_emitted.saturating_add(1);
 				}
 			}
+			RenderEvt::OverlayReleased {
+				session_id,
+				overlay_id,
+				buffer,
+				release_fence,
+			} => {
+				let Some((_id, client)) = self
+					.connected_clients
+					.iter_mut()
+					.find(|(_, c)| c.client_view.authenticated_session() == Some(session_id))
+				else {
+					return;
+				};
+				client
+					.client_view
+					.notify_overlay_release(overlay_id, buffer, release_fence)
+					.await;
+			}
+			RenderEvt::CaptureFrame {
+				session_id,
+				capture_id,
+				buffer,
+				time_usec,
+				ready_fence,
+			} => {
+				let Some((_id, client)) = self
+					.connected_clients
+					.iter_mut()
+					.find(|(_, c)| c.client_view.authenticated_session() == Some(session_id))
+				else {
+					return;
+				};
+				client
+					.client_view
+					.notify_capture_frame(capture_id, buffer, time_usec, ready_fence)
+					.await;
+			}
+			RenderEvt::CaptureEnded {
+				session_id,
+				capture_id,
+				reason,
+			} => {
+				tracing::info!(%session_id, %capture_id, %reason, "capture ended");
+				let Some((_id, client)) = self
+					.connected_clients
+					.iter_mut()
+					.find(|(_, c)| c.client_view.authenticated_session() == Some(session_id))
+				else {
+					return;
+				};
+				client
+					.client_view
+					.notify_capture_ended(capture_id, reason)
+					.await;
+			}
+			RenderEvt::SessionPreview {
+				client_id,
+				session_id,
+				preview,
+			} => match preview {
+				Ok(preview) => {
+					if let Some(client) = self.connected_clients.get_mut(&client_id) {
+						client
+							.client_view
+							.notify_session_preview(session_id, preview)
+							.await;
+					}
+				}
+				Err(reason) => {
+					tracing::debug!(%session_id, %reason, "no session preview for client");
+					self
+						.reject_client_request(client_id, "no_frame", &session_id.to_string())
+						.await;
+				}
+			},
 			RenderEvt::FatalError { reason } => {
 				tracing::error!(?reason, "renderer fatal error");
 				// TODO: Shutdown server
@@ -1027,14 +1983,48 @@ impl ShiftServer {
 	async fn handle_input_event(&mut self, event: InputEvt) {
 		match event {
 			InputEvt::Event(input_event) => {
+				if let InputEventPayload::Key {
+					key,
+					state,
+					time_usec,
+					..
+				} = &input_event.event
+				{
+					match self
+						.global_shortcuts
+						.handle_key(&input_event.seat, *key, state.clone())
+					{
+						KeyDisposition::Forward => {}
+						KeyDisposition::Swallow => return,
+						KeyDisposition::Triggered {
+							session_id,
+							shortcut_id,
+						} => {
+							self
+								.fire_global_shortcut(session_id, shortcut_id, input_event.seat, *time_usec)
+								.await;
+							return;
+						}
+					}
+				}
+				if let Some(grab_session_id) = self.keyboard_grab
+					&& matches!(input_event.event, InputEventPayload::Key { .. })
+				{
+					self.flush_pending_input_motion().await;
+					self
+						.forward_input_event_to_session(grab_session_id, input_event)
+						.await;
+					return;
+				}
 				let Some(active_session_id) = self.current_session else {
 					return;
 				};
-				if Self::is_coalescable_motion(&input_event) {
+				if Self::is_coalescable_motion(&input_event.event) {
 					match self.pending_input_motion.as_ref() {
 						Some((pending_session, pending_event))
 							if *pending_session == active_session_id
-								&& Self::same_motion_kind(pending_event, &input_event) =>
+								&& pending_event.seat == input_event.seat
+								&& Self::same_motion_kind(&pending_event.event, &input_event.event) =>
 						{
 							self.pending_input_motion = Some((active_session_id, input_event));
 						}
@@ -1053,6 +2043,16 @@ impl ShiftServer {
 						.await;
 				}
 			}
+			InputEvt::DeviceAdded(device) => {
+				tracing::info!(id = device.id, name = %device.name, "input device added");
+				self.input_devices.insert(device.id, device.clone());
+				self.broadcast_input_device_added(&device).await;
+			}
+			InputEvt::DeviceRemoved { device } => {
+				if self.input_devices.remove(&device).is_some() {
+					self.broadcast_input_device_removed(device).await;
+				}
+			}
 			InputEvt::FatalError { reason } => {
 				tracing::error!(%reason, "input layer fatal error");
 			}
@@ -1100,10 +2100,92 @@ impl ShiftServer {
 			.any(|pending| pending.session_id == session_id)
 	}
 
+	async fn fire_global_shortcut(
+		&mut self,
+		session_id: SessionId,
+		shortcut_id: String,
+		seat: SeatId,
+		time_usec: u64,
+	) {
+		tracing::debug!(%session_id, %shortcut_id, %seat, "global shortcut triggered");
+		for (id, client) in self.connected_clients.iter_mut() {
+			if client.client_view.authenticated_session() != Some(session_id) {
+				continue;
+			}
+			if !client
+				.client_view
+				.notify_global_shortcut(shortcut_id.clone(), seat.clone(), time_usec)
+				.await
+			{
+				tracing::warn!(%id, "failed to notify global shortcut");
+			}
+		}
+	}
+
+	/// Hands a session message to the clients of `target`; false when none took it.
+	async fn deliver_session_message(
+		&mut self,
+		sender: SessionId,
+		target: SessionId,
+		payload: &SessionMessageSendPayload,
+	) -> bool {
+		tracing::debug!(%sender, %target, len = payload.data.len(), "session message");
+		let mut delivered = false;
+		for (id, client) in self.connected_clients.iter_mut() {
+			if client.client_view.authenticated_session() != Some(target) {
+				continue;
+			}
+			if client
+				.client_view
+				.notify_session_message(
+					sender,
+					payload.correlation_id,
+					payload.reply_to,
+					payload.data.clone(),
+				)
+				.await
+			{
+				delivered = true;
+			} else {
+				tracing::warn!(%id, "failed to notify session message");
+			}
+		}
+		delivered
+	}
+
+	/// Forwards a crash report to the clients of admin sessions other than the crashed one.
+	async fn notify_admins_session_crashed(
+		&mut self,
+		session_id: SessionId,
+		report: CrashReportPayload,
+	) {
+		let client_ids = self
+			.connected_clients
+			.iter()
+			.filter_map(|(id, client)| {
+				let admin_id = client.client_view.authenticated_session()?;
+				let admin = self.active_sessions.get(&admin_id)?;
+				(admin_id != session_id && admin.role() == Role::Admin).then_some(*id)
+			})
+			.collect::<Vec<_>>();
+		for id in client_ids {
+			let Some(client) = self.connected_clients.get_mut(&id) else {
+				continue;
+			};
+			if !client
+				.client_view
+				.notify_session_crashed(session_id, report.clone())
+				.await
+			{
+				tracing::warn!(%id, %session_id, "failed to notify session crash");
+			}
+		}
+	}
+
 	async fn forward_input_event_to_session(
 		&mut self,
 		session_id: SessionId,
-		event: InputEventPayload,
+		event: SeatInputEventPayload,
 	) {
 		let Some((_id, client)) = self
 			.connected_clients
@@ -1152,7 +2234,7 @@ impl ShiftServer {
                     };
                 }
 
-				let hellopkt = TabMessageFrame::hello("shift 0.1.0-alpha");
+				let hellopkt = TabMessageFrame::hello("shift 0.1.0-alpha", self.drm_device.clone());
 				let client_async_fd = or_continue!(
 					client_socket.into_std().and_then(AsyncFd::new),
 					"failed to accept connection: AsyncFd creation from client_socket failed: {}"
@@ -1162,8 +2244,11 @@ impl ShiftServer {
 					hellopkt.send_frame_to_async_fd(&client_async_fd).await,
 					"failed to send hello packet: {}"
 				);
-				let (new_client, mut new_client_view) =
-					Client::wrap_socket(client_async_fd, self.monitors.values().cloned().collect());
+				let (new_client, mut new_client_view) = Client::wrap_socket(
+					client_async_fd,
+					self.monitors.values().cloned().collect(),
+					self.input_devices.values().cloned().collect(),
+				);
 				let client_id = new_client_view.id();
 
 				self.connected_clients.insert(
@@ -1193,6 +2278,18 @@ impl ShiftServer {
 		}
 	}
 
+	async fn broadcast_monitor_changed(&mut self, monitor: &crate::monitor::Monitor) {
+		for (id, client) in self.connected_clients.iter_mut() {
+			if !client
+				.client_view
+				.notify_monitor_changed(monitor.clone())
+				.await
+			{
+				tracing::warn!(%id, "failed to notify monitor changed");
+			}
+		}
+	}
+
 	async fn broadcast_monitor_removed(&mut self, monitor: &crate::monitor::Monitor) {
 		let name: Arc<str> = monitor.name.clone().into();
 		for (id, client) in self.connected_clients.iter_mut() {
@@ -1206,6 +2303,62 @@ impl ShiftServer {
 		}
 	}
 
+	async fn broadcast_safe_area(
+		&mut self,
+		monitor_id: MonitorId,
+		occluded: Arc<[tab_protocol::Rect]>,
+	) {
+		for (id, client) in self.connected_clients.iter_mut() {
+			if !client
+				.client_view
+				.notify_safe_area(monitor_id, Arc::clone(&occluded))
+				.await
+			{
+				tracing::warn!(%id, "failed to notify safe area");
+			}
+		}
+	}
+
+	async fn broadcast_monitor_power(&mut self, monitor_id: MonitorId, power: MonitorPower) {
+		for (id, client) in self.connected_clients.iter_mut() {
+			if !client
+				.client_view
+				.notify_monitor_power(monitor_id, power)
+				.await
+			{
+				tracing::warn!(%id, "failed to notify monitor power");
+			}
+		}
+	}
+
+	async fn broadcast_accessibility(&mut self, settings: AccessibilitySettings) {
+		for (id, client) in self.connected_clients.iter_mut() {
+			if !client.client_view.notify_accessibility(settings).await {
+				tracing::warn!(%id, "failed to notify accessibility settings");
+			}
+		}
+	}
+
+	async fn broadcast_input_device_added(&mut self, device: &InputDeviceInfo) {
+		for (id, client) in self.connected_clients.iter_mut() {
+			if !client
+				.client_view
+				.notify_input_device_added(device.clone())
+				.await
+			{
+				tracing::warn!(%id, "failed to notify input device added");
+			}
+		}
+	}
+
+	async fn broadcast_input_device_removed(&mut self, device: u32) {
+		for (id, client) in self.connected_clients.iter_mut() {
+			if !client.client_view.notify_input_device_removed(device).await {
+				tracing::warn!(%id, "failed to notify input device removed");
+			}
+		}
+	}
+
 	async fn disconnect_client(&mut self, client_id: ClientId) {
 		let Some(client) = self.connected_clients.remove(&client_id) else {
 			return;
@@ -1213,6 +2366,14 @@ impl ShiftServer {
 		if let Some(session_id) = client.client_view.authenticated_session() {
 			self.active_sessions.remove(&session_id);
 			self.loading_sessions.remove(&session_id);
+			self.idle_inhibitors.remove(&session_id);
+			self.global_shortcuts.remove_session(session_id);
+			if self.keyboard_grab == Some(session_id) {
+				self.keyboard_grab = None;
+			}
+			self
+				.session_message_replies
+				.retain(|(receiver, sender, _)| *receiver != session_id && *sender != session_id);
 			self.awake_sessions.remove(&session_id);
 			self.awake_until.remove(&session_id);
 			self
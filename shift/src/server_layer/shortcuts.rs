@@ -0,0 +1,109 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use tab_protocol::{KeyState, SeatId};
+
+use crate::sessions::SessionId;
+
+#[derive(Debug)]
+struct Registration {
+	session_id: SessionId,
+	shortcut_id: String,
+	keys: BTreeSet<u32>,
+}
+
+/// What to do with a key event after matching it against global shortcuts.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum KeyDisposition {
+	/// Not part of a triggered shortcut; deliver it to the focused session.
+	Forward,
+	/// Release of a key that triggered a shortcut.
+	Swallow,
+	/// The press completed a shortcut owned by `session_id`.
+	Triggered {
+		session_id: SessionId,
+		shortcut_id: String,
+	},
+}
+
+/// Key combinations admin sessions reserved, and the per-seat key state to match them.
+#[derive(Debug, Default)]
+pub(super) struct GlobalShortcuts {
+	registrations: Vec<Registration>,
+	held: HashMap<SeatId, BTreeSet<u32>>,
+	/// Presses that triggered a shortcut; their releases are swallowed as well.
+	triggered: HashSet<(SeatId, u32)>,
+}
+
+impl GlobalShortcuts {
+	/// Registers or replaces one of `session_id`'s shortcuts.
+	///
+	/// Returns false, leaving registrations untouched, when another registration already
+	/// holds the same keys.
+	pub(super) fn register(
+		&mut self,
+		session_id: SessionId,
+		shortcut_id: String,
+		keys: BTreeSet<u32>,
+	) -> bool {
+		let conflict = self.registrations.iter().any(|registration| {
+			registration.keys == keys
+				&& (registration.session_id != session_id || registration.shortcut_id != shortcut_id)
+		});
+		if conflict {
+			return false;
+		}
+		self.unregister(session_id, &shortcut_id);
+		self.registrations.push(Registration {
+			session_id,
+			shortcut_id,
+			keys,
+		});
+		true
+	}
+
+	pub(super) fn unregister(&mut self, session_id: SessionId, shortcut_id: &str) -> bool {
+		let before = self.registrations.len();
+		self.registrations.retain(|registration| {
+			registration.session_id != session_id || registration.shortcut_id != shortcut_id
+		});
+		self.registrations.len() != before
+	}
+
+	pub(super) fn remove_session(&mut self, session_id: SessionId) {
+		self
+			.registrations
+			.retain(|registration| registration.session_id != session_id);
+	}
+
+	/// Tracks a key event and matches presses against the registered shortcuts.
+	///
+	/// A shortcut triggers when its last key is pressed while exactly its keys are held.
+	pub(super) fn handle_key(&mut self, seat: &SeatId, key: u32, state: KeyState) -> KeyDisposition {
+		let held = self.held.entry(seat.clone()).or_default();
+		match state {
+			KeyState::Released => {
+				held.remove(&key);
+				if self.triggered.remove(&(seat.clone(), key)) {
+					KeyDisposition::Swallow
+				} else {
+					KeyDisposition::Forward
+				}
+			}
+			KeyState::Pressed => {
+				held.insert(key);
+				let Some(registration) = self
+					.registrations
+					.iter()
+					.find(|registration| registration.keys == *held)
+				else {
+					return KeyDisposition::Forward;
+				};
+				self.triggered.insert((seat.clone(), key));
+				KeyDisposition::Triggered {
+					session_id: registration.session_id,
+					shortcut_id: registration.shortcut_id.clone(),
+				}
+			}
+		}
+	}
+}
@@ -54,6 +54,7 @@ impl PendingSession {
 				.as_ref()
 				.map(Arc::clone)
 				.unwrap_or_else(|| self.default_session_name().into()),
+			metadata: Default::default(),
 		}
 	}
 	pub fn default_session_name(&self) -> String {
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use crate::{define_id_type, sessions::Role};
 
@@ -10,6 +10,7 @@ pub struct Session {
 	pub(super) role: Role,
 	pub(super) ready: bool,
 	pub(super) display_name: Arc<str>,
+	pub(super) metadata: BTreeMap<String, String>,
 }
 
 impl Session {
@@ -18,6 +19,24 @@ impl Session {
 		cloned.ready = ready;
 		cloned
 	}
+	/// Copy with a new display name, if given, and metadata merged in; `None` values remove keys.
+	pub fn with_update(
+		&self,
+		display_name: Option<&str>,
+		metadata: &BTreeMap<String, Option<String>>,
+	) -> Self {
+		let mut cloned = self.clone();
+		if let Some(display_name) = display_name {
+			cloned.display_name = display_name.into();
+		}
+		for (key, value) in metadata {
+			match value {
+				Some(value) => cloned.metadata.insert(key.clone(), value.clone()),
+				None => cloned.metadata.remove(key),
+			};
+		}
+		cloned
+	}
 	pub fn id(&self) -> SessionId {
 		self.id
 	}
@@ -30,4 +49,7 @@ impl Session {
 	pub fn display_name(&self) -> &str {
 		&self.display_name
 	}
+	pub fn metadata(&self) -> &BTreeMap<String, String> {
+		&self.metadata
+	}
 }
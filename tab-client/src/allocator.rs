@@ -0,0 +1,44 @@
+use std::fmt;
+use std::os::fd::RawFd;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::{error::TabClientError, monitor::MonitorState, swapchain::TabSwapchain};
+
+/// Allocates the dmabuf buffers of swapchains, overlays and captures.
+///
+/// [`crate::GbmAllocator`] is used unless [`crate::TabClientConfig::with_allocator`]
+/// supplies another one, e.g. buffers exported from an existing Vulkan device or a test
+/// allocator. Buffers must be importable by the server's GPU.
+pub trait Allocator {
+	/// Allocates the two buffers of a monitor swapchain, sized to the monitor's mode.
+	fn create_swapchain(&self, monitor: &MonitorState) -> Result<TabSwapchain, TabClientError>;
+
+	/// Allocates a swapchain with an alpha channel for an overlay or capture surface.
+	fn create_overlay_swapchain(
+		&self,
+		overlay_id: &str,
+		width: i32,
+		height: i32,
+	) -> Result<TabSwapchain, TabClientError>;
+
+	/// Render node the buffers are allocated on, if the allocator uses one.
+	fn render_node_path(&self) -> Option<&Path> {
+		None
+	}
+
+	/// Device fd polled along with the socket, or -1 for none.
+	fn drm_fd(&self) -> RawFd {
+		-1
+	}
+}
+
+/// Allocator shared between clones of a [`crate::TabClientConfig`].
+#[derive(Clone)]
+pub(crate) struct SharedAllocator(pub(crate) Rc<dyn Allocator>);
+
+impl fmt::Debug for SharedAllocator {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str("SharedAllocator(..)")
+	}
+}
@@ -3,7 +3,6 @@
 use std::{
 	cell::RefCell,
 	collections::{HashMap, VecDeque},
-	env,
 	ffi::{CStr, CString},
 	os::raw::{c_char, c_int},
 	ptr,
@@ -485,6 +484,8 @@ impl TabClientHandle {
 				let mut guard = q.borrow_mut();
 				match evt {
 					MonitorEvent::Added(state) => guard.push_back(PendingEvent::MonitorAdded(state.clone())),
+					MonitorEvent::Changed(_) | MonitorEvent::SafeArea { .. } => {}
+					MonitorEvent::Power { .. } => {}
 					MonitorEvent::Removed { monitor_id, name } => {
 						guard.push_back(PendingEvent::MonitorRemoved {
 							monitor_id: monitor_id.clone(),
@@ -508,6 +509,21 @@ impl TabClientHandle {
 						*buffer,
 						*release_fence_fd,
 					)),
+					// The C API only uses the blocking request path.
+					RenderEvent::BufferRequestAcked { .. } | RenderEvent::BufferRequestFailed { .. } => {}
+					// Overlays and captures are not exposed to C; close the duplicated fences.
+					RenderEvent::OverlayReleased {
+						release_fence_fd, ..
+					}
+					| RenderEvent::CaptureFrame {
+						ready_fence_fd: release_fence_fd,
+						..
+					} => {
+						if let Some(fd) = release_fence_fd {
+							unsafe { libc::close(*fd) };
+						}
+					}
+					RenderEvent::CaptureEnded { .. } => {}
 				}
 			});
 		}
@@ -531,6 +547,18 @@ impl TabClientHandle {
 					SessionEvent::Created { token, .. } => {
 						guard.push_back(PendingEvent::SessionCreated(token.clone()))
 					}
+					SessionEvent::Preview { pixels_fd, .. } => {
+						unsafe { libc::close(*pixels_fd) };
+					}
+					SessionEvent::PreviewUnavailable { .. }
+					| SessionEvent::Message { .. }
+					| SessionEvent::MessageUndeliverable { .. }
+					| SessionEvent::Crashed { .. }
+					| SessionEvent::RequestFailed { .. }
+					| SessionEvent::Error(_)
+					| SessionEvent::Accessibility(_)
+					| SessionEvent::ServerUnresponsive { .. }
+					| SessionEvent::ServerRecovered { .. } => {}
 				}
 			});
 		}
@@ -539,7 +567,14 @@ impl TabClientHandle {
 			client.on_input_event(move |evt| {
 				let mut guard = q.borrow_mut();
 				match evt {
-					InputEvent::Event(event) => guard.push_back(PendingEvent::Input(event.clone())),
+					InputEvent::Event { payload, .. } => {
+						guard.push_back(PendingEvent::Input(payload.clone()))
+					}
+					InputEvent::DeviceAdded(_)
+					| InputEvent::DeviceRemoved(_)
+					| InputEvent::GlobalShortcut { .. }
+					| InputEvent::GlobalShortcutConflict { .. }
+					| InputEvent::KeyboardGrabUnavailable => {}
 				}
 			});
 		}
@@ -613,7 +648,7 @@ fn cstring_to_string(ptr: *const c_char) -> Option<String> {
 }
 
 fn resolve_token(token: *const c_char) -> Option<String> {
-	cstring_to_string(token).or_else(|| env::var("SHIFT_SESSION_TOKEN").ok())
+	cstring_to_string(token).or_else(|| crate::session_token_from_env().ok())
 }
 
 fn monitor_info_to_c(state: &MonitorState) -> TabMonitorInfo {
@@ -1575,16 +1610,14 @@ pub unsafe extern "C" fn tab_client_request_buffer(
 			None
 		};
 		if let Err(err) = handle.client.request_buffer(&id, buffer, acquire_fence) {
-			let err_text = err.to_string();
-			let ownership_related = err_text.contains("ownership_violation")
-				|| err_text.contains("buffer_request_inflight")
-				|| err_text.contains("session_sleeping");
+			let ownership_related =
+				matches!(&err, TabClientError::Server(server) if server.is_ownership_related());
 			if ownership_related {
 				entry.swapchain.mark_busy(buffer);
 			} else {
 				entry.swapchain.rollback();
 			}
-			handle.record_error(err_text);
+			handle.record_error(err);
 			return false;
 		}
 		entry.swapchain.mark_busy(buffer);
@@ -1689,7 +1722,10 @@ pub unsafe extern "C" fn tab_client_session_switch(
 		};
 		let animation = cstring_to_string(animation);
 		let duration = Duration::from_millis(duration_ms as u64);
-		if let Err(err) = handle.client.switch_session(&session_id, animation, duration) {
+		if let Err(err) = handle
+			.client
+			.switch_session(&session_id, animation, duration)
+		{
 			handle.record_error(err);
 			return false;
 		}
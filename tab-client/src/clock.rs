@@ -0,0 +1,96 @@
+use std::time::{Duration, Instant};
+
+/// Maps server input timestamps onto this process's [`Instant`] clock.
+///
+/// The offset between both clocks comes from clock sync round trips: the client sends its
+/// `CLOCK_MONOTONIC` time, the server echoes it with its own, and the offset is taken from
+/// the round trip with the smallest latency. Until a round trip completed both clocks are
+/// assumed equal, which holds when the server runs on the same machine.
+#[derive(Debug, Clone)]
+pub struct ClockSync {
+	anchor: Instant,
+	anchor_usec: u64,
+	/// Server clock minus client clock, in microseconds.
+	offset_usec: i64,
+	round_trip: Option<Duration>,
+	samples: u32,
+}
+
+impl Default for ClockSync {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl ClockSync {
+	/// Round trips taken after [`ClockSync::restart`] before sampling stops.
+	pub(crate) const SAMPLES: u32 = 4;
+
+	pub fn new() -> Self {
+		Self {
+			anchor: Instant::now(),
+			anchor_usec: monotonic_usec(),
+			offset_usec: 0,
+			round_trip: None,
+			samples: 0,
+		}
+	}
+
+	/// Server clock minus client clock in microseconds; 0 until a round trip completed.
+	pub fn offset_usec(&self) -> i64 {
+		self.offset_usec
+	}
+
+	/// Latency of the round trip the offset was taken from, bounding its error.
+	pub fn round_trip(&self) -> Option<Duration> {
+		self.round_trip
+	}
+
+	/// Returns true once a round trip completed.
+	pub fn is_synced(&self) -> bool {
+		self.round_trip.is_some()
+	}
+
+	/// Converts a server timestamp, e.g. an input event's `time_usec`, to an [`Instant`]
+	/// comparable with `Instant::now()`.
+	pub fn to_instant(&self, server_usec: u64) -> Instant {
+		let client_usec = server_usec as i64 - self.offset_usec;
+		let delta = client_usec - self.anchor_usec as i64;
+		let offset = Duration::from_micros(delta.unsigned_abs());
+		if delta >= 0 {
+			self.anchor + offset
+		} else {
+			self.anchor.checked_sub(offset).unwrap_or(self.anchor)
+		}
+	}
+
+	/// Forgets the sample count so the next round trips may replace the offset.
+	pub(crate) fn restart(&mut self) {
+		self.samples = 0;
+		self.round_trip = None;
+	}
+
+	/// Records a round trip; returns true while more samples are wanted.
+	pub(crate) fn record(&mut self, sent_usec: u64, server_usec: u64, received_usec: u64) -> bool {
+		let round_trip = received_usec.saturating_sub(sent_usec);
+		self.samples += 1;
+		if self
+			.round_trip
+			.is_none_or(|best| round_trip < best.as_micros() as u64)
+		{
+			self.round_trip = Some(Duration::from_micros(round_trip));
+			self.offset_usec = server_usec as i64 - (sent_usec + round_trip / 2) as i64;
+		}
+		self.samples < Self::SAMPLES
+	}
+}
+
+/// `CLOCK_MONOTONIC` in microseconds, the clock of the client side of clock sync requests.
+pub fn monotonic_usec() -> u64 {
+	let mut ts = libc::timespec {
+		tv_sec: 0,
+		tv_nsec: 0,
+	};
+	unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+	ts.tv_sec as u64 * 1_000_000 + ts.tv_nsec as u64 / 1_000
+}
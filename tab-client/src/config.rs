@@ -1,6 +1,23 @@
+use std::fs::File;
+use std::io::Read;
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{self, AtomicBool, Ordering};
+use std::time::Duration;
 
 use tab_protocol::DEFAULT_SOCKET_PATH;
+use tab_protocol::unix_socket_utils;
+
+use crate::allocator::{Allocator, SharedAllocator};
+use crate::{DrmFourcc, TabClientError};
+
+/// Longest token accepted from a file or fd.
+const MAX_TOKEN_LEN: usize = 4096;
+
+/// Set once the fd named by `SHIFT_SESSION_TOKEN_FD` was consumed; its number may since
+/// have been reused for another file.
+static TOKEN_FD_TAKEN: AtomicBool = AtomicBool::new(false);
 
 /// Builder-style configuration for establishing a Tab connection.
 #[derive(Debug, Clone)]
@@ -8,27 +25,112 @@ pub struct TabClientConfig {
 	socket_path: PathBuf,
 	token: String,
 	render_node: Option<PathBuf>,
+	linear_buffers: bool,
+	shm_buffers: bool,
+	swapchain_format: DrmFourcc,
+	buffer_request_ack_timeout: Duration,
+	ping_interval: Option<Duration>,
+	allocator: Option<SharedAllocator>,
 }
 
 impl TabClientConfig {
+	/// Default for [`TabClientConfig::buffer_request_ack_timeout`].
+	pub const DEFAULT_BUFFER_REQUEST_ACK_TIMEOUT: Duration = Duration::from_millis(250);
+
 	pub fn new(token: impl Into<String>) -> Self {
 		Self {
 			socket_path: PathBuf::from(DEFAULT_SOCKET_PATH),
 			token: token.into(),
 			render_node: None,
+			linear_buffers: false,
+			shm_buffers: false,
+			swapchain_format: DrmFourcc::Xrgb8888,
+			buffer_request_ack_timeout: Self::DEFAULT_BUFFER_REQUEST_ACK_TIMEOUT,
+			ping_interval: None,
+			allocator: None,
 		}
 	}
 
+	/// Reads the token from the file at `path`, e.g. one only the session's user can read.
+	///
+	/// Surrounding whitespace is trimmed and the bytes read are zeroed afterwards.
+	pub fn token_from_file(path: impl AsRef<Path>) -> Result<Self, TabClientError> {
+		Ok(Self::new(read_token(File::open(path)?)?))
+	}
+
+	/// Reads the token from `fd`, e.g. the read end of a pipe, up to end of file and closes
+	/// it.
+	///
+	/// Surrounding whitespace is trimmed and the bytes read are zeroed afterwards.
+	pub fn token_from_fd(fd: OwnedFd) -> Result<Self, TabClientError> {
+		Ok(Self::new(read_token(File::from(fd))?))
+	}
+
 	pub fn socket_path(mut self, path: impl AsRef<Path>) -> Self {
 		self.socket_path = path.as_ref().into();
 		self
 	}
 
+	/// Connects to the socket discovered for `seat`: `SHIFT_SOCKET` if set, then
+	/// `$XDG_RUNTIME_DIR/shift/<seat>.sock` if it exists, then the legacy default.
+	pub fn discover_socket(mut self, seat: &str) -> Self {
+		self.socket_path = unix_socket_utils::discover_socket(seat);
+		self
+	}
+
+	/// Lists the sockets available to clients of `seat`, most preferred first.
+	pub fn socket_candidates(seat: &str) -> Vec<PathBuf> {
+		unix_socket_utils::socket_candidates(seat)
+	}
+
 	pub fn render_node(mut self, path: impl AsRef<Path>) -> Self {
 		self.render_node = Some(path.as_ref().into());
 		self
 	}
 
+	/// Allocates CPU-mappable linear buffers, for apps that render in software.
+	pub fn linear_buffers(mut self, enabled: bool) -> Self {
+		self.linear_buffers = enabled;
+		self
+	}
+
+	/// Allocates swapchains in shared memory and opens no render node, for clients
+	/// without GPU access. Overlays and captures are unavailable.
+	pub fn shm_buffers(mut self, enabled: bool) -> Self {
+		self.shm_buffers = enabled;
+		self
+	}
+
+	/// Pixel format of GBM-allocated monitor swapchains; XRGB8888 is used when the render
+	/// node cannot render to it.
+	pub fn swapchain_format(mut self, format: DrmFourcc) -> Self {
+		self.swapchain_format = format;
+		self
+	}
+
+	/// How long `TabClient::request_buffer` waits for the server's acknowledgement.
+	pub fn buffer_request_ack_timeout(mut self, timeout: Duration) -> Self {
+		self.buffer_request_ack_timeout = timeout;
+		self
+	}
+
+	/// How often `TabClient::poll_keepalive` pings the server; `None` (the default) sends
+	/// no pings.
+	pub fn ping_interval(mut self, interval: Option<Duration>) -> Self {
+		self.ping_interval = interval;
+		self
+	}
+
+	/// Allocates dmabuf buffers with `allocator` instead of GBM on a render node.
+	///
+	/// Takes precedence over [`TabClientConfig::render_node`],
+	/// [`TabClientConfig::linear_buffers`] and [`TabClientConfig::shm_buffers`]; clones of
+	/// the config share the allocator.
+	pub fn with_allocator(mut self, allocator: Box<dyn Allocator>) -> Self {
+		self.allocator = Some(SharedAllocator(Rc::from(allocator)));
+		self
+	}
+
 	pub fn token(&self) -> &str {
 		&self.token
 	}
@@ -40,4 +142,107 @@ impl TabClientConfig {
 	pub fn render_node_path(&self) -> Option<&Path> {
 		self.render_node.as_deref()
 	}
+
+	pub fn linear_buffers_enabled(&self) -> bool {
+		self.linear_buffers
+	}
+
+	pub fn shm_buffers_enabled(&self) -> bool {
+		self.shm_buffers
+	}
+
+	pub fn swapchain_format_value(&self) -> DrmFourcc {
+		self.swapchain_format
+	}
+
+	pub fn buffer_request_ack_timeout_value(&self) -> Duration {
+		self.buffer_request_ack_timeout
+	}
+
+	pub fn ping_interval_value(&self) -> Option<Duration> {
+		self.ping_interval
+	}
+
+	pub(crate) fn allocator(&self) -> Option<Rc<dyn Allocator>> {
+		self.allocator.as_ref().map(|shared| Rc::clone(&shared.0))
+	}
+}
+
+impl Drop for TabClientConfig {
+	fn drop(&mut self) {
+		zeroize(&mut std::mem::take(&mut self.token).into_bytes());
+	}
+}
+
+/// Reads the session token passed by shift or the launcher.
+///
+/// Tries, in order, the fd named by `SHIFT_SESSION_TOKEN_FD` (consumed by the first call),
+/// the file named by `SHIFT_SESSION_TOKEN_FILE`, then `SHIFT_SESSION_TOKEN` itself, which
+/// stays readable in `/proc/<pid>/environ` and is only kept for compatibility.
+pub fn session_token_from_env() -> Result<String, TabClientError> {
+	let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+	if let Some(fd) = var("SHIFT_SESSION_TOKEN_FD") {
+		let fd: RawFd = fd.parse().map_err(|_| {
+			TabClientError::TokenUnavailable(format!("invalid SHIFT_SESSION_TOKEN_FD {fd:?}"))
+		})?;
+		if TOKEN_FD_TAKEN.swap(true, Ordering::SeqCst) {
+			return Err(TabClientError::TokenUnavailable(
+				"SHIFT_SESSION_TOKEN_FD was already consumed".into(),
+			));
+		}
+		// SAFETY: fcntl only inspects the descriptor table.
+		if fd < 0 || unsafe { libc::fcntl(fd, libc::F_GETFD) } == -1 {
+			return Err(TabClientError::TokenUnavailable(format!(
+				"SHIFT_SESSION_TOKEN_FD {fd} is not open"
+			)));
+		}
+		// SAFETY: the fd is open, was handed to this process for the token and is only
+		// taken once.
+		return read_token(File::from(unsafe { OwnedFd::from_raw_fd(fd) }));
+	}
+	if let Some(path) = var("SHIFT_SESSION_TOKEN_FILE") {
+		return read_token(File::open(path)?);
+	}
+	var("SHIFT_SESSION_TOKEN").ok_or_else(|| {
+		TabClientError::TokenUnavailable(
+			"none of SHIFT_SESSION_TOKEN_FD, SHIFT_SESSION_TOKEN_FILE or SHIFT_SESSION_TOKEN is set"
+				.into(),
+		)
+	})
+}
+
+/// Reads a whole token from `source` into a fixed buffer, so no reallocation leaves
+/// copies behind, and zeroes the buffer before returning.
+fn read_token(mut source: File) -> Result<String, TabClientError> {
+	let mut raw = vec![0u8; MAX_TOKEN_LEN];
+	let mut len = 0;
+	let result = loop {
+		if len == raw.len() {
+			break Err(TabClientError::TokenUnavailable("token too long".into()));
+		}
+		match source.read(&mut raw[len..]) {
+			Ok(0) => break Ok(()),
+			Ok(n) => len += n,
+			Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+			Err(e) => break Err(e.into()),
+		}
+	};
+	let token = result.and_then(|()| match std::str::from_utf8(&raw[..len]).map(str::trim) {
+		Ok("") => Err(TabClientError::TokenUnavailable("empty token".into())),
+		Ok(token) => Ok(token.to_string()),
+		Err(_) => Err(TabClientError::TokenUnavailable(
+			"token is not UTF-8".into(),
+		)),
+	});
+	zeroize(&mut raw);
+	token
+}
+
+/// Overwrites `bytes` with zeros in a way the compiler may not elide.
+fn zeroize(bytes: &mut [u8]) {
+	for byte in bytes.iter_mut() {
+		// SAFETY: `byte` is a valid, exclusive reference.
+		unsafe { std::ptr::write_volatile(byte, 0) };
+	}
+	atomic::compiler_fence(Ordering::SeqCst);
 }
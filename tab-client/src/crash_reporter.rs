@@ -0,0 +1,45 @@
+use std::os::fd::{AsFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+
+use tab_protocol::message_frame::TabMessageFrame;
+use tab_protocol::{CrashReportPayload, message_header};
+
+use crate::error::TabClientError;
+
+/// Sends crash reports over a duplicate of a client's socket.
+///
+/// Unlike [`crate::TabClient`] it is `Send + Sync`, so a panic hook can own it. The server
+/// forwards reports to admin sessions as [`crate::SessionEvent::Crashed`]. The duplicate keeps
+/// the connection open, so drop the reporter along with the client.
+#[derive(Debug)]
+pub struct CrashReporter {
+	socket: OwnedFd,
+}
+
+impl CrashReporter {
+	pub(crate) fn new(socket: &UnixStream) -> Result<Self, TabClientError> {
+		Ok(Self {
+			socket: socket.as_fd().try_clone_to_owned()?,
+		})
+	}
+
+	/// Sends a report, truncating `message` to `CrashReportPayload::MAX_MESSAGE_LEN` bytes.
+	pub fn send(
+		&self,
+		message: &str,
+		backtrace_hash: u64,
+		app_version: Option<&str>,
+	) -> Result<(), TabClientError> {
+		let mut len = message.len().min(CrashReportPayload::MAX_MESSAGE_LEN);
+		while !message.is_char_boundary(len) {
+			len -= 1;
+		}
+		let payload = CrashReportPayload {
+			message: message[..len].to_string(),
+			backtrace_hash,
+			app_version: app_version.map(str::to_string),
+		};
+		TabMessageFrame::json(message_header::CRASH_REPORT, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+}
@@ -1,8 +1,64 @@
 use std::path::PathBuf;
 
 use gbm::InvalidFdError;
+use tab_protocol::{ErrorPayload, ServerErrorCode};
 use thiserror::Error;
 
+/// Error reported by the server, with its typed code and optional detail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerError {
+	pub code: ServerErrorCode,
+	pub message: Option<String>,
+}
+
+impl ServerError {
+	pub fn new(code: ServerErrorCode) -> Self {
+		Self {
+			code,
+			message: None,
+		}
+	}
+
+	/// True when the request failed because the session may not present right now,
+	/// rather than because the request itself was wrong.
+	pub fn is_ownership_related(&self) -> bool {
+		matches!(
+			self.code,
+			ServerErrorCode::OwnershipViolation
+				| ServerErrorCode::BufferRequestInflight
+				| ServerErrorCode::SessionSleeping
+		)
+	}
+}
+
+impl From<ErrorPayload> for ServerError {
+	fn from(payload: ErrorPayload) -> Self {
+		Self {
+			code: payload.error_code(),
+			message: payload.message,
+		}
+	}
+}
+
+impl std::fmt::Display for ServerError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match &self.message {
+			Some(message) => write!(f, "{}: {message}", self.code),
+			None => write!(f, "{}", self.code),
+		}
+	}
+}
+
+impl TabClientError {
+	/// Server error code, when the server rejected the request.
+	pub fn server_code(&self) -> Option<&ServerErrorCode> {
+		match self {
+			Self::Server(err) => Some(&err.code),
+			_ => None,
+		}
+	}
+}
+
 #[derive(Debug, Error)]
 pub enum TabClientError {
 	#[error("io error: {0}")]
@@ -13,8 +69,11 @@ pub enum TabClientError {
 	Nix(#[from] nix::Error),
 	#[error("authentication failed: {0}")]
 	Auth(String),
+	/// No session token could be read from a file, fd or the environment.
+	#[error("session token unavailable: {0}")]
+	TokenUnavailable(String),
 	#[error("server rejected request: {0}")]
-	Server(String),
+	Server(ServerError),
 	#[error("unexpected message: {0}")]
 	Unexpected(&'static str),
 	#[error("failed to open render node {path}: {source}")]
@@ -24,10 +83,18 @@ pub enum TabClientError {
 	},
 	#[error("gbm device initialization failed: {0}")]
 	GbmInit(String),
+	/// The connection was configured for shm buffers only and opened no render node.
+	#[error("no render node opened for shm-only connection")]
+	NoRenderNode,
 	#[error("monitor has invalid dimensions")]
 	InvalidMonitorDimensions,
+	#[error("overlay has invalid dimensions")]
+	InvalidOverlayDimensions,
 	#[error("unknown monitor: {0}")]
 	UnknownMonitor(String),
+	/// The server did not acknowledge a buffer request for the monitor in time.
+	#[error("buffer_request_ack timeout for monitor {0}")]
+	AckTimeout(String),
 	#[error("failed to export dma-buf fd: {0}")]
 	BufferExport(#[from] InvalidFdError),
 }
@@ -1,15 +1,35 @@
-use crate::MonitorState;
-use std::os::fd::RawFd;
-use tab_protocol::{BufferIndex, InputEventPayload, SessionInfo};
+use crate::{MonitorState, ServerError};
+use std::any::Any;
+use std::fmt;
+use std::os::fd::{FromRawFd, OwnedFd, RawFd};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use tab_protocol::{
+	AccessibilitySettings, BufferIndex, InputDeviceInfo, InputEventPayload, MonitorPower, Rect,
+	SeatId, SessionInfo,
+};
 
 /// Monitor lifecycle event emitted to listeners.
 #[derive(Debug, Clone)]
 pub enum MonitorEvent {
 	Added(MonitorState),
+	/// Mode or properties of a connected monitor changed; the id is unchanged.
+	Changed(MonitorState),
 	Removed {
 		monitor_id: String,
 		name: String,
 	},
+	/// Compositor chrome over a monitor changed; `occluded` is in physical pixels
+	/// relative to the monitor and empty once nothing covers it.
+	SafeArea {
+		monitor_id: String,
+		occluded: Vec<Rect>,
+	},
+	/// A monitor's display changed power state.
+	Power {
+		monitor_id: String,
+		power: MonitorPower,
+	},
 }
 
 /// Rendering-related notifications.
@@ -20,6 +40,37 @@ pub enum RenderEvent {
 		buffer: BufferIndex,
 		release_fence_fd: Option<RawFd>,
 	},
+	/// The server accepted a request sent with `TabClient::send_buffer_request`.
+	BufferRequestAcked {
+		monitor_id: String,
+		buffer: BufferIndex,
+	},
+	/// The server rejected a request sent with `TabClient::send_buffer_request`.
+	BufferRequestFailed {
+		monitor_id: String,
+		buffer: BufferIndex,
+		error: ServerError,
+	},
+	/// The server stopped displaying an overlay buffer; `monitor_id` of the
+	/// overlay's swapchain holds the overlay id.
+	OverlayReleased {
+		overlay_id: String,
+		buffer: BufferIndex,
+		release_fence_fd: Option<RawFd>,
+	},
+	/// A capture buffer holds a new frame; `monitor_id` of the capture's swapchain holds the
+	/// capture id. Hand the buffer back with `TabClient::release_capture_frame`.
+	///
+	/// Each listener, and the event queue, receives its own copy of `ready_fence_fd`; listeners
+	/// must close theirs.
+	CaptureFrame {
+		capture_id: String,
+		buffer: BufferIndex,
+		time_usec: u64,
+		ready_fence_fd: Option<RawFd>,
+	},
+	/// The server stopped a capture because its source went away.
+	CaptureEnded { capture_id: String, reason: String },
 }
 
 #[derive(Debug, Clone)]
@@ -28,10 +79,181 @@ pub enum SessionEvent {
 	Awake(String),
 	Sleep(String),
 	State(SessionInfo),
-	Created { session: SessionInfo, token: String },
+	Created {
+		session: SessionInfo,
+		token: String,
+		/// Id passed to `TabClient::request_session_create`; `None` for `create_session`.
+		request_id: Option<u64>,
+	},
+	/// Snapshot requested with `TabClient::request_session_preview`.
+	///
+	/// `pixels_fd` is a shm fd holding `height` rows of `stride` bytes in `fourcc` layout;
+	/// each listener, and the event queue, receives its own copy of the fd; listeners must
+	/// close theirs.
+	Preview {
+		session_id: String,
+		monitor_id: String,
+		width: i32,
+		height: i32,
+		stride: i32,
+		fourcc: i32,
+		pixels_fd: RawFd,
+	},
+	/// A preview request failed because the session had no frame on screen.
+	PreviewUnavailable {
+		session_id: String,
+	},
+	/// Data another session sent with `TabClient::send_session_message`.
+	Message {
+		from_session_id: String,
+		correlation_id: Option<u64>,
+		reply_to: Option<u64>,
+		data: Vec<u8>,
+	},
+	/// A message to `session_id` was refused: the session is gone or may not receive it.
+	MessageUndeliverable {
+		session_id: String,
+	},
+	/// Admin-only: `session_id` panicked and sent a crash report before disconnecting.
+	Crashed {
+		session_id: String,
+		message: String,
+		/// Hash of the panic backtrace; equal hashes usually mean the same crash.
+		backtrace_hash: u64,
+		app_version: Option<String>,
+	},
+	/// The server rejected a request sent with a `request_id`.
+	RequestFailed {
+		request_id: u64,
+		error: ServerError,
+	},
+	/// The server reported an error that names no request, e.g. a protocol violation or a
+	/// rejected call that carried no `request_id`.
+	Error(ServerError),
+	/// System-wide accessibility settings changed, or were set before this client connected.
+	Accessibility(AccessibilitySettings),
+	/// The keepalive ping sent at `since` went unanswered for a whole ping interval.
+	ServerUnresponsive {
+		since: Instant,
+	},
+	/// The server answered the ping it left unanswered.
+	ServerRecovered {
+		unresponsive_for: Duration,
+	},
 }
 
 #[derive(Debug, Clone)]
 pub enum InputEvent {
-	Event(InputEventPayload),
+	Event {
+		seat: SeatId,
+		payload: InputEventPayload,
+	},
+	/// A device was plugged in; its id matches the `device` field of later events.
+	DeviceAdded(InputDeviceInfo),
+	/// A previously announced device was unplugged.
+	DeviceRemoved(InputDeviceInfo),
+	/// A shortcut registered with `TabClient::register_global_shortcut` was pressed.
+	GlobalShortcut {
+		seat: SeatId,
+		shortcut_id: String,
+		time_usec: u64,
+	},
+	/// The server refused a global shortcut whose keys another registration holds.
+	GlobalShortcutConflict { shortcut_id: String },
+	/// The server refused a keyboard grab because another session holds it.
+	KeyboardGrabUnavailable,
+}
+
+/// Server message outside the Tab protocol, decoded by a decoder registered with
+/// `TabClient::register_extension`.
+#[derive(Clone)]
+pub struct ExtensionEvent {
+	/// Header of the frame the event was decoded from.
+	pub header: String,
+	value: Rc<dyn Any>,
+}
+
+impl ExtensionEvent {
+	pub(crate) fn new(header: String, value: Rc<dyn Any>) -> Self {
+		Self { header, value }
+	}
+
+	/// Returns true if the decoder produced a `T`.
+	pub fn is<T: Any>(&self) -> bool {
+		self.value.is::<T>()
+	}
+
+	/// Borrows the decoded value if it is a `T`.
+	pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+		self.value.downcast_ref()
+	}
+}
+
+impl fmt::Debug for ExtensionEvent {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("ExtensionEvent")
+			.field("header", &self.header)
+			.finish_non_exhaustive()
+	}
+}
+
+/// Event of any kind, returned by `TabClient::poll_event`.
+#[derive(Debug, Clone)]
+pub enum TabEvent {
+	Monitor(MonitorEvent),
+	Render(RenderEvent),
+	Session(SessionEvent),
+	Input(InputEvent),
+	Extension(ExtensionEvent),
+}
+
+impl TabEvent {
+	/// Closes the fds a queued event owns, for events dropped instead of returned.
+	pub(crate) fn close_fds(self) {
+		let fd = match self {
+			Self::Render(RenderEvent::BufferReleased {
+				release_fence_fd, ..
+			})
+			| Self::Render(RenderEvent::OverlayReleased {
+				release_fence_fd, ..
+			}) => release_fence_fd,
+			Self::Render(RenderEvent::CaptureFrame { ready_fence_fd, .. }) => ready_fence_fd,
+			Self::Session(SessionEvent::Preview { pixels_fd, .. }) => Some(pixels_fd),
+			_ => None,
+		};
+		if let Some(fd) = fd {
+			// SAFETY: the queue owned its copy of the fd and nothing else refers to it.
+			drop(unsafe { OwnedFd::from_raw_fd(fd) });
+		}
+	}
+}
+
+impl From<MonitorEvent> for TabEvent {
+	fn from(event: MonitorEvent) -> Self {
+		Self::Monitor(event)
+	}
+}
+
+impl From<RenderEvent> for TabEvent {
+	fn from(event: RenderEvent) -> Self {
+		Self::Render(event)
+	}
+}
+
+impl From<SessionEvent> for TabEvent {
+	fn from(event: SessionEvent) -> Self {
+		Self::Session(event)
+	}
+}
+
+impl From<InputEvent> for TabEvent {
+	fn from(event: InputEvent) -> Self {
+		Self::Input(event)
+	}
+}
+
+impl From<ExtensionEvent> for TabEvent {
+	fn from(event: ExtensionEvent) -> Self {
+		Self::Extension(event)
+	}
 }
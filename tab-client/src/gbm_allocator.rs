@@ -1,13 +1,17 @@
 use std::{
-	fs::OpenOptions,
-	os::fd::{AsRawFd, RawFd},
+	fs::{self, OpenOptions},
+	os::{
+		fd::{AsRawFd, RawFd},
+		unix::fs::MetadataExt,
+	},
 	path::{Path, PathBuf},
 };
 
 use gbm::{BufferObjectFlags, Device, Format};
-use tab_protocol::BufferIndex;
+use tab_protocol::{BufferIndex, DrmDeviceInfo};
 
 use crate::{
+	allocator::Allocator,
 	error::TabClientError,
 	monitor::MonitorState,
 	swapchain::{TabBuffer, TabSwapchain},
@@ -24,22 +28,56 @@ const DEFAULT_RENDER_NODES: &[&str] = &[
 	"/dev/dri/renderD135",
 ];
 
+/// Finds the local render node of the DRM device advertised by the server.
+///
+/// Matches by device number, so it works even if node names differ between the server's
+/// and the client's view of `/dev/dri`.
+pub fn matching_render_node(device: &DrmDeviceInfo) -> Option<PathBuf> {
+	let matches = |path: &Path| {
+		fs::metadata(path).is_ok_and(|meta| {
+			let rdev = meta.rdev();
+			libc::major(rdev) == device.major && libc::minor(rdev) == device.minor
+		})
+	};
+	let hint = device.render_node.as_deref().map(Path::new);
+	if let Some(hint) = hint.filter(|hint| matches(hint)) {
+		return Some(hint.to_path_buf());
+	}
+	fs::read_dir("/dev/dri")
+		.ok()?
+		.filter_map(Result::ok)
+		.map(|entry| entry.path())
+		.filter(|path| {
+			path
+				.file_name()
+				.is_some_and(|name| name.to_string_lossy().starts_with("renderD"))
+		})
+		.find(|path| matches(path))
+}
+
 pub struct GbmAllocator {
 	device: Device<std::fs::File>,
+	render_node: PathBuf,
 	format: Format,
 	preferred_usage: BufferObjectFlags,
 	fallback_usage: BufferObjectFlags,
 }
 
 impl GbmAllocator {
-	pub fn new(configured_node: Option<&Path>) -> Result<Self, TabClientError> {
+	/// Opens the configured render node, or else the one of `server_device`, falling back
+	/// to the first usable `/dev/dri/renderD*` node.
+	pub fn new(
+		configured_node: Option<&Path>,
+		server_device: Option<&DrmDeviceInfo>,
+	) -> Result<Self, TabClientError> {
 		let mut last_error = None;
-		for candidate in Self::render_node_candidates(configured_node) {
+		for candidate in Self::render_node_candidates(configured_node, server_device) {
 			match OpenOptions::new().read(true).write(true).open(&candidate) {
 				Ok(file) => match Device::new(file) {
 					Ok(device) => {
 						return Ok(Self {
 							device,
+							render_node: candidate,
 							format: Format::Xrgb8888,
 							preferred_usage: BufferObjectFlags::RENDERING,
 							fallback_usage: BufferObjectFlags::RENDERING,
@@ -62,48 +100,131 @@ impl GbmAllocator {
 		)
 	}
 
+	/// Allocates monitor swapchains in `format` instead of XRGB8888, unless the device
+	/// cannot render to it.
+	pub fn with_format(mut self, format: Format) -> Self {
+		if self
+			.device
+			.is_format_supported(format, BufferObjectFlags::RENDERING)
+		{
+			self.format = format;
+		}
+		self
+	}
+
+	/// Allocates CPU-mappable linear buffers, as needed to read frames back.
+	pub fn with_linear_buffers(mut self) -> Self {
+		self.preferred_usage = BufferObjectFlags::RENDERING | BufferObjectFlags::LINEAR;
+		self.fallback_usage = self.preferred_usage;
+		self
+	}
+
 	pub fn drm_fd(&self) -> RawFd {
 		self.device.as_raw_fd()
 	}
 
+	/// Render node the buffers are allocated on.
+	pub fn render_node_path(&self) -> &Path {
+		&self.render_node
+	}
+
 	pub fn create_swapchain(&self, monitor: &MonitorState) -> Result<TabSwapchain, TabClientError> {
 		let width =
 			u32::try_from(monitor.info.width).map_err(|_| TabClientError::InvalidMonitorDimensions)?;
 		let height =
 			u32::try_from(monitor.info.height).map_err(|_| TabClientError::InvalidMonitorDimensions)?;
+		self.create_buffers(monitor.info.id.clone(), width, height, self.format)
+	}
+
+	/// Allocates a swapchain with an alpha channel for an overlay surface.
+	pub fn create_overlay_swapchain(
+		&self,
+		overlay_id: &str,
+		width: i32,
+		height: i32,
+	) -> Result<TabSwapchain, TabClientError> {
+		let (Ok(width), Ok(height)) = (u32::try_from(width), u32::try_from(height)) else {
+			return Err(TabClientError::InvalidOverlayDimensions);
+		};
+		if width == 0 || height == 0 {
+			return Err(TabClientError::InvalidOverlayDimensions);
+		}
+		self.create_buffers(overlay_id.to_string(), width, height, Format::Argb8888)
+	}
+
+	fn create_buffers(
+		&self,
+		id: String,
+		width: u32,
+		height: u32,
+		format: Format,
+	) -> Result<TabSwapchain, TabClientError> {
 		let bo0 = self
 			.device
-			.create_buffer_object::<()>(width, height, self.format, self.preferred_usage)
+			.create_buffer_object::<()>(width, height, format, self.preferred_usage)
 			.or_else(|_| {
 				self
 					.device
-					.create_buffer_object::<()>(width, height, self.format, self.fallback_usage)
+					.create_buffer_object::<()>(width, height, format, self.fallback_usage)
 			})?;
 		let bo1 = self
 			.device
-			.create_buffer_object::<()>(width, height, self.format, self.preferred_usage)
+			.create_buffer_object::<()>(width, height, format, self.preferred_usage)
 			.or_else(|_| {
 				self
 					.device
-					.create_buffer_object::<()>(width, height, self.format, self.fallback_usage)
+					.create_buffer_object::<()>(width, height, format, self.fallback_usage)
 			})?;
 		let buffers = [
 			TabBuffer::new(BufferIndex::Zero, bo0),
 			TabBuffer::new(BufferIndex::One, bo1),
 		];
-		Ok(TabSwapchain::new(monitor.info.id.clone(), buffers))
+		Ok(TabSwapchain::new(id, buffers))
 	}
 
-	fn render_node_candidates(configured: Option<&Path>) -> Vec<PathBuf> {
+	fn render_node_candidates(
+		configured: Option<&Path>,
+		server_device: Option<&DrmDeviceInfo>,
+	) -> Vec<PathBuf> {
 		if let Some(path) = configured {
 			vec![path.to_path_buf()]
 		} else if let Ok(env) = std::env::var("TAB_CLIENT_RENDER_NODE") {
 			vec![PathBuf::from(env)]
 		} else {
-			DEFAULT_RENDER_NODES
+			let matching = server_device.and_then(matching_render_node);
+			matching
 				.iter()
-				.map(|p| PathBuf::from(p))
+				.cloned()
+				.chain(
+					DEFAULT_RENDER_NODES
+						.iter()
+						.map(PathBuf::from)
+						.filter(|path| Some(path) != matching.as_ref()),
+				)
 				.collect()
 		}
 	}
 }
+
+impl Allocator for GbmAllocator {
+	fn create_swapchain(&self, monitor: &MonitorState) -> Result<TabSwapchain, TabClientError> {
+		GbmAllocator::create_swapchain(self, monitor)
+	}
+
+	fn create_overlay_swapchain(
+		&self,
+		overlay_id: &str,
+		width: i32,
+		height: i32,
+	) -> Result<TabSwapchain, TabClientError> {
+		GbmAllocator::create_overlay_swapchain(self, overlay_id, width, height)
+	}
+
+	fn render_node_path(&self) -> Option<&Path> {
+		Some(GbmAllocator::render_node_path(self))
+	}
+
+	fn drm_fd(&self) -> RawFd {
+		GbmAllocator::drm_fd(self)
+	}
+}
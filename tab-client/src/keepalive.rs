@@ -0,0 +1,79 @@
+use std::time::{Duration, Instant};
+
+/// Ping schedule and server liveness behind [`crate::TabClient::poll_keepalive`].
+///
+/// One ping is outstanding at a time. The server counts as unresponsive once a ping went
+/// unanswered for a whole interval, and as recovered when that ping's pong arrives.
+#[derive(Debug)]
+pub(crate) struct Keepalive {
+	interval: Option<Duration>,
+	last_sent: Option<Instant>,
+	/// When the unanswered ping was sent.
+	outstanding: Option<Instant>,
+	latency: Option<Duration>,
+	unresponsive: bool,
+}
+
+/// Outcome of [`Keepalive::poll`].
+pub(crate) enum KeepaliveAction {
+	None,
+	SendPing,
+	/// The ping sent at the given time went unanswered for an interval.
+	Unresponsive(Instant),
+}
+
+impl Keepalive {
+	pub(crate) fn new(interval: Option<Duration>) -> Self {
+		Self {
+			interval,
+			last_sent: None,
+			outstanding: None,
+			latency: None,
+			unresponsive: false,
+		}
+	}
+
+	pub(crate) fn poll(&mut self, now: Instant) -> KeepaliveAction {
+		let Some(interval) = self.interval else {
+			return KeepaliveAction::None;
+		};
+		if let Some(sent) = self.outstanding {
+			if !self.unresponsive && now >= sent + interval {
+				self.unresponsive = true;
+				return KeepaliveAction::Unresponsive(sent);
+			}
+			return KeepaliveAction::None;
+		}
+		if self.last_sent.is_some_and(|sent| now < sent + interval) {
+			return KeepaliveAction::None;
+		}
+		self.last_sent = Some(now);
+		self.outstanding = Some(now);
+		KeepaliveAction::SendPing
+	}
+
+	/// Records a pong; returns how long the server was unresponsive if it just recovered.
+	pub(crate) fn pong(&mut self, now: Instant) -> Option<Duration> {
+		let sent = self.outstanding.take()?;
+		self.latency = Some(now.saturating_duration_since(sent));
+		std::mem::take(&mut self.unresponsive).then(|| now.saturating_duration_since(sent))
+	}
+
+	/// When [`Keepalive::poll`] has something to do next.
+	pub(crate) fn deadline(&self) -> Option<Instant> {
+		let interval = self.interval?;
+		match self.outstanding {
+			Some(_) if self.unresponsive => None,
+			Some(sent) => Some(sent + interval),
+			None => Some(match self.last_sent {
+				Some(sent) => sent + interval,
+				None => Instant::now(),
+			}),
+		}
+	}
+
+	/// Round trip of the last answered ping.
+	pub(crate) fn latency(&self) -> Option<Duration> {
+		self.latency
+	}
+}
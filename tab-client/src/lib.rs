@@ -1,37 +1,68 @@
 //! Tab client rewrite crate.
 
+mod allocator;
 mod c_bindings;
+mod clock;
 mod config;
+mod crash_reporter;
 mod error;
 mod events;
 mod gbm_allocator;
+mod keepalive;
+mod listeners;
+mod memfd_allocator;
 mod monitor;
 mod swapchain;
 
-pub use config::TabClientConfig;
-pub use error::TabClientError;
-pub use events::{InputEvent, MonitorEvent, RenderEvent, SessionEvent};
+pub use allocator::Allocator;
+pub use clock::{ClockSync, monotonic_usec};
+pub use config::{TabClientConfig, session_token_from_env};
+pub use crash_reporter::CrashReporter;
+pub use error::{ServerError, TabClientError};
+pub use events::{ExtensionEvent, InputEvent, MonitorEvent, RenderEvent, SessionEvent, TabEvent};
+pub use gbm::Format as DrmFourcc;
+pub use gbm_allocator::GbmAllocator;
+pub use gbm_allocator::matching_render_node;
+pub use listeners::ListenerId;
+pub use memfd_allocator::MemfdAllocator;
 pub use monitor::{MonitorId, MonitorState};
 pub use swapchain::{TabBuffer, TabSwapchain};
 
-use std::collections::HashMap;
+use std::any::Any;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::os::{
-	fd::{AsFd, AsRawFd, IntoRawFd, OwnedFd, RawFd},
+	fd::{AsRawFd, IntoRawFd, OwnedFd, RawFd},
 	unix::net::UnixStream,
 };
+use std::path::Path;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 
+use keepalive::{Keepalive, KeepaliveAction};
+use listeners::Listeners;
+
 use tab_protocol::message_frame::{TabMessageFrame, TabMessageFrameReader};
 use tab_protocol::message_header;
 use tab_protocol::{
-	AuthErrorPayload, AuthOkPayload, AuthPayload, BufferIndex, BufferReleasePayload,
-	BufferRequestAckPayload, InputEventPayload, MonitorInfo, SessionActivePayload,
+	AccessibilityPayload, AccessibilitySettings, AuthErrorPayload, AuthOkPayload, AuthPayload,
+	BufferIndex, BufferReleasePayload, BufferRequestAckPayload, CaptureEndedPayload,
+	CaptureFramePayload, CaptureReleasePayload, CaptureSource, CaptureStartPayload,
+	CaptureStopPayload, ClockSyncPayload, ClockSyncRequestPayload, Colorspace, DrmDeviceInfo,
+	ErrorPayload, GammaRamps, GlobalShortcutPayload, GlobalShortcutRegisterPayload,
+	GlobalShortcutUnregisterPayload, IdleInhibitPayload, InputDeviceInfo, InputEventPayload,
+	InputInjectPayload, KeyboardGrabPayload, MonitorBrightnessPayload, MonitorGammaPayload,
+	MonitorInfo, MonitorMode, MonitorModeSetPayload, MonitorPower, MonitorPowerPayload,
+	MonitorPrimarySetPayload, OverlayCreatePayload, OverlayDestroyPayload, OverlayMovePayload,
+	OverlayPresentPayload, OverlayReleasePayload, ProtocolError, Rect, SafeAreaPayload,
+	ScanoutHintPayload, SeatId, SeatInputEventPayload, ServerErrorCode, SessionActivePayload,
 	SessionAwakePayload, SessionCreatePayload, SessionCreatedPayload, SessionInfo,
-	SessionReadyPayload, SessionRole, SessionSleepPayload, SessionStatePayload,
-	SessionSwitchPayload, TabMessage,
+	SessionMessagePayload, SessionMessageSendPayload, SessionPreviewPayload,
+	SessionPreviewRequestPayload, SessionReadyPayload, SessionRole, SessionSleepPayload,
+	SessionStatePayload, SessionSwitchPayload, SessionUpdatePayload, SurfaceColorspacePayload,
+	TabMessage,
 };
 
-use crate::gbm_allocator::GbmAllocator;
+type ExtensionDecoder = Box<dyn Fn(&TabMessageFrame) -> Result<Rc<dyn Any>, ProtocolError>>;
 
 /// Primary synchronous Tab client handle.
 pub struct TabClient {
@@ -39,15 +70,27 @@ pub struct TabClient {
 	reader: TabMessageFrameReader,
 	session: SessionInfo,
 	monitors: HashMap<MonitorId, MonitorState>,
-	monitor_listeners: Vec<Box<dyn Fn(&MonitorEvent)>>,
-	render_listeners: Vec<Box<dyn Fn(&RenderEvent)>>,
-	session_listeners: Vec<Box<dyn Fn(&SessionEvent)>>,
-	input_listeners: Vec<Box<dyn Fn(&InputEvent)>>,
-	gbm: GbmAllocator,
+	input_devices: HashMap<u32, InputDeviceInfo>,
+	monitor_listeners: Listeners<MonitorEvent>,
+	render_listeners: Listeners<RenderEvent>,
+	session_listeners: Listeners<SessionEvent>,
+	input_listeners: Listeners<InputEvent>,
+	extension_listeners: Listeners<ExtensionEvent>,
+	next_listener_id: u64,
+	/// Events kept for [`TabClient::poll_event`]; `None` until enabled.
+	event_queue: Option<VecDeque<TabEvent>>,
+	extensions: Vec<(String, ExtensionDecoder)>,
+	inflight_buffer_requests: VecDeque<(String, BufferIndex)>,
+	/// `None` for connections configured with `TabClientConfig::shm_buffers`.
+	allocator: Option<Rc<dyn Allocator>>,
+	shm: MemfdAllocator,
+	server_drm_device: Option<DrmDeviceInfo>,
+	buffer_request_ack_timeout: Duration,
+	clock: ClockSync,
+	keepalive: Keepalive,
 }
 
 impl TabClient {
-	const BUFFER_REQUEST_ACK_TIMEOUT: Duration = Duration::from_millis(250);
 	const SESSION_CREATE_TIMEOUT: Duration = Duration::from_millis(500);
 
 	pub fn connect(config: TabClientConfig) -> Result<Self, TabClientError> {
@@ -73,25 +116,135 @@ impl TabClient {
 			.into_iter()
 			.map(|info| (info.id.clone(), MonitorState::new(info)))
 			.collect();
-		let gbm = GbmAllocator::new(config.render_node_path())?;
+		let input_devices = auth_ok
+			.input_devices
+			.into_iter()
+			.map(|info| (info.id, info))
+			.collect();
+		let allocator: Option<Rc<dyn Allocator>> = if let Some(allocator) = config.allocator() {
+			Some(allocator)
+		} else if config.shm_buffers_enabled() {
+			None
+		} else {
+			let gbm = GbmAllocator::new(config.render_node_path(), payload.drm_device.as_ref())?
+				.with_format(config.swapchain_format_value());
+			Some(Rc::new(if config.linear_buffers_enabled() {
+				gbm.with_linear_buffers()
+			} else {
+				gbm
+			}))
+		};
 		socket.set_nonblocking(true)?;
+		Self::send_clock_sync_request(&socket)?;
 		Ok(Self {
 			socket,
 			reader,
 			session: auth_ok.session,
 			monitors,
-			monitor_listeners: Vec::new(),
-			render_listeners: Vec::new(),
-			session_listeners: Vec::new(),
-			input_listeners: Vec::new(),
-			gbm,
+			input_devices,
+			monitor_listeners: Listeners::default(),
+			render_listeners: Listeners::default(),
+			session_listeners: Listeners::default(),
+			input_listeners: Listeners::default(),
+			extension_listeners: Listeners::default(),
+			next_listener_id: 0,
+			event_queue: None,
+			extensions: Vec::new(),
+			inflight_buffer_requests: VecDeque::new(),
+			allocator,
+			shm: MemfdAllocator::new(),
+			server_drm_device: payload.drm_device,
+			buffer_request_ack_timeout: config.buffer_request_ack_timeout_value(),
+			clock: ClockSync::new(),
+			keepalive: Keepalive::new(config.ping_interval_value()),
 		})
 	}
 
+	/// Reads the server's hello and disconnects, returning the GPU the server advertises.
+	///
+	/// Lets an application pick its render node before it connects for real.
+	pub fn probe_drm_device(socket_path: &Path) -> Result<Option<DrmDeviceInfo>, TabClientError> {
+		let socket = tab_protocol::unix_socket_utils::connect_seqpacket(socket_path)?;
+		let mut reader = TabMessageFrameReader::new();
+		match Self::read_message(&socket, &mut reader)? {
+			TabMessage::Hello(payload) => Ok(payload.drm_device),
+			_ => Err(TabClientError::Unexpected("expected hello")),
+		}
+	}
+
+	/// GPU the server composites on, as advertised in its hello.
+	pub fn server_drm_device(&self) -> Option<&DrmDeviceInfo> {
+		self.server_drm_device.as_ref()
+	}
+
+	/// Render node buffers are allocated on; `None` for shm-only connections and
+	/// allocators without one.
+	pub fn render_node_path(&self) -> Option<&Path> {
+		self
+			.allocator
+			.as_ref()
+			.and_then(|allocator| allocator.render_node_path())
+	}
+
 	pub fn session(&self) -> &SessionInfo {
 		&self.session
 	}
 
+	/// Reporter for sending a crash report from a panic hook; see [`CrashReporter`].
+	pub fn crash_reporter(&self) -> Result<CrashReporter, TabClientError> {
+		CrashReporter::new(&self.socket)
+	}
+
+	/// Offset between the server's input timestamps and this process's clock.
+	///
+	/// Estimated from a few clock sync round trips started on connect.
+	pub fn clock(&self) -> &ClockSync {
+		&self.clock
+	}
+
+	/// Starts a new series of clock sync round trips, e.g. after a suspend.
+	pub fn sync_clock(&mut self) -> Result<(), TabClientError> {
+		self.clock.restart();
+		Self::send_clock_sync_request(&self.socket)
+	}
+
+	/// Pings the server when a ping is due and reports a server that stopped answering.
+	///
+	/// Call it after every [`TabClient::dispatch_events`] and again by
+	/// [`TabClient::keepalive_deadline`]. A ping unanswered for a whole
+	/// [`TabClientConfig::ping_interval`] emits [`SessionEvent::ServerUnresponsive`]; its
+	/// late pong emits [`SessionEvent::ServerRecovered`].
+	pub fn poll_keepalive(&mut self) -> Result<(), TabClientError> {
+		match self.keepalive.poll(Instant::now()) {
+			KeepaliveAction::None => {}
+			KeepaliveAction::SendPing => {
+				TabMessageFrame::no_payload(message_header::PING).encode_and_send(&self.socket)?;
+			}
+			KeepaliveAction::Unresponsive(since) => {
+				self.emit(SessionEvent::ServerUnresponsive { since });
+			}
+		}
+		Ok(())
+	}
+
+	/// When [`TabClient::poll_keepalive`] has to run next; `None` without pings.
+	pub fn keepalive_deadline(&self) -> Option<Instant> {
+		self.keepalive.deadline()
+	}
+
+	/// Round trip of the last ping the server answered.
+	pub fn server_latency(&self) -> Option<Duration> {
+		self.keepalive.latency()
+	}
+
+	fn send_clock_sync_request(socket: &UnixStream) -> Result<(), TabClientError> {
+		let payload = ClockSyncRequestPayload {
+			client_time_usec: monotonic_usec(),
+		};
+		TabMessageFrame::json(message_header::CLOCK_SYNC_REQUEST, payload).encode_and_send(socket)?;
+		Ok(())
+	}
+
 	pub fn monitors(&self) -> impl Iterator<Item = &MonitorState> {
 		self.monitors.values()
 	}
@@ -100,6 +253,14 @@ impl TabClient {
 		self.monitors.get(id)
 	}
 
+	pub fn input_devices(&self) -> impl Iterator<Item = &InputDeviceInfo> {
+		self.input_devices.values()
+	}
+
+	pub fn input_device(&self, id: u32) -> Option<&InputDeviceInfo> {
+		self.input_devices.get(&id)
+	}
+
 	pub fn socket_fd(&self) -> RawFd {
 		self.socket.as_raw_fd()
 	}
@@ -108,20 +269,50 @@ impl TabClient {
 		[self.socket.as_raw_fd(), self.drm_fd()]
 	}
 
+	/// Render node fd, or -1 for shm-only connections, which `poll` skips.
 	pub fn drm_fd(&self) -> RawFd {
-		self.gbm.drm_fd()
+		self
+			.allocator
+			.as_ref()
+			.map_or(-1, |allocator| allocator.drm_fd())
 	}
 
+	/// Allocates and links a swapchain for `monitor_id`, in shared memory for shm-only
+	/// connections.
 	pub fn create_swapchain(&self, monitor_id: &str) -> Result<TabSwapchain, TabClientError> {
 		let monitor = self
 			.monitors
 			.get(monitor_id)
 			.ok_or_else(|| TabClientError::UnknownMonitor(monitor_id.to_string()))?;
-		let swapchain = self.gbm.create_swapchain(monitor)?;
+		let swapchain = match &self.allocator {
+			Some(allocator) => allocator.create_swapchain(monitor)?,
+			None => self.shm.create_swapchain(monitor)?,
+		};
 		self.framebuffer_link(&swapchain)?;
 		Ok(swapchain)
 	}
 
+	/// Allocates and links a swapchain in shared memory, for frames drawn by the CPU.
+	///
+	/// The server uploads each submitted buffer, which suits small or rarely updated
+	/// content; buffers are `XRGB8888`.
+	pub fn create_shm_swapchain(&self, monitor_id: &str) -> Result<TabSwapchain, TabClientError> {
+		let monitor = self
+			.monitors
+			.get(monitor_id)
+			.ok_or_else(|| TabClientError::UnknownMonitor(monitor_id.to_string()))?;
+		let swapchain = self.shm.create_swapchain(monitor)?;
+		self.framebuffer_link(&swapchain)?;
+		Ok(swapchain)
+	}
+
+	fn allocator(&self) -> Result<&dyn Allocator, TabClientError> {
+		self
+			.allocator
+			.as_deref()
+			.ok_or(TabClientError::NoRenderNode)
+	}
+
 	pub fn framebuffer_link(&self, swapchain: &TabSwapchain) -> Result<(), TabClientError> {
 		let payload = swapchain.framebuffer_link_payload();
 		let mut frame = TabMessageFrame::json(message_header::FRAMEBUFFER_LINK, payload);
@@ -148,6 +339,31 @@ impl TabClient {
 		Ok(())
 	}
 
+	/// Sends a buffer request without waiting for the server acknowledgement.
+	///
+	/// The outcome is delivered to render listeners as
+	/// [`RenderEvent::BufferRequestAcked`] or [`RenderEvent::BufferRequestFailed`]
+	/// from a later `dispatch_events`. Rejections are matched to their request by the
+	/// `buffer_request` the server tags the error with.
+	pub fn send_buffer_request(
+		&mut self,
+		monitor_id: &str,
+		buffer: BufferIndex,
+		acquire_fence: Option<RawFd>,
+	) -> Result<(), TabClientError> {
+		let payload = format!("{monitor_id} {}", buffer as u8);
+		let frame = TabMessageFrame {
+			header: message_header::BUFFER_REQUEST.into(),
+			payload: Some(payload),
+			fds: acquire_fence.map_or_else(Vec::new, |fd| vec![fd]),
+		};
+		frame.encode_and_send(&self.socket)?;
+		self
+			.inflight_buffer_requests
+			.push_back((monitor_id.to_string(), buffer));
+		Ok(())
+	}
+
 	pub fn send_ready(&self) -> Result<(), TabClientError> {
 		let payload = SessionReadyPayload {
 			session_id: self.session.id.clone(),
@@ -156,17 +372,464 @@ impl TabClient {
 		Ok(())
 	}
 
+	/// Updates this session's display name and metadata; `None` metadata values remove keys.
+	///
+	/// The server answers with a `SessionEvent::State`, which also updates [`Self::session`].
+	pub fn update_session(
+		&self,
+		display_name: Option<String>,
+		metadata: BTreeMap<String, Option<String>>,
+	) -> Result<(), TabClientError> {
+		let payload = SessionUpdatePayload {
+			display_name,
+			metadata,
+		};
+		TabMessageFrame::json(message_header::SESSION_UPDATE, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only: asks for a snapshot of `session_id`'s latest frame, scaled to fit
+	/// `max_width` x `max_height`.
+	///
+	/// The snapshot arrives as a `SessionEvent::Preview`, or as a
+	/// `SessionEvent::PreviewUnavailable` when the session has no frame on screen.
+	pub fn request_session_preview(
+		&self,
+		session_id: &str,
+		monitor_id: Option<&str>,
+		max_width: i32,
+		max_height: i32,
+	) -> Result<(), TabClientError> {
+		let payload = SessionPreviewRequestPayload {
+			session_id: session_id.to_string(),
+			monitor_id: monitor_id.map(str::to_string),
+			max_width,
+			max_height,
+		};
+		TabMessageFrame::json(message_header::SESSION_PREVIEW_REQUEST, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only: reserves `keys`, evdev key codes, as a shortcut delivered to this
+	/// session whichever session has focus.
+	///
+	/// Presses arrive as `InputEvent::GlobalShortcut`; a combination already taken is
+	/// reported as `InputEvent::GlobalShortcutConflict`. Other rejections arrive as a
+	/// `SessionEvent::RequestFailed` when sent with a `request_id`.
+	pub fn register_global_shortcut(
+		&self,
+		shortcut_id: &str,
+		keys: Vec<u32>,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = GlobalShortcutRegisterPayload {
+			shortcut_id: shortcut_id.to_string(),
+			keys,
+			request_id,
+		};
+		TabMessageFrame::json(message_header::GLOBAL_SHORTCUT_REGISTER, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Sends `data` to another session through the server.
+	///
+	/// Admin sessions may message any session, others only admin sessions or the sender
+	/// of a message carrying a `correlation_id`, answered by passing it as `reply_to`.
+	/// Refused messages are reported as `SessionEvent::MessageUndeliverable`.
+	pub fn send_session_message(
+		&self,
+		session_id: &str,
+		data: Vec<u8>,
+		correlation_id: Option<u64>,
+		reply_to: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = SessionMessageSendPayload {
+			session_id: session_id.to_string(),
+			correlation_id,
+			reply_to,
+			data,
+		};
+		TabMessageFrame::json(message_header::SESSION_MESSAGE_SEND, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	pub fn unregister_global_shortcut(
+		&self,
+		shortcut_id: &str,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = GlobalShortcutUnregisterPayload {
+			shortcut_id: shortcut_id.to_string(),
+			request_id,
+		};
+		TabMessageFrame::json(message_header::GLOBAL_SHORTCUT_UNREGISTER, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only: routes key events to this session while another stays active.
+	///
+	/// A grab another session holds is reported as `InputEvent::KeyboardGrabUnavailable`.
+	pub fn set_keyboard_grab(&self, grab: bool) -> Result<(), TabClientError> {
+		let payload = KeyboardGrabPayload { grab };
+		TabMessageFrame::json(message_header::KEYBOARD_GRAB, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	pub fn set_idle_inhibit(
+		&self,
+		inhibit: bool,
+		reason: Option<String>,
+	) -> Result<(), TabClientError> {
+		let payload = IdleInhibitPayload { inhibit, reason };
+		TabMessageFrame::json(message_header::IDLE_INHIBIT, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; the new mode arrives as a `MonitorEvent::Changed` once applied.
+	///
+	/// With a `request_id`, a rejection arrives as a `SessionEvent::RequestFailed`.
+	pub fn set_monitor_mode(
+		&self,
+		monitor_id: &str,
+		mode: MonitorMode,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = MonitorModeSetPayload {
+			monitor_id: monitor_id.to_string(),
+			mode,
+			request_id,
+		};
+		TabMessageFrame::json(message_header::MONITOR_MODE_SET, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; affected monitors arrive as `MonitorEvent::Changed` once applied.
+	///
+	/// With a `request_id`, a rejection arrives as a `SessionEvent::RequestFailed`.
+	pub fn set_primary_monitor(
+		&self,
+		monitor_id: &str,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = MonitorPrimarySetPayload {
+			monitor_id: monitor_id.to_string(),
+			request_id,
+		};
+		TabMessageFrame::json(message_header::MONITOR_PRIMARY_SET, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; the server answers other roles with a `forbidden` error.
+	///
+	/// With a `request_id`, a rejection arrives as a `SessionEvent::RequestFailed`
+	/// carrying it instead of a plain server error.
+	pub fn set_monitor_brightness(
+		&self,
+		monitor_id: &str,
+		brightness: f32,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = MonitorBrightnessPayload {
+			monitor_id: monitor_id.to_string(),
+			brightness,
+			request_id,
+		};
+		TabMessageFrame::json(message_header::MONITOR_BRIGHTNESS, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; the new state arrives as `MonitorEvent::Power` once the display
+	/// switched, a rejection as a `SessionEvent::RequestFailed` when sent with a
+	/// `request_id`.
+	pub fn set_monitor_power(
+		&self,
+		monitor_id: &str,
+		power: MonitorPower,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = MonitorPowerPayload {
+			monitor_id: monitor_id.to_string(),
+			power,
+			request_id,
+		};
+		TabMessageFrame::json(message_header::MONITOR_POWER, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; delivers `event` to the clients of `session_id` as input from `seat`.
+	///
+	/// With a `request_id`, a rejection arrives as a `SessionEvent::RequestFailed`.
+	pub fn inject_input(
+		&self,
+		session_id: &str,
+		seat: SeatId,
+		event: InputEventPayload,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = InputInjectPayload {
+			session_id: session_id.to_string(),
+			input: SeatInputEventPayload { seat, event },
+			request_id,
+		};
+		TabMessageFrame::json(message_header::INPUT_INJECT, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; every client, this one included, receives the settings as
+	/// `SessionEvent::Accessibility`. With a `request_id`, a rejection arrives as a
+	/// `SessionEvent::RequestFailed`.
+	pub fn set_accessibility(
+		&self,
+		settings: AccessibilitySettings,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = AccessibilityPayload {
+			settings,
+			request_id,
+		};
+		TabMessageFrame::json(message_header::ACCESSIBILITY, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; `None` restores the identity ramp. `request_id` tags a rejection like
+	/// in `set_monitor_brightness`.
+	pub fn set_gamma_lut(
+		&self,
+		monitor_id: &str,
+		ramps: Option<GammaRamps>,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = MonitorGammaPayload {
+			monitor_id: monitor_id.to_string(),
+			ramps,
+			request_id,
+		};
+		TabMessageFrame::json(message_header::MONITOR_GAMMA, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Tells the server whether this session's frames on a monitor are fullscreen and opaque.
+	pub fn set_scanout_hint(
+		&self,
+		monitor_id: &str,
+		fullscreen_opaque: bool,
+	) -> Result<(), TabClientError> {
+		let payload = ScanoutHintPayload {
+			monitor_id: monitor_id.to_string(),
+			fullscreen_opaque,
+		};
+		TabMessageFrame::json(message_header::SCANOUT_HINT, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Tells the server which color space this session's frames on a monitor are in.
+	pub fn set_surface_colorspace(
+		&self,
+		monitor_id: &str,
+		colorspace: Colorspace,
+	) -> Result<(), TabClientError> {
+		let payload = SurfaceColorspacePayload {
+			monitor_id: monitor_id.to_string(),
+			colorspace,
+		};
+		TabMessageFrame::json(message_header::SURFACE_COLORSPACE, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Admin-only; declares the parts of a monitor this session draws chrome over.
+	///
+	/// The server forwards the list to every client as a `MonitorEvent::SafeArea`; an
+	/// empty list clears it. With a `request_id`, a rejection arrives as a
+	/// `SessionEvent::RequestFailed`.
+	pub fn set_safe_area(
+		&self,
+		monitor_id: &str,
+		occluded: Vec<Rect>,
+		request_id: Option<u64>,
+	) -> Result<(), TabClientError> {
+		let payload = SafeAreaPayload {
+			monitor_id: monitor_id.to_string(),
+			occluded,
+			request_id,
+		};
+		TabMessageFrame::json(message_header::SAFE_AREA, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Allocates an overlay swapchain and links it to the server.
+	///
+	/// The overlay is drawn above this session's frames on `monitor_id`, at `position`
+	/// relative to the monitor's top-left corner; higher `z` overlays are drawn on top.
+	/// The swapchain's `monitor_id` field holds `overlay_id`.
+	pub fn create_overlay(
+		&self,
+		overlay_id: &str,
+		monitor_id: &str,
+		size: (i32, i32),
+		position: (i32, i32),
+		z: i32,
+	) -> Result<TabSwapchain, TabClientError> {
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(TabClientError::UnknownMonitor(monitor_id.to_string()));
+		}
+		let swapchain = self
+			.allocator()?
+			.create_overlay_swapchain(overlay_id, size.0, size.1)?;
+		let buffer = &swapchain.buffers[0];
+		let payload = OverlayCreatePayload {
+			overlay_id: overlay_id.to_string(),
+			monitor_id: monitor_id.to_string(),
+			x: position.0,
+			y: position.1,
+			z,
+			width: buffer.width(),
+			height: buffer.height(),
+			stride: buffer.stride(),
+			offset: buffer.offset(),
+			fourcc: buffer.fourcc(),
+		};
+		let mut frame = TabMessageFrame::json(message_header::OVERLAY_CREATE, payload);
+		frame.fds = Vec::from(swapchain.export_fds());
+		frame.encode_and_send(&self.socket)?;
+		Ok(swapchain)
+	}
+
+	pub fn move_overlay(&self, overlay_id: &str, position: (i32, i32)) -> Result<(), TabClientError> {
+		let payload = OverlayMovePayload {
+			overlay_id: overlay_id.to_string(),
+			x: position.0,
+			y: position.1,
+		};
+		TabMessageFrame::json(message_header::OVERLAY_MOVE, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Shows an overlay buffer once `acquire_fence` signals.
+	///
+	/// The previously shown buffer comes back as [`RenderEvent::OverlayReleased`].
+	pub fn present_overlay(
+		&self,
+		overlay_id: &str,
+		buffer: BufferIndex,
+		acquire_fence: Option<RawFd>,
+	) -> Result<(), TabClientError> {
+		let payload = OverlayPresentPayload {
+			overlay_id: overlay_id.to_string(),
+			buffer,
+		};
+		let mut frame = TabMessageFrame::json(message_header::OVERLAY_PRESENT, payload);
+		frame.fds = acquire_fence.map_or_else(Vec::new, |fd| vec![fd]);
+		frame.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	pub fn destroy_overlay(&self, overlay_id: &str) -> Result<(), TabClientError> {
+		let payload = OverlayDestroyPayload {
+			overlay_id: overlay_id.to_string(),
+		};
+		TabMessageFrame::json(message_header::OVERLAY_DESTROY, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	/// Allocates a capture swapchain and starts streaming `source` into it. Admin only.
+	///
+	/// Frames arrive as [`RenderEvent::CaptureFrame`], scaled to `size`. The swapchain's
+	/// `monitor_id` field holds `capture_id`.
+	pub fn start_capture(
+		&self,
+		capture_id: &str,
+		source: CaptureSource,
+		size: (i32, i32),
+	) -> Result<TabSwapchain, TabClientError> {
+		let (CaptureSource::Monitor { monitor_id } | CaptureSource::Session { monitor_id, .. }) =
+			&source;
+		if !self.monitors.contains_key(monitor_id) {
+			return Err(TabClientError::UnknownMonitor(monitor_id.clone()));
+		}
+		let swapchain = self
+			.allocator()?
+			.create_overlay_swapchain(capture_id, size.0, size.1)?;
+		let buffer = &swapchain.buffers[0];
+		let payload = CaptureStartPayload {
+			capture_id: capture_id.to_string(),
+			source,
+			width: buffer.width(),
+			height: buffer.height(),
+			stride: buffer.stride(),
+			offset: buffer.offset(),
+			fourcc: buffer.fourcc(),
+		};
+		let mut frame = TabMessageFrame::json(message_header::CAPTURE_START, payload);
+		frame.fds = Vec::from(swapchain.export_fds());
+		frame.encode_and_send(&self.socket)?;
+		Ok(swapchain)
+	}
+
+	/// Hands a buffer from [`RenderEvent::CaptureFrame`] back to the server for reuse.
+	pub fn release_capture_frame(
+		&self,
+		capture_id: &str,
+		buffer: BufferIndex,
+	) -> Result<(), TabClientError> {
+		let payload = CaptureReleasePayload {
+			capture_id: capture_id.to_string(),
+			buffer,
+		};
+		TabMessageFrame::json(message_header::CAPTURE_RELEASE, payload)
+			.encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	pub fn stop_capture(&self, capture_id: &str) -> Result<(), TabClientError> {
+		let payload = CaptureStopPayload {
+			capture_id: capture_id.to_string(),
+		};
+		TabMessageFrame::json(message_header::CAPTURE_STOP, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
 	pub fn create_session(
 		&mut self,
 		role: SessionRole,
 		display_name: Option<String>,
 	) -> Result<SessionCreatedPayload, TabClientError> {
-		let payload = SessionCreatePayload { role, display_name };
-		TabMessageFrame::json(message_header::SESSION_CREATE, payload)
-			.encode_and_send(&self.socket)?;
+		let payload = SessionCreatePayload {
+			role,
+			display_name,
+			request_id: None,
+		};
+		TabMessageFrame::json(message_header::SESSION_CREATE, payload).encode_and_send(&self.socket)?;
 		self.wait_for_session_created()
 	}
 
+	/// Asks the server to create a session without waiting for the answer.
+	///
+	/// The result arrives as a `SessionEvent::Created` or `SessionEvent::RequestFailed`
+	/// carrying `request_id`.
+	pub fn request_session_create(
+		&self,
+		role: SessionRole,
+		display_name: Option<String>,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		let payload = SessionCreatePayload {
+			role,
+			display_name,
+			request_id: Some(request_id),
+		};
+		TabMessageFrame::json(message_header::SESSION_CREATE, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
 	pub fn switch_session(
 		&self,
 		session_id: &str,
@@ -177,38 +840,123 @@ impl TabClient {
 			session_id: session_id.to_string(),
 			animation,
 			duration,
+			request_id: None,
 		};
-		TabMessageFrame::json(message_header::SESSION_SWITCH, payload)
-			.encode_and_send(&self.socket)?;
+		TabMessageFrame::json(message_header::SESSION_SWITCH, payload).encode_and_send(&self.socket)?;
 		Ok(())
 	}
 
-	pub fn on_monitor_event<F>(&mut self, listener: F)
+	/// Like `switch_session`, but a rejection arrives as a `SessionEvent::RequestFailed`
+	/// carrying `request_id` instead of a plain server error.
+	pub fn request_session_switch(
+		&self,
+		session_id: &str,
+		animation: Option<String>,
+		duration: Duration,
+		request_id: u64,
+	) -> Result<(), TabClientError> {
+		let payload = SessionSwitchPayload {
+			session_id: session_id.to_string(),
+			animation,
+			duration,
+			request_id: Some(request_id),
+		};
+		TabMessageFrame::json(message_header::SESSION_SWITCH, payload).encode_and_send(&self.socket)?;
+		Ok(())
+	}
+
+	pub fn on_monitor_event<F>(&mut self, listener: F) -> ListenerId
+	where
+		F: FnMut(&MonitorEvent) + 'static,
+	{
+		let id = self.next_listener_id();
+		self.monitor_listeners.add(id, Box::new(listener))
+	}
+
+	pub fn on_render_event<F>(&mut self, listener: F) -> ListenerId
+	where
+		F: FnMut(&RenderEvent) + 'static,
+	{
+		let id = self.next_listener_id();
+		self.render_listeners.add(id, Box::new(listener))
+	}
+
+	pub fn on_session_event<F>(&mut self, listener: F) -> ListenerId
 	where
-		F: Fn(&MonitorEvent) + 'static,
+		F: FnMut(&SessionEvent) + 'static,
 	{
-		self.monitor_listeners.push(Box::new(listener));
+		let id = self.next_listener_id();
+		self.session_listeners.add(id, Box::new(listener))
 	}
 
-	pub fn on_render_event<F>(&mut self, listener: F)
+	pub fn on_input_event<F>(&mut self, listener: F) -> ListenerId
 	where
-		F: Fn(&RenderEvent) + 'static,
+		F: FnMut(&InputEvent) + 'static,
 	{
-		self.render_listeners.push(Box::new(listener));
+		let id = self.next_listener_id();
+		self.input_listeners.add(id, Box::new(listener))
 	}
 
-	pub fn on_session_event<F>(&mut self, listener: F)
+	/// Decodes server messages whose header starts with `header_prefix`.
+	///
+	/// Frames the Tab protocol does not know are otherwise dropped; this lets forks of the
+	/// protocol add messages without patching the client. Decoded values reach the
+	/// listeners added with [`TabClient::on_extension_event`]. The longest matching prefix
+	/// wins; the decoder owns any fds attached to the frame. Decoder errors are returned
+	/// from [`TabClient::dispatch_events`].
+	pub fn register_extension<T, F>(&mut self, header_prefix: impl Into<String>, decoder: F)
 	where
-		F: Fn(&SessionEvent) + 'static,
+		T: Any,
+		F: Fn(&TabMessageFrame) -> Result<T, ProtocolError> + 'static,
 	{
-		self.session_listeners.push(Box::new(listener));
+		let decoder: ExtensionDecoder =
+			Box::new(move |frame| decoder(frame).map(|value| Rc::new(value) as Rc<dyn Any>));
+		self.extensions.push((header_prefix.into(), decoder));
 	}
 
-	pub fn on_input_event<F>(&mut self, listener: F)
+	pub fn on_extension_event<F>(&mut self, listener: F) -> ListenerId
 	where
-		F: Fn(&InputEvent) + 'static,
+		F: FnMut(&ExtensionEvent) + 'static,
 	{
-		self.input_listeners.push(Box::new(listener));
+		let id = self.next_listener_id();
+		self.extension_listeners.add(id, Box::new(listener))
+	}
+
+	/// Removes a listener added with one of the `on_*_event` methods.
+	///
+	/// Returns false if `id` was already removed.
+	pub fn remove_listener(&mut self, id: ListenerId) -> bool {
+		self.monitor_listeners.remove(id)
+			|| self.render_listeners.remove(id)
+			|| self.session_listeners.remove(id)
+			|| self.input_listeners.remove(id)
+			|| self.extension_listeners.remove(id)
+	}
+
+	/// Keeps every event for [`TabClient::poll_event`], in addition to calling listeners.
+	///
+	/// Off by default. Disabling drops the queued events and closes their fds.
+	pub fn set_event_queue(&mut self, enabled: bool) {
+		if !enabled {
+			for event in self.event_queue.take().into_iter().flatten() {
+				event.close_fds();
+			}
+		} else if self.event_queue.is_none() {
+			self.event_queue = Some(VecDeque::new());
+		}
+	}
+
+	/// Pops the oldest event queued by [`TabClient::dispatch_events`].
+	///
+	/// Always `None` unless the queue was enabled with [`TabClient::set_event_queue`]. Fds of
+	/// returned events belong to the caller, as they do to listeners.
+	pub fn poll_event(&mut self) -> Option<TabEvent> {
+		self.event_queue.as_mut()?.pop_front()
+	}
+
+	/// Number of queued events [`TabClient::poll_event`] has yet to return.
+	pub fn queued_events(&self) -> usize {
+		self.event_queue.as_ref().map_or(0, VecDeque::len)
 	}
 
 	pub fn dispatch_events(&mut self) -> Result<(), TabClientError> {
@@ -225,6 +973,26 @@ impl TabClient {
 		Ok(())
 	}
 
+	fn next_listener_id(&mut self) -> u64 {
+		self.next_listener_id += 1;
+		self.next_listener_id
+	}
+
+	/// Calls the listeners of the event's kind, then queues it if the queue is enabled.
+	fn emit(&mut self, event: impl Into<TabEvent>) {
+		let event = event.into();
+		match &event {
+			TabEvent::Monitor(event) => self.monitor_listeners.emit(event),
+			TabEvent::Render(event) => self.render_listeners.emit(event),
+			TabEvent::Session(event) => self.session_listeners.emit(event),
+			TabEvent::Input(event) => self.input_listeners.emit(event),
+			TabEvent::Extension(event) => self.extension_listeners.emit(event),
+		}
+		if let Some(queue) = &mut self.event_queue {
+			queue.push_back(event);
+		}
+	}
+
 	fn read_message(
 		socket: &UnixStream,
 		reader: &mut TabMessageFrameReader,
@@ -262,8 +1030,41 @@ impl TabClient {
 			TabMessage::MonitorRemoved(payload) => {
 				self.handle_monitor_removed(payload.monitor_id, payload.name);
 			}
+			TabMessage::MonitorChanged(payload) => {
+				self.handle_monitor_changed(payload.monitor);
+			}
+			TabMessage::SafeArea(payload) => {
+				self.handle_safe_area(payload.monitor_id, payload.occluded);
+			}
+			TabMessage::MonitorPower(payload) => {
+				let event = MonitorEvent::Power {
+					monitor_id: payload.monitor_id,
+					power: payload.power,
+				};
+				self.emit(event);
+			}
+			TabMessage::Accessibility(payload) => {
+				let event = SessionEvent::Accessibility(payload.settings);
+				self.emit(event);
+			}
 			TabMessage::SessionCreated(payload) => {
-				self.handle_session_created(payload.session, payload.token);
+				self.handle_session_created(payload);
+			}
+			TabMessage::ClockSync(ClockSyncPayload {
+				client_time_usec,
+				server_time_usec,
+			}) => {
+				let resync = self
+					.clock
+					.record(client_time_usec, server_time_usec, monotonic_usec());
+				if resync {
+					Self::send_clock_sync_request(&self.socket)?;
+				}
+			}
+			TabMessage::Pong => {
+				if let Some(unresponsive_for) = self.keepalive.pong(Instant::now()) {
+					self.emit(SessionEvent::ServerRecovered { unresponsive_for });
+				}
 			}
 			TabMessage::BufferRelease {
 				payload,
@@ -271,6 +1072,22 @@ impl TabClient {
 			} => {
 				self.handle_buffer_release(payload, release_fence);
 			}
+			TabMessage::OverlayRelease {
+				payload,
+				release_fence,
+			} => {
+				self.handle_overlay_release(payload, release_fence);
+			}
+			TabMessage::CaptureFrame {
+				payload,
+				ready_fence,
+			} => {
+				self.handle_capture_frame(payload, ready_fence);
+			}
+			TabMessage::CaptureEnded(CaptureEndedPayload { capture_id, reason }) => {
+				let event = RenderEvent::CaptureEnded { capture_id, reason };
+				self.emit(event);
+			}
 			TabMessage::SessionAwake(SessionAwakePayload { session_id }) => {
 				self.handle_session_awake(session_id);
 			}
@@ -283,29 +1100,118 @@ impl TabClient {
 			TabMessage::SessionState(SessionStatePayload { session }) => {
 				self.handle_session_state(session);
 			}
+			TabMessage::SessionPreview { payload, pixels } => {
+				self.handle_session_preview(payload, pixels);
+			}
 			TabMessage::InputEvent(payload) => {
 				self.handle_input_event(payload);
 			}
+			TabMessage::InputDeviceAdded(payload) => {
+				self.handle_input_device_added(payload.device);
+			}
+			TabMessage::InputDeviceRemoved(payload) => {
+				self.handle_input_device_removed(payload.device);
+			}
+			TabMessage::BufferRequestAck(payload) => {
+				self.handle_buffer_request_ack(payload);
+			}
+			TabMessage::GlobalShortcut(payload) => {
+				self.handle_global_shortcut(payload);
+			}
+			TabMessage::Error(
+				err @ ErrorPayload {
+					request_id: Some(request_id),
+					..
+				},
+			) => {
+				let event = SessionEvent::RequestFailed {
+					request_id,
+					error: err.into(),
+				};
+				self.emit(event);
+			}
+			TabMessage::Error(err) if err.error_code() == ServerErrorCode::ShortcutConflict => {
+				self.handle_global_shortcut_conflict(err.message.unwrap_or_default());
+			}
+			TabMessage::Error(err) if err.error_code() == ServerErrorCode::GrabUnavailable => {
+				self.handle_keyboard_grab_unavailable();
+			}
+			TabMessage::Error(err) if err.error_code() == ServerErrorCode::NoFrame => {
+				self.handle_session_preview_unavailable(err.message.unwrap_or_default());
+			}
+			TabMessage::SessionMessage(payload) => {
+				self.handle_session_message(payload);
+			}
+			TabMessage::SessionCrashed(payload) => {
+				let event = SessionEvent::Crashed {
+					session_id: payload.session_id,
+					message: payload.message,
+					backtrace_hash: payload.backtrace_hash,
+					app_version: payload.app_version,
+				};
+				self.emit(event);
+			}
+			TabMessage::Error(err) if err.error_code() == ServerErrorCode::MessageUndeliverable => {
+				let event = SessionEvent::MessageUndeliverable {
+					session_id: err.message.unwrap_or_default(),
+				};
+				self.emit(event);
+			}
+			TabMessage::Error(err) if err.buffer_request.is_some() => {
+				self.handle_buffer_request_error(err);
+			}
+			TabMessage::Error(err) => {
+				self.emit(SessionEvent::Error(err.into()));
+			}
+			TabMessage::Unknown(frame) => {
+				self.handle_extension_message(frame)?;
+			}
 			_ => {}
 		}
 		Ok(())
 	}
 
+	fn handle_extension_message(&mut self, frame: TabMessageFrame) -> Result<(), TabClientError> {
+		let header = frame.header.0.as_str();
+		let Some((_, decoder)) = self
+			.extensions
+			.iter()
+			.filter(|(prefix, _)| header.starts_with(prefix.as_str()))
+			.max_by_key(|(prefix, _)| prefix.len())
+		else {
+			return Ok(());
+		};
+		let event = ExtensionEvent::new(header.to_string(), decoder(&frame)?);
+		self.emit(event);
+		Ok(())
+	}
+
 	fn handle_monitor_added(&mut self, info: MonitorInfo) {
 		let state = MonitorState::new(info);
 		self.monitors.insert(state.info.id.clone(), state.clone());
 		let event = MonitorEvent::Added(state);
-		for listener in &self.monitor_listeners {
-			listener(&event);
-		}
+		self.emit(event);
+	}
+
+	fn handle_monitor_changed(&mut self, info: MonitorInfo) {
+		let state = MonitorState::new(info);
+		self.monitors.insert(state.info.id.clone(), state.clone());
+		let event = MonitorEvent::Changed(state);
+		self.emit(event);
+	}
+
+	fn handle_safe_area(&mut self, monitor_id: String, occluded: Vec<Rect>) {
+		let event = MonitorEvent::SafeArea {
+			monitor_id,
+			occluded,
+		};
+		self.emit(event);
 	}
 
 	fn handle_monitor_removed(&mut self, monitor_id: String, name: String) {
 		self.monitors.remove(&monitor_id);
 		let event = MonitorEvent::Removed { monitor_id, name };
-		for listener in &self.monitor_listeners {
-			listener(&event);
-		}
+		self.emit(event);
 	}
 
 	fn handle_buffer_release(
@@ -315,71 +1221,218 @@ impl TabClient {
 	) {
 		let monitor_id = payload.monitor_id;
 		let buffer = payload.buffer;
-		for listener in &self.render_listeners {
-			let release_fence_fd = release_fence
-				.as_ref()
-				.and_then(|fd| fd.as_fd().try_clone_to_owned().ok())
-				.map(|fd| fd.into_raw_fd());
-			let event = RenderEvent::BufferReleased {
+		self.render_listeners.emit_each(|| {
+			Some(RenderEvent::BufferReleased {
 				monitor_id: monitor_id.clone(),
 				buffer,
-				release_fence_fd,
-			};
-			listener(&event);
+				release_fence_fd: release_fence.as_ref().and_then(dup_raw_fd),
+			})
+		});
+		// The queue takes the original fd; it is closed here otherwise.
+		if let Some(queue) = &mut self.event_queue {
+			queue.push_back(TabEvent::Render(RenderEvent::BufferReleased {
+				monitor_id,
+				buffer,
+				release_fence_fd: release_fence.map(IntoRawFd::into_raw_fd),
+			}));
+		}
+	}
+
+	fn handle_overlay_release(
+		&mut self,
+		payload: OverlayReleasePayload,
+		release_fence: Option<OwnedFd>,
+	) {
+		self.render_listeners.emit_each(|| {
+			Some(RenderEvent::OverlayReleased {
+				overlay_id: payload.overlay_id.clone(),
+				buffer: payload.buffer,
+				release_fence_fd: release_fence.as_ref().and_then(dup_raw_fd),
+			})
+		});
+		if let Some(queue) = &mut self.event_queue {
+			queue.push_back(TabEvent::Render(RenderEvent::OverlayReleased {
+				overlay_id: payload.overlay_id,
+				buffer: payload.buffer,
+				release_fence_fd: release_fence.map(IntoRawFd::into_raw_fd),
+			}));
+		}
+	}
+
+	fn handle_capture_frame(&mut self, payload: CaptureFramePayload, ready_fence: Option<OwnedFd>) {
+		self.render_listeners.emit_each(|| {
+			Some(RenderEvent::CaptureFrame {
+				capture_id: payload.capture_id.clone(),
+				buffer: payload.buffer,
+				time_usec: payload.time_usec,
+				ready_fence_fd: ready_fence.as_ref().and_then(dup_raw_fd),
+			})
+		});
+		if let Some(queue) = &mut self.event_queue {
+			queue.push_back(TabEvent::Render(RenderEvent::CaptureFrame {
+				capture_id: payload.capture_id,
+				buffer: payload.buffer,
+				time_usec: payload.time_usec,
+				ready_fence_fd: ready_fence.map(IntoRawFd::into_raw_fd),
+			}));
 		}
 	}
 
+	fn handle_buffer_request_ack(&mut self, payload: BufferRequestAckPayload) {
+		let Some(pos) = self
+			.inflight_buffer_requests
+			.iter()
+			.position(|(monitor_id, buffer)| {
+				*monitor_id == payload.monitor_id && *buffer == payload.buffer
+			})
+		else {
+			return;
+		};
+		self.inflight_buffer_requests.remove(pos);
+		let event = RenderEvent::BufferRequestAcked {
+			monitor_id: payload.monitor_id,
+			buffer: payload.buffer,
+		};
+		self.emit(event);
+	}
+
+	fn handle_buffer_request_error(&mut self, mut err: ErrorPayload) {
+		let Some(request) = err.buffer_request.take() else {
+			return;
+		};
+		let Some(pos) = self
+			.inflight_buffer_requests
+			.iter()
+			.position(|(monitor_id, buffer)| {
+				*monitor_id == request.monitor_id && *buffer == request.buffer
+			})
+		else {
+			return;
+		};
+		self.inflight_buffer_requests.remove(pos);
+		let event = RenderEvent::BufferRequestFailed {
+			monitor_id: request.monitor_id,
+			buffer: request.buffer,
+			error: err.into(),
+		};
+		self.emit(event);
+	}
+
 	fn handle_session_awake(&mut self, session_id: String) {
 		let event = SessionEvent::Awake(session_id);
-		for listener in &self.session_listeners {
-			listener(&event);
-		}
+		self.emit(event);
 	}
 
 	fn handle_session_active(&mut self, session_id: String) {
 		let event = SessionEvent::Active(session_id);
-		for listener in &self.session_listeners {
-			listener(&event);
-		}
+		self.emit(event);
 	}
 
 	fn handle_session_sleep(&mut self, session_id: String) {
 		let event = SessionEvent::Sleep(session_id);
-		for listener in &self.session_listeners {
-			listener(&event);
-		}
+		self.emit(event);
 	}
 
-	fn handle_session_created(&mut self, session: SessionInfo, token: String) {
-		let event = SessionEvent::Created { session, token };
-		for listener in &self.session_listeners {
-			listener(&event);
-		}
+	fn handle_session_created(&mut self, payload: SessionCreatedPayload) {
+		let event = SessionEvent::Created {
+			session: payload.session,
+			token: payload.token,
+			request_id: payload.request_id,
+		};
+		self.emit(event);
 	}
 
 	fn handle_session_state(&mut self, session: SessionInfo) {
-		let event = SessionEvent::State(session);
-		for listener in &self.session_listeners {
-			listener(&event);
+		if session.id == self.session.id {
+			self.session = session.clone();
 		}
+		let event = SessionEvent::State(session);
+		self.emit(event);
 	}
 
-	fn handle_input_event(&mut self, payload: InputEventPayload) {
-		let event = InputEvent::Event(payload);
-		for listener in &self.input_listeners {
-			listener(&event);
+	fn handle_session_preview(&mut self, payload: SessionPreviewPayload, pixels: OwnedFd) {
+		let preview = |pixels_fd| SessionEvent::Preview {
+			session_id: payload.session_id.clone(),
+			monitor_id: payload.monitor_id.clone(),
+			width: payload.width,
+			height: payload.height,
+			stride: payload.stride,
+			fourcc: payload.fourcc,
+			pixels_fd,
+		};
+		self
+			.session_listeners
+			.emit_each(|| dup_raw_fd(&pixels).map(preview));
+		if let Some(queue) = &mut self.event_queue {
+			queue.push_back(TabEvent::Session(preview(pixels.into_raw_fd())));
 		}
 	}
 
+	fn handle_session_preview_unavailable(&mut self, session_id: String) {
+		let event = SessionEvent::PreviewUnavailable { session_id };
+		self.emit(event);
+	}
+
+	fn handle_session_message(&mut self, payload: SessionMessagePayload) {
+		let event = SessionEvent::Message {
+			from_session_id: payload.session_id,
+			correlation_id: payload.correlation_id,
+			reply_to: payload.reply_to,
+			data: payload.data,
+		};
+		self.emit(event);
+	}
+
+	fn handle_input_event(&mut self, payload: SeatInputEventPayload) {
+		let event = InputEvent::Event {
+			seat: payload.seat,
+			payload: payload.event,
+		};
+		self.emit(event);
+	}
+
+	fn handle_global_shortcut(&mut self, payload: GlobalShortcutPayload) {
+		let event = InputEvent::GlobalShortcut {
+			seat: payload.seat,
+			shortcut_id: payload.shortcut_id,
+			time_usec: payload.time_usec,
+		};
+		self.emit(event);
+	}
+
+	fn handle_global_shortcut_conflict(&mut self, shortcut_id: String) {
+		let event = InputEvent::GlobalShortcutConflict { shortcut_id };
+		self.emit(event);
+	}
+
+	fn handle_keyboard_grab_unavailable(&mut self) {
+		let event = InputEvent::KeyboardGrabUnavailable;
+		self.emit(event);
+	}
+
+	fn handle_input_device_added(&mut self, info: InputDeviceInfo) {
+		self.input_devices.insert(info.id, info.clone());
+		let event = InputEvent::DeviceAdded(info);
+		self.emit(event);
+	}
+
+	fn handle_input_device_removed(&mut self, id: u32) {
+		let Some(info) = self.input_devices.remove(&id) else {
+			return;
+		};
+		let event = InputEvent::DeviceRemoved(info);
+		self.emit(event);
+	}
+
 	fn wait_for_buffer_request_ack(
 		&mut self,
 		monitor_id: &str,
 		buffer: BufferIndex,
 	) -> Result<(), TabClientError> {
-		let deadline = Instant::now() + Self::BUFFER_REQUEST_ACK_TIMEOUT;
+		let deadline = Instant::now() + self.buffer_request_ack_timeout;
 		loop {
 			if Instant::now() >= deadline {
-				return Err(TabClientError::Unexpected("buffer_request_ack timeout"));
+				return Err(TabClientError::AckTimeout(monitor_id.to_string()));
 			}
 			match self.reader.read_framed(&self.socket) {
 				Ok(frame) => {
@@ -393,12 +1446,12 @@ impl TabClient {
 								return Ok(());
 							}
 						}
-						TabMessage::Error(err) => {
-							let details = err
-								.message
-								.map(|m| format!("{}: {m}", err.code))
-								.unwrap_or(err.code);
-							return Err(TabClientError::Server(details));
+						TabMessage::Error(err)
+							if err.buffer_request.as_ref().is_some_and(|request| {
+								request.monitor_id == monitor_id && request.buffer == buffer
+							}) =>
+						{
+							return Err(TabClientError::Server(err.into()));
 						}
 						other => self.handle_message(other)?,
 					}
@@ -421,16 +1474,12 @@ impl TabClient {
 				Ok(frame) => {
 					let message = TabMessage::try_from(frame)?;
 					match message {
-						TabMessage::SessionCreated(payload) => {
-							self.handle_session_created(payload.session.clone(), payload.token.clone());
+						TabMessage::SessionCreated(payload) if payload.request_id.is_none() => {
+							self.handle_session_created(payload.clone());
 							return Ok(payload);
 						}
-						TabMessage::Error(err) => {
-							let details = err
-								.message
-								.map(|m| format!("{}: {m}", err.code))
-								.unwrap_or(err.code);
-							return Err(TabClientError::Server(details));
+						TabMessage::Error(err) if err.request_id.is_none() && err.buffer_request.is_none() => {
+							return Err(TabClientError::Server(err.into()));
 						}
 						other => self.handle_message(other)?,
 					}
@@ -468,3 +1517,8 @@ impl TabClient {
 		}
 	}
 }
+
+/// Duplicates `fd` for an event receiver, which owns the copy.
+fn dup_raw_fd(fd: &OwnedFd) -> Option<RawFd> {
+	fd.try_clone().ok().map(IntoRawFd::into_raw_fd)
+}
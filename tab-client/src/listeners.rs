@@ -0,0 +1,51 @@
+/// Handle of a listener added with one of the `TabClient::on_*_event` methods.
+///
+/// Pass it to [`crate::TabClient::remove_listener`] to stop receiving events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ListenerId(u64);
+
+type Listener<E> = Box<dyn FnMut(&E)>;
+
+/// Listeners of one event kind, in the order they were added.
+pub(crate) struct Listeners<E> {
+	entries: Vec<(ListenerId, Listener<E>)>,
+}
+
+impl<E> Default for Listeners<E> {
+	fn default() -> Self {
+		Self {
+			entries: Vec::new(),
+		}
+	}
+}
+
+impl<E> Listeners<E> {
+	pub(crate) fn add(&mut self, id: u64, listener: Listener<E>) -> ListenerId {
+		let id = ListenerId(id);
+		self.entries.push((id, listener));
+		id
+	}
+
+	/// Returns true when `id` was one of these listeners.
+	pub(crate) fn remove(&mut self, id: ListenerId) -> bool {
+		let before = self.entries.len();
+		self.entries.retain(|(entry, _)| *entry != id);
+		self.entries.len() != before
+	}
+
+	pub(crate) fn emit(&mut self, event: &E) {
+		for (_, listener) in &mut self.entries {
+			listener(event);
+		}
+	}
+
+	/// Builds an event per listener, for events whose fds every receiver must own; listeners
+	/// get nothing when `make` returns `None`.
+	pub(crate) fn emit_each(&mut self, mut make: impl FnMut() -> Option<E>) {
+		for (_, listener) in &mut self.entries {
+			if let Some(event) = make() {
+				listener(&event);
+			}
+		}
+	}
+}
@@ -0,0 +1,101 @@
+use std::ffi::CStr;
+use std::os::fd::{FromRawFd, OwnedFd};
+
+use tab_protocol::BufferIndex;
+
+use crate::{
+	allocator::Allocator,
+	error::TabClientError,
+	monitor::MonitorState,
+	swapchain::{TabBuffer, TabSwapchain},
+};
+
+/// DRM_FORMAT_XRGB8888.
+const FOURCC_XRGB8888: i32 = 0x3432_5258;
+/// DRM_FORMAT_ARGB8888.
+const FOURCC_ARGB8888: i32 = 0x3432_5241;
+
+/// Allocates swapchain buffers in anonymous shared memory instead of GBM.
+///
+/// For clients without a render node, e.g. in headless containers, and for tests. The
+/// buffers are plain memory and cannot be imported by EGL; the server uploads them
+/// instead, which suits small or rarely updated surfaces.
+#[derive(Debug, Default)]
+pub struct MemfdAllocator;
+
+impl MemfdAllocator {
+	pub fn new() -> Self {
+		Self
+	}
+
+	pub fn create_swapchain(&self, monitor: &MonitorState) -> Result<TabSwapchain, TabClientError> {
+		if monitor.info.width <= 0 || monitor.info.height <= 0 {
+			return Err(TabClientError::InvalidMonitorDimensions);
+		}
+		let (width, height) = (monitor.info.width, monitor.info.height);
+		let buffers = [
+			self.create_buffer(BufferIndex::Zero, width, height, FOURCC_XRGB8888)?,
+			self.create_buffer(BufferIndex::One, width, height, FOURCC_XRGB8888)?,
+		];
+		Ok(TabSwapchain::new(monitor.info.id.clone(), buffers))
+	}
+
+	/// Allocates a swapchain with an alpha channel for an overlay surface.
+	pub fn create_overlay_swapchain(
+		&self,
+		overlay_id: &str,
+		width: i32,
+		height: i32,
+	) -> Result<TabSwapchain, TabClientError> {
+		if width <= 0 || height <= 0 {
+			return Err(TabClientError::InvalidOverlayDimensions);
+		}
+		let buffers = [
+			self.create_buffer(BufferIndex::Zero, width, height, FOURCC_ARGB8888)?,
+			self.create_buffer(BufferIndex::One, width, height, FOURCC_ARGB8888)?,
+		];
+		Ok(TabSwapchain::new(overlay_id, buffers))
+	}
+
+	fn create_buffer(
+		&self,
+		index: BufferIndex,
+		width: i32,
+		height: i32,
+		fourcc: i32,
+	) -> Result<TabBuffer, TabClientError> {
+		const NAME: &CStr = c"tab-client-buffer";
+		let stride = width * 4;
+		let raw =
+			unsafe { libc::memfd_create(NAME.as_ptr(), libc::MFD_CLOEXEC | libc::MFD_ALLOW_SEALING) };
+		if raw < 0 {
+			return Err(std::io::Error::last_os_error().into());
+		}
+		let fd = unsafe { OwnedFd::from_raw_fd(raw) };
+		let len = stride as libc::off_t * height as libc::off_t;
+		if unsafe { libc::ftruncate(raw, len) } < 0 {
+			return Err(std::io::Error::last_os_error().into());
+		}
+		// The server maps the buffer once and only accepts memfds that cannot change size.
+		let seals = libc::F_SEAL_SHRINK | libc::F_SEAL_GROW | libc::F_SEAL_SEAL;
+		if unsafe { libc::fcntl(raw, libc::F_ADD_SEALS, seals) } < 0 {
+			return Err(std::io::Error::last_os_error().into());
+		}
+		Ok(TabBuffer::from_fd(index, fd, width, height, stride, 0, fourcc).with_shm_memory())
+	}
+}
+
+impl Allocator for MemfdAllocator {
+	fn create_swapchain(&self, monitor: &MonitorState) -> Result<TabSwapchain, TabClientError> {
+		MemfdAllocator::create_swapchain(self, monitor)
+	}
+
+	fn create_overlay_swapchain(
+		&self,
+		overlay_id: &str,
+		width: i32,
+		height: i32,
+	) -> Result<TabSwapchain, TabClientError> {
+		MemfdAllocator::create_overlay_swapchain(self, overlay_id, width, height)
+	}
+}
@@ -1,51 +1,153 @@
-use std::os::fd::{AsRawFd, OwnedFd, RawFd};
+use std::os::fd::{AsFd, AsRawFd, BorrowedFd, OwnedFd, RawFd};
 
 use gbm::BufferObject;
-use tab_protocol::{BufferIndex, FramebufferLinkPayload};
+use tab_protocol::{BufferIndex, BufferMemory, FramebufferLinkPayload};
 
 /// Metadata describing a DMA-BUF-backed buffer.
+///
+/// The buffer owns its fd and keeps it open for its whole lifetime; [`TabBuffer::fd`]
+/// borrows it, and [`TabBuffer::try_clone_fd`] duplicates it for importers that take
+/// ownership, e.g. Vulkan external memory. Metadata never changes after allocation.
 #[derive(Debug)]
 pub struct TabBuffer {
 	pub index: BufferIndex,
-	bo: BufferObject<()>,
+	_bo: Option<BufferObject<()>>,
 	fd: OwnedFd,
+	width: i32,
+	height: i32,
+	stride: i32,
+	offset: i32,
+	fourcc: i32,
+	modifier: u64,
+	memory: BufferMemory,
 }
 
 impl TabBuffer {
+	/// `DRM_FORMAT_MOD_INVALID`: the layout is implied by the driver.
+	pub const MODIFIER_INVALID: u64 = 0x00ff_ffff_ffff_ffff;
+
 	pub fn new(index: BufferIndex, bo: BufferObject<()>) -> Self {
 		Self {
 			index,
 			fd: bo.fd().unwrap(),
-			bo,
+			width: bo.width() as i32,
+			height: bo.height() as i32,
+			stride: bo.stride() as i32,
+			offset: bo.offset(0) as i32,
+			fourcc: bo.format() as u32 as i32,
+			modifier: u64::from(bo.modifier()),
+			memory: BufferMemory::Dmabuf,
+			_bo: Some(bo),
 		}
 	}
 
+	/// Wraps an already-allocated single-plane buffer fd.
+	///
+	/// The modifier is [`TabBuffer::MODIFIER_INVALID`] unless set with
+	/// [`TabBuffer::with_modifier`].
+	pub fn from_fd(
+		index: BufferIndex,
+		fd: OwnedFd,
+		width: i32,
+		height: i32,
+		stride: i32,
+		offset: i32,
+		fourcc: i32,
+	) -> Self {
+		Self {
+			index,
+			_bo: None,
+			fd,
+			width,
+			height,
+			stride,
+			offset,
+			fourcc,
+			modifier: Self::MODIFIER_INVALID,
+			memory: BufferMemory::Dmabuf,
+		}
+	}
+
+	/// Records the DRM format modifier the buffer was allocated with.
+	pub fn with_modifier(mut self, modifier: u64) -> Self {
+		self.modifier = modifier;
+		self
+	}
+
+	/// Marks the fd as plain shared memory rather than a dmabuf.
+	pub fn with_shm_memory(mut self) -> Self {
+		self.memory = BufferMemory::Shm;
+		self
+	}
+
 	pub fn width(&self) -> i32 {
-		self.bo.width() as i32
+		self.width
 	}
 
 	pub fn height(&self) -> i32 {
-		self.bo.height() as i32
+		self.height
 	}
 
 	pub fn stride(&self) -> i32 {
-		self.bo.stride() as i32
+		self.stride
 	}
 
 	pub fn offset(&self) -> i32 {
-		self.bo.offset(0) as i32
+		self.offset
 	}
 
 	pub fn fourcc(&self) -> i32 {
-		self.bo.format() as u32 as i32
+		self.fourcc
+	}
+
+	/// DRM format modifier, [`TabBuffer::MODIFIER_INVALID`] when unknown.
+	pub fn modifier(&self) -> u64 {
+		self.modifier
 	}
 
+	/// Borrowed fd, open as long as the buffer exists.
 	pub fn fd(&self) -> RawFd {
 		self.fd.as_raw_fd()
 	}
+
+	/// Duplicates the fd; the duplicate keeps the memory alive after the swapchain is gone.
+	pub fn try_clone_fd(&self) -> std::io::Result<OwnedFd> {
+		self.fd.try_clone()
+	}
+
+	/// Duplicates the fd into a buffer with the same metadata and no GBM object.
+	pub fn try_clone(&self) -> std::io::Result<Self> {
+		Ok(Self {
+			index: self.index,
+			_bo: None,
+			fd: self.fd.try_clone()?,
+			width: self.width,
+			height: self.height,
+			stride: self.stride,
+			offset: self.offset,
+			fourcc: self.fourcc,
+			modifier: self.modifier,
+			memory: self.memory,
+		})
+	}
+
+	pub fn memory(&self) -> BufferMemory {
+		self.memory
+	}
+}
+
+impl AsFd for TabBuffer {
+	fn as_fd(&self) -> BorrowedFd<'_> {
+		self.fd.as_fd()
+	}
 }
 
 /// Double-buffer swapchain model.
+///
+/// Both buffers are allocated up front and stay the same until the swapchain is dropped,
+/// so external renderers can import them once through [`TabSwapchain::buffers`]. The
+/// client may only write a buffer between acquiring it and submitting it; afterwards the
+/// server reads it until it is released.
 #[derive(Debug)]
 pub struct TabSwapchain {
 	pub monitor_id: String,
@@ -53,6 +155,7 @@ pub struct TabSwapchain {
 	current: BufferIndex,
 	last_acquired: Option<BufferIndex>,
 	busy: [bool; 2],
+	ages: [u32; 2],
 }
 
 impl TabSwapchain {
@@ -63,6 +166,7 @@ impl TabSwapchain {
 			current: BufferIndex::Zero,
 			last_acquired: None,
 			busy: [false, false],
+			ages: [0, 0],
 		}
 	}
 
@@ -88,6 +192,21 @@ impl TabSwapchain {
 		}
 	}
 
+	/// Returns both buffers, indexed by [`BufferIndex`].
+	pub fn buffers(&self) -> &[TabBuffer] {
+		&self.buffers
+	}
+
+	/// Returns the buffer at `idx`.
+	pub fn buffer(&self, idx: BufferIndex) -> &TabBuffer {
+		&self.buffers[idx as usize]
+	}
+
+	/// Buffer dimensions in pixels; both buffers share them.
+	pub fn size(&self) -> (i32, i32) {
+		(self.buffers[0].width(), self.buffers[0].height())
+	}
+
 	pub fn current(&self) -> (&TabBuffer, BufferIndex) {
 		(&self.buffers[self.current as usize], self.current)
 	}
@@ -101,6 +220,31 @@ impl TabSwapchain {
 		self.busy[idx as usize] = false;
 	}
 
+	/// Returns true while `idx` is submitted and not yet released by the server.
+	pub fn is_busy(&self, idx: BufferIndex) -> bool {
+		self.busy[idx as usize]
+	}
+
+	/// Frames since the buffer's contents were submitted, as in `EGL_EXT_buffer_age`.
+	///
+	/// 1 means the buffer holds the previous frame; 0 means its contents are undefined.
+	pub fn buffer_age(&self, idx: BufferIndex) -> u32 {
+		self.ages[idx as usize]
+	}
+
+	/// Records that `idx` was submitted as the newest frame, aging the other buffer.
+	pub fn mark_submitted(&mut self, idx: BufferIndex) {
+		for age in self.ages.iter_mut().filter(|age| **age > 0) {
+			*age = age.saturating_add(1);
+		}
+		self.ages[idx as usize] = 1;
+	}
+
+	/// Forgets the contents of `idx`, e.g. after the server rejected it.
+	pub fn invalidate_contents(&mut self, idx: BufferIndex) {
+		self.ages[idx as usize] = 0;
+	}
+
 	pub fn framebuffer_link_payload(&self) -> FramebufferLinkPayload {
 		let buffer = &self.buffers[0];
 		FramebufferLinkPayload {
@@ -110,6 +254,7 @@ impl TabSwapchain {
 			stride: buffer.stride(),
 			offset: buffer.offset(),
 			fourcc: buffer.fourcc(),
+			memory: buffer.memory(),
 		}
 	}
 
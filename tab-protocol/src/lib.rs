@@ -5,6 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::{
+	collections::BTreeMap,
 	os::fd::{FromRawFd, OwnedFd},
 	str::FromStr,
 	time::Duration,
@@ -14,6 +15,8 @@ pub mod message_frame;
 pub mod unix_socket_utils;
 /// Default Unix domain socket for Tab connections.
 pub const DEFAULT_SOCKET_PATH: &str = "/tmp/shift.sock";
+/// Logical seat name used when the input backend does not report one.
+pub const DEFAULT_SEAT: &str = "default";
 /// Protocol identifier string expected in `hello` payloads. Used to check if the client and server are compatible.
 pub const PROTOCOL_VERSION: &str = const_str::concat!("tab/v", env!("CARGO_PKG_VERSION"));
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -53,9 +56,15 @@ pub enum TabMessage {
 		payload: BufferReleasePayload,
 		release_fence: Option<OwnedFd>,
 	},
-	InputEvent(InputEventPayload),
+	InputEvent(SeatInputEventPayload),
+	InputInject(InputInjectPayload),
+	InputDeviceAdded(InputDeviceAddedPayload),
+	InputDeviceRemoved(InputDeviceRemovedPayload),
 	MonitorAdded(MonitorAddedPayload),
 	MonitorRemoved(MonitorRemovedPayload),
+	MonitorChanged(MonitorChangedPayload),
+	MonitorModeSet(MonitorModeSetPayload),
+	MonitorPrimarySet(MonitorPrimarySetPayload),
 	SessionSwitch(SessionSwitchPayload),
 	SessionCreate(SessionCreatePayload),
 	SessionCreated(SessionCreatedPayload),
@@ -64,6 +73,55 @@ pub enum TabMessage {
 	SessionActive(SessionActivePayload),
 	SessionAwake(SessionAwakePayload),
 	SessionSleep(SessionSleepPayload),
+	SessionUpdate(SessionUpdatePayload),
+	SessionPreviewRequest(SessionPreviewRequestPayload),
+	SessionPreview {
+		payload: SessionPreviewPayload,
+		pixels: OwnedFd,
+	},
+	IdleInhibit(IdleInhibitPayload),
+	GlobalShortcutRegister(GlobalShortcutRegisterPayload),
+	GlobalShortcutUnregister(GlobalShortcutUnregisterPayload),
+	GlobalShortcut(GlobalShortcutPayload),
+	SessionMessageSend(SessionMessageSendPayload),
+	SessionMessage(SessionMessagePayload),
+	CrashReport(CrashReportPayload),
+	SessionCrashed(SessionCrashedPayload),
+	KeyboardGrab(KeyboardGrabPayload),
+	MonitorBrightness(MonitorBrightnessPayload),
+	MonitorGamma(MonitorGammaPayload),
+	MonitorPower(MonitorPowerPayload),
+	Accessibility(AccessibilityPayload),
+	ScanoutHint(ScanoutHintPayload),
+	SurfaceColorspace(SurfaceColorspacePayload),
+	SafeArea(SafeAreaPayload),
+	OverlayCreate {
+		payload: OverlayCreatePayload,
+		dma_bufs: [OwnedFd; 2],
+	},
+	OverlayMove(OverlayMovePayload),
+	OverlayPresent {
+		payload: OverlayPresentPayload,
+		acquire_fence: Option<OwnedFd>,
+	},
+	OverlayRelease {
+		payload: OverlayReleasePayload,
+		release_fence: Option<OwnedFd>,
+	},
+	OverlayDestroy(OverlayDestroyPayload),
+	CaptureStart {
+		payload: CaptureStartPayload,
+		dma_bufs: [OwnedFd; 2],
+	},
+	CaptureStop(CaptureStopPayload),
+	CaptureFrame {
+		payload: CaptureFramePayload,
+		ready_fence: Option<OwnedFd>,
+	},
+	CaptureRelease(CaptureReleasePayload),
+	CaptureEnded(CaptureEndedPayload),
+	ClockSyncRequest(ClockSyncRequestPayload),
+	ClockSync(ClockSyncPayload),
 	Error(ErrorPayload),
 	Ping,
 	Pong,
@@ -186,9 +244,21 @@ impl TabMessage {
 				})
 			}
 			message_header::INPUT_EVENT => {
-				let payload: InputEventPayload = msg.expect_payload_json()?;
+				let payload: SeatInputEventPayload = msg.expect_payload_json()?;
 				Ok(TabMessage::InputEvent(payload))
 			}
+			message_header::INPUT_INJECT => {
+				let payload: InputInjectPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::InputInject(payload))
+			}
+			message_header::INPUT_DEVICE_ADDED => {
+				let payload: InputDeviceAddedPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::InputDeviceAdded(payload))
+			}
+			message_header::INPUT_DEVICE_REMOVED => {
+				let payload: InputDeviceRemovedPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::InputDeviceRemoved(payload))
+			}
 			message_header::MONITOR_ADDED => {
 				let payload: MonitorAddedPayload = msg.expect_payload_json()?;
 				Ok(TabMessage::MonitorAdded(payload))
@@ -197,6 +267,18 @@ impl TabMessage {
 				let payload: MonitorRemovedPayload = msg.expect_payload_json()?;
 				Ok(TabMessage::MonitorRemoved(payload))
 			}
+			message_header::MONITOR_CHANGED => {
+				let payload: MonitorChangedPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::MonitorChanged(payload))
+			}
+			message_header::MONITOR_MODE_SET => {
+				let payload: MonitorModeSetPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::MonitorModeSet(payload))
+			}
+			message_header::MONITOR_PRIMARY_SET => {
+				let payload: MonitorPrimarySetPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::MonitorPrimarySet(payload))
+			}
 			message_header::SESSION_SWITCH => {
 				let payload: SessionSwitchPayload = msg.expect_payload_json()?;
 				Ok(TabMessage::SessionSwitch(payload))
@@ -229,6 +311,158 @@ impl TabMessage {
 				let payload: SessionSleepPayload = msg.expect_payload_json()?;
 				Ok(TabMessage::SessionSleep(payload))
 			}
+			message_header::SESSION_UPDATE => {
+				let payload: SessionUpdatePayload = msg.expect_payload_json()?;
+				Ok(TabMessage::SessionUpdate(payload))
+			}
+			message_header::SESSION_PREVIEW_REQUEST => {
+				let payload: SessionPreviewRequestPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::SessionPreviewRequest(payload))
+			}
+			message_header::SESSION_PREVIEW => {
+				let payload: SessionPreviewPayload = msg.expect_payload_json()?;
+				msg.expect_n_fds(1)?;
+				let pixels = unsafe { OwnedFd::from_raw_fd(msg.fds[0]) };
+				Ok(TabMessage::SessionPreview { payload, pixels })
+			}
+			message_header::IDLE_INHIBIT => {
+				let payload: IdleInhibitPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::IdleInhibit(payload))
+			}
+			message_header::GLOBAL_SHORTCUT_REGISTER => {
+				let payload: GlobalShortcutRegisterPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::GlobalShortcutRegister(payload))
+			}
+			message_header::GLOBAL_SHORTCUT_UNREGISTER => {
+				let payload: GlobalShortcutUnregisterPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::GlobalShortcutUnregister(payload))
+			}
+			message_header::GLOBAL_SHORTCUT => {
+				let payload: GlobalShortcutPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::GlobalShortcut(payload))
+			}
+			message_header::SESSION_MESSAGE_SEND => {
+				let payload: SessionMessageSendPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::SessionMessageSend(payload))
+			}
+			message_header::SESSION_MESSAGE => {
+				let payload: SessionMessagePayload = msg.expect_payload_json()?;
+				Ok(TabMessage::SessionMessage(payload))
+			}
+			message_header::CRASH_REPORT => {
+				let payload: CrashReportPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::CrashReport(payload))
+			}
+			message_header::SESSION_CRASHED => {
+				let payload: SessionCrashedPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::SessionCrashed(payload))
+			}
+			message_header::KEYBOARD_GRAB => {
+				let payload: KeyboardGrabPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::KeyboardGrab(payload))
+			}
+			message_header::MONITOR_BRIGHTNESS => {
+				let payload: MonitorBrightnessPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::MonitorBrightness(payload))
+			}
+			message_header::MONITOR_GAMMA => {
+				let payload: MonitorGammaPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::MonitorGamma(payload))
+			}
+			message_header::MONITOR_POWER => {
+				let payload: MonitorPowerPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::MonitorPower(payload))
+			}
+			message_header::ACCESSIBILITY => {
+				let payload: AccessibilityPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::Accessibility(payload))
+			}
+			message_header::SCANOUT_HINT => {
+				let payload: ScanoutHintPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::ScanoutHint(payload))
+			}
+			message_header::SURFACE_COLORSPACE => {
+				let payload: SurfaceColorspacePayload = msg.expect_payload_json()?;
+				Ok(TabMessage::SurfaceColorspace(payload))
+			}
+			message_header::SAFE_AREA => {
+				let payload: SafeAreaPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::SafeArea(payload))
+			}
+			message_header::OVERLAY_CREATE => {
+				let payload: OverlayCreatePayload = msg.expect_payload_json()?;
+				msg.expect_n_fds(2)?;
+				let dma_bufs = unsafe {
+					[
+						OwnedFd::from_raw_fd(msg.fds[0]),
+						OwnedFd::from_raw_fd(msg.fds[1]),
+					]
+				};
+				Ok(TabMessage::OverlayCreate { payload, dma_bufs })
+			}
+			message_header::OVERLAY_MOVE => {
+				let payload: OverlayMovePayload = msg.expect_payload_json()?;
+				Ok(TabMessage::OverlayMove(payload))
+			}
+			message_header::OVERLAY_PRESENT => {
+				let payload: OverlayPresentPayload = msg.expect_payload_json()?;
+				let acquire_fence = take_optional_fence(&msg)?;
+				Ok(TabMessage::OverlayPresent {
+					payload,
+					acquire_fence,
+				})
+			}
+			message_header::OVERLAY_RELEASE => {
+				let payload: OverlayReleasePayload = msg.expect_payload_json()?;
+				let release_fence = take_optional_fence(&msg)?;
+				Ok(TabMessage::OverlayRelease {
+					payload,
+					release_fence,
+				})
+			}
+			message_header::OVERLAY_DESTROY => {
+				let payload: OverlayDestroyPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::OverlayDestroy(payload))
+			}
+			message_header::CAPTURE_START => {
+				let payload: CaptureStartPayload = msg.expect_payload_json()?;
+				msg.expect_n_fds(2)?;
+				let dma_bufs = unsafe {
+					[
+						OwnedFd::from_raw_fd(msg.fds[0]),
+						OwnedFd::from_raw_fd(msg.fds[1]),
+					]
+				};
+				Ok(TabMessage::CaptureStart { payload, dma_bufs })
+			}
+			message_header::CAPTURE_STOP => {
+				let payload: CaptureStopPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::CaptureStop(payload))
+			}
+			message_header::CAPTURE_FRAME => {
+				let payload: CaptureFramePayload = msg.expect_payload_json()?;
+				let ready_fence = take_optional_fence(&msg)?;
+				Ok(TabMessage::CaptureFrame {
+					payload,
+					ready_fence,
+				})
+			}
+			message_header::CAPTURE_RELEASE => {
+				let payload: CaptureReleasePayload = msg.expect_payload_json()?;
+				Ok(TabMessage::CaptureRelease(payload))
+			}
+			message_header::CAPTURE_ENDED => {
+				let payload: CaptureEndedPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::CaptureEnded(payload))
+			}
+			message_header::CLOCK_SYNC_REQUEST => {
+				let payload: ClockSyncRequestPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::ClockSyncRequest(payload))
+			}
+			message_header::CLOCK_SYNC => {
+				let payload: ClockSyncPayload = msg.expect_payload_json()?;
+				Ok(TabMessage::ClockSync(payload))
+			}
 			message_header::ERROR => {
 				let payload: ErrorPayload = msg.expect_payload_json()?;
 				Ok(TabMessage::Error(payload))
@@ -239,11 +473,40 @@ impl TabMessage {
 		}
 	}
 }
+/// Takes ownership of the single fence fd a frame may carry.
+fn take_optional_fence(msg: &TabMessageFrame) -> Result<Option<OwnedFd>, ProtocolError> {
+	match msg.fds.len() {
+		0 => Ok(None),
+		1 => Ok(Some(unsafe { OwnedFd::from_raw_fd(msg.fds[0]) })),
+		found => Err(ProtocolError::ExpectedFds {
+			expected: 1,
+			found: found as u32,
+		}),
+	}
+}
 /// Typed payloads
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HelloPayload {
 	pub server: String,
 	pub protocol: String,
+	/// GPU the server composites on; clients should allocate buffers on the same device.
+	#[serde(default)]
+	pub drm_device: Option<DrmDeviceInfo>,
+}
+
+/// Identifies a DRM device independently of node naming.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DrmDeviceInfo {
+	/// Major number of the device's render node (or primary node without one).
+	pub major: u32,
+	/// Minor number of the device's render node (or primary node without one).
+	pub minor: u32,
+	/// Render node path as seen by the server, e.g. `/dev/dri/renderD129`.
+	#[serde(default)]
+	pub render_node: Option<String>,
+	/// PCI `vendor:device` id, e.g. `8086:46a6`, for devices on a PCI bus.
+	#[serde(default)]
+	pub pci_id: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -251,13 +514,73 @@ pub struct AuthPayload {
 	pub token: String,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MonitorInfo {
 	pub id: String,
 	pub width: i32,
 	pub height: i32,
 	pub refresh_rate: i32,
 	pub name: String,
+	/// Modes the monitor can be switched to, including the current one.
+	#[serde(default)]
+	pub modes: Vec<MonitorMode>,
+	/// Color characteristics from the monitor's EDID, if the server could read it.
+	#[serde(default)]
+	pub color: Option<MonitorColorInfo>,
+	/// Physical pixels per logical pixel; may be fractional. `width` and `height` stay in
+	/// physical pixels.
+	#[serde(default = "default_monitor_scale")]
+	pub scale: f64,
+	/// Whether an admin session made this the primary monitor; at most one is.
+	#[serde(default)]
+	pub primary: bool,
+}
+
+fn default_monitor_scale() -> f64 {
+	1.0
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MonitorMode {
+	pub width: i32,
+	pub height: i32,
+	pub refresh_rate: i32,
+}
+
+/// CIE 1931 xy chromaticity coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Chromaticity {
+	pub x: f32,
+	pub y: f32,
+}
+
+/// Color characteristics a monitor reports in its EDID.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorColorInfo {
+	pub red: Chromaticity,
+	pub green: Chromaticity,
+	pub blue: Chromaticity,
+	pub white: Chromaticity,
+	/// Display gamma, e.g. `2.2`; `None` when the EDID leaves it undefined.
+	pub gamma: Option<f32>,
+	/// Colorspaces the monitor accepts besides sRGB, from its CTA-861 extension.
+	#[serde(default)]
+	pub colorspaces: Vec<Colorspace>,
+}
+
+/// Color space of a session's frames, which the server converts for display.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Colorspace {
+	/// sRGB primaries and transfer function; untagged frames are sRGB.
+	#[default]
+	Srgb,
+	/// DCI-P3 primaries with a D65 white point and the sRGB transfer function.
+	DisplayP3,
+	/// BT.2020 primaries with the PQ (SMPTE ST 2084) transfer function, as used by HDR10.
+	Bt2020Pq,
+	/// BT.2020 primaries with the hybrid log-gamma transfer function.
+	Bt2020Hlg,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -266,6 +589,9 @@ pub struct SessionInfo {
 	pub role: SessionRole,
 	pub display_name: Option<String>,
 	pub state: SessionLifecycle,
+	/// Free-form key/values the session attached to itself, e.g. for switcher UIs.
+	#[serde(default)]
+	pub metadata: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -284,10 +610,12 @@ pub enum SessionRole {
 	Session,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AuthOkPayload {
 	pub session: SessionInfo,
 	pub monitors: Vec<MonitorInfo>,
+	#[serde(default)]
+	pub input_devices: Vec<InputDeviceInfo>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -295,6 +623,22 @@ pub struct AuthErrorPayload {
 	pub error: String,
 }
 
+/// Kind of memory backing linked buffers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BufferMemory {
+	/// GPU buffers shared as dmabufs and sampled directly.
+	#[default]
+	Dmabuf,
+	/// Shared memory in `XRGB8888` or `ARGB8888`, for clients without GPU access; the
+	/// server uploads a buffer's pixels whenever it is submitted.
+	///
+	/// Buffers must be memfds sealed with `F_SEAL_SHRINK` and `F_SEAL_GROW`, no larger than
+	/// the monitor's current mode.
+	Shm,
+}
+
+/// Links the session's two buffers for a monitor; sent with their two fds.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FramebufferLinkPayload {
 	pub monitor_id: String,
@@ -303,9 +647,11 @@ pub struct FramebufferLinkPayload {
 	pub stride: i32,
 	pub offset: i32,
 	pub fourcc: i32,
+	#[serde(default)]
+	pub memory: BufferMemory,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BufferRequestPayload {
 	pub monitor_id: String,
 	pub buffer: BufferIndex,
@@ -322,6 +668,47 @@ pub struct BufferReleasePayload {
 	pub monitor_id: String,
 	pub buffer: BufferIndex,
 }
+/// Logical input seat; devices on different seats drive independent cursors.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SeatId(pub String);
+
+impl Default for SeatId {
+	fn default() -> Self {
+		Self(DEFAULT_SEAT.into())
+	}
+}
+
+impl std::fmt::Display for SeatId {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.0)
+	}
+}
+
+/// `input_event` payload: the event plus the seat its device belongs to.
+///
+/// `seat` is serialized next to the event's `kind` tag and defaults to
+/// [`DEFAULT_SEAT`] when absent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeatInputEventPayload {
+	#[serde(default)]
+	pub seat: SeatId,
+	#[serde(flatten)]
+	pub event: InputEventPayload,
+}
+
+/// Admin-only: delivers `input` to the clients of `session_id` as an `input_event`, as if
+/// it came from a device of `input.seat`. Unknown sessions are rejected with
+/// `unknown_session`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputInjectPayload {
+	pub session_id: String,
+	pub input: SeatInputEventPayload,
+	/// Id echoed in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum InputEventPayload {
@@ -489,6 +876,71 @@ pub enum InputEventPayload {
 	},
 }
 
+impl InputEventPayload {
+	/// Server timestamp of the event in microseconds.
+	pub fn time_usec(&self) -> u64 {
+		match self {
+			Self::PointerMotion { time_usec, .. }
+			| Self::PointerMotionAbsolute { time_usec, .. }
+			| Self::PointerButton { time_usec, .. }
+			| Self::PointerAxis { time_usec, .. }
+			| Self::Key { time_usec, .. }
+			| Self::TouchDown { time_usec, .. }
+			| Self::TouchUp { time_usec, .. }
+			| Self::TouchMotion { time_usec, .. }
+			| Self::TouchFrame { time_usec }
+			| Self::TouchCancel { time_usec }
+			| Self::TableToolProximity { time_usec, .. }
+			| Self::TabletToolAxis { time_usec, .. }
+			| Self::TabletToolTip { time_usec, .. }
+			| Self::TabletToolButton { time_usec, .. }
+			| Self::TablePadButton { time_usec, .. }
+			| Self::TablePadRing { time_usec, .. }
+			| Self::TablePadStrip { time_usec, .. }
+			| Self::SwitchToggle { time_usec, .. }
+			| Self::GestureSwipeBegin { time_usec, .. }
+			| Self::GestureSwipeUpdate { time_usec, .. }
+			| Self::GestureSwipeEnd { time_usec, .. }
+			| Self::GesturePinchBegin { time_usec, .. }
+			| Self::GesturePinchUpdate { time_usec, .. }
+			| Self::GesturePinchEnd { time_usec, .. }
+			| Self::GestureHoldBegin { time_usec, .. }
+			| Self::GestureHoldEnd { time_usec, .. } => *time_usec,
+		}
+	}
+
+	/// Device that produced the event, if the event carries one.
+	pub fn device(&self) -> Option<u32> {
+		match self {
+			Self::PointerMotion { device, .. }
+			| Self::PointerMotionAbsolute { device, .. }
+			| Self::PointerButton { device, .. }
+			| Self::PointerAxis { device, .. }
+			| Self::Key { device, .. }
+			| Self::TouchDown { device, .. }
+			| Self::TouchUp { device, .. }
+			| Self::TouchMotion { device, .. }
+			| Self::TableToolProximity { device, .. }
+			| Self::TabletToolAxis { device, .. }
+			| Self::TabletToolTip { device, .. }
+			| Self::TabletToolButton { device, .. }
+			| Self::TablePadButton { device, .. }
+			| Self::TablePadRing { device, .. }
+			| Self::TablePadStrip { device, .. }
+			| Self::SwitchToggle { device, .. }
+			| Self::GestureSwipeBegin { device, .. }
+			| Self::GestureSwipeUpdate { device, .. }
+			| Self::GestureSwipeEnd { device, .. }
+			| Self::GesturePinchBegin { device, .. }
+			| Self::GesturePinchUpdate { device, .. }
+			| Self::GesturePinchEnd { device, .. }
+			| Self::GestureHoldBegin { device, .. }
+			| Self::GestureHoldEnd { device, .. } => Some(*device),
+			Self::TouchFrame { .. } | Self::TouchCancel { .. } => None,
+		}
+	}
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ButtonState {
 	Pressed,
@@ -585,6 +1037,47 @@ pub enum SwitchState {
 	Off,
 }
 
+/// Capability advertised by an input device.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputDeviceCapability {
+	Keyboard,
+	Pointer,
+	Touch,
+	TabletTool,
+	TabletPad,
+	Gesture,
+	Switch,
+}
+
+/// Input device known to the server, keyed by the `device` id carried in input events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputDeviceInfo {
+	pub id: u32,
+	pub name: String,
+	#[serde(default)]
+	pub seat: SeatId,
+	pub capabilities: Vec<InputDeviceCapability>,
+	/// Connector name of the monitor the device is mapped to (touchscreens, tablets).
+	pub output_name: Option<String>,
+}
+
+impl InputDeviceInfo {
+	pub fn has_capability(&self, capability: InputDeviceCapability) -> bool {
+		self.capabilities.contains(&capability)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputDeviceAddedPayload {
+	pub device: InputDeviceInfo,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputDeviceRemovedPayload {
+	pub device: u32,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MonitorAddedPayload {
 	pub monitor: MonitorInfo,
@@ -596,23 +1089,59 @@ pub struct MonitorRemovedPayload {
 	pub name: String,
 }
 
+/// Sent when a connected monitor's properties change in place.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorChangedPayload {
+	pub monitor: MonitorInfo,
+}
+
+/// Admin-only: switches a monitor to one of its advertised modes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorModeSetPayload {
+	pub monitor_id: String,
+	pub mode: MonitorMode,
+	/// Id echoed in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
+/// Admin-only: makes a monitor the primary one.
+///
+/// Affected monitors are re-announced with `MONITOR_CHANGED`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorPrimarySetPayload {
+	pub monitor_id: String,
+	/// Id echoed in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SessionSwitchPayload {
 	pub session_id: String,
 	pub animation: Option<String>,
 	pub duration: Duration,
+	/// Id echoed in the error rejecting the switch.
+	#[serde(default)]
+	pub request_id: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SessionCreatePayload {
 	pub role: SessionRole,
 	pub display_name: Option<String>,
+	/// Id echoed in the matching `session_created`, or in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SessionCreatedPayload {
 	pub session: SessionInfo,
 	pub token: String,
+	/// `request_id` of the `session_create` this answers.
+	#[serde(default)]
+	pub request_id: Option<u64>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -640,10 +1169,558 @@ pub struct SessionSleepPayload {
 	pub session_id: String,
 }
 
+/// Updates the sending session's display name and metadata.
+///
+/// A missing `display_name` keeps the current one. Metadata entries are merged into the
+/// session's; a `null` value removes the key. Admin sessions learn about the change
+/// through `SESSION_STATE`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionUpdatePayload {
+	pub display_name: Option<String>,
+	#[serde(default)]
+	pub metadata: BTreeMap<String, Option<String>>,
+}
+
+/// Admin-only: asks for a downscaled snapshot of a session's latest frame.
+///
+/// The frame shown on `monitor_id`, or on any monitor when unset, is scaled to fit
+/// within `max_width` x `max_height` keeping its aspect ratio. The server answers with
+/// `SESSION_PREVIEW`, or a `no_frame` error carrying the session id as its message when
+/// the session has no frame to show.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionPreviewRequestPayload {
+	pub session_id: String,
+	pub monitor_id: Option<String>,
+	pub max_width: i32,
+	pub max_height: i32,
+}
+
+/// Snapshot answering `SESSION_PREVIEW_REQUEST`.
+///
+/// Sent with one shm fd holding `height` rows of `stride` bytes in `fourcc` layout. The
+/// fd is a private copy the receiver maps read-only and closes when done.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionPreviewPayload {
+	pub session_id: String,
+	pub monitor_id: String,
+	pub width: i32,
+	pub height: i32,
+	pub stride: i32,
+	pub fourcc: i32,
+}
+
+/// Asks the server to keep the sending session awake and on screen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdleInhibitPayload {
+	pub inhibit: bool,
+	pub reason: Option<String>,
+}
+
+/// Admin-only: reserves a key combination for the sending session, whichever session
+/// has focus.
+///
+/// `keys` are evdev key codes that must all be held, the last one pressed triggering
+/// the shortcut; left and right modifiers are distinct keys. Registering an id again
+/// replaces its keys. A combination another registration already holds is rejected with
+/// `shortcut_conflict`, carrying `shortcut_id` as its message and never `request_id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalShortcutRegisterPayload {
+	pub shortcut_id: String,
+	pub keys: Vec<u32>,
+	/// Id echoed in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
+impl GlobalShortcutRegisterPayload {
+	/// Most keys a shortcut may combine.
+	pub const MAX_KEYS: usize = 8;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalShortcutUnregisterPayload {
+	pub shortcut_id: String,
+	/// Id echoed in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
+/// A registered shortcut was pressed on `seat`.
+///
+/// The triggering key press and its release are not delivered as input events.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GlobalShortcutPayload {
+	pub shortcut_id: String,
+	#[serde(default)]
+	pub seat: SeatId,
+	pub time_usec: u64,
+}
+
+/// Sends opaque `data` to the session `session_id`, brokered by the server.
+///
+/// Admin sessions may message any session; other sessions may message admin sessions,
+/// and answer a message they received by echoing its `correlation_id` as `reply_to`.
+/// Messages the target may not or cannot receive are rejected with
+/// `message_undeliverable`, carrying the target's `session_id` as its message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMessageSendPayload {
+	pub session_id: String,
+	/// Id the receiver echoes in `reply_to` when answering.
+	#[serde(default)]
+	pub correlation_id: Option<u64>,
+	/// `correlation_id` of the message this one answers.
+	#[serde(default)]
+	pub reply_to: Option<u64>,
+	pub data: Vec<u8>,
+}
+
+impl SessionMessageSendPayload {
+	/// Largest `data` accepted, keeping the encoded frame within one packet.
+	pub const MAX_DATA_LEN: usize = 768;
+}
+
+/// Message another session sent with `session_message_send`; `session_id` is the sender.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionMessagePayload {
+	pub session_id: String,
+	#[serde(default)]
+	pub correlation_id: Option<u64>,
+	#[serde(default)]
+	pub reply_to: Option<u64>,
+	pub data: Vec<u8>,
+}
+
+/// Best-effort report a session sends from its panic hook before it disconnects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrashReportPayload {
+	/// Panic message, truncated to `MAX_MESSAGE_LEN` bytes.
+	pub message: String,
+	/// Hash of the panicking thread's backtrace, for grouping reports of the same crash.
+	pub backtrace_hash: u64,
+	#[serde(default)]
+	pub app_version: Option<String>,
+}
+
+impl CrashReportPayload {
+	/// Longest `message` sent, keeping the encoded frame within one packet.
+	pub const MAX_MESSAGE_LEN: usize = 512;
+}
+
+/// Admin-only: a session reported a crash with `crash_report`; `session_id` is the sender.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionCrashedPayload {
+	pub session_id: String,
+	pub message: String,
+	pub backtrace_hash: u64,
+	#[serde(default)]
+	pub app_version: Option<String>,
+}
+
+/// Admin-only: routes key events to the sending session while another session stays
+/// active and on screen.
+///
+/// Pointer, touch and tablet input keep following the active session. Only one session
+/// holds the grab; while another session holds it, the request is rejected with
+/// `grab_unavailable`. The grab ends when released or when the session disconnects.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyboardGrabPayload {
+	pub grab: bool,
+}
+
+/// Admin-only: scales a monitor's output, `1.0` being unmodified and `0.0` black.
+///
+/// Shift applies it while compositing, not through the panel backlight; see
+/// [`MonitorGammaPayload`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonitorBrightnessPayload {
+	pub monitor_id: String,
+	pub brightness: f32,
+	/// Id echoed in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
+/// Power state of a monitor's display, driven through DPMS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MonitorPower {
+	#[default]
+	On,
+	/// Blanked with the quickest wake-up; many displays treat it like `Off`.
+	Standby,
+	Off,
+}
+
+/// Admin request to change a monitor's power state.
+///
+/// Once the display changed state, the server sends the same message to every client,
+/// and to clients connecting later for monitors that are not [`MonitorPower::On`].
+/// Nothing is shown on a monitor while it is not on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorPowerPayload {
+	pub monitor_id: String,
+	pub power: MonitorPower,
+	/// Id echoed in the error rejecting the request; unset when the server sends it.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
+/// System-wide accessibility preferences applications should adapt to.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilitySettings {
+	/// Avoid non-essential motion such as animated transitions.
+	#[serde(default)]
+	pub reduced_motion: bool,
+	/// Factor to scale text by, `1.0` being the default size.
+	#[serde(default = "default_text_scale")]
+	pub text_scale: f32,
+	/// Prefer high-contrast colors.
+	#[serde(default)]
+	pub high_contrast: bool,
+}
+
+fn default_text_scale() -> f32 {
+	1.0
+}
+
+impl Default for AccessibilitySettings {
+	fn default() -> Self {
+		Self {
+			reduced_motion: false,
+			text_scale: default_text_scale(),
+			high_contrast: false,
+		}
+	}
+}
+
+impl AccessibilitySettings {
+	/// Returns true when `text_scale` is finite and positive.
+	pub fn is_valid(&self) -> bool {
+		self.text_scale.is_finite() && self.text_scale > 0.0
+	}
+}
+
+/// Admin request to change the accessibility settings.
+///
+/// The server sends the same message to every client, and to clients connecting later
+/// while the settings differ from the defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccessibilityPayload {
+	pub settings: AccessibilitySettings,
+	/// Id echoed in the error rejecting the request; unset when the server sends it.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
+/// Marks the session's frames on a monitor as fullscreen and opaque.
+///
+/// Such frames need no composition and may be scanned out directly; the server
+/// falls back to compositing whenever that is not possible.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanoutHintPayload {
+	pub monitor_id: String,
+	pub fullscreen_opaque: bool,
+}
+
+/// Tags the color space of the session's frames on a monitor.
+///
+/// The server converts tagged frames while compositing; frames are sRGB until tagged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SurfaceColorspacePayload {
+	pub monitor_id: String,
+	pub colorspace: Colorspace,
+}
+
+/// Axis-aligned rectangle in physical pixels, relative to a monitor's top-left corner.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct Rect {
+	pub x: i32,
+	pub y: i32,
+	pub width: i32,
+	pub height: i32,
+}
+
+/// Parts of a monitor covered by compositor chrome such as bars or notifications.
+///
+/// Admin sessions send it to declare what they draw over other sessions; the server
+/// forwards the latest list to every client. An empty list clears the monitor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafeAreaPayload {
+	pub monitor_id: String,
+	pub occluded: Vec<Rect>,
+	/// Id echoed in the error rejecting the request; unset when the server sends it.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
+/// Creates an overlay surface composited above the session's frames on a monitor.
+///
+/// Sent with the two dmabuf fds backing the overlay, like `framebuffer_link`. Buffers
+/// carry premultiplied alpha; higher `z` overlays are drawn on top.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayCreatePayload {
+	pub overlay_id: String,
+	pub monitor_id: String,
+	pub x: i32,
+	pub y: i32,
+	pub z: i32,
+	pub width: i32,
+	pub height: i32,
+	pub stride: i32,
+	pub offset: i32,
+	pub fourcc: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayMovePayload {
+	pub overlay_id: String,
+	pub x: i32,
+	pub y: i32,
+}
+
+/// Shows an overlay buffer; may carry an acquire fence fd.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayPresentPayload {
+	pub overlay_id: String,
+	pub buffer: BufferIndex,
+}
+
+/// Hands an overlay buffer back to the client; may carry a release fence fd.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayReleasePayload {
+	pub overlay_id: String,
+	pub buffer: BufferIndex,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OverlayDestroyPayload {
+	pub overlay_id: String,
+}
+
+/// What a capture stream records.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CaptureSource {
+	/// The composited output of a monitor, as scanned out.
+	Monitor { monitor_id: String },
+	/// A session's own frames on a monitor, without overlays or color adjustments.
+	Session {
+		session_id: String,
+		monitor_id: String,
+	},
+}
+
+/// Admin-only: starts streaming `source` into two client-allocated buffers.
+///
+/// Sent with the two dmabuf fds, like `overlay_create`. Every new source frame is scaled
+/// into a free buffer and announced with `capture_frame`; the client hands the buffer
+/// back with `capture_release`. Frames arriving while both buffers are held are dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureStartPayload {
+	pub capture_id: String,
+	pub source: CaptureSource,
+	pub width: i32,
+	pub height: i32,
+	pub stride: i32,
+	pub offset: i32,
+	pub fourcc: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureStopPayload {
+	pub capture_id: String,
+}
+
+/// A capture buffer holds a new frame; may carry a fence fd that signals once the
+/// server finished writing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureFramePayload {
+	pub capture_id: String,
+	pub buffer: BufferIndex,
+	/// `CLOCK_MONOTONIC` time the source frame was composited, in microseconds.
+	pub time_usec: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureReleasePayload {
+	pub capture_id: String,
+	pub buffer: BufferIndex,
+}
+
+/// The server stopped a capture on its own, e.g. because its source went away.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CaptureEndedPayload {
+	pub capture_id: String,
+	pub reason: String,
+}
+
+/// Asks the server for its input clock; answered with [`ClockSyncPayload`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockSyncRequestPayload {
+	/// Client `CLOCK_MONOTONIC` time the request was sent, in microseconds.
+	pub client_time_usec: u64,
+}
+
+/// Answer to a clock sync request, sent as soon as the server reads it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClockSyncPayload {
+	/// `client_time_usec` of the request, echoed back.
+	pub client_time_usec: u64,
+	/// Server time on the clock of input `time_usec` values, in microseconds.
+	pub server_time_usec: u64,
+}
+
+/// 16-bit gamma ramps; all channels must have the same, non-zero length.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GammaRamps {
+	pub red: Vec<u16>,
+	pub green: Vec<u16>,
+	pub blue: Vec<u16>,
+}
+
+impl GammaRamps {
+	pub const MAX_LEN: usize = 4096;
+
+	/// Linear ramps of `len` entries.
+	pub fn identity(len: usize) -> Self {
+		let last = len.saturating_sub(1).max(1) as u32;
+		let ramp: Vec<u16> = (0..len as u32)
+			.map(|i| (i * u16::MAX as u32 / last) as u16)
+			.collect();
+		Self {
+			red: ramp.clone(),
+			green: ramp.clone(),
+			blue: ramp,
+		}
+	}
+
+	pub fn is_valid(&self) -> bool {
+		let len = self.red.len();
+		(1..=Self::MAX_LEN).contains(&len) && self.green.len() == len && self.blue.len() == len
+	}
+}
+
+/// Admin-only: replaces a monitor's gamma ramps; `None` restores the identity ramp.
+///
+/// Shift folds the ramps and the brightness into an 8-bit lookup table applied while
+/// compositing: ramps are resampled to 256 entries of 8 bits, and neither the CRTC
+/// `GAMMA_LUT` nor the backlight is touched. An adjusted monitor is always composited.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorGammaPayload {
+	pub monitor_id: String,
+	pub ramps: Option<GammaRamps>,
+	/// Id echoed in the error rejecting the request.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ErrorPayload {
 	pub code: String,
 	pub message: Option<String>,
+	/// `request_id` of the request this error rejects, when it carried one.
+	#[serde(default)]
+	pub request_id: Option<u64>,
+	/// The `buffer_request` this error rejects.
+	#[serde(default)]
+	pub buffer_request: Option<BufferRequestPayload>,
+}
+
+impl ErrorPayload {
+	/// Typed form of `code`.
+	pub fn error_code(&self) -> ServerErrorCode {
+		ServerErrorCode::from(self.code.as_str())
+	}
+}
+
+/// Error codes sent by the server in [`ErrorPayload::code`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServerErrorCode {
+	/// The request needs an admin session or prior authentication.
+	Forbidden,
+	UnknownMessage,
+	ProtocolViolation,
+	InvalidArgument,
+	UnknownMonitor,
+	UnknownSession,
+	InvalidSessionId,
+	/// The target session has not finished loading.
+	SessionLoading,
+	/// The session lifecycle does not allow the requested transition.
+	InvalidTransition,
+	/// Frames are not accepted while the session sleeps.
+	SessionSleeping,
+	/// The requested buffer is currently owned by the server.
+	OwnershipViolation,
+	/// A buffer request for the monitor is already being processed.
+	BufferRequestInflight,
+	BufferRequestRejected,
+	/// The session has no frame on screen to take a preview from.
+	NoFrame,
+	/// Another global shortcut already uses the key combination.
+	ShortcutConflict,
+	/// Another session holds the keyboard grab.
+	GrabUnavailable,
+	/// The target session does not exist or may not receive the message.
+	MessageUndeliverable,
+	/// A code this version of the protocol does not know.
+	Other(String),
+}
+
+impl ServerErrorCode {
+	pub fn as_str(&self) -> &str {
+		match self {
+			Self::Forbidden => "forbidden",
+			Self::UnknownMessage => "unknown_message",
+			Self::ProtocolViolation => "protocol_violation",
+			Self::InvalidArgument => "invalid_argument",
+			Self::UnknownMonitor => "unknown_monitor",
+			Self::UnknownSession => "unknown_session",
+			Self::InvalidSessionId => "invalid_session_id",
+			Self::SessionLoading => "session_loading",
+			Self::InvalidTransition => "invalid_transition",
+			Self::SessionSleeping => "session_sleeping",
+			Self::OwnershipViolation => "ownership_violation",
+			Self::BufferRequestInflight => "buffer_request_inflight",
+			Self::BufferRequestRejected => "buffer_request_rejected",
+			Self::NoFrame => "no_frame",
+			Self::ShortcutConflict => "shortcut_conflict",
+			Self::GrabUnavailable => "grab_unavailable",
+			Self::MessageUndeliverable => "message_undeliverable",
+			Self::Other(code) => code,
+		}
+	}
+}
+
+impl From<&str> for ServerErrorCode {
+	fn from(code: &str) -> Self {
+		match code {
+			"forbidden" => Self::Forbidden,
+			"unknown_message" => Self::UnknownMessage,
+			"protocol_violation" => Self::ProtocolViolation,
+			"invalid_argument" => Self::InvalidArgument,
+			"unknown_monitor" => Self::UnknownMonitor,
+			"unknown_session" => Self::UnknownSession,
+			"invalid_session_id" => Self::InvalidSessionId,
+			"session_loading" => Self::SessionLoading,
+			"invalid_transition" => Self::InvalidTransition,
+			"session_sleeping" => Self::SessionSleeping,
+			"ownership_violation" => Self::OwnershipViolation,
+			"buffer_request_inflight" => Self::BufferRequestInflight,
+			"buffer_request_rejected" => Self::BufferRequestRejected,
+			"no_frame" => Self::NoFrame,
+			"shortcut_conflict" => Self::ShortcutConflict,
+			"grab_unavailable" => Self::GrabUnavailable,
+			"message_undeliverable" => Self::MessageUndeliverable,
+			other => Self::Other(other.to_string()),
+		}
+	}
+}
+
+impl std::fmt::Display for ServerErrorCode {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(self.as_str())
+	}
 }
 
 pub use message_header::MessageHeader;
@@ -5,7 +5,7 @@ use std::collections::VecDeque;
 use std::io::{ErrorKind, IoSlice, IoSliceMut};
 use std::os::fd::{AsRawFd, RawFd};
 
-use crate::{HelloPayload, MessageHeader, PROTOCOL_VERSION, ProtocolError};
+use crate::{DrmDeviceInfo, HelloPayload, MessageHeader, PROTOCOL_VERSION, ProtocolError};
 
 /// Raw framed Tab message: header line + payload line (strings) plus optional FDs.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -180,7 +180,7 @@ impl TabMessageFrame {
 	}
 
 	#[tracing::instrument(skip_all)]
-	pub(crate) fn expect_payload_json<'a, T>(&'a self) -> Result<T, ProtocolError>
+	pub fn expect_payload_json<'a, T>(&'a self) -> Result<T, ProtocolError>
 	where
 		T: serde::Deserialize<'a>,
 	{
@@ -215,10 +215,11 @@ impl TabMessageFrame {
 			fds: Vec::new(),
 		}
 	}
-	pub fn hello(server: impl Into<String>) -> Self {
+	pub fn hello(server: impl Into<String>, drm_device: Option<DrmDeviceInfo>) -> Self {
 		let payload = HelloPayload {
 			server: server.into(),
 			protocol: PROTOCOL_VERSION.to_string(),
+			drm_device,
 		};
 		let json = serde_json::to_value(payload).expect("HelloPayload is serializable");
 		Self::json("hello", json)
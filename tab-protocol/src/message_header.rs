@@ -22,8 +22,14 @@ define_headers! {
 		BUFFER_REQUEST_ACK,
 		BUFFER_RELEASE,
 		INPUT_EVENT,
+		INPUT_INJECT,
+		INPUT_DEVICE_ADDED,
+		INPUT_DEVICE_REMOVED,
 		MONITOR_ADDED,
 		MONITOR_REMOVED,
+		MONITOR_CHANGED,
+		MONITOR_MODE_SET,
+		MONITOR_PRIMARY_SET,
 		SESSION_SWITCH,
 		SESSION_CREATE,
 		SESSION_CREATED,
@@ -32,6 +38,37 @@ define_headers! {
 		SESSION_ACTIVE,
 		SESSION_AWAKE,
 		SESSION_SLEEP,
+		SESSION_UPDATE,
+		SESSION_PREVIEW_REQUEST,
+		SESSION_PREVIEW,
+		IDLE_INHIBIT,
+		GLOBAL_SHORTCUT_REGISTER,
+		GLOBAL_SHORTCUT_UNREGISTER,
+		GLOBAL_SHORTCUT,
+		SESSION_MESSAGE_SEND,
+		SESSION_MESSAGE,
+		CRASH_REPORT,
+		SESSION_CRASHED,
+		KEYBOARD_GRAB,
+		MONITOR_BRIGHTNESS,
+		MONITOR_GAMMA,
+		MONITOR_POWER,
+		ACCESSIBILITY,
+		SCANOUT_HINT,
+		SURFACE_COLORSPACE,
+		SAFE_AREA,
+		OVERLAY_CREATE,
+		OVERLAY_MOVE,
+		OVERLAY_PRESENT,
+		OVERLAY_RELEASE,
+		OVERLAY_DESTROY,
+		CAPTURE_START,
+		CAPTURE_STOP,
+		CAPTURE_FRAME,
+		CAPTURE_RELEASE,
+		CAPTURE_ENDED,
+		CLOCK_SYNC_REQUEST,
+		CLOCK_SYNC,
 		ERROR,
 		PING,
 		PONG,
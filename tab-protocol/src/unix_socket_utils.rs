@@ -3,7 +3,63 @@ use nix::sys::socket::{
 };
 use std::os::fd::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
 use std::os::unix::net::UnixStream;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+use crate::DEFAULT_SOCKET_PATH;
+
+/// Per-seat socket path, `$XDG_RUNTIME_DIR/shift/<seat>.sock`, if `XDG_RUNTIME_DIR` is set.
+pub fn seat_socket_path(seat: &str) -> Option<PathBuf> {
+	let runtime_dir = std::env::var_os("XDG_RUNTIME_DIR").filter(|dir| !dir.is_empty())?;
+	Some(
+		PathBuf::from(runtime_dir)
+			.join("shift")
+			.join(format!("{seat}.sock")),
+	)
+}
+
+/// Sockets a client may connect to, most preferred first.
+///
+/// Lists `SHIFT_SOCKET` if set, then the socket of `seat` and of every other seat found in
+/// `$XDG_RUNTIME_DIR/shift`, then [`DEFAULT_SOCKET_PATH`]. Apart from `SHIFT_SOCKET`, only
+/// paths that exist are listed.
+pub fn socket_candidates(seat: &str) -> Vec<PathBuf> {
+	let mut candidates = Vec::new();
+	if let Some(path) = std::env::var_os("SHIFT_SOCKET").filter(|path| !path.is_empty()) {
+		candidates.push(PathBuf::from(path));
+	}
+	if let Some(own) = seat_socket_path(seat) {
+		if own.exists() {
+			candidates.push(own.clone());
+		}
+		if let Some(dir) = own.parent()
+			&& let Ok(entries) = std::fs::read_dir(dir)
+		{
+			let mut others: Vec<PathBuf> = entries
+				.filter_map(|entry| Some(entry.ok()?.path()))
+				.filter(|path| path.extension().is_some_and(|ext| ext == "sock") && *path != own)
+				.collect();
+			others.sort();
+			candidates.extend(others);
+		}
+	}
+	let legacy = PathBuf::from(DEFAULT_SOCKET_PATH);
+	if legacy.exists() && !candidates.contains(&legacy) {
+		candidates.push(legacy);
+	}
+	candidates
+}
+
+/// Socket a client of `seat` should connect to: the first of [`socket_candidates`] that
+/// is `SHIFT_SOCKET` or belongs to `seat`, else [`DEFAULT_SOCKET_PATH`].
+pub fn discover_socket(seat: &str) -> PathBuf {
+	if let Some(path) = std::env::var_os("SHIFT_SOCKET").filter(|path| !path.is_empty()) {
+		return path.into();
+	}
+	seat_socket_path(seat)
+		.filter(|path| path.exists())
+		.unwrap_or_else(|| DEFAULT_SOCKET_PATH.into())
+}
+
 /// Bind a Unix seqpacket listener at the given path (removes any stale socket file).
 pub fn bind_seqpacket_listener(path: impl AsRef<Path>) -> Result<RawFd, nix::Error> {
 	let path = path.as_ref();